@@ -0,0 +1,19 @@
+//! Audio for the dropbear engine, built on [rodio].
+//!
+//! This is kept out of `dropbear-engine` so that headless and server builds can leave audio out
+//! entirely. Games add it with the [`AudioPlugin`]:
+//!
+//! ```ignore
+//! app.add_plugin(AudioPlugin);
+//! ```
+//!
+//! The plugin inserts an [`AudioManager`] resource, which systems fetch to play sounds.
+
+mod manager;
+mod plugin;
+mod sound;
+
+pub use manager::{AudioListener, AudioManager, SourceId};
+pub use plugin::AudioPlugin;
+pub use rodio;
+pub use sound::{AudioSource, MusicTrack, SoundEffect};
@@ -0,0 +1,257 @@
+//! Playing audio through an output device.
+
+use crate::sound::{AudioSource, MusicTrack, SoundEffect};
+use dropbear_engine::asset::{ASSET_REGISTRY, AudioHandle};
+use glam::Vec3;
+use rodio::decoder::LoopedDecoder;
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, SpatialSink};
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::sync::Arc;
+
+/// How far apart the ears of the [`AudioListener`] are, in world units.
+const EAR_DISTANCE: f32 = 0.2;
+
+type AudioReader = Cursor<Arc<[u8]>>;
+
+/// Where sounds are heard from, usually the position of the active camera.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AudioListener {
+    pub position: Vec3,
+    /// The direction to the right of the listener, which the ears are placed along
+    pub right: Vec3,
+}
+
+impl Default for AudioListener {
+    fn default() -> Self {
+        Self {
+            position: Vec3::ZERO,
+            right: Vec3::X,
+        }
+    }
+}
+
+impl AudioListener {
+    /// The positions of the left and right ears.
+    fn ears(&self) -> ([f32; 3], [f32; 3]) {
+        let offset = self.right.normalize_or_zero() * (EAR_DISTANCE / 2.0);
+        (
+            (self.position - offset).to_array(),
+            (self.position + offset).to_array(),
+        )
+    }
+}
+
+/// Identifies an [`AudioSource`] that is playing, returned by [`AudioManager::play_source`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SourceId(u64);
+
+/// Plays sound effects, music and sounds placed in the world.
+///
+/// Volumes passed in are multiplied by the master volume, so the volume of everything can be
+/// changed at once with [`AudioManager::set_master_volume`].
+pub struct AudioManager {
+    /// `None` if the output device couldn't be opened, in which case nothing is played. The
+    /// stream has to be kept alive for as long as anything plays.
+    output: Option<(OutputStream, OutputStreamHandle)>,
+    listener: AudioListener,
+    master_volume: f32,
+    /// The sink playing music, along with the volume of the track
+    music: Option<(Sink, f32)>,
+    sources: HashMap<SourceId, (SpatialSink, f32)>,
+    next_source_id: u64,
+}
+
+impl AudioManager {
+    /// Opens an output stream on `device`.
+    ///
+    /// If the device can't be opened, a warning is logged and the manager plays nothing, so the
+    /// game still runs on machines without audio.
+    pub fn from_context(device: &rodio::Device) -> Self {
+        match OutputStream::try_from_device(device) {
+            Ok(output) => Self::with_output(Some(output)),
+            Err(e) => {
+                log::warn!("Unable to open audio device, audio is disabled: {}", e);
+                Self::silent()
+            }
+        }
+    }
+
+    /// Opens an output stream on the default output device, falling back to playing nothing like
+    /// [`AudioManager::from_context`].
+    pub fn from_default_device() -> Self {
+        match OutputStream::try_default() {
+            Ok(output) => Self::with_output(Some(output)),
+            Err(e) => {
+                log::warn!(
+                    "Unable to open default audio device, audio is disabled: {}",
+                    e
+                );
+                Self::silent()
+            }
+        }
+    }
+
+    /// Creates a manager that plays nothing.
+    pub fn silent() -> Self {
+        Self::with_output(None)
+    }
+
+    fn with_output(output: Option<(OutputStream, OutputStreamHandle)>) -> Self {
+        Self {
+            output,
+            listener: AudioListener::default(),
+            master_volume: 1.0,
+            music: None,
+            sources: HashMap::new(),
+            next_source_id: 0,
+        }
+    }
+
+    /// Checks if the manager has no output device, so nothing is played.
+    pub fn is_silent(&self) -> bool {
+        self.output.is_none()
+    }
+
+    pub fn listener(&self) -> &AudioListener {
+        &self.listener
+    }
+
+    /// Moves the listener. Playing sources are heard from the new position after the next
+    /// [`AudioManager::update`].
+    pub fn set_listener(&mut self, listener: AudioListener) {
+        self.listener = listener;
+    }
+
+    pub fn master_volume(&self) -> f32 {
+        self.master_volume
+    }
+
+    /// Changes the volume of everything, including what is already playing.
+    pub fn set_master_volume(&mut self, volume: f32) {
+        self.master_volume = volume.max(0.0);
+        if let Some((sink, track_volume)) = &self.music {
+            sink.set_volume(track_volume * self.master_volume);
+        }
+        for (sink, source_volume) in self.sources.values() {
+            sink.set_volume(source_volume * self.master_volume);
+        }
+    }
+
+    /// Plays a sound effect once, at the same volume in both ears.
+    pub fn play_effect(&self, effect: &SoundEffect) -> anyhow::Result<()> {
+        let Some((_, stream)) = &self.output else {
+            return Ok(());
+        };
+
+        let sink = Sink::try_new(stream)?;
+        sink.set_volume(effect.volume * self.master_volume);
+        sink.append(decode(effect.handle)?);
+        sink.detach();
+        Ok(())
+    }
+
+    /// Plays a music track, stopping the one that was playing.
+    pub fn play_music(&mut self, track: &MusicTrack) -> anyhow::Result<()> {
+        self.stop_music();
+        let Some((_, stream)) = &self.output else {
+            return Ok(());
+        };
+
+        let sink = Sink::try_new(stream)?;
+        sink.set_volume(track.volume * self.master_volume);
+        if track.looping {
+            sink.append(decode_looped(track.handle)?);
+        } else {
+            sink.append(decode(track.handle)?);
+        }
+        self.music = Some((sink, track.volume));
+        Ok(())
+    }
+
+    pub fn stop_music(&mut self) {
+        if let Some((sink, _)) = self.music.take() {
+            sink.stop();
+        }
+    }
+
+    pub fn is_music_playing(&self) -> bool {
+        self.music.as_ref().is_some_and(|(sink, _)| !sink.empty())
+    }
+
+    /// Starts playing a sound from a point in the world.
+    ///
+    /// Returns `None` if the manager is silent.
+    pub fn play_source(&mut self, source: &AudioSource) -> anyhow::Result<Option<SourceId>> {
+        let Some((_, stream)) = &self.output else {
+            return Ok(None);
+        };
+
+        let (left_ear, right_ear) = self.listener.ears();
+        let sink = SpatialSink::try_new(stream, source.position.to_array(), left_ear, right_ear)?;
+        sink.set_volume(source.sound.volume * self.master_volume);
+        if source.looping {
+            sink.append(decode_looped(source.sound.handle)?);
+        } else {
+            sink.append(decode(source.sound.handle)?);
+        }
+
+        let id = SourceId(self.next_source_id);
+        self.next_source_id += 1;
+        self.sources.insert(id, (sink, source.sound.volume));
+        Ok(Some(id))
+    }
+
+    /// Moves a playing source, such as to follow the entity it belongs to.
+    pub fn set_source_position(&mut self, id: SourceId, position: Vec3) {
+        if let Some((sink, _)) = self.sources.get(&id) {
+            sink.set_emitter_position(position.to_array());
+        }
+    }
+
+    pub fn stop_source(&mut self, id: SourceId) {
+        if let Some((sink, _)) = self.sources.remove(&id) {
+            sink.stop();
+        }
+    }
+
+    /// Checks if a source is still playing. Sources that finished are only forgotten on the next
+    /// [`AudioManager::update`].
+    pub fn is_source_playing(&self, id: SourceId) -> bool {
+        self.sources.contains_key(&id)
+    }
+
+    /// Moves the ears of every playing source to the listener, and forgets the sources and
+    /// music that have finished.
+    ///
+    /// This is called every frame by the [`AudioPlugin`](crate::AudioPlugin).
+    pub fn update(&mut self) {
+        let (left_ear, right_ear) = self.listener.ears();
+        self.sources.retain(|_, (sink, _)| {
+            if sink.empty() {
+                return false;
+            }
+            sink.set_left_ear_position(left_ear);
+            sink.set_right_ear_position(right_ear);
+            true
+        });
+
+        if self.music.as_ref().is_some_and(|(sink, _)| sink.empty()) {
+            self.music = None;
+        }
+    }
+}
+
+fn audio_bytes(handle: AudioHandle) -> anyhow::Result<Arc<[u8]>> {
+    ASSET_REGISTRY
+        .get_audio(handle)
+        .ok_or_else(|| anyhow::anyhow!("Audio {:?} is not loaded", handle))
+}
+
+fn decode(handle: AudioHandle) -> anyhow::Result<Decoder<AudioReader>> {
+    Ok(Decoder::new(Cursor::new(audio_bytes(handle)?))?)
+}
+
+fn decode_looped(handle: AudioHandle) -> anyhow::Result<LoopedDecoder<AudioReader>> {
+    Ok(Decoder::new_looped(Cursor::new(audio_bytes(handle)?))?)
+}
@@ -0,0 +1,25 @@
+//! The plugin that adds audio to an [`App`].
+
+use crate::AudioManager;
+use dropbear_engine::App;
+use dropbear_engine::plugin::Plugin;
+
+/// Inserts an [`AudioManager`] that plays through the default output device, and updates it
+/// every frame.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AudioPlugin;
+
+impl Plugin for AudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(AudioManager::from_default_device())
+            .add_system(|resources, _dt| {
+                if let Some(audio) = resources.get_mut::<AudioManager>() {
+                    audio.update();
+                }
+            });
+    }
+
+    fn name(&self) -> &str {
+        "AudioPlugin"
+    }
+}
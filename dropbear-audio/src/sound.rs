@@ -0,0 +1,73 @@
+//! The kinds of sound that an [`AudioManager`](crate::AudioManager) can play.
+
+use dropbear_engine::asset::{ASSET_REGISTRY, AudioHandle};
+use glam::Vec3;
+use std::path::Path;
+
+/// A short sound, such as a footstep or a button click.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SoundEffect {
+    pub handle: AudioHandle,
+    /// From `0.0` (silent) to `1.0`, multiplied by the master volume
+    pub volume: f32,
+}
+
+impl SoundEffect {
+    pub fn new(handle: AudioHandle) -> Self {
+        Self {
+            handle,
+            volume: 1.0,
+        }
+    }
+
+    /// Loads the file into the asset registry, reusing it if it was already loaded.
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        Ok(Self::new(ASSET_REGISTRY.load_audio(path)?))
+    }
+}
+
+/// Background music. Only one track plays at a time, so playing another replaces it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MusicTrack {
+    pub handle: AudioHandle,
+    /// From `0.0` (silent) to `1.0`, multiplied by the master volume
+    pub volume: f32,
+    /// Whether the track starts again once it ends
+    pub looping: bool,
+}
+
+impl MusicTrack {
+    /// Creates a track that loops at full volume.
+    pub fn new(handle: AudioHandle) -> Self {
+        Self {
+            handle,
+            volume: 1.0,
+            looping: true,
+        }
+    }
+
+    /// Loads the file into the asset registry, reusing it if it was already loaded.
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        Ok(Self::new(ASSET_REGISTRY.load_audio(path)?))
+    }
+}
+
+/// A sound that comes from a point in the world, so it is louder in the ear of the
+/// [`AudioListener`](crate::AudioListener) that is closer to it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AudioSource {
+    pub sound: SoundEffect,
+    pub position: Vec3,
+    /// Whether the sound starts again once it ends, until it is stopped
+    pub looping: bool,
+}
+
+impl AudioSource {
+    pub fn new(sound: SoundEffect, position: Vec3) -> Self {
+        Self {
+            sound,
+            position,
+            looping: false,
+        }
+    }
+}
@@ -1,12 +1,25 @@
-use std::sync::{
-    Arc, LazyLock,
-    atomic::{AtomicU64, Ordering},
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, LazyLock,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
 };
 
+use anyhow::Context;
+use crossbeam_channel::{Receiver, unbounded};
 use dashmap::DashMap;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use parking_lot::{Mutex, RwLock};
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 use crate::{
-    model::{Material, Mesh, Model, ModelId},
+    atlas::Atlas,
+    graphics::{SharedGraphicsContext, Texture},
+    model::{LoadedModel, MODEL_CACHE, Material, Mesh, Model, ModelId},
     utils::ResourceReference,
 };
 
@@ -27,11 +40,135 @@ impl AssetHandle {
     }
 }
 
+/// A handle to audio loaded with [`AssetRegistry::load_audio`].
+///
+/// The registry only keeps the encoded bytes of the file, decoding is left to `dropbear-audio`.
+#[cfg(feature = "audio")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AudioHandle(AssetHandle);
+
+#[cfg(feature = "audio")]
+impl AudioHandle {
+    /// Returns the [`AssetHandle`] this handle wraps.
+    pub fn asset_handle(&self) -> AssetHandle {
+        self.0
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum AssetKind {
     Model,
     Material,
     Mesh,
+    Texture,
+}
+
+/// How a texture is sampled outside the `0..1` range.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WrapMode {
+    #[default]
+    ClampToEdge,
+    Repeat,
+    MirrorRepeat,
+}
+
+impl From<WrapMode> for wgpu::AddressMode {
+    fn from(value: WrapMode) -> Self {
+        match value {
+            WrapMode::ClampToEdge => wgpu::AddressMode::ClampToEdge,
+            WrapMode::Repeat => wgpu::AddressMode::Repeat,
+            WrapMode::MirrorRepeat => wgpu::AddressMode::MirrorRepeat,
+        }
+    }
+}
+
+/// How a texture is filtered when it is magnified or minified.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FilterMode {
+    Nearest,
+    #[default]
+    Linear,
+}
+
+impl From<FilterMode> for wgpu::FilterMode {
+    fn from(value: FilterMode) -> Self {
+        match value {
+            FilterMode::Nearest => wgpu::FilterMode::Nearest,
+            FilterMode::Linear => wgpu::FilterMode::Linear,
+        }
+    }
+}
+
+/// Per-texture settings used when a texture is imported through the [`AssetRegistry`].
+///
+/// These are persisted in a `.euimport` sidecar file next to the texture.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextureImportSettings {
+    /// Set for colour data (such as albedo), unset for linear data (such as normal maps)
+    pub srgb: bool,
+    pub wrap_u: WrapMode,
+    pub wrap_v: WrapMode,
+    pub filter: FilterMode,
+    pub generate_mipmaps: bool,
+}
+
+impl Default for TextureImportSettings {
+    fn default() -> Self {
+        Self {
+            srgb: true,
+            wrap_u: WrapMode::default(),
+            wrap_v: WrapMode::default(),
+            filter: FilterMode::default(),
+            generate_mipmaps: false,
+        }
+    }
+}
+
+impl TextureImportSettings {
+    /// The extension appended to a texture's file name to get its sidecar file.
+    pub const SIDECAR_EXTENSION: &'static str = "euimport";
+
+    /// The settings used for the material textures of a model without a sidecar file.
+    ///
+    /// These are uploaded as linear data so the colours of existing models stay the same.
+    pub const MODEL_DEFAULT: Self = Self {
+        srgb: false,
+        wrap_u: WrapMode::ClampToEdge,
+        wrap_v: WrapMode::ClampToEdge,
+        filter: FilterMode::Linear,
+        generate_mipmaps: false,
+    };
+
+    /// Returns the path of the sidecar file for a texture, such as `albedo.png.euimport`.
+    pub fn sidecar_path(texture_path: &Path) -> PathBuf {
+        let mut path = texture_path.as_os_str().to_owned();
+        path.push(".");
+        path.push(Self::SIDECAR_EXTENSION);
+        PathBuf::from(path)
+    }
+
+    /// Reads the settings from a texture's sidecar file, returning `None` if it does not exist.
+    pub fn load(texture_path: &Path) -> anyhow::Result<Option<Self>> {
+        let sidecar = Self::sidecar_path(texture_path);
+        if !sidecar.exists() {
+            return Ok(None);
+        }
+        let contents = std::fs::read_to_string(&sidecar)?;
+        let settings = ron::from_str(&contents)
+            .with_context(|| format!("Unable to parse {}", sidecar.display()))?;
+        Ok(Some(settings))
+    }
+
+    /// Writes the settings to a texture's sidecar file.
+    pub fn save(&self, texture_path: &Path) -> anyhow::Result<()> {
+        let sidecar = Self::sidecar_path(texture_path);
+        std::fs::write(
+            &sidecar,
+            ron::ser::to_string_pretty(self, PrettyConfig::default())?,
+        )?;
+        log::debug!("Wrote texture import settings to {}", sidecar.display());
+        Ok(())
+    }
 }
 
 #[derive(Debug, Eq, PartialEq, Hash)]
@@ -54,6 +191,10 @@ pub struct AssetRegistry {
     model_references: DashMap<AssetHandle, ResourceReference>,
     model_reference_lookup: DashMap<ResourceReference, AssetHandle>,
     models: DashMap<AssetHandle, Arc<Model>>,
+    /// The files that models were loaded from, used to reload them when they change
+    model_paths: DashMap<AssetHandle, PathBuf>,
+    /// Watches the files in `model_paths`, started by [`AssetRegistry::watch_for_changes`]
+    watcher: Mutex<Option<AssetWatcher>>,
 
     material_lookup: DashMap<(ModelId, String), AssetHandle>,
     material_owners: DashMap<AssetHandle, ModelId>,
@@ -67,6 +208,21 @@ pub struct AssetRegistry {
     mesh_reference_lookup: DashMap<ResourceReference, AssetHandle>,
     meshes: DashMap<AssetHandle, Arc<Mesh>>,
 
+    texture_lookup: DashMap<PathBuf, AssetHandle>,
+    textures: DashMap<AssetHandle, Arc<Texture>>,
+    texture_settings: DashMap<PathBuf, TextureImportSettings>,
+    atlases: DashMap<AssetHandle, Arc<Atlas>>,
+
+    #[cfg(feature = "audio")]
+    audio_lookup: DashMap<PathBuf, AudioHandle>,
+    #[cfg(feature = "audio")]
+    audio: DashMap<AudioHandle, Arc<[u8]>>,
+
+    /// Directory where compressed textures are cached, typically `<project>/.eucache`
+    texture_cache_dir: RwLock<Option<PathBuf>>,
+    /// Whether imported textures should be compressed to BC7
+    compress_textures: AtomicBool,
+
     /// Internal pointer database, typically used when querying in the database
     pointers: DashMap<PointerKind, usize>,
 }
@@ -80,6 +236,8 @@ impl AssetRegistry {
             model_references: DashMap::new(),
             model_reference_lookup: DashMap::new(),
             models: DashMap::new(),
+            model_paths: DashMap::new(),
+            watcher: Mutex::new(None),
             material_lookup: DashMap::new(),
             material_owners: DashMap::new(),
             material_references: DashMap::new(),
@@ -90,6 +248,16 @@ impl AssetRegistry {
             mesh_references: DashMap::new(),
             mesh_reference_lookup: DashMap::new(),
             meshes: DashMap::new(),
+            texture_lookup: DashMap::new(),
+            textures: DashMap::new(),
+            texture_settings: DashMap::new(),
+            atlases: DashMap::new(),
+            #[cfg(feature = "audio")]
+            audio_lookup: DashMap::new(),
+            #[cfg(feature = "audio")]
+            audio: DashMap::new(),
+            texture_cache_dir: RwLock::new(None),
+            compress_textures: AtomicBool::new(false),
             pointers: DashMap::new(),
         }
     }
@@ -133,6 +301,513 @@ impl AssetRegistry {
         model_handle
     }
 
+    /// Remembers the file a model was loaded from, so it can be reloaded with
+    /// [`AssetRegistry::reload_model`] and watched for changes.
+    pub fn track_model_path(&self, handle: AssetHandle, path: &Path) {
+        let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        if let Some(watcher) = self.watcher.lock().as_mut()
+            && let Err(e) = watcher.watch(&path)
+        {
+            log::warn!("Unable to watch {} for changes: {}", path.display(), e);
+        }
+        self.model_paths.insert(handle, path);
+    }
+
+    /// Returns the handle of the model loaded from a file, if there is one.
+    pub fn model_for_path(&self, path: &Path) -> Option<AssetHandle> {
+        let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        self.model_paths
+            .iter()
+            .find(|entry| *entry.value() == path)
+            .map(|entry| *entry.key())
+    }
+
+    /// Starts watching the files of every model loaded from disk, including ones loaded after
+    /// this is called. Does nothing if the registry is already watching.
+    ///
+    /// Models whose file changed can be fetched with [`AssetRegistry::changed_models`].
+    pub fn watch_for_changes(&self) -> anyhow::Result<()> {
+        let mut guard = self.watcher.lock();
+        if guard.is_some() {
+            return Ok(());
+        }
+
+        let mut watcher = AssetWatcher::new()?;
+        for entry in self.model_paths.iter() {
+            if let Err(e) = watcher.watch(entry.value()) {
+                log::warn!(
+                    "Unable to watch {} for changes: {}",
+                    entry.value().display(),
+                    e
+                );
+            }
+        }
+        *guard = Some(watcher);
+        log::debug!("Watching {} models for changes", self.model_paths.len());
+        Ok(())
+    }
+
+    /// Returns every model whose file changed since the last call, without duplicates.
+    ///
+    /// Always empty if [`AssetRegistry::watch_for_changes`] hasn't been called.
+    pub fn changed_models(&self) -> Vec<AssetHandle> {
+        let changed_paths: HashSet<PathBuf> = match self.watcher.lock().as_ref() {
+            Some(watcher) => watcher.receiver.try_iter().collect(),
+            None => return Vec::new(),
+        };
+
+        self.model_paths
+            .iter()
+            .filter(|entry| changed_paths.contains(entry.value()))
+            .map(|entry| *entry.key())
+            .collect()
+    }
+
+    /// Parses the file of a model again and uploads its new vertex and index buffers, keeping
+    /// the same handle. The materials and meshes cached for the old model are dropped.
+    ///
+    /// Renderers keep drawing the old model until they are given the new one with
+    /// [`MeshRenderer::refresh_model`](crate::entity::MeshRenderer::refresh_model).
+    pub async fn reload_model(
+        &self,
+        graphics: Arc<SharedGraphicsContext>,
+        handle: AssetHandle,
+    ) -> anyhow::Result<()> {
+        let path = self
+            .model_paths
+            .get(&handle)
+            .map(|path| path.clone())
+            .ok_or_else(|| anyhow::anyhow!("Model {:?} was not loaded from a file", handle))?;
+        let old = self
+            .get_model(handle)
+            .ok_or_else(|| anyhow::anyhow!("Model {:?} is not registered", handle))?;
+
+        MODEL_CACHE.lock().retain(|_, model| model.id != old.id);
+        self.forget_model_components(old.id);
+
+        let loaded = Model::load_raw(
+            graphics,
+            &path,
+            Some(&old.label),
+            self,
+            LazyLock::force(&MODEL_CACHE),
+        )
+        .await?;
+
+        if loaded.asset_handle() != handle {
+            log::warn!(
+                "Reloaded model {} was registered as {:?} instead of {:?}",
+                path.display(),
+                loaded.asset_handle(),
+                handle
+            );
+            self.models.insert(handle, loaded.get());
+            self.model_id_lookup.insert(loaded.id(), handle);
+        }
+
+        log::info!("Reloaded model {}", path.display());
+        Ok(())
+    }
+
+    /// Reads an audio file into the registry, returning the handle of the existing audio if the
+    /// file was already loaded.
+    #[cfg(feature = "audio")]
+    pub fn load_audio(&self, path: &Path) -> anyhow::Result<AudioHandle> {
+        if let Some(handle) = self.audio_lookup.get(path) {
+            return Ok(*handle);
+        }
+
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Unable to read audio file {}", path.display()))?;
+        let handle = self.register_audio(bytes);
+        self.audio_lookup.insert(path.to_path_buf(), handle);
+        log::debug!("Loaded audio {} as {:?}", path.display(), handle);
+        Ok(handle)
+    }
+
+    /// Adds encoded audio (such as the contents of an `.ogg` or `.wav` file) to the registry.
+    #[cfg(feature = "audio")]
+    pub fn register_audio(&self, bytes: impl Into<Arc<[u8]>>) -> AudioHandle {
+        let handle = AudioHandle(self.allocate_handle());
+        self.audio.insert(handle, bytes.into());
+        handle
+    }
+
+    /// Fetches the encoded bytes of audio by its handle.
+    #[cfg(feature = "audio")]
+    pub fn get_audio(&self, handle: AudioHandle) -> Option<Arc<[u8]>> {
+        self.audio
+            .get(&handle)
+            .map(|entry| Arc::clone(entry.value()))
+    }
+
+    /// Sets the directory that compressed textures are cached into.
+    ///
+    /// If no directory is set, textures are recompressed on every import.
+    pub fn set_texture_cache_dir(&self, dir: Option<PathBuf>) {
+        *self.texture_cache_dir.write() = dir;
+    }
+
+    /// Sets whether [`AssetRegistry::import_texture`] compresses textures to BC7.
+    pub fn set_compress_textures(&self, compress: bool) {
+        self.compress_textures.store(compress, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if imported textures are compressed to BC7.
+    pub fn compress_textures(&self) -> bool {
+        self.compress_textures.load(Ordering::Relaxed)
+    }
+
+    /// Fetches a texture by its handle.
+    pub fn get_texture(&self, handle: AssetHandle) -> Option<Arc<Texture>> {
+        self.textures.get(&handle).map(|entry| Arc::clone(entry.value()))
+    }
+
+    /// Fetches the import settings of a texture.
+    ///
+    /// If the settings have not been loaded yet, they are read from the texture's `.euimport`
+    /// sidecar, falling back to [`TextureImportSettings::default`].
+    pub fn texture_import_settings(&self, path: &Path) -> TextureImportSettings {
+        if let Some(settings) = self.texture_settings.get(path) {
+            return *settings;
+        }
+
+        let settings = match TextureImportSettings::load(path) {
+            Ok(settings) => settings.unwrap_or_default(),
+            Err(e) => {
+                log::warn!(
+                    "Unable to load import settings for {}, using defaults: {}",
+                    path.display(),
+                    e
+                );
+                TextureImportSettings::default()
+            }
+        };
+        self.texture_settings.insert(path.to_path_buf(), settings);
+        settings
+    }
+
+    /// Reads the import settings used for every material texture of a model from the model's
+    /// own `.euimport` sidecar, such as `crate.glb.euimport`.
+    ///
+    /// This falls back to [`TextureImportSettings::MODEL_DEFAULT`] instead of the default of a
+    /// standalone texture. The sidecar is read every time, as models are loaded far less often
+    /// than they are drawn.
+    pub fn model_texture_settings(&self, model_path: &Path) -> TextureImportSettings {
+        match TextureImportSettings::load(model_path) {
+            Ok(settings) => settings.unwrap_or(TextureImportSettings::MODEL_DEFAULT),
+            Err(e) => {
+                log::warn!(
+                    "Unable to load import settings for {}, using defaults: {}",
+                    model_path.display(),
+                    e
+                );
+                TextureImportSettings::MODEL_DEFAULT
+            }
+        }
+    }
+
+    /// Sets the import settings of a texture and writes them to its `.euimport` sidecar.
+    ///
+    /// This does not affect an already imported texture until [`AssetRegistry::reimport_texture`]
+    /// is called.
+    pub fn set_texture_import_settings(
+        &self,
+        path: &Path,
+        settings: TextureImportSettings,
+    ) -> anyhow::Result<()> {
+        settings.save(path)?;
+        self.texture_settings.insert(path.to_path_buf(), settings);
+        Ok(())
+    }
+
+    /// Imports a texture from a path using its [`TextureImportSettings`], compressing it to BC7
+    /// if [`AssetRegistry::set_compress_textures`] is enabled.
+    pub fn import_texture(
+        &self,
+        graphics: Arc<SharedGraphicsContext>,
+        path: &Path,
+    ) -> anyhow::Result<AssetHandle> {
+        if self.compress_textures() {
+            return self.import_texture_compressed(graphics, path);
+        }
+
+        if let Some(existing) = self.loaded_texture(path) {
+            return Ok(existing);
+        }
+
+        let settings = self.texture_import_settings(path);
+        let image = image::open(path)
+            .with_context(|| format!("Unable to open texture {}", path.display()))?
+            .to_rgba8();
+        let texture = Texture::from_rgba_image_with_settings(graphics, &image, &settings);
+        Ok(self.insert_texture(path, texture))
+    }
+
+    /// Imports an image to be drawn by a [`crate::sprite::SpriteRenderer`].
+    ///
+    /// Unlike [`AssetRegistry::import_texture`], the texture is never compressed, as sprites are
+    /// usually small and rely on exact alpha values for their edges.
+    pub fn import_sprite(
+        &self,
+        graphics: Arc<SharedGraphicsContext>,
+        path: &Path,
+    ) -> anyhow::Result<AssetHandle> {
+        if let Some(existing) = self.loaded_texture(path) {
+            return Ok(existing);
+        }
+
+        let settings = self.texture_import_settings(path);
+        let image = image::open(path)
+            .with_context(|| format!("Unable to open sprite {}", path.display()))?
+            .to_rgba8();
+        let texture = Texture::from_rgba_image_with_settings(graphics, &image, &settings);
+        Ok(self.insert_texture(path, texture))
+    }
+
+    /// Imports a texture from a path and compresses it to BC7.
+    ///
+    /// The compressed blocks are cached as `<hash>.bc7` in the texture cache directory, so
+    /// importing the same image again skips recompression. If the device does not support BC
+    /// compression, the texture is uploaded as RGBA8 instead.
+    pub fn import_texture_compressed(
+        &self,
+        graphics: Arc<SharedGraphicsContext>,
+        path: &Path,
+    ) -> anyhow::Result<AssetHandle> {
+        if let Some(existing) = self.loaded_texture(path) {
+            return Ok(existing);
+        }
+
+        let settings = self.texture_import_settings(path);
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Unable to read texture {}", path.display()))?;
+
+        let compress = supports_bc_compression(&graphics);
+        let texture = self
+            .prepare_texture(&bytes, compress)?
+            .upload(graphics, &settings);
+        Ok(self.insert_texture(path, texture))
+    }
+
+    /// Returns `true` if textures loaded for models should be compressed to BC7, which is only
+    /// the case if [`AssetRegistry::set_compress_textures`] is enabled and the device supports it.
+    pub fn should_compress_textures(&self, graphics: &SharedGraphicsContext) -> bool {
+        self.compress_textures() && supports_bc_compression(graphics)
+    }
+
+    /// Decodes an encoded image, compressing it to BC7 if `compress` is set.
+    ///
+    /// The compressed blocks are cached as `<hash>.bc7` in the texture cache directory, so
+    /// preparing the same image again skips recompression. This does not touch the GPU, so it can
+    /// be run on another thread.
+    pub fn prepare_texture(&self, bytes: &[u8], compress: bool) -> anyhow::Result<PreparedTexture> {
+        if !compress {
+            let image = image::load_from_memory(bytes)?.to_rgba8();
+            return Ok(PreparedTexture::Rgba(image));
+        }
+
+        let hash = format!("{:x}", Sha256::digest(bytes));
+        let cache_path = self
+            .texture_cache_dir
+            .read()
+            .as_ref()
+            .map(|dir| dir.join(format!("{}.bc7", hash)));
+
+        let cached = cache_path
+            .as_ref()
+            .and_then(|p| std::fs::read(p).ok())
+            .and_then(|data| decode_bc7_cache(&data));
+
+        let (dimensions, blocks) = if let Some(cached) = cached {
+            log::debug!("Using cached BC7 texture {}", hash);
+            cached
+        } else {
+            let (dimensions, blocks) = compress_bc7(bytes)?;
+            if let Some(cache_path) = &cache_path {
+                if let Some(parent) = cache_path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(cache_path, encode_bc7_cache(dimensions, &blocks))?;
+                log::debug!("Cached BC7 texture at {}", cache_path.display());
+            }
+            (dimensions, blocks)
+        };
+
+        Ok(PreparedTexture::Bc7 { dimensions, blocks })
+    }
+
+    /// Evicts the GPU texture of a path and imports it again with its current
+    /// [`TextureImportSettings`].
+    ///
+    /// The returned handle is the same as the one from the original import.
+    pub fn reimport_texture(
+        &self,
+        graphics: Arc<SharedGraphicsContext>,
+        path: &Path,
+    ) -> anyhow::Result<AssetHandle> {
+        self.evict_texture(path);
+        self.import_texture(graphics, path)
+    }
+
+    /// Copies a glTF model into `<resources_dir>/models` and loads it.
+    ///
+    /// Models that are already inside `resources_dir` are loaded in place. If a different file
+    /// with the same name already exists, a numeric suffix is added to the copy.
+    ///
+    /// A `.gltf` that keeps its buffers or images in separate files is rejected, as models are
+    /// loaded from a single file.
+    pub async fn import_model(
+        &self,
+        graphics: Arc<SharedGraphicsContext>,
+        source: &Path,
+        resources_dir: &Path,
+    ) -> anyhow::Result<LoadedModel> {
+        let extension = source
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        if !matches!(extension.as_deref(), Some("glb" | "gltf")) {
+            anyhow::bail!("{} is not a glTF model", source.display());
+        }
+
+        if extension.as_deref() == Some("gltf") {
+            let bytes = std::fs::read(source)
+                .with_context(|| format!("Unable to read {}", source.display()))?;
+            let external = external_gltf_uris(&bytes)
+                .with_context(|| format!("Unable to parse {}", source.display()))?;
+            if !external.is_empty() {
+                anyhow::bail!(
+                    "{} uses external files ({}), export it as a .glb or with embedded data instead",
+                    source.display(),
+                    external.join(", ")
+                );
+            }
+        }
+
+        let destination = if source.starts_with(resources_dir) {
+            source.to_path_buf()
+        } else {
+            let models_dir = resources_dir.join("models");
+            std::fs::create_dir_all(&models_dir)?;
+            let destination = Self::unique_import_path(source, &models_dir)?;
+            if !destination.exists() {
+                std::fs::copy(source, &destination).with_context(|| {
+                    format!("Unable to copy {} into the project", source.display())
+                })?;
+                log::info!("Imported {} to {}", source.display(), destination.display());
+            }
+            destination
+        };
+
+        let label = destination
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string());
+        Model::load_raw(
+            graphics,
+            &destination,
+            label.as_deref(),
+            self,
+            LazyLock::force(&MODEL_CACHE),
+        )
+        .await
+    }
+
+    /// Finds a path in `dir` to copy `source` to, reusing an existing file if it has the same
+    /// contents.
+    fn unique_import_path(source: &Path, dir: &Path) -> anyhow::Result<PathBuf> {
+        let stem = source
+            .file_stem()
+            .ok_or_else(|| anyhow::anyhow!("{} has no file name", source.display()))?
+            .to_string_lossy();
+        let extension = source
+            .extension()
+            .map(|e| e.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let source_bytes = std::fs::read(source)?;
+
+        let mut index = 0;
+        loop {
+            let name = if index == 0 {
+                format!("{stem}.{extension}")
+            } else {
+                format!("{stem}_{index}.{extension}")
+            };
+            let candidate = dir.join(name);
+            if !candidate.exists() || std::fs::read(&candidate)? == source_bytes {
+                return Ok(candidate);
+            }
+            index += 1;
+        }
+    }
+
+    /// Removes the GPU texture of a path from the registry, keeping its handle reserved so it can
+    /// be reimported. Returns `true` if a texture was evicted.
+    pub fn evict_texture(&self, path: &Path) -> bool {
+        let Some(handle) = self.texture_lookup.get(path).map(|h| *h) else {
+            return false;
+        };
+        let evicted = self.textures.remove(&handle).is_some();
+        if evicted {
+            log::debug!("Evicted texture {}", path.display());
+        }
+        evicted
+    }
+
+    /// Fetches the handle of the texture at a path, allocating one if the texture has never been
+    /// imported. The texture itself is not loaded, so [`AssetRegistry::get_texture`] returns `None`
+    /// until it is imported.
+    pub fn reserve_texture_handle(&self, path: &Path) -> AssetHandle {
+        *self
+            .texture_lookup
+            .entry(path.to_path_buf())
+            .or_insert_with(|| self.allocate_handle())
+    }
+
+    /// Returns `true` if a texture has been imported from this path and is still loaded.
+    pub fn is_texture_loaded(&self, path: &Path) -> bool {
+        self.loaded_texture(path).is_some()
+    }
+
+    fn loaded_texture(&self, path: &Path) -> Option<AssetHandle> {
+        let handle = *self.texture_lookup.get(path)?;
+        self.textures.contains_key(&handle).then_some(handle)
+    }
+
+    fn insert_texture(&self, path: &Path, texture: Texture) -> AssetHandle {
+        let handle = self
+            .texture_lookup
+            .get(path)
+            .map(|h| *h)
+            .unwrap_or_else(|| self.allocate_handle());
+        self.textures.insert(handle, Arc::new(texture));
+        self.texture_lookup.insert(path.to_path_buf(), handle);
+        handle
+    }
+
+    /// Registers the texture of a packed [`Atlas`], returning the handle of the texture.
+    pub fn register_atlas(&self, atlas: Atlas, texture: Texture) -> AssetHandle {
+        let handle = self.allocate_handle();
+        self.textures.insert(handle, Arc::new(texture));
+        self.atlases.insert(handle, Arc::new(atlas));
+        handle
+    }
+
+    /// Fetches the atlas that a texture handle was packed from.
+    pub fn get_atlas(&self, handle: AssetHandle) -> Option<Arc<Atlas>> {
+        self.atlases.get(&handle).map(|entry| entry.clone())
+    }
+
+    /// Iterates through all loaded textures.
+    pub fn iter_texture(&self) -> dashmap::iter::Iter<'_, AssetHandle, Arc<Texture>> {
+        self.textures.iter()
+    }
+
+    /// Iterates through all atlases, keyed by their texture handle.
+    pub fn iter_atlas(&self) -> dashmap::iter::Iter<'_, AssetHandle, Arc<Atlas>> {
+        self.atlases.iter()
+    }
+
     /// Iterates through all models, allowing you to iterate through all items in the
     /// model registry.
     pub fn iter_model(&self) -> dashmap::iter::Iter<'_, AssetHandle, Arc<Model>> {
@@ -221,6 +896,8 @@ impl AssetRegistry {
             Some(AssetKind::Material)
         } else if self.meshes.contains_key(&handle) {
             Some(AssetKind::Mesh)
+        } else if self.textures.contains_key(&handle) {
+            Some(AssetKind::Texture)
         } else {
             None
         }
@@ -231,6 +908,7 @@ impl AssetRegistry {
         self.models.contains_key(&handle)
             || self.materials.contains_key(&handle)
             || self.meshes.contains_key(&handle)
+            || self.textures.contains_key(&handle)
     }
 
     /// Returns `true` if the handle represents the expected asset kind.
@@ -345,6 +1023,35 @@ impl AssetRegistry {
             .map(|entry| *entry)
     }
 
+    /// Removes the materials and meshes cached for a model, along with their handles.
+    fn forget_model_components(&self, model_id: ModelId) {
+        self.model_id_lookup.remove(&model_id);
+
+        self.material_lookup.retain(|(owner, _), handle| {
+            if *owner != model_id {
+                return true;
+            }
+            self.materials.remove(handle);
+            self.material_owners.remove(handle);
+            if let Some((_, reference)) = self.material_references.remove(handle) {
+                self.material_reference_lookup.remove(&reference);
+            }
+            false
+        });
+
+        self.mesh_lookup.retain(|(owner, _), handle| {
+            if *owner != model_id {
+                return true;
+            }
+            self.meshes.remove(handle);
+            self.mesh_owners.remove(handle);
+            if let Some((_, reference)) = self.mesh_references.remove(handle) {
+                self.mesh_reference_lookup.remove(&reference);
+            }
+            false
+        });
+    }
+
     fn cache_model_components(&self, model: &Arc<Model>) {
         let model_id = model.id;
 
@@ -403,6 +1110,158 @@ impl Default for AssetRegistry {
 
 pub static ASSET_REGISTRY: LazyLock<AssetRegistry> = LazyLock::new(AssetRegistry::new);
 
+/// Watches the folders of tracked model files and collects every file in them that changes.
+///
+/// Folders are watched rather than the files themselves, as most exporters replace the file
+/// instead of writing to it.
+struct AssetWatcher {
+    watcher: RecommendedWatcher,
+    receiver: Receiver<PathBuf>,
+    watched_dirs: HashSet<PathBuf>,
+}
+
+impl AssetWatcher {
+    fn new() -> anyhow::Result<Self> {
+        let (sender, receiver) = unbounded();
+        let watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+            let event = match result {
+                Ok(event) => event,
+                Err(e) => {
+                    log::warn!("Asset watcher error: {}", e);
+                    return;
+                }
+            };
+
+            if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                return;
+            }
+
+            for path in event.paths {
+                let _ = sender.send(path);
+            }
+        })?;
+
+        Ok(Self {
+            watcher,
+            receiver,
+            watched_dirs: HashSet::new(),
+        })
+    }
+
+    fn watch(&mut self, file: &Path) -> anyhow::Result<()> {
+        let Some(dir) = file.parent() else {
+            anyhow::bail!("{} has no parent folder", file.display());
+        };
+        if self.watched_dirs.contains(dir) {
+            return Ok(());
+        }
+
+        self.watcher.watch(dir, RecursiveMode::NonRecursive)?;
+        self.watched_dirs.insert(dir.to_path_buf());
+        Ok(())
+    }
+}
+
+/// Lists the URIs of the buffers and images in a glTF document that point to other files, leaving
+/// out the ones embedded as `data:` URIs.
+fn external_gltf_uris(bytes: &[u8]) -> anyhow::Result<Vec<String>> {
+    let gltf = gltf::Gltf::from_slice(bytes)?;
+    let buffers = gltf.buffers().filter_map(|buffer| match buffer.source() {
+        gltf::buffer::Source::Uri(uri) => Some(uri.to_string()),
+        gltf::buffer::Source::Bin => None,
+    });
+    let images = gltf.images().filter_map(|image| match image.source() {
+        gltf::image::Source::Uri { uri, .. } => Some(uri.to_string()),
+        gltf::image::Source::View { .. } => None,
+    });
+    Ok(buffers
+        .chain(images)
+        .filter(|uri| !uri.starts_with("data:"))
+        .collect())
+}
+
+/// A texture decoded by [`AssetRegistry::prepare_texture`], ready to be uploaded to the GPU.
+pub enum PreparedTexture {
+    Rgba(image::RgbaImage),
+    Bc7 {
+        dimensions: (u32, u32),
+        blocks: Vec<u8>,
+    },
+}
+
+impl PreparedTexture {
+    /// Creates the GPU texture, using the colour space, sampler and mipmap options of the
+    /// [`TextureImportSettings`].
+    pub fn upload(
+        &self,
+        graphics: Arc<SharedGraphicsContext>,
+        settings: &TextureImportSettings,
+    ) -> Texture {
+        match self {
+            Self::Rgba(image) => Texture::from_rgba_image_with_settings(graphics, image, settings),
+            Self::Bc7 { dimensions, blocks } => {
+                Texture::from_bc7_buffer(graphics, blocks, *dimensions, settings)
+            }
+        }
+    }
+}
+
+/// Checks if the device can sample BC compressed textures, warning once if it can't.
+fn supports_bc_compression(graphics: &SharedGraphicsContext) -> bool {
+    let supported = graphics
+        .device
+        .features()
+        .contains(wgpu::Features::TEXTURE_COMPRESSION_BC);
+    if !supported {
+        log_once::warn_once!("BC texture compression is not supported, using RGBA8");
+    }
+    supported
+}
+
+/// Compresses an encoded image into BC7 blocks, padding it to a multiple of 4 if required.
+fn compress_bc7(bytes: &[u8]) -> anyhow::Result<((u32, u32), Vec<u8>)> {
+    let image = image::load_from_memory(bytes)?.to_rgba8();
+    let (width, height) = image.dimensions();
+    let padded = (width.div_ceil(4) * 4, height.div_ceil(4) * 4);
+
+    let image = if padded != (width, height) {
+        let mut canvas = image::RgbaImage::new(padded.0, padded.1);
+        image::imageops::replace(&mut canvas, &image, 0, 0);
+        canvas
+    } else {
+        image
+    };
+
+    let surface = intel_tex_2::RgbaSurface {
+        data: &image,
+        width: padded.0,
+        height: padded.1,
+        stride: padded.0 * 4,
+    };
+    let blocks = intel_tex_2::bc7::compress_blocks(&intel_tex_2::bc7::alpha_basic_settings(), &surface);
+    Ok((padded, blocks))
+}
+
+/// Cache layout: width (u32 LE), height (u32 LE), followed by the BC7 blocks.
+fn encode_bc7_cache(dimensions: (u32, u32), blocks: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(8 + blocks.len());
+    data.extend_from_slice(&dimensions.0.to_le_bytes());
+    data.extend_from_slice(&dimensions.1.to_le_bytes());
+    data.extend_from_slice(blocks);
+    data
+}
+
+fn decode_bc7_cache(data: &[u8]) -> Option<((u32, u32), Vec<u8>)> {
+    let width = u32::from_le_bytes(data.get(0..4)?.try_into().ok()?);
+    let height = u32::from_le_bytes(data.get(4..8)?.try_into().ok()?);
+    let blocks = data.get(8..)?;
+    let expected = (width.div_ceil(4) * height.div_ceil(4) * 16) as usize;
+    if blocks.len() != expected {
+        return None;
+    }
+    Some(((width, height), blocks.to_vec()))
+}
+
 fn material_reference_from_model(model: &Model, name: &str) -> Option<ResourceReference> {
     let base_uri = model.path.as_uri()?;
     let material_component = sanitize_material_component(name);
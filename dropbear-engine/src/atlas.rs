@@ -0,0 +1,175 @@
+//! Packing of many small images into a single texture, so sprites that share an atlas can be drawn
+//! together.
+
+use crate::asset::{ASSET_REGISTRY, AssetHandle};
+use crate::graphics::{SharedGraphicsContext, Texture};
+use image::{DynamicImage, GenericImage, RgbaImage};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// The area an image takes up in an atlas, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    /// Converts the rect to texture coordinates as `[min_u, min_v, max_u, max_v]`.
+    pub fn uv(&self, atlas_size: (u32, u32)) -> [f32; 4] {
+        let (width, height) = (atlas_size.0 as f32, atlas_size.1 as f32);
+        [
+            self.x as f32 / width,
+            self.y as f32 / height,
+            (self.x + self.width) as f32 / width,
+            (self.y + self.height) as f32 / height,
+        ]
+    }
+}
+
+/// Packs images into a texture atlas using a shelf algorithm.
+///
+/// Images are placed left to right along a shelf, and a new shelf is started above the tallest
+/// image of the previous one once the row is full.
+pub struct AtlasBuilder {
+    width: u32,
+    cursor_x: u32,
+    shelf_y: u32,
+    shelf_height: u32,
+    images: Vec<(String, Rect, RgbaImage)>,
+}
+
+impl AtlasBuilder {
+    /// Creates a builder for an atlas that is `size` pixels wide. The size is rounded up to a
+    /// power of two, and grows if an image wider than it is added.
+    pub fn new(size: u32) -> Self {
+        Self {
+            width: size.max(1).next_power_of_two(),
+            cursor_x: 0,
+            shelf_y: 0,
+            shelf_height: 0,
+            images: Vec::new(),
+        }
+    }
+
+    /// Places an image in the atlas, returning the area it was given.
+    ///
+    /// Adding an image with a label that is already in the atlas replaces its lookup, but both
+    /// images are still packed.
+    pub fn add_image(&mut self, label: &str, img: DynamicImage) -> Rect {
+        let img = img.to_rgba8();
+        let (width, height) = img.dimensions();
+
+        if width > self.width {
+            log::warn!(
+                "Image '{}' is wider than the atlas ({} > {}), growing the atlas",
+                label,
+                width,
+                self.width
+            );
+            self.width = width.next_power_of_two();
+        }
+
+        if self.cursor_x + width > self.width {
+            self.shelf_y += self.shelf_height;
+            self.cursor_x = 0;
+            self.shelf_height = 0;
+        }
+
+        let rect = Rect {
+            x: self.cursor_x,
+            y: self.shelf_y,
+            width,
+            height,
+        };
+        self.cursor_x += width;
+        self.shelf_height = self.shelf_height.max(height);
+
+        self.images.push((label.to_string(), rect, img));
+        rect
+    }
+
+    /// The size the atlas texture will have once built.
+    pub fn size(&self) -> (u32, u32) {
+        let height = (self.shelf_y + self.shelf_height)
+            .max(1)
+            .next_power_of_two();
+        (self.width, height)
+    }
+
+    /// Copies every image into a single texture and registers it in the [`ASSET_REGISTRY`].
+    pub fn build(self, graphics: Arc<SharedGraphicsContext>, label: &str) -> AtlasHandle {
+        let size = self.size();
+        let mut image = RgbaImage::new(size.0, size.1);
+        let mut sprites = BTreeMap::new();
+
+        for (sprite_label, rect, img) in self.images {
+            if let Err(e) = image.copy_from(&img, rect.x, rect.y) {
+                log::warn!(
+                    "Unable to copy '{}' into atlas '{}': {}",
+                    sprite_label,
+                    label,
+                    e
+                );
+                continue;
+            }
+            sprites.insert(sprite_label, rect);
+        }
+
+        let texture = Texture::from_rgba_buffer(graphics, image.as_raw(), size);
+        let uvs = sprites
+            .iter()
+            .map(|(sprite_label, rect)| (sprite_label.clone(), rect.uv(size)))
+            .collect();
+
+        let atlas = Atlas {
+            label: label.to_string(),
+            size,
+            sprites,
+            image,
+        };
+        let texture = ASSET_REGISTRY.register_atlas(atlas, texture);
+
+        log::debug!(
+            "Packed atlas '{}' into a {}x{} texture",
+            label,
+            size.0,
+            size.1
+        );
+
+        AtlasHandle {
+            texture,
+            uvs: Arc::new(uvs),
+        }
+    }
+}
+
+/// A packed atlas, as stored in the [`ASSET_REGISTRY`].
+#[derive(Debug)]
+pub struct Atlas {
+    pub label: String,
+    /// Width and height of the atlas texture
+    pub size: (u32, u32),
+    /// The area of every image in the atlas, by label
+    pub sprites: BTreeMap<String, Rect>,
+    /// The packed pixels, kept around so the atlas can be previewed in the editor
+    pub image: RgbaImage,
+}
+
+/// A reference to a built atlas texture, along with the texture coordinates of every sprite in it.
+#[derive(Debug, Clone)]
+pub struct AtlasHandle {
+    /// The handle of the atlas texture
+    pub texture: AssetHandle,
+    /// Texture coordinates as `[min_u, min_v, max_u, max_v]`, by sprite label
+    pub uvs: Arc<HashMap<String, [f32; 4]>>,
+}
+
+impl AtlasHandle {
+    /// Fetches the texture coordinates of a sprite in the atlas.
+    pub fn uv(&self, label: &str) -> Option<[f32; 4]> {
+        self.uvs.get(label).copied()
+    }
+}
@@ -1,5 +1,8 @@
 //! Vertices and different buffers used for wgpu
 
+use std::marker::PhantomData;
+use wgpu::util::DeviceExt;
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct Vertex {
@@ -27,3 +30,163 @@ impl Vertex {
         }
     }
 }
+
+/// A [`wgpu::Buffer`] holding values of `T`, which keeps track of how many fit in it.
+///
+/// [`wgpu::BufferUsages::COPY_DST`] is always added to the usage so the buffer can be
+/// [updated](TypedBuffer::update).
+#[derive(Clone)]
+pub struct TypedBuffer<T: bytemuck::Pod> {
+    buffer: wgpu::Buffer,
+    usage: wgpu::BufferUsages,
+    label: Option<String>,
+    /// The number of values that fit in the buffer
+    capacity: usize,
+    /// The number of values last written to the buffer
+    len: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<T: bytemuck::Pod> std::fmt::Debug for TypedBuffer<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TypedBuffer")
+            .field("type", &std::any::type_name::<T>())
+            .field("label", &self.label)
+            .field("capacity", &self.capacity)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl<T: bytemuck::Pod> TypedBuffer<T> {
+    /// Creates a buffer holding `data`, with room for exactly that many values.
+    pub fn new(
+        device: &wgpu::Device,
+        usage: wgpu::BufferUsages,
+        data: &[T],
+        label: Option<&str>,
+    ) -> Self {
+        let usage = usage | wgpu::BufferUsages::COPY_DST;
+        Self {
+            buffer: Self::create(device, usage, data, label),
+            usage,
+            label: label.map(str::to_string),
+            capacity: data.len(),
+            len: data.len(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Creates a uniform buffer holding a single value.
+    pub fn uniform(device: &wgpu::Device, value: T, label: Option<&str>) -> Self {
+        Self::new(device, wgpu::BufferUsages::UNIFORM, &[value], label)
+    }
+
+    fn create(
+        device: &wgpu::Device,
+        usage: wgpu::BufferUsages,
+        data: &[T],
+        label: Option<&str>,
+    ) -> wgpu::Buffer {
+        device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label,
+            contents: bytemuck::cast_slice(data),
+            usage,
+        })
+    }
+
+    /// Writes `data` to the start of the buffer.
+    ///
+    /// If `data` doesn't fit, a new buffer big enough for it is created in place of the old one
+    /// and `true` is returned, in which case any bind groups using the buffer have to be created
+    /// again.
+    pub fn update(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, data: &[T]) -> bool {
+        self.len = data.len();
+        let Some(capacity) = grown_capacity(self.capacity, data.len()) else {
+            queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(data));
+            return false;
+        };
+
+        log::trace!(
+            "Growing buffer {:?} from {} to {} values",
+            self.label,
+            self.capacity,
+            capacity
+        );
+        self.buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: self.label.as_deref(),
+            size: (capacity * std::mem::size_of::<T>()) as wgpu::BufferAddress,
+            usage: self.usage,
+            mapped_at_creation: false,
+        });
+        self.capacity = capacity;
+        queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(data));
+        true
+    }
+
+    /// Binds the whole buffer at `binding` of a bind group.
+    pub fn bind_group_entry(&self, binding: u32) -> wgpu::BindGroupEntry<'_> {
+        wgpu::BindGroupEntry {
+            binding,
+            resource: self.buffer.as_entire_binding(),
+        }
+    }
+
+    pub fn buffer(&self) -> &wgpu::Buffer {
+        &self.buffer
+    }
+
+    /// The number of values that fit in the buffer without creating a new one.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of values last written to the buffer.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The part of the buffer holding the values last written, such as for binding it as a
+    /// vertex buffer.
+    pub fn slice(&self) -> wgpu::BufferSlice<'_> {
+        self.buffer
+            .slice(..(self.len * std::mem::size_of::<T>()) as wgpu::BufferAddress)
+    }
+}
+
+/// The capacity a buffer holding `capacity` values needs to grow to for `needed` values, or `None`
+/// if they already fit.
+///
+/// The capacity at least doubles, so a buffer that is updated every frame with a slowly growing
+/// number of values is only created again a handful of times.
+pub fn grown_capacity(capacity: usize, needed: usize) -> Option<usize> {
+    if needed <= capacity {
+        None
+    } else {
+        Some(needed.max(capacity * 2))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fits_without_growing() {
+        assert_eq!(grown_capacity(8, 0), None);
+        assert_eq!(grown_capacity(8, 5), None);
+        assert_eq!(grown_capacity(8, 8), None);
+    }
+
+    #[test]
+    fn test_grows_to_at_least_double() {
+        assert_eq!(grown_capacity(8, 9), Some(16));
+        assert_eq!(grown_capacity(8, 40), Some(40));
+        assert_eq!(grown_capacity(0, 1), Some(1));
+        assert_eq!(grown_capacity(0, 0), None);
+    }
+}
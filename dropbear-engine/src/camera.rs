@@ -2,13 +2,14 @@
 
 use std::sync::Arc;
 
-use glam::{DMat4, DQuat, DVec3, Mat4};
+use glam::{DMat4, DQuat, DVec3, DVec4, Mat4};
 use serde::{Deserialize, Serialize};
 use wgpu::{
-    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroup, BindGroupDescriptor, BindGroupLayout, BindGroupLayoutDescriptor,
     BindGroupLayoutEntry, BindingType, Buffer, BufferBindingType, ShaderStages,
 };
 
+use crate::buffer::TypedBuffer;
 use crate::graphics::SharedGraphicsContext;
 
 /// Matrix that converts OpenGL (from [`glam`]) to [`wgpu`] values
@@ -44,6 +45,39 @@ impl Default for CameraSettings {
     }
 }
 
+/// How a [`Camera`] projects the scene onto the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum ProjectionKind {
+    /// Uses the camera's `fov_y` and near plane, with an infinite far plane
+    #[default]
+    Perspective,
+    /// Projects along parallel lines within a box, for 2D games and top-down map views
+    Orthographic {
+        left: f64,
+        right: f64,
+        bottom: f64,
+        top: f64,
+        near: f64,
+        far: f64,
+    },
+}
+
+impl ProjectionKind {
+    /// Creates an orthographic projection centred on the camera, showing `half_height` units
+    /// above and below it.
+    pub fn orthographic(half_height: f64, aspect: f64, near: f64, far: f64) -> Self {
+        let half_width = half_height * aspect;
+        Self::Orthographic {
+            left: -half_width,
+            right: half_width,
+            bottom: -half_height,
+            top: half_height,
+            near,
+            far,
+        }
+    }
+}
+
 /// The basic values of a Camera.
 #[derive(Default, Debug, Clone)]
 pub struct Camera {
@@ -62,6 +96,8 @@ pub struct Camera {
     pub znear: f64,
     /// Far buffer?
     pub zfar: f64,
+    /// Whether the camera uses a perspective or orthographic projection
+    pub projection: ProjectionKind,
     /// Yaw (rotation)
     pub yaw: f64,
     /// Pitch (rotation)
@@ -70,9 +106,15 @@ pub struct Camera {
     /// Tuning values that control movement and projection
     pub settings: CameraSettings,
 
+    /// Bitmask of the [`EntityLayer`](crate::entity::EntityLayer)s this camera renders
+    pub visible_layers: u32,
+
+    /// The shake currently being applied to the camera, if any
+    pub shake: Option<ActiveCameraShake>,
+
     /// Uniform/interface for Rust and the GPU
     pub uniform: CameraUniform,
-    buffer: Option<Buffer>,
+    buffer: Option<TypedBuffer<CameraUniform>>,
 
     layout: Option<BindGroupLayout>,
     bind_group: Option<BindGroup>,
@@ -83,6 +125,100 @@ pub struct Camera {
     pub proj_mat: DMat4,
 }
 
+/// Describes a camera shake, such as from an explosion.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CameraShake {
+    /// The maximum distance the camera is offset by
+    pub intensity: f32,
+    /// How long the shake lasts, in seconds
+    pub duration: f32,
+    /// How quickly the shake fades out. `1.0` is linear, higher values fade out faster.
+    pub decay: f32,
+    /// How many times per second the camera oscillates
+    pub frequency: f32,
+}
+
+impl CameraShake {
+    /// Creates a new shake with a linear decay and a frequency of 25Hz.
+    pub fn new(intensity: f32, duration: f32) -> Self {
+        Self {
+            intensity,
+            duration,
+            decay: 1.0,
+            frequency: 25.0,
+        }
+    }
+}
+
+/// A [`CameraShake`] that is currently being applied to a [`Camera`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ActiveCameraShake {
+    pub shake: CameraShake,
+    /// Time since the shake started, in seconds
+    pub elapsed: f32,
+    /// The offset added to the camera this frame
+    pub offset: DVec3,
+}
+
+impl ActiveCameraShake {
+    /// Time left until the shake stops, in seconds.
+    pub fn remaining(&self) -> f32 {
+        (self.shake.duration - self.elapsed).max(0.0)
+    }
+
+    /// The current maximum offset, after decay.
+    pub fn amplitude(&self) -> f32 {
+        if self.shake.duration <= 0.0 {
+            return 0.0;
+        }
+        let progress = (self.elapsed / self.shake.duration).clamp(0.0, 1.0);
+        self.shake.intensity * (1.0 - progress).powf(self.shake.decay.max(0.0))
+    }
+}
+
+/// The side planes of a camera's view volume, used for culling.
+///
+/// The near and far planes are not included as the perspective projection has an infinite far
+/// plane.
+#[derive(Debug, Clone, Copy)]
+pub struct Frustum {
+    /// Left, right, bottom and top planes, with normals pointing inwards
+    planes: [DVec4; 4],
+}
+
+impl Frustum {
+    pub fn from_view_proj(view_proj: DMat4) -> Self {
+        let x = view_proj.row(0);
+        let y = view_proj.row(1);
+        let w = view_proj.row(3);
+
+        let normalise = |plane: DVec4| {
+            let length = plane.truncate().length();
+            if length > 0.0 {
+                plane / length
+            } else {
+                plane
+            }
+        };
+
+        Self {
+            planes: [
+                normalise(w + x),
+                normalise(w - x),
+                normalise(w + y),
+                normalise(w - y),
+            ],
+        }
+    }
+
+    /// Returns `true` if any part of the sphere is inside the frustum.
+    pub fn intersects_sphere(&self, centre: DVec3, radius: f64) -> bool {
+        self.planes
+            .iter()
+            .all(|p| p.truncate().dot(centre) + p.w >= -radius)
+    }
+}
+
 /// A simple builder/struct that allows you to build a [`Camera`]
 pub struct CameraBuilder {
     pub eye: DVec3,
@@ -91,6 +227,7 @@ pub struct CameraBuilder {
     pub aspect: f64,
     pub znear: f64,
     pub zfar: f64,
+    pub projection: ProjectionKind,
     pub settings: CameraSettings,
 }
 
@@ -114,6 +251,7 @@ impl Camera {
             aspect: builder.aspect,
             znear: builder.znear,
             zfar: builder.zfar,
+            projection: builder.projection,
             uniform,
             buffer: None,
             layout: None,
@@ -121,6 +259,7 @@ impl Camera {
             yaw,
             pitch,
             settings: builder.settings,
+            visible_layers: u32::MAX,
             label: if let Some(l) = label {
                 l.to_string()
             } else {
@@ -129,8 +268,8 @@ impl Camera {
             ..Default::default()
         };
         camera.update_view_proj();
-        let buffer = graphics.create_uniform(camera.uniform, Some("Camera Uniform"));
-        camera.create_bind_group_layout(graphics.clone(), buffer.clone());
+        let buffer = TypedBuffer::uniform(&graphics.device, camera.uniform, Some("Camera Uniform"));
+        camera.create_bind_group_layout(graphics.clone(), &buffer);
         camera.buffer = Some(buffer);
         log::debug!("Created new camera");
         camera
@@ -147,6 +286,7 @@ impl Camera {
                 aspect: (graphics.screen_size.0 / graphics.screen_size.1).into(),
                 znear: 0.1,
                 zfar: 100.0,
+                projection: ProjectionKind::Perspective,
                 settings: CameraSettings::new(1.0, 0.002, 45.0),
             },
             label,
@@ -160,7 +300,7 @@ impl Camera {
     }
 
     pub fn uniform_buffer(&self) -> &Buffer {
-        self.buffer.as_ref().unwrap()
+        self.buffer.as_ref().unwrap().buffer()
     }
 
     pub fn layout(&self) -> &BindGroupLayout {
@@ -179,6 +319,95 @@ impl Camera {
         self.eye
     }
 
+    /// Starts shaking the camera, replacing any shake that is currently active.
+    pub fn apply_shake(&mut self, shake: CameraShake) {
+        self.shake = Some(ActiveCameraShake {
+            shake,
+            elapsed: 0.0,
+            offset: DVec3::ZERO,
+        });
+    }
+
+    /// Advances the active shake by `dt` seconds, removing it once it has finished.
+    ///
+    /// This should be called once per frame before [`Camera::update`].
+    pub fn update_shake(&mut self, dt: f32) {
+        let Some(active) = &mut self.shake else {
+            return;
+        };
+
+        active.elapsed += dt;
+        if active.elapsed >= active.shake.duration {
+            self.shake = None;
+            return;
+        }
+
+        // a few sine waves at unrelated frequencies, so the motion doesn't look periodic
+        let phase = (std::f32::consts::TAU * active.shake.frequency * active.elapsed) as f64;
+        let amplitude = active.amplitude() as f64;
+        active.offset = DVec3::new(
+            phase.sin(),
+            (phase * 1.31 + 1.7).sin(),
+            (phase * 0.73 + 3.1).sin(),
+        ) * amplitude;
+    }
+
+    /// The offset currently applied by the camera shake.
+    pub fn shake_offset(&self) -> DVec3 {
+        self.shake.map(|s| s.offset).unwrap_or(DVec3::ZERO)
+    }
+
+    /// Returns `true` if the camera renders entities on the given layer bitmask.
+    pub fn can_see(&self, layer_mask: u32) -> bool {
+        self.visible_layers & layer_mask != 0
+    }
+
+    /// Sets the aspect ratio, widening or narrowing an orthographic projection around its centre
+    /// so its height stays the same.
+    pub fn set_aspect(&mut self, aspect: f64) {
+        self.aspect = aspect;
+
+        if let ProjectionKind::Orthographic {
+            left,
+            right,
+            bottom,
+            top,
+            ..
+        } = &mut self.projection
+        {
+            let centre = (*left + *right) / 2.0;
+            let half_width = (*top - *bottom) / 2.0 * aspect;
+            *left = centre - half_width;
+            *right = centre + half_width;
+        }
+    }
+
+    /// Builds the projection matrix from the camera's [`ProjectionKind`].
+    ///
+    /// Both projections map depth in reverse, with the near plane at 1 and the far plane at 0.
+    pub fn projection_matrix(&self) -> DMat4 {
+        match self.projection {
+            ProjectionKind::Perspective => DMat4::perspective_infinite_reverse_lh(
+                self.settings.fov_y.to_radians(),
+                self.aspect,
+                self.znear,
+            ),
+            ProjectionKind::Orthographic {
+                left,
+                right,
+                bottom,
+                top,
+                near,
+                far,
+            } => DMat4::orthographic_lh(left, right, bottom, top, far, near),
+        }
+    }
+
+    /// Returns the view frustum of the camera, built from the last computed view and projection.
+    pub fn frustum(&self) -> Frustum {
+        Frustum::from_view_proj(self.proj_mat * self.view_mat)
+    }
+
     /// Prints out the values of the camera.
     pub fn debug_camera_state(&self) {
         let camera = self;
@@ -189,17 +418,16 @@ impl Camera {
         log::debug!("  FOV Y: {}", camera.settings.fov_y);
         log::debug!("  Aspect: {}", camera.aspect);
         log::debug!("  Z Near: {}", camera.znear);
+        log::debug!("  Projection: {:?}", camera.projection);
         log::debug!("  Proj Mat finite: {}", camera.proj_mat.is_finite());
         log::debug!("  View Mat finite: {}", camera.view_mat.is_finite());
     }
 
     fn build_vp(&mut self) -> DMat4 {
-        let view = DMat4::look_at_lh(self.eye, self.target, self.up);
-        let proj = DMat4::perspective_infinite_reverse_lh(
-            self.settings.fov_y.to_radians(),
-            self.aspect,
-            self.znear,
-        );
+        // the target is offset as well, so the shake moves the camera instead of rotating it
+        let offset = self.shake_offset();
+        let view = DMat4::look_at_lh(self.eye + offset, self.target + offset, self.up);
+        let proj = self.projection_matrix();
 
         self.view_mat = view;
         self.proj_mat = proj;
@@ -210,7 +438,7 @@ impl Camera {
     pub fn create_bind_group_layout(
         &mut self,
         graphics: Arc<SharedGraphicsContext>,
-        camera_buffer: Buffer,
+        camera_buffer: &TypedBuffer<CameraUniform>,
     ) {
         let camera_bind_group_layout =
             graphics
@@ -231,10 +459,7 @@ impl Camera {
 
         let camera_bind_group = graphics.device.create_bind_group(&BindGroupDescriptor {
             layout: &camera_bind_group_layout,
-            entries: &[BindGroupEntry {
-                binding: 0,
-                resource: camera_buffer.as_entire_binding(),
-            }],
+            entries: &[camera_buffer.bind_group_entry(0)],
             label: Some("camera_bind_group"),
         });
         self.layout = Some(camera_bind_group_layout);
@@ -243,11 +468,9 @@ impl Camera {
 
     pub fn update(&mut self, graphics: Arc<SharedGraphicsContext>) {
         self.update_view_proj();
-        graphics.queue.write_buffer(
-            self.buffer.as_ref().unwrap(),
-            0,
-            bytemuck::cast_slice(&[self.uniform]),
-        );
+        if let Some(buffer) = &mut self.buffer {
+            buffer.update(&graphics.device, &graphics.queue, &[self.uniform]);
+        }
     }
 
     pub fn update_view_proj(&mut self) {
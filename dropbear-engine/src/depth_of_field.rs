@@ -0,0 +1,480 @@
+//! Depth of field, which blurs what is in front of and behind the point the camera focuses on.
+//!
+//! A circle of confusion is worked out for every pixel from the depth buffer, splitting the frame
+//! into a near and a far field. Both fields are blurred with a Kawase blur and blended back over
+//! the frame, so the near field bleeds over what is in focus while the far field stays behind it.
+
+use crate::camera::{Camera, OPENGL_TO_WGPU_MATRIX};
+use crate::graphics::{FrameGraphicsContext, SharedGraphicsContext};
+use crate::shader::Shader;
+use glam::DMat4;
+use parking_lot::Mutex;
+use std::sync::{Arc, LazyLock};
+use wgpu::util::DeviceExt;
+use wgpu::{BindGroup, BindGroupLayout, RenderPipeline, TextureView};
+
+/// The number of Kawase blur passes, each spreading the blur further out.
+const BLUR_PASSES: u32 = 4;
+
+const FIELD_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// The settings depth of field is rendered with, or `None` if it is turned off.
+static DEPTH_OF_FIELD: LazyLock<Mutex<Option<DepthOfFieldSettings>>> =
+    LazyLock::new(|| Mutex::new(None));
+
+/// Turns depth of field on from the next frame, or changes its settings.
+pub fn set_depth_of_field(settings: DepthOfFieldSettings) {
+    *DEPTH_OF_FIELD.lock() = Some(settings);
+}
+
+/// Turns depth of field off.
+pub fn clear_depth_of_field() {
+    *DEPTH_OF_FIELD.lock() = None;
+}
+
+/// The settings depth of field is currently rendered with, see [`set_depth_of_field`].
+pub fn depth_of_field() -> Option<DepthOfFieldSettings> {
+    DEPTH_OF_FIELD.lock().clone()
+}
+
+/// Tuning values for depth of field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepthOfFieldSettings {
+    /// The distance from the camera that is in focus, in world units
+    pub focal_distance: f32,
+    /// How deep the area around the focal distance that stays sharp is, in world units. The
+    /// blur also fades in over the same distance.
+    pub focal_range: f32,
+    /// The furthest the blur spreads, in pixels
+    pub max_blur: f32,
+    /// The label of an entity to focus on. When set, the focal distance is measured from the
+    /// camera to the entity every frame instead of using [`Self::focal_distance`].
+    pub focus_entity: Option<String>,
+}
+
+impl Default for DepthOfFieldSettings {
+    fn default() -> Self {
+        Self {
+            focal_distance: 10.0,
+            focal_range: 5.0,
+            max_blur: 8.0,
+            focus_entity: None,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct DepthOfFieldUniform {
+    inv_proj: [[f32; 4]; 4],
+    focal_distance: f32,
+    focal_range: f32,
+    max_blur: f32,
+    _padding: f32,
+}
+
+/// The textures the near and far fields are blurred in, which are recreated when the viewport is
+/// resized.
+struct DepthOfFieldTargets {
+    size: wgpu::Extent3d,
+    near_view: TextureView,
+    far_view: TextureView,
+    near_scratch_view: TextureView,
+    far_scratch_view: TextureView,
+}
+
+/// Renders depth of field on top of a frame.
+pub struct DepthOfField {
+    coc_pipeline: RenderPipeline,
+    blur_pipeline: RenderPipeline,
+    composite_pipeline: RenderPipeline,
+    coc_layout: BindGroupLayout,
+    blur_layout: BindGroupLayout,
+    composite_layout: BindGroupLayout,
+    sampler: wgpu::Sampler,
+    targets: Option<DepthOfFieldTargets>,
+}
+
+impl DepthOfField {
+    pub fn new(graphics: Arc<SharedGraphicsContext>) -> Self {
+        let shader = Shader::new(
+            graphics.clone(),
+            crate::shader::shader_wesl::DEPTH_OF_FIELD_SHADER,
+            Some("depth_of_field_shader"),
+        );
+
+        let depth_entry = wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Depth,
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+        let uniform_entry = wgpu::BindGroupLayoutEntry {
+            binding: 1,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let texture_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+
+        let create_layout = |label, entries: &[wgpu::BindGroupLayoutEntry]| {
+            graphics
+                .device
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: Some(label),
+                    entries,
+                })
+        };
+        let coc_layout = create_layout(
+            "depth_of_field_coc_bind_group_layout",
+            &[depth_entry, uniform_entry, texture_entry(2)],
+        );
+        let blur_layout = create_layout(
+            "depth_of_field_blur_bind_group_layout",
+            &[
+                uniform_entry,
+                texture_entry(3),
+                texture_entry(4),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 5,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        );
+        let composite_layout = create_layout(
+            "depth_of_field_composite_bind_group_layout",
+            &[
+                depth_entry,
+                uniform_entry,
+                texture_entry(6),
+                texture_entry(7),
+            ],
+        );
+
+        let field_target = Some(wgpu::ColorTargetState {
+            format: FIELD_FORMAT,
+            blend: None,
+            write_mask: wgpu::ColorWrites::ALL,
+        });
+        // the blur covers the scene by its alpha, leaving the scene's own alpha alone
+        let composite_target = Some(wgpu::ColorTargetState {
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            blend: Some(wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::SrcAlpha,
+                    dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::Zero,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+            }),
+            write_mask: wgpu::ColorWrites::ALL,
+        });
+
+        let coc_pipeline = fullscreen_pipeline(
+            &graphics,
+            &shader,
+            &coc_layout,
+            "fs_coc",
+            &[field_target.clone(), field_target.clone()],
+            "Depth of Field Circle of Confusion Pipeline",
+        );
+        let blur_pipeline = fullscreen_pipeline(
+            &graphics,
+            &shader,
+            &blur_layout,
+            "fs_blur",
+            &[field_target.clone(), field_target],
+            "Depth of Field Blur Pipeline",
+        );
+        let composite_pipeline = fullscreen_pipeline(
+            &graphics,
+            &shader,
+            &composite_layout,
+            "fs_composite",
+            &[composite_target],
+            "Depth of Field Composite Pipeline",
+        );
+
+        let sampler = graphics.device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("depth_of_field_sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        log::debug!("Created depth of field pipelines");
+
+        Self {
+            coc_pipeline,
+            blur_pipeline,
+            composite_pipeline,
+            coc_layout,
+            blur_layout,
+            composite_layout,
+            sampler,
+            targets: None,
+        }
+    }
+
+    /// Blurs the frame in front of and behind the focal range.
+    ///
+    /// `focus_distance` replaces [`DepthOfFieldSettings::focal_distance`] when it is set, which
+    /// is how [`DepthOfFieldSettings::focus_entity`] is followed, since the engine doesn't know the
+    /// labels of entities. This should be called last, after everything else in the frame is
+    /// drawn. Nothing is drawn if depth of field is turned off. Returns the number of draw calls
+    /// made.
+    pub fn render(
+        &mut self,
+        graphics: &SharedGraphicsContext,
+        frame: &mut FrameGraphicsContext<'_>,
+        camera: &Camera,
+        focus_distance: Option<f32>,
+    ) -> u32 {
+        let Some(settings) = depth_of_field() else {
+            return 0;
+        };
+
+        let size = frame.depth_texture.size;
+        if self.targets.as_ref().is_none_or(|t| t.size != size) {
+            self.targets = Some(create_targets(graphics, size));
+        }
+        let Some(targets) = &self.targets else {
+            return 0;
+        };
+
+        let proj = DMat4::from_cols_array_2d(&OPENGL_TO_WGPU_MATRIX) * camera.proj_mat;
+        let uniform = DepthOfFieldUniform {
+            inv_proj: proj.inverse().as_mat4().to_cols_array_2d(),
+            focal_distance: focus_distance.unwrap_or(settings.focal_distance),
+            focal_range: settings.focal_range.max(0.0),
+            max_blur: settings.max_blur.max(0.0),
+            _padding: 0.0,
+        };
+
+        // a buffer per call, so each viewport rendered this frame keeps its own projection
+        let uniform_buffer =
+            graphics
+                .device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("Depth of Field Uniform"),
+                    contents: bytemuck::cast_slice(&[uniform]),
+                    usage: wgpu::BufferUsages::UNIFORM,
+                });
+
+        let create_bind_group = |layout: &BindGroupLayout, entries: &[wgpu::BindGroupEntry]| {
+            graphics
+                .device
+                .create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("depth_of_field_bind_group"),
+                    layout,
+                    entries,
+                })
+        };
+        let view_entry = |binding, view| wgpu::BindGroupEntry {
+            binding,
+            resource: wgpu::BindingResource::TextureView(view),
+        };
+        let uniform_entry = wgpu::BindGroupEntry {
+            binding: 1,
+            resource: uniform_buffer.as_entire_binding(),
+        };
+        let sampler_entry = wgpu::BindGroupEntry {
+            binding: 5,
+            resource: wgpu::BindingResource::Sampler(&self.sampler),
+        };
+
+        // the depth texture and the frame change between calls, so none of these can be kept
+        let coc_bind_group = create_bind_group(
+            &self.coc_layout,
+            &[
+                view_entry(0, &frame.depth_texture.view),
+                uniform_entry.clone(),
+                view_entry(2, frame.view),
+            ],
+        );
+        let blur_bind_group = create_bind_group(
+            &self.blur_layout,
+            &[
+                uniform_entry.clone(),
+                view_entry(3, &targets.near_view),
+                view_entry(4, &targets.far_view),
+                sampler_entry.clone(),
+            ],
+        );
+        let scratch_blur_bind_group = create_bind_group(
+            &self.blur_layout,
+            &[
+                uniform_entry.clone(),
+                view_entry(3, &targets.near_scratch_view),
+                view_entry(4, &targets.far_scratch_view),
+                sampler_entry,
+            ],
+        );
+        let composite_bind_group = create_bind_group(
+            &self.composite_layout,
+            &[
+                view_entry(0, &frame.depth_texture.view),
+                uniform_entry,
+                view_entry(6, &targets.near_view),
+                view_entry(7, &targets.far_view),
+            ],
+        );
+
+        let fields = [&targets.near_view, &targets.far_view];
+        let scratch_fields = [&targets.near_scratch_view, &targets.far_scratch_view];
+
+        let mut passes = vec![(&self.coc_pipeline, &coc_bind_group, &fields[..], 0)];
+        // ping-pongs between the fields and the scratch textures, ending back in the fields
+        for pass in 0..BLUR_PASSES {
+            if pass % 2 == 0 {
+                passes.push((
+                    &self.blur_pipeline,
+                    &blur_bind_group,
+                    &scratch_fields[..],
+                    pass,
+                ));
+            } else {
+                passes.push((
+                    &self.blur_pipeline,
+                    &scratch_blur_bind_group,
+                    &fields[..],
+                    pass,
+                ));
+            }
+        }
+        let frame_view = [frame.view];
+        passes.push((
+            &self.composite_pipeline,
+            &composite_bind_group,
+            &frame_view[..],
+            0,
+        ));
+
+        for (pipeline, bind_group, views, pass) in &passes {
+            let color_attachments = views
+                .iter()
+                .map(|view| {
+                    Some(wgpu::RenderPassColorAttachment {
+                        view,
+                        depth_slice: None,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Load,
+                            store: wgpu::StoreOp::Store,
+                        },
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            let mut render_pass = frame
+                .encoder
+                .begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("Depth of Field Render Pass"),
+                    color_attachments: &color_attachments,
+                    depth_stencil_attachment: None,
+                    occlusion_query_set: None,
+                    timestamp_writes: None,
+                });
+            render_pass.set_pipeline(pipeline);
+            render_pass.set_bind_group(0, *bind_group, &[]);
+            // the instance index tells the blur shader which pass it is
+            render_pass.draw(0..3, *pass..*pass + 1);
+        }
+
+        passes.len() as u32
+    }
+}
+
+fn create_targets(graphics: &SharedGraphicsContext, size: wgpu::Extent3d) -> DepthOfFieldTargets {
+    let create_view = |label| {
+        graphics
+            .device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: Some(label),
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: FIELD_FORMAT,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                    | wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            })
+            .create_view(&wgpu::TextureViewDescriptor::default())
+    };
+
+    DepthOfFieldTargets {
+        size,
+        near_view: create_view("depth_of_field_near_texture"),
+        far_view: create_view("depth_of_field_far_texture"),
+        near_scratch_view: create_view("depth_of_field_near_scratch_texture"),
+        far_scratch_view: create_view("depth_of_field_far_scratch_texture"),
+    }
+}
+
+fn fullscreen_pipeline(
+    graphics: &SharedGraphicsContext,
+    shader: &Shader,
+    layout: &BindGroupLayout,
+    fragment_entry: &str,
+    targets: &[Option<wgpu::ColorTargetState>],
+    label: &str,
+) -> RenderPipeline {
+    let pipeline_layout = graphics
+        .device
+        .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some(label),
+            bind_group_layouts: &[layout],
+            push_constant_ranges: &[],
+        });
+
+    graphics
+        .device
+        .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(label),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader.module,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader.module,
+                entry_point: Some(fragment_entry),
+                targets,
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        })
+}
@@ -22,12 +22,20 @@ use dropbear_macro::SerializableComponent;
 pub struct EntityTransform {
     local: Transform,
     world: Transform,
+    /// Offset applied to the model after scaling but before rotating, so the model rotates
+    /// around a point other than its origin.
+    #[serde(default)]
+    pivot: DVec3,
 }
 
 impl EntityTransform {
     /// Creates a new [EntityTransform] from a local and world [Transform]
     pub fn new(local: Transform, world: Transform) -> Self {
-        Self { local, world }
+        Self {
+            local,
+            world,
+            pivot: DVec3::ZERO,
+        }
     }
 
     /// Creates a new [EntityTransform] from a world [Transform] and a default local transform.
@@ -37,6 +45,7 @@ impl EntityTransform {
         Self {
             world,
             local: Transform::default(),
+            pivot: DVec3::ZERO,
         }
     }
 
@@ -60,6 +69,16 @@ impl EntityTransform {
         &mut self.world
     }
 
+    /// Gets the pivot offset of the model
+    pub fn pivot(&self) -> DVec3 {
+        self.pivot
+    }
+
+    /// Sets the pivot offset of the model
+    pub fn set_pivot(&mut self, pivot: DVec3) {
+        self.pivot = pivot;
+    }
+
     /// Combines both transforms into one, propagating the local transform
     /// to the world transform and returning a uniform [Transform]
     pub fn sync(&self) -> Transform {
@@ -110,6 +129,13 @@ impl Transform {
         DMat4::from_scale_rotation_translation(self.scale, self.rotation, self.position)
     }
 
+    /// Returns the matrix of the model, with the model offset by `pivot` before it is rotated.
+    pub(crate) fn matrix_with_pivot(&self, pivot: DVec3) -> DMat4 {
+        DMat4::from_rotation_translation(self.rotation, self.position)
+            * DMat4::from_translation(pivot)
+            * DMat4::from_scale(self.scale)
+    }
+
     /// Rotates the model on its X axis by a certain angle
     pub fn rotate_x(&mut self, angle_rad: f64) {
         self.rotation *= DQuat::from_euler(glam::EulerRot::XYZ, angle_rad, 0.0, 0.0);
@@ -141,6 +167,24 @@ impl Transform {
     }
 }
 
+/// The number of layers that can be named and picked in the editor.
+pub const NAMED_LAYER_COUNT: u32 = 16;
+
+/// The layer that an entity is rendered on, from 0 to 31.
+///
+/// An entity is only rendered by a [`Camera`](crate::camera::Camera) whose
+/// [`visible_layers`](crate::camera::Camera::visible_layers) has the bit for this layer set.
+/// Entities without this component are treated as being on layer 0.
+#[derive(Default, Debug, Deserialize, Serialize, Copy, PartialEq, Eq, Clone, SerializableComponent)]
+pub struct EntityLayer(pub u32);
+
+impl EntityLayer {
+    /// Returns the bitmask of this layer.
+    pub fn mask(&self) -> u32 {
+        1u32.checked_shl(self.0).unwrap_or(0)
+    }
+}
+
 #[derive(Clone)]
 /// A renderer for meshes and materials related to a model.
 ///
@@ -152,6 +196,9 @@ pub struct MeshRenderer {
     pub previous_matrix: DMat4,
     pub is_selected: bool,
     pub material_overrides: Vec<MaterialOverride>,
+    /// Emissive factors that were changed from the ones the model was loaded with, by material
+    /// name
+    pub emissive_overrides: HashMap<String, [f32; 3]>,
     original_material_snapshots: HashMap<String, MaterialSnapshot>,
     texture_identifier_cache: HashMap<String, String>,
 }
@@ -188,6 +235,7 @@ impl MeshRenderer {
             previous_matrix: DMat4::IDENTITY,
             is_selected: false,
             material_overrides: Vec::new(),
+            emissive_overrides: HashMap::new(),
             original_material_snapshots: HashMap::new(),
             texture_identifier_cache: HashMap::new(),
         }
@@ -218,7 +266,13 @@ impl MeshRenderer {
     }
 
     pub fn update(&mut self, transform: &Transform) {
-        let current_matrix = transform.matrix();
+        self.update_with_pivot(transform, DVec3::ZERO);
+    }
+
+    /// Updates the instance like [`MeshRenderer::update`], offsetting the model by the pivot of
+    /// an [`EntityTransform`].
+    pub fn update_with_pivot(&mut self, transform: &Transform, pivot: DVec3) {
+        let current_matrix = transform.matrix_with_pivot(pivot);
         if self.previous_matrix != current_matrix {
             self.instance = Instance::from_matrix(current_matrix);
             self.previous_matrix = current_matrix;
@@ -233,10 +287,51 @@ impl MeshRenderer {
     pub fn set_handle_raw(&mut self, handle: LoadedModel) {
         self.handle = handle;
         self.material_overrides.clear();
+        self.emissive_overrides.clear();
         self.original_material_snapshots.clear();
         self.texture_identifier_cache.clear();
     }
 
+    /// Swaps the model for the one now registered under the same handle, such as after
+    /// [`AssetRegistry::reload_model`], and applies the material and emissive overrides to it again.
+    pub fn refresh_model(&mut self, graphics: Arc<SharedGraphicsContext>) -> anyhow::Result<()> {
+        let handle = self.asset_handle();
+        let model = ASSET_REGISTRY
+            .get_model(handle)
+            .ok_or_else(|| anyhow!("Model {:?} is no longer registered", handle))?;
+        self.handle = LoadedModel::from_registered(handle, model);
+        self.original_material_snapshots.clear();
+        self.texture_identifier_cache.clear();
+
+        for entry in std::mem::take(&mut self.material_overrides) {
+            if let Err(e) = self.apply_material_override(
+                &entry.target_material,
+                entry.source_model.clone(),
+                &entry.source_material,
+            ) {
+                log::warn!(
+                    "Unable to apply material override to '{}': {}",
+                    entry.target_material,
+                    e
+                );
+            }
+        }
+
+        for (material_name, emissive_factor) in std::mem::take(&mut self.emissive_overrides) {
+            if let Err(e) =
+                self.set_emissive_factor(graphics.clone(), &material_name, emissive_factor)
+            {
+                log::warn!(
+                    "Unable to apply emissive factor to '{}': {}",
+                    material_name,
+                    e
+                );
+            }
+        }
+
+        Ok(())
+    }
+
     /// Swaps the currently loaded model for that renderer by the provided [`AssetHandle`]
     ///
     /// Returns an error if the assethandle provided is not in the model registry.
@@ -450,6 +545,29 @@ impl MeshRenderer {
         Ok(())
     }
 
+    /// Changes the emissive factor of one of the model's materials, remembering it in
+    /// [`MeshRenderer::emissive_overrides`] so it is kept when the scene is saved.
+    pub fn set_emissive_factor(
+        &mut self,
+        graphics: Arc<SharedGraphicsContext>,
+        material_name: &str,
+        emissive_factor: [f32; 3],
+    ) -> anyhow::Result<()> {
+        let model = self.make_model_mut();
+        if !model.set_material_emissive_factor(graphics, material_name, emissive_factor) {
+            anyhow::bail!(
+                "Material '{}' does not exist on model '{}'",
+                material_name,
+                model.label
+            );
+        }
+
+        self.emissive_overrides
+            .insert(material_name.to_string(), emissive_factor);
+        self.sync_asset_registry();
+        Ok(())
+    }
+
     pub fn material_overrides(&self) -> &[MaterialOverride] {
         &self.material_overrides
     }
@@ -0,0 +1,423 @@
+//! Fonts rasterised into texture atlases, for drawing text inside a 3D scene or over it as a HUD.
+//!
+//! Text is drawn as a sprite for every glyph by the [`SpritePipeline`](crate::sprite::SpritePipeline),
+//! so it is sorted and blended along with every other sprite.
+
+use crate::asset::AssetHandle;
+use crate::atlas::{AtlasBuilder, AtlasHandle};
+use crate::camera::{Camera, OPENGL_TO_WGPU_MATRIX};
+use crate::graphics::SharedGraphicsContext;
+use crate::sprite::SpriteInstance;
+use ab_glyph::{Font as _, FontVec, GlyphId, PxScale, ScaleFont};
+use glam::{DMat4, DVec3, Vec2};
+use image::{DynamicImage, Rgba, RgbaImage};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+
+/// The width of a font's atlas before it grows to fit every glyph.
+const ATLAS_SIZE: u32 = 512;
+
+/// Drawn in place of characters that are not in the atlas.
+const FALLBACK_CHAR: char = '?';
+
+/// The characters rasterised into every font's atlas, which are printable ASCII and Latin-1.
+fn atlas_chars() -> impl Iterator<Item = char> {
+    (' '..='~').chain('\u{a0}'..='\u{ff}')
+}
+
+/// Where a glyph is drawn relative to the pen, in pixels.
+#[derive(Debug, Clone, Copy)]
+struct GlyphMetrics {
+    id: GlyphId,
+    /// How far the pen moves after the glyph
+    advance: f32,
+    /// The top left corner of the glyph's image relative to the pen on the baseline, with y
+    /// pointing down
+    offset: Vec2,
+    /// The size of the glyph's image, which is zero for glyphs with nothing to draw
+    size: Vec2,
+}
+
+/// A glyph placed by [`Font::layout`].
+#[derive(Debug, Clone, Copy)]
+struct PlacedGlyph {
+    /// Texture coordinates of the glyph in the font's atlas
+    uv: [f32; 4],
+    /// The top left corner of the glyph in pixels, relative to the top left of the text
+    min: Vec2,
+    size: Vec2,
+}
+
+/// A font rasterised at a single size into a texture atlas.
+///
+/// Cloning a font is cheap, as the glyphs and atlas are shared.
+#[derive(Clone)]
+pub struct Font {
+    font: Arc<FontVec>,
+    scale: PxScale,
+    glyphs: Arc<HashMap<char, GlyphMetrics>>,
+    atlas: AtlasHandle,
+}
+
+impl Font {
+    /// Loads a TrueType or OpenType font from a file and rasterises it at `size` pixels high.
+    pub fn load(
+        graphics: Arc<SharedGraphicsContext>,
+        path: &Path,
+        size: f32,
+    ) -> anyhow::Result<Self> {
+        let bytes = std::fs::read(path)?;
+        Self::from_bytes(graphics, bytes, size, &path.display().to_string())
+    }
+
+    /// Rasterises a TrueType or OpenType font at `size` pixels high.
+    pub fn from_bytes(
+        graphics: Arc<SharedGraphicsContext>,
+        bytes: Vec<u8>,
+        size: f32,
+        label: &str,
+    ) -> anyhow::Result<Self> {
+        let font = FontVec::try_from_vec(bytes)
+            .map_err(|e| anyhow::anyhow!("Unable to read font '{}': {}", label, e))?;
+        let scale = PxScale::from(size);
+        let scaled = font.as_scaled(scale);
+
+        let mut builder = AtlasBuilder::new(ATLAS_SIZE);
+        let mut glyphs = HashMap::new();
+        for c in atlas_chars() {
+            let id = font.glyph_id(c);
+            // the font doesn't have the character
+            if id.0 == 0 && c != FALLBACK_CHAR {
+                continue;
+            }
+
+            let mut metrics = GlyphMetrics {
+                id,
+                advance: scaled.h_advance(id),
+                offset: Vec2::ZERO,
+                size: Vec2::ZERO,
+            };
+
+            if let Some(outlined) = font.outline_glyph(id.with_scale(scale)) {
+                let bounds = outlined.px_bounds();
+                // a transparent border stops neighbouring glyphs bleeding in when sampled
+                let mut image =
+                    RgbaImage::new(bounds.width() as u32 + 2, bounds.height() as u32 + 2);
+                outlined.draw(|x, y, coverage| {
+                    let alpha = (coverage.clamp(0.0, 1.0) * 255.0) as u8;
+                    image.put_pixel(x + 1, y + 1, Rgba([255, 255, 255, alpha]));
+                });
+
+                metrics.offset = Vec2::new(bounds.min.x, bounds.min.y) - Vec2::ONE;
+                metrics.size = Vec2::new(image.width() as f32, image.height() as f32);
+                builder.add_image(&c.to_string(), DynamicImage::ImageRgba8(image));
+            }
+
+            glyphs.insert(c, metrics);
+        }
+
+        let atlas = builder.build(graphics, &format!("{} glyphs", label));
+        log::debug!(
+            "Loaded font '{}' at {}px with {} glyphs",
+            label,
+            size,
+            glyphs.len()
+        );
+
+        Ok(Self {
+            font: Arc::new(font),
+            scale,
+            glyphs: Arc::new(glyphs),
+            atlas,
+        })
+    }
+
+    /// The height of the font in pixels.
+    pub fn size(&self) -> f32 {
+        self.scale.y
+    }
+
+    /// The distance between the baselines of two lines, in pixels.
+    pub fn line_height(&self) -> f32 {
+        let scaled = self.font.as_scaled(self.scale);
+        scaled.height() + scaled.line_gap()
+    }
+
+    /// The handle of the atlas texture the glyphs are drawn from.
+    pub fn texture(&self) -> AssetHandle {
+        self.atlas.texture
+    }
+
+    fn glyph(&self, c: char) -> Option<&GlyphMetrics> {
+        self.glyphs
+            .get(&c)
+            .or_else(|| self.glyphs.get(&FALLBACK_CHAR))
+    }
+
+    /// The width and height of `text` in pixels.
+    pub fn measure(&self, text: &str) -> Vec2 {
+        self.layout(text).1
+    }
+
+    /// Places every glyph of `text`, starting a new line at every `\n`. Returns the glyphs along
+    /// with the size of the whole block of text.
+    fn layout(&self, text: &str) -> (Vec<PlacedGlyph>, Vec2) {
+        let scaled = self.font.as_scaled(self.scale);
+        let line_height = self.line_height();
+
+        let mut placed = Vec::new();
+        let mut width: f32 = 0.0;
+        let mut lines = 1;
+        let mut pen = Vec2::new(0.0, scaled.ascent());
+        let mut previous: Option<GlyphId> = None;
+
+        for c in text.chars() {
+            if c == '\n' {
+                width = width.max(pen.x);
+                pen = Vec2::new(0.0, pen.y + line_height);
+                lines += 1;
+                previous = None;
+                continue;
+            }
+
+            let Some(glyph) = self.glyph(c) else {
+                continue;
+            };
+            if let Some(previous) = previous {
+                pen.x += scaled.kern(previous, glyph.id);
+            }
+
+            let label = if self.glyphs.contains_key(&c) {
+                c
+            } else {
+                FALLBACK_CHAR
+            };
+            if glyph.size != Vec2::ZERO
+                && let Some(uv) = self.atlas.uv(&label.to_string())
+            {
+                placed.push(PlacedGlyph {
+                    uv,
+                    min: pen + glyph.offset,
+                    size: glyph.size,
+                });
+            }
+
+            pen.x += glyph.advance;
+            previous = Some(glyph.id);
+        }
+
+        let size = Vec2::new(
+            width.max(pen.x),
+            scaled.height() + (lines - 1) as f32 * line_height,
+        );
+        (placed, size)
+    }
+}
+
+/// Where a piece of text is drawn.
+#[derive(Debug, Clone, Copy)]
+pub enum TextAnchor {
+    /// Centred on a point in the world
+    World(DVec3),
+    /// With its top left corner at a position on the viewport, in pixels
+    Screen(Vec2),
+}
+
+struct QueuedText {
+    text: String,
+    anchor: TextAnchor,
+    color: [f32; 4],
+    font: Font,
+}
+
+/// Text that a script asked to draw, which names its font by path as scripts can't load fonts.
+#[derive(Debug, Clone)]
+pub struct TextRequest {
+    pub text: String,
+    pub anchor: TextAnchor,
+    pub color: [f32; 4],
+    /// The font file the text is drawn with
+    pub font_path: PathBuf,
+    /// The height of the font in pixels
+    pub size: f32,
+}
+
+/// Text requested by scripts, which don't have access to a [`TextRenderer`].
+static TEXT_REQUESTS: LazyLock<Mutex<Vec<TextRequest>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// Queues text to be drawn by the next [`TextRenderer::queue_requests`].
+pub fn request_text(request: TextRequest) {
+    TEXT_REQUESTS.lock().push(request);
+}
+
+/// Collects the text to draw this frame, which is drawn by
+/// [`SpritePipeline::update_with_text`](crate::sprite::SpritePipeline::update_with_text).
+///
+/// Text has to be queued again every frame it should be visible.
+#[derive(Default)]
+pub struct TextRenderer {
+    queued: Vec<QueuedText>,
+    /// Fonts loaded for [`TextRequest`]s by path and size, or `None` if the font couldn't be
+    /// loaded, so it isn't tried again every frame
+    fonts: HashMap<(PathBuf, u32), Option<Font>>,
+}
+
+impl TextRenderer {
+    /// How many pixels of a font fit in one world unit, for text drawn in the world.
+    pub const PIXELS_PER_UNIT: f32 = 100.0;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Draws text centred on a point in the world, always facing the camera.
+    pub fn draw_text(&mut self, text: &str, position: DVec3, color: [f32; 4], font: &Font) {
+        self.queue(text, TextAnchor::World(position), color, font);
+    }
+
+    /// Draws text over the scene, with its top left corner at `screen_pos` pixels from the top
+    /// left of the viewport.
+    pub fn draw_text_screen(&mut self, text: &str, screen_pos: Vec2, color: [f32; 4], font: &Font) {
+        self.queue(text, TextAnchor::Screen(screen_pos), color, font);
+    }
+
+    fn queue(&mut self, text: &str, anchor: TextAnchor, color: [f32; 4], font: &Font) {
+        self.queued.push(QueuedText {
+            text: text.to_string(),
+            anchor,
+            color,
+            font: font.clone(),
+        });
+    }
+
+    /// Queues the text that scripts asked for with [`request_text`], loading any fonts that
+    /// haven't been used yet.
+    pub fn queue_requests(&mut self, graphics: Arc<SharedGraphicsContext>) {
+        let requests = std::mem::take(&mut *TEXT_REQUESTS.lock());
+        for request in requests {
+            let font = self
+                .fonts
+                .entry((request.font_path.clone(), request.size.to_bits()))
+                .or_insert_with(|| {
+                    match Font::load(graphics.clone(), &request.font_path, request.size) {
+                        Ok(font) => Some(font),
+                        Err(e) => {
+                            log::error!(
+                                "Unable to load font '{}': {}",
+                                request.font_path.display(),
+                                e
+                            );
+                            None
+                        }
+                    }
+                });
+
+            if let Some(font) = font.clone() {
+                self.queue(&request.text, request.anchor, request.color, &font);
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queued.is_empty()
+    }
+
+    /// Empties the queue, along with any text requested by scripts that hasn't been queued yet.
+    pub fn clear(&mut self) {
+        self.queued.clear();
+        TEXT_REQUESTS.lock().clear();
+    }
+
+    /// Turns the queued text into sprites, and empties the queue.
+    ///
+    /// Each sprite is returned with its squared distance from the camera and its texture. Text on
+    /// the screen is given a distance of zero, so it is drawn over everything else.
+    pub(crate) fn take_sprites(
+        &mut self,
+        camera: &Camera,
+        viewport_size: Vec2,
+    ) -> Vec<(f64, Option<AssetHandle>, SpriteInstance)> {
+        let camera_right = camera.view_mat.row(0).truncate().as_vec3();
+        let camera_up = camera.view_mat.row(1).truncate().as_vec3();
+        let screen = ScreenPlane::new(camera, viewport_size);
+
+        let mut sprites = Vec::new();
+        for queued in self.queued.drain(..) {
+            let (glyphs, size) = queued.font.layout(&queued.text);
+            let texture = Some(queued.font.texture());
+
+            for glyph in glyphs {
+                let centre = glyph.min + glyph.size / 2.0;
+                let (distance, position, right, up) = match queued.anchor {
+                    TextAnchor::World(position) => {
+                        let offset = (centre - size / 2.0) / Self::PIXELS_PER_UNIT;
+                        let half_size = glyph.size / 2.0 / Self::PIXELS_PER_UNIT;
+                        let position =
+                            position.as_vec3() + camera_right * offset.x - camera_up * offset.y;
+                        (
+                            position.as_dvec3().distance_squared(camera.eye),
+                            position,
+                            camera_right * half_size.x,
+                            camera_up * half_size.y,
+                        )
+                    }
+                    TextAnchor::Screen(screen_pos) => {
+                        let centre = screen_pos + centre;
+                        let position = screen.unproject(centre);
+                        let right = screen.unproject(centre + Vec2::new(glyph.size.x / 2.0, 0.0))
+                            - position;
+                        let up = screen.unproject(centre - Vec2::new(0.0, glyph.size.y / 2.0))
+                            - position;
+                        (0.0, position.as_vec3(), right.as_vec3(), up.as_vec3())
+                    }
+                };
+
+                sprites.push((
+                    distance,
+                    texture,
+                    SpriteInstance {
+                        position: position.to_array(),
+                        right: right.to_array(),
+                        up: up.to_array(),
+                        color: queued.color,
+                        uv: glyph.uv,
+                    },
+                ));
+            }
+        }
+        sprites
+    }
+}
+
+/// A plane just in front of the camera's near plane, which text on the screen is placed on so it
+/// is drawn with the same pipeline as sprites in the world.
+struct ScreenPlane {
+    inverse_view_proj: DMat4,
+    depth: f64,
+    viewport_size: Vec2,
+}
+
+impl ScreenPlane {
+    fn new(camera: &Camera, viewport_size: Vec2) -> Self {
+        let view_proj =
+            DMat4::from_cols_array_2d(&OPENGL_TO_WGPU_MATRIX) * camera.proj_mat * camera.view_mat;
+        let in_front = camera.eye + camera.forward() * (camera.znear * 2.0);
+        let depth = view_proj.project_point3(in_front).z;
+
+        Self {
+            inverse_view_proj: view_proj.inverse(),
+            depth,
+            viewport_size: viewport_size.max(Vec2::ONE),
+        }
+    }
+
+    /// The point in the world that is drawn at `pixel`, measured from the top left of the
+    /// viewport.
+    fn unproject(&self, pixel: Vec2) -> DVec3 {
+        let ndc = pixel / self.viewport_size * 2.0 - Vec2::ONE;
+        self.inverse_view_proj
+            .project_point3(DVec3::new(ndc.x as f64, -ndc.y as f64, self.depth))
+    }
+}
@@ -1,3 +1,4 @@
+use crate::asset::TextureImportSettings;
 use crate::shader::Shader;
 use crate::{
     State,
@@ -33,6 +34,9 @@ pub struct SharedGraphicsContext {
     pub queue: Arc<Queue>,
     pub instance: Arc<wgpu::Instance>,
     pub texture_bind_layout: Arc<BindGroupLayout>,
+    /// Layout of a material's emissive texture, sampler and factor, see
+    /// [`Material::create_emissive_bind_group`](crate::model::Material::create_emissive_bind_group)
+    pub emissive_bind_layout: Arc<BindGroupLayout>,
     pub window: Arc<Window>,
     pub viewport_texture: Arc<Texture>,
     pub egui_renderer: Arc<Mutex<EguiRenderer>>,
@@ -106,6 +110,7 @@ impl<'a> RenderContext<'a> {
                 queue: state.queue.clone(),
                 instance: Arc::new(state.instance.clone()),
                 texture_bind_layout: Arc::new(state.texture_bind_layout.clone()),
+                emissive_bind_layout: Arc::new(state.emissive_bind_layout.clone()),
                 window: state.window.clone(),
                 viewport_texture: Arc::new(state.viewport_texture.clone()),
                 egui_renderer: state.egui_renderer.clone(),
@@ -216,6 +221,21 @@ impl<'a> RenderContext<'a> {
             .forget_lifetime()
     }
 
+    /// Creates a context that renders into `target` instead of the viewport texture, while
+    /// recording into the same encoder.
+    pub fn with_target<'b>(&'b mut self, target: &'b ViewportTarget) -> RenderContext<'b> {
+        let size = target.size();
+        RenderContext {
+            shared: self.shared.clone(),
+            frame: FrameGraphicsContext {
+                encoder: &mut *self.frame.encoder,
+                view: &target.colour.view,
+                depth_texture: &target.depth,
+                screen_size: (size.width as f32, size.height as f32),
+            },
+        }
+    }
+
     pub fn continue_pass(&mut self) -> RenderPass<'static> {
         self.frame
             .encoder
@@ -245,6 +265,67 @@ impl<'a> RenderContext<'a> {
     }
 }
 
+/// An extra colour and depth texture that a scene can be rendered into, which egui can draw
+/// like the main viewport texture.
+///
+/// The egui texture is freed when this is dropped.
+pub struct ViewportTarget {
+    pub colour: Texture,
+    pub depth: Texture,
+    pub texture_id: TextureId,
+    egui_renderer: Arc<Mutex<EguiRenderer>>,
+}
+
+impl ViewportTarget {
+    pub fn new(graphics: &SharedGraphicsContext, size: Extent3d, label: &str) -> Self {
+        let colour =
+            Texture::create_viewport_texture_with_size(size, &graphics.device, Some(label));
+        let depth = Texture::create_depth_texture_with_size(size, &graphics.device, Some(label));
+        let texture_id = graphics
+            .egui_renderer
+            .lock()
+            .renderer()
+            .register_native_texture(&graphics.device, &colour.view, wgpu::FilterMode::Linear);
+
+        Self {
+            colour,
+            depth,
+            texture_id,
+            egui_renderer: graphics.egui_renderer.clone(),
+        }
+    }
+
+    /// The size of the colour and depth textures.
+    pub fn size(&self) -> Extent3d {
+        self.colour.size
+    }
+
+    /// Recreates the textures at a new size, keeping the same egui texture id.
+    pub fn resize(&mut self, graphics: &SharedGraphicsContext, size: Extent3d, label: &str) {
+        self.colour =
+            Texture::create_viewport_texture_with_size(size, &graphics.device, Some(label));
+        self.depth = Texture::create_depth_texture_with_size(size, &graphics.device, Some(label));
+        self.egui_renderer
+            .lock()
+            .renderer()
+            .update_egui_texture_from_wgpu_texture(
+                &graphics.device,
+                &self.colour.view,
+                wgpu::FilterMode::Linear,
+                self.texture_id,
+            );
+    }
+}
+
+impl Drop for ViewportTarget {
+    fn drop(&mut self) {
+        self.egui_renderer
+            .lock()
+            .renderer()
+            .free_texture(&self.texture_id);
+    }
+}
+
 #[derive(Clone)]
 /// Describes a texture, like an image of some sort. Can be a normal texture on a model or a viewport or depth texture.
 pub struct Texture {
@@ -370,7 +451,15 @@ impl Texture {
             height: config.height.max(1),
             depth_or_array_layers: 1,
         };
+        Self::create_depth_texture_with_size(size, device, label)
+    }
 
+    /// Creates a new depth texture of any size, such as for a [`ViewportTarget`].
+    pub fn create_depth_texture_with_size(
+        size: Extent3d,
+        device: &Device,
+        label: Option<&str>,
+    ) -> Self {
         let desc = TextureDescriptor {
             label,
             size,
@@ -418,7 +507,15 @@ impl Texture {
             height: config.height.max(1),
             depth_or_array_layers: 1,
         };
+        Self::create_viewport_texture_with_size(size, device, label)
+    }
 
+    /// Creates a viewport texture of any size, such as for a [`ViewportTarget`].
+    pub fn create_viewport_texture_with_size(
+        size: Extent3d,
+        device: &Device,
+        label: Option<&str>,
+    ) -> Self {
         let desc = TextureDescriptor {
             label,
             size,
@@ -552,6 +649,217 @@ impl Texture {
         }
     }
 
+    /// Creates a new [`Texture`] from an RGBA image, using the colour space, sampler and mipmap
+    /// options of the [`TextureImportSettings`].
+    ///
+    /// Mipmaps are generated on the CPU by downscaling the original image for each level.
+    pub fn from_rgba_image_with_settings(
+        graphics: Arc<SharedGraphicsContext>,
+        image: &image::RgbaImage,
+        settings: &TextureImportSettings,
+    ) -> Texture {
+        let dimensions = image.dimensions();
+        let texture_size = wgpu::Extent3d {
+            width: dimensions.0,
+            height: dimensions.1,
+            depth_or_array_layers: 1,
+        };
+
+        let mip_level_count = if settings.generate_mipmaps {
+            dimensions.0.max(dimensions.1).max(1).ilog2() + 1
+        } else {
+            1
+        };
+
+        let format = if settings.srgb {
+            wgpu::TextureFormat::Rgba8UnormSrgb
+        } else {
+            wgpu::TextureFormat::Rgba8Unorm
+        };
+
+        let diffuse_texture = graphics.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("imported_texture"),
+            size: texture_size,
+            mip_level_count,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+
+        for level in 0..mip_level_count {
+            let width = (dimensions.0 >> level).max(1);
+            let height = (dimensions.1 >> level).max(1);
+            let resized;
+            let data = if level == 0 {
+                image
+            } else {
+                resized = image::imageops::resize(
+                    image,
+                    width,
+                    height,
+                    image::imageops::FilterType::Triangle,
+                );
+                &resized
+            };
+
+            graphics.queue.write_texture(
+                wgpu::TexelCopyTextureInfo {
+                    texture: &diffuse_texture,
+                    mip_level: level,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                data,
+                wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(4 * width),
+                    rows_per_image: Some(height),
+                },
+                wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+
+        let diffuse_sampler = Self::create_sampler_with_settings(&graphics, settings);
+        let view = diffuse_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let diffuse_bind_group = graphics
+            .device
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &graphics.texture_bind_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&diffuse_sampler),
+                    },
+                ],
+                label: Some("texture_bind_group"),
+            });
+
+        log::trace!("Done creating imported texture with {} mip levels", mip_level_count);
+
+        Texture {
+            texture: diffuse_texture,
+            sampler: diffuse_sampler,
+            view,
+            size: texture_size,
+            bind_group: Some(diffuse_bind_group),
+            layout: Some(graphics.texture_bind_layout.clone()),
+        }
+    }
+
+    /// Creates a sampler from the wrap and filter modes of the [`TextureImportSettings`].
+    fn create_sampler_with_settings(
+        graphics: &SharedGraphicsContext,
+        settings: &TextureImportSettings,
+    ) -> Sampler {
+        let filter: wgpu::FilterMode = settings.filter.into();
+        graphics.device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: settings.wrap_u.into(),
+            address_mode_v: settings.wrap_v.into(),
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: filter,
+            min_filter: filter,
+            mipmap_filter: filter,
+            ..Default::default()
+        })
+    }
+
+    /// Creates a new [`Texture`] from BC7 compressed blocks.
+    ///
+    /// The dimensions must be a multiple of 4, and the device must have been created with
+    /// [`wgpu::Features::TEXTURE_COMPRESSION_BC`]. Mipmaps are not generated for compressed
+    /// textures.
+    pub fn from_bc7_buffer(
+        graphics: Arc<SharedGraphicsContext>,
+        bc7_data: &[u8],
+        dimensions: (u32, u32),
+        settings: &TextureImportSettings,
+    ) -> Texture {
+        let texture_size = wgpu::Extent3d {
+            width: dimensions.0,
+            height: dimensions.1,
+            depth_or_array_layers: 1,
+        };
+
+        let format = if settings.srgb {
+            wgpu::TextureFormat::Bc7RgbaUnormSrgb
+        } else {
+            wgpu::TextureFormat::Bc7RgbaUnorm
+        };
+
+        let diffuse_texture = graphics.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("bc7_texture"),
+            size: texture_size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+
+        // each 4x4 block is 16 bytes
+        let blocks_wide = dimensions.0.div_ceil(4);
+        let blocks_high = dimensions.1.div_ceil(4);
+        graphics.queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &diffuse_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            bc7_data,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(16 * blocks_wide),
+                rows_per_image: Some(blocks_high),
+            },
+            texture_size,
+        );
+
+        let diffuse_sampler = Self::create_sampler_with_settings(&graphics, settings);
+
+        let view = diffuse_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let diffuse_bind_group = graphics
+            .device
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &graphics.texture_bind_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&diffuse_sampler),
+                    },
+                ],
+                label: Some("texture_bind_group"),
+            });
+
+        log::trace!("Done creating BC7 texture");
+
+        Texture {
+            texture: diffuse_texture,
+            sampler: diffuse_sampler,
+            view,
+            size: texture_size,
+            bind_group: Some(diffuse_bind_group),
+            layout: Some(graphics.texture_bind_layout.clone()),
+        }
+    }
+
     /// Creates a new [`Texture`] with a specified sampler (wgpu) and already converted RGBA byte buffer.
     pub fn new_with_sampler_with_rgba_buffer(
         graphics: Arc<SharedGraphicsContext>,
@@ -769,6 +1077,7 @@ impl Instance {
         InstanceRaw {
             model: model_matrix.as_mat4().to_cols_array_2d(),
             normal: Mat3::from_quat(self.rotation.as_quat()).to_cols_array_2d(),
+            material_properties: [[0.0; 4]; 2],
         }
     }
 
@@ -792,9 +1101,18 @@ impl Instance {
 pub struct InstanceRaw {
     model: [[f32; 4]; 4],
     normal: [[f32; 3]; 3],
+    /// User defined material values, available to custom material shaders as two `vec4<f32>`s at
+    /// locations 12 and 13
+    material_properties: [[f32; 4]; 2],
 }
 
 impl InstanceRaw {
+    /// Sets the custom material values of the instance
+    pub fn with_material_properties(mut self, material_properties: [[f32; 4]; 2]) -> Self {
+        self.material_properties = material_properties;
+        self
+    }
+
     pub fn desc() -> VertexBufferLayout<'static> {
         VertexBufferLayout {
             array_stride: size_of::<InstanceRaw>() as BufferAddress,
@@ -837,6 +1155,17 @@ impl InstanceRaw {
                     shader_location: 11,
                     format: wgpu::VertexFormat::Float32x3,
                 },
+                // material properties
+                wgpu::VertexAttribute {
+                    offset: size_of::<[f32; 25]>() as wgpu::BufferAddress,
+                    shader_location: 12,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: size_of::<[f32; 29]>() as wgpu::BufferAddress,
+                    shader_location: 13,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
             ],
         }
     }
@@ -1,5 +1,6 @@
-use gilrs::{Axis, EventType, Gilrs};
+use gilrs::{Axis, EventType, GamepadId, Gilrs};
 use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
 use std::{
     collections::{HashMap, HashSet},
     rc::Rc,
@@ -8,9 +9,66 @@ use winit::{
     dpi::PhysicalPosition, event::MouseButton, event_loop::ActiveEventLoop, keyboard::KeyCode,
 };
 
+pub use gilrs::{Axis as GamepadAxis, Button as GamepadButton};
+
 pub type KeyboardImpl = Rc<RwLock<dyn Keyboard>>;
 pub type MouseImpl = Rc<RwLock<dyn Mouse>>;
 pub type ControllerImpl = Rc<RwLock<dyn Controller>>;
+pub type InputListenerImpl = Rc<RwLock<dyn InputListener>>;
+
+/// Who input is meant for at the moment, so the same keys can do different things depending on
+/// what the user is interacting with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum InputContext {
+    /// A UI widget has focus, for example a text field being typed into
+    UI,
+    /// Input drives the game, or the camera in the editor viewport
+    Gameplay,
+    /// Editor wide input, such as keyboard shortcuts
+    #[default]
+    Editor,
+}
+
+impl InputContext {
+    /// Checks if an action that belongs to `action_context` can fire while this is the active
+    /// context.
+    ///
+    /// Only [`InputContext::UI`] actions fire while a UI widget has focus, every action fires
+    /// otherwise.
+    pub fn allows(&self, action_context: InputContext) -> bool {
+        !matches!(self, InputContext::UI) || matches!(action_context, InputContext::UI)
+    }
+}
+
+/// A stack of [`InputContext`]s, where the top one is the active context.
+///
+/// Cloning this gives another handle to the same stack, so scenes can push and pop contexts of the
+/// [`Manager`] they are attached to.
+#[derive(Debug, Clone, Default)]
+pub struct InputContexts(Rc<RwLock<Vec<InputContext>>>);
+
+impl InputContexts {
+    pub fn push(&self, context: InputContext) {
+        self.0.write().push(context);
+    }
+
+    pub fn pop(&self) -> Option<InputContext> {
+        self.0.write().pop()
+    }
+
+    /// The context on top of the stack, or [`InputContext::Editor`] if the stack is empty.
+    pub fn current(&self) -> InputContext {
+        self.0.read().last().copied().unwrap_or_default()
+    }
+}
+
+/// A named action triggered by any of its keys, only while its context is allowed by the
+/// active [`InputContext`].
+#[derive(Debug, Clone)]
+pub struct InputAction {
+    pub context: InputContext,
+    pub keys: Vec<KeyCode>,
+}
 
 pub trait Keyboard {
     fn key_down(&mut self, key: KeyCode, event_loop: &ActiveEventLoop);
@@ -32,6 +90,41 @@ pub trait Controller {
     fn on_disconnect(&mut self, id: gilrs::GamepadId);
 }
 
+/// A keyboard or mouse event handled by the [`Manager`], whether it came from the window or an
+/// [`InputSource`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum InputEvent {
+    KeyPressed(KeyCode),
+    KeyReleased(KeyCode),
+    MouseMoved {
+        position: (f64, f64),
+        delta: Option<(f64, f64)>,
+    },
+    MouseButton {
+        button: MouseButton,
+        pressed: bool,
+    },
+    /// A scroll of the mouse wheel, in lines
+    Scroll {
+        delta: (f32, f32),
+    },
+}
+
+/// Receives every [`InputEvent`] the [`Manager`] handles, such as to record them.
+pub trait InputListener {
+    fn on_input(&mut self, event: &InputEvent);
+}
+
+/// Feeds [`InputEvent`]s to the [`Manager`] in place of the window, such as a recorded session.
+///
+/// Events from the window are ignored for as long as a source is set.
+pub trait InputSource {
+    /// Returns the events that are due, called once every frame.
+    fn poll(&mut self) -> Vec<InputEvent>;
+    /// Returns `true` once there are no more events, after which the source is removed.
+    fn is_finished(&self) -> bool;
+}
+
 pub struct Manager {
     // keyboard
     pressed_keys: HashSet<KeyCode>,
@@ -43,12 +136,23 @@ pub struct Manager {
     just_pressed_mouse_buttons: HashSet<MouseButton>,
     just_released_mouse_buttons: HashSet<MouseButton>,
     mouse_position: PhysicalPosition<f64>,
+    scroll_delta: (f32, f32),
+
+    // gamepad
+    connected_gamepads: HashSet<GamepadId>,
+    pressed_gamepad_buttons: HashMap<GamepadId, HashSet<GamepadButton>>,
+    gamepad_axes: HashMap<GamepadId, HashMap<GamepadAxis, f32>>,
 
     keyboard_handlers: HashMap<String, KeyboardImpl>,
     mouse_handlers: HashMap<String, MouseImpl>,
     controller_handlers: HashMap<String, ControllerImpl>,
+    listeners: HashMap<String, InputListenerImpl>,
+    source: Option<Box<dyn InputSource>>,
 
     active_handlers: HashSet<String>,
+
+    contexts: InputContexts,
+    actions: HashMap<String, InputAction>,
 }
 
 impl Default for Manager {
@@ -67,13 +171,58 @@ impl Manager {
             just_pressed_mouse_buttons: HashSet::new(),
             just_released_mouse_buttons: HashSet::new(),
             mouse_position: PhysicalPosition::new(0.0, 0.0),
+            scroll_delta: (0.0, 0.0),
+            connected_gamepads: HashSet::new(),
+            pressed_gamepad_buttons: HashMap::new(),
+            gamepad_axes: HashMap::new(),
             keyboard_handlers: HashMap::new(),
             mouse_handlers: HashMap::new(),
             controller_handlers: HashMap::new(),
+            listeners: HashMap::new(),
+            source: None,
             active_handlers: HashSet::new(),
+            contexts: InputContexts::default(),
+            actions: HashMap::new(),
         }
     }
 
+    /// Makes `context` the active input context, until it is popped.
+    pub fn push_context(&mut self, context: InputContext) {
+        self.contexts.push(context);
+    }
+
+    /// Removes the active input context, going back to the one before it.
+    pub fn pop_context(&mut self) -> Option<InputContext> {
+        self.contexts.pop()
+    }
+
+    /// The active input context.
+    pub fn context(&self) -> InputContext {
+        self.contexts.current()
+    }
+
+    /// Returns a handle to the context stack, for scenes that have to switch contexts themselves.
+    pub fn contexts(&self) -> InputContexts {
+        self.contexts.clone()
+    }
+
+    /// Binds an action to a set of keys, replacing any action with the same name.
+    pub fn bind_action(&mut self, name: &str, context: InputContext, keys: Vec<KeyCode>) {
+        self.actions
+            .insert(name.to_string(), InputAction { context, keys });
+    }
+
+    /// Checks if any key of the action is held down.
+    ///
+    /// Returns `false` if there is no such action, or if its context is not allowed by the active
+    /// context (see [`InputContext::allows`]).
+    pub fn is_action_pressed(&self, name: &str) -> bool {
+        self.actions.get(name).is_some_and(|action| {
+            self.context().allows(action.context)
+                && action.keys.iter().any(|key| self.is_key_pressed(*key))
+        })
+    }
+
     pub fn set_active_handlers(&mut self, handlers: Vec<String>) {
         self.active_handlers.clear();
         for name in handlers {
@@ -89,7 +238,71 @@ impl Manager {
         self.mouse_handlers.insert(name.to_string(), handler);
     }
 
+    /// Adds a listener that receives every event handled from now on, replacing any listener
+    /// with the same name.
+    pub fn add_listener(&mut self, name: &str, listener: InputListenerImpl) {
+        self.listeners.insert(name.to_string(), listener);
+    }
+
+    pub fn remove_listener(&mut self, name: &str) -> Option<InputListenerImpl> {
+        self.listeners.remove(name)
+    }
+
+    /// Feeds events from `source` instead of the window until it finishes, or until it is
+    /// replaced by another call. Passing `None` goes back to events from the window.
+    pub fn set_source(&mut self, source: Option<Box<dyn InputSource>>) {
+        self.source = source;
+    }
+
+    /// Checks if events are coming from an [`InputSource`] rather than the window.
+    pub fn has_source(&self) -> bool {
+        self.source.is_some()
+    }
+
+    /// Handles the events of the [`InputSource`] that are due, removing the source once it has
+    /// finished.
+    pub fn poll_source(&mut self, event_loop: &ActiveEventLoop) {
+        let Some(source) = &mut self.source else {
+            return;
+        };
+
+        let events = source.poll();
+        if source.is_finished() {
+            log::debug!("Input source finished, going back to window input");
+            self.source = None;
+        }
+
+        for event in events {
+            self.handle_event(event, event_loop);
+        }
+    }
+
+    /// Handles an [`InputEvent`] the same way as the matching event from the window.
+    pub fn handle_event(&mut self, event: InputEvent, event_loop: &ActiveEventLoop) {
+        match event {
+            InputEvent::KeyPressed(key) => self.handle_key_input(key, true, event_loop),
+            InputEvent::KeyReleased(key) => self.handle_key_input(key, false, event_loop),
+            InputEvent::MouseMoved { position, delta } => {
+                self.handle_mouse_movement(PhysicalPosition::new(position.0, position.1), delta)
+            }
+            InputEvent::MouseButton { button, pressed } => self.handle_mouse_input(button, pressed),
+            InputEvent::Scroll { delta } => self.handle_scroll(delta),
+        }
+    }
+
+    fn notify_listeners(&self, event: InputEvent) {
+        for listener in self.listeners.values() {
+            listener.write().on_input(&event);
+        }
+    }
+
     pub fn handle_key_input(&mut self, key: KeyCode, pressed: bool, event_loop: &ActiveEventLoop) {
+        self.notify_listeners(if pressed {
+            InputEvent::KeyPressed(key)
+        } else {
+            InputEvent::KeyReleased(key)
+        });
+
         if pressed {
             if !self.pressed_keys.contains(&key) {
                 self.just_pressed_keys.insert(key);
@@ -114,6 +327,8 @@ impl Manager {
     }
 
     pub fn handle_mouse_input(&mut self, button: MouseButton, pressed: bool) {
+        self.notify_listeners(InputEvent::MouseButton { button, pressed });
+
         if pressed {
             if !self.pressed_mouse_buttons.contains(&button) {
                 self.just_pressed_mouse_buttons.insert(button);
@@ -142,6 +357,11 @@ impl Manager {
         position: PhysicalPosition<f64>,
         mouse_delta: Option<(f64, f64)>,
     ) {
+        self.notify_listeners(InputEvent::MouseMoved {
+            position: (position.x, position.y),
+            delta: mouse_delta,
+        });
+
         self.mouse_position = position;
         for (name, handler) in self.mouse_handlers.iter_mut() {
             if self.active_handlers.contains(name) {
@@ -150,6 +370,14 @@ impl Manager {
         }
     }
 
+    /// Adds to how far the mouse wheel was scrolled this frame, in lines.
+    pub fn handle_scroll(&mut self, delta: (f32, f32)) {
+        self.notify_listeners(InputEvent::Scroll { delta });
+
+        self.scroll_delta.0 += delta.0;
+        self.scroll_delta.1 += delta.1;
+    }
+
     pub fn is_key_pressed(&self, key: KeyCode) -> bool {
         self.pressed_keys.contains(&key)
     }
@@ -178,11 +406,40 @@ impl Manager {
         self.mouse_position
     }
 
+    /// How far the mouse wheel was scrolled since the last frame, in lines.
+    pub fn get_scroll_delta(&self) -> (f32, f32) {
+        self.scroll_delta
+    }
+
+    /// Checks if a button is held down on the gamepad with the given id.
+    pub fn gamepad_button_pressed(&self, gamepad_id: usize, button: GamepadButton) -> bool {
+        self.pressed_gamepad_buttons
+            .iter()
+            .any(|(id, buttons)| usize::from(*id) == gamepad_id && buttons.contains(&button))
+    }
+
+    /// Fetches the value of an axis on the gamepad with the given id, from `-1.0` to `1.0`.
+    ///
+    /// Returns `0.0` if the gamepad is not connected or the axis has not moved yet.
+    pub fn gamepad_axis_value(&self, gamepad_id: usize, axis: GamepadAxis) -> f32 {
+        self.gamepad_axes
+            .iter()
+            .find(|(id, _)| usize::from(**id) == gamepad_id)
+            .and_then(|(_, axes)| axes.get(&axis).copied())
+            .unwrap_or(0.0)
+    }
+
+    /// The ids of every connected gamepad.
+    pub fn connected_gamepads(&self) -> impl Iterator<Item = usize> + '_ {
+        self.connected_gamepads.iter().map(|id| usize::from(*id))
+    }
+
     pub fn update(&mut self, gilrs: &mut Gilrs) {
         self.just_pressed_keys.clear();
         self.just_released_keys.clear();
         self.just_pressed_mouse_buttons.clear();
         self.just_released_mouse_buttons.clear();
+        self.scroll_delta = (0.0, 0.0);
         self.poll_controllers(gilrs);
     }
 
@@ -191,6 +448,44 @@ impl Manager {
     }
 
     pub fn handle_controller_event(&mut self, event: gilrs::Event) {
+        match event.event {
+            EventType::ButtonPressed(button, _) => {
+                self.pressed_gamepad_buttons
+                    .entry(event.id)
+                    .or_default()
+                    .insert(button);
+            }
+            EventType::ButtonReleased(button, _) => {
+                if let Some(buttons) = self.pressed_gamepad_buttons.get_mut(&event.id) {
+                    buttons.remove(&button);
+                }
+            }
+            EventType::AxisChanged(axis, value, _) => {
+                self.gamepad_axes
+                    .entry(event.id)
+                    .or_default()
+                    .insert(axis, value);
+            }
+            EventType::Connected => {
+                self.connected_gamepads.insert(event.id);
+            }
+            EventType::Disconnected => {
+                self.connected_gamepads.remove(&event.id);
+                self.pressed_gamepad_buttons.remove(&event.id);
+                self.gamepad_axes.remove(&event.id);
+            }
+            _ => {}
+        }
+
+        let axis = |axis: Axis| -> f32 {
+            self.gamepad_axes
+                .get(&event.id)
+                .and_then(|axes| axes.get(&axis).copied())
+                .unwrap_or(0.0)
+        };
+        let left_stick = (axis(Axis::LeftStickX), axis(Axis::LeftStickY));
+        let right_stick = (axis(Axis::RightStickX), axis(Axis::RightStickY));
+
         for (name, handler) in self.controller_handlers.iter_mut() {
             if self.active_handlers.contains(name) {
                 match event.event {
@@ -200,17 +495,15 @@ impl Manager {
                     EventType::ButtonReleased(button, _) => {
                         handler.write().button_up(button, event.id);
                     }
-                    EventType::AxisChanged(Axis::LeftStickX, x, _) => {
-                        handler.write().left_stick_changed(x, 0.0, event.id);
-                    }
-                    EventType::AxisChanged(Axis::LeftStickY, y, _) => {
-                        handler.write().left_stick_changed(0.0, y, event.id);
-                    }
-                    EventType::AxisChanged(Axis::RightStickX, x, _) => {
-                        handler.write().right_stick_changed(x, 0.0, event.id);
+                    EventType::AxisChanged(Axis::LeftStickX | Axis::LeftStickY, _, _) => {
+                        handler
+                            .write()
+                            .left_stick_changed(left_stick.0, left_stick.1, event.id);
                     }
-                    EventType::AxisChanged(Axis::RightStickY, y, _) => {
-                        handler.write().right_stick_changed(0.0, y, event.id);
+                    EventType::AxisChanged(Axis::RightStickX | Axis::RightStickY, _, _) => {
+                        handler
+                            .write()
+                            .right_stick_changed(right_stick.0, right_stick.1, event.id);
                     }
                     EventType::Connected => {
                         handler.write().on_connect(event.id);
@@ -1,23 +1,76 @@
 pub mod asset;
+pub mod atlas;
 pub mod attenuation;
 pub mod buffer;
 pub mod camera;
 pub mod colour;
+pub mod depth_of_field;
 pub mod egui_renderer;
 pub mod entity;
+pub mod font;
 pub mod graphics;
 pub mod input;
+pub mod light_probe;
 pub mod lighting;
+pub mod lod;
 pub mod model;
+pub mod morph;
 pub mod panic;
+pub mod particles;
+pub mod picking;
+pub mod plugin;
 pub mod procedural;
 pub mod resources;
 pub mod scene;
 pub mod shader;
+pub mod sprite;
+pub mod ssao;
+pub mod stats;
+pub mod tone_mapping;
 pub mod utils;
 
 pub static WGPU_BACKEND: OnceLock<String> = OnceLock::new();
 
+/// The index of the monitor that fullscreen windows are placed on, into the list of available
+/// monitors. The primary monitor is used if this is `None` or out of range.
+///
+/// This starts off as [`WindowConfiguration::preferred_monitor`], and can be changed at any time
+/// with [`set_preferred_monitor`].
+static PREFERRED_MONITOR: LazyLock<Mutex<Option<usize>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Changes the monitor used the next time the window goes fullscreen.
+pub fn set_preferred_monitor(index: Option<usize>) {
+    *PREFERRED_MONITOR.lock() = index;
+}
+
+/// The index of the monitor used when the window goes fullscreen, see [`set_preferred_monitor`].
+pub fn preferred_monitor() -> Option<usize> {
+    *PREFERRED_MONITOR.lock()
+}
+
+/// The range that [`set_ui_scale`] clamps the UI scale to.
+pub const UI_SCALE_RANGE: std::ops::RangeInclusive<f32> = 0.5..=3.0;
+
+/// How much bigger egui is drawn than the window's scale factor asks for.
+static UI_SCALE: LazyLock<Mutex<f32>> = LazyLock::new(|| Mutex::new(1.0));
+
+/// Changes the scale of egui, on top of the scale factor of the monitor the window is on.
+pub fn set_ui_scale(scale: f32) {
+    *UI_SCALE.lock() = scale.clamp(*UI_SCALE_RANGE.start(), *UI_SCALE_RANGE.end());
+}
+
+/// The scale egui is drawn at on top of the window's scale factor, see [`set_ui_scale`].
+pub fn ui_scale() -> f32 {
+    *UI_SCALE.lock()
+}
+
+/// How many pixels of a touchpad scroll count as one line of a mouse wheel.
+const PIXELS_PER_SCROLL_LINE: f64 = 20.0;
+
+/// The window icon used when [`WindowConfiguration::icon`] is not set.
+pub const DEFAULT_WINDOW_ICON: &[u8] =
+    include_bytes!("../../resources/textures/eucalyptus-icon.png");
+
 use app_dirs2::{AppDataType, AppInfo};
 use bytemuck::Contiguous;
 use chrono::Local;
@@ -35,8 +88,8 @@ use serde::{Deserialize, Serialize};
 use spin_sleep::SpinSleeper;
 use std::fs::OpenOptions;
 use std::io::Write;
-use std::path::Path;
-use std::sync::OnceLock;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, OnceLock};
 use std::{
     fmt::{self, Display, Formatter},
     sync::Arc,
@@ -50,10 +103,11 @@ use winit::event::{DeviceEvent, DeviceId};
 use winit::{
     application::ApplicationHandler,
     dpi::PhysicalSize,
-    event::{KeyEvent, WindowEvent},
+    event::{KeyEvent, MouseScrollDelta, WindowEvent},
     event_loop::{ActiveEventLoop, EventLoop},
     keyboard::{KeyCode, PhysicalKey},
-    window::Window,
+    monitor::MonitorHandle,
+    window::{Fullscreen, Icon, Window},
 };
 
 use crate::{egui_renderer::EguiRenderer, graphics::Texture};
@@ -72,11 +126,14 @@ pub struct State {
     pub is_surface_configured: bool,
     pub depth_texture: Texture,
     pub texture_bind_layout: BindGroupLayout,
+    pub emissive_bind_layout: BindGroupLayout,
     pub egui_renderer: Arc<Mutex<EguiRenderer>>,
     pub instance: Instance,
     pub viewport_texture: Texture,
     pub texture_id: Arc<TextureId>,
     pub future_queue: Arc<FutureQueue>,
+    /// The [`ui_scale`] the viewport texture was last created for
+    applied_ui_scale: f32,
 
     pub window: Arc<Window>, // note to self: functions can only be called in the main thread
 }
@@ -106,7 +163,8 @@ impl State {
         let (device, queue) = adapter
             .request_device(&wgpu::DeviceDescriptor {
                 label: None,
-                required_features: wgpu::Features::empty(),
+                // BC compression is optional, textures fall back to RGBA8 without it
+                required_features: adapter.features() & wgpu::Features::TEXTURE_COMPRESSION_BC,
                 required_limits: wgpu::Limits::default(),
                 experimental_features: unsafe { ExperimentalFeatures::enabled() },
                 memory_hints: Default::default(),
@@ -204,6 +262,39 @@ Hardware:
                 label: Some("texture_bind_group_layout"),
             });
 
+        let emissive_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            multisampled: false,
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 2,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                ],
+                label: Some("emissive_bind_group_layout"),
+            });
+
         let mut egui_renderer = Arc::new(Mutex::new(EguiRenderer::new(
             &device,
             config.format,
@@ -226,12 +317,14 @@ Hardware:
             is_surface_configured: true,
             depth_texture,
             texture_bind_layout: texture_bind_group_layout,
+            emissive_bind_layout: emissive_bind_group_layout,
             window,
             instance,
             egui_renderer,
             viewport_texture,
             texture_id: Arc::new(texture_id),
             future_queue,
+            applied_ui_scale: ui_scale(),
         };
 
         Ok(result)
@@ -301,9 +394,16 @@ Hardware:
             }
         };
 
+        let ui_scale = ui_scale();
+        if ui_scale != self.applied_ui_scale {
+            // the viewport is laid out in points, so its texture is made again for the new scale
+            self.applied_ui_scale = ui_scale;
+            self.resize(self.config.width, self.config.height);
+        }
+
         let screen_descriptor = ScreenDescriptor {
             size_in_pixels: [self.config.width, self.config.height],
-            pixels_per_point: self.window.scale_factor() as f32,
+            pixels_per_point: self.window.scale_factor() as f32 * ui_scale,
         };
 
         let view = output
@@ -392,12 +492,26 @@ pub struct App {
     /// Winit doesn't use async, so this is the next best alternative.
     future_queue: Arc<FutureQueue>,
     delta_position: Option<(f64, f64)>,
+    /// Plugins that have been added but not built yet.
+    pending_plugins: Vec<Box<dyn plugin::Plugin>>,
+    /// Plugins that have been built, kept around so they live as long as the app.
+    plugins: Vec<Box<dyn plugin::Plugin>>,
+    /// Resources inserted by plugins.
+    resources: plugin::Resources,
+    /// Systems added by plugins, run every frame before rendering.
+    systems: Vec<plugin::System>,
 }
 
 impl App {
     /// Creates a new instance of the application. It only sets the default for the struct + the
     /// window config.
     fn new(config: WindowConfiguration, future_queue: Option<Arc<FutureQueue>>) -> Self {
+        set_preferred_monitor(config.preferred_monitor);
+        ssao::set_ssao_settings(config.ssao);
+        if let Some(settings) = config.depth_of_field.clone() {
+            depth_of_field::set_depth_of_field(settings);
+        }
+        tone_mapping::set_tone_mapping(config.tone_mapping);
         let result = Self {
             state: None,
             config: config.clone(),
@@ -410,6 +524,10 @@ impl App {
             gilrs: GilrsBuilder::new().build().unwrap(),
             future_queue: future_queue.unwrap_or_else(|| Arc::new(FutureQueue::new())),
             delta_position: None,
+            pending_plugins: Vec::new(),
+            plugins: Vec::new(),
+            resources: plugin::Resources::new(),
+            systems: Vec::new(),
         };
         log::debug!("Created new instance of app");
         result
@@ -424,6 +542,60 @@ impl App {
         self.target_fps = fps.max(1);
     }
 
+    /// Adds a plugin, which is built when the app starts running.
+    pub fn add_plugin<P: plugin::Plugin>(&mut self, plugin: P) -> &mut Self {
+        log::debug!("Added plugin {}", plugin.name());
+        self.pending_plugins.push(Box::new(plugin));
+        self
+    }
+
+    /// Inserts a resource that systems can read and change, replacing any of the same type.
+    pub fn insert_resource<T: 'static>(&mut self, value: T) -> &mut Self {
+        self.resources.insert(value);
+        self
+    }
+
+    /// Adds a system that is run every frame, before the scenes are updated.
+    pub fn add_system(
+        &mut self,
+        system: impl FnMut(&mut plugin::Resources, f32) + 'static,
+    ) -> &mut Self {
+        self.systems.push(Box::new(system));
+        self
+    }
+
+    pub fn resources(&self) -> &plugin::Resources {
+        &self.resources
+    }
+
+    pub fn resources_mut(&mut self) -> &mut plugin::Resources {
+        &mut self.resources
+    }
+
+    pub fn scene_manager_mut(&mut self) -> &mut scene::Manager {
+        &mut self.scene_manager
+    }
+
+    pub fn input_manager_mut(&mut self) -> &mut input::Manager {
+        &mut self.input_manager
+    }
+
+    pub fn future_queue(&self) -> Arc<FutureQueue> {
+        self.future_queue.clone()
+    }
+
+    /// Builds every plugin that has been added. Plugins that add other plugins while being built
+    /// have those built as well.
+    fn build_plugins(&mut self) {
+        while !self.pending_plugins.is_empty() {
+            for plugin in std::mem::take(&mut self.pending_plugins) {
+                log::debug!("Building plugin {}", plugin.name());
+                plugin.build(self);
+                self.plugins.push(plugin);
+            }
+        }
+    }
+
     /// The run function. This function runs the app into gear.
     ///
     /// ## Warning
@@ -446,6 +618,52 @@ impl App {
     ) -> anyhow::Result<()>
     where
         F: FnOnce(scene::Manager, input::Manager) -> (scene::Manager, input::Manager),
+    {
+        Self::run_with_plugins(
+            config,
+            app_name,
+            future_queue,
+            |_| {},
+            |scene, input| async move { setup(scene, input) },
+        )
+        .await
+    }
+
+    /// The same as [`App::run`], but with an `async` setup closure, allowing for async
+    /// initialisation such as loading configs or fetching remote manifests before the first scene.
+    ///
+    /// The setup future is driven to completion before the event loop starts. Use the
+    /// [`run_app_async!`] macro instead of calling this directly.
+    pub async fn run_async<F, Fut>(
+        config: WindowConfiguration,
+        app_name: &str,
+        future_queue: Option<Arc<FutureQueue>>,
+        setup: F,
+    ) -> anyhow::Result<()>
+    where
+        F: FnOnce(scene::Manager, input::Manager) -> Fut,
+        Fut: Future<Output = (scene::Manager, input::Manager)>,
+    {
+        Self::run_with_plugins(config, app_name, future_queue, |_| {}, setup).await
+    }
+
+    /// The same as [`App::run_async`], but `plugins` is called with the app first so that
+    /// plugins can be added with [`App::add_plugin`]. Every plugin is built before the setup
+    /// closure is run.
+    ///
+    /// Use the plugin arm of the [`run_app!`] or [`run_app_async!`] macros instead of calling
+    /// this directly.
+    pub async fn run_with_plugins<P, F, Fut>(
+        config: WindowConfiguration,
+        app_name: &str,
+        future_queue: Option<Arc<FutureQueue>>,
+        plugins: P,
+        setup: F,
+    ) -> anyhow::Result<()>
+    where
+        P: FnOnce(&mut App),
+        F: FnOnce(scene::Manager, input::Manager) -> Fut,
+        Fut: Future<Output = (scene::Manager, input::Manager)>,
     {
         let log_dir = app_dirs2::app_root(AppDataType::UserData, &config.app_info)
             .expect("Failed to get app data directory")
@@ -556,9 +774,12 @@ impl App {
         let mut app = Box::new(App::new(config, future_queue));
         log::debug!("Configured app with details: {}", app.config);
 
+        plugins(&mut app);
+        app.build_plugins();
+
         log::debug!("Running through setup");
 
-        let (new_scene, new_input) = setup(app.scene_manager, app.input_manager);
+        let (new_scene, new_input) = setup(app.scene_manager, app.input_manager).await;
         app.scene_manager = new_scene;
         app.input_manager = new_input;
         log::debug!("Running app");
@@ -566,6 +787,53 @@ impl App {
 
         Ok(())
     }
+
+    /// Runs the game as a headless server, ticking the active scene `config.target_tps` times a
+    /// second until it sends [`scene::SceneCommand::Quit`].
+    ///
+    /// No window, event loop or graphics context is created, so this runs on machines without a
+    /// GPU. Scenes are driven through [`scene::Scene::load_headless`] and [`scene::Scene::tick`]
+    /// instead of their rendering counterparts, and are never rendered.
+    ///
+    /// # Parameters:
+    /// - config: The tick rate and port of the server.
+    /// - setup: A closure that adds the scenes and switches to the first one.
+    pub fn run_server<F>(config: ServerConfiguration, setup: F) -> anyhow::Result<()>
+    where
+        F: FnOnce(scene::Manager) -> scene::Manager,
+    {
+        let _ = Builder::new()
+            .filter_level(LevelFilter::Info)
+            .parse_default_env()
+            .try_init();
+
+        log::info!(
+            "dropbear-engine v{} running as a server on port {} at {} ticks per second",
+            env!("CARGO_PKG_VERSION"),
+            config.port,
+            config.target_tps
+        );
+
+        let mut scene_manager = setup(scene::Manager::new());
+        let tick_time = Duration::from_secs_f32(1.0 / config.target_tps.max(1) as f32);
+        let sleeper = SpinSleeper::default();
+        let mut dt = tick_time.as_secs_f32();
+
+        loop {
+            let tick_start = Instant::now();
+            if !scene_manager.tick(dt) {
+                break;
+            }
+
+            let tick_elapsed = tick_start.elapsed();
+            if tick_elapsed < tick_time {
+                sleeper.sleep(tick_time - tick_elapsed);
+            }
+            dt = tick_start.elapsed().as_secs_f32();
+        }
+
+        Ok(())
+    }
 }
 
 #[macro_export]
@@ -580,10 +848,40 @@ impl App {
 /// * queue - [`Option<Throwable<FutureQueue>>`]: An optional value for a [`FutureQueue`]
 /// * setup - [`FnOnce`]: A function that sets up all the scenes. It shouldn't be loaded
 ///   but instead be set as an [`Arc<Mutex<T>>`].
+/// * plugins - [`FnOnce`] (optional, after `plugins =`): A function that adds plugins to the
+///   [`App`] with [`App::add_plugin`].
 macro_rules! run_app {
     ($config:expr, $queue:expr, $setup:expr) => {
         $crate::App::run($config, env!("CARGO_PKG_NAME"), $queue, $setup)
     };
+    ($config:expr, $queue:expr, $setup:expr, plugins = $plugins:expr) => {
+        $crate::App::run_with_plugins(
+            $config,
+            env!("CARGO_PKG_NAME"),
+            $queue,
+            $plugins,
+            |scene, input| async move { ($setup)(scene, input) },
+        )
+    };
+}
+
+#[macro_export]
+/// The same as [`run_app!`], but for an `async` setup closure, which is run with
+/// [`App::run_async()`].
+///
+/// # Parameters
+/// * config - [`WindowConfiguration`]: The configuration/settings of the window.
+/// * queue - [`Option<Throwable<FutureQueue>>`]: An optional value for a [`FutureQueue`]
+/// * setup - [`FnOnce`]: A function returning a future that sets up all the scenes.
+/// * plugins - [`FnOnce`] (optional, after `plugins =`): A function that adds plugins to the
+///   [`App`] with [`App::add_plugin`].
+macro_rules! run_app_async {
+    ($config:expr, $queue:expr, $setup:expr) => {
+        $crate::App::run_async($config, env!("CARGO_PKG_NAME"), $queue, $setup)
+    };
+    ($config:expr, $queue:expr, $setup:expr, plugins = $plugins:expr) => {
+        $crate::App::run_with_plugins($config, env!("CARGO_PKG_NAME"), $queue, $plugins, $setup)
+    };
 }
 
 impl ApplicationHandler for App {
@@ -600,10 +898,20 @@ impl ApplicationHandler for App {
             window_attributes = window_attributes.with_maximized(true);
         } else if self.config.window_config.windowed_mode.is_fullscreen() {
             window_attributes = window_attributes
-                .with_fullscreen(Some(winit::window::Fullscreen::Borderless(None)));
+                .with_fullscreen(Some(borderless_fullscreen(event_loop.available_monitors())));
+        }
+
+        let icon = load_window_icon(self.config.icon.as_deref());
+
+        #[cfg(target_os = "windows")]
+        {
+            use winit::platform::windows::WindowAttributesExtWindows;
+            window_attributes = window_attributes.with_taskbar_icon(icon.clone());
         }
 
         let window = Arc::new(event_loop.create_window(window_attributes).unwrap());
+        // on most platforms the window icon is also used for the taskbar entry
+        window.set_window_icon(icon);
 
         self.state = Some(block_on(State::new(window, self.future_queue.clone())).unwrap());
 
@@ -646,6 +954,11 @@ impl ApplicationHandler for App {
                 self.input_manager.set_active_handlers(active_handlers);
 
                 self.input_manager.update(&mut self.gilrs);
+                self.input_manager.poll_source(event_loop);
+
+                for system in &mut self.systems {
+                    system(&mut self.resources, self.delta_time);
+                }
 
                 let render_result =
                     state.render(&mut self.scene_manager, self.delta_time, event_loop);
@@ -684,20 +997,16 @@ impl ApplicationHandler for App {
             } => {
                 if code == KeyCode::F11
                     && key_state.is_pressed()
-                    && let Some(state) = &self.state
+                    && let Some(state) = &mut self.state
                 {
                     match self.config.window_config.windowed_mode {
                         WindowedModes::Windowed(_, _) => {
                             if state.window.fullscreen().is_some() {
-                                state.window.set_fullscreen(None);
-                                let _ = state
-                                    .window
-                                    .request_inner_size(PhysicalSize::new(1280, 720));
-                                state.window.set_maximized(false);
+                                leave_fullscreen(state);
                             } else {
-                                state.window.set_fullscreen(Some(
-                                    winit::window::Fullscreen::Borderless(None),
-                                ));
+                                state.window.set_fullscreen(Some(borderless_fullscreen(
+                                    state.window.available_monitors(),
+                                )));
                             }
                         }
                         WindowedModes::Maximised => {
@@ -706,34 +1015,45 @@ impl ApplicationHandler for App {
                                 state.window.set_maximized(true);
                             } else {
                                 state.window.set_maximized(false);
-                                state.window.set_fullscreen(Some(
-                                    winit::window::Fullscreen::Borderless(None),
-                                ));
+                                state.window.set_fullscreen(Some(borderless_fullscreen(
+                                    state.window.available_monitors(),
+                                )));
                             }
                         }
-                        WindowedModes::Fullscreen => {
-                            state.window.set_fullscreen(None);
-                            let _ = state
-                                .window
-                                .request_inner_size(PhysicalSize::new(1280, 720));
-                            state.window.set_maximized(false);
-                        }
+                        WindowedModes::Fullscreen => leave_fullscreen(state),
                     }
                 }
-                self.input_manager
-                    .handle_key_input(code, key_state.is_pressed(), event_loop);
+                if !self.input_manager.has_source() {
+                    self.input_manager
+                        .handle_key_input(code, key_state.is_pressed(), event_loop);
+                }
             }
             WindowEvent::MouseInput {
                 button,
                 state: button_state,
                 ..
-            } => {
+            } if !self.input_manager.has_source() => {
                 self.input_manager
                     .handle_mouse_input(button, button_state.is_pressed());
             }
-            WindowEvent::CursorMoved { position, .. } => {
+            WindowEvent::CursorMoved { position, .. } if !self.input_manager.has_source() => {
                 self.input_manager.handle_mouse_movement(position, None);
             }
+            WindowEvent::MouseWheel { delta, .. } if !self.input_manager.has_source() => {
+                let delta = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => (x, y),
+                    MouseScrollDelta::PixelDelta(position) => (
+                        (position.x / PIXELS_PER_SCROLL_LINE) as f32,
+                        (position.y / PIXELS_PER_SCROLL_LINE) as f32,
+                    ),
+                };
+                self.input_manager.handle_scroll(delta);
+            }
+            WindowEvent::DroppedFile(path) => {
+                log::debug!("File dropped: {}", path.display());
+                self.scene_manager
+                    .send_command(scene::SceneCommand::ImportDroppedFile(path));
+            }
             _ => {}
         }
     }
@@ -746,7 +1066,7 @@ impl ApplicationHandler for App {
     ) {
         #[allow(clippy::single_match)]
         match event {
-            DeviceEvent::MouseMotion { delta } => {
+            DeviceEvent::MouseMotion { delta } if !self.input_manager.has_source() => {
                 self.delta_position = Some(delta);
                 self.input_manager
                     .handle_mouse_movement(self.input_manager.get_mouse_position(), Some(delta));
@@ -766,6 +1086,112 @@ pub struct WindowConfiguration {
     pub title: String,
     pub window_config: MutableWindowConfiguration,
     pub app_info: AppInfo,
+    /// Path to an image used as the window and taskbar icon. The Eucalyptus icon is used if
+    /// this is `None`.
+    pub icon: Option<PathBuf>,
+    /// The index of the monitor to go fullscreen on, into the list of available monitors. The
+    /// primary monitor is used if this is `None`.
+    pub preferred_monitor: Option<usize>,
+    /// Settings for screen-space ambient occlusion, which is turned off if this is `None`.
+    pub ssao: Option<ssao::SsaoSettings>,
+    /// Settings for depth of field, which is turned off if this is `None`.
+    pub depth_of_field: Option<depth_of_field::DepthOfFieldSettings>,
+    /// The curve the frame is tone mapped with, see [`tone_mapping::ToneMapping`].
+    pub tone_mapping: tone_mapping::ToneMappingOperator,
+}
+
+/// The configuration of a headless server started with [`App::run_server`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServerConfiguration {
+    /// How many times a second the active scene is ticked.
+    pub target_tps: u32,
+    /// The port the game's networking listens on. The engine doesn't open any sockets itself.
+    pub port: u16,
+}
+
+impl ServerConfiguration {
+    pub fn new(target_tps: u32, port: u16) -> Self {
+        Self { target_tps, port }
+    }
+}
+
+/// Creates a borderless fullscreen mode on the [`preferred_monitor`], falling back to the
+/// primary monitor if it is not connected.
+fn borderless_fullscreen(mut monitors: impl Iterator<Item = MonitorHandle>) -> Fullscreen {
+    let Some(index) = preferred_monitor() else {
+        return Fullscreen::Borderless(None);
+    };
+
+    let monitor = monitors.nth(index);
+    if monitor.is_none() {
+        log::warn!(
+            "Preferred monitor {} is not available, using the primary monitor",
+            index
+        );
+    }
+    Fullscreen::Borderless(monitor)
+}
+
+/// Leaves fullscreen into a 1280x720 window.
+///
+/// If the new size is applied straight away there may not be a resize event for it, so the
+/// surface is resized here to whatever size the window actually ended up with. Otherwise the
+/// platform decides when and whether to resize the window, such as a Wayland compositor, and the
+/// surface is resized once the resize event arrives.
+fn leave_fullscreen(state: &mut State) {
+    let requested = PhysicalSize::new(1280, 720);
+    state.window.set_fullscreen(None);
+    match state.window.request_inner_size(requested) {
+        Some(size) => {
+            if size != requested {
+                log::warn!(
+                    "Requested a {}x{} window, but got {}x{}",
+                    requested.width,
+                    requested.height,
+                    size.width,
+                    size.height
+                );
+            }
+            state.resize(size.width, size.height);
+        }
+        None => log::debug!("Waiting for the window to be resized after leaving fullscreen"),
+    }
+    state.window.set_maximized(false);
+}
+
+/// Loads the window icon from `path`, falling back to [`DEFAULT_WINDOW_ICON`] if there is no
+/// path or the image cannot be loaded.
+fn load_window_icon(path: Option<&Path>) -> Option<Icon> {
+    fn decode(image: image::DynamicImage) -> anyhow::Result<Icon> {
+        let rgba = image.to_rgba8();
+        let (width, height) = rgba.dimensions();
+        Ok(Icon::from_rgba(rgba.into_raw(), width, height)?)
+    }
+
+    if let Some(path) = path {
+        match image::open(path)
+            .map_err(anyhow::Error::from)
+            .and_then(decode)
+        {
+            Ok(icon) => return Some(icon),
+            Err(e) => log::warn!(
+                "Unable to load window icon {}, using the default icon: {}",
+                path.display(),
+                e
+            ),
+        }
+    }
+
+    match image::load_from_memory(DEFAULT_WINDOW_ICON)
+        .map_err(anyhow::Error::from)
+        .and_then(decode)
+    {
+        Ok(icon) => Some(icon),
+        Err(e) => {
+            log::error!("Unable to load the default window icon: {}", e);
+            None
+        }
+    }
 }
 
 /// Window configuration that contains values that can be serialized into files/mutated by the user.
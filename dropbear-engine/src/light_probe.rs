@@ -0,0 +1,325 @@
+//! Light probes, which store localised ambient lighting as spherical harmonics.
+//!
+//! See <https://graphics.stanford.edu/papers/envmap/envmap.pdf>
+
+use crate::entity::{EntityTransform, MeshRenderer, Transform};
+use crate::lighting::{LightComponent, LightType};
+use dropbear_macro::SerializableComponent;
+use dropbear_traits::SerializableComponent;
+use glam::{DVec3, Vec3};
+use serde::{Deserialize, Serialize};
+
+pub const MAX_LIGHT_PROBES: usize = 4;
+
+/// A component that overrides the global ambient term for any fragment within its radius.
+///
+/// The coefficients are the first 9 spherical harmonic terms of the irradiance around the probe,
+/// and are filled in by the [`IrradianceBaker`].
+#[derive(Debug, Clone, Serialize, Deserialize, SerializableComponent)]
+pub struct LightProbe {
+    pub irradiance_coefficients: [Vec3; 9],
+    pub radius: f32,
+}
+
+impl Default for LightProbe {
+    fn default() -> Self {
+        Self {
+            irradiance_coefficients: [Vec3::ZERO; 9],
+            radius: 10.0,
+        }
+    }
+}
+
+impl LightProbe {
+    /// Returns `true` if the probe has been baked at least once.
+    pub fn is_baked(&self) -> bool {
+        self.irradiance_coefficients
+            .iter()
+            .any(|c| *c != Vec3::ZERO)
+    }
+
+    pub fn to_uniform(&self, position: DVec3) -> LightProbeUniform {
+        let mut coefficients = [[0.0; 4]; 9];
+        for (i, c) in self.irradiance_coefficients.iter().enumerate() {
+            coefficients[i] = c.extend(0.0).to_array();
+        }
+
+        LightProbeUniform {
+            position_radius: position.as_vec3().extend(self.radius).to_array(),
+            coefficients,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct LightProbeUniform {
+    /// xyz is the position, w is the radius
+    pub position_radius: [f32; 4],
+    pub coefficients: [[f32; 4]; 9],
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct LightProbeArrayUniform {
+    pub probes: [LightProbeUniform; MAX_LIGHT_PROBES],
+    pub probe_count: u32,
+    pub _padding: [u32; 3],
+}
+
+impl Default for LightProbeArrayUniform {
+    fn default() -> Self {
+        Self {
+            probes: [LightProbeUniform::default(); MAX_LIGHT_PROBES],
+            probe_count: 0,
+            _padding: [0; 3],
+        }
+    }
+}
+
+/// A sphere that blocks rays while baking, approximated from a [`MeshRenderer`].
+struct Occluder {
+    centre: DVec3,
+    radius: f64,
+}
+
+/// A light that contributes to the baked irradiance.
+struct BakeLight {
+    position: DVec3,
+    direction: DVec3,
+    colour: DVec3,
+    light_type: LightType,
+    constant: f64,
+    linear: f64,
+    quadratic: f64,
+}
+
+/// Offline baker that computes the spherical harmonic coefficients of a [`LightProbe`].
+///
+/// Rays are marched from the probe position against a coarse approximation of the scene, where
+/// each mesh is treated as a bounding sphere. It is meant to be run from the editor, not per frame.
+pub struct IrradianceBaker {
+    /// Number of directions sampled around the probe
+    pub sample_count: u32,
+    /// Distance between each raymarching step
+    pub step_size: f64,
+    /// How far a ray travels before it is considered to have escaped
+    pub max_distance: f64,
+    /// Albedo used for any surface that is hit
+    pub surface_albedo: f64,
+}
+
+impl Default for IrradianceBaker {
+    fn default() -> Self {
+        Self {
+            sample_count: 256,
+            step_size: 0.25,
+            max_distance: 50.0,
+            surface_albedo: 0.5,
+        }
+    }
+}
+
+impl IrradianceBaker {
+    /// Bakes every [`LightProbe`] in the world, returning the number of probes baked.
+    pub fn bake_all(&self, world: &mut hecs::World) -> usize {
+        let probes: Vec<(hecs::Entity, DVec3)> = world
+            .query::<(&LightProbe, Option<&Transform>, Option<&EntityTransform>)>()
+            .iter()
+            .map(|(e, (_, t, et))| (e, probe_position(t, et)))
+            .collect();
+
+        for (entity, position) in &probes {
+            self.bake_entity(world, *entity, *position);
+        }
+
+        log::info!("Baked {} light probes", probes.len());
+        probes.len()
+    }
+
+    /// Bakes a single entity's [`LightProbe`], returning `false` if the entity does not have one.
+    pub fn bake_one(&self, world: &mut hecs::World, entity: hecs::Entity) -> bool {
+        let position = match world
+            .query_one::<(&LightProbe, Option<&Transform>, Option<&EntityTransform>)>(entity)
+        {
+            Ok(mut q) => match q.get() {
+                Some((_, t, et)) => probe_position(t, et),
+                None => return false,
+            },
+            Err(_) => return false,
+        };
+
+        self.bake_entity(world, entity, position);
+        true
+    }
+
+    fn bake_entity(&self, world: &mut hecs::World, entity: hecs::Entity, position: DVec3) {
+        let coefficients = self.bake(world, position);
+        if let Ok(mut probe) = world.get::<&mut LightProbe>(entity) {
+            probe.irradiance_coefficients = coefficients;
+            log::debug!("Baked light probe for entity {:?}", entity);
+        }
+    }
+
+    /// Computes the irradiance coefficients at a position in the world.
+    pub fn bake(&self, world: &hecs::World, position: DVec3) -> [Vec3; 9] {
+        let occluders = collect_occluders(world);
+        let lights = collect_lights(world);
+
+        let mut radiance_sh = [DVec3::ZERO; 9];
+        let n = self.sample_count.max(1);
+        let weight = 4.0 * std::f64::consts::PI / n as f64;
+
+        for i in 0..n {
+            let dir = fibonacci_direction(i, n);
+            let radiance = self.trace(position, dir, &occluders, &lights);
+            let basis = sh_basis(dir);
+            for (c, b) in radiance_sh.iter_mut().zip(basis) {
+                *c += radiance * b * weight;
+            }
+        }
+
+        // convolve radiance with a cosine lobe to turn it into irradiance
+        const BAND_FACTORS: [f64; 9] = [
+            std::f64::consts::PI,
+            2.0 * std::f64::consts::PI / 3.0,
+            2.0 * std::f64::consts::PI / 3.0,
+            2.0 * std::f64::consts::PI / 3.0,
+            std::f64::consts::PI / 4.0,
+            std::f64::consts::PI / 4.0,
+            std::f64::consts::PI / 4.0,
+            std::f64::consts::PI / 4.0,
+            std::f64::consts::PI / 4.0,
+        ];
+
+        let mut result = [Vec3::ZERO; 9];
+        for ((out, c), factor) in result.iter_mut().zip(radiance_sh).zip(BAND_FACTORS) {
+            *out = (c * factor).as_vec3();
+        }
+        result
+    }
+
+    fn trace(
+        &self,
+        origin: DVec3,
+        dir: DVec3,
+        occluders: &[Occluder],
+        lights: &[BakeLight],
+    ) -> DVec3 {
+        let mut t = self.step_size;
+        while t < self.max_distance {
+            let point = origin + dir * t;
+            // occluders containing the probe itself are ignored, otherwise every ray is blocked
+            if let Some(occluder) = occluders.iter().find(|o| {
+                let r2 = o.radius * o.radius;
+                point.distance_squared(o.centre) <= r2 && origin.distance_squared(o.centre) > r2
+            }) {
+                let normal = (point - occluder.centre).normalize_or_zero();
+                return direct_lighting(point, normal, lights) * self.surface_albedo;
+            }
+            t += self.step_size;
+        }
+
+        // ray escaped, so only directional lights (sky) contribute
+        lights
+            .iter()
+            .filter(|l| l.light_type == LightType::Directional)
+            .map(|l| l.colour * dir.dot(-l.direction).max(0.0))
+            .fold(DVec3::ZERO, |a, b| a + b)
+    }
+}
+
+fn probe_position(
+    transform: Option<&Transform>,
+    entity_transform: Option<&EntityTransform>,
+) -> DVec3 {
+    if let Some(et) = entity_transform {
+        et.sync().position
+    } else if let Some(t) = transform {
+        t.position
+    } else {
+        DVec3::ZERO
+    }
+}
+
+fn collect_occluders(world: &hecs::World) -> Vec<Occluder> {
+    world
+        .query::<&MeshRenderer>()
+        .iter()
+        .map(|(_, renderer)| Occluder {
+            centre: renderer.instance.position,
+            radius: renderer.instance.scale.max_element() * 0.5,
+        })
+        .collect()
+}
+
+fn collect_lights(world: &hecs::World) -> Vec<BakeLight> {
+    let mut lights = Vec::new();
+    for (_, (light, t, et)) in world
+        .query::<(
+            &LightComponent,
+            Option<&Transform>,
+            Option<&EntityTransform>,
+        )>()
+        .iter()
+    {
+        if !light.enabled {
+            continue;
+        }
+
+        let transform = et.map(|et| et.sync()).or(t.copied()).unwrap_or_default();
+        lights.push(BakeLight {
+            position: transform.position,
+            direction: (transform.rotation * DVec3::new(0.0, 0.0, -1.0)).normalize_or_zero(),
+            colour: light.colour * light.intensity as f64,
+            light_type: light.light_type,
+            constant: light.attenuation.constant as f64,
+            linear: light.attenuation.linear as f64,
+            quadratic: light.attenuation.quadratic as f64,
+        });
+    }
+    lights
+}
+
+fn direct_lighting(point: DVec3, normal: DVec3, lights: &[BakeLight]) -> DVec3 {
+    let mut total = DVec3::ZERO;
+    for light in lights {
+        let (to_light, attenuation) = match light.light_type {
+            LightType::Directional => (-light.direction, 1.0),
+            LightType::Point | LightType::Spot | LightType::Area => {
+                let delta = light.position - point;
+                let d = delta.length();
+                (
+                    delta.normalize_or_zero(),
+                    1.0 / (light.constant + light.linear * d + light.quadratic * d * d),
+                )
+            }
+        };
+        total += light.colour * normal.dot(to_light).max(0.0) * attenuation;
+    }
+    total
+}
+
+/// Evenly distributes `n` directions around a sphere.
+fn fibonacci_direction(i: u32, n: u32) -> DVec3 {
+    let golden_angle = std::f64::consts::PI * (3.0 - 5.0_f64.sqrt());
+    let y = 1.0 - 2.0 * (i as f64 + 0.5) / n as f64;
+    let r = (1.0 - y * y).sqrt();
+    let theta = golden_angle * i as f64;
+    DVec3::new(theta.cos() * r, y, theta.sin() * r)
+}
+
+/// The real spherical harmonic basis functions up to band 2.
+fn sh_basis(d: DVec3) -> [f64; 9] {
+    [
+        0.282095,
+        0.488603 * d.y,
+        0.488603 * d.z,
+        0.488603 * d.x,
+        1.092548 * d.x * d.y,
+        1.092548 * d.y * d.z,
+        0.315392 * (3.0 * d.z * d.z - 1.0),
+        1.092548 * d.x * d.z,
+        0.546274 * (d.x * d.x - d.y * d.y),
+    ]
+}
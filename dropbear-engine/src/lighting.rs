@@ -1,5 +1,7 @@
 use crate::attenuation::{Attenuation, RANGE_50};
+use crate::buffer::TypedBuffer;
 use crate::graphics::SharedGraphicsContext;
+use crate::light_probe::{LightProbe, LightProbeArrayUniform, MAX_LIGHT_PROBES};
 use crate::shader::Shader;
 use crate::{
     camera::Camera,
@@ -13,7 +15,7 @@ use std::fmt::{Display, Formatter};
 use std::sync::Arc;
 use wgpu::{
     BindGroup, BindGroupLayout, Buffer, BufferAddress, CompareFunction, DepthBiasState,
-    RenderPipeline, StencilState, VertexBufferLayout, util::DeviceExt,
+    RenderPipeline, StencilState, VertexBufferLayout,
 };
 
 pub const MAX_LIGHTS: usize = 8;
@@ -30,6 +32,10 @@ pub struct LightUniform {
     pub linear: f32,
     pub quadratic: f32,
     pub cutoff: f32,
+    /// Half of the area light's width along its local X axis
+    pub right: [f32; 4],
+    /// Half of the area light's height along its local Y axis
+    pub up: [f32; 4],
 }
 
 fn dvec3_to_uniform_array(vec: DVec3) -> [f32; 4] {
@@ -45,6 +51,20 @@ fn dvec3_colour_to_uniform_array(vec: DVec3, light_type: LightType) -> [f32; 4]
     ]
 }
 
+/// Returns the half extents of an area light as `(right, up)`, or zero for other light types.
+fn area_extents_to_uniform_arrays(light: &LightComponent, rotation: DQuat) -> ([f32; 4], [f32; 4]) {
+    if light.light_type != LightType::Area {
+        return ([0.0; 4], [0.0; 4]);
+    }
+
+    let right = rotation * DVec3::X * (light.area_width as f64 * 0.5);
+    let up = rotation * DVec3::Y * (light.area_height as f64 * 0.5);
+    (
+        [right.x as f32, right.y as f32, right.z as f32, 0.0],
+        [up.x as f32, up.y as f32, up.z as f32, 0.0],
+    )
+}
+
 fn dvec3_direction_to_uniform_array(vec: DVec3, outer_cutoff_angle: f32) -> [f32; 4] {
     [
         vec.x as f32,
@@ -65,6 +85,8 @@ impl Default for LightUniform {
             linear: 0.0,
             quadratic: 0.0,
             cutoff: f32::cos(12.5_f32.to_radians()),
+            right: [0.0; 4],
+            up: [0.0; 4],
         }
     }
 }
@@ -97,6 +119,8 @@ pub enum LightType {
     Point = 1,
     // Example: Flashlight
     Spot = 2,
+    // Example: Window, ceiling panel
+    Area = 3,
 }
 
 impl Display for LightType {
@@ -105,6 +129,7 @@ impl Display for LightType {
             LightType::Directional => write!(f, "Directional"),
             LightType::Point => write!(f, "Point"),
             LightType::Spot => write!(f, "Spot"),
+            LightType::Area => write!(f, "Area"),
         }
     }
 }
@@ -115,6 +140,7 @@ impl From<LightType> for u32 {
             LightType::Directional => 0,
             LightType::Point => 1,
             LightType::Spot => 2,
+            LightType::Area => 3,
         }
     }
 }
@@ -131,6 +157,10 @@ pub struct LightComponent {
     pub visible: bool,            // all - cube
     pub cutoff_angle: f32,        // spot
     pub outer_cutoff_angle: f32,  // spot
+    #[serde(default = "LightComponent::default_area_size")]
+    pub area_width: f32, // area
+    #[serde(default = "LightComponent::default_area_size")]
+    pub area_height: f32, // area
 }
 
 impl Default for LightComponent {
@@ -146,6 +176,8 @@ impl Default for LightComponent {
             cutoff_angle: 12.5,
             outer_cutoff_angle: 17.5,
             visible: true,
+            area_width: Self::default_area_size(),
+            area_height: Self::default_area_size(),
         }
     }
 }
@@ -156,6 +188,10 @@ impl LightComponent {
         dir.normalize()
     }
 
+    fn default_area_size() -> f32 {
+        1.0
+    }
+
     pub fn new(
         colour: DVec3,
         light_type: LightType,
@@ -163,7 +199,7 @@ impl LightComponent {
         attenuation: Option<Attenuation>,
     ) -> Self {
         let direction = match light_type {
-            LightType::Directional | LightType::Spot => Self::default_direction(),
+            LightType::Directional | LightType::Spot | LightType::Area => Self::default_direction(),
             LightType::Point => DVec3::ZERO,
         };
 
@@ -178,6 +214,8 @@ impl LightComponent {
             cutoff_angle: 12.5,
             outer_cutoff_angle: 17.5,
             visible: true,
+            area_width: Self::default_area_size(),
+            area_height: Self::default_area_size(),
         }
     }
 
@@ -193,6 +231,13 @@ impl LightComponent {
         Self::new(colour, LightType::Spot, intensity, None)
     }
 
+    pub fn area(colour: DVec3, intensity: f32, width: f32, height: f32) -> Self {
+        let mut light = Self::new(colour, LightType::Area, intensity, None);
+        light.area_width = width;
+        light.area_height = height;
+        light
+    }
+
     pub fn hide_cube(&mut self) {
         self.visible = false;
     }
@@ -215,10 +260,10 @@ pub struct Light {
     pub uniform: LightUniform,
     pub cube_model: Arc<Model>,
     pub label: String,
-    buffer: Option<Buffer>,
+    buffer: Option<TypedBuffer<LightUniform>>,
     layout: Option<BindGroupLayout>,
     bind_group: Option<BindGroup>,
-    pub instance_buffer: Option<Buffer>,
+    pub instance_buffer: Option<TypedBuffer<InstanceRaw>>,
 }
 
 impl Light {
@@ -230,6 +275,7 @@ impl Light {
     ) -> Self {
         let forward = DVec3::new(0.0, 0.0, -1.0);
         let direction = transform.rotation * forward;
+        let (right, up) = area_extents_to_uniform_arrays(&light, transform.rotation);
 
         let uniform = LightUniform {
             position: dvec3_to_uniform_array(transform.position),
@@ -242,6 +288,8 @@ impl Light {
             linear: light.attenuation.linear,
             quadratic: light.attenuation.quadratic,
             cutoff: f32::cos(light.cutoff_angle.to_radians()),
+            right,
+            up,
         };
 
         log::trace!("Created new light uniform");
@@ -257,7 +305,7 @@ impl Light {
 
         let label_str = label.unwrap_or("Light").to_string();
 
-        let buffer = graphics.create_uniform(uniform, label);
+        let buffer = TypedBuffer::uniform(&graphics.device, uniform, label);
 
         let layout = graphics
             .device
@@ -279,10 +327,7 @@ impl Light {
             .device
             .create_bind_group(&wgpu::BindGroupDescriptor {
                 layout: &layout,
-                entries: &[wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: buffer.as_entire_binding(),
-                }],
+                entries: &[buffer.bind_group_entry(0)],
                 label,
             });
 
@@ -292,17 +337,12 @@ impl Light {
             DVec3::new(0.25, 0.25, 0.25),
         );
 
-        let instance_buffer =
-            graphics
-                .device
-                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                    label: match label {
-                        Some(_) => label,
-                        None => Some("instance buffer"),
-                    },
-                    contents: bytemuck::cast_slice(&[instance.to_raw()]),
-                    usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-                });
+        let instance_buffer = TypedBuffer::new(
+            &graphics.device,
+            wgpu::BufferUsages::VERTEX,
+            &[instance.to_raw()],
+            label.or(Some("instance buffer")),
+        );
 
         log::debug!("Created new light [{}]", label_str);
 
@@ -332,6 +372,10 @@ impl Light {
         self.uniform.quadratic = light.attenuation.quadratic;
 
         self.uniform.cutoff = f32::cos(light.cutoff_angle.to_radians());
+
+        let (right, up) = area_extents_to_uniform_arrays(light, transform.rotation);
+        self.uniform.right = right;
+        self.uniform.up = up;
     }
 
     pub fn uniform(&self) -> &LightUniform {
@@ -355,16 +399,50 @@ impl Light {
     }
 
     pub fn buffer(&self) -> &Buffer {
-        self.buffer.as_ref().unwrap()
+        self.buffer.as_ref().unwrap().buffer()
+    }
+}
+
+/// How many lights were sent to the shader by the last [`LightManager::update`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LightStats {
+    /// The number of enabled lights sent to the shader
+    pub active_count: u32,
+    /// The most lights that can be sent to the shader, any more are left out
+    pub max_count: u32,
+    pub directional: u32,
+    pub point: u32,
+    pub spot: u32,
+    pub area: u32,
+}
+
+impl LightStats {
+    /// Checks if the light limit has been reached, in which case any more lights are not drawn.
+    pub fn is_full(&self) -> bool {
+        self.max_count > 0 && self.active_count >= self.max_count
+    }
+
+    fn record(&mut self, light_type: LightType) {
+        self.active_count += 1;
+        match light_type {
+            LightType::Directional => self.directional += 1,
+            LightType::Point => self.point += 1,
+            LightType::Spot => self.spot += 1,
+            LightType::Area => self.area += 1,
+        }
     }
 }
 
 #[derive(Clone)]
 pub struct LightManager {
     pub pipeline: Option<RenderPipeline>,
-    light_array_buffer: Option<Buffer>,
+    light_array_buffer: Option<TypedBuffer<LightArrayUniform>>,
+    light_probe_buffer: Option<TypedBuffer<LightProbeArrayUniform>>,
     light_array_bind_group: Option<BindGroup>,
     light_array_layout: Option<BindGroupLayout>,
+    /// The most lights sent to the shader, never more than [`MAX_LIGHTS`]
+    capacity: usize,
+    stats: LightStats,
 }
 
 impl Default for LightManager {
@@ -375,47 +453,94 @@ impl Default for LightManager {
 
 impl LightManager {
     pub fn new() -> Self {
+        Self::new_with_capacity(MAX_LIGHTS as u32)
+    }
+
+    /// Creates a light manager that sends at most `max` lights to the shader.
+    ///
+    /// The shader has room for [`MAX_LIGHTS`] lights, so a larger `max` is clamped to it.
+    pub fn new_with_capacity(max: u32) -> Self {
+        let capacity = (max as usize).min(MAX_LIGHTS);
+        if capacity < max as usize {
+            log::warn!(
+                "Requested {} lights, but the shader only supports {}",
+                max,
+                MAX_LIGHTS
+            );
+        }
+
         log::info!("Initialised lighting");
         Self {
             pipeline: None,
             light_array_buffer: None,
+            light_probe_buffer: None,
             light_array_bind_group: None,
             light_array_layout: None,
+            capacity,
+            stats: LightStats {
+                max_count: capacity as u32,
+                ..Default::default()
+            },
         }
     }
 
+    /// Counts the lights sent to the shader by the last [`LightManager::update`].
+    pub fn stats(&self) -> LightStats {
+        self.stats
+    }
+
     pub fn create_light_array_resources(&mut self, graphics: Arc<SharedGraphicsContext>) {
         let layout = graphics
             .device
             .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                entries: &[wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Buffer {
-                        ty: wgpu::BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: None,
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
                     },
-                    count: None,
-                }],
+                    // light probes
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                ],
                 label: Some("Light Array Layout"),
             });
 
-        let buffer = graphics.create_uniform(LightArrayUniform::default(), Some("Light Array"));
+        let buffer = TypedBuffer::uniform(
+            &graphics.device,
+            LightArrayUniform::default(),
+            Some("Light Array"),
+        );
+        let probe_buffer = TypedBuffer::uniform(
+            &graphics.device,
+            LightProbeArrayUniform::default(),
+            Some("Light Probe Array"),
+        );
 
         let bind_group = graphics
             .device
             .create_bind_group(&wgpu::BindGroupDescriptor {
                 layout: &layout,
-                entries: &[wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: buffer.as_entire_binding(),
-                }],
+                entries: &[buffer.bind_group_entry(0), probe_buffer.bind_group_entry(1)],
                 label: Some("Light Array Bind Group"),
             });
 
         self.light_array_layout = Some(layout);
         self.light_array_buffer = Some(buffer);
+        self.light_probe_buffer = Some(probe_buffer);
         self.light_array_bind_group = Some(bind_group);
         log::debug!("Created light array resources")
     }
@@ -423,6 +548,10 @@ impl LightManager {
     pub fn update(&mut self, graphics: Arc<SharedGraphicsContext>, world: &hecs::World) {
         let mut light_array = LightArrayUniform::default();
         let mut light_index = 0;
+        let mut stats = LightStats {
+            max_count: self.capacity as u32,
+            ..Default::default()
+        };
 
         for (_, (light_component, transform, light)) in world
             .query::<(&LightComponent, &Transform, &mut Light)>()
@@ -432,18 +561,14 @@ impl LightManager {
             // note: its not.
             let instance = Instance::from_matrix(transform.matrix());
 
-            if let Some(instance_buffer) = &light.instance_buffer {
-                let instance_raw = instance.to_raw();
-                graphics.queue.write_buffer(
-                    instance_buffer,
-                    0,
-                    bytemuck::cast_slice(&[instance_raw]),
-                );
+            if let Some(instance_buffer) = &mut light.instance_buffer {
+                instance_buffer.update(&graphics.device, &graphics.queue, &[instance.to_raw()]);
             }
 
-            if light_component.enabled && light_index < MAX_LIGHTS {
+            if light_component.enabled && light_index < self.capacity {
                 light_array.lights[light_index] = *light.uniform();
                 light_index += 1;
+                stats.record(light_component.light_type);
             }
         }
 
@@ -454,27 +579,49 @@ impl LightManager {
             let sync_transform = transform.sync();
             let instance = Instance::from_matrix(sync_transform.matrix());
 
-            if let Some(instance_buffer) = &light.instance_buffer {
-                let instance_raw = instance.to_raw();
-                graphics.queue.write_buffer(
-                    instance_buffer,
-                    0,
-                    bytemuck::cast_slice(&[instance_raw]),
-                );
+            if let Some(instance_buffer) = &mut light.instance_buffer {
+                instance_buffer.update(&graphics.device, &graphics.queue, &[instance.to_raw()]);
             }
 
-            if light_component.enabled && light_index < MAX_LIGHTS {
+            if light_component.enabled && light_index < self.capacity {
                 light_array.lights[light_index] = *light.uniform();
                 light_index += 1;
+                stats.record(light_component.light_type);
             }
         }
 
         light_array.light_count = light_index as u32;
+        self.stats = stats;
 
-        if let Some(buffer) = &self.light_array_buffer {
-            graphics
-                .queue
-                .write_buffer(buffer, 0, bytemuck::cast_slice(&[light_array]));
+        if let Some(buffer) = &mut self.light_array_buffer {
+            buffer.update(&graphics.device, &graphics.queue, &[light_array]);
+        }
+
+        let mut probe_array = LightProbeArrayUniform::default();
+        let mut probe_index = 0;
+        for (_, (probe, transform, entity_transform)) in world
+            .query::<(&LightProbe, Option<&Transform>, Option<&EntityTransform>)>()
+            .iter()
+        {
+            if probe_index >= MAX_LIGHT_PROBES || !probe.is_baked() {
+                continue;
+            }
+
+            let position = if let Some(entity_transform) = entity_transform {
+                entity_transform.sync().position
+            } else if let Some(transform) = transform {
+                transform.position
+            } else {
+                DVec3::ZERO
+            };
+
+            probe_array.probes[probe_index] = probe.to_uniform(position);
+            probe_index += 1;
+        }
+        probe_array.probe_count = probe_index as u32;
+
+        if let Some(buffer) = &mut self.light_probe_buffer {
+            buffer.update(&graphics.device, &graphics.queue, &[probe_array]);
         }
 
         log_once::debug_once!("LightUniform size = {}", size_of::<LightUniform>())
@@ -0,0 +1,96 @@
+//! Level of detail, used to swap a model for cheaper versions of itself as it moves away from the
+//! camera.
+
+use crate::asset::{ASSET_REGISTRY, AssetHandle};
+use crate::entity::{EntityTransform, Transform};
+use crate::utils::ResourceReference;
+use dropbear_macro::SerializableComponent;
+use dropbear_traits::SerializableComponent;
+use glam::DVec3;
+use serde::{Deserialize, Serialize};
+
+/// A component that picks which model an entity is drawn with based on its distance from the
+/// camera.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SerializableComponent)]
+pub struct LodGroup {
+    /// The models of each level as `(max_distance, model)`, sorted by distance in ascending order.
+    ///
+    /// This is what gets saved to the scene. Call [`LodGroup::resolve`] once the models are loaded
+    /// to fill [`LodGroup::levels`].
+    pub sources: Vec<(f32, ResourceReference)>,
+    /// The loaded models of each level as `(max_distance, model)`, sorted by distance in
+    /// ascending order.
+    #[serde(skip)]
+    pub levels: Vec<(f32, AssetHandle)>,
+}
+
+impl LodGroup {
+    /// Sorts [`LodGroup::sources`] and looks up the handle of every level that has been loaded into
+    /// the [`ASSET_REGISTRY`]. Levels that are not loaded yet are skipped.
+    pub fn resolve(&mut self) {
+        self.sources.sort_by(|a, b| a.0.total_cmp(&b.0));
+        self.levels = self
+            .sources
+            .iter()
+            .filter_map(|(distance, reference)| {
+                ASSET_REGISTRY
+                    .model_handle_from_reference(reference)
+                    .map(|handle| (*distance, handle))
+            })
+            .collect();
+    }
+
+    /// Returns the model of the first level whose max distance is further than `distance`.
+    ///
+    /// Past the last threshold, the last level is kept.
+    pub fn select(&self, distance: f32) -> Option<AssetHandle> {
+        self.levels
+            .iter()
+            .find(|(max_distance, _)| distance <= *max_distance)
+            .or(self.levels.last())
+            .map(|(_, handle)| *handle)
+    }
+}
+
+/// The model picked by a [`LodGroup`] for the current frame.
+///
+/// Renderers should draw this model instead of the one from the entity's mesh renderer when it
+/// is present.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActiveModel(pub AssetHandle);
+
+/// Picks a level for every [`LodGroup`] in the world and writes it to the entity's
+/// [`ActiveModel`].
+pub fn update_world(world: &mut hecs::World, camera_eye: DVec3) {
+    let mut changes = Vec::new();
+    for (entity, (group, active, t, et)) in world
+        .query::<(
+            &LodGroup,
+            Option<&ActiveModel>,
+            Option<&Transform>,
+            Option<&EntityTransform>,
+        )>()
+        .iter()
+    {
+        let position = et
+            .map(|et| et.sync().position)
+            .or(t.map(|t| t.position))
+            .unwrap_or_default();
+        let selected = group.select(position.distance(camera_eye) as f32);
+
+        if selected != active.map(|a| a.0) {
+            changes.push((entity, selected));
+        }
+    }
+
+    for (entity, selected) in changes {
+        match selected {
+            Some(handle) => {
+                let _ = world.insert_one(entity, ActiveModel(handle));
+            }
+            None => {
+                let _ = world.remove_one::<ActiveModel>(entity);
+            }
+        }
+    }
+}
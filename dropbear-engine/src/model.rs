@@ -1,10 +1,13 @@
-use crate::asset::AssetRegistry;
+use crate::asset::{AssetRegistry, TextureImportSettings};
+use crate::morph::{MorphTarget, MorphTargetSet};
+use crate::picking::Aabb;
 use crate::{
     asset::{ASSET_REGISTRY, AssetHandle},
     graphics::{SharedGraphicsContext, Texture},
     utils::ResourceReference,
 };
-use image::GenericImageView;
+use anyhow::Context;
+use glam::{DVec3, Vec3};
 use parking_lot::Mutex;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -145,6 +148,87 @@ pub struct Material {
     pub diffuse_texture: Texture,
     pub bind_group: wgpu::BindGroup,
     pub texture_tag: Option<String>,
+    /// Colour the diffuse texture is multiplied by in the file the material was loaded from,
+    /// white if it had none. Kept so exported scenes keep the colour of the source material.
+    pub base_colour_factor: [f32; 4],
+    /// Texture of the light the material gives off, multiplied by [`Material::emissive_factor`].
+    /// Materials without one sample white, so the factor alone decides the colour.
+    pub emissive_texture: Option<Texture>,
+    /// Colour added on top of the lit colour regardless of lighting, black if the material
+    /// doesn't glow
+    pub emissive_factor: [f32; 3],
+    /// Bind group of the emissive texture and factor, bound to group 3 of the model shader
+    pub emissive_bind_group: wgpu::BindGroup,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct EmissiveUniform {
+    factor: [f32; 4],
+}
+
+impl Material {
+    /// Creates the bind group read by the model shader for the emissive term of a material.
+    ///
+    /// A new uniform buffer is created every time, so materials cloned from the same model don't
+    /// share their factor.
+    pub fn create_emissive_bind_group(
+        graphics: Arc<SharedGraphicsContext>,
+        emissive_texture: Option<&Texture>,
+        emissive_factor: [f32; 3],
+    ) -> wgpu::BindGroup {
+        let white;
+        let texture = match emissive_texture {
+            Some(texture) => texture,
+            None => {
+                white = Texture::from_rgba_buffer(graphics.clone(), &[255, 255, 255, 255], (1, 1));
+                &white
+            }
+        };
+
+        let [r, g, b] = emissive_factor;
+        let buffer = graphics.create_uniform(
+            EmissiveUniform {
+                factor: [r, g, b, 0.0],
+            },
+            Some("emissive_uniform_buffer"),
+        );
+
+        graphics
+            .device
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &graphics.emissive_bind_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&texture.view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&texture.sampler),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: buffer.as_entire_binding(),
+                    },
+                ],
+                label: Some("emissive_bind_group"),
+            })
+    }
+
+    /// Changes the emissive factor of the material, rebuilding its emissive bind group.
+    pub fn set_emissive_factor(
+        &mut self,
+        graphics: Arc<SharedGraphicsContext>,
+        emissive_factor: [f32; 3],
+    ) {
+        self.emissive_factor = emissive_factor;
+        self.emissive_bind_group = Self::create_emissive_bind_group(
+            graphics,
+            self.emissive_texture.as_ref(),
+            emissive_factor,
+        );
+    }
 }
 
 #[derive(Clone)]
@@ -154,9 +238,57 @@ pub struct Mesh {
     pub index_buffer: wgpu::Buffer,
     pub num_elements: u32,
     pub material: usize,
+    /// Local space bounds of the vertices, used for picking
+    pub bounds: Aabb,
+    /// CPU side copy of the vertices uploaded to [`Mesh::vertex_buffer`], kept around for
+    /// exporting
+    pub vertices: Vec<ModelVertex>,
+    /// CPU side copy of the indices uploaded to [`Mesh::index_buffer`]
+    pub indices: Vec<u32>,
+    /// Blend shapes of the mesh, applied by a [`crate::morph::MorphSystem`]
+    pub morph_targets: MorphTargetSet,
 }
 
 impl Model {
+    /// Returns the bounds of every mesh in the model combined, or `None` if it has no meshes.
+    pub fn bounds(&self) -> Option<Aabb> {
+        self.meshes
+            .iter()
+            .map(|m| m.bounds)
+            .reduce(|a, b| a.union(&b))
+    }
+
+    /// Returns the number of vertices across every mesh in the model.
+    pub fn vertex_count(&self) -> u64 {
+        self.meshes.iter().map(|m| m.vertices.len() as u64).sum()
+    }
+
+    /// Returns the number of indices across every mesh in the model.
+    pub fn index_count(&self) -> u64 {
+        self.meshes.iter().map(|m| m.num_elements as u64).sum()
+    }
+
+    /// Changes the emissive factor of the material identified by `material_name`.
+    ///
+    /// Returns `false` if the model has no such material.
+    pub fn set_material_emissive_factor(
+        &mut self,
+        graphics: Arc<SharedGraphicsContext>,
+        material_name: &str,
+        emissive_factor: [f32; 3],
+    ) -> bool {
+        if let Some(material) = self
+            .materials
+            .iter_mut()
+            .find(|mat| mat.name == material_name)
+        {
+            material.set_emissive_factor(graphics, emissive_factor);
+            true
+        } else {
+            false
+        }
+    }
+
     /// Replaces the diffuse texture for the material identified by `material_name`.
     /// When `texture_tag` is provided it will be stored so the caller can later
     /// confirm which texture is applied.
@@ -287,6 +419,31 @@ impl Model {
         registry: &AssetRegistry,
         cache: &Mutex<HashMap<String, Arc<Model>>>,
     ) -> anyhow::Result<LoadedModel>
+    where
+        B: AsRef<[u8]>,
+    {
+        Self::load_from_memory_with_settings(
+            graphics,
+            buffer,
+            label,
+            registry,
+            cache,
+            TextureImportSettings::MODEL_DEFAULT,
+        )
+        .await
+    }
+
+    /// Loads a model, creating its material textures with the given [`TextureImportSettings`].
+    ///
+    /// The textures are compressed to BC7 if [`AssetRegistry::should_compress_textures`].
+    async fn load_from_memory_with_settings<B>(
+        graphics: Arc<SharedGraphicsContext>,
+        buffer: B,
+        label: Option<&str>,
+        registry: &AssetRegistry,
+        cache: &Mutex<HashMap<String, Arc<Model>>>,
+        texture_settings: TextureImportSettings,
+    ) -> anyhow::Result<LoadedModel>
     where
         B: AsRef<[u8]>,
     {
@@ -313,66 +470,104 @@ impl Model {
         let (gltf, buffers, _images) = gltf::import_slice(buffer.as_ref())?;
         let mut meshes = Vec::new();
 
+        let read_image = |texture: gltf::Texture| match texture.source().source() {
+            gltf::image::Source::View { view, mime_type: _ } => {
+                let buffer_data = &buffers[view.buffer().index()];
+                let start = view.offset();
+                let end = start + view.length();
+                Some(buffer_data[start..end].to_vec())
+            }
+            gltf::image::Source::Uri { uri, mime_type: _ } => {
+                log::warn!("External URI textures not supported: {}", uri);
+                None
+            }
+        };
+
         let mut texture_data = Vec::new();
         for material in gltf.materials() {
             log::debug!("Processing material: {:?}", material.name());
             let material_name = material.name().unwrap_or("Unnamed Material").to_string();
 
-            let image_data =
-                if let Some(pbr) = material.pbr_metallic_roughness().base_color_texture() {
-                    let texture_info = pbr.texture();
-                    let image = texture_info.source();
-                    match image.source() {
-                        gltf::image::Source::View { view, mime_type: _ } => {
-                            let buffer_data = &buffers[view.buffer().index()];
-                            let start = view.offset();
-                            let end = start + view.length();
-                            buffer_data[start..end].to_vec()
-                        }
-                        gltf::image::Source::Uri { uri, mime_type: _ } => {
-                            log::warn!("External URI textures not supported: {}", uri);
-                            GREY_TEXTURE_BYTES.to_vec()
-                        }
-                    }
-                } else {
-                    GREY_TEXTURE_BYTES.to_vec()
-                };
-
-            texture_data.push((material_name, image_data));
+            let image_data = material
+                .pbr_metallic_roughness()
+                .base_color_texture()
+                .and_then(|info| read_image(info.texture()))
+                .unwrap_or_else(|| GREY_TEXTURE_BYTES.to_vec());
+            let emissive_data = material
+                .emissive_texture()
+                .and_then(|info| read_image(info.texture()));
+
+            texture_data.push((
+                material_name,
+                image_data,
+                emissive_data,
+                material.pbr_metallic_roughness().base_color_factor(),
+                material.emissive_factor(),
+            ));
         }
 
         if texture_data.is_empty() {
-            texture_data.push(("Default".to_string(), GREY_TEXTURE_BYTES.to_vec()));
+            texture_data.push((
+                "Default".to_string(),
+                GREY_TEXTURE_BYTES.to_vec(),
+                None,
+                [1.0; 4],
+                [0.0; 3],
+            ));
         }
 
+        let compress = registry.should_compress_textures(&graphics);
         let parallel_start = Instant::now();
-        let processed_textures: Vec<_> = texture_data
+        let processed_textures = texture_data
             .into_par_iter()
-            .map(|(material_name, image_data)| {
-                let material_start = Instant::now();
-
-                let load_start = Instant::now();
-                let diffuse_image = image::load_from_memory(&image_data).unwrap();
-                log::trace!("Loading image to memory: {:?}", load_start.elapsed());
-
-                let rgba_start = Instant::now();
-                let diffuse_rgba = diffuse_image.to_rgba8();
-                log::trace!(
-                    "Converting diffuse image to rgba8 took {:?}",
-                    rgba_start.elapsed()
-                );
-
-                let dimensions = diffuse_image.dimensions();
-
-                log::trace!(
-                    "Parallel processing of material '{}' took: {:?}",
+            .map(
+                |(
                     material_name,
-                    material_start.elapsed()
-                );
-
-                (material_name, diffuse_rgba.into_raw(), dimensions)
-            })
-            .collect();
+                    image_data,
+                    emissive_data,
+                    base_colour_factor,
+                    emissive_factor,
+                )| {
+                    let material_start = Instant::now();
+
+                    let load_start = Instant::now();
+                    let diffuse = registry
+                        .prepare_texture(&image_data, compress)
+                        .with_context(|| {
+                            format!("Unable to decode texture of material '{}'", material_name)
+                        })?;
+                    log::trace!("Preparing diffuse texture took {:?}", load_start.elapsed());
+
+                    let emissive = emissive_data.and_then(|data| {
+                        match registry.prepare_texture(&data, compress) {
+                            Ok(emissive) => Some(emissive),
+                            Err(e) => {
+                                log::warn!(
+                                    "Unable to decode emissive texture of material '{}': {}",
+                                    material_name,
+                                    e
+                                );
+                                None
+                            }
+                        }
+                    });
+
+                    log::trace!(
+                        "Parallel processing of material '{}' took: {:?}",
+                        material_name,
+                        material_start.elapsed()
+                    );
+
+                    Ok::<_, anyhow::Error>((
+                        material_name,
+                        diffuse,
+                        emissive,
+                        base_colour_factor,
+                        emissive_factor,
+                    ))
+                },
+            )
+            .collect::<anyhow::Result<Vec<_>>>()?;
 
         log::trace!(
             "Total parallel image processing took: {:?}",
@@ -380,19 +575,32 @@ impl Model {
         );
 
         let mut materials = Vec::new();
-        for (material_name, rgba_data, dimensions) in processed_textures {
+        for (material_name, diffuse, emissive, base_colour_factor, emissive_factor) in
+            processed_textures
+        {
             let start = Instant::now();
 
-            let diffuse_texture =
-                Texture::from_rgba_buffer(graphics.clone(), &rgba_data, dimensions);
+            let diffuse_texture = diffuse.upload(graphics.clone(), &texture_settings);
             let bind_group = diffuse_texture.bind_group().to_owned();
             let texture_tag = Some(material_name.clone());
 
+            let emissive_texture =
+                emissive.map(|emissive| emissive.upload(graphics.clone(), &texture_settings));
+            let emissive_bind_group = Material::create_emissive_bind_group(
+                graphics.clone(),
+                emissive_texture.as_ref(),
+                emissive_factor,
+            );
+
             materials.push(Material {
                 name: material_name,
                 diffuse_texture,
                 bind_group,
                 texture_tag,
+                base_colour_factor,
+                emissive_texture,
+                emissive_factor,
+                emissive_bind_group,
             });
 
             log::trace!("Time to create GPU texture: {:?}", start.elapsed());
@@ -400,6 +608,8 @@ impl Model {
 
         for mesh in gltf.meshes() {
             log::debug!("Processing mesh: {:?}", mesh.name());
+            let target_names = morph_target_names(&mesh);
+            let default_weights = mesh.weights().unwrap_or_default();
             for primitive in mesh.primitives() {
                 let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
 
@@ -441,6 +651,21 @@ impl Model {
                     .collect();
                 indices.hash(&mut hasher);
 
+                let targets = reader
+                    .read_morph_targets()
+                    .enumerate()
+                    .map(|(i, (positions, _, _))| MorphTarget {
+                        name: target_names
+                            .get(i)
+                            .cloned()
+                            .unwrap_or_else(|| format!("target_{}", i)),
+                        weight: default_weights.get(i).copied().unwrap_or(0.0),
+                        delta_positions: positions
+                            .map(|iter| iter.map(|p| DVec3::from(Vec3::from(p))).collect())
+                            .unwrap_or_default(),
+                    })
+                    .collect();
+
                 let vertex_buffer =
                     graphics
                         .device
@@ -467,6 +692,10 @@ impl Model {
                     index_buffer,
                     num_elements: indices.len() as u32,
                     material: material_index,
+                    bounds: Aabb::from_points(&positions),
+                    vertices,
+                    indices,
+                    morph_targets: MorphTargetSet { targets },
                 });
             }
         }
@@ -527,14 +756,25 @@ impl Model {
             cache_guard.get(&path_str).cloned()
         } {
             log::debug!("Model loaded from cache: {:?}", path_str);
-            return Ok(LoadedModel::new_raw(registry, cached_model));
+            let loaded = LoadedModel::new_raw(registry, cached_model);
+            registry.track_model_path(loaded.asset_handle(), path);
+            return Ok(loaded);
         }
         log::debug!("Model does not exist in cache, loading memory...");
 
         log::debug!("Path of model: {}", path.display());
 
         let buffer = std::fs::read(path)?;
-        let loaded = Self::load_from_memory_raw(graphics, buffer, label, registry, cache).await?;
+        let texture_settings = registry.model_texture_settings(path);
+        let loaded = Self::load_from_memory_with_settings(
+            graphics,
+            buffer,
+            label,
+            registry,
+            cache,
+            texture_settings,
+        )
+        .await?;
 
         let mut model_clone: Model = (*loaded).clone();
         if let Ok(reference) = ResourceReference::from_path(path) {
@@ -554,10 +794,28 @@ impl Model {
         }
 
         log::debug!("Model cached and loaded: {:?}", file_name);
-        Ok(LoadedModel::new_raw(registry, updated))
+        let loaded = LoadedModel::new_raw(registry, updated);
+        registry.track_model_path(loaded.asset_handle(), path);
+        Ok(loaded)
     }
 }
 
+/// Reads the names of a mesh's morph targets from the `targetNames` array in its extras, which is
+/// where most exporters (including Blender) put them.
+fn morph_target_names(mesh: &gltf::Mesh) -> Vec<String> {
+    #[derive(Deserialize)]
+    struct MeshExtras {
+        #[serde(rename = "targetNames", default)]
+        target_names: Vec<String>,
+    }
+
+    mesh.extras()
+        .as_ref()
+        .and_then(|extras| serde_json::from_str::<MeshExtras>(extras.get()).ok())
+        .map(|extras| extras.target_names)
+        .unwrap_or_default()
+}
+
 pub trait DrawModel<'a> {
     #[allow(unused)]
     fn draw_mesh(
@@ -619,6 +877,7 @@ where
         self.set_bind_group(0, &material.bind_group, &[]);
         self.set_bind_group(1, camera_bind_group, &[]);
         self.set_bind_group(2, light_bind_group, &[]);
+        self.set_bind_group(3, &material.emissive_bind_group, &[]);
         self.draw_indexed(0..mesh.num_elements, 0, instances);
     }
 
@@ -0,0 +1,422 @@
+//! Morph targets (also known as blend shapes), used to deform a mesh with weighted per-vertex
+//! offsets, for example for facial expressions.
+//!
+//! The targets are loaded from the model, while the weights live on the entity in a
+//! [`MorphWeights`] component. Every frame, a compute shader blends the targets of each entity
+//! into its own scratch vertex buffer, which is drawn instead of the mesh's vertex buffer.
+
+use crate::entity::MeshRenderer;
+use crate::graphics::SharedGraphicsContext;
+use crate::model::{Material, Mesh, Model, ModelId, ModelVertex};
+use crate::particles::{storage_entry, uniform_entry};
+use crate::shader::Shader;
+use dropbear_macro::SerializableComponent;
+use dropbear_traits::SerializableComponent;
+use glam::DVec3;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::sync::Arc;
+use wgpu::util::DeviceExt;
+use wgpu::{BindGroup, BindGroupLayout, Buffer, ComputePipeline};
+
+const WORKGROUP_SIZE: u32 = 64;
+
+/// A single blend shape of a mesh.
+#[derive(Debug, Clone, Default)]
+pub struct MorphTarget {
+    pub name: String,
+    /// The default weight of the target, as set in the model file
+    pub weight: f32,
+    /// The offset of every vertex of the mesh when the target is fully applied
+    pub delta_positions: Vec<DVec3>,
+}
+
+/// Every morph target of a mesh.
+#[derive(Debug, Clone, Default)]
+pub struct MorphTargetSet {
+    pub targets: Vec<MorphTarget>,
+}
+
+impl MorphTargetSet {
+    pub fn is_empty(&self) -> bool {
+        self.targets.is_empty()
+    }
+
+    /// Fetches a target by its name.
+    pub fn get(&self, name: &str) -> Option<&MorphTarget> {
+        self.targets.iter().find(|t| t.name == name)
+    }
+}
+
+/// The weights of the morph targets of an entity's model, by target name.
+///
+/// Targets that are not in [`MorphWeights::weights`] use the default weight from the model. Meshes
+/// that share a target name are all driven by the same weight.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SerializableComponent)]
+pub struct MorphWeights {
+    pub weights: BTreeMap<String, f32>,
+}
+
+impl MorphWeights {
+    /// Sets the weight of a target, clamped between `0.0` and `1.0`.
+    pub fn set(&mut self, target: &str, weight: f32) {
+        self.weights
+            .insert(target.to_string(), weight.clamp(0.0, 1.0));
+    }
+
+    /// Adds every target of the model that does not have a weight yet, with its default weight.
+    pub fn sync(&mut self, model: &Model) {
+        for target in model.meshes.iter().flat_map(|m| &m.morph_targets.targets) {
+            self.weights
+                .entry(target.name.clone())
+                .or_insert(target.weight);
+        }
+    }
+
+    fn weight_of(&self, target: &MorphTarget) -> f32 {
+        self.weights
+            .get(&target.name)
+            .copied()
+            .unwrap_or(target.weight)
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct MorphParams {
+    vertex_count: u32,
+    target_count: u32,
+    _padding: [u32; 2],
+}
+
+/// GPU resources for blending the targets of one mesh.
+struct MeshMorphBuffers {
+    vertex_count: u32,
+    weights: Buffer,
+    output: Buffer,
+    bind_group: BindGroup,
+}
+
+/// GPU resources for every mesh of an entity's model. Meshes without targets are `None`.
+struct EntityMorphBuffers {
+    model: ModelId,
+    meshes: Vec<Option<MeshMorphBuffers>>,
+}
+
+/// Blends the morph targets of every entity with a [`MorphWeights`] component.
+pub struct MorphSystem {
+    pipeline: ComputePipeline,
+    layout: BindGroupLayout,
+    entities: HashMap<hecs::Entity, EntityMorphBuffers>,
+}
+
+impl MorphSystem {
+    pub fn new(graphics: Arc<SharedGraphicsContext>) -> Self {
+        let shader = Shader::new(
+            graphics.clone(),
+            crate::shader::shader_wesl::MORPH_SHADER,
+            Some("morph_shader"),
+        );
+
+        let layout = graphics
+            .device
+            .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Morph Bind Group Layout"),
+                entries: &[
+                    storage_entry(0, wgpu::ShaderStages::COMPUTE, true),
+                    storage_entry(1, wgpu::ShaderStages::COMPUTE, true),
+                    storage_entry(2, wgpu::ShaderStages::COMPUTE, true),
+                    uniform_entry(3, wgpu::ShaderStages::COMPUTE),
+                    storage_entry(4, wgpu::ShaderStages::COMPUTE, false),
+                ],
+            });
+
+        let pipeline_layout =
+            graphics
+                .device
+                .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: Some("Morph Pipeline Layout"),
+                    bind_group_layouts: &[&layout],
+                    push_constant_ranges: &[],
+                });
+
+        let pipeline = graphics
+            .device
+            .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some("Morph Compute Pipeline"),
+                layout: Some(&pipeline_layout),
+                module: &shader.module,
+                entry_point: Some("cs_main"),
+                compilation_options: Default::default(),
+                cache: None,
+            });
+
+        log::debug!("Created morph pipeline");
+
+        Self {
+            pipeline,
+            layout,
+            entities: HashMap::new(),
+        }
+    }
+
+    /// Uploads the weights of every entity with a [`MorphWeights`] component and blends their
+    /// targets.
+    pub fn update(&mut self, graphics: Arc<SharedGraphicsContext>, world: &mut hecs::World) {
+        let mut alive = Vec::new();
+        for (entity, (renderer, weights)) in world.query_mut::<(&MeshRenderer, &mut MorphWeights)>()
+        {
+            let model = renderer.model();
+            if model.meshes.iter().all(|m| m.morph_targets.is_empty()) {
+                continue;
+            }
+
+            weights.sync(&model);
+            alive.push(entity);
+
+            if self
+                .entities
+                .get(&entity)
+                .is_none_or(|b| b.model != model.id)
+            {
+                let buffers = self.create_buffers(&graphics, &model);
+                self.entities.insert(entity, buffers);
+            }
+
+            let Some(buffers) = self.entities.get(&entity) else {
+                continue;
+            };
+
+            for (mesh, mesh_buffers) in model.meshes.iter().zip(&buffers.meshes) {
+                let Some(mesh_buffers) = mesh_buffers else {
+                    continue;
+                };
+
+                let values: Vec<f32> = mesh
+                    .morph_targets
+                    .targets
+                    .iter()
+                    .map(|t| weights.weight_of(t))
+                    .collect();
+                graphics.queue.write_buffer(
+                    &mesh_buffers.weights,
+                    0,
+                    bytemuck::cast_slice(&values),
+                );
+            }
+        }
+
+        self.entities.retain(|e, _| alive.contains(e));
+
+        if self.entities.is_empty() {
+            return;
+        }
+
+        let mut encoder = graphics
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Morph Compute Encoder"),
+            });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Morph Compute Pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            for buffers in self.entities.values() {
+                for mesh_buffers in buffers.meshes.iter().flatten() {
+                    pass.set_bind_group(0, &mesh_buffers.bind_group, &[]);
+                    pass.dispatch_workgroups(
+                        mesh_buffers.vertex_count.div_ceil(WORKGROUP_SIZE),
+                        1,
+                        1,
+                    );
+                }
+            }
+        }
+        graphics.queue.submit(std::iter::once(encoder.finish()));
+    }
+
+    /// Checks if the entity is drawn with blended vertices, in which case it should be drawn with
+    /// [`MorphSystem::draw_model_instanced`] instead of being batched with other instances of its
+    /// model.
+    pub fn is_morphed(&self, entity: hecs::Entity) -> bool {
+        self.entities.contains_key(&entity)
+    }
+
+    /// Draws the model of an entity, using the blended vertices for every mesh that has morph
+    /// targets.
+    ///
+    /// This expects the same bind groups as [`crate::model::DrawModel::draw_model_instanced`].
+    pub fn draw_model_instanced(
+        &self,
+        render_pass: &mut wgpu::RenderPass<'_>,
+        entity: hecs::Entity,
+        model: &Model,
+        instances: Range<u32>,
+        camera_bind_group: &BindGroup,
+        light_bind_group: &BindGroup,
+    ) {
+        let buffers = self.entities.get(&entity).filter(|b| b.model == model.id);
+
+        for (i, mesh) in model.meshes.iter().enumerate() {
+            let vertex_buffer = buffers
+                .and_then(|b| b.meshes.get(i))
+                .and_then(|b| b.as_ref())
+                .map_or(&mesh.vertex_buffer, |b| &b.output);
+            let material = &model.materials[mesh.material];
+
+            draw_mesh(
+                render_pass,
+                mesh,
+                vertex_buffer,
+                material,
+                instances.clone(),
+                camera_bind_group,
+                light_bind_group,
+            );
+        }
+    }
+
+    /// Removes all GPU resources, for example when the scene is reloaded.
+    pub fn clear(&mut self) {
+        self.entities.clear();
+    }
+
+    fn create_buffers(
+        &self,
+        graphics: &SharedGraphicsContext,
+        model: &Model,
+    ) -> EntityMorphBuffers {
+        let meshes = model
+            .meshes
+            .iter()
+            .map(|mesh| {
+                (!mesh.morph_targets.is_empty()).then(|| self.create_mesh_buffers(graphics, mesh))
+            })
+            .collect();
+
+        log::debug!("Created morph buffers for model '{}'", model.label);
+
+        EntityMorphBuffers {
+            model: model.id,
+            meshes,
+        }
+    }
+
+    fn create_mesh_buffers(
+        &self,
+        graphics: &SharedGraphicsContext,
+        mesh: &Mesh,
+    ) -> MeshMorphBuffers {
+        let vertex_count = mesh.vertices.len() as u32;
+        let targets = &mesh.morph_targets.targets;
+
+        let base = graphics
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Morph Base Vertex Buffer"),
+                contents: bytemuck::cast_slice(&mesh.vertices),
+                usage: wgpu::BufferUsages::STORAGE,
+            });
+
+        let deltas: Vec<[f32; 4]> = targets
+            .iter()
+            .flat_map(|t| {
+                (0..mesh.vertices.len()).map(|i| {
+                    t.delta_positions
+                        .get(i)
+                        .map_or([0.0; 4], |d| d.as_vec3().extend(0.0).to_array())
+                })
+            })
+            .collect();
+        let deltas = graphics
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Morph Delta Buffer"),
+                contents: bytemuck::cast_slice(&deltas),
+                usage: wgpu::BufferUsages::STORAGE,
+            });
+
+        let weights = graphics.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Morph Weight Buffer"),
+            size: (targets.len() * size_of::<f32>()) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let params = graphics
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Morph Params Uniform"),
+                contents: bytemuck::cast_slice(&[MorphParams {
+                    vertex_count,
+                    target_count: targets.len() as u32,
+                    _padding: [0; 2],
+                }]),
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+
+        let output = graphics.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Morph Output Vertex Buffer"),
+            size: (mesh.vertices.len() * size_of::<ModelVertex>()) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = graphics
+            .device
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Morph Bind Group"),
+                layout: &self.layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: base.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: deltas.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: weights.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 3,
+                        resource: params.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 4,
+                        resource: output.as_entire_binding(),
+                    },
+                ],
+            });
+
+        MeshMorphBuffers {
+            vertex_count,
+            weights,
+            output,
+            bind_group,
+        }
+    }
+}
+
+fn draw_mesh(
+    render_pass: &mut wgpu::RenderPass<'_>,
+    mesh: &Mesh,
+    vertex_buffer: &Buffer,
+    material: &Material,
+    instances: Range<u32>,
+    camera_bind_group: &BindGroup,
+    light_bind_group: &BindGroup,
+) {
+    render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+    render_pass.set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+    render_pass.set_bind_group(0, &material.bind_group, &[]);
+    render_pass.set_bind_group(1, camera_bind_group, &[]);
+    render_pass.set_bind_group(2, light_bind_group, &[]);
+    render_pass.set_bind_group(3, &material.emissive_bind_group, &[]);
+    render_pass.draw_indexed(0..mesh.num_elements, 0, instances);
+}
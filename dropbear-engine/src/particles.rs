@@ -0,0 +1,635 @@
+//! GPU driven particles.
+//!
+//! Particles are spawned on the CPU by writing into a ring buffer, then a compute shader
+//! advances their position and age each frame. They are drawn as camera facing billboards.
+
+use crate::camera::Camera;
+use crate::entity::{EntityTransform, Transform};
+use crate::graphics::{SharedGraphicsContext, Texture};
+use crate::shader::Shader;
+use dropbear_macro::SerializableComponent;
+use dropbear_traits::SerializableComponent;
+use glam::{DVec3, Quat, Vec3, Vec4};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use wgpu::{BindGroup, BindGroupLayout, Buffer, ComputePipeline, RenderPipeline};
+
+/// Number of samples a [`Curve`] or [`ColorCurve`] is baked into before being sent to the GPU.
+pub const CURVE_SAMPLES: usize = 16;
+/// Upper bound for [`ParticleEmitter::max_particles`], to stop the buffer from growing forever.
+pub const MAX_PARTICLES_PER_EMITTER: u32 = 65_536;
+const WORKGROUP_SIZE: u32 = 64;
+
+/// A curve of keyframes over the normalised lifetime (`0.0..=1.0`) of a particle.
+///
+/// Keys are expected to be sorted by time, which [`Curve::sort`] does for you.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Curve {
+    pub keys: Vec<(f32, f32)>,
+}
+
+impl Default for Curve {
+    fn default() -> Self {
+        Self::constant(1.0)
+    }
+}
+
+impl Curve {
+    /// Creates a curve that is always `value`.
+    pub fn constant(value: f32) -> Self {
+        Self {
+            keys: vec![(0.0, value)],
+        }
+    }
+
+    /// Creates a curve that linearly goes from `start` to `end`.
+    pub fn linear(start: f32, end: f32) -> Self {
+        Self {
+            keys: vec![(0.0, start), (1.0, end)],
+        }
+    }
+
+    pub fn sort(&mut self) {
+        self.keys.sort_by(|a, b| a.0.total_cmp(&b.0));
+    }
+
+    /// Returns the value of the curve at `t`, linearly interpolating between keys.
+    pub fn evaluate(&self, t: f32) -> f32 {
+        evaluate_keys(&self.keys, t, 1.0, |a, b, f| a + (b - a) * f)
+    }
+
+    pub fn bake(&self) -> [f32; CURVE_SAMPLES] {
+        std::array::from_fn(|i| self.evaluate(i as f32 / (CURVE_SAMPLES - 1) as f32))
+    }
+}
+
+/// A curve of colour keyframes over the normalised lifetime of a particle.
+///
+/// Colours are linear RGBA.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColorCurve {
+    pub keys: Vec<(f32, Vec4)>,
+}
+
+impl Default for ColorCurve {
+    fn default() -> Self {
+        Self {
+            keys: vec![(0.0, Vec4::ONE), (1.0, Vec4::new(1.0, 1.0, 1.0, 0.0))],
+        }
+    }
+}
+
+impl ColorCurve {
+    /// Creates a curve that is always `colour`.
+    pub fn constant(colour: Vec4) -> Self {
+        Self {
+            keys: vec![(0.0, colour)],
+        }
+    }
+
+    pub fn sort(&mut self) {
+        self.keys.sort_by(|a, b| a.0.total_cmp(&b.0));
+    }
+
+    /// Returns the colour of the curve at `t`, linearly interpolating between keys.
+    pub fn evaluate(&self, t: f32) -> Vec4 {
+        evaluate_keys(&self.keys, t, Vec4::ONE, |a, b, f| a.lerp(b, f))
+    }
+
+    pub fn bake(&self) -> [[f32; 4]; CURVE_SAMPLES] {
+        std::array::from_fn(|i| {
+            self.evaluate(i as f32 / (CURVE_SAMPLES - 1) as f32)
+                .to_array()
+        })
+    }
+}
+
+fn evaluate_keys<T: Copy>(
+    keys: &[(f32, T)],
+    t: f32,
+    default: T,
+    lerp: impl Fn(T, T, f32) -> T,
+) -> T {
+    let (Some(first), Some(last)) = (keys.first(), keys.last()) else {
+        return default;
+    };
+
+    if t <= first.0 {
+        return first.1;
+    }
+    if t >= last.0 {
+        return last.1;
+    }
+
+    for pair in keys.windows(2) {
+        let (t0, v0) = pair[0];
+        let (t1, v1) = pair[1];
+        if t <= t1 {
+            let span = t1 - t0;
+            let f = if span > 0.0 { (t - t0) / span } else { 1.0 };
+            return lerp(v0, v1, f);
+        }
+    }
+
+    last.1
+}
+
+/// A component that continuously spawns particles from the entity's position.
+#[derive(Debug, Clone, Serialize, Deserialize, SerializableComponent)]
+pub struct ParticleEmitter {
+    /// Maximum number of particles alive at once. Older particles are recycled first.
+    pub max_particles: u32,
+    /// Particles spawned per second
+    pub emission_rate: f32,
+    /// How long each particle lives for, in seconds
+    pub lifetime: f32,
+    /// Velocity of a newly spawned particle, relative to the entity's rotation
+    pub initial_velocity: Vec3,
+    /// Half-angle of the cone (in degrees) that the initial velocity is randomised within
+    pub spread: f32,
+    pub size_over_life: Curve,
+    pub color_over_life: ColorCurve,
+
+    #[serde(skip)]
+    pending_burst: u32,
+    #[serde(skip)]
+    emission_accumulator: f32,
+}
+
+impl Default for ParticleEmitter {
+    fn default() -> Self {
+        Self {
+            max_particles: 1000,
+            emission_rate: 50.0,
+            lifetime: 2.0,
+            initial_velocity: Vec3::new(0.0, 2.0, 0.0),
+            spread: 15.0,
+            size_over_life: Curve::linear(0.25, 0.0),
+            color_over_life: ColorCurve::default(),
+            pending_burst: 0,
+            emission_accumulator: 0.0,
+        }
+    }
+}
+
+impl ParticleEmitter {
+    /// Queues `count` particles to be spawned on the next update, on top of the emission rate.
+    pub fn emit(&mut self, count: u32) {
+        self.pending_burst = self.pending_burst.saturating_add(count);
+    }
+
+    /// Returns the number of particles that should be spawned this frame.
+    fn take_spawn_count(&mut self, dt: f32) -> u32 {
+        self.emission_accumulator += self.emission_rate.max(0.0) * dt;
+        let continuous = self.emission_accumulator.floor();
+        self.emission_accumulator -= continuous;
+
+        let count = (continuous as u32).saturating_add(std::mem::take(&mut self.pending_burst));
+        count.min(self.capacity())
+    }
+
+    fn capacity(&self) -> u32 {
+        self.max_particles.clamp(1, MAX_PARTICLES_PER_EMITTER)
+    }
+
+    /// Radius of the sphere around the emitter that all of its particles stay within.
+    pub fn bounding_radius(&self) -> f32 {
+        let max_size = self
+            .size_over_life
+            .keys
+            .iter()
+            .map(|(_, s)| s.abs())
+            .fold(0.0, f32::max);
+        self.initial_velocity.length() * self.lifetime.max(0.0) + max_size
+    }
+}
+
+/// The state of a single particle, as stored on the GPU.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Particle {
+    /// xyz is the world position, w is the age in seconds
+    pub position: [f32; 4],
+    /// xyz is the velocity, w is the lifetime in seconds
+    pub velocity: [f32; 4],
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ParticleEmitterUniform {
+    pub camera_right: [f32; 4],
+    pub camera_up: [f32; 4],
+    pub size_curve: [[f32; 4]; CURVE_SAMPLES / 4],
+    pub colour_curve: [[f32; 4]; CURVE_SAMPLES],
+    pub delta_time: f32,
+    pub max_particles: u32,
+    pub _padding: [u32; 2],
+}
+
+/// GPU resources for a single [`ParticleEmitter`].
+struct EmitterBuffers {
+    capacity: u32,
+    cursor: u32,
+    particles: Buffer,
+    uniform: Buffer,
+    compute_bind_group: BindGroup,
+    render_bind_group: BindGroup,
+    /// Centre of the bounding sphere used for frustum culling
+    centre: DVec3,
+    radius: f64,
+}
+
+/// Simulates and renders every [`ParticleEmitter`] in a world.
+pub struct ParticleSystem {
+    compute_pipeline: ComputePipeline,
+    render_pipeline: RenderPipeline,
+    compute_layout: BindGroupLayout,
+    render_layout: BindGroupLayout,
+    emitters: HashMap<hecs::Entity, EmitterBuffers>,
+    rng_state: u64,
+}
+
+impl ParticleSystem {
+    pub fn new(graphics: Arc<SharedGraphicsContext>, camera_layout: &BindGroupLayout) -> Self {
+        let compute_shader = Shader::new(
+            graphics.clone(),
+            crate::shader::shader_wesl::PARTICLE_UPDATE_SHADER,
+            Some("particle_update_shader"),
+        );
+        let render_shader = Shader::new(
+            graphics.clone(),
+            crate::shader::shader_wesl::PARTICLE_SHADER,
+            Some("particle_shader"),
+        );
+
+        let compute_layout =
+            graphics
+                .device
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: Some("Particle Compute Bind Group Layout"),
+                    entries: &[
+                        storage_entry(0, wgpu::ShaderStages::COMPUTE, false),
+                        uniform_entry(1, wgpu::ShaderStages::COMPUTE),
+                    ],
+                });
+
+        let render_layout =
+            graphics
+                .device
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: Some("Particle Render Bind Group Layout"),
+                    entries: &[
+                        storage_entry(0, wgpu::ShaderStages::VERTEX, true),
+                        uniform_entry(1, wgpu::ShaderStages::VERTEX),
+                    ],
+                });
+
+        let compute_pipeline_layout =
+            graphics
+                .device
+                .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: Some("Particle Compute Pipeline Layout"),
+                    bind_group_layouts: &[&compute_layout],
+                    push_constant_ranges: &[],
+                });
+
+        let compute_pipeline =
+            graphics
+                .device
+                .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                    label: Some("Particle Compute Pipeline"),
+                    layout: Some(&compute_pipeline_layout),
+                    module: &compute_shader.module,
+                    entry_point: Some("cs_main"),
+                    compilation_options: Default::default(),
+                    cache: None,
+                });
+
+        let render_pipeline_layout =
+            graphics
+                .device
+                .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: Some("Particle Render Pipeline Layout"),
+                    bind_group_layouts: &[camera_layout, &render_layout],
+                    push_constant_ranges: &[],
+                });
+
+        let render_pipeline =
+            graphics
+                .device
+                .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: Some("Particle Render Pipeline"),
+                    layout: Some(&render_pipeline_layout),
+                    vertex: wgpu::VertexState {
+                        module: &render_shader.module,
+                        entry_point: Some("vs_main"),
+                        buffers: &[],
+                        compilation_options: Default::default(),
+                    },
+                    fragment: Some(wgpu::FragmentState {
+                        module: &render_shader.module,
+                        entry_point: Some("fs_main"),
+                        targets: &[Some(wgpu::ColorTargetState {
+                            format: wgpu::TextureFormat::Rgba8Unorm,
+                            blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                            write_mask: wgpu::ColorWrites::ALL,
+                        })],
+                        compilation_options: Default::default(),
+                    }),
+                    primitive: wgpu::PrimitiveState {
+                        topology: wgpu::PrimitiveTopology::TriangleList,
+                        strip_index_format: None,
+                        front_face: wgpu::FrontFace::Ccw,
+                        // billboards always face the camera
+                        cull_mode: None,
+                        polygon_mode: wgpu::PolygonMode::Fill,
+                        unclipped_depth: false,
+                        conservative: false,
+                    },
+                    // particles are transparent, so they are tested against but do not write depth
+                    depth_stencil: Some(wgpu::DepthStencilState {
+                        format: Texture::DEPTH_FORMAT,
+                        depth_write_enabled: false,
+                        depth_compare: wgpu::CompareFunction::Greater,
+                        stencil: wgpu::StencilState::default(),
+                        bias: wgpu::DepthBiasState::default(),
+                    }),
+                    multisample: wgpu::MultisampleState {
+                        count: 1,
+                        mask: !0,
+                        alpha_to_coverage_enabled: false,
+                    },
+                    multiview: None,
+                    cache: None,
+                });
+
+        log::debug!("Created particle pipelines");
+
+        Self {
+            compute_pipeline,
+            render_pipeline,
+            compute_layout,
+            render_layout,
+            emitters: HashMap::new(),
+            rng_state: 0x853c_49e6_748f_ea9b,
+        }
+    }
+
+    /// Spawns new particles for every emitter in the world, then runs the simulation for `dt`
+    /// seconds.
+    pub fn update(
+        &mut self,
+        graphics: Arc<SharedGraphicsContext>,
+        world: &mut hecs::World,
+        camera: &Camera,
+        dt: f32,
+    ) {
+        let camera_right = camera.view_mat.row(0).truncate().as_vec3();
+        let camera_up = camera.view_mat.row(1).truncate().as_vec3();
+
+        let mut alive = Vec::new();
+        for (entity, (emitter, t, et)) in world.query_mut::<(
+            &mut ParticleEmitter,
+            Option<&Transform>,
+            Option<&EntityTransform>,
+        )>() {
+            alive.push(entity);
+            let transform = et.map(|et| et.sync()).or(t.copied()).unwrap_or_default();
+            let capacity = emitter.capacity();
+
+            if self
+                .emitters
+                .get(&entity)
+                .is_none_or(|b| b.capacity != capacity)
+            {
+                let buffers = self.create_buffers(&graphics, capacity);
+                self.emitters.insert(entity, buffers);
+            }
+
+            let spawn_count = emitter.take_spawn_count(dt);
+            let spawned: Vec<Particle> = (0..spawn_count)
+                .map(|_| self.spawn_particle(emitter, &transform))
+                .collect();
+
+            let Some(buffers) = self.emitters.get_mut(&entity) else {
+                continue;
+            };
+
+            write_ring(&graphics, buffers, &spawned);
+
+            buffers.centre = transform.position;
+            buffers.radius = emitter.bounding_radius() as f64;
+
+            let mut size_curve = [[0.0; 4]; CURVE_SAMPLES / 4];
+            for (i, s) in emitter.size_over_life.bake().into_iter().enumerate() {
+                size_curve[i / 4][i % 4] = s;
+            }
+
+            let uniform = ParticleEmitterUniform {
+                camera_right: camera_right.extend(0.0).to_array(),
+                camera_up: camera_up.extend(0.0).to_array(),
+                size_curve,
+                colour_curve: emitter.color_over_life.bake(),
+                delta_time: dt,
+                max_particles: capacity,
+                _padding: [0; 2],
+            };
+            graphics
+                .queue
+                .write_buffer(&buffers.uniform, 0, bytemuck::cast_slice(&[uniform]));
+        }
+
+        self.emitters.retain(|e, _| alive.contains(e));
+
+        if self.emitters.is_empty() {
+            return;
+        }
+
+        let mut encoder = graphics
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Particle Compute Encoder"),
+            });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Particle Compute Pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.compute_pipeline);
+            for buffers in self.emitters.values() {
+                pass.set_bind_group(0, &buffers.compute_bind_group, &[]);
+                pass.dispatch_workgroups(buffers.capacity.div_ceil(WORKGROUP_SIZE), 1, 1);
+            }
+        }
+        graphics.queue.submit(std::iter::once(encoder.finish()));
+    }
+
+    /// Draws every emitter whose bounds are inside the camera's frustum.
+    ///
+    /// The camera bind group is expected to be the same one that was used to build the pipeline.
+    /// Returns the number of draw calls made.
+    pub fn render(&self, render_pass: &mut wgpu::RenderPass<'_>, camera: &Camera) -> u32 {
+        let frustum = camera.frustum();
+        let mut draw_calls = 0;
+
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, camera.bind_group(), &[]);
+        for buffers in self.emitters.values() {
+            if !frustum.intersects_sphere(buffers.centre, buffers.radius) {
+                continue;
+            }
+
+            render_pass.set_bind_group(1, &buffers.render_bind_group, &[]);
+            render_pass.draw(0..6, 0..buffers.capacity);
+            draw_calls += 1;
+        }
+        draw_calls
+    }
+
+    /// Removes all GPU resources, for example when the scene is reloaded.
+    pub fn clear(&mut self) {
+        self.emitters.clear();
+    }
+
+    fn create_buffers(&self, graphics: &SharedGraphicsContext, capacity: u32) -> EmitterBuffers {
+        // zeroed particles have a lifetime of 0, so they start off dead
+        let particles = graphics.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Particle Buffer"),
+            size: (capacity as usize * size_of::<Particle>()) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let uniform = graphics.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Particle Emitter Uniform"),
+            size: size_of::<ParticleEmitterUniform>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let entries = [
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: particles.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: uniform.as_entire_binding(),
+            },
+        ];
+
+        let compute_bind_group = graphics
+            .device
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Particle Compute Bind Group"),
+                layout: &self.compute_layout,
+                entries: &entries,
+            });
+        let render_bind_group = graphics
+            .device
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Particle Render Bind Group"),
+                layout: &self.render_layout,
+                entries: &entries,
+            });
+
+        log::debug!("Created particle buffers with a capacity of {}", capacity);
+
+        EmitterBuffers {
+            capacity,
+            cursor: 0,
+            particles,
+            uniform,
+            compute_bind_group,
+            render_bind_group,
+            centre: DVec3::ZERO,
+            radius: 0.0,
+        }
+    }
+
+    fn spawn_particle(&mut self, emitter: &ParticleEmitter, transform: &Transform) -> Particle {
+        let speed = emitter.initial_velocity.length();
+        let direction = emitter.initial_velocity.normalize_or(Vec3::Y);
+
+        // pick a random direction inside a cone around the initial velocity
+        let cos_spread = emitter.spread.clamp(0.0, 180.0).to_radians().cos();
+        let cos_theta = 1.0 + (cos_spread - 1.0) * self.next_f32();
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = std::f32::consts::TAU * self.next_f32();
+        let local = Vec3::new(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta);
+
+        let velocity = transform.rotation.as_quat()
+            * (Quat::from_rotation_arc(Vec3::Z, direction) * local)
+            * speed;
+
+        Particle {
+            position: transform.position.as_vec3().extend(0.0).to_array(),
+            velocity: velocity.extend(emitter.lifetime.max(0.0)).to_array(),
+        }
+    }
+
+    /// xorshift, which is plenty random enough for particles.
+    fn next_f32(&mut self) -> f32 {
+        let mut x = self.rng_state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.rng_state = x;
+        (x >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+/// Writes newly spawned particles into the ring buffer, overwriting the oldest ones.
+fn write_ring(
+    graphics: &SharedGraphicsContext,
+    buffers: &mut EmitterBuffers,
+    spawned: &[Particle],
+) {
+    let stride = size_of::<Particle>() as wgpu::BufferAddress;
+    let mut remaining = spawned;
+    while !remaining.is_empty() {
+        let space = (buffers.capacity - buffers.cursor) as usize;
+        let (chunk, rest) = remaining.split_at(space.min(remaining.len()));
+        graphics.queue.write_buffer(
+            &buffers.particles,
+            buffers.cursor as wgpu::BufferAddress * stride,
+            bytemuck::cast_slice(chunk),
+        );
+        buffers.cursor = (buffers.cursor + chunk.len() as u32) % buffers.capacity;
+        remaining = rest;
+    }
+}
+
+pub(crate) fn storage_entry(
+    binding: u32,
+    visibility: wgpu::ShaderStages,
+    read_only: bool,
+) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Storage { read_only },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    }
+}
+
+pub(crate) fn uniform_entry(
+    binding: u32,
+    visibility: wgpu::ShaderStages,
+) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    }
+}
@@ -0,0 +1,228 @@
+//! Ray-casting utilities used to pick entities with the mouse.
+
+use crate::camera::Camera;
+use crate::entity::MeshRenderer;
+use glam::{DMat4, DVec3, DVec4, Vec2, Vec3};
+
+/// An axis-aligned bounding box in the local space of a mesh.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Default for Aabb {
+    fn default() -> Self {
+        Self {
+            min: Vec3::ZERO,
+            max: Vec3::ZERO,
+        }
+    }
+}
+
+impl Aabb {
+    /// Creates the smallest box that contains all of the given points.
+    ///
+    /// Returns a zero-sized box at the origin if there are no points.
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a [f32; 3]>) -> Self {
+        let mut min = Vec3::splat(f32::MAX);
+        let mut max = Vec3::splat(f32::MIN);
+        let mut any = false;
+        for p in points {
+            let p = Vec3::from_array(*p);
+            min = min.min(p);
+            max = max.max(p);
+            any = true;
+        }
+
+        if any {
+            Self { min, max }
+        } else {
+            Self::default()
+        }
+    }
+
+    /// Returns the point in the middle of the box.
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Returns a box that contains both `self` and `other`.
+    pub fn union(&self, other: &Aabb) -> Self {
+        Self {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    /// Returns the eight corners of the box.
+    ///
+    /// Bit 0 of the index picks the x of `max` over `min`, bit 1 the y and bit 2 the z, so two
+    /// corners share an edge if their indices differ by a single bit.
+    pub fn corners(&self) -> [Vec3; 8] {
+        std::array::from_fn(|i| {
+            Vec3::new(
+                if i & 1 == 0 { self.min.x } else { self.max.x },
+                if i & 2 == 0 { self.min.y } else { self.max.y },
+                if i & 4 == 0 { self.min.z } else { self.max.z },
+            )
+        })
+    }
+
+    /// Returns the smallest axis-aligned box that contains this box once it has been transformed
+    /// by `matrix`.
+    pub fn transformed(&self, matrix: &DMat4) -> Self {
+        let corners: Vec<[f32; 3]> = self
+            .corners()
+            .iter()
+            .map(|corner| {
+                matrix
+                    .transform_point3(corner.as_dvec3())
+                    .as_vec3()
+                    .to_array()
+            })
+            .collect();
+        Self::from_points(&corners)
+    }
+}
+
+/// A ray in world space.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Ray {
+    pub origin: DVec3,
+    /// Normalised direction of the ray
+    pub direction: DVec3,
+}
+
+impl Ray {
+    pub fn new(origin: DVec3, direction: DVec3) -> Self {
+        Self {
+            origin,
+            direction: direction.normalize_or_zero(),
+        }
+    }
+
+    /// Returns the point along the ray at distance `t`.
+    pub fn at(&self, t: f64) -> DVec3 {
+        self.origin + self.direction * t
+    }
+
+    /// Tests the ray against an [`Aabb`] using the slab method, returning the distance to the
+    /// closest intersection in front of the origin.
+    pub fn intersect_aabb(&self, aabb: &Aabb) -> Option<f64> {
+        let min = aabb.min.as_dvec3();
+        let max = aabb.max.as_dvec3();
+        let inv = self.direction.recip();
+
+        let t1 = (min - self.origin) * inv;
+        let t2 = (max - self.origin) * inv;
+
+        let t_near = t1.min(t2).max_element();
+        let t_far = t1.max(t2).min_element();
+
+        if t_far < 0.0 || t_near > t_far || t_near.is_nan() {
+            return None;
+        }
+
+        Some(t_near.max(0.0))
+    }
+
+    /// Transforms the ray by a matrix. The direction is left unnormalised so distances along the
+    /// transformed ray can be compared against the original.
+    fn transformed(&self, matrix: &DMat4) -> Self {
+        Self {
+            origin: matrix.transform_point3(self.origin),
+            direction: matrix.transform_vector3(self.direction),
+        }
+    }
+}
+
+/// Creates a world space ray from a cursor position relative to the top left corner of the
+/// viewport.
+pub fn ray_from_screen(cursor: Vec2, camera: &Camera, viewport_size: (u32, u32)) -> Ray {
+    let width = viewport_size.0.max(1) as f64;
+    let height = viewport_size.1.max(1) as f64;
+
+    let ndc_x = 2.0 * cursor.x as f64 / width - 1.0;
+    let ndc_y = 1.0 - 2.0 * cursor.y as f64 / height;
+
+    let inverse = (camera.proj_mat * camera.view_mat).inverse();
+
+    // the projection uses reverse z, so the near plane is at a depth of 1
+    let unproject = |depth: f64| {
+        let p = inverse * DVec4::new(ndc_x, ndc_y, depth, 1.0);
+        p.truncate() / p.w
+    };
+
+    let near = unproject(1.0);
+    let far = unproject(0.5);
+
+    Ray::new(near, far - near)
+}
+
+/// Finds the closest entity with a [`MeshRenderer`] that the ray hits, using the bounding box of
+/// its model.
+pub fn intersect_world(ray: &Ray, world: &hecs::World) -> Option<hecs::Entity> {
+    raycast_world(ray, world).map(|(entity, _)| entity)
+}
+
+/// Same as [`intersect_world`], but also returns the distance along the ray to the hit, so the
+/// world space point can be found with [`Ray::at`].
+pub fn raycast_world(ray: &Ray, world: &hecs::World) -> Option<(hecs::Entity, f64)> {
+    raycast_world_filtered(ray, world, |_| true)
+}
+
+/// Same as [`raycast_world`], but only tests the entities that `filter` returns `true` for.
+pub fn raycast_world_filtered(
+    ray: &Ray,
+    world: &hecs::World,
+    mut filter: impl FnMut(hecs::Entity) -> bool,
+) -> Option<(hecs::Entity, f64)> {
+    let mut closest: Option<(hecs::Entity, f64)> = None;
+
+    for (entity, renderer) in world.query::<&MeshRenderer>().iter() {
+        if !filter(entity) {
+            continue;
+        }
+        let Some(bounds) = renderer.model().bounds() else {
+            continue;
+        };
+
+        let model_matrix = model_matrix(renderer);
+        if model_matrix.determinant() == 0.0 {
+            continue;
+        }
+
+        let local_ray = ray.transformed(&model_matrix.inverse());
+        if let Some(t) = local_ray.intersect_aabb(&bounds)
+            && closest.is_none_or(|(_, best)| t < best)
+        {
+            closest = Some((entity, t));
+        }
+    }
+
+    closest
+}
+
+/// Returns the bounding box of a model in world space, as of the last time its instance was
+/// updated.
+pub fn world_bounds(renderer: &MeshRenderer) -> Option<Aabb> {
+    let bounds = renderer.model().bounds()?;
+    Some(bounds.transformed(&model_matrix(renderer)))
+}
+
+fn model_matrix(renderer: &MeshRenderer) -> DMat4 {
+    let instance = &renderer.instance;
+    DMat4::from_scale_rotation_translation(instance.scale, instance.rotation, instance.position)
+}
+
+/// Intersects the ray with the horizontal plane at `y = height`, returning the distance along
+/// the ray if it hits in front of the origin.
+pub fn intersect_ground(ray: &Ray, height: f64) -> Option<f64> {
+    if ray.direction.y.abs() < f64::EPSILON {
+        return None;
+    }
+
+    let t = (height - ray.origin.y) / ray.direction.y;
+    (t >= 0.0).then_some(t)
+}
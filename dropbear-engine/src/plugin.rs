@@ -0,0 +1,80 @@
+//! Plugins, which let optional subsystems (such as audio, physics or networking) hook into the
+//! [`App`] without the engine having to know about them.
+//!
+//! A plugin is registered with [`App::add_plugin`], and its [`Plugin::build`] is called once
+//! [`App::run`] is called, before the event loop starts. In `build`, a plugin can insert
+//! [`Resources`] and add systems that run every frame.
+//!
+//! ```ignore
+//! struct PhysicsPlugin;
+//!
+//! impl Plugin for PhysicsPlugin {
+//!     fn build(&self, app: &mut App) {
+//!         app.insert_resource(PhysicsWorld::default())
+//!             .add_system(|resources, dt| {
+//!                 if let Some(world) = resources.get_mut::<PhysicsWorld>() {
+//!                     world.step(dt);
+//!                 }
+//!             });
+//!     }
+//! }
+//! ```
+
+use crate::App;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+/// An extension to the engine, registered with [`App::add_plugin`].
+pub trait Plugin: 'static {
+    /// Sets up the plugin, by inserting resources and adding systems to the app.
+    ///
+    /// This is called once, before the event loop starts.
+    fn build(&self, app: &mut App);
+
+    /// The name of the plugin, used for logging.
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+}
+
+/// A system added by a plugin, which is called every frame with the time the last frame took.
+pub type System = Box<dyn FnMut(&mut Resources, f32)>;
+
+/// Values shared between plugins and their systems, where there is at most one of each type.
+#[derive(Default)]
+pub struct Resources {
+    values: HashMap<TypeId, Box<dyn Any>>,
+}
+
+impl Resources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a resource, returning the one of the same type it replaced.
+    pub fn insert<T: 'static>(&mut self, value: T) -> Option<T> {
+        self.values
+            .insert(TypeId::of::<T>(), Box::new(value))
+            .and_then(|old| old.downcast().ok())
+            .map(|old| *old)
+    }
+
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.values.get(&TypeId::of::<T>())?.downcast_ref()
+    }
+
+    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.values.get_mut(&TypeId::of::<T>())?.downcast_mut()
+    }
+
+    pub fn remove<T: 'static>(&mut self) -> Option<T> {
+        self.values
+            .remove(&TypeId::of::<T>())
+            .and_then(|value| value.downcast().ok())
+            .map(|value| *value)
+    }
+
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<T>())
+    }
+}
@@ -7,6 +7,8 @@ use crate::asset::{ASSET_REGISTRY, AssetRegistry};
 use crate::entity::MeshRenderer;
 use crate::graphics::{SharedGraphicsContext, Texture};
 use crate::model::{LoadedModel, MODEL_CACHE, Material, Mesh, Model, ModelId, ModelVertex};
+use crate::morph::MorphTargetSet;
+use crate::picking::Aabb;
 use crate::utils::{ResourceReference, ResourceReferenceType};
 use parking_lot::Mutex;
 use std::collections::HashMap;
@@ -149,16 +151,26 @@ impl PlaneBuilder {
             index_buffer,
             num_elements: indices.len() as u32,
             material: 0,
+            bounds: Aabb::from_points(vertices.iter().map(|v| &v.position)),
+            vertices,
+            indices,
+            morph_targets: MorphTargetSet::default(),
         };
 
         let diffuse_texture =
             Texture::new_with_sampler(graphics.clone(), texture_bytes, AddressMode::Repeat);
         let bind_group = diffuse_texture.bind_group().clone();
+        let emissive_bind_group =
+            Material::create_emissive_bind_group(graphics.clone(), None, [0.0; 3]);
         let material = Material {
             name: "plane_material".to_string(),
             diffuse_texture,
             bind_group,
             texture_tag: Some("plane_material".to_string()),
+            base_colour_factor: [1.0; 4],
+            emissive_texture: None,
+            emissive_factor: [0.0; 3],
+            emissive_bind_group,
         };
 
         let model = Arc::new(Model {
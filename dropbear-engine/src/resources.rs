@@ -28,3 +28,165 @@
 
 //     Ok((path, txt))
 // }
+
+use crate::asset::{ASSET_REGISTRY, AssetHandle};
+use crate::graphics::SharedGraphicsContext;
+use dropbear_future_queue::FutureHandle;
+use glam::DVec3;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Loads textures close to the camera and unloads ones far away from it, so large worlds don't
+/// have to keep every texture in memory.
+///
+/// Textures are identified by path, and are given a handle with
+/// [`AssetRegistry::reserve_texture_handle`](crate::asset::AssetRegistry::reserve_texture_handle)
+/// before they are loaded. Unloading a texture frees the GPU texture but keeps its handle, so
+/// anything drawn with an unloaded texture has to fall back to a placeholder, such as the grey
+/// texture of [`crate::sprite::SpritePipeline`].
+pub struct TextureStreamer {
+    graphics: Arc<SharedGraphicsContext>,
+    /// Textures further than this from the camera are unloaded, and closer ones are loaded
+    pub stream_distance: f32,
+    /// The most textures that start loading in a single [`TextureStreamer::update`]
+    pub max_loads_per_update: usize,
+    /// Every texture seen by the last update, with its distance from the camera
+    distances: HashMap<PathBuf, f32>,
+    /// Textures that are being loaded on the future queue
+    loading: HashMap<PathBuf, FutureHandle>,
+    /// Textures that could not be loaded, which are not tried again
+    failed: HashSet<PathBuf>,
+}
+
+/// A texture waiting to be loaded, ordered so the closest texture is at the top of the queue.
+struct PendingTexture {
+    distance: f32,
+    path: PathBuf,
+}
+
+impl PartialEq for PendingTexture {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PendingTexture {}
+
+impl PartialOrd for PendingTexture {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PendingTexture {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.distance.total_cmp(&self.distance)
+    }
+}
+
+impl TextureStreamer {
+    /// The default [`TextureStreamer::stream_distance`].
+    pub const DEFAULT_STREAM_DISTANCE: f32 = 100.0;
+
+    pub fn new(graphics: Arc<SharedGraphicsContext>) -> Self {
+        Self {
+            graphics,
+            stream_distance: Self::DEFAULT_STREAM_DISTANCE,
+            max_loads_per_update: 4,
+            distances: HashMap::new(),
+            loading: HashMap::new(),
+            failed: HashSet::new(),
+        }
+    }
+
+    /// Loads and unloads textures depending on how far they are from `camera_position`.
+    ///
+    /// `textures` is every texture in the world along with where it is used. A texture used in
+    /// more than one place is streamed by whichever is closest.
+    pub fn update(
+        &mut self,
+        camera_position: DVec3,
+        textures: impl IntoIterator<Item = (PathBuf, DVec3)>,
+    ) {
+        self.distances.clear();
+        for (path, position) in textures {
+            let distance = position.distance(camera_position) as f32;
+            let closest = self.distances.entry(path).or_insert(f32::MAX);
+            *closest = closest.min(distance);
+        }
+
+        self.finish_loads();
+
+        let mut queue = BinaryHeap::new();
+        for (path, distance) in &self.distances {
+            let loaded = ASSET_REGISTRY.is_texture_loaded(path);
+            if *distance > self.stream_distance {
+                if loaded {
+                    ASSET_REGISTRY.evict_texture(path);
+                }
+            } else if !loaded && !self.loading.contains_key(path) && !self.failed.contains(path) {
+                queue.push(PendingTexture {
+                    distance: *distance,
+                    path: path.clone(),
+                });
+            }
+        }
+
+        while self.loading.len() < self.max_loads_per_update
+            && let Some(pending) = queue.pop()
+        {
+            let graphics = self.graphics.clone();
+            let path = pending.path.clone();
+            let handle = self.graphics.future_queue.push(async move {
+                ASSET_REGISTRY
+                    .import_sprite(graphics, &path)
+                    .map_err(|e| e.to_string())
+            });
+            self.loading.insert(pending.path, handle);
+        }
+    }
+
+    /// Collects the textures that have finished loading.
+    fn finish_loads(&mut self) {
+        let future_queue = self.graphics.future_queue.clone();
+        self.loading.retain(|path, handle| {
+            let Some(result) =
+                future_queue.exchange_owned_as::<Result<AssetHandle, String>>(handle)
+            else {
+                return true;
+            };
+
+            match result {
+                Ok(_) => log::trace!("Streamed in texture {}", path.display()),
+                Err(e) => {
+                    log::warn!("Unable to stream texture {}: {}", path.display(), e);
+                    self.failed.insert(path.clone());
+                }
+            }
+            false
+        });
+    }
+
+    /// The number of streamed textures that are loaded.
+    pub fn loaded_count(&self) -> usize {
+        self.distances
+            .keys()
+            .filter(|path| ASSET_REGISTRY.is_texture_loaded(path))
+            .count()
+    }
+
+    /// The number of textures seen by the last [`TextureStreamer::update`].
+    pub fn total_count(&self) -> usize {
+        self.distances.len()
+    }
+}
+
+impl Drop for TextureStreamer {
+    fn drop(&mut self) {
+        for handle in self.loading.values() {
+            self.graphics.future_queue.cancel(handle);
+        }
+    }
+}
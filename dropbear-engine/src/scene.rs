@@ -6,7 +6,7 @@ use winit::event_loop::ActiveEventLoop;
 
 use crate::input;
 use parking_lot::RwLock;
-use std::{collections::HashMap, rc::Rc};
+use std::{collections::HashMap, path::PathBuf, rc::Rc};
 
 pub trait Scene {
     fn load(&mut self, graphics: &mut crate::graphics::RenderContext);
@@ -20,7 +20,16 @@ pub trait Scene {
     fn run_command(&mut self) -> SceneCommand {
         SceneCommand::None
     }
+    /// Receives a [`SceneCommand`] sent to the scene by the app, such as
+    /// [`SceneCommand::ImportDroppedFile`].
+    fn handle_command(&mut self, _command: SceneCommand) {}
     fn clear_ui(&mut self) {}
+    /// Called instead of [`Scene::load`] when the scene is switched to on a headless server
+    /// started with [`App::run_server`](crate::App::run_server).
+    fn load_headless(&mut self) {}
+    /// Called instead of [`Scene::update`] on every tick of a headless server, which has no
+    /// graphics context. Scenes are never rendered on a server.
+    fn tick(&mut self, _dt: f32) {}
 }
 
 #[derive(Clone)]
@@ -29,6 +38,19 @@ pub enum SceneCommand {
     Quit,
     SwitchScene(String),
     DebugMessage(String),
+    /// Adds a scene to the [`Manager`] under the name, replacing any scene with the same name.
+    AddScene { scene: SceneImpl, name: String },
+    /// Removes a scene from the [`Manager`]. If it is the active scene, the manager switches
+    /// back to the previously active scene.
+    RemoveScene { name: String },
+    /// Sent to the active scene when a file is dropped onto the window.
+    ImportDroppedFile(PathBuf),
+    /// Loads the entities of a scene into the world of the active scene, without clearing what
+    /// is already there. Handled by the active scene.
+    LoadAdditively { name: String },
+    /// Removes the entities loaded from a scene from the world of the active scene. Handled by the
+    /// active scene.
+    UnloadScene(String),
 }
 
 impl Default for SceneCommand {
@@ -42,6 +64,7 @@ pub type SceneImpl = Rc<RwLock<dyn Scene>>;
 #[derive(Clone)]
 pub struct Manager {
     current_scene: Option<String>,
+    previous_scene: Option<String>,
     next_scene: Option<String>,
     scenes: HashMap<String, SceneImpl>,
     scene_input_map: HashMap<String, String>,
@@ -58,6 +81,7 @@ impl Manager {
         Self {
             scenes: HashMap::new(),
             current_scene: None,
+            previous_scene: None,
             next_scene: None,
             scene_input_map: HashMap::new(),
         }
@@ -76,6 +100,41 @@ impl Manager {
         self.scenes.insert(name.to_string(), scene);
     }
 
+    /// Removes a scene, returning it if it existed.
+    ///
+    /// If the scene is currently active, it is exited and the manager switches to the previously
+    /// active scene.
+    ///
+    /// # Panics
+    /// Panics if the active scene is removed and there is no previous scene to switch back to.
+    pub fn remove(&mut self, name: &str, event_loop: &ActiveEventLoop) -> Option<SceneImpl> {
+        let scene = self.scenes.remove(name)?;
+        self.scene_input_map.remove(name);
+
+        if self.previous_scene.as_deref() == Some(name) {
+            self.previous_scene = None;
+        }
+        if self.next_scene.as_deref() == Some(name) {
+            self.next_scene = None;
+        }
+
+        if self.current_scene.as_deref() == Some(name) {
+            scene.write().exit(event_loop);
+            self.current_scene = None;
+
+            let Some(previous) = self.previous_scene.take() else {
+                panic!(
+                    "Removed the active scene '{}' but there is no previous scene to switch to",
+                    name
+                );
+            };
+            self.switch(&previous);
+        }
+
+        log::debug!("Removed scene: {}", name);
+        Some(scene)
+    }
+
     pub fn attach_input(&mut self, scene_name: &str, input_name: &str) {
         self.scene_input_map
             .insert(scene_name.to_string(), input_name.to_string());
@@ -101,7 +160,7 @@ impl Manager {
                     scene.write().load(graphics);
                 }
             }
-            self.current_scene = Some(next_scene_name);
+            self.previous_scene = self.current_scene.replace(next_scene_name);
         }
 
         // update scene
@@ -136,10 +195,100 @@ impl Manager {
                 }
                 SceneCommand::None => {}
                 SceneCommand::DebugMessage(msg) => log::debug!("{}", msg),
+                SceneCommand::AddScene { scene, name } => {
+                    self.add(&name, scene);
+                    log::debug!("Added scene: {}", name);
+                }
+                SceneCommand::RemoveScene { name } => {
+                    if self.remove(&name, event_loop).is_none() {
+                        log::warn!("No such scene as {}, not removing", name);
+                    }
+                }
+                SceneCommand::ImportDroppedFile(path) => {
+                    log::warn!(
+                        "ImportDroppedFile({}) is only handled by scenes, ignoring",
+                        path.display()
+                    );
+                }
+                command @ (SceneCommand::LoadAdditively { .. } | SceneCommand::UnloadScene(_)) => {
+                    self.send_command(command);
+                }
             }
         }
     }
 
+    /// Ticks the active scene on a headless server through [`Scene::tick`], switching scenes
+    /// first if a switch is pending.
+    ///
+    /// There is no event loop on a server, so scenes that are switched away from are not exited,
+    /// and the active scene can't be removed. Returns `false` once the active scene sends
+    /// [`SceneCommand::Quit`].
+    pub fn tick(&mut self, dt: f32) -> bool {
+        if let Some(next_scene_name) = self.next_scene.take() {
+            if let Some(scene) = self.scenes.get_mut(&next_scene_name) {
+                scene.write().load_headless();
+            }
+            self.previous_scene = self.current_scene.replace(next_scene_name);
+        }
+
+        let Some(scene) = self
+            .current_scene
+            .as_ref()
+            .and_then(|name| self.scenes.get(name))
+            .cloned()
+        else {
+            return true;
+        };
+
+        scene.write().tick(dt);
+        let command = scene.write().run_command();
+        match command {
+            SceneCommand::None => {}
+            SceneCommand::Quit => {
+                log::info!("Stopping server!");
+                return false;
+            }
+            // switching to the active scene loads it again on the next tick
+            SceneCommand::SwitchScene(target) => self.switch(&target),
+            SceneCommand::DebugMessage(msg) => log::debug!("{}", msg),
+            SceneCommand::AddScene { scene, name } => {
+                self.add(&name, scene);
+                log::debug!("Added scene: {}", name);
+            }
+            SceneCommand::RemoveScene { name } => {
+                if self.current_scene.as_deref() == Some(name.as_str()) {
+                    log::warn!("Unable to remove the active scene {} on a server", name);
+                } else if self.scenes.remove(&name).is_some() {
+                    self.scene_input_map.remove(&name);
+                    log::debug!("Removed scene: {}", name);
+                } else {
+                    log::warn!("No such scene as {}, not removing", name);
+                }
+            }
+            SceneCommand::ImportDroppedFile(path) => {
+                log::warn!(
+                    "ImportDroppedFile({}) can't happen without a window, ignoring",
+                    path.display()
+                );
+            }
+            command @ (SceneCommand::LoadAdditively { .. } | SceneCommand::UnloadScene(_)) => {
+                self.send_command(command);
+            }
+        }
+        true
+    }
+
+    /// Sends a command to the active scene through [`Scene::handle_command`].
+    pub fn send_command(&mut self, command: SceneCommand) {
+        if let Some(scene_name) = &self.current_scene
+            && let Some(scene) = self.scenes.get_mut(scene_name)
+        {
+            scene.write().handle_command(command);
+        } else {
+            log::warn!("No active scene to send command to");
+        }
+    }
+
     pub fn render<'a>(&mut self, graphics: &mut crate::graphics::RenderContext<'a>) {
         if let Some(scene_name) = &self.current_scene
             && let Some(scene) = self.scenes.get_mut(scene_name)
@@ -1,5 +1,6 @@
 //! Deals with shaders, including WESL shaders
 use crate::graphics::SharedGraphicsContext;
+use futures::executor::block_on;
 use std::sync::Arc;
 use wgpu::ShaderModule;
 
@@ -35,4 +36,21 @@ impl Shader {
             module,
         }
     }
+
+    /// Same as [`Shader::new`], but returns the compilation error instead of panicking if the
+    /// shader is invalid, such as one written by a user.
+    pub fn try_new(
+        graphics: Arc<SharedGraphicsContext>,
+        shader_file_contents: &str,
+        label: Option<&str>,
+    ) -> anyhow::Result<Self> {
+        graphics
+            .device
+            .push_error_scope(wgpu::ErrorFilter::Validation);
+        let shader = Self::new(graphics.clone(), shader_file_contents, label);
+        match block_on(graphics.device.pop_error_scope()) {
+            Some(e) => Err(anyhow::anyhow!("{}", e)),
+            None => Ok(shader),
+        }
+    }
 }
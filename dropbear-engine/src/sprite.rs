@@ -0,0 +1,358 @@
+//! Textured quads for drawing 2D elements (such as icons, decals or foliage cards) inside of a
+//! 3D scene.
+//!
+//! Sprites are drawn after the opaque models with alpha blending, sorted from back to front.
+
+use crate::asset::{ASSET_REGISTRY, AssetHandle};
+use crate::atlas::AtlasHandle;
+use crate::camera::Camera;
+use crate::entity::{EntityTransform, Transform};
+use crate::font::TextRenderer;
+use crate::graphics::{SharedGraphicsContext, Texture};
+use crate::shader::Shader;
+use crate::utils::ResourceReference;
+use dropbear_macro::SerializableComponent;
+use dropbear_traits::SerializableComponent;
+use glam::{Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use wgpu::util::DeviceExt;
+use wgpu::{BindGroupLayout, Buffer, RenderPipeline};
+
+/// A component that draws a textured quad at the entity's position.
+#[derive(Debug, Clone, Serialize, Deserialize, SerializableComponent)]
+pub struct SpriteRenderer {
+    /// The image drawn on the quad. A white square is drawn if this is not set.
+    pub texture: ResourceReference,
+    /// The handle of [`SpriteRenderer::texture`] once it has been imported with
+    /// [`crate::asset::AssetRegistry::import_sprite`].
+    #[serde(skip)]
+    pub texture_handle: Option<AssetHandle>,
+    /// A sprite in a packed atlas, along with its label. This takes priority over
+    /// [`SpriteRenderer::texture_handle`], and lets sprites that share an atlas be drawn together.
+    #[serde(skip)]
+    pub atlas: Option<(AtlasHandle, String)>,
+    /// Linear RGBA tint that the texture is multiplied by
+    pub color: [f32; 4],
+    /// Width and height of the quad in world units, before the entity's scale is applied
+    pub size: Vec2,
+    /// If enabled, the quad always faces the camera. Otherwise it follows the entity's rotation,
+    /// facing along its local +Z axis.
+    pub billboard: bool,
+}
+
+impl Default for SpriteRenderer {
+    fn default() -> Self {
+        Self {
+            texture: ResourceReference::new(),
+            texture_handle: None,
+            atlas: None,
+            color: [1.0; 4],
+            size: Vec2::ONE,
+            billboard: true,
+        }
+    }
+}
+
+impl SpriteRenderer {
+    /// Creates a sprite that draws an already imported texture.
+    pub fn from_texture(handle: AssetHandle) -> Self {
+        Self {
+            texture_handle: Some(handle),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a sprite that draws the image with the given label from an atlas.
+    pub fn from_atlas(atlas: AtlasHandle, label: &str) -> Self {
+        if atlas.uv(label).is_none() {
+            log::warn!("Atlas does not contain a sprite labelled '{}'", label);
+        }
+
+        Self {
+            atlas: Some((atlas, label.to_string())),
+            ..Default::default()
+        }
+    }
+
+    /// The texture this sprite is drawn with, and the area of it that is used.
+    pub fn texture_region(&self) -> (Option<AssetHandle>, [f32; 4]) {
+        match &self.atlas {
+            Some((atlas, label)) => (Some(atlas.texture), atlas.uv(label).unwrap_or(FULL_UV)),
+            None => (self.texture_handle, FULL_UV),
+        }
+    }
+}
+
+/// Texture coordinates that cover a whole texture.
+const FULL_UV: [f32; 4] = [0.0, 0.0, 1.0, 1.0];
+
+/// A single sprite, as stored in the instance buffer.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct SpriteInstance {
+    pub position: [f32; 3],
+    /// Half of the quad's width along its right axis
+    pub right: [f32; 3],
+    /// Half of the quad's height along its up axis
+    pub up: [f32; 3],
+    pub color: [f32; 4],
+    /// The area of the texture that is drawn, as `[min_u, min_v, max_u, max_v]`
+    pub uv: [f32; 4],
+}
+
+impl SpriteInstance {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 5] = wgpu::vertex_attr_array![
+        0 => Float32x3,
+        1 => Float32x3,
+        2 => Float32x3,
+        3 => Float32x4,
+        4 => Float32x4,
+    ];
+
+    pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: size_of::<SpriteInstance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+/// Renders every [`SpriteRenderer`] in a world.
+pub struct SpritePipeline {
+    render_pipeline: RenderPipeline,
+    instance_buffer: Buffer,
+    capacity: usize,
+    /// Used for sprites without a texture
+    white: Texture,
+    /// Used for sprites whose texture is not loaded, such as one that has been streamed out
+    grey: Texture,
+    /// Sprites from the last update, in draw order
+    sprites: Vec<Option<AssetHandle>>,
+}
+
+impl SpritePipeline {
+    const INITIAL_CAPACITY: usize = 64;
+
+    pub fn new(graphics: Arc<SharedGraphicsContext>, camera_layout: &BindGroupLayout) -> Self {
+        let shader = Shader::new(
+            graphics.clone(),
+            crate::shader::shader_wesl::SPRITE_SHADER,
+            Some("sprite_shader"),
+        );
+
+        let render_pipeline_layout =
+            graphics
+                .device
+                .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: Some("Sprite Render Pipeline Layout"),
+                    bind_group_layouts: &[camera_layout, &graphics.texture_bind_layout],
+                    push_constant_ranges: &[],
+                });
+
+        let render_pipeline =
+            graphics
+                .device
+                .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: Some("Sprite Render Pipeline"),
+                    layout: Some(&render_pipeline_layout),
+                    vertex: wgpu::VertexState {
+                        module: &shader.module,
+                        entry_point: Some("vs_main"),
+                        buffers: &[SpriteInstance::desc()],
+                        compilation_options: Default::default(),
+                    },
+                    fragment: Some(wgpu::FragmentState {
+                        module: &shader.module,
+                        entry_point: Some("fs_main"),
+                        targets: &[Some(wgpu::ColorTargetState {
+                            format: wgpu::TextureFormat::Rgba8Unorm,
+                            blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                            write_mask: wgpu::ColorWrites::ALL,
+                        })],
+                        compilation_options: Default::default(),
+                    }),
+                    primitive: wgpu::PrimitiveState {
+                        topology: wgpu::PrimitiveTopology::TriangleList,
+                        strip_index_format: None,
+                        front_face: wgpu::FrontFace::Ccw,
+                        // sprites are visible from both sides
+                        cull_mode: None,
+                        polygon_mode: wgpu::PolygonMode::Fill,
+                        unclipped_depth: false,
+                        conservative: false,
+                    },
+                    // sprites are transparent, so they are tested against but do not write depth
+                    depth_stencil: Some(wgpu::DepthStencilState {
+                        format: Texture::DEPTH_FORMAT,
+                        depth_write_enabled: false,
+                        depth_compare: wgpu::CompareFunction::Greater,
+                        stencil: wgpu::StencilState::default(),
+                        bias: wgpu::DepthBiasState::default(),
+                    }),
+                    multisample: wgpu::MultisampleState {
+                        count: 1,
+                        mask: !0,
+                        alpha_to_coverage_enabled: false,
+                    },
+                    multiview: None,
+                    cache: None,
+                });
+
+        let instance_buffer = create_instance_buffer(&graphics, Self::INITIAL_CAPACITY);
+        let white = Texture::from_rgba_buffer(graphics.clone(), &[255; 4], (1, 1));
+        let grey = Texture::from_rgba_buffer(graphics.clone(), &[128, 128, 128, 255], (1, 1));
+
+        log::debug!("Created sprite pipeline");
+
+        Self {
+            render_pipeline,
+            instance_buffer,
+            capacity: Self::INITIAL_CAPACITY,
+            white,
+            grey,
+            sprites: Vec::new(),
+        }
+    }
+
+    /// Collects every sprite in the world, sorts them from back to front and uploads them to the
+    /// instance buffer.
+    pub fn update(
+        &mut self,
+        graphics: Arc<SharedGraphicsContext>,
+        world: &hecs::World,
+        camera: &Camera,
+    ) {
+        self.update_with_text(graphics, world, camera, &mut TextRenderer::default());
+    }
+
+    /// Does the same as [`SpritePipeline::update`], but also draws the text queued in `text`,
+    /// emptying it.
+    pub fn update_with_text(
+        &mut self,
+        graphics: Arc<SharedGraphicsContext>,
+        world: &hecs::World,
+        camera: &Camera,
+        text: &mut TextRenderer,
+    ) {
+        let camera_right = camera.view_mat.row(0).truncate().as_vec3();
+        let camera_up = camera.view_mat.row(1).truncate().as_vec3();
+        let frustum = camera.frustum();
+
+        let mut sprites = Vec::new();
+        for (_, (sprite, t, et)) in world
+            .query::<(
+                &SpriteRenderer,
+                Option<&Transform>,
+                Option<&EntityTransform>,
+            )>()
+            .iter()
+        {
+            let transform = et.map(|et| et.sync()).or(t.copied()).unwrap_or_default();
+            let half_size = sprite.size * transform.scale.truncate().as_vec2() * 0.5;
+
+            let (right, up) = if sprite.billboard {
+                (camera_right, camera_up)
+            } else {
+                let rotation = transform.rotation.as_quat();
+                (rotation * Vec3::X, rotation * Vec3::Y)
+            };
+
+            let radius = half_size.length() as f64;
+            if !frustum.intersects_sphere(transform.position, radius) {
+                continue;
+            }
+
+            let distance = transform.position.distance_squared(camera.eye);
+            let (texture, uv) = sprite.texture_region();
+            let instance = SpriteInstance {
+                position: transform.position.as_vec3().to_array(),
+                right: (right * half_size.x).to_array(),
+                up: (up * half_size.y).to_array(),
+                color: sprite.color,
+                uv,
+            };
+            sprites.push((distance, texture, instance));
+        }
+
+        let viewport = graphics.viewport_texture.size;
+        sprites.extend(text.take_sprites(
+            camera,
+            Vec2::new(viewport.width as f32, viewport.height as f32),
+        ));
+
+        // transparent geometry has to be drawn from back to front to blend correctly
+        sprites.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        if sprites.len() > self.capacity {
+            self.capacity = sprites.len().next_power_of_two();
+            self.instance_buffer = create_instance_buffer(&graphics, self.capacity);
+        }
+
+        let instances: Vec<SpriteInstance> = sprites.iter().map(|(_, _, i)| *i).collect();
+        if !instances.is_empty() {
+            graphics
+                .queue
+                .write_buffer(&self.instance_buffer, 0, bytemuck::cast_slice(&instances));
+        }
+
+        self.sprites = sprites.into_iter().map(|(_, handle, _)| handle).collect();
+    }
+
+    /// Draws the sprites collected by the last [`SpritePipeline::update`].
+    ///
+    /// The camera bind group is expected to be the same one that was used to build the pipeline.
+    /// Returns the number of draw calls made.
+    pub fn render(&self, render_pass: &mut wgpu::RenderPass<'_>, camera: &Camera) -> u32 {
+        if self.sprites.is_empty() {
+            return 0;
+        }
+
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, camera.bind_group(), &[]);
+        render_pass.set_vertex_buffer(0, self.instance_buffer.slice(..));
+
+        // neighbouring sprites with the same texture (such as ones from the same atlas) are drawn
+        // in a single call
+        let mut start = 0;
+        let mut draw_calls = 0;
+        while start < self.sprites.len() {
+            let handle = self.sprites[start];
+            let end = self.sprites[start..]
+                .iter()
+                .position(|h| *h != handle)
+                .map_or(self.sprites.len(), |offset| start + offset);
+
+            let texture = handle.and_then(|h| ASSET_REGISTRY.get_texture(h));
+            match &texture {
+                Some(texture) if texture.bind_group.is_some() => {
+                    render_pass.set_bind_group(1, texture.bind_group(), &[]);
+                }
+                // the texture has not been streamed in yet
+                _ if handle.is_some() => render_pass.set_bind_group(1, self.grey.bind_group(), &[]),
+                _ => render_pass.set_bind_group(1, self.white.bind_group(), &[]),
+            }
+
+            render_pass.draw(0..6, start as u32..end as u32);
+            draw_calls += 1;
+            start = end;
+        }
+        draw_calls
+    }
+
+    /// Removes all collected sprites, for example when the scene is reloaded.
+    pub fn clear(&mut self) {
+        self.sprites.clear();
+    }
+}
+
+fn create_instance_buffer(graphics: &SharedGraphicsContext, capacity: usize) -> Buffer {
+    graphics
+        .device
+        .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Sprite Instance Buffer"),
+            contents: bytemuck::cast_slice(&vec![SpriteInstance::default(); capacity]),
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        })
+}
@@ -0,0 +1,515 @@
+//! Screen-space ambient occlusion, which darkens creases and the ground where objects meet it.
+//!
+//! The occlusion is estimated from the depth buffer after the opaque models are drawn, and blurred
+//! to hide the noise from the random sample rotations. The models are then drawn again with
+//! [`Ssao::begin_ambient_pass`], which subtracts the occluded part of their ambient light, so
+//! direct light and emissive colours are left as they are.
+
+use crate::camera::{Camera, OPENGL_TO_WGPU_MATRIX};
+use crate::graphics::{FrameGraphicsContext, InstanceRaw, SharedGraphicsContext, Texture};
+use crate::model::{ModelVertex, Vertex};
+use crate::shader::Shader;
+use glam::DMat4;
+use parking_lot::Mutex;
+use std::sync::{Arc, LazyLock};
+use wgpu::util::DeviceExt;
+use wgpu::{BindGroup, BindGroupLayout, RenderPipeline, TextureView};
+
+/// The most samples the shader takes per pixel.
+pub const MAX_SSAO_SAMPLES: usize = 64;
+
+const AO_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R8Unorm;
+
+/// The settings SSAO is rendered with.
+///
+/// This starts off as [`WindowConfiguration::ssao`](crate::WindowConfiguration::ssao), and can be
+/// changed at any time with [`set_ssao_settings`].
+static SSAO_SETTINGS: LazyLock<Mutex<Option<SsaoSettings>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Changes the settings used from the next frame on, or turns SSAO off with `None`.
+pub fn set_ssao_settings(settings: Option<SsaoSettings>) {
+    *SSAO_SETTINGS.lock() = settings;
+}
+
+/// The settings SSAO is currently rendered with, see [`set_ssao_settings`].
+pub fn ssao_settings() -> Option<SsaoSettings> {
+    *SSAO_SETTINGS.lock()
+}
+
+/// Tuning values for screen-space ambient occlusion.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SsaoSettings {
+    /// How far around each point is checked for occluders, in world units
+    pub radius: f32,
+    /// Depth difference that is ignored, which stops flat surfaces from occluding themselves
+    pub bias: f32,
+    /// Samples taken per pixel, up to [`MAX_SSAO_SAMPLES`]
+    pub samples: u32,
+}
+
+impl Default for SsaoSettings {
+    fn default() -> Self {
+        Self {
+            radius: 0.5,
+            bias: 0.025,
+            samples: 16,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct SsaoUniform {
+    proj: [[f32; 4]; 4],
+    inv_proj: [[f32; 4]; 4],
+    kernel: [[f32; 4]; MAX_SSAO_SAMPLES],
+    radius: f32,
+    bias: f32,
+    samples: u32,
+    _padding: u32,
+}
+
+/// Points in a hemisphere facing +Z, packed closer to the centre so nearby occluders count more.
+///
+/// These come from a Hammersley sequence rather than a random generator, so every run looks
+/// the same.
+fn sample_kernel() -> [[f32; 4]; MAX_SSAO_SAMPLES] {
+    let mut kernel = [[0.0; 4]; MAX_SSAO_SAMPLES];
+    for (i, sample) in kernel.iter_mut().enumerate() {
+        let u = (i as f32 + 0.5) / MAX_SSAO_SAMPLES as f32;
+        let v = (i as u32).reverse_bits() as f32 / u32::MAX as f32;
+
+        let phi = std::f32::consts::TAU * v;
+        let cos_theta = 1.0 - u;
+        let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+
+        let t = i as f32 / MAX_SSAO_SAMPLES as f32;
+        let scale = 0.1 + 0.9 * t * t;
+
+        *sample = [
+            phi.cos() * sin_theta * scale,
+            phi.sin() * sin_theta * scale,
+            cos_theta * scale,
+            0.0,
+        ];
+    }
+    kernel
+}
+
+/// The textures SSAO renders into, which are recreated when the viewport is resized.
+struct SsaoTargets {
+    size: wgpu::Extent3d,
+    ao_view: TextureView,
+    blur_bind_group: BindGroup,
+    vertical_blur_bind_group: BindGroup,
+    ambient_bind_group: BindGroup,
+    blur_view: TextureView,
+}
+
+/// Renders screen-space ambient occlusion on top of a frame.
+pub struct Ssao {
+    ssao_pipeline: RenderPipeline,
+    blur_horizontal_pipeline: RenderPipeline,
+    blur_vertical_pipeline: RenderPipeline,
+    ambient_pipeline: RenderPipeline,
+    ssao_layout: BindGroupLayout,
+    blur_layout: BindGroupLayout,
+    ambient_layout: BindGroupLayout,
+    kernel: [[f32; 4]; MAX_SSAO_SAMPLES],
+    targets: Option<SsaoTargets>,
+    /// Whether the occlusion was rendered for the current frame
+    is_rendered: bool,
+}
+
+impl Ssao {
+    /// Creates the SSAO pipelines.
+    ///
+    /// `model_layouts` are the bind group layouts of the pipeline the models are drawn with, as
+    /// the ambient pass draws them again with the same shader.
+    pub fn new(graphics: Arc<SharedGraphicsContext>, model_layouts: &[&BindGroupLayout]) -> Self {
+        let shader = Shader::new(
+            graphics.clone(),
+            crate::shader::shader_wesl::SSAO_SHADER,
+            Some("ssao_shader"),
+        );
+
+        let ssao_layout =
+            graphics
+                .device
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: Some("ssao_bind_group_layout"),
+                    entries: &[
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 0,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Texture {
+                                sample_type: wgpu::TextureSampleType::Depth,
+                                view_dimension: wgpu::TextureViewDimension::D2,
+                                multisampled: false,
+                            },
+                            count: None,
+                        },
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 1,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Buffer {
+                                ty: wgpu::BufferBindingType::Uniform,
+                                has_dynamic_offset: false,
+                                min_binding_size: None,
+                            },
+                            count: None,
+                        },
+                    ],
+                });
+        let blur_layout = ao_texture_layout(&graphics, 2, "ssao_blur_bind_group_layout");
+        let ambient_layout = ao_texture_layout(&graphics, 0, "ssao_ambient_bind_group_layout");
+
+        let ao_target = wgpu::ColorTargetState {
+            format: AO_FORMAT,
+            blend: None,
+            write_mask: wgpu::ColorWrites::ALL,
+        };
+
+        let ssao_pipeline = fullscreen_pipeline(
+            &graphics,
+            &shader,
+            &ssao_layout,
+            "fs_ssao",
+            ao_target.clone(),
+            "SSAO Pipeline",
+        );
+        let blur_horizontal_pipeline = fullscreen_pipeline(
+            &graphics,
+            &shader,
+            &blur_layout,
+            "fs_blur_horizontal",
+            ao_target.clone(),
+            "SSAO Horizontal Blur Pipeline",
+        );
+        let blur_vertical_pipeline = fullscreen_pipeline(
+            &graphics,
+            &shader,
+            &blur_layout,
+            "fs_blur_vertical",
+            ao_target,
+            "SSAO Vertical Blur Pipeline",
+        );
+        let ambient_pipeline = ambient_pipeline(graphics.clone(), model_layouts, &ambient_layout);
+
+        log::debug!("Created SSAO pipelines");
+
+        Self {
+            ssao_pipeline,
+            blur_horizontal_pipeline,
+            blur_vertical_pipeline,
+            ambient_pipeline,
+            ssao_layout,
+            blur_layout,
+            ambient_layout,
+            kernel: sample_kernel(),
+            targets: None,
+            is_rendered: false,
+        }
+    }
+
+    /// Estimates the occlusion from the frame's depth buffer and blurs it, ready for
+    /// [`Ssao::begin_ambient_pass`].
+    ///
+    /// This should be called after the opaque models are drawn, and before anything transparent.
+    /// Nothing is drawn if SSAO is turned off with [`set_ssao_settings`]. Returns the number of
+    /// draw calls made.
+    pub fn render(
+        &mut self,
+        graphics: &SharedGraphicsContext,
+        frame: &mut FrameGraphicsContext<'_>,
+        camera: &Camera,
+    ) -> u32 {
+        self.is_rendered = false;
+        let Some(settings) = ssao_settings() else {
+            return 0;
+        };
+
+        let size = frame.depth_texture.size;
+        if self.targets.as_ref().is_none_or(|t| t.size != size) {
+            self.targets = Some(self.create_targets(graphics, size));
+        }
+        let Some(targets) = &self.targets else {
+            return 0;
+        };
+
+        let proj = DMat4::from_cols_array_2d(&OPENGL_TO_WGPU_MATRIX) * camera.proj_mat;
+        let uniform = SsaoUniform {
+            proj: proj.as_mat4().to_cols_array_2d(),
+            inv_proj: proj.inverse().as_mat4().to_cols_array_2d(),
+            kernel: self.kernel,
+            radius: settings.radius,
+            bias: settings.bias,
+            samples: settings.samples.min(MAX_SSAO_SAMPLES as u32),
+            _padding: 0,
+        };
+
+        // a buffer per call, so each viewport rendered this frame keeps its own projection
+        let uniform_buffer =
+            graphics
+                .device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("SSAO Uniform"),
+                    contents: bytemuck::cast_slice(&[uniform]),
+                    usage: wgpu::BufferUsages::UNIFORM,
+                });
+
+        // the depth texture is recreated on resize, so its bind group can't be kept around
+        let ssao_bind_group = graphics
+            .device
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("ssao_bind_group"),
+                layout: &self.ssao_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&frame.depth_texture.view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: uniform_buffer.as_entire_binding(),
+                    },
+                ],
+            });
+
+        let passes = [
+            (&self.ssao_pipeline, &ssao_bind_group, &targets.ao_view),
+            (
+                &self.blur_horizontal_pipeline,
+                &targets.blur_bind_group,
+                &targets.blur_view,
+            ),
+            (
+                &self.blur_vertical_pipeline,
+                &targets.vertical_blur_bind_group,
+                &targets.ao_view,
+            ),
+        ];
+
+        for (pipeline, bind_group, view) in passes {
+            let mut render_pass = frame
+                .encoder
+                .begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("SSAO Render Pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view,
+                        depth_slice: None,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Load,
+                            store: wgpu::StoreOp::Store,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                    occlusion_query_set: None,
+                    timestamp_writes: None,
+                });
+            render_pass.set_pipeline(pipeline);
+            render_pass.set_bind_group(0, bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+
+        self.is_rendered = true;
+        passes.len() as u32
+    }
+
+    /// Sets up `render_pass` to take the occluded ambient light out of the models drawn this
+    /// frame, returning `false` if there is no occlusion to apply.
+    ///
+    /// Call this after [`Ssao::render`], then draw the models that were drawn with the default
+    /// shader again with the same instances. Models with a custom shader have their own idea of
+    /// ambient light, so they are left out.
+    pub fn begin_ambient_pass(&self, render_pass: &mut wgpu::RenderPass<'_>) -> bool {
+        let Some(targets) = self.targets.as_ref().filter(|_| self.is_rendered) else {
+            return false;
+        };
+        render_pass.set_pipeline(&self.ambient_pipeline);
+        render_pass.set_bind_group(4, &targets.ambient_bind_group, &[]);
+        true
+    }
+
+    fn create_targets(
+        &self,
+        graphics: &SharedGraphicsContext,
+        size: wgpu::Extent3d,
+    ) -> SsaoTargets {
+        let create_view = |label| {
+            graphics
+                .device
+                .create_texture(&wgpu::TextureDescriptor {
+                    label: Some(label),
+                    size,
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: AO_FORMAT,
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                        | wgpu::TextureUsages::TEXTURE_BINDING,
+                    view_formats: &[],
+                })
+                .create_view(&wgpu::TextureViewDescriptor::default())
+        };
+        let ao_view = create_view("ssao_ao_texture");
+        let blur_view = create_view("ssao_blur_texture");
+
+        let create_bind_group = |layout: &BindGroupLayout, binding, view: &TextureView| {
+            graphics
+                .device
+                .create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("ssao_texture_bind_group"),
+                    layout,
+                    entries: &[wgpu::BindGroupEntry {
+                        binding,
+                        resource: wgpu::BindingResource::TextureView(view),
+                    }],
+                })
+        };
+
+        SsaoTargets {
+            size,
+            blur_bind_group: create_bind_group(&self.blur_layout, 2, &ao_view),
+            vertical_blur_bind_group: create_bind_group(&self.blur_layout, 2, &blur_view),
+            ambient_bind_group: create_bind_group(&self.ambient_layout, 0, &ao_view),
+            ao_view,
+            blur_view,
+        }
+    }
+}
+
+fn ao_texture_layout(
+    graphics: &SharedGraphicsContext,
+    binding: u32,
+    label: &str,
+) -> BindGroupLayout {
+    graphics
+        .device
+        .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some(label),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            }],
+        })
+}
+
+/// The main shader's vertex stage with `fs_ambient_occlusion`, which subtracts the occluded
+/// ambient light from what was drawn at the same depth.
+fn ambient_pipeline(
+    graphics: Arc<SharedGraphicsContext>,
+    model_layouts: &[&BindGroupLayout],
+    ambient_layout: &BindGroupLayout,
+) -> RenderPipeline {
+    let shader = Shader::new(
+        graphics.clone(),
+        crate::shader::shader_wesl::SHADER_SHADER,
+        Some("ssao_ambient_shader"),
+    );
+
+    let mut bind_group_layouts = model_layouts.to_vec();
+    bind_group_layouts.push(ambient_layout);
+    let pipeline_layout = graphics
+        .device
+        .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("SSAO Ambient Pipeline"),
+            bind_group_layouts: &bind_group_layouts,
+            push_constant_ranges: &[],
+        });
+
+    graphics
+        .device
+        .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("SSAO Ambient Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader.module,
+                entry_point: Some("vs_main"),
+                buffers: &[ModelVertex::desc(), InstanceRaw::desc()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader.module,
+                entry_point: Some("fs_ambient_occlusion"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: wgpu::TextureFormat::Rgba8Unorm,
+                    // the frame minus the occluded ambient light, leaving its alpha alone
+                    blend: Some(wgpu::BlendState {
+                        color: wgpu::BlendComponent {
+                            src_factor: wgpu::BlendFactor::One,
+                            dst_factor: wgpu::BlendFactor::One,
+                            operation: wgpu::BlendOperation::ReverseSubtract,
+                        },
+                        alpha: wgpu::BlendComponent {
+                            src_factor: wgpu::BlendFactor::Zero,
+                            dst_factor: wgpu::BlendFactor::One,
+                            operation: wgpu::BlendOperation::Add,
+                        },
+                    }),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            // only the surfaces that ended up in front are darkened
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: Texture::DEPTH_FORMAT,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Equal,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        })
+}
+
+fn fullscreen_pipeline(
+    graphics: &SharedGraphicsContext,
+    shader: &Shader,
+    layout: &BindGroupLayout,
+    fragment_entry: &str,
+    target: wgpu::ColorTargetState,
+    label: &str,
+) -> RenderPipeline {
+    let pipeline_layout = graphics
+        .device
+        .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some(label),
+            bind_group_layouts: &[layout],
+            push_constant_ranges: &[],
+        });
+
+    graphics
+        .device
+        .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(label),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader.module,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader.module,
+                entry_point: Some(fragment_entry),
+                targets: &[Some(target)],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        })
+}
@@ -0,0 +1,111 @@
+//! Counters for what was drawn in a frame, and estimates of how much GPU memory the loaded
+//! assets take up.
+
+use crate::asset::ASSET_REGISTRY;
+use crate::lighting::{LightManager, LightStats};
+use crate::model::Model;
+use crate::resources::TextureStreamer;
+
+/// Rendering statistics of a single frame.
+///
+/// Draw calls and geometry are counted while rendering through [`SceneStats::record_model`], while
+/// the memory usage is estimated from everything loaded in the [`ASSET_REGISTRY`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SceneStats {
+    pub entity_count: u32,
+    pub draw_call_count: u32,
+    pub total_vertex_count: u64,
+    pub total_index_count: u64,
+    pub texture_memory_bytes: u64,
+    pub gpu_buffer_memory_bytes: u64,
+    /// The number of streamed textures that are loaded, see [`TextureStreamer`]
+    pub streamed_texture_count: u32,
+    /// The number of textures handled by the [`TextureStreamer`]
+    pub total_streamed_texture_count: u32,
+    /// The lights sent to the shader, see [`LightManager`]
+    pub lights: LightStats,
+}
+
+impl SceneStats {
+    /// Starts counting a new frame.
+    pub fn begin_frame(&mut self, entity_count: u32) {
+        *self = Self {
+            entity_count,
+            ..Default::default()
+        };
+    }
+
+    /// Counts a model drawn with `instances` instances, which issues one draw call per mesh.
+    pub fn record_model(&mut self, model: &Model, instances: u32) {
+        self.draw_call_count += model.meshes.len() as u32;
+        self.total_vertex_count += model.vertex_count() * instances as u64;
+        self.total_index_count += model.index_count() * instances as u64;
+    }
+
+    /// Counts draw calls that don't draw a model, such as particles or sprites.
+    pub fn record_draw_calls(&mut self, count: u32) {
+        self.draw_call_count += count;
+    }
+
+    /// Counts how many of the textures handled by a [`TextureStreamer`] are loaded.
+    pub fn record_streaming(&mut self, streamer: &TextureStreamer) {
+        self.streamed_texture_count = streamer.loaded_count() as u32;
+        self.total_streamed_texture_count = streamer.total_count() as u32;
+    }
+
+    /// Copies the light counts of the last [`LightManager::update`].
+    pub fn record_lights(&mut self, light_manager: &LightManager) {
+        self.lights = light_manager.stats();
+    }
+
+    /// Estimates the memory used by the textures and mesh buffers in the [`ASSET_REGISTRY`].
+    pub fn measure_memory(&mut self) {
+        self.texture_memory_bytes = ASSET_REGISTRY
+            .iter_texture()
+            .map(|entry| texture_memory(&entry.value().texture))
+            .sum();
+        self.gpu_buffer_memory_bytes = ASSET_REGISTRY
+            .iter_model()
+            .map(|entry| {
+                entry
+                    .value()
+                    .meshes
+                    .iter()
+                    .map(|mesh| mesh.vertex_buffer.size() + mesh.index_buffer.size())
+                    .sum::<u64>()
+            })
+            .sum();
+    }
+}
+
+/// Estimates the size of a texture from its dimensions, format and mip levels.
+pub fn texture_memory(texture: &wgpu::Texture) -> u64 {
+    let format = texture.format();
+    let block_size = format.block_copy_size(None).unwrap_or(4) as u64;
+    let (block_width, block_height) = format.block_dimensions();
+    let size = texture.size();
+
+    (0..texture.mip_level_count())
+        .map(|level| {
+            let width = (size.width >> level).max(1).div_ceil(block_width) as u64;
+            let height = (size.height >> level).max(1).div_ceil(block_height) as u64;
+            width * height * size.depth_or_array_layers as u64 * block_size
+        })
+        .sum()
+}
+
+/// Formats a byte count in the largest fitting unit, such as `12.5 MiB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
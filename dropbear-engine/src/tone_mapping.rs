@@ -0,0 +1,338 @@
+//! Tone mapping, which squeezes colours that are too bright to display back into range with a
+//! curve instead of clipping them.
+//!
+//! The curve is applied to the rendered frame into a scratch texture, which is then copied back
+//! over the frame.
+//!
+//! The frame is still rendered to an 8-bit target, so colours are already clamped to 1.0 before
+//! they are tone mapped. The curves only darken the frame until it is rendered in a float format,
+//! which is why tone mapping is off by default.
+
+use crate::graphics::{FrameGraphicsContext, SharedGraphicsContext};
+use crate::shader::Shader;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::sync::{Arc, LazyLock};
+use wgpu::util::DeviceExt;
+use wgpu::{BindGroupLayout, RenderPipeline, TextureView};
+
+const SCRATCH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+/// The curve frames are tone mapped with.
+///
+/// This starts off as [`WindowConfiguration::tone_mapping`](crate::WindowConfiguration::tone_mapping),
+/// and can be changed at any time with [`set_tone_mapping`].
+static TONE_MAPPING: LazyLock<Mutex<ToneMappingOperator>> =
+    LazyLock::new(|| Mutex::new(ToneMappingOperator::default()));
+
+/// Changes the curve used from the next frame on.
+pub fn set_tone_mapping(operator: ToneMappingOperator) {
+    *TONE_MAPPING.lock() = operator;
+}
+
+/// The curve frames are currently tone mapped with, see [`set_tone_mapping`].
+pub fn tone_mapping() -> ToneMappingOperator {
+    *TONE_MAPPING.lock()
+}
+
+/// The curve used to bring bright colours back into the displayable range.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ToneMappingOperator {
+    /// Colours are left as they are, and clip at full brightness
+    #[default]
+    None,
+    /// A simple curve that keeps dark colours as they are, but washes out bright ones
+    Reinhard,
+    /// A fit of the ACES filmic curve, with more contrast and saturated highlights
+    AcesFilmic,
+    /// John Hable's filmic curve from Uncharted 2, with softer highlights
+    Uncharted2,
+    /// Timothy Lottes' curve, which keeps mid greys close to where they were
+    Lottes,
+}
+
+impl ToneMappingOperator {
+    pub const ALL: [Self; 5] = [
+        Self::None,
+        Self::Reinhard,
+        Self::AcesFilmic,
+        Self::Uncharted2,
+        Self::Lottes,
+    ];
+
+    /// The index of the curve in the shader.
+    fn shader_index(self) -> u32 {
+        match self {
+            Self::None => 0,
+            Self::Reinhard => 1,
+            Self::AcesFilmic => 2,
+            Self::Uncharted2 => 3,
+            Self::Lottes => 4,
+        }
+    }
+}
+
+impl Display for ToneMappingOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::None => "None",
+            Self::Reinhard => "Reinhard",
+            Self::AcesFilmic => "ACES Filmic",
+            Self::Uncharted2 => "Uncharted 2",
+            Self::Lottes => "Lottes",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct ToneMappingUniform {
+    operator: u32,
+    _padding: [u32; 3],
+}
+
+impl ToneMappingUniform {
+    fn new(operator: ToneMappingOperator) -> Self {
+        Self {
+            operator: operator.shader_index(),
+            _padding: [0; 3],
+        }
+    }
+}
+
+/// The texture the tone mapped frame is written to, which is recreated when the viewport is
+/// resized.
+struct ToneMappingTarget {
+    size: wgpu::Extent3d,
+    view: TextureView,
+}
+
+/// Tone maps a frame.
+pub struct ToneMapping {
+    tone_map_pipeline: RenderPipeline,
+    copy_pipeline: RenderPipeline,
+    layout: BindGroupLayout,
+    uniform_buffer: wgpu::Buffer,
+    target: Option<ToneMappingTarget>,
+}
+
+impl ToneMapping {
+    pub fn new(graphics: Arc<SharedGraphicsContext>) -> Self {
+        let shader = Shader::new(
+            graphics.clone(),
+            crate::shader::shader_wesl::TONE_MAPPING_SHADER,
+            Some("tone_mapping_shader"),
+        );
+
+        let layout = graphics
+            .device
+            .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("tone_mapping_bind_group_layout"),
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                ],
+            });
+
+        let tone_map_pipeline = fullscreen_pipeline(
+            &graphics,
+            &shader,
+            &layout,
+            "fs_tone_map",
+            SCRATCH_FORMAT,
+            "Tone Mapping Pipeline",
+        );
+        let copy_pipeline = fullscreen_pipeline(
+            &graphics,
+            &shader,
+            &layout,
+            "fs_copy",
+            wgpu::TextureFormat::Rgba8Unorm,
+            "Tone Mapping Copy Pipeline",
+        );
+
+        let uniform_buffer =
+            graphics
+                .device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("Tone Mapping Uniform"),
+                    contents: bytemuck::cast_slice(&[ToneMappingUniform::new(tone_mapping())]),
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                });
+
+        log::debug!("Created tone mapping pipelines");
+
+        Self {
+            tone_map_pipeline,
+            copy_pipeline,
+            layout,
+            uniform_buffer,
+            target: None,
+        }
+    }
+
+    /// Applies the [`tone_mapping`] curve to the frame.
+    ///
+    /// This should be called last, after every other post-processing effect. Nothing is drawn if
+    /// the curve is [`ToneMappingOperator::None`]. Returns the number of draw calls made.
+    pub fn render(
+        &mut self,
+        graphics: &SharedGraphicsContext,
+        frame: &mut FrameGraphicsContext<'_>,
+    ) -> u32 {
+        let operator = tone_mapping();
+        if operator == ToneMappingOperator::None {
+            return 0;
+        }
+
+        let size = frame.depth_texture.size;
+        if self.target.as_ref().is_none_or(|t| t.size != size) {
+            self.target = Some(create_target(graphics, size));
+        }
+        let Some(target) = &self.target else {
+            return 0;
+        };
+
+        graphics.queue.write_buffer(
+            &self.uniform_buffer,
+            0,
+            bytemuck::cast_slice(&[ToneMappingUniform::new(operator)]),
+        );
+
+        let create_bind_group = |input: &TextureView| {
+            graphics
+                .device
+                .create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("tone_mapping_bind_group"),
+                    layout: &self.layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: wgpu::BindingResource::TextureView(input),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: self.uniform_buffer.as_entire_binding(),
+                        },
+                    ],
+                })
+        };
+
+        // the frame changes between calls, so neither of these can be kept
+        let tone_map_bind_group = create_bind_group(frame.view);
+        let copy_bind_group = create_bind_group(&target.view);
+
+        let passes = [
+            (&self.tone_map_pipeline, &tone_map_bind_group, &target.view),
+            (&self.copy_pipeline, &copy_bind_group, frame.view),
+        ];
+
+        for (pipeline, bind_group, view) in passes {
+            let mut render_pass = frame
+                .encoder
+                .begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("Tone Mapping Render Pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view,
+                        depth_slice: None,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Load,
+                            store: wgpu::StoreOp::Store,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                    occlusion_query_set: None,
+                    timestamp_writes: None,
+                });
+            render_pass.set_pipeline(pipeline);
+            render_pass.set_bind_group(0, bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+
+        passes.len() as u32
+    }
+}
+
+fn create_target(graphics: &SharedGraphicsContext, size: wgpu::Extent3d) -> ToneMappingTarget {
+    let view = graphics
+        .device
+        .create_texture(&wgpu::TextureDescriptor {
+            label: Some("tone_mapping_texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: SCRATCH_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        })
+        .create_view(&wgpu::TextureViewDescriptor::default());
+
+    ToneMappingTarget { size, view }
+}
+
+fn fullscreen_pipeline(
+    graphics: &SharedGraphicsContext,
+    shader: &Shader,
+    layout: &BindGroupLayout,
+    fragment_entry: &str,
+    format: wgpu::TextureFormat,
+    label: &str,
+) -> RenderPipeline {
+    let pipeline_layout = graphics
+        .device
+        .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some(label),
+            bind_group_layouts: &[layout],
+            push_constant_ranges: &[],
+        });
+
+    graphics
+        .device
+        .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(label),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader.module,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader.module,
+                entry_point: Some(fragment_entry),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        })
+}
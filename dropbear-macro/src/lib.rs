@@ -1,21 +1,35 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{DeriveInput, parse_macro_input};
+use syn::punctuated::Punctuated;
+use syn::{Data, DeriveInput, Expr, Lit, LitStr, Meta, Token, parse_macro_input};
 
-/// A `derive` macro that converts a struct to a usable [SerializableComponent].
+/// A `derive` macro that converts a struct or enum to a usable [SerializableComponent].
 ///
 /// You have to implement `serde::Serialize`, `serde::Deserialize` and `Clone` for the
-/// struct to be usable (it will throw errors anyway).
+/// type to be usable, and have `serde` as a dependency. If any of them are missing, the error
+/// points at the type instead of the generated code. Named structs, tuple structs and enums are
+/// all supported, but unions are not.
 ///
 /// # Usage
 /// ```
+/// # use dropbear_traits::SerializableComponent;
+/// # use serde::{Deserialize, Serialize};
 /// use dropbear_macro::SerializableComponent;
 ///
-/// #[derive(Serialize, Deserialize, Clone, SerializableComponent)] // required to be implemented
+/// #[derive(Debug, Serialize, Deserialize, Clone, SerializableComponent)] // required to be implemented
 /// struct MyComponent {
 ///     value1: String,
 ///     value2: i32,
 /// }
+///
+/// #[derive(Debug, Serialize, Deserialize, Clone, SerializableComponent)]
+/// struct Health(f32);
+///
+/// #[derive(Debug, Serialize, Deserialize, Clone, SerializableComponent)]
+/// enum PhysicsMode {
+///     Static,
+///     Dynamic,
+/// }
 /// ```
 #[proc_macro_derive(SerializableComponent)]
 pub fn derive_component(input: TokenStream) -> TokenStream {
@@ -23,7 +37,34 @@ pub fn derive_component(input: TokenStream) -> TokenStream {
     let name = &input.ident;
     let name_str = name.to_string();
 
+    if let Data::Union(_) = &input.data {
+        return syn::Error::new_spanned(
+            name,
+            "`#[derive(SerializableComponent)]` does not support unions",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    // a derive can't see the other derives in the same list, so the traits are checked with a
+    // bound on the type instead, which reports the error at the type's name
     let expanded = quote! {
+        const _: () = {
+            #[diagnostic::on_unimplemented(
+                message = "`SerializableComponent` requires #[derive(Serialize, Deserialize, Clone)] on `{Self}`",
+                label = "`Serialize`, `Deserialize` or `Clone` is not implemented for this type"
+            )]
+            trait SerializableComponentRequirements {}
+
+            impl<T> SerializableComponentRequirements for T where
+                T: serde::Serialize + serde::de::DeserializeOwned + Clone
+            {
+            }
+
+            fn assert_requirements<T: SerializableComponentRequirements>() {}
+            let _ = assert_requirements::<#name>;
+        };
+
         #[typetag::serde]
         impl SerializableComponent for #name {
             fn as_any(&self) -> &dyn std::any::Any {
@@ -46,3 +87,468 @@ pub fn derive_component(input: TokenStream) -> TokenStream {
 
     TokenStream::from(expanded)
 }
+
+/// A `derive` macro that implements [EventPayload] for a struct or enum, allowing it to be
+/// sent through the script event bus.
+///
+/// The event name is the name of the type in `snake_case` (`PlayerDied` -> `player_died`).
+///
+/// You have to implement `serde::Serialize` and `serde::Deserialize` for the type, and have
+/// `serde_json` and `anyhow` as dependencies. Enums must be internally tagged with
+/// `#[serde(tag = "type")]` so the payload stays readable from scripts.
+///
+/// # Usage
+/// ```
+/// # use dropbear_traits::EventPayload;
+/// # use serde::{Deserialize, Serialize};
+/// use dropbear_macro::Event;
+///
+/// #[derive(Serialize, Deserialize, Event)]
+/// struct PlayerDied {
+///     score: u32,
+/// }
+///
+/// assert_eq!(PlayerDied::event_name(), "player_died");
+/// ```
+#[proc_macro_derive(Event)]
+pub fn derive_event(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let event_name = to_snake_case(&name.to_string());
+
+    if let Data::Enum(_) = &input.data
+        && !has_serde_type_tag(&input)
+    {
+        return syn::Error::new_spanned(
+            name,
+            "`#[derive(Event)]` on an enum requires `#[serde(tag = \"type\")]`",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let expanded = quote! {
+        impl #impl_generics EventPayload for #name #ty_generics #where_clause {
+            fn event_name() -> &'static str {
+                #event_name
+            }
+
+            fn to_json(&self) -> serde_json::Value {
+                serde_json::to_value(self).unwrap_or(serde_json::Value::Null)
+            }
+
+            fn from_json(value: serde_json::Value) -> anyhow::Result<Self> {
+                Ok(serde_json::from_value(value)?)
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// A `derive` macro that marks a type as a [Resource], which can be shared between scenes
+/// through the resource registry.
+///
+/// This only implements the marker trait, and adds no methods.
+///
+/// # Usage
+/// ```
+/// # use dropbear_traits::Resource;
+/// use dropbear_macro::Resource;
+///
+/// #[derive(Resource)]
+/// struct HighScore {
+///     score: u32,
+/// }
+/// # fn assert_resource<T: Resource>() {}
+/// # assert_resource::<HighScore>();
+/// ```
+#[proc_macro_derive(Resource)]
+pub fn derive_resource(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let expanded = quote! {
+        impl #impl_generics Resource for #name #ty_generics #where_clause {}
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// A `derive` macro that implements [Inspectable] for a struct, drawing each field as a labelled
+/// row in the inspector.
+///
+/// Every field has to implement [InspectValue]. `Inspectable`, `InspectValue`, `InspectOptions`
+/// and `egui` all have to be in scope, which can be done by importing them from
+/// `eucalyptus_core::inspect` and `eucalyptus_core`.
+///
+/// Fields can be customised with the `#[inspect(..)]` attribute:
+/// - `range = 0.0..=1.0` - Clamps a number to the range
+/// - `speed = 0.01` - Sets how much a number changes per pixel dragged
+/// - `label = "Custom Name"` - Replaces the field name shown in the inspector
+/// - `skip` - Leaves the field out of the inspector
+/// - `color` - Edits a `[f32; 3]` or `[f32; 4]` with a colour picker, and is a compile error on
+///   any other type
+///
+/// # Usage
+/// This example is not run, as the traits it implements live in `eucalyptus_core`, which depends
+/// on this crate. See `eucalyptus_core::camera::CameraFollowTarget` for a type that uses it.
+/// ```ignore
+/// use dropbear_macro::Inspectable;
+/// use eucalyptus_core::inspect::{InspectOptions, InspectValue, Inspectable};
+///
+/// #[derive(Inspectable)]
+/// struct Flashlight {
+///     #[inspect(range = 0.0..=1.0, speed = 0.01)]
+///     intensity: f32,
+///     #[inspect(color, label = "Beam Colour")]
+///     colour: [f32; 3],
+///     #[inspect(skip)]
+///     flicker_timer: f32,
+/// }
+/// ```
+#[proc_macro_derive(Inspectable, attributes(inspect))]
+pub fn derive_inspectable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let Data::Struct(data) = &input.data else {
+        return syn::Error::new_spanned(name, "`#[derive(Inspectable)]` only supports structs")
+            .to_compile_error()
+            .into();
+    };
+
+    let mut rows = Vec::new();
+    for (index, field) in data.fields.iter().enumerate() {
+        let attr = match InspectAttr::from_field(field) {
+            Ok(attr) => attr,
+            Err(e) => return e.to_compile_error().into(),
+        };
+        if attr.skip {
+            continue;
+        }
+        if let Some(path) = &attr.color
+            && !is_colour_array(&field.ty)
+        {
+            return syn::Error::new_spanned(
+                path,
+                "`color` can only be used on a `[f32; 3]` or `[f32; 4]`",
+            )
+            .to_compile_error()
+            .into();
+        }
+
+        let member = match &field.ident {
+            Some(ident) => quote! { #ident },
+            None => {
+                let index = syn::Index::from(index);
+                quote! { #index }
+            }
+        };
+        let label = attr.label.unwrap_or_else(|| match &field.ident {
+            Some(ident) => to_title_case(&ident.to_string()),
+            None => index.to_string(),
+        });
+        let range = match attr.range {
+            Some((start, end)) => quote! { Some((#start) as f64..=(#end) as f64) },
+            None => quote! { None },
+        };
+        let speed = match attr.speed {
+            Some(speed) => quote! { Some((#speed) as f64) },
+            None => quote! { None },
+        };
+        let color = attr.color.is_some();
+
+        rows.push(quote! {
+            ui.horizontal(|ui| {
+                ui.label(#label);
+                let options = InspectOptions {
+                    range: #range,
+                    speed: #speed,
+                    color: #color,
+                };
+                changed |= InspectValue::inspect_value(&mut self.#member, ui, &options);
+            });
+        });
+    }
+
+    let expanded = quote! {
+        impl #impl_generics Inspectable for #name #ty_generics #where_clause {
+            fn inspect(&mut self, ui: &mut egui::Ui) -> bool {
+                let mut changed = false;
+                #(#rows)*
+                changed
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// The options of a field's `#[inspect(..)]` attribute.
+#[derive(Default)]
+struct InspectAttr {
+    range: Option<(Expr, Expr)>,
+    speed: Option<Expr>,
+    label: Option<String>,
+    skip: bool,
+    /// The `color` option as written, so an error on a field of the wrong type points at it
+    color: Option<syn::Path>,
+}
+
+impl InspectAttr {
+    fn from_field(field: &syn::Field) -> syn::Result<Self> {
+        let mut result = Self::default();
+
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("inspect")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("range") {
+                    let expr: Expr = meta.value()?.parse()?;
+                    let Expr::Range(range) = &expr else {
+                        return Err(meta.error("expected a range such as `0.0..=1.0`"));
+                    };
+                    let (Some(start), Some(end), syn::RangeLimits::Closed(_)) =
+                        (&range.start, &range.end, &range.limits)
+                    else {
+                        return Err(meta.error("expected an inclusive range such as `0.0..=1.0`"));
+                    };
+                    result.range = Some((*start.clone(), *end.clone()));
+                } else if meta.path.is_ident("speed") {
+                    result.speed = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("label") {
+                    let label: LitStr = meta.value()?.parse()?;
+                    result.label = Some(label.value());
+                } else if meta.path.is_ident("skip") {
+                    result.skip = true;
+                } else if meta.path.is_ident("color") {
+                    result.color = Some(meta.path.clone());
+                } else {
+                    return Err(meta.error(
+                        "unknown inspect attribute, expected one of `range`, `speed`, `label`, `skip` or `color`",
+                    ));
+                }
+                Ok(())
+            })?;
+        }
+
+        Ok(result)
+    }
+}
+
+/// A `derive` macro that generates `capture` and `restore` functions for a struct of component
+/// clones, such as a backup of an entity before play mode.
+///
+/// Every field has to be a `Clone` component. An `Option<T>` field is captured as `None` if the
+/// entity does not have a `T`, and restoring a `None` removes the `T` from the entity. The other
+/// fields are required, so `capture` returns `None` if the entity is missing any of them.
+///
+/// `hecs` and `anyhow` have to be dependencies of the crate.
+///
+/// # Usage
+/// ```
+/// use dropbear_macro::EntitySnapshot;
+///
+/// #[derive(Clone)]
+/// struct Health(f32);
+///
+/// #[derive(Clone)]
+/// struct Shield(f32);
+///
+/// #[derive(EntitySnapshot)]
+/// struct PlayerSnapshot {
+///     health: Health,
+///     shield: Option<Shield>,
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let mut world = hecs::World::new();
+/// let entity = world.spawn((Health(100.0),));
+///
+/// let snapshot = PlayerSnapshot::capture(&world, entity);
+/// world.get::<&mut Health>(entity)?.0 = 10.0;
+/// world.insert_one(entity, Shield(50.0))?;
+///
+/// if let Some(snapshot) = snapshot {
+///     snapshot.restore(&mut world, entity)?;
+/// }
+/// assert_eq!(world.get::<&Health>(entity)?.0, 100.0);
+/// assert!(world.get::<&Shield>(entity).is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[proc_macro_derive(EntitySnapshot)]
+pub fn derive_entity_snapshot(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            syn::Fields::Named(fields) => &fields.named,
+            _ => {
+                return syn::Error::new_spanned(
+                    name,
+                    "`#[derive(EntitySnapshot)]` requires a struct with named fields",
+                )
+                .to_compile_error()
+                .into();
+            }
+        },
+        _ => {
+            return syn::Error::new_spanned(
+                name,
+                "`#[derive(EntitySnapshot)]` only supports structs",
+            )
+            .to_compile_error()
+            .into();
+        }
+    };
+
+    let mut captures = Vec::new();
+    let mut restores = Vec::new();
+    for field in fields {
+        let ident = &field.ident;
+        match option_inner_type(&field.ty) {
+            Some(inner) => {
+                captures.push(quote! {
+                    #ident: world.get::<&#inner>(entity).ok().map(|c| (*c).clone())
+                });
+                restores.push(quote! {
+                    match self.#ident {
+                        Some(component) => world.insert_one(entity, component)?,
+                        None => {
+                            let _ = world.remove_one::<#inner>(entity);
+                        }
+                    }
+                });
+            }
+            None => {
+                let ty = &field.ty;
+                captures.push(quote! {
+                    #ident: (*world.get::<&#ty>(entity).ok()?).clone()
+                });
+                restores.push(quote! {
+                    world.insert_one(entity, self.#ident)?;
+                });
+            }
+        }
+    }
+
+    let expanded = quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Clones the components of an entity, returning `None` if it is missing a required
+            /// component.
+            pub fn capture(world: &hecs::World, entity: hecs::Entity) -> Option<Self> {
+                Some(Self {
+                    #(#captures),*
+                })
+            }
+
+            /// Puts the captured components back onto the entity, replacing the ones it has.
+            pub fn restore(self, world: &mut hecs::World, entity: hecs::Entity) -> anyhow::Result<()> {
+                #(#restores)*
+                Ok(())
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Checks if the type is written as `[f32; 3]` or `[f32; 4]`.
+fn is_colour_array(ty: &syn::Type) -> bool {
+    let syn::Type::Array(array) = ty else {
+        return false;
+    };
+    let syn::Type::Path(elem) = &*array.elem else {
+        return false;
+    };
+    if !elem.path.is_ident("f32") {
+        return false;
+    }
+    matches!(
+        &array.len,
+        Expr::Lit(syn::ExprLit { lit: Lit::Int(len), .. })
+            if matches!(len.base10_digits(), "3" | "4")
+    )
+}
+
+/// Returns `T` if the type is an `Option<T>`.
+fn option_inner_type(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        syn::GenericArgument::Type(inner) => Some(inner),
+        _ => None,
+    }
+}
+
+/// Checks if the type has a `#[serde(tag = "type")]` attribute.
+fn has_serde_type_tag(input: &DeriveInput) -> bool {
+    input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+        .filter_map(|attr| {
+            attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+        .any(|meta| {
+            if let Meta::NameValue(nv) = meta
+                && nv.path.is_ident("tag")
+                && let Expr::Lit(expr) = &nv.value
+                && let Lit::Str(s) = &expr.lit
+            {
+                return s.value() == "type";
+            }
+            false
+        })
+}
+
+/// Converts a `PascalCase` identifier into `snake_case`.
+fn to_snake_case(ident: &str) -> String {
+    let mut result = String::with_capacity(ident.len() + 4);
+    let chars: Vec<char> = ident.chars().collect();
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            let prev_lower = i > 0 && (chars[i - 1].is_lowercase() || chars[i - 1].is_numeric());
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if i > 0 && (prev_lower || (chars[i - 1].is_uppercase() && next_lower)) {
+                result.push('_');
+            }
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(*c);
+        }
+    }
+    result
+}
+
+/// Converts a `snake_case` field name into `Title Case` for a label.
+fn to_title_case(ident: &str) -> String {
+    ident
+        .split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
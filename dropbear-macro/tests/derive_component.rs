@@ -0,0 +1,51 @@
+use dropbear_macro::SerializableComponent;
+use dropbear_traits::SerializableComponent;
+use glam::DVec3;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, SerializableComponent)]
+struct Velocity(DVec3);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SerializableComponent)]
+enum PhysicsMode {
+    Static,
+    Dynamic,
+}
+
+#[test]
+fn test_tuple_struct_component() {
+    let component: Box<dyn SerializableComponent> = Box::new(Velocity(DVec3::new(1.0, 2.0, 3.0)));
+    assert_eq!(component.type_name(), "Velocity");
+
+    let velocity = component.as_any().downcast_ref::<Velocity>().unwrap();
+    assert_eq!(velocity.0, DVec3::new(1.0, 2.0, 3.0));
+}
+
+#[test]
+fn test_enum_component() {
+    let component: Box<dyn SerializableComponent> = Box::new(PhysicsMode::Dynamic);
+    assert_eq!(component.type_name(), "PhysicsMode");
+
+    let cloned = component.clone();
+    assert_eq!(
+        cloned.as_any().downcast_ref::<PhysicsMode>(),
+        Some(&PhysicsMode::Dynamic)
+    );
+}
+
+#[test]
+fn test_enum_component_round_trip() {
+    let component: Box<dyn SerializableComponent> = Box::new(PhysicsMode::Static);
+    let json = serde_json::to_string(&component).unwrap();
+    let loaded: Box<dyn SerializableComponent> = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        loaded.as_any().downcast_ref::<PhysicsMode>(),
+        Some(&PhysicsMode::Static)
+    );
+}
+
+#[test]
+fn test_missing_serde_error() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/serializable_component_*.rs");
+}
@@ -0,0 +1,50 @@
+use dropbear_macro::Event;
+use dropbear_traits::{EventPayload, EventQueue};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Event)]
+struct PlayerDied {
+    score: u32,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Event)]
+#[serde(tag = "type")]
+enum HUDMessage {
+    Show { text: String },
+    Hide,
+}
+
+#[test]
+fn test_event_names() {
+    assert_eq!(PlayerDied::event_name(), "player_died");
+    assert_eq!(HUDMessage::event_name(), "hud_message");
+}
+
+#[test]
+fn test_event_json_round_trip() {
+    let event = HUDMessage::Show {
+        text: "Game Over".to_string(),
+    };
+    let json = event.to_json();
+    assert_eq!(json["type"], "Show");
+    assert_eq!(HUDMessage::from_json(json).unwrap(), event);
+}
+
+#[test]
+fn test_drain_typed_round_trip() {
+    let mut queue = EventQueue::new();
+    queue.emit_typed(PlayerDied { score: 10 });
+    queue.emit_typed(HUDMessage::Hide);
+    queue.emit_typed(PlayerDied { score: 20 });
+
+    let died: Vec<PlayerDied> = queue.drain_typed();
+    assert_eq!(
+        died,
+        vec![PlayerDied { score: 10 }, PlayerDied { score: 20 }]
+    );
+
+    // events of other types stay queued until they are drained
+    assert_eq!(queue.len(), 1);
+    assert_eq!(queue.drain_typed::<HUDMessage>(), vec![HUDMessage::Hide]);
+    assert!(queue.is_empty());
+}
@@ -0,0 +1,5 @@
+#[test]
+fn test_inspect_attribute_errors() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/inspect_*.rs");
+}
@@ -0,0 +1,12 @@
+use dropbear_macro::Inspectable;
+
+#[derive(Inspectable)]
+struct Flashlight {
+    #[inspect(color)]
+    intensity: f32,
+}
+
+fn main() {
+    let light = Flashlight { intensity: 1.0 };
+    let _ = light.intensity;
+}
@@ -0,0 +1,12 @@
+use dropbear_macro::Inspectable;
+
+#[derive(Inspectable)]
+struct Flashlight {
+    #[inspect(colour)]
+    tint: [f32; 3],
+}
+
+fn main() {
+    let light = Flashlight { tint: [1.0; 3] };
+    let _ = light.tint;
+}
@@ -0,0 +1,10 @@
+use dropbear_macro::SerializableComponent;
+use dropbear_traits::SerializableComponent;
+
+#[derive(Debug, Clone, SerializableComponent)]
+struct Velocity(f32);
+
+fn main() {
+    let velocity = Velocity(1.0);
+    let _ = velocity.0;
+}
@@ -31,4 +31,24 @@ fn main() {
         .build_artifact(&"package::shader".parse().unwrap(), "dropbear_shader");
     wesl::Wesl::new("src/shaders")
         .build_artifact(&"package::outline".parse().unwrap(), "dropbear_outline");
+    wesl::Wesl::new("src/shaders")
+        .build_artifact(&"package::particle".parse().unwrap(), "dropbear_particle");
+    wesl::Wesl::new("src/shaders").build_artifact(
+        &"package::particle_update".parse().unwrap(),
+        "dropbear_particle_update",
+    );
+    wesl::Wesl::new("src/shaders")
+        .build_artifact(&"package::sprite".parse().unwrap(), "dropbear_sprite");
+    wesl::Wesl::new("src/shaders")
+        .build_artifact(&"package::morph".parse().unwrap(), "dropbear_morph");
+    wesl::Wesl::new("src/shaders")
+        .build_artifact(&"package::ssao".parse().unwrap(), "dropbear_ssao");
+    wesl::Wesl::new("src/shaders").build_artifact(
+        &"package::depth_of_field".parse().unwrap(),
+        "dropbear_depth_of_field",
+    );
+    wesl::Wesl::new("src/shaders").build_artifact(
+        &"package::tone_mapping".parse().unwrap(),
+        "dropbear_tone_mapping",
+    );
 }
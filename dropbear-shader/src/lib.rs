@@ -5,3 +5,10 @@ wesl::wesl_pkg!(dropbear);
 pub const LIGHT_SHADER: &str = include_wesl!("dropbear_light");
 pub const SHADER_SHADER: &str = include_wesl!("dropbear_shader");
 pub const OUTLINE_SHADER: &str = include_wesl!("dropbear_outline");
+pub const PARTICLE_SHADER: &str = include_wesl!("dropbear_particle");
+pub const PARTICLE_UPDATE_SHADER: &str = include_wesl!("dropbear_particle_update");
+pub const SPRITE_SHADER: &str = include_wesl!("dropbear_sprite");
+pub const MORPH_SHADER: &str = include_wesl!("dropbear_morph");
+pub const SSAO_SHADER: &str = include_wesl!("dropbear_ssao");
+pub const DEPTH_OF_FIELD_SHADER: &str = include_wesl!("dropbear_depth_of_field");
+pub const TONE_MAPPING_SHADER: &str = include_wesl!("dropbear_tone_mapping");
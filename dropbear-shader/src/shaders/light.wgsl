@@ -3,11 +3,13 @@
 struct Light {
     position: vec4<f32>,
     direction: vec4<f32>, // x, y, z, outer_cutoff_angle
-    color: vec4<f32>, // r, g, b, light_type (0, 1, 2)
+    color: vec4<f32>, // r, g, b, light_type (0, 1, 2, 3)
     constant: f32,
     lin: f32,
     quadratic: f32,
     cutoff: f32,
+    right: vec4<f32>, // area lights: half extent along the local x axis
+    up: vec4<f32>, // area lights: half extent along the local y axis
 }
 
 struct CameraUniform {
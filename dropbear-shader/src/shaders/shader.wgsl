@@ -1,6 +1,8 @@
 // Main shader for standard objects.
 
 const MAX_LIGHTS: u32 = 8;
+const MAX_LIGHT_PROBES: u32 = 4;
+const PI: f32 = 3.14159265;
 
 struct CameraUniform {
     view_pos: vec4<f32>,
@@ -10,11 +12,13 @@ struct CameraUniform {
 struct Light {
     position: vec4<f32>,
     direction: vec4<f32>, // x, y, z, outer_cutoff_angle
-    color: vec4<f32>, // r, g, b, light_type (0, 1, 2)
+    color: vec4<f32>, // r, g, b, light_type (0, 1, 2, 3)
     constant: f32,
     lin: f32,
     quadratic: f32,
     cutoff: f32,
+    right: vec4<f32>, // area lights: half extent along the local x axis
+    up: vec4<f32>, // area lights: half extent along the local y axis
 }
 
 struct LightArray {
@@ -23,6 +27,16 @@ struct LightArray {
     ambient_strength: f32,
 }
 
+struct LightProbeUniform {
+    position_radius: vec4<f32>, // x, y, z, radius
+    coefficients: array<vec4<f32>, 9>, // spherical harmonic irradiance, w is unused
+}
+
+struct LightProbeArray {
+    probes: array<LightProbeUniform, MAX_LIGHT_PROBES>,
+    probe_count: u32,
+}
+
 @group(0) @binding(0)
 var t_diffuse: texture_2d<f32>;
 @group(0) @binding(1)
@@ -34,6 +48,24 @@ var<uniform> camera: CameraUniform;
 @group(2) @binding(0)
 var<uniform> light_array: LightArray;
 
+@group(2) @binding(1)
+var<uniform> light_probes: LightProbeArray;
+
+struct EmissiveUniform {
+    factor: vec4<f32>, // r, g, b, unused
+}
+
+@group(3) @binding(0)
+var t_emissive: texture_2d<f32>;
+@group(3) @binding(1)
+var s_emissive: sampler;
+@group(3) @binding(2)
+var<uniform> emissive: EmissiveUniform;
+
+// only bound for fs_ambient_occlusion, 1 where nothing is occluded
+@group(4) @binding(0)
+var t_ambient_occlusion: texture_2d<f32>;
+
 struct InstanceInput {
     @location(5) model_matrix_0: vec4<f32>,
     @location(6) model_matrix_1: vec4<f32>,
@@ -43,6 +75,9 @@ struct InstanceInput {
     @location(9) normal_matrix_0: vec3<f32>,
     @location(10) normal_matrix_1: vec3<f32>,
     @location(11) normal_matrix_2: vec3<f32>,
+
+    // locations 12 and 13 hold up to 8 user defined material properties (see MaterialProperties),
+    // which only custom material shaders read
 };
 
 struct VertexInput {
@@ -169,6 +204,102 @@ fn spot_light(light: Light, world_pos: vec3<f32>, world_normal: vec3<f32>, view_
     return ambient_attenuated + diffuse_attenuated + specular_attenuated;
 }
 
+// integral of the clamped cosine over a polygon edge, from "Real-Time Polygonal-Light Shading with
+// Linearly Transformed Cosines" (Heitz et al. 2016). for a lambertian surface the LTC matrix is the
+// identity, so summing the edges gives the exact form factor of the polygon.
+fn integrate_edge(v1: vec3<f32>, v2: vec3<f32>) -> vec3<f32> {
+    let cos_theta = clamp(dot(v1, v2), -0.9999, 0.9999);
+    let theta = acos(cos_theta);
+    return cross(v1, v2) * (theta / sin(theta));
+}
+
+fn area_light(light: Light, world_pos: vec3<f32>, world_normal: vec3<f32>, view_dir: vec3<f32>, tex_color: vec3<f32>) -> vec3<f32> {
+    let centre = light.position.xyz;
+    let right = light.right.xyz;
+    let up = light.up.xyz;
+    let light_normal = normalize(light.direction.xyz);
+    let norm = normalize(world_normal);
+
+    // area lights only emit from their front face
+    if dot(world_pos - centre, light_normal) <= 0.0 {
+        return vec3<f32>(0.0);
+    }
+
+    let p0 = normalize(centre - right - up - world_pos);
+    let p1 = normalize(centre + right - up - world_pos);
+    let p2 = normalize(centre + right + up - world_pos);
+    let p3 = normalize(centre - right + up - world_pos);
+
+    var edges = integrate_edge(p0, p1);
+    edges += integrate_edge(p1, p2);
+    edges += integrate_edge(p2, p3);
+    edges += integrate_edge(p3, p0);
+    let form_factor = max(dot(edges, norm), 0.0) / (2.0 * PI);
+
+    let diffuse = light.color.xyz * form_factor * tex_color;
+
+    // the specular highlight uses the point on the rectangle closest to the reflection ray
+    let half_width = max(length(right), 0.0001);
+    let half_height = max(length(up), 0.0001);
+    let right_dir = right / half_width;
+    let up_dir = up / half_height;
+
+    let reflect_dir = reflect(-view_dir, norm);
+    var closest = centre;
+    let denom = dot(reflect_dir, light_normal);
+    if abs(denom) > 0.0001 {
+        let t = max(dot(centre - world_pos, light_normal) / denom, 0.0);
+        let local = world_pos + reflect_dir * t - centre;
+        closest = centre
+            + right_dir * clamp(dot(local, right_dir), -half_width, half_width)
+            + up_dir * clamp(dot(local, up_dir), -half_height, half_height);
+    }
+
+    let light_dir = normalize(closest - world_pos);
+    let spec = pow(max(dot(view_dir, reflect(-light_dir, norm)), 0.0), 32.0);
+    let specular = light.color.xyz * spec * tex_color;
+
+    let distance = length(closest - world_pos);
+    let attenuation = 1.0 / (light.constant + (light.lin * distance) + (light.quadratic * (distance * distance)));
+
+    return diffuse + specular * attenuation;
+}
+
+// evaluates the irradiance of a light probe in the direction of the normal
+fn eval_probe_irradiance(probe: LightProbeUniform, n: vec3<f32>) -> vec3<f32> {
+    let c = probe.coefficients;
+    var irradiance = c[0].xyz * 0.282095;
+    irradiance += c[1].xyz * 0.488603 * n.y;
+    irradiance += c[2].xyz * 0.488603 * n.z;
+    irradiance += c[3].xyz * 0.488603 * n.x;
+    irradiance += c[4].xyz * 1.092548 * n.x * n.y;
+    irradiance += c[5].xyz * 1.092548 * n.y * n.z;
+    irradiance += c[6].xyz * 0.315392 * (3.0 * n.z * n.z - 1.0);
+    irradiance += c[7].xyz * 1.092548 * n.x * n.z;
+    irradiance += c[8].xyz * 0.546274 * (n.x * n.x - n.y * n.y);
+    return max(irradiance, vec3<f32>(0.0));
+}
+
+fn ambient_light(world_position: vec3<f32>, world_normal: vec3<f32>) -> vec3<f32> {
+    var total_ambient = vec3<f32>(0.0);
+    for (var i: u32 = 0u; i < light_array.light_count; i = i + 1u) {
+        let light = light_array._lights[i];
+        total_ambient += light.color.xyz * light_array.ambient_strength;
+    }
+
+    // the nearest light probe in range replaces the global ambient
+    var nearest_probe_distance = 3.40282e38;
+    for (var i: u32 = 0u; i < light_probes.probe_count; i = i + 1u) {
+        let probe = light_probes.probes[i];
+        let distance = length(probe.position_radius.xyz - world_position);
+        if distance <= probe.position_radius.w && distance < nearest_probe_distance {
+            nearest_probe_distance = distance;
+            total_ambient = eval_probe_irradiance(probe, world_normal) / PI;
+        }
+    }
+    return total_ambient;
+}
+
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     var tex_color = textureSample(t_diffuse, s_diffuse, in.tex_coords);
@@ -181,11 +312,7 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
 
     var final_color = vec3<f32>(0.0);
 
-    var total_ambient = vec3<f32>(0.0);
-    for (var i: u32 = 0u; i < light_array.light_count; i = i + 1u) {
-        let light = light_array._lights[i];
-        total_ambient += light.color.xyz * light_array.ambient_strength;
-    }
+    let total_ambient = ambient_light(in.world_position, world_normal);
 
     for (var i: u32 = 0u; i < light_array.light_count; i = i + 1u) {
         let light = light_array._lights[i];
@@ -200,10 +327,31 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
         } else if light.color.w == 2.0 {
             // spot
             final_color += spot_light(light, in.world_position, world_normal, view_dir, tex_color.xyz);
+        } else if light.color.w == 3.0 {
+            // area
+            final_color += area_light(light, in.world_position, world_normal, view_dir, tex_color.xyz);
         }
     }
 
     final_color = (total_ambient * tex_color.xyz) + final_color;
 
+    // emissive light isn't affected by the lights in the scene
+    let emissive_sample = textureSample(t_emissive, s_emissive, in.tex_coords);
+    final_color += emissive.factor.xyz * emissive_sample.xyz;
+
     return vec4<f32>(final_color, tex_color.a);
-}
\ No newline at end of file
+}
+
+// the part of the ambient light that is occluded, which the ambient occlusion pipeline subtracts
+// from what fs_main drew
+@fragment
+fn fs_ambient_occlusion(in: VertexOutput) -> @location(0) vec4<f32> {
+    let tex_color = textureSample(t_diffuse, s_diffuse, in.tex_coords);
+    if (tex_color.a < 0.1) {
+        discard;
+    }
+
+    let ao = textureLoad(t_ambient_occlusion, vec2<i32>(in.clip_position.xy), 0).r;
+    let ambient = ambient_light(in.world_position, normalize(in.world_normal)) * tex_color.xyz;
+    return vec4<f32>(ambient * (1.0 - ao), 0.0);
+}
@@ -34,6 +34,11 @@ impl Clone for Box<dyn SerializableComponent> {
     }
 }
 
+/// A marker for types that are shared between scenes through the resource registry.
+///
+/// There is nothing to implement, so `#[derive(Resource)]` is all a type needs.
+pub trait Resource: Send + Sync + 'static {}
+
 pub trait ComponentConverter: Send + Sync {
     fn type_id(&self) -> TypeId;
     fn type_name(&self) -> &'static str;
@@ -59,6 +64,64 @@ pub trait ComponentDeserializer: Send + Sync {
     ) -> Result<()>;
 }
 
+/// A typed payload that can be sent through the script event bus.
+///
+/// Events cross into scripts as JSON, so the type has to round trip through
+/// [`serde_json::Value`]. `#[derive(Event)]` does this with the type's serde implementation.
+pub trait EventPayload: Sized + Send + Sync + 'static {
+    /// The name of the event, which is the type name in `snake_case`.
+    fn event_name() -> &'static str;
+    /// Converts the event into a [`serde_json::Value`].
+    fn to_json(&self) -> serde_json::Value;
+    /// Attempts to convert a [`serde_json::Value`] back into the event.
+    fn from_json(value: serde_json::Value) -> Result<Self>;
+}
+
+/// Events waiting to be drained, stored as JSON under their [`EventPayload::event_name`].
+#[derive(Debug, Default)]
+pub struct EventQueue {
+    events: Vec<(&'static str, serde_json::Value)>,
+}
+
+impl EventQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pushes a typed event onto the queue.
+    pub fn emit_typed<E: EventPayload>(&mut self, event: E) {
+        log::trace!("Emitting event [{}]", E::event_name());
+        self.events.push((E::event_name(), event.to_json()));
+    }
+
+    /// Removes and returns all queued events of type `E`, leaving events of other types queued.
+    ///
+    /// Events that fail to deserialize into `E` are logged and dropped.
+    pub fn drain_typed<E: EventPayload>(&mut self) -> Vec<E> {
+        let mut drained = Vec::new();
+        self.events.retain(|(name, value)| {
+            if *name != E::event_name() {
+                return true;
+            }
+            match E::from_json(value.clone()) {
+                Ok(event) => drained.push(event),
+                Err(e) => log::warn!("Unable to deserialize event [{}]: {}", name, e),
+            }
+            false
+        });
+        drained
+    }
+
+    /// The number of events still queued, of any type.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
 struct DirectConverter<T: SerializableComponent + hecs::Component + Clone> {
     _phantom: std::marker::PhantomData<T>,
 }
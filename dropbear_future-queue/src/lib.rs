@@ -40,9 +40,11 @@ use std::any::Any;
 use std::cell::RefCell;
 use std::collections::VecDeque;
 use std::future::Future;
+use std::hash::{Hash, Hasher};
 use std::pin::Pin;
 use std::rc::Rc;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
 
@@ -68,10 +70,70 @@ pub enum FutureStatus {
     Cancelled,
 }
 
+/// A flag shared between a [`FutureHandle`] and its future, used to ask the future to stop.
+///
+/// Cancellation is cooperative: a future pushed with [`FutureQueue::push_cancellable`] should
+/// check [`CancellationToken::is_cancelled`] at its yield points and return early. The result
+/// of a cancelled future is never delivered.
+#[derive(Default, Clone, Debug)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Marks the token as cancelled. This cannot be undone.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
 /// A handle to the future task
-#[derive(Default, Copy, Clone, Eq, Hash, PartialEq, Debug)]
+///
+/// Handles are compared and hashed by their id only.
+#[derive(Default, Clone, Debug)]
 pub struct FutureHandle {
     pub id: u64,
+    token: CancellationToken,
+}
+
+impl FutureHandle {
+    /// Requests the future to stop by setting its [`CancellationToken`].
+    ///
+    /// Unlike [`FutureQueue::cancel`], this does not abort the task, so the future is able to
+    /// finish its current step and clean up. Its result is discarded either way.
+    pub fn cancel(&self) {
+        log(format!("Cancellation requested for handle: {:?}", self.id));
+        self.token.cancel();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Returns the token that is shared with the future.
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+}
+
+impl PartialEq for FutureHandle {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for FutureHandle {}
+
+impl Hash for FutureHandle {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
 }
 
 /// Internal storage per handle — separate from FutureHandle
@@ -80,6 +142,23 @@ struct HandleEntry {
     status: FutureStatus,
     cached_result: Option<AnyResult>,
     task_handle: Option<JoinHandle<()>>,
+    token: CancellationToken,
+}
+
+impl HandleEntry {
+    /// Marks the entry as cancelled if its token was cancelled through a [`FutureHandle`].
+    fn sync_cancellation(&mut self) {
+        if self.token.is_cancelled()
+            && !matches!(
+                self.status,
+                FutureStatus::Completed | FutureStatus::Cancelled
+            )
+        {
+            self.status = FutureStatus::Cancelled;
+            self.receiver = None;
+            self.cached_result = None;
+        }
+    }
 }
 
 /// A queue for polling futures. It is stored in here until [`FutureQueue::poll`] is run.
@@ -108,12 +187,47 @@ impl FutureQueue {
     where
         F: Future<Output = T> + Send + 'static,
         T: Send + Sync + 'static,
+    {
+        self.push_cancellable(|_| future)
+    }
+
+    /// Pushes a future that is given the [`CancellationToken`] of its handle, so it can stop
+    /// early after [`FutureHandle::cancel`] is called.
+    ///
+    /// # Example
+    /// ```rust
+    /// use dropbear_future_queue::{FutureQueue, FutureStatus};
+    ///
+    /// # tokio_test::block_on(async {
+    /// let queue = FutureQueue::new();
+    /// let handle = queue.push_cancellable(|token| async move {
+    ///     for _ in 0..100 {
+    ///         if token.is_cancelled() {
+    ///             return;
+    ///         }
+    ///         tokio::time::sleep(tokio::time::Duration::from_millis(1)).await;
+    ///     }
+    /// });
+    ///
+    /// handle.cancel();
+    /// assert!(matches!(queue.get_status(&handle), Some(FutureStatus::Cancelled)));
+    /// # });
+    /// ```
+    pub fn push_cancellable<F, Fut, T>(&self, future: F) -> FutureHandle
+    where
+        F: FnOnce(CancellationToken) -> Fut,
+        Fut: Future<Output = T> + Send + 'static,
+        T: Send + Sync + 'static,
     {
         let mut next_id = self.next_id.lock();
         let id = *next_id;
         *next_id += 1;
 
-        let id = FutureHandle { id };
+        let token = CancellationToken::new();
+        let id = FutureHandle {
+            id,
+            token: token.clone(),
+        };
 
         let (sender, receiver) = oneshot::channel::<AnyResult>();
 
@@ -122,13 +236,22 @@ impl FutureQueue {
             status: FutureStatus::NotPolled,
             cached_result: None,
             task_handle: None,
+            token: token.clone(),
         };
 
-        self.handle_registry.lock().insert(id, entry);
+        self.handle_registry.lock().insert(id.clone(), entry);
 
+        let future = future(token.clone());
         let wrapped_future: Pin<Box<dyn Future<Output = ()> + Send>> = Box::pin(async move {
             log("Starting future execution");
             let result = future.await;
+
+            if token.is_cancelled() {
+                // dropping the sender closes the channel without a result
+                log("Future was cancelled, discarding result");
+                return;
+            }
+
             let boxed_result: AnyResult = Arc::new(result);
             log("Future completed, sending result");
 
@@ -138,7 +261,7 @@ impl FutureQueue {
             log("Result sent via channel");
         });
 
-        self.queued.lock().push_back((id, wrapped_future));
+        self.queued.lock().push_back((id.clone(), wrapped_future));
 
         id
     }
@@ -161,6 +284,14 @@ impl FutureQueue {
         while let Some((id, future)) = queue.pop_front() {
             log(format!("Processing future with id: {:?}", id));
 
+            if id.is_cancelled() {
+                log("Future was cancelled before being polled, skipping");
+                if let Some(entry) = self.handle_registry.lock().get_mut(&id) {
+                    entry.sync_cancellation();
+                }
+                continue;
+            }
+
             {
                 let mut registry = self.handle_registry.lock();
                 if let Some(entry) = registry.get_mut(&id) {
@@ -194,6 +325,7 @@ impl FutureQueue {
     pub fn exchange(&self, handle: &FutureHandle) -> Option<AnyResult> {
         let mut registry = self.handle_registry.lock();
         if let Some(entry) = registry.get_mut(handle) {
+            entry.sync_cancellation();
             match &entry.status {
                 FutureStatus::Completed => {
                     log("FutureStatus::Completed - returning cached result");
@@ -239,6 +371,7 @@ impl FutureQueue {
     pub fn exchange_owned(&self, handle: &FutureHandle) -> Option<AnyResult> {
         let mut registry = self.handle_registry.lock();
         if let Some(entry) = registry.get_mut(handle) {
+            entry.sync_cancellation();
             match &entry.status {
                 FutureStatus::Completed => {
                     log("FutureStatus::Completed - taking ownership of cached result");
@@ -297,8 +430,11 @@ impl FutureQueue {
 
     /// Get status of a handle
     pub fn get_status(&self, handle: &FutureHandle) -> Option<FutureStatus> {
-        let registry = self.handle_registry.lock();
-        registry.get(handle).map(|entry| entry.status.clone())
+        let mut registry = self.handle_registry.lock();
+        registry.get_mut(handle).map(|entry| {
+            entry.sync_cancellation();
+            entry.status.clone()
+        })
     }
 
     /// Cancels a running future by its handle.
@@ -317,6 +453,8 @@ impl FutureQueue {
                 return false;
             }
 
+            entry.token.cancel();
+
             if let Some(task_handle) = entry.task_handle.take() {
                 task_handle.abort();
                 log(format!("Aborted task for handle: {:?}", handle));
@@ -340,13 +478,14 @@ impl FutureQueue {
     pub fn cleanup(&self) {
         let mut registry = self.handle_registry.lock();
         let completed_ids: Vec<FutureHandle> = registry
-            .iter()
-            .filter_map(|(&id, entry)| {
+            .iter_mut()
+            .filter_map(|(id, entry)| {
+                entry.sync_cancellation();
                 matches!(
                     entry.status,
                     FutureStatus::Completed | FutureStatus::Cancelled
                 )
-                .then_some(id)
+                .then(|| id.clone())
             })
             .collect();
 
@@ -471,3 +610,50 @@ fn test_exchange_owned_as() {
             assert!(queue.exchange_owned_as::<i32>(&handle).is_none());
         });
 }
+
+#[test]
+fn test_cancel_handle() {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(async {
+            let queue = FutureQueue::new();
+            let (stopped_sender, stopped_receiver) = oneshot::channel::<bool>();
+
+            let handle = queue.push_cancellable(|token| async move {
+                let mut stopped_early = false;
+                for _ in 0..1000 {
+                    if token.is_cancelled() {
+                        stopped_early = true;
+                        break;
+                    }
+                    tokio::time::sleep(tokio::time::Duration::from_millis(1)).await;
+                }
+                let _ = stopped_sender.send(stopped_early);
+                67 + 41
+            });
+
+            queue.poll();
+            tokio::time::sleep(tokio::time::Duration::from_millis(5)).await;
+
+            handle.cancel();
+            assert!(handle.is_cancelled());
+
+            let stopped_early =
+                tokio::time::timeout(tokio::time::Duration::from_millis(500), stopped_receiver)
+                    .await
+                    .expect("Future never checked its cancellation token")
+                    .unwrap();
+            assert!(stopped_early);
+
+            assert!(matches!(
+                queue.get_status(&handle),
+                Some(FutureStatus::Cancelled)
+            ));
+            assert!(queue.exchange_as::<i32>(&handle).is_none());
+
+            queue.cleanup();
+            assert!(queue.get_status(&handle).is_none());
+        });
+}
@@ -1,11 +1,15 @@
-use crate::states::Camera3D;
+use crate::hierarchy::EntityTransformExt;
+use crate::inspect::{InspectOptions, InspectValue, Inspectable};
+use crate::states::{Camera3D, Label, SCENES};
 use crate::traits::SerializableComponent;
 use dropbear_engine::camera::{Camera, CameraBuilder, CameraSettings};
-use dropbear_macro::SerializableComponent;
+use dropbear_engine::entity::EntityTransform;
+use dropbear_macro::{Inspectable, SerializableComponent};
 use glam::DVec3;
 use serde::{Deserialize, Serialize};
+use std::ops::{Add, Mul, Sub};
 
-#[derive(Debug, Clone, Serialize, Deserialize, SerializableComponent)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SerializableComponent)]
 pub struct CameraComponent {
     pub settings: CameraSettings,
     pub camera_type: CameraType,
@@ -48,6 +52,7 @@ impl From<Camera3D> for CameraBuilder {
             aspect: value.aspect,
             znear: value.near as f64,
             zfar: value.far as f64,
+            projection: value.projection,
             settings: CameraSettings {
                 speed: value.speed as f64,
                 sensitivity: value.sensitivity as f64,
@@ -96,17 +101,38 @@ impl DebugCamera {
     }
 }
 
-// #[derive(Debug, Default, Clone)]
-// pub struct CameraFollowTarget {
-//     pub follow_target: String,
-//     pub offset: DVec3,
-// }
+/// Makes a camera follow the entity labelled `target_label`, moved every frame by
+/// [`follow_system`].
+#[derive(Debug, Clone, Serialize, Deserialize, SerializableComponent, Inspectable)]
+pub struct CameraFollowTarget {
+    #[inspect(label = "Target")]
+    pub target_label: String,
+    /// Added to the target's position to get where the camera should sit
+    #[inspect(speed = 0.05)]
+    pub offset: DVec3,
+    /// How quickly the camera catches up, where `1.0` snaps straight to the target and lower
+    /// values lag behind
+    #[inspect(range = 0.0..=1.0, speed = 0.01)]
+    pub smoothing: f32,
+}
+
+impl Default for CameraFollowTarget {
+    fn default() -> Self {
+        Self {
+            target_label: String::new(),
+            offset: DVec3::new(0.0, 2.0, 5.0),
+            smoothing: 0.1,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum CameraType {
     Normal,
     Debug,
     Player,
+    /// The camera is being driven by a [`CameraPath`], and ignores user input.
+    Cinematic,
 }
 
 impl Default for CameraType {
@@ -121,3 +147,257 @@ pub enum CameraAction {
     ClearPlayerTarget,
     SetCurrentPositionAsOffset(hecs::Entity),
 }
+
+/// A single pose along a [`CameraPath`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CameraKeyframe {
+    /// Time since the start of the path, in seconds
+    pub time: f32,
+    pub eye: DVec3,
+    pub target: DVec3,
+    /// Vertical field of view, in degrees
+    pub fov: f64,
+}
+
+/// A list of camera poses that can be played back for cutscenes and trailers.
+///
+/// Keyframes are expected to be sorted by time, which [`CameraPath::sort`] does for you.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CameraPath {
+    pub keyframes: Vec<CameraKeyframe>,
+}
+
+impl CameraPath {
+    pub fn sort(&mut self) {
+        self.keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+    }
+
+    /// The time of the last keyframe.
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map(|k| k.time).unwrap_or(0.0)
+    }
+
+    /// Returns the interpolated pose at `time`, or `None` if the path has no keyframes.
+    ///
+    /// Keyframes are joined with cubic Hermite splines, using Catmull-Rom tangents so the camera
+    /// moves smoothly through every keyframe.
+    pub fn sample(&self, time: f32) -> Option<CameraKeyframe> {
+        let keys = &self.keyframes;
+        let first = keys.first()?;
+        let last = keys.last()?;
+
+        if time <= first.time {
+            return Some(*first);
+        }
+        if time >= last.time {
+            return Some(*last);
+        }
+
+        let i = keys.windows(2).position(|w| time <= w[1].time)?;
+        let (k0, k1) = (&keys[i], &keys[i + 1]);
+        let span = (k1.time - k0.time) as f64;
+        if span <= 0.0 {
+            return Some(*k1);
+        }
+        let s = (time - k0.time) as f64 / span;
+
+        let tangent = |index: usize, value: fn(&CameraKeyframe) -> DVec3| {
+            let prev = &keys[index.saturating_sub(1)];
+            let next = &keys[(index + 1).min(keys.len() - 1)];
+            let dt = (next.time - prev.time) as f64;
+            if dt > 0.0 {
+                (value(next) - value(prev)) / dt
+            } else {
+                DVec3::ZERO
+            }
+        };
+        let fov_tangent = |index: usize| {
+            let prev = &keys[index.saturating_sub(1)];
+            let next = &keys[(index + 1).min(keys.len() - 1)];
+            let dt = (next.time - prev.time) as f64;
+            if dt > 0.0 {
+                (next.fov - prev.fov) / dt
+            } else {
+                0.0
+            }
+        };
+
+        Some(CameraKeyframe {
+            time,
+            eye: hermite(
+                k0.eye,
+                tangent(i, |k| k.eye),
+                k1.eye,
+                tangent(i + 1, |k| k.eye),
+                span,
+                s,
+            ),
+            target: hermite(
+                k0.target,
+                tangent(i, |k| k.target),
+                k1.target,
+                tangent(i + 1, |k| k.target),
+                span,
+                s,
+            ),
+            fov: hermite(k0.fov, fov_tangent(i), k1.fov, fov_tangent(i + 1), span, s),
+        })
+    }
+}
+
+/// Evaluates a cubic Hermite spline between `p0` and `p1` at `s` (`0.0..=1.0`), where the
+/// tangents `m0` and `m1` are per second and `span` is the length of the segment in seconds.
+fn hermite<T>(p0: T, m0: T, p1: T, m1: T, span: f64, s: f64) -> T
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<f64, Output = T>,
+{
+    let s2 = s * s;
+    let s3 = s2 * s;
+    let h00 = 2.0 * s3 - 3.0 * s2 + 1.0;
+    let h10 = s3 - 2.0 * s2 + s;
+    let h01 = -2.0 * s3 + 3.0 * s2;
+    let h11 = s3 - s2;
+    p0 * h00 + m0 * (h10 * span) + p1 * h01 + m1 * (h11 * span)
+}
+
+/// Attached to a camera while it is playing back a [`CameraPath`].
+///
+/// This is not serialized, and is removed once the path has finished.
+#[derive(Debug, Clone)]
+pub struct CameraPathPlayback {
+    pub path: CameraPath,
+    pub elapsed: f32,
+    /// The camera type to restore once playback has finished
+    pub previous_type: CameraType,
+}
+
+/// Starts playing `path` on the camera `entity`, switching it to [`CameraType::Cinematic`].
+pub fn play_camera_path(
+    world: &mut hecs::World,
+    entity: hecs::Entity,
+    path: CameraPath,
+) -> anyhow::Result<()> {
+    if path.keyframes.is_empty() {
+        anyhow::bail!("Camera path has no keyframes");
+    }
+
+    let previous_type = {
+        let mut component = world.get::<&mut CameraComponent>(entity)?;
+        let previous_type = match world.get::<&CameraPathPlayback>(entity) {
+            Ok(playback) => playback.previous_type,
+            Err(_) => component.camera_type,
+        };
+        component.camera_type = CameraType::Cinematic;
+        previous_type
+    };
+
+    world.insert_one(
+        entity,
+        CameraPathPlayback {
+            path,
+            elapsed: 0.0,
+            previous_type,
+        },
+    )?;
+    Ok(())
+}
+
+/// Advances every [`CameraPathPlayback`] in the world by `dt` seconds, moving their cameras
+/// along the path.
+pub fn update_camera_paths(world: &mut hecs::World, dt: f32) {
+    let mut finished = Vec::new();
+
+    for (entity, (camera, component, playback)) in
+        world.query_mut::<(&mut Camera, &mut CameraComponent, &mut CameraPathPlayback)>()
+    {
+        playback.elapsed += dt;
+
+        if let Some(pose) = playback.path.sample(playback.elapsed) {
+            camera.eye = pose.eye;
+            camera.target = pose.target;
+            // the component overwrites the camera settings every frame
+            component.settings.fov_y = pose.fov;
+        }
+
+        if playback.elapsed >= playback.path.duration() {
+            component.camera_type = playback.previous_type;
+            finished.push(entity);
+        }
+    }
+
+    for entity in finished {
+        let _ = world.remove_one::<CameraPathPlayback>(entity);
+        log::debug!("Camera path finished playing on {:?}", entity);
+    }
+}
+
+/// Moves every camera with a [`CameraFollowTarget`] towards its target's position plus the
+/// offset, and points it at the target.
+///
+/// Cameras playing a [`CameraPath`] are left alone until the path has finished.
+pub fn follow_system(world: &mut hecs::World, dt: f32) {
+    let targets: Vec<(String, DVec3)> = world
+        .query::<(&Label, &EntityTransform)>()
+        .iter()
+        .map(|(entity, (label, transform))| {
+            (
+                label.to_string(),
+                transform.propagate(world, entity).position,
+            )
+        })
+        .collect();
+
+    for (_, (camera, component, follow)) in world
+        .query_mut::<(&mut Camera, &CameraComponent, &CameraFollowTarget)>()
+        .without::<&CameraPathPlayback>()
+    {
+        if matches!(
+            component.camera_type,
+            CameraType::Debug | CameraType::Cinematic
+        ) {
+            continue;
+        }
+
+        let Some((_, target_position)) = targets
+            .iter()
+            .find(|(label, _)| *label == follow.target_label)
+        else {
+            log_once::warn_once!(
+                "Camera follow target '{}' does not exist",
+                follow.target_label
+            );
+            continue;
+        };
+
+        // scaled by dt so the lag is the same at any frame rate, tuned for 60 fps
+        let smoothing = follow.smoothing.clamp(0.0, 1.0) as f64;
+        let t = 1.0 - (1.0 - smoothing).powf(dt as f64 * 60.0);
+        let desired = *target_position + follow.offset;
+        camera.eye = camera.eye.lerp(desired, t);
+        camera.target = *target_position;
+    }
+}
+
+/// Looks up a camera path by name in the loaded scenes.
+pub fn find_camera_path(name: &str) -> Option<CameraPath> {
+    SCENES
+        .read()
+        .iter()
+        .find_map(|scene| scene.camera_paths.get(name).cloned())
+}
+
+/// Returns the camera that scripts should drive, preferring the starting camera and
+/// never picking the debug camera.
+pub fn scripted_camera(world: &hecs::World) -> Option<hecs::Entity> {
+    let mut fallback = None;
+    for (entity, component) in world.query::<&CameraComponent>().iter() {
+        if matches!(component.camera_type, CameraType::Debug) {
+            continue;
+        }
+        if component.starting_camera {
+            return Some(entity);
+        }
+        fallback.get_or_insert(entity);
+    }
+    fallback
+}
@@ -1,21 +1,36 @@
-use crate::scene::SceneConfig;
+use crate::scene::{SceneConfig, SceneEntity, SceneTemplate};
 use crate::states::{
     EditorSettings, EditorTab, File, Folder, Node, RESOURCES, ResourceType, SCENES, SOURCE,
+    SerializedMeshRenderer,
 };
+use crate::camera::CameraComponent;
 use chrono::Utc;
+use dropbear_engine::asset::ASSET_REGISTRY;
+use dropbear_engine::camera::Camera;
+use dropbear_engine::lod::LodGroup;
+use dropbear_engine::sprite::SpriteRenderer;
+use dropbear_engine::utils::ResourceReference;
 use egui_dock::DockState;
+use glam::DVec3;
 use ron::ser::PrettyConfig;
 use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
 use std::fs;
 use std::path::{Path, PathBuf};
 use crate::runtime::RuntimeSettings;
 
+pub mod migrations;
+
 /// The root config file, responsible for building and other metadata.
 ///
 /// # Location
 /// This file is {project_name}.eucp and is located at {project_dir}/{project_name}.eucp
-#[derive(Debug, Deserialize, Serialize, Default, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ProjectConfig {
+    /// The version of the file format, used to migrate projects made with older versions of the
+    /// editor. See [`migrations`].
+    #[serde(default = "migrations::unversioned")]
+    pub schema_version: u32,
     pub project_name: String,
     pub project_path: PathBuf,
     pub date_created: String,
@@ -31,6 +46,75 @@ pub struct ProjectConfig {
 
     #[serde(default)]
     pub last_opened_scene: Option<String>,
+
+    /// The scene a built game starts in. If this is `None`, the last opened scene is used
+    /// instead, see [`ProjectConfig::starting_scene`].
+    #[serde(default)]
+    pub default_scene: Option<String>,
+
+    /// User defined names for the entity layers, indexed by layer.
+    #[serde(default)]
+    pub layer_names: Vec<String>,
+
+    /// Where the editor's debug camera was when the project was last saved.
+    #[serde(default)]
+    pub editor_camera: Option<EditorCameraState>,
+}
+
+impl Default for ProjectConfig {
+    /// An empty config at the current schema version, so it is not migrated when it is next
+    /// loaded.
+    fn default() -> Self {
+        Self {
+            schema_version: migrations::CURRENT_SCHEMA_VERSION,
+            project_name: String::new(),
+            project_path: PathBuf::new(),
+            date_created: String::new(),
+            date_last_accessed: String::new(),
+            dock_layout: None,
+            editor_settings: Default::default(),
+            runtime_settings: Default::default(),
+            last_opened_scene: None,
+            default_scene: None,
+            layer_names: Vec::new(),
+            editor_camera: None,
+        }
+    }
+}
+
+/// The position and movement settings of the editor's debug camera, so it can be restored when the
+/// project is reopened.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub struct EditorCameraState {
+    pub eye: DVec3,
+    pub target: DVec3,
+    pub yaw: f64,
+    pub pitch: f64,
+    pub speed: f64,
+}
+
+impl EditorCameraState {
+    /// Captures the state of a debug camera.
+    pub fn capture(camera: &Camera, component: &CameraComponent) -> Self {
+        Self {
+            eye: camera.eye,
+            target: camera.target,
+            yaw: camera.yaw,
+            pitch: camera.pitch,
+            speed: component.settings.speed,
+        }
+    }
+
+    /// Moves a debug camera back to the captured state.
+    pub fn apply(&self, camera: &mut Camera, component: &mut CameraComponent) {
+        camera.eye = self.eye;
+        camera.target = self.target;
+        camera.yaw = self.yaw;
+        camera.pitch = self.pitch;
+        component.settings.speed = self.speed;
+        camera.settings.speed = self.speed;
+        camera.update_view_proj();
+    }
 }
 
 impl ProjectConfig {
@@ -41,6 +125,7 @@ impl ProjectConfig {
         let date_last_accessed = format!("{}", Utc::now().format("%Y-%m-%d %H:%M:%S"));
 
         let mut result = Self {
+            schema_version: migrations::CURRENT_SCHEMA_VERSION,
             project_name,
             project_path: project_path.as_ref().to_path_buf(),
             date_created,
@@ -48,12 +133,55 @@ impl ProjectConfig {
             editor_settings: Default::default(),
             dock_layout: None,
             last_opened_scene: None,
+            default_scene: None,
             runtime_settings: Default::default(),
+            layer_names: Vec::new(),
+            editor_camera: None,
         };
         let _ = result.load_config_to_memory();
         result
     }
 
+    /// Picks the scene out of `scenes` that a built game starts in.
+    ///
+    /// This is the [`ProjectConfig::default_scene`] if it is set, falling back to the last
+    /// opened scene and then to the first scene by name. Returns `None` if there are no scenes.
+    pub fn starting_scene<'a>(&self, scenes: &'a [SceneConfig]) -> Option<&'a SceneConfig> {
+        let find = |name: &Option<String>| {
+            let name = name.as_deref()?;
+            let scene = scenes.iter().find(|scene| scene.scene_name == name);
+            if scene.is_none() {
+                log::warn!("Scene '{}' does not exist", name);
+            }
+            scene
+        };
+
+        find(&self.default_scene)
+            .or_else(|| find(&self.last_opened_scene))
+            .or_else(|| scenes.iter().min_by(|a, b| a.scene_name.cmp(&b.scene_name)))
+    }
+
+    /// Fills the scene made by [`ProjectConfig::new`] with the entities of `template`.
+    ///
+    /// The scene is only written to disk by the next [`ProjectConfig::write_to_all`].
+    pub fn apply_scene_template(&mut self, template: SceneTemplate) -> anyhow::Result<()> {
+        let mut scenes = SCENES.write();
+        let Some(scene) = scenes.first_mut() else {
+            anyhow::bail!("The project has no scene to apply the template to");
+        };
+        *scene = SceneConfig::from_template(template, scene.scene_name.clone(), &scene.path);
+        Ok(())
+    }
+
+    /// Fetches the display name of an entity layer, or "Layer {layer}" if it has not been named.
+    pub fn layer_name(&self, layer: u32) -> String {
+        self.layer_names
+            .get(layer as usize)
+            .filter(|name| !name.is_empty())
+            .cloned()
+            .unwrap_or_else(|| format!("Layer {}", layer))
+    }
+
     /// This function writes the [`ProjectConfig`] struct (and other PathBufs) to a file of the choice
     /// under the PathBuf path parameter.
     ///
@@ -81,7 +209,17 @@ impl ProjectConfig {
     /// * path - The root config **file** for the project
     pub fn read_from(path: impl AsRef<Path>) -> anyhow::Result<Self> {
         let ron_str = fs::read_to_string(path.as_ref())?;
-        let mut config: ProjectConfig = ron::de::from_str(ron_str.as_str())?;
+        let schema_version = migrations::schema_version(&ron_str)?;
+        let mut config: ProjectConfig = if schema_version < migrations::CURRENT_SCHEMA_VERSION {
+            log::info!(
+                "Migrating project from schema version {} to {}",
+                schema_version,
+                migrations::CURRENT_SCHEMA_VERSION
+            );
+            migrations::migrate_project(&ron_str)?
+        } else {
+            ron::de::from_str(ron_str.as_str())?
+        };
         config.project_path = path.as_ref().parent().unwrap().to_path_buf();
         log::info!("Loaded project!");
         log::debug!("Loaded config info");
@@ -97,6 +235,9 @@ impl ProjectConfig {
     pub fn load_config_to_memory(&mut self) -> anyhow::Result<()> {
         let project_root = PathBuf::from(&self.project_path);
 
+        ASSET_REGISTRY.set_texture_cache_dir(Some(project_root.join(".eucache")));
+        ASSET_REGISTRY.set_compress_textures(self.runtime_settings.compress_textures);
+
         // resource config
         match ResourceConfig::read_from(&project_root) {
             Ok(resources) => {
@@ -303,6 +444,98 @@ impl ProjectConfig {
         self.write_to(&path)?;
         Ok(())
     }
+
+    /// Checks that every file referenced by the entities of the scenes in `{project_root}/scenes`
+    /// exists in `{project_root}/resources`, returning one error per missing file.
+    ///
+    /// Scenes that can't be read are skipped, as they fail loudly once they are loaded anyway.
+    pub fn validate_asset_references(project_root: &Path) -> Vec<AssetValidationError> {
+        let mut errors = Vec::new();
+        let Ok(entries) = fs::read_dir(project_root.join("scenes")) else {
+            return errors;
+        };
+        let resources = project_root.join("resources");
+
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if !path.is_file() || path.extension().and_then(|s| s.to_str()) != Some("eucs") {
+                continue;
+            }
+
+            let scene = match SceneConfig::read_from(&path) {
+                Ok(scene) => scene,
+                Err(e) => {
+                    log::warn!("Unable to validate scene {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+
+            for entity in &scene.entities {
+                for reference in referenced_assets(entity) {
+                    // internal assets are bundled with the engine
+                    let Some(relative) = reference.relative_path() else {
+                        continue;
+                    };
+                    if relative.starts_with("internal/") {
+                        continue;
+                    }
+
+                    let missing_path = resources.join(relative);
+                    if !missing_path.exists() {
+                        errors.push(AssetValidationError {
+                            scene_name: scene.scene_name.clone(),
+                            entity_label: entity.label.to_string(),
+                            missing_path,
+                        });
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+/// A file that an entity refers to, but that doesn't exist in the project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetValidationError {
+    /// The scene the entity is in
+    pub scene_name: String,
+    pub entity_label: String,
+    pub missing_path: PathBuf,
+}
+
+impl Display for AssetValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "'{}' in scene '{}' references a missing asset: {}",
+            self.entity_label,
+            self.scene_name,
+            self.missing_path.display()
+        )
+    }
+}
+
+/// Collects the file references of every component of an entity that loads an asset.
+fn referenced_assets(entity: &SceneEntity) -> Vec<&ResourceReference> {
+    let mut references = Vec::new();
+    for component in &entity.components {
+        let component = component.as_any();
+        if let Some(renderer) = component.downcast_ref::<SerializedMeshRenderer>() {
+            references.push(&renderer.handle);
+            references.extend(
+                renderer
+                    .material_override
+                    .iter()
+                    .map(|entry| &entry.source_model),
+            );
+        } else if let Some(group) = component.downcast_ref::<LodGroup>() {
+            references.extend(group.sources.iter().map(|(_, reference)| reference));
+        } else if let Some(sprite) = component.downcast_ref::<SpriteRenderer>() {
+            references.push(&sprite.texture);
+        }
+    }
+    references
 }
 
 /// The resource config.
@@ -468,3 +701,85 @@ fn collect_nodes(
     }
     nodes
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scenes(names: &[&str]) -> Vec<SceneConfig> {
+        names
+            .iter()
+            .map(|name| SceneConfig::new(name.to_string(), format!("scenes/{}.eucs", name)))
+            .collect()
+    }
+
+    fn project(default_scene: Option<&str>, last_opened_scene: Option<&str>) -> ProjectConfig {
+        ProjectConfig {
+            default_scene: default_scene.map(str::to_string),
+            last_opened_scene: last_opened_scene.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn starting_scene_name(project: &ProjectConfig, scenes: &[SceneConfig]) -> Option<String> {
+        project
+            .starting_scene(scenes)
+            .map(|scene| scene.scene_name.clone())
+    }
+
+    #[test]
+    fn test_default_is_current_schema_version() {
+        assert_eq!(
+            ProjectConfig::default().schema_version,
+            migrations::CURRENT_SCHEMA_VERSION
+        );
+    }
+
+    #[test]
+    fn test_starting_scene_uses_default_scene() {
+        let scenes = scenes(&["Menu", "Level 1", "Level 2"]);
+        let project = project(Some("Level 2"), Some("Level 1"));
+
+        assert_eq!(
+            starting_scene_name(&project, &scenes).as_deref(),
+            Some("Level 2")
+        );
+    }
+
+    #[test]
+    fn test_starting_scene_falls_back_to_last_opened_scene() {
+        let scenes = scenes(&["Menu", "Level 1"]);
+        let project = project(None, Some("Menu"));
+
+        assert_eq!(
+            starting_scene_name(&project, &scenes).as_deref(),
+            Some("Menu")
+        );
+    }
+
+    #[test]
+    fn test_starting_scene_falls_back_to_first_scene() {
+        let scenes = scenes(&["Menu", "Level 2", "Level 1"]);
+
+        assert_eq!(
+            starting_scene_name(&project(None, None), &scenes).as_deref(),
+            Some("Level 1")
+        );
+    }
+
+    #[test]
+    fn test_starting_scene_skips_missing_scenes() {
+        let scenes = scenes(&["Menu", "Level 1"]);
+        let project = project(Some("Deleted"), Some("Also Deleted"));
+
+        assert_eq!(
+            starting_scene_name(&project, &scenes).as_deref(),
+            Some("Level 1")
+        );
+    }
+
+    #[test]
+    fn test_starting_scene_without_scenes() {
+        assert!(project(Some("Level 1"), None).starting_scene(&[]).is_none());
+    }
+}
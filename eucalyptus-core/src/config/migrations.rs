@@ -0,0 +1,138 @@
+//! Upgrades `.eucp` project files written by older versions of the editor.
+//!
+//! Every [`ProjectConfig`] stores the `schema_version` it was written with. When a project with an
+//! older version is opened, each migration from its version onwards is run in order on the RON
+//! text, before it is deserialised. The project is then saved again with
+//! [`CURRENT_SCHEMA_VERSION`].
+//!
+//! To add a new version, bump [`CURRENT_SCHEMA_VERSION`], keep a copy of the old layout of
+//! [`ProjectConfig`] in this module and add a migration to [`MIGRATIONS`], such as
+//! `(1, migrate_v1_to_v2)`. The migration reads the file into the old struct, converts it into the
+//! new one and writes it back out, so enum variants and every other value come through as they
+//! were.
+
+use crate::config::ProjectConfig;
+use serde::Deserialize;
+
+/// The schema version of project files written by this version of the editor.
+pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+/// Takes a project file of one schema version and upgrades it to the next.
+pub type MigrationFn = fn(&str) -> anyhow::Result<String>;
+
+/// Every known migration, as (the version it migrates from, the migration), in ascending order.
+pub const MIGRATIONS: &[(u32, MigrationFn)] = &[];
+
+/// The schema version of projects written before the version was stored.
+pub(crate) fn unversioned() -> u32 {
+    1
+}
+
+/// The field needed to work out how to migrate a project file.
+#[derive(Deserialize)]
+#[serde(rename = "ProjectConfig")]
+struct ProjectHeader {
+    #[serde(default = "unversioned")]
+    schema_version: u32,
+}
+
+/// The schema version a project file was written with.
+pub fn schema_version(ron_str: &str) -> anyhow::Result<u32> {
+    Ok(ron::de::from_str::<ProjectHeader>(ron_str)?.schema_version)
+}
+
+/// Runs every migration from `from_version` up to [`CURRENT_SCHEMA_VERSION`] in sequence.
+pub fn migrate(ron_str: &str, from_version: u32) -> anyhow::Result<String> {
+    let mut ron_str = ron_str.to_string();
+    for (version, migration) in MIGRATIONS {
+        if *version >= from_version && *version < CURRENT_SCHEMA_VERSION {
+            log::debug!("Migrating project from schema version {}", version);
+            ron_str = migration(&ron_str)?;
+        }
+    }
+    Ok(ron_str)
+}
+
+/// Reads a project file written with an older schema version, migrating it to the current one.
+pub fn migrate_project(ron_str: &str) -> anyhow::Result<ProjectConfig> {
+    let from_version = schema_version(ron_str)?;
+    let mut config: ProjectConfig = ron::de::from_str(&migrate(ron_str, from_version)?)?;
+    config.schema_version = CURRENT_SCHEMA_VERSION;
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::states::EditorTab;
+    use dropbear_engine::tone_mapping::ToneMappingOperator;
+    use egui_dock::DockState;
+    use ron::ser::PrettyConfig;
+
+    /// A project saved before `schema_version` was stored.
+    const UNVERSIONED_PROJECT: &str = r#"
+ProjectConfig(
+    project_name: "Sample",
+    project_path: "/projects/Sample",
+    date_created: "2025-06-01 12:00:00 UTC",
+    date_last_accessed: "2025-06-02 12:00:00 UTC",
+    dock_layout: None,
+    editor_settings: (
+        is_debug_menu_shown: true,
+        preferred_monitor: Some(1),
+        snap: (
+            position_snap: Some(0.5),
+            rotation_snap: None,
+            scale_snap: None,
+        ),
+        tone_mapping: Reinhard,
+    ),
+    last_opened_scene: Some("Level 1"),
+)
+"#;
+
+    #[test]
+    fn test_unversioned_project_is_version_one() {
+        assert_eq!(schema_version(UNVERSIONED_PROJECT).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_migrate_unversioned_project() {
+        let config = migrate_project(UNVERSIONED_PROJECT).unwrap();
+
+        assert_eq!(config.schema_version, CURRENT_SCHEMA_VERSION);
+        assert_eq!(config.project_name, "Sample");
+        assert_eq!(config.last_opened_scene.as_deref(), Some("Level 1"));
+        assert!(config.editor_settings.is_debug_menu_shown);
+        assert_eq!(config.editor_settings.preferred_monitor, Some(1));
+        assert_eq!(config.editor_settings.snap.position_snap, Some(0.5));
+        assert_eq!(
+            config.editor_settings.tone_mapping,
+            ToneMappingOperator::Reinhard
+        );
+    }
+
+    #[test]
+    fn test_migrate_keeps_dock_layout() {
+        let config = ProjectConfig {
+            dock_layout: Some(DockState::new(vec![
+                EditorTab::Viewport,
+                EditorTab::Plugin(2),
+            ])),
+            ..Default::default()
+        };
+        let ron_str = ron::ser::to_string_pretty(&config, PrettyConfig::default())
+            .unwrap()
+            .replace(&format!("schema_version: {},", config.schema_version), "");
+        assert_eq!(schema_version(&ron_str).unwrap(), 1);
+
+        let migrated = migrate_project(&ron_str).unwrap();
+        let tabs: Vec<EditorTab> = migrated
+            .dock_layout
+            .unwrap()
+            .iter_all_tabs()
+            .map(|(_, tab)| tab.clone())
+            .collect();
+        assert_eq!(tabs, vec![EditorTab::Viewport, EditorTab::Plugin(2)]);
+    }
+}
@@ -0,0 +1,231 @@
+//! Works out which entities started or stopped touching, for the collision callbacks of scripts.
+//!
+//! There is no physics engine yet, so contacts come from overlapping world space bounding boxes
+//! of [`MeshRenderer`]s rather than from a physics step.
+
+use crate::scripting::{CollisionEvent, CollisionPhase};
+use crate::states::Script;
+use dropbear_engine::entity::MeshRenderer;
+use dropbear_engine::picking::{Aabb, world_bounds};
+use glam::Vec3;
+use hecs::{Entity, World};
+use std::collections::HashMap;
+
+/// Remembers which pairs of entities were touching, so only changes are reported.
+#[derive(Debug, Default)]
+pub struct ContactTracker {
+    /// Every touching pair, with the lower entity first, and the last normal between them
+    touching: HashMap<(Entity, Entity), Vec3>,
+}
+
+impl ContactTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compares the bounds of every entity with a [`Script`] against every other entity with a
+    /// [`MeshRenderer`], returning an event for each pair that started or stopped touching since
+    /// the last call.
+    ///
+    /// Entities without a script are still checked against scripted ones, but never against
+    /// each other, as nothing could react to them.
+    pub fn step(&mut self, world: &World) -> Vec<CollisionEvent> {
+        let mut bounds = Vec::new();
+        for (entity, (renderer, script)) in world.query::<(&MeshRenderer, Option<&Script>)>().iter()
+        {
+            if let Some(aabb) = world_bounds(renderer) {
+                bounds.push((entity, aabb, script.is_some()));
+            }
+        }
+
+        self.step_bounds(&bounds)
+    }
+
+    /// Does the work of [`ContactTracker::step`] with the world space bounds of each entity, and
+    /// whether it has a script.
+    fn step_bounds(&mut self, bounds: &[(Entity, Aabb, bool)]) -> Vec<CollisionEvent> {
+        let mut touching = HashMap::new();
+        for (i, (entity_a, bounds_a, scripted_a)) in bounds.iter().enumerate() {
+            for (entity_b, bounds_b, scripted_b) in &bounds[i + 1..] {
+                if !scripted_a && !scripted_b {
+                    continue;
+                }
+                let Some(normal) = contact_normal(bounds_a, bounds_b) else {
+                    continue;
+                };
+                // the pair is stored the same way round every frame, with the normal to match
+                if entity_a.to_bits() < entity_b.to_bits() {
+                    touching.insert((*entity_a, *entity_b), normal);
+                } else {
+                    touching.insert((*entity_b, *entity_a), -normal);
+                }
+            }
+        }
+
+        let mut events = Vec::new();
+        for (&(entity_a, entity_b), &normal) in &touching {
+            if !self.touching.contains_key(&(entity_a, entity_b)) {
+                events.push(event(entity_a, entity_b, normal, CollisionPhase::Enter));
+            }
+        }
+        for (&(entity_a, entity_b), &normal) in &self.touching {
+            if !touching.contains_key(&(entity_a, entity_b)) {
+                events.push(event(entity_a, entity_b, normal, CollisionPhase::Exit));
+            }
+        }
+
+        self.touching = touching;
+        events
+    }
+
+    /// Forgets every contact, such as when play mode starts again.
+    pub fn clear(&mut self) {
+        self.touching.clear();
+    }
+}
+
+fn event(
+    entity_a: Entity,
+    entity_b: Entity,
+    normal: Vec3,
+    phase: CollisionPhase,
+) -> CollisionEvent {
+    CollisionEvent {
+        entity_a,
+        entity_b,
+        normal,
+        impulse: 0.0,
+        phase,
+    }
+}
+
+/// The normal pointing from `a` to `b` if the boxes overlap, along the axis they overlap the least
+/// on.
+fn contact_normal(a: &Aabb, b: &Aabb) -> Option<Vec3> {
+    let overlap = a.max.min(b.max) - a.min.max(b.min);
+    if overlap.min_element() < 0.0 {
+        return None;
+    }
+
+    let axis = if overlap.x <= overlap.y && overlap.x <= overlap.z {
+        Vec3::X
+    } else if overlap.y <= overlap.z {
+        Vec3::Y
+    } else {
+        Vec3::Z
+    };
+    if (b.center() - a.center()).dot(axis) < 0.0 {
+        Some(-axis)
+    } else {
+        Some(axis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cube(center: Vec3) -> Aabb {
+        Aabb {
+            min: center - Vec3::splat(0.5),
+            max: center + Vec3::splat(0.5),
+        }
+    }
+
+    /// Two entities, where the first sorts before the second as in [`ContactTracker`]'s pairs.
+    fn two_entities() -> (Entity, Entity) {
+        let mut world = World::new();
+        (world.spawn(()), world.spawn(()))
+    }
+
+    #[test]
+    fn test_enter_when_boxes_overlap() {
+        let (a, b) = two_entities();
+        let mut tracker = ContactTracker::new();
+
+        let events = tracker.step_bounds(&[
+            (a, cube(Vec3::ZERO), true),
+            (b, cube(Vec3::new(0.8, 0.0, 0.0)), false),
+        ]);
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].phase, CollisionPhase::Enter);
+        assert_eq!((events[0].entity_a, events[0].entity_b), (a, b));
+        assert_eq!(events[0].normal, Vec3::X);
+    }
+
+    #[test]
+    fn test_no_events_while_staying_in_contact() {
+        let (a, b) = two_entities();
+        let mut tracker = ContactTracker::new();
+        tracker.step_bounds(&[
+            (a, cube(Vec3::ZERO), true),
+            (b, cube(Vec3::new(0.8, 0.0, 0.0)), true),
+        ]);
+
+        // still overlapping after moving a little
+        let events = tracker.step_bounds(&[
+            (a, cube(Vec3::ZERO), true),
+            (b, cube(Vec3::new(0.6, 0.0, 0.0)), true),
+        ]);
+
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_exit_when_boxes_separate() {
+        let (a, b) = two_entities();
+        let mut tracker = ContactTracker::new();
+        tracker.step_bounds(&[
+            (a, cube(Vec3::ZERO), true),
+            (b, cube(Vec3::new(0.0, 0.8, 0.0)), false),
+        ]);
+
+        let events = tracker.step_bounds(&[
+            (a, cube(Vec3::ZERO), true),
+            (b, cube(Vec3::new(0.0, 2.0, 0.0)), false),
+        ]);
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].phase, CollisionPhase::Exit);
+        assert_eq!((events[0].entity_a, events[0].entity_b), (a, b));
+        // the normal of the last frame they were touching
+        assert_eq!(events[0].normal, Vec3::Y);
+    }
+
+    #[test]
+    fn test_exit_when_despawned_while_touching() {
+        let (a, b) = two_entities();
+        let mut tracker = ContactTracker::new();
+        tracker.step_bounds(&[
+            (a, cube(Vec3::ZERO), true),
+            (b, cube(Vec3::new(0.8, 0.0, 0.0)), true),
+        ]);
+
+        // b no longer has bounds, as it was despawned
+        let events = tracker.step_bounds(&[(a, cube(Vec3::ZERO), true)]);
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].phase, CollisionPhase::Exit);
+        assert_eq!((events[0].entity_a, events[0].entity_b), (a, b));
+
+        assert!(
+            tracker
+                .step_bounds(&[(a, cube(Vec3::ZERO), true)])
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_unscripted_entities_are_not_checked_against_each_other() {
+        let (a, b) = two_entities();
+        let mut tracker = ContactTracker::new();
+
+        let events = tracker.step_bounds(&[
+            (a, cube(Vec3::ZERO), false),
+            (b, cube(Vec3::new(0.8, 0.0, 0.0)), false),
+        ]);
+
+        assert!(events.is_empty());
+    }
+}
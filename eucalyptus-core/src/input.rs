@@ -1,12 +1,24 @@
 use dropbear_engine::gilrs::{Button, GamepadId};
+use dropbear_engine::input::{InputListener, InputSource};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
 use std::sync::Arc;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, VecDeque},
     time::{Duration, Instant},
 };
 use winit::window::Window;
 use winit::{event::MouseButton, keyboard::KeyCode};
 
+pub use dropbear_engine::input::InputEvent;
+
+/// The file extension of input recordings, saved by [`InputRecorder::save`].
+pub const INPUT_RECORDING_EXTENSION: &str = "euinput";
+
+/// Bumped whenever the layout of an input recording changes, so old files are rejected instead
+/// of being misread.
+const INPUT_RECORDING_VERSION: u32 = 1;
+
 #[derive(Clone, Debug)]
 pub struct InputState {
     pub window: Option<Arc<Window>>,
@@ -90,4 +102,157 @@ impl InputState {
     pub fn is_gamepad_connected(&self, gamepad_id: GamepadId) -> bool {
         self.connected_gamepads.contains(&gamepad_id)
     }
+
+    /// Looks up a connected gamepad from the numeric id handed out to scripts.
+    pub fn gamepad_from_id(&self, id: usize) -> Option<GamepadId> {
+        self.connected_gamepads
+            .iter()
+            .copied()
+            .find(|gamepad| usize::from(*gamepad) == id)
+    }
+}
+
+/// An [`InputEvent`] along with how long after the start of the recording it happened.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RecordedInput {
+    pub time: Duration,
+    pub event: InputEvent,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InputRecording {
+    version: u32,
+    events: Vec<RecordedInput>,
+}
+
+/// Records every event handled by the input manager, so the session can be replayed with an
+/// [`InputPlayback`].
+///
+/// The recorder is added to the manager as a listener:
+///
+/// ```ignore
+/// let recorder = Rc::new(RwLock::new(InputRecorder::new()));
+/// app.input_manager_mut().add_listener("recorder", recorder.clone());
+/// // ...
+/// recorder.read().save(Path::new("bug.euinput"))?;
+/// ```
+pub struct InputRecorder {
+    start: Instant,
+    events: Vec<RecordedInput>,
+}
+
+impl Default for InputRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InputRecorder {
+    /// Creates a recorder, with its timestamps starting from now.
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            events: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, event: InputEvent) {
+        self.events.push(RecordedInput {
+            time: self.start.elapsed(),
+            event,
+        });
+    }
+
+    pub fn events(&self) -> &[RecordedInput] {
+        &self.events
+    }
+
+    /// Writes the recorded events to a binary `.euinput` file.
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let recording = InputRecording {
+            version: INPUT_RECORDING_VERSION,
+            events: self.events.clone(),
+        };
+        let bytes = bincode::serde::encode_to_vec(&recording, bincode::config::standard())?;
+        std::fs::write(path, bytes)?;
+        log::info!(
+            "Saved {} input events to {}",
+            self.events.len(),
+            path.display()
+        );
+        Ok(())
+    }
+}
+
+impl InputListener for InputRecorder {
+    fn on_input(&mut self, event: &InputEvent) {
+        self.record(*event);
+    }
+}
+
+/// Replays events recorded by an [`InputRecorder`] at the same times they happened, in place of
+/// input from the window.
+///
+/// ```ignore
+/// let playback = InputPlayback::from_file(Path::new("bug.euinput"))?;
+/// app.input_manager_mut().set_source(Some(Box::new(playback)));
+/// ```
+pub struct InputPlayback {
+    /// Set on the first poll, so time spent loading isn't counted
+    start: Option<Instant>,
+    events: VecDeque<RecordedInput>,
+}
+
+impl InputPlayback {
+    /// Reads a recording saved by [`InputRecorder::save`].
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let bytes = std::fs::read(path)?;
+        let (recording, _): (InputRecording, usize) =
+            bincode::serde::decode_from_slice(&bytes, bincode::config::standard())?;
+        if recording.version != INPUT_RECORDING_VERSION {
+            anyhow::bail!(
+                "{} is an input recording of version {}, expected version {}",
+                path.display(),
+                recording.version,
+                INPUT_RECORDING_VERSION
+            );
+        }
+
+        log::info!(
+            "Loaded {} input events from {}",
+            recording.events.len(),
+            path.display()
+        );
+        Ok(Self::from_events(recording.events))
+    }
+
+    pub fn from_events(events: impl IntoIterator<Item = RecordedInput>) -> Self {
+        Self {
+            start: None,
+            events: events.into_iter().collect(),
+        }
+    }
+
+    /// The number of events that have not been replayed yet.
+    pub fn remaining(&self) -> usize {
+        self.events.len()
+    }
+}
+
+impl InputSource for InputPlayback {
+    fn poll(&mut self) -> Vec<InputEvent> {
+        let elapsed = self.start.get_or_insert_with(Instant::now).elapsed();
+
+        let mut due = Vec::new();
+        while self.events.front().is_some_and(|e| e.time <= elapsed) {
+            if let Some(recorded) = self.events.pop_front() {
+                due.push(recorded.event);
+            }
+        }
+        due
+    }
+
+    fn is_finished(&self) -> bool {
+        self.events.is_empty()
+    }
 }
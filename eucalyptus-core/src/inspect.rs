@@ -0,0 +1,119 @@
+//! Editor UI for plain data types, generated with `#[derive(Inspectable)]` from `dropbear_macro`.
+
+use glam::{DVec3, Vec3};
+use std::ops::RangeInclusive;
+
+/// A type that can draw its own fields in the editor's inspector.
+///
+/// Implement it by hand when a field needs a widget that [`InspectValue`] doesn't have, such as
+/// a combo box, and otherwise use `#[derive(Inspectable)]`.
+pub trait Inspectable {
+    /// Draws the fields of the value, returning true if any of them were changed.
+    fn inspect(&mut self, ui: &mut egui::Ui) -> bool;
+}
+
+/// Settings for how a single field is edited, set with the `#[inspect(..)]` attribute.
+#[derive(Debug, Clone, Default)]
+pub struct InspectOptions {
+    /// The inclusive range a number is clamped to.
+    pub range: Option<RangeInclusive<f64>>,
+    /// How much a number changes per pixel dragged.
+    pub speed: Option<f64>,
+    /// Edits a `[f32; 3]` or `[f32; 4]` with a colour picker instead of drag values.
+    pub color: bool,
+}
+
+/// A field type that can be edited by a derived [`Inspectable`].
+pub trait InspectValue {
+    /// Draws the widget for the value, returning true if it was changed.
+    fn inspect_value(&mut self, ui: &mut egui::Ui, options: &InspectOptions) -> bool;
+}
+
+fn drag_value<T: egui::emath::Numeric>(
+    value: &mut T,
+    ui: &mut egui::Ui,
+    options: &InspectOptions,
+) -> bool {
+    let mut drag = egui::DragValue::new(value);
+    if let Some(speed) = options.speed {
+        drag = drag.speed(speed);
+    }
+    if let Some(range) = options.range.clone() {
+        drag = drag.range(range);
+    }
+    ui.add(drag).changed()
+}
+
+macro_rules! impl_numeric {
+    ($($ty:ty),*) => {
+        $(
+            impl InspectValue for $ty {
+                fn inspect_value(&mut self, ui: &mut egui::Ui, options: &InspectOptions) -> bool {
+                    drag_value(self, ui, options)
+                }
+            }
+        )*
+    };
+}
+
+impl_numeric!(f32, f64, i8, i16, i32, i64, u8, u16, u32, u64, usize);
+
+impl InspectValue for bool {
+    fn inspect_value(&mut self, ui: &mut egui::Ui, _options: &InspectOptions) -> bool {
+        ui.checkbox(self, "").changed()
+    }
+}
+
+impl InspectValue for String {
+    fn inspect_value(&mut self, ui: &mut egui::Ui, _options: &InspectOptions) -> bool {
+        ui.text_edit_singleline(self).changed()
+    }
+}
+
+impl InspectValue for [f32; 3] {
+    fn inspect_value(&mut self, ui: &mut egui::Ui, options: &InspectOptions) -> bool {
+        if options.color {
+            return ui.color_edit_button_rgb(self).changed();
+        }
+
+        let mut changed = false;
+        for value in self.iter_mut() {
+            changed |= drag_value(value, ui, options);
+        }
+        changed
+    }
+}
+
+impl InspectValue for [f32; 4] {
+    fn inspect_value(&mut self, ui: &mut egui::Ui, options: &InspectOptions) -> bool {
+        if options.color {
+            return ui.color_edit_button_rgba_unmultiplied(self).changed();
+        }
+
+        let mut changed = false;
+        for value in self.iter_mut() {
+            changed |= drag_value(value, ui, options);
+        }
+        changed
+    }
+}
+
+impl InspectValue for Vec3 {
+    fn inspect_value(&mut self, ui: &mut egui::Ui, options: &InspectOptions) -> bool {
+        let mut changed = false;
+        changed |= drag_value(&mut self.x, ui, options);
+        changed |= drag_value(&mut self.y, ui, options);
+        changed |= drag_value(&mut self.z, ui, options);
+        changed
+    }
+}
+
+impl InspectValue for DVec3 {
+    fn inspect_value(&mut self, ui: &mut egui::Ui, options: &InspectOptions) -> bool {
+        let mut changed = false;
+        changed |= drag_value(&mut self.x, ui, options);
+        changed |= drag_value(&mut self.y, ui, options);
+        changed |= drag_value(&mut self.z, ui, options);
+        changed
+    }
+}
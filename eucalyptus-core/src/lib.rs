@@ -1,8 +1,10 @@
 pub mod camera;
 pub mod component;
 pub mod config;
+pub mod contacts;
 pub mod hierarchy;
 pub mod input;
+pub mod inspect;
 pub mod logging;
 pub mod ptr;
 pub mod result;
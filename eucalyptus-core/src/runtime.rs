@@ -1,5 +1,10 @@
 use crate::scene::{SceneConfig};
-use crate::states::{PROJECT, SCENES};
+use crate::states::{MaterialShader, PROJECT, SCENES};
+use parking_lot::RwLock;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
 
 /// The settings of a project in its runtime. 
 /// 
@@ -14,12 +19,18 @@ use crate::states::{PROJECT, SCENES};
     Debug,
     Clone,
 )]
-pub struct RuntimeSettings {}
+pub struct RuntimeSettings {
+    /// Compresses imported textures to BC7, cached under `<project>/.eucache`.
+    #[serde(default)]
+    pub compress_textures: bool,
+}
 
 impl RuntimeSettings {
     /// Creates a new [`RuntimeSettings`] config. 
     pub fn new() -> Self {
-        Self { }
+        Self {
+            compress_textures: false,
+        }
     }
 }
 
@@ -47,6 +58,17 @@ pub struct RuntimeProjectConfig {
     
     #[bincode(with_serde)]
     pub scenes: Vec<SceneConfig>,
+
+    /// The name of the scene in `scenes` that the game starts in
+    #[bincode(with_serde)]
+    #[serde(default)]
+    pub default_scene: String,
+
+    /// The source of every custom shader used by a [`MaterialShader`] in the scenes, by its path
+    /// relative to the project root
+    #[bincode(with_serde)]
+    #[serde(default)]
+    pub shaders: HashMap<PathBuf, String>,
 }
 
 impl RuntimeProjectConfig {
@@ -58,7 +80,154 @@ impl RuntimeProjectConfig {
         Self {
             project_name: project.project_name.clone(),
             runtime_settings: project.runtime_settings.clone(),
+            default_scene: project
+                .starting_scene(&scenes)
+                .map(|scene| scene.scene_name.clone())
+                .unwrap_or_default(),
             scenes: scenes.to_vec(),
+            shaders: collect_shader_sources(&project.project_path, &scenes),
+        }
+    }
+
+    /// Looks up a scene by name, such as the target of a [`SceneCommand::SwitchScene`].
+    ///
+    /// [`SceneCommand::SwitchScene`]: dropbear_engine::scene::SceneCommand::SwitchScene
+    pub fn scene(&self, name: &str) -> Option<&SceneConfig> {
+        self.scenes.iter().find(|scene| scene.scene_name == name)
+    }
+
+    /// The scene the game starts in, see [`RuntimeProjectConfig::default_scene`].
+    pub fn starting_scene(&self) -> anyhow::Result<&SceneConfig> {
+        self.scene(&self.default_scene).ok_or_else(|| {
+            anyhow::anyhow!(
+                "The starting scene '{}' is not in the package",
+                self.default_scene
+            )
+        })
+    }
+}
+
+/// Reads the source of every custom shader used by the scenes, so they can be packaged.
+///
+/// Shaders that can't be read are skipped with a warning.
+pub fn collect_shader_sources(
+    project_root: &Path,
+    scenes: &[SceneConfig],
+) -> HashMap<PathBuf, String> {
+    let mut shaders = HashMap::new();
+    let paths = scenes
+        .iter()
+        .flat_map(|scene| &scene.entities)
+        .flat_map(|entity| &entity.components)
+        .filter_map(|component| component.as_any().downcast_ref::<MaterialShader>())
+        .filter_map(|shader| shader.shader_path.clone());
+
+    for path in paths {
+        if shaders.contains_key(&path) {
+            continue;
+        }
+        match std::fs::read_to_string(project_root.join(&path)) {
+            Ok(source) => {
+                shaders.insert(path, source);
+            }
+            Err(e) => log::warn!("Unable to read shader {}: {}", path.display(), e),
+        }
+    }
+    shaders
+}
+
+static RESOURCE_REGISTRY: LazyLock<ResourceRegistry> = LazyLock::new(ResourceRegistry::new);
+
+/// Data that is kept around for the whole runtime, so it can be shared between scenes.
+///
+/// Rust code stores resources by type, while scripts can only reach the JSON values, which are
+/// stored by key.
+pub struct ResourceRegistry {
+    resources: Arc<RwLock<HashMap<TypeId, Box<dyn Any + Send + Sync>>>>,
+    values: Arc<RwLock<HashMap<String, serde_json::Value>>>,
+}
+
+impl ResourceRegistry {
+    fn new() -> Self {
+        Self {
+            resources: Arc::new(RwLock::new(HashMap::new())),
+            values: Arc::new(RwLock::new(HashMap::new())),
         }
     }
-}
\ No newline at end of file
+
+    /// Fetches the registry shared by the whole runtime.
+    pub fn global() -> &'static ResourceRegistry {
+        &RESOURCE_REGISTRY
+    }
+
+    /// Stores a resource, replacing any existing resource of the same type.
+    pub fn insert<T: 'static + Send + Sync>(&self, value: T) {
+        self.resources
+            .write()
+            .insert(TypeId::of::<T>(), Box::new(Arc::new(value)));
+    }
+
+    /// Fetches the resource of type `T`, if one has been inserted.
+    pub fn get<T: 'static + Send + Sync>(&self) -> Option<Arc<T>> {
+        self.resources
+            .read()
+            .get(&TypeId::of::<T>())
+            .and_then(|resource| resource.downcast_ref::<Arc<T>>())
+            .cloned()
+    }
+
+    /// Removes the resource of type `T`, returning it if one was inserted.
+    pub fn remove<T: 'static + Send + Sync>(&self) -> Option<Arc<T>> {
+        self.resources
+            .write()
+            .remove(&TypeId::of::<T>())
+            .and_then(|resource| resource.downcast::<Arc<T>>().ok())
+            .map(|resource| *resource)
+    }
+
+    /// Stores a JSON value under `key`, which scripts can read back with [`Self::get_value`].
+    pub fn set_value(&self, key: impl Into<String>, value: serde_json::Value) {
+        self.values.write().insert(key.into(), value);
+    }
+
+    /// Fetches the JSON value stored under `key`.
+    pub fn get_value(&self, key: &str) -> Option<serde_json::Value> {
+        self.values.read().get(key).cloned()
+    }
+
+    /// Removes every resource and value, for example when the runtime is restarted.
+    pub fn clear(&self) {
+        self.resources.write().clear();
+        self.values.write().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(default_scene: &str) -> RuntimeProjectConfig {
+        RuntimeProjectConfig {
+            project_name: "Sample".to_string(),
+            runtime_settings: RuntimeSettings::new(),
+            scenes: ["Menu", "Level 1"]
+                .iter()
+                .map(|name| SceneConfig::new(name.to_string(), format!("scenes/{}.eucs", name)))
+                .collect(),
+            default_scene: default_scene.to_string(),
+            shaders: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_starting_scene() {
+        let package = package("Level 1");
+        assert_eq!(package.starting_scene().unwrap().scene_name, "Level 1");
+    }
+
+    #[test]
+    fn test_missing_starting_scene() {
+        assert!(package("Deleted").starting_scene().is_err());
+        assert!(package("").starting_scene().is_err());
+    }
+}
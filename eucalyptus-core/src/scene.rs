@@ -1,16 +1,21 @@
-use crate::camera::{CameraComponent, CameraType};
+use crate::camera::{CameraComponent, CameraFollowTarget, CameraPath, CameraType};
 use crate::hierarchy::{Children, Parent, SceneHierarchy};
 use crate::states::{
-    Camera3D, Label, Light, ModelProperties, PROJECT, Script,
+    Camera3D, Label, Light, LoadPhase, ModelProperties, PROJECT, Script,
     SerializedMeshRenderer, WorldLoadingStatus,
 };
 use crate::utils::ResolveReference;
 use dropbear_engine::asset::ASSET_REGISTRY;
-use dropbear_engine::camera::{Camera, CameraBuilder};
-use dropbear_engine::entity::{EntityTransform, MeshRenderer, Transform};
+use dropbear_engine::camera::{Camera, CameraBuilder, ProjectionKind};
+use dropbear_engine::entity::{EntityLayer, EntityTransform, MeshRenderer, Transform};
+use dropbear_engine::future::CancellationToken;
 use dropbear_engine::graphics::SharedGraphicsContext;
+use dropbear_engine::light_probe::LightProbe;
 use dropbear_engine::lighting::{Light as EngineLight, LightComponent};
+use dropbear_engine::lod::LodGroup;
 use dropbear_engine::model::Model;
+use dropbear_engine::morph::MorphWeights;
+use dropbear_engine::particles::ParticleEmitter;
 use dropbear_engine::utils::{ResourceReference, ResourceReferenceType};
 use dropbear_traits::SerializableComponent;
 use dropbear_traits::registry::ComponentRegistry;
@@ -18,12 +23,16 @@ use glam::{DQuat, DVec3};
 use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
 use ron::ser::PrettyConfig;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::mpsc::UnboundedSender;
 
+/// The steps of [`SceneConfig::load_into_world`] after the scene's own entities, which are the
+/// default light and, in the editor, the viewport camera.
+const DEFAULT_ENTITY_STEPS: usize = if cfg!(feature = "editor") { 2 } else { 1 };
+
 #[derive(Default, Debug, Serialize, Deserialize, Clone)]
 pub struct SceneEntity {
     #[serde(default)]
@@ -71,9 +80,39 @@ impl SceneSettings {
     }
 }
 
+/// The starting camera of a [`SceneTemplate`], where `rotation` turns the +Z axis to face where
+/// the camera looks.
+fn template_camera(position: DVec3, rotation: DQuat, projection: ProjectionKind) -> SceneEntity {
+    let camera = Camera3D {
+        label: "Main Camera".to_string(),
+        transform: Transform {
+            position,
+            rotation,
+            ..Default::default()
+        },
+        projection,
+        camera_type: CameraType::Player,
+        starting_camera: true,
+        ..Default::default()
+    };
+
+    SceneEntity {
+        label: Label::new("Main Camera"),
+        components: vec![Box::new(camera)],
+        entity_id: None,
+    }
+}
+
+/// The name of the scene an entity was loaded from, used to unload the entities of a single
+/// scene with [`SceneConfig::unload_from_world`].
+///
+/// This is never saved. Entities spawned at runtime don't have one.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SceneOrigin(pub String);
+
 /// Specifies the configuration of a scene, such as its entities, hierarchies and any settings that 
 /// may be necessary. 
-#[derive(Default, Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SceneConfig {
     #[serde(default)]
     pub scene_name: String,
@@ -87,11 +126,69 @@ pub struct SceneConfig {
     #[serde(default)]
     pub settings: SceneSettings,
 
+    /// Named camera paths that can be played back in this scene
+    #[serde(default)]
+    pub camera_paths: BTreeMap<String, CameraPath>,
+
+    /// The colour the viewport is cleared to before the scene is drawn, as linear RGBA
+    #[serde(default = "default_clear_color")]
+    pub clear_color: [f64; 4],
+
     #[serde(skip)]
     pub path: PathBuf,
 }
 
+fn default_clear_color() -> [f64; 4] {
+    SceneConfig::DEFAULT_CLEAR_COLOR
+}
+
+impl Default for SceneConfig {
+    fn default() -> Self {
+        Self::new(String::new(), PathBuf::new())
+    }
+}
+
+/// The entities a new scene starts with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SceneTemplate {
+    /// No entities at all
+    Empty,
+    /// A player camera looking at a ground plane lit by a directional light
+    #[default]
+    Default3D,
+    /// An orthographic player camera looking straight down the Z axis, for sprites on the XY
+    /// plane
+    Default2D,
+}
+
+impl SceneTemplate {
+    pub const ALL: [SceneTemplate; 3] = [
+        SceneTemplate::Empty,
+        SceneTemplate::Default3D,
+        SceneTemplate::Default2D,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SceneTemplate::Empty => "Empty",
+            SceneTemplate::Default3D => "3D",
+            SceneTemplate::Default2D => "2D",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            SceneTemplate::Empty => "An empty scene",
+            SceneTemplate::Default3D => "A camera, a directional light and a ground plane",
+            SceneTemplate::Default2D => "An orthographic camera facing the XY plane, for sprites",
+        }
+    }
+}
+
 impl SceneConfig {
+    /// Opaque black.
+    pub const DEFAULT_CLEAR_COLOR: [f64; 4] = [0.0, 0.0, 0.0, 1.0];
+
     /// Creates a new instance of the scene config
     pub fn new(scene_name: String, path: impl AsRef<Path>) -> Self {
         Self {
@@ -100,7 +197,82 @@ impl SceneConfig {
             entities: Vec::new(),
             hierarchy_map: SceneHierarchy::new(),
             settings: SceneSettings::new(),
+            camera_paths: BTreeMap::new(),
+            clear_color: Self::DEFAULT_CLEAR_COLOR,
+        }
+    }
+
+    /// Creates a scene config filled with the entities of a [`SceneTemplate`].
+    pub fn from_template(
+        template: SceneTemplate,
+        scene_name: String,
+        path: impl AsRef<Path>,
+    ) -> Self {
+        let mut scene = Self::new(scene_name, path);
+
+        match template {
+            SceneTemplate::Empty => {}
+            SceneTemplate::Default3D => {
+                // pitched down so the camera looks at the origin from above and behind
+                let pitch = 2.0_f64.atan2(5.0);
+                scene.entities.push(template_camera(
+                    DVec3::new(0.0, 2.0, 5.0),
+                    DQuat::from_rotation_y(std::f64::consts::PI) * DQuat::from_rotation_x(pitch),
+                    ProjectionKind::Perspective,
+                ));
+
+                let light_component = LightComponent::directional(DVec3::ONE, 1.0);
+                let light = Light {
+                    label: "Sun".to_string(),
+                    transform: Transform {
+                        position: DVec3::new(2.0, 4.0, 2.0),
+                        rotation: DQuat::from_rotation_arc(
+                            DVec3::new(0.0, 0.0, -1.0),
+                            LightComponent::default_direction(),
+                        ),
+                        ..Default::default()
+                    },
+                    light_component,
+                    enabled: true,
+                    entity_id: None,
+                };
+                scene.entities.push(SceneEntity {
+                    label: Label::new("Sun"),
+                    components: vec![Box::new(light)],
+                    entity_id: None,
+                });
+
+                match ResourceReference::from_euca_uri("euca://internal/dropbear/models/cube") {
+                    Ok(handle) => scene.entities.push(SceneEntity {
+                        label: Label::new("Ground"),
+                        components: vec![
+                            Box::new(EntityTransform::new_from_world(Transform {
+                                position: DVec3::new(0.0, -0.5, 0.0),
+                                scale: DVec3::new(20.0, 0.1, 20.0),
+                                ..Default::default()
+                            })),
+                            Box::new(SerializedMeshRenderer {
+                                handle,
+                                material_override: Vec::new(),
+                                emissive_overrides: HashMap::new(),
+                            }),
+                            Box::new(ModelProperties::new()),
+                        ],
+                        entity_id: None,
+                    }),
+                    Err(e) => log::warn!("Unable to add the ground to the template: {}", e),
+                }
+            }
+            SceneTemplate::Default2D => {
+                scene.entities.push(template_camera(
+                    DVec3::new(0.0, 0.0, 10.0),
+                    DQuat::from_rotation_y(std::f64::consts::PI),
+                    ProjectionKind::orthographic(5.0, 16.0 / 9.0, 0.1, 100.0),
+                ));
+            }
         }
+
+        scene
     }
 
     /// Helper function to load a component and add it to the entity builder
@@ -225,13 +397,27 @@ impl SceneConfig {
                 }
             }
 
+            for (material_name, emissive_factor) in &renderer.emissive_overrides {
+                if let Err(err) =
+                    model.set_emissive_factor(graphics.clone(), material_name, *emissive_factor)
+                {
+                    log::warn!(
+                        "Failed to apply emissive factor to '{}' on '{}': {}",
+                        material_name,
+                        label,
+                        err
+                    );
+                }
+            }
+
             builder.add(model);
         } else if let Some(props) = component.as_any().downcast_ref::<ModelProperties>() {
             builder.add(props.clone());
         } else if let Some(camera_comp) = component.as_any().downcast_ref::<Camera3D>() {
             let cam_builder = CameraBuilder::from(camera_comp.clone());
             let comp = CameraComponent::from(camera_comp.clone());
-            let camera = Camera::new(graphics.clone(), cam_builder, Some(label));
+            let mut camera = Camera::new(graphics.clone(), cam_builder, Some(label));
+            camera.visible_layers = camera_comp.visible_layers;
             builder.add_bundle((camera, comp));
         } else if let Some(light_conf) = component.as_any().downcast_ref::<Light>() {
             let light = EngineLight::new(
@@ -247,6 +433,39 @@ impl SceneConfig {
             builder.add(light_conf.transform);
         } else if let Some(script) = component.as_any().downcast_ref::<Script>() {
             builder.add(script.clone());
+        } else if let Some(probe) = component.as_any().downcast_ref::<LightProbe>() {
+            builder.add(probe.clone());
+        } else if let Some(layer) = component.as_any().downcast_ref::<EntityLayer>() {
+            builder.add(*layer);
+        } else if let Some(emitter) = component.as_any().downcast_ref::<ParticleEmitter>() {
+            builder.add(emitter.clone());
+        } else if let Some(weights) = component.as_any().downcast_ref::<MorphWeights>() {
+            builder.add(weights.clone());
+        } else if let Some(follow) = component.as_any().downcast_ref::<CameraFollowTarget>() {
+            builder.add(follow.clone());
+        } else if let Some(group) = component.as_any().downcast_ref::<LodGroup>() {
+            let mut group = group.clone();
+            for (_, reference) in &group.sources {
+                if ASSET_REGISTRY
+                    .model_handle_from_reference(reference)
+                    .is_some()
+                {
+                    continue;
+                }
+
+                if matches!(reference.ref_type, ResourceReferenceType::File(_)) {
+                    let path = reference.resolve()?;
+                    Model::load(graphics.clone(), &path, reference.as_uri()).await?;
+                } else {
+                    log::warn!(
+                        "LOD level for '{}' references unsupported resource {:?}",
+                        label,
+                        reference
+                    );
+                }
+            }
+            group.resolve();
+            builder.add(group);
         } else if component.as_any().downcast_ref::<Parent>().is_some() {
             log::debug!(
                 "Skipping Parent component for '{}' - will be rebuilt from hierarchy_map",
@@ -294,12 +513,16 @@ impl SceneConfig {
         Ok(config)
     }
 
+    /// Clears the world and loads every entity of the scene into it, returning the active camera.
+    ///
+    /// If `cancel` is cancelled, loading stops before the next entity and an error is returned.
     pub async fn load_into_world(
         &self,
         world: &mut hecs::World,
         graphics: Arc<SharedGraphicsContext>,
         registry: Option<&ComponentRegistry>,
         progress_sender: Option<UnboundedSender<WorldLoadingStatus>>,
+        cancel: Option<&CancellationToken>,
     ) -> anyhow::Result<hecs::Entity> {
         if let Some(ref s) = progress_sender {
             let _ = s.send(WorldLoadingStatus::Idle);
@@ -323,6 +546,274 @@ impl SceneConfig {
 
         log::info!("World cleared, now has {} entities", world.len());
 
+        // the default light and viewport camera are counted after the scene's own entities
+        let total = self.entities.len() + DEFAULT_ENTITY_STEPS;
+        self.spawn_entities(
+            world,
+            graphics.clone(),
+            registry,
+            progress_sender.as_ref(),
+            total,
+            cancel,
+        )
+        .await?;
+
+        {
+            let mut has_light = false;
+            if world
+                .query::<(&LightComponent, &EngineLight)>()
+                .iter()
+                .next()
+                .is_some()
+            {
+                has_light = true;
+            }
+
+            if !has_light {
+                log::info!("No lights in scene, spawning default light");
+
+                let legacy_lights: Vec<hecs::Entity> = world
+                    .query::<&Label>()
+                    .iter()
+                    .filter_map(|(entity, label)| {
+                        if label.as_str() == "Default Light" {
+                            Some(entity)
+                        } else {
+                            None
+                        }
+                    })
+                    .collect();
+
+                for entity in legacy_lights {
+                    if let Err(err) = world.despawn(entity) {
+                        log::warn!(
+                            "Failed to remove legacy 'Default Light' entity {:?}: {}",
+                            entity,
+                            err
+                        );
+                    } else {
+                        log::debug!(
+                            "Removed legacy 'Default Light' placeholder entity {:?}",
+                            entity
+                        );
+                    }
+                }
+
+                if let Some(ref s) = progress_sender {
+                    let _ = s.send(WorldLoadingStatus::Progress {
+                        current: (self.entities.len() + 1) as u32,
+                        total: total as u32,
+                        phase: LoadPhase::Lights,
+                        current_name: String::from("Default Light"),
+                    });
+                }
+                let comp = LightComponent::directional(glam::DVec3::ONE, 1.0);
+                let light_direction = LightComponent::default_direction();
+                let rotation =
+                    DQuat::from_rotation_arc(DVec3::new(0.0, 0.0, -1.0), light_direction);
+                let trans = Transform {
+                    position: glam::DVec3::new(2.0, 4.0, 2.0),
+                    rotation,
+                    ..Default::default()
+                };
+                let light =
+                    EngineLight::new(graphics.clone(), comp.clone(), trans, Some("Default Light")).await;
+
+                let light_config = Light {
+                    label: "Default Light".to_string(),
+                    transform: trans,
+                    light_component: comp.clone(),
+                    enabled: true,
+                    entity_id: None,
+                };
+
+                {
+                    world.spawn((
+                        Label::from("Default Light"),
+                        comp,
+                        trans,
+                        light,
+                        light_config,
+                        ModelProperties::default(),
+                    ));
+                }
+            }
+        }
+
+        log::info!("Loaded {} entities from scene", self.entities.len());
+        #[cfg(feature = "editor")]
+        {
+            let debug_camera = {
+                world
+                    .query::<(&Camera, &CameraComponent)>()
+                    .iter()
+                    .find_map(|(entity, (_, component))| {
+                        if matches!(component.camera_type, CameraType::Debug) {
+                            Some(entity)
+                        } else {
+                            None
+                        }
+                    })
+            };
+
+            {
+                if let Some(camera_entity) = debug_camera {
+                    log::info!("Using existing debug camera for editor");
+                    Ok(camera_entity)
+                } else {
+                    log::info!("No debug camera found, creating viewport camera for editor");
+
+                    let legacy_cameras: Vec<hecs::Entity> = world
+                        .query::<&Label>()
+                        .iter()
+                        .filter_map(|(entity, label)| {
+                            if label.as_str() == "Viewport Camera" {
+                                Some(entity)
+                            } else {
+                                None
+                            }
+                        })
+                        .collect();
+
+                    for entity in legacy_cameras {
+                        if let Err(err) = world.despawn(entity) {
+                            log::warn!(
+                                "Failed to remove legacy 'Viewport Camera' entity {:?}: {}",
+                                entity,
+                                err
+                            );
+                        } else {
+                            log::debug!(
+                                "Removed legacy 'Viewport Camera' placeholder entity {:?}",
+                                entity
+                            );
+                        }
+                    }
+
+                    if let Some(ref s) = progress_sender {
+                        let _ = s.send(WorldLoadingStatus::Progress {
+                            current: (self.entities.len() + 2) as u32,
+                            total: total as u32,
+                            phase: LoadPhase::Cameras,
+                            current_name: String::from("Viewport Camera"),
+                        });
+                    }
+                    let camera = Camera::predetermined(graphics.clone(), Some("Viewport Camera"));
+                    let component = crate::camera::DebugCamera::new();
+                    let label = Label::new("Viewport Camera");
+                    let camera_entity = { world.spawn((label, camera, component)) };
+                    Ok(camera_entity)
+                }
+            }
+        }
+
+        #[cfg(not(feature = "editor"))]
+        {
+            let player_camera = world
+                .query::<(&Camera, &CameraComponent)>()
+                .iter()
+                .find_map(|(entity, (_, component))| {
+                    if matches!(component.camera_type, CameraType::Player) {
+                        Some(entity)
+                    } else {
+                        None
+                    }
+                });
+
+            if let Some(camera_entity) = player_camera {
+                log::info!("Using player camera for runtime");
+                Ok(camera_entity)
+            } else {
+                panic!("Runtime mode requires a player camera, but none was found in the scene!");
+            }
+        }
+    }
+
+    /// Adds the entities of the scene to `world` without clearing it, such as for streaming in the
+    /// zones of a larger world.
+    ///
+    /// Unlike [`SceneConfig::load_into_world`], no default light or camera is spawned. Any
+    /// entities that were already loaded from this scene are unloaded first, so loading a scene
+    /// twice does not duplicate it.
+    pub async fn load_additive(
+        &self,
+        world: &mut hecs::World,
+        graphics: Arc<SharedGraphicsContext>,
+        registry: Option<&ComponentRegistry>,
+        progress_sender: Option<UnboundedSender<WorldLoadingStatus>>,
+    ) -> anyhow::Result<()> {
+        let unloaded = Self::unload_from_world(world, &self.scene_name);
+        if unloaded > 0 {
+            log::debug!(
+                "Unloaded {} entities of scene [{}] before loading it again",
+                unloaded,
+                self.scene_name
+            );
+        }
+
+        log::info!(
+            "Loading scene [{}] additively into world with {} entities",
+            self.scene_name,
+            world.len()
+        );
+
+        self.spawn_entities(
+            world,
+            graphics,
+            registry,
+            progress_sender.as_ref(),
+            self.entities.len(),
+            None,
+        )
+        .await?;
+
+        log::info!(
+            "Added {} entities from scene [{}]",
+            self.entities.len(),
+            self.scene_name
+        );
+        Ok(())
+    }
+
+    /// Despawns every entity that was loaded from the scene called `scene_name`, returning how
+    /// many were despawned.
+    pub fn unload_from_world(world: &mut hecs::World, scene_name: &str) -> usize {
+        let entities: Vec<hecs::Entity> = world
+            .query::<&SceneOrigin>()
+            .iter()
+            .filter(|(_, origin)| origin.0 == scene_name)
+            .map(|(entity, _)| entity)
+            .collect();
+
+        for entity in &entities {
+            let _ = world.despawn(*entity);
+        }
+
+        // entities from other scenes may still list the despawned ones as children
+        for (_, children) in world.query_mut::<&mut Children>() {
+            children
+                .children_mut()
+                .retain(|child| !entities.contains(child));
+        }
+
+        entities.len()
+    }
+
+    /// Spawns every entity of the scene into `world` and rebuilds their hierarchy, tagging each
+    /// with a [`SceneOrigin`].
+    ///
+    /// `total` is the number of steps reported to `progress_sender`, which can be more than the
+    /// number of entities if the caller loads more afterwards. `cancel` is checked before each
+    /// entity, as loading their models is the slow part.
+    async fn spawn_entities(
+        &self,
+        world: &mut hecs::World,
+        graphics: Arc<SharedGraphicsContext>,
+        registry: Option<&ComponentRegistry>,
+        progress_sender: Option<&UnboundedSender<WorldLoadingStatus>>,
+        total: usize,
+        cancel: Option<&CancellationToken>,
+    ) -> anyhow::Result<()> {
         let entity_configs: Vec<(usize, SceneEntity)> = {
             let cloned = self.entities.clone();
             cloned
@@ -335,6 +826,10 @@ impl SceneConfig {
         let mut label_to_entity: HashMap<Label, hecs::Entity> = HashMap::new();
 
         for (index, entity_config) in entity_configs {
+            if cancel.is_some_and(|token| token.is_cancelled()) {
+                anyhow::bail!("Loading of scene [{}] was cancelled", self.scene_name);
+            }
+
             let SceneEntity {
                 label,
                 components,
@@ -346,19 +841,19 @@ impl SceneConfig {
 
             log::debug!("Loading entity: {}", label_for_logs);
 
-            let total = self.entities.len();
-
-            if let Some(ref s) = progress_sender {
-                let _ = s.send(WorldLoadingStatus::LoadingEntity {
-                    index,
-                    name: label_for_logs.clone(),
-                    total,
+            if let Some(s) = progress_sender {
+                let _ = s.send(WorldLoadingStatus::Progress {
+                    current: (index + 1) as u32,
+                    total: total as u32,
+                    phase: LoadPhase::Entities,
+                    current_name: label_for_logs.clone(),
                 });
             }
 
             let mut builder = hecs::EntityBuilder::new();
 
             builder.add(label_for_map.clone());
+            builder.add(SceneOrigin(self.scene_name.clone()));
 
             let mut has_entity_transform = false;
 
@@ -397,7 +892,7 @@ impl SceneConfig {
                         let transform = entity_transform.sync();
 
                         if let Some(renderer) = renderer_opt {
-                            renderer.update(&transform);
+                            renderer.update_with_pivot(&transform, entity_transform.pivot());
                             log::debug!("Updated renderer transform for '{}'", label_for_logs);
                         }
 
@@ -496,172 +991,6 @@ impl SceneConfig {
             }
         }
 
-        {
-            let mut has_light = false;
-            if world
-                .query::<(&LightComponent, &EngineLight)>()
-                .iter()
-                .next()
-                .is_some()
-            {
-                has_light = true;
-            }
-
-            if !has_light {
-                log::info!("No lights in scene, spawning default light");
-
-                let legacy_lights: Vec<hecs::Entity> = world
-                    .query::<&Label>()
-                    .iter()
-                    .filter_map(|(entity, label)| {
-                        if label.as_str() == "Default Light" {
-                            Some(entity)
-                        } else {
-                            None
-                        }
-                    })
-                    .collect();
-
-                for entity in legacy_lights {
-                    if let Err(err) = world.despawn(entity) {
-                        log::warn!(
-                            "Failed to remove legacy 'Default Light' entity {:?}: {}",
-                            entity,
-                            err
-                        );
-                    } else {
-                        log::debug!(
-                            "Removed legacy 'Default Light' placeholder entity {:?}",
-                            entity
-                        );
-                    }
-                }
-
-                if let Some(ref s) = progress_sender {
-                    let _ = s.send(WorldLoadingStatus::LoadingEntity {
-                        index: 0,
-                        name: String::from("Default Light"),
-                        total: 1,
-                    });
-                }
-                let comp = LightComponent::directional(glam::DVec3::ONE, 1.0);
-                let light_direction = LightComponent::default_direction();
-                let rotation =
-                    DQuat::from_rotation_arc(DVec3::new(0.0, 0.0, -1.0), light_direction);
-                let trans = Transform {
-                    position: glam::DVec3::new(2.0, 4.0, 2.0),
-                    rotation,
-                    ..Default::default()
-                };
-                let light =
-                    EngineLight::new(graphics.clone(), comp.clone(), trans, Some("Default Light")).await;
-
-                let light_config = Light {
-                    label: "Default Light".to_string(),
-                    transform: trans,
-                    light_component: comp.clone(),
-                    enabled: true,
-                    entity_id: None,
-                };
-
-                {
-                    world.spawn((
-                        Label::from("Default Light"),
-                        comp,
-                        trans,
-                        light,
-                        light_config,
-                        ModelProperties::default(),
-                    ));
-                }
-            }
-        }
-
-        log::info!("Loaded {} entities from scene", self.entities.len());
-        #[cfg(feature = "editor")]
-        {
-            let debug_camera = {
-                world
-                    .query::<(&Camera, &CameraComponent)>()
-                    .iter()
-                    .find_map(|(entity, (_, component))| {
-                        if matches!(component.camera_type, CameraType::Debug) {
-                            Some(entity)
-                        } else {
-                            None
-                        }
-                    })
-            };
-
-            {
-                if let Some(camera_entity) = debug_camera {
-                    log::info!("Using existing debug camera for editor");
-                    Ok(camera_entity)
-                } else {
-                    log::info!("No debug camera found, creating viewport camera for editor");
-
-                    let legacy_cameras: Vec<hecs::Entity> = world
-                        .query::<&Label>()
-                        .iter()
-                        .filter_map(|(entity, label)| {
-                            if label.as_str() == "Viewport Camera" {
-                                Some(entity)
-                            } else {
-                                None
-                            }
-                        })
-                        .collect();
-
-                    for entity in legacy_cameras {
-                        if let Err(err) = world.despawn(entity) {
-                            log::warn!(
-                                "Failed to remove legacy 'Viewport Camera' entity {:?}: {}",
-                                entity,
-                                err
-                            );
-                        } else {
-                            log::debug!(
-                                "Removed legacy 'Viewport Camera' placeholder entity {:?}",
-                                entity
-                            );
-                        }
-                    }
-
-                    if let Some(ref s) = progress_sender {
-                        let _ = s.send(WorldLoadingStatus::LoadingEntity {
-                            index: 0,
-                            name: String::from("Viewport Camera"),
-                            total: 1,
-                        });
-                    }
-                    let camera = Camera::predetermined(graphics.clone(), Some("Viewport Camera"));
-                    let component = crate::camera::DebugCamera::new();
-                    let label = Label::new("Viewport Camera");
-                    let camera_entity = { world.spawn((label, camera, component)) };
-                    Ok(camera_entity)
-                }
-            }
-        }
-
-        #[cfg(not(feature = "editor"))]
-        {
-            let player_camera = world
-                .query::<(&Camera, &CameraComponent)>()
-                .iter()
-                .find_map(|(entity, (_, component))| {
-                    if matches!(component.camera_type, CameraType::Player) {
-                        Some(entity)
-                    } else {
-                        None
-                    }
-                });
-
-            if let Some(camera_entity) = player_camera {
-                log::info!("Using player camera for runtime");
-                Ok(camera_entity)
-            } else {
-                panic!("Runtime mode requires a player camera, but none was found in the scene!");
-            }
-        }
+        Ok(())
     }
 }
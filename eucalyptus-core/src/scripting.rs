@@ -4,15 +4,27 @@ pub mod native;
 
 use crate::input::InputState;
 use crate::ptr::{GraphicsPtr, InputStatePtr, WorldPtr};
+use crate::scene::SceneEntity;
 use crate::scripting::jni::JavaContext;
 use crate::scripting::native::NativeLibrary;
-use crate::states::Script;
+use crate::spawn::{PendingSpawn, push_pending_spawn};
+use crate::states::{Label, ModelProperties, Script, SerializedMeshRenderer};
+use crate::utils::ResolveReference;
 use anyhow::Context;
 use crossbeam_channel::Sender;
 use dropbear_engine::asset::ASSET_REGISTRY;
+use dropbear_engine::entity::{EntityTransform, Transform};
+use dropbear_engine::font::{self, TextAnchor, TextRequest};
+use dropbear_engine::utils::ResourceReference;
+use dropbear_traits::{EventPayload, EventQueue};
+use glam::Vec3;
 use hecs::{Entity, World};
+use parking_lot::Mutex;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 
@@ -34,6 +46,143 @@ pub enum ScriptTarget {
     },
 }
 
+/// Whether a contact pair started or stopped touching.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollisionPhase {
+    Enter,
+    Exit,
+}
+
+/// A contact between two entities, found by [`ContactTracker`](crate::contacts::ContactTracker).
+#[derive(Debug, Clone, Copy)]
+pub struct CollisionEvent {
+    pub entity_a: Entity,
+    pub entity_b: Entity,
+    /// The contact normal, pointing from `entity_a` to `entity_b`
+    pub normal: Vec3,
+    pub impulse: f32,
+    pub phase: CollisionPhase,
+}
+
+/// A scene being loaded into or unloaded from the world, see
+/// [`ScriptManager::broadcast_scene_event`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SceneEvent {
+    Load { scene_name: String },
+    Unload { scene_name: String },
+}
+
+/// An entity that a script asked to be spawned.
+#[derive(Debug, Clone)]
+pub struct SpawnRequest {
+    /// The euca URI of the model the entity is rendered with
+    pub model_path: String,
+    pub label: String,
+    pub transform: Transform,
+}
+
+/// Spawn requests made through the script exports, which don't have access to the
+/// [`ScriptManager`].
+static SPAWN_REQUESTS: LazyLock<Mutex<Vec<SpawnRequest>>> =
+    LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// Labels of the entities that scripts asked to be despawned.
+static DESPAWN_REQUESTS: LazyLock<Mutex<Vec<String>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// Queues an entity to be spawned by the next [`ScriptManager::flush_spawn_queue`].
+pub fn request_spawn(request: SpawnRequest) {
+    SPAWN_REQUESTS.lock().push(request);
+}
+
+/// Queues the entity with the given label to be despawned by the next
+/// [`ScriptManager::flush_spawn_queue`].
+pub fn request_despawn(label: String) {
+    DESPAWN_REQUESTS.lock().push(label);
+}
+
+/// Queues text to be drawn for this frame only, with the font at the euca URI `font` rasterised at
+/// `size` pixels high.
+pub fn draw_text(
+    text: String,
+    font: &str,
+    size: f32,
+    anchor: TextAnchor,
+    color: [f32; 4],
+) -> anyhow::Result<()> {
+    let font_path = ResourceReference::from_euca_uri(font)?.resolve()?;
+    font::request_text(TextRequest {
+        text,
+        anchor,
+        color,
+        font_path,
+        size,
+    });
+    Ok(())
+}
+
+/// A callback that a script asked to be called after a delay.
+#[derive(Debug, Clone)]
+pub struct Timer {
+    pub id: u64,
+    /// Seconds left until the timer fires
+    pub delay: f32,
+    /// Whether the timer starts again after firing, instead of being removed
+    pub repeat: bool,
+    /// Seconds between each firing of a repeating timer
+    pub interval: f32,
+    /// The name passed to `System.onTimer`, used by scripts to tell their timers apart
+    pub callback_name: String,
+    pub data: serde_json::Value,
+}
+
+static NEXT_TIMER_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Timers started through the script exports, which don't have access to the [`ScriptManager`].
+static TIMER_REQUESTS: LazyLock<Mutex<Vec<Timer>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// Ids of the timers that scripts asked to be cleared.
+static CLEARED_TIMERS: LazyLock<Mutex<Vec<u64>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// Queues a timer to be started by the next [`ScriptManager::tick_timers`], returning its id.
+///
+/// `data` is parsed as JSON, and kept as a plain string if it isn't valid JSON.
+pub fn request_timer(callback_name: String, delay: f32, repeat: bool, data: &str) -> u64 {
+    let id = NEXT_TIMER_ID.fetch_add(1, Ordering::Relaxed);
+    let data =
+        serde_json::from_str(data).unwrap_or_else(|_| serde_json::Value::String(data.to_string()));
+    let delay = delay.max(0.0);
+
+    TIMER_REQUESTS.lock().push(Timer {
+        id,
+        delay,
+        repeat,
+        interval: delay,
+        callback_name,
+        data,
+    });
+    id
+}
+
+/// Stops the timer with the given id from firing again.
+pub fn clear_timer(id: u64) {
+    CLEARED_TIMERS.lock().push(id);
+}
+
+/// Values stored by scripts with `setStore`, which the exports reach without the
+/// [`ScriptManager`].
+static SCENE_STORE: LazyLock<Mutex<HashMap<String, serde_json::Value>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Stores a JSON value under `key` in the scene store, replacing the value already there.
+pub fn set_store(key: impl Into<String>, value: serde_json::Value) {
+    SCENE_STORE.lock().insert(key.into(), value);
+}
+
+/// Fetches the JSON value stored under `key` in the scene store.
+pub fn get_store(key: &str) -> Option<serde_json::Value> {
+    SCENE_STORE.lock().get(key).cloned()
+}
+
 /// An enum representing the status of the build process.
 ///
 /// This is used for cross-thread [`crossbeam_channel::unbounded`] channels
@@ -55,6 +204,9 @@ pub struct ScriptManager {
     script_target: ScriptTarget,
     /// The entity tag database. This is a map of tag<->list of entities.
     entity_tag_database: HashMap<String, Vec<Entity>>,
+    /// The order that tagged entities are updated in, stored as (priority, tag, entities) and
+    /// sorted by ascending priority.
+    update_order: Vec<(i32, String, Vec<Entity>)>,
     /// Whether or not the JVM has been created.
     ///
     /// This bool is required as the JNI specifications only allow for one JVM per process.
@@ -62,6 +214,19 @@ pub struct ScriptManager {
     /// The path to the library. This is set if the [`ScriptTarget`] is [`ScriptTarget::Native`] or
     /// [`ScriptTarget::JVM`]
     lib_path: Option<PathBuf>,
+    /// Events that have been emitted but not yet drained.
+    event_queue: EventQueue,
+    /// Entities that scripts asked to be spawned during the last update.
+    spawn_queue: Vec<SpawnRequest>,
+    /// Labels of the entities that scripts asked to be despawned during the last update.
+    despawn_queue: Vec<String>,
+    /// Timers that are waiting to fire.
+    timer_queue: Vec<Timer>,
+    /// How long the scripts of each entity took to run during the last update.
+    entity_timings: HashMap<Entity, Duration>,
+    /// The entities scripts have despawned, if they are being kept, see
+    /// [`ScriptManager::keep_despawned_entities`].
+    despawned: Option<World>,
 }
 
 impl ScriptManager {
@@ -74,8 +239,15 @@ impl ScriptManager {
             library: None,
             script_target: Default::default(),
             entity_tag_database: HashMap::new(),
+            update_order: Vec::new(),
             jvm_created: false,
             lib_path: None,
+            event_queue: EventQueue::new(),
+            spawn_queue: Vec::new(),
+            despawn_queue: Vec::new(),
+            timer_queue: Vec::new(),
+            entity_timings: HashMap::new(),
+            despawned: None,
         };
 
         #[cfg(feature = "jvm")]
@@ -106,6 +278,10 @@ impl ScriptManager {
         self.entity_tag_database = entity_tag_database.clone();
         self.script_target = target.clone();
 
+        // timers from the last play session would otherwise fire in this one
+        self.timer_queue.clear();
+        TIMER_REQUESTS.lock().clear();
+
         match &target {
             ScriptTarget::JVM { library_path } => {
                 self.lib_path = Some(library_path.clone());
@@ -201,6 +377,8 @@ impl ScriptManager {
     ///   empty or [`NativeLibrary::update_tagged`] if there are tags.
     /// - [`ScriptTarget::None`] - This returns an error.
     ///
+    /// Tagged entities are updated in order of their [`Script::priority`], lowest first.
+    ///
     /// # Safety
     /// This function is marked unsafe because clippy forced me to, but also
     /// world is rebuilt from the pointer.
@@ -213,6 +391,7 @@ impl ScriptManager {
         if let Some(world) = unsafe { _world.as_ref() } {
             self.rebuild_entity_tag_database(world);
         }
+        self.entity_timings.clear();
 
         match self.script_target {
             ScriptTarget::None => Err(anyhow::anyhow!(
@@ -223,7 +402,7 @@ impl ScriptManager {
                     if self.entity_tag_database.is_empty() {
                         jvm.update_all_systems(dt)?;
                     } else {
-                        for (tag, entities) in &self.entity_tag_database {
+                        for (_, tag, entities) in &self.update_order {
                             let entity_ids: Vec<u64> = entities
                                 .iter()
                                 .map(|entity| entity.to_bits().get())
@@ -232,7 +411,16 @@ impl ScriptManager {
                             if entity_ids.is_empty() {
                                 jvm.update_systems_for_tag(tag, dt)?;
                             } else {
+                                #[cfg(feature = "profiling")]
+                                puffin::profile_scope!("script", tag);
+
+                                let start = Instant::now();
                                 jvm.update_systems_for_entities(tag, &entity_ids, dt)?;
+                                record_tag_timing(
+                                    &mut self.entity_timings,
+                                    entities,
+                                    start.elapsed(),
+                                );
                             }
                         }
                     }
@@ -244,7 +432,27 @@ impl ScriptManager {
             }
             ScriptTarget::Native { .. } => {
                 if let Some(library) = &mut self.library {
-                    library.update_all(dt)?;
+                    if self.entity_tag_database.is_empty() {
+                        library.update_all(dt)?;
+                    } else {
+                        let mut updated: Vec<&str> = Vec::new();
+                        for (_, tag, entities) in &self.update_order {
+                            // native systems are only addressable by tag
+                            if !updated.contains(&tag.as_str()) {
+                                #[cfg(feature = "profiling")]
+                                puffin::profile_scope!("script", tag);
+
+                                let start = Instant::now();
+                                library.update_tagged(tag.clone(), dt)?;
+                                updated.push(tag);
+                                record_tag_timing(
+                                    &mut self.entity_timings,
+                                    entities,
+                                    start.elapsed(),
+                                );
+                            }
+                        }
+                    }
                     return Ok(());
                 }
                 Err(anyhow::anyhow!(
@@ -270,17 +478,322 @@ impl ScriptManager {
         Ok(())
     }
 
+    /// Calls `onCollisionEnter`/`onCollisionExit` on the scripts of both entities of each
+    /// event, passing the label of the other entity and the normal pointing towards it.
+    ///
+    /// This must be called after the contacts of a frame have been found rather than while
+    /// finding them, so anything a script changes in the world is only picked up by the next
+    /// step.
+    ///
+    /// # ScriptTarget behaviours
+    /// - [`ScriptTarget::JVM`] - This runs [`JavaContext::dispatch_collision`] for every tag of
+    ///   the entity's [`Script`].
+    /// - [`ScriptTarget::Native`] - Collision callbacks are not supported yet, so this does
+    ///   nothing.
+    /// - [`ScriptTarget::None`] - This does nothing.
+    pub fn dispatch_collision_events(
+        &self,
+        world: &World,
+        events: &[CollisionEvent],
+    ) -> anyhow::Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let jvm = match (&self.script_target, &self.jvm) {
+            (ScriptTarget::JVM { .. }, Some(jvm)) => jvm,
+            (ScriptTarget::Native { .. }, _) => {
+                log::debug!("Collision callbacks are not supported by native scripts, skipping");
+                return Ok(());
+            }
+            _ => return Ok(()),
+        };
+
+        let label_of = |entity: Entity| {
+            world
+                .get::<&Label>(entity)
+                .map(|label| label.to_string())
+                .unwrap_or_default()
+        };
+
+        for event in events {
+            let entered = event.phase == CollisionPhase::Enter;
+            for (entity, other, normal) in [
+                (event.entity_a, event.entity_b, event.normal),
+                (event.entity_b, event.entity_a, -event.normal),
+            ] {
+                let Ok(script) = world.get::<&Script>(entity) else {
+                    continue;
+                };
+
+                let other_label = label_of(other);
+                for tag in script.normalised_tags() {
+                    jvm.dispatch_collision(
+                        &tag,
+                        entity.to_bits().get(),
+                        &other_label,
+                        normal.to_array(),
+                        entered,
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Calls `onSceneLoad`/`onSceneUnload` on the script of every entity with a [`Script`],
+    /// passing the name of the scene.
+    ///
+    /// Loads should be broadcast once the entities of the scene are in the world, and unloads
+    /// before they are removed, so the scripts of the scene's own entities hear about both.
+    ///
+    /// # ScriptTarget behaviours
+    /// - [`ScriptTarget::JVM`] - This runs [`JavaContext::dispatch_scene_event`] for every tag of
+    ///   the entity's [`Script`].
+    /// - [`ScriptTarget::Native`] - Scene callbacks are not supported yet, so this does nothing.
+    /// - [`ScriptTarget::None`] - This does nothing.
+    pub fn broadcast_scene_event(&self, event: SceneEvent, world: &World) -> anyhow::Result<()> {
+        let jvm = match (&self.script_target, &self.jvm) {
+            (ScriptTarget::JVM { .. }, Some(jvm)) => jvm,
+            (ScriptTarget::Native { .. }, _) => {
+                log::debug!("Scene callbacks are not supported by native scripts, skipping");
+                return Ok(());
+            }
+            _ => return Ok(()),
+        };
+
+        let (scene_name, loaded) = match &event {
+            SceneEvent::Load { scene_name } => (scene_name, true),
+            SceneEvent::Unload { scene_name } => (scene_name, false),
+        };
+
+        // collected first, as the scripts may need to borrow the scripts in the world themselves
+        let scripted: Vec<(Entity, Vec<String>)> = world
+            .query::<&Script>()
+            .iter()
+            .map(|(entity, script)| (entity, script.normalised_tags().collect()))
+            .collect();
+
+        log::debug!(
+            "Broadcasting {:?} to {} scripted entities",
+            event,
+            scripted.len()
+        );
+        for (entity, tags) in scripted {
+            for tag in &tags {
+                jvm.dispatch_scene_event(tag, entity.to_bits().get(), scene_name, loaded)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Pushes a typed event onto the script event bus.
+    ///
+    /// The event is stored under [`EventPayload::event_name`] as JSON until it is drained.
+    pub fn emit_typed<E: EventPayload>(&mut self, event: E) {
+        self.event_queue.emit_typed(event);
+    }
+
+    /// Removes and returns all queued events of type `E`.
+    ///
+    /// Events that fail to deserialize into `E` are logged and dropped.
+    pub fn drain_typed<E: EventPayload>(&mut self) -> Vec<E> {
+        self.event_queue.drain_typed()
+    }
+
+    /// Spawns and despawns the entities that scripts asked for during the last update.
+    ///
+    /// This must be called after [`ScriptManager::update_script`]. Despawns happen straight away,
+    /// while spawns are pushed as a [`PendingSpawn`] so their model is loaded asynchronously and the
+    /// entity appears once it is ready.
+    pub fn flush_spawn_queue(&mut self, world: &mut World) {
+        self.spawn_queue.append(&mut SPAWN_REQUESTS.lock());
+        self.despawn_queue.append(&mut DESPAWN_REQUESTS.lock());
+
+        for label in self.despawn_queue.drain(..) {
+            let entity = world
+                .query::<&Label>()
+                .iter()
+                .find(|(_, entity_label)| entity_label.as_str() == label)
+                .map(|(entity, _)| entity);
+
+            match entity {
+                Some(entity) => {
+                    match &mut self.despawned {
+                        Some(despawned) => {
+                            if let Ok(taken) = world.take(entity) {
+                                despawned.spawn_at(entity, taken);
+                            }
+                        }
+                        None => {
+                            let _ = world.despawn(entity);
+                        }
+                    }
+                    log::debug!("Despawned '{}' from script", label);
+                }
+                None => log::warn!("Unable to despawn '{}': no entity has that label", label),
+            }
+        }
+
+        for request in self.spawn_queue.drain(..) {
+            let handle = match ResourceReference::from_euca_uri(&request.model_path) {
+                Ok(handle) => handle,
+                Err(e) => {
+                    log::warn!("Unable to spawn '{}': {}", request.label, e);
+                    continue;
+                }
+            };
+
+            push_pending_spawn(PendingSpawn {
+                scene_entity: SceneEntity {
+                    label: Label::new(request.label),
+                    components: vec![
+                        Box::new(EntityTransform::new_from_world(request.transform)),
+                        Box::new(SerializedMeshRenderer {
+                            handle,
+                            material_override: Vec::new(),
+                            emissive_overrides: HashMap::new(),
+                        }),
+                        Box::new(ModelProperties::new()),
+                    ],
+                    entity_id: None,
+                },
+                handle: None,
+            });
+        }
+    }
+
+    /// Moves the entities scripts despawn from now on into a separate [`World`] instead of
+    /// dropping them, so they can be put back with [`ScriptManager::take_despawned_entities`].
+    ///
+    /// The editor uses this to undo what scripts did once play mode stops.
+    pub fn keep_despawned_entities(&mut self) {
+        self.despawned = Some(World::new());
+    }
+
+    /// Returns the entities despawned since [`ScriptManager::keep_despawned_entities`] was
+    /// called, still using their original [`Entity`] ids, and stops keeping them.
+    pub fn take_despawned_entities(&mut self) -> Option<World> {
+        self.despawned.take()
+    }
+
+    /// Counts down every pending timer by `dt`, calling `onTimer` on the scripts for the ones
+    /// that have expired.
+    ///
+    /// Repeating timers are started again with their interval, while the rest are removed once
+    /// they have fired.
+    ///
+    /// # ScriptTarget behaviours
+    /// - [`ScriptTarget::JVM`] - This runs [`JavaContext::dispatch_timer`], which calls `onTimer`
+    ///   on every loaded system.
+    /// - [`ScriptTarget::Native`] - Native scripts can't start timers, as there is no way to call
+    ///   them back yet, so this does nothing.
+    /// - [`ScriptTarget::None`] - This does nothing.
+    pub fn tick_timers(&mut self, dt: f32) -> anyhow::Result<()> {
+        self.timer_queue.append(&mut TIMER_REQUESTS.lock());
+        let cleared = std::mem::take(&mut *CLEARED_TIMERS.lock());
+        self.timer_queue
+            .retain(|timer| !cleared.contains(&timer.id));
+
+        let mut expired = Vec::new();
+        self.timer_queue.retain_mut(|timer| {
+            timer.delay -= dt;
+            if timer.delay > 0.0 {
+                return true;
+            }
+
+            expired.push(timer.clone());
+            if timer.repeat {
+                // an interval of 0 would fire every frame, which is what was asked for
+                timer.delay += timer.interval.max(dt);
+            }
+            timer.repeat
+        });
+
+        if expired.is_empty() {
+            return Ok(());
+        }
+
+        let jvm = match (&self.script_target, &self.jvm) {
+            (ScriptTarget::JVM { .. }, Some(jvm)) => jvm,
+            _ => return Ok(()),
+        };
+
+        for timer in expired {
+            log::trace!("Timer {} [{}] fired", timer.id, timer.callback_name);
+            jvm.dispatch_timer(&timer.callback_name, &timer.data.to_string())?;
+        }
+
+        Ok(())
+    }
+
+    /// A copy of the scene store, which scripts write to with `setStore` and read from with
+    /// `getStore`.
+    ///
+    /// The store keeps script state between updates, such as kill counts or an inventory, and is
+    /// shared by every script. It is never saved, and only lasts until [`Self::clear_store`].
+    pub fn scene_store(&self) -> HashMap<String, serde_json::Value> {
+        SCENE_STORE.lock().clone()
+    }
+
+    /// Empties the scene store, which should be done when play mode stops.
+    pub fn clear_store(&mut self) {
+        SCENE_STORE.lock().clear();
+        log::debug!("Cleared the scene store");
+    }
+
+    /// How long the scripts of each entity took to run during the last
+    /// [`ScriptManager::update_script`].
+    ///
+    /// Scripts are updated a whole tag at a time, so the time a tag took is split evenly between
+    /// its entities.
+    pub fn entity_timings(&self) -> &HashMap<Entity, Duration> {
+        &self.entity_timings
+    }
+
     /// Rebuilds the ScriptManagers entity database by parsing a [`World`].
     fn rebuild_entity_tag_database(&mut self, world: &World) {
         let mut new_map: HashMap<String, Vec<Entity>> = HashMap::new();
+        let mut order: HashMap<(i32, String), Vec<Entity>> = HashMap::new();
 
         for (entity, script) in world.query::<&Script>().iter() {
-            for tag in &script.tags {
+            for tag in script.normalised_tags() {
                 new_map.entry(tag.clone()).or_default().push(entity);
+                order
+                    .entry((script.priority, tag))
+                    .or_default()
+                    .push(entity);
             }
         }
 
+        let mut update_order: Vec<(i32, String, Vec<Entity>)> = order
+            .into_iter()
+            .map(|((priority, tag), entities)| (priority, tag, entities))
+            .collect();
+        // sorting by tag as well keeps the order stable between frames
+        update_order.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+
         self.entity_tag_database = new_map;
+        self.update_order = update_order;
+    }
+}
+
+/// Adds the time that the scripts of a tag took to `timings`, split evenly between the entities
+/// with the tag.
+fn record_tag_timing(
+    timings: &mut HashMap<Entity, Duration>,
+    entities: &[Entity],
+    elapsed: Duration,
+) {
+    if entities.is_empty() {
+        return;
+    }
+
+    let share = elapsed / entities.len() as u32;
+    for entity in entities {
+        *timings.entry(*entity).or_default() += share;
     }
 }
 
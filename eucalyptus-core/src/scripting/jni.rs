@@ -445,6 +445,119 @@ impl JavaContext {
         }
     }
 
+    /// Calls `onCollisionEnter` (or `onCollisionExit` if `entered` is false) on every system of
+    /// the tag, with the entity the systems are attached to set as the current entity.
+    pub fn dispatch_collision(
+        &self,
+        tag: &str,
+        entity_id: u64,
+        other_label: &str,
+        normal: [f32; 3],
+        entered: bool,
+    ) -> anyhow::Result<()> {
+        if let Some(ref manager_ref) = self.system_manager_instance {
+            let mut env = self.jvm.attach_current_thread()?;
+
+            log::trace!(
+                "Calling SystemManager.dispatchCollision() with tag: {}, entity: {}, other: {}",
+                tag,
+                entity_id,
+                other_label
+            );
+
+            let tag_jstring = env.new_string(tag)?;
+            let other_jstring = env.new_string(other_label)?;
+            env.call_method(
+                manager_ref,
+                "dispatchCollision",
+                "(Ljava/lang/String;JLjava/lang/String;DDDZ)V",
+                &[
+                    JValue::Object(&tag_jstring),
+                    JValue::Long(entity_id as jlong),
+                    JValue::Object(&other_jstring),
+                    JValue::Double(normal[0] as f64),
+                    JValue::Double(normal[1] as f64),
+                    JValue::Double(normal[2] as f64),
+                    JValue::Bool(entered as u8),
+                ],
+            )?;
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(
+                "SystemManager not initialised when dispatching collision for tag: {}",
+                tag
+            ))
+        }
+    }
+
+    pub fn dispatch_scene_event(
+        &self,
+        tag: &str,
+        entity_id: u64,
+        scene_name: &str,
+        loaded: bool,
+    ) -> anyhow::Result<()> {
+        if let Some(ref manager_ref) = self.system_manager_instance {
+            let mut env = self.jvm.attach_current_thread()?;
+
+            log::trace!(
+                "Calling SystemManager.dispatchSceneEvent() with tag: {}, entity: {}, scene: {}",
+                tag,
+                entity_id,
+                scene_name
+            );
+
+            let tag_jstring = env.new_string(tag)?;
+            let scene_jstring = env.new_string(scene_name)?;
+            env.call_method(
+                manager_ref,
+                "dispatchSceneEvent",
+                "(Ljava/lang/String;JLjava/lang/String;Z)V",
+                &[
+                    JValue::Object(&tag_jstring),
+                    JValue::Long(entity_id as jlong),
+                    JValue::Object(&scene_jstring),
+                    JValue::Bool(loaded as u8),
+                ],
+            )?;
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(
+                "SystemManager not initialised when dispatching scene event for tag: {}",
+                tag
+            ))
+        }
+    }
+
+    pub fn dispatch_timer(&self, callback_name: &str, data: &str) -> anyhow::Result<()> {
+        if let Some(ref manager_ref) = self.system_manager_instance {
+            let mut env = self.jvm.attach_current_thread()?;
+
+            log::trace!(
+                "Calling SystemManager.dispatchTimer() with callback: {}",
+                callback_name
+            );
+
+            let callback_jstring = env.new_string(callback_name)?;
+            let data_jstring = env.new_string(data)?;
+            env.call_method(
+                manager_ref,
+                "dispatchTimer",
+                "(Ljava/lang/String;Ljava/lang/String;)V",
+                &[
+                    JValue::Object(&callback_jstring),
+                    JValue::Object(&data_jstring),
+                ],
+            )?;
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(
+                "SystemManager not initialised when dispatching timer: {}",
+                callback_name
+            ))
+        }
+    }
+
     pub fn get_system_count_for_tag(&self, tag: &str) -> anyhow::Result<i32> {
         if let Some(ref manager_ref) = self.system_manager_instance {
             let mut env = self.jvm.attach_current_thread()?;
@@ -1,26 +1,37 @@
 #![allow(non_snake_case)]
 
-use crate::camera::{CameraComponent, CameraType};
+use crate::camera::{
+    CameraComponent, CameraType, find_camera_path, play_camera_path, scripted_camera,
+};
 use crate::hierarchy::{Children, EntityTransformExt, Parent};
 use crate::ptr::{AssetRegistryPtr, GraphicsPtr, InputStatePtr, WorldPtr};
+use crate::runtime::ResourceRegistry;
 use crate::scripting::jni::utils::{
-    create_vector3, extract_vector3, java_button_to_rust, new_float_array,
+    create_vector3, extract_vector3, java_button_to_rust, new_float_array, new_string_array,
+};
+use crate::scripting::{
+    SpawnRequest, clear_timer, draw_text, get_store, request_despawn, request_spawn, request_timer,
+    set_store,
 };
-use crate::states::{Label, ModelProperties, Value};
-use crate::utils::keycode_from_ordinal;
+use crate::states::{Label, MaterialProperties, ModelProperties, Script, Value, set_material_property};
+use crate::utils::{gamepad_button_from_ordinal, keycode_from_ordinal};
 use crate::window::{GraphicsCommand, WindowCommand};
 use crate::{convert_jlong_to_entity, convert_jstring, convert_ptr};
 use dropbear_engine::asset::PointerKind::Const;
 use dropbear_engine::asset::{ASSET_REGISTRY, AssetHandle, AssetRegistry};
-use dropbear_engine::camera::Camera;
+use dropbear_engine::camera::{Camera, CameraShake};
+use dropbear_engine::depth_of_field::{self, DepthOfFieldSettings};
 use dropbear_engine::entity::{EntityTransform, MeshRenderer, Transform};
+use dropbear_engine::font::TextAnchor;
 use dropbear_engine::model::Model;
+use dropbear_engine::morph::MorphWeights;
+use dropbear_engine::particles::ParticleEmitter;
 use dropbear_engine::utils::ResourceReference;
-use glam::{DQuat, DVec3};
+use glam::{DQuat, DVec3, Vec2};
 use hecs::World;
 use jni::JNIEnv;
-use jni::objects::{JClass, JObject, JPrimitiveArray, JString, JValue};
-use jni::sys::{JNI_FALSE, jboolean, jclass, jdouble, jfloatArray, jint, jlong, jobject, jobjectArray, jstring, jlongArray};
+use jni::objects::{JClass, JDoubleArray, JObject, JPrimitiveArray, JString, JValue};
+use jni::sys::{JNI_FALSE, jboolean, jclass, jdouble, jfloat, jfloatArray, jint, jlong, jobject, jobjectArray, jstring, jlongArray};
 use parking_lot::Mutex;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
@@ -1884,6 +1895,103 @@ pub fn Java_com_dropbear_ffi_JNINative_isCursorHidden(
     }
 }
 
+/// `JNIEXPORT jlongArray JNICALL Java_com_dropbear_ffi_JNINative_getConnectedGamepads
+///   (JNIEnv *, jclass, jlong);`
+#[unsafe(no_mangle)]
+pub fn Java_com_dropbear_ffi_JNINative_getConnectedGamepads(
+    env: JNIEnv,
+    _class: JClass,
+    input_handle: jlong,
+) -> jlongArray {
+    let input = convert_ptr!(input_handle, InputStatePtr => crate::input::InputState);
+
+    let gamepads: Vec<i64> = input
+        .connected_gamepads
+        .iter()
+        .map(|id| usize::from(*id) as i64)
+        .collect();
+
+    let array = match env.new_long_array(gamepads.len() as i32) {
+        Ok(array) => array,
+        Err(e) => {
+            return crate::ffi_error_return!("Unable to create a new long array: {}", e);
+        }
+    };
+
+    if let Err(e) = env.set_long_array_region(&array, 0, &gamepads) {
+        return crate::ffi_error_return!("Unable to populate long array: {}", e);
+    }
+
+    array.into_raw()
+}
+
+/// `JNIEXPORT jboolean JNICALL Java_com_dropbear_ffi_JNINative_isGamepadButtonPressed
+///   (JNIEnv *, jclass, jlong, jlong, jint);`
+#[unsafe(no_mangle)]
+pub fn Java_com_dropbear_ffi_JNINative_isGamepadButtonPressed(
+    _env: JNIEnv,
+    _class: JClass,
+    input_handle: jlong,
+    gamepad_id: jlong,
+    button: jint,
+) -> jboolean {
+    let input = input_handle as InputStatePtr;
+    if input.is_null() {
+        println!(
+            "[Java_com_dropbear_ffi_JNINative_isGamepadButtonPressed] [ERROR] Input state pointer is null"
+        );
+        return false.into();
+    }
+    let input = unsafe { &*input };
+
+    let Some(button) = gamepad_button_from_ordinal(button) else {
+        println!(
+            "[Java_com_dropbear_ffi_JNINative_isGamepadButtonPressed] [WARN] Ordinal button is invalid"
+        );
+        return false.into();
+    };
+
+    match input.gamepad_from_id(gamepad_id as usize) {
+        Some(gamepad) => input.is_button_pressed(gamepad, button).into(),
+        None => false.into(),
+    }
+}
+
+/// `JNIEXPORT jfloatArray JNICALL Java_com_dropbear_ffi_JNINative_getGamepadSticks
+///   (JNIEnv *, jclass, jlong, jlong);`
+///
+/// Returns the stick positions as `[left_x, left_y, right_x, right_y]`.
+#[unsafe(no_mangle)]
+pub fn Java_com_dropbear_ffi_JNINative_getGamepadSticks(
+    env: JNIEnv,
+    _class: JClass,
+    input_handle: jlong,
+    gamepad_id: jlong,
+) -> jfloatArray {
+    let input = convert_ptr!(input_handle, InputStatePtr => crate::input::InputState);
+
+    let (left, right) = match input.gamepad_from_id(gamepad_id as usize) {
+        Some(gamepad) => (
+            input.get_left_stick(gamepad),
+            input.get_right_stick(gamepad),
+        ),
+        None => ((0.0, 0.0), (0.0, 0.0)),
+    };
+
+    let array = match env.new_float_array(4) {
+        Ok(array) => array,
+        Err(e) => {
+            return crate::ffi_error_return!("Unable to create a new float array: {}", e);
+        }
+    };
+
+    if let Err(e) = env.set_float_array_region(&array, 0, &[left.0, left.1, right.0, right.1]) {
+        return crate::ffi_error_return!("Unable to populate float array: {}", e);
+    }
+
+    array.into_raw()
+}
+
 /// `JNIEXPORT jlong JNICALL Java_com_dropbear_ffi_JNINative_getModel
 ///   (JNIEnv *, jclass, jlong, jlong);`
 #[unsafe(no_mangle)]
@@ -2571,4 +2679,483 @@ pub fn Java_com_dropbear_ffi_JNINative_getParent(
     } else {
         crate::ffi_error_return!("No entity exists")
     }
-}
\ No newline at end of file
+}
+
+/// `JNIEXPORT void JNICALL Java_com_dropbear_ffi_JNINative_emitParticles
+///   (JNIEnv *, jclass, jlong, jstring, jint);`
+#[unsafe(no_mangle)]
+pub fn Java_com_dropbear_ffi_JNINative_emitParticles(
+    mut env: JNIEnv,
+    _class: JClass,
+    world_handle: jlong,
+    label: JString,
+    count: jint,
+) {
+    let world = convert_ptr!(world_handle, WorldPtr => World);
+    let label = convert_jstring!(env, label);
+
+    for (_, (entity_label, emitter)) in world.query::<(&Label, &mut ParticleEmitter)>().iter() {
+        if entity_label.as_str() == label {
+            emitter.emit(count.max(0) as u32);
+            return;
+        }
+    }
+
+    println!(
+        "[Java_com_dropbear_ffi_JNINative_emitParticles] [ERROR] No particle emitter exists on entity '{}'",
+        label
+    );
+}
+
+/// `JNIEXPORT void JNICALL Java_com_dropbear_ffi_JNINative_setEmissionRate
+///   (JNIEnv *, jclass, jlong, jstring, jfloat);`
+#[unsafe(no_mangle)]
+pub fn Java_com_dropbear_ffi_JNINative_setEmissionRate(
+    mut env: JNIEnv,
+    _class: JClass,
+    world_handle: jlong,
+    label: JString,
+    rate: jfloat,
+) {
+    let world = convert_ptr!(world_handle, WorldPtr => World);
+    let label = convert_jstring!(env, label);
+
+    for (_, (entity_label, emitter)) in world.query::<(&Label, &mut ParticleEmitter)>().iter() {
+        if entity_label.as_str() == label {
+            emitter.emission_rate = rate.max(0.0);
+            return;
+        }
+    }
+
+    println!(
+        "[Java_com_dropbear_ffi_JNINative_setEmissionRate] [ERROR] No particle emitter exists on entity '{}'",
+        label
+    );
+}
+
+/// `JNIEXPORT void JNICALL Java_com_dropbear_ffi_JNINative_setMorphWeight
+///   (JNIEnv *, jclass, jlong, jstring, jstring, jfloat);`
+#[unsafe(no_mangle)]
+pub fn Java_com_dropbear_ffi_JNINative_setMorphWeight(
+    mut env: JNIEnv,
+    _class: JClass,
+    world_handle: jlong,
+    label: JString,
+    target_name: JString,
+    weight: jfloat,
+) {
+    let world = convert_ptr!(world_handle, WorldPtr => World);
+    let label = convert_jstring!(env, label);
+    let target_name = convert_jstring!(env, target_name);
+
+    for (_, (entity_label, weights)) in world.query::<(&Label, &mut MorphWeights)>().iter() {
+        if entity_label.as_str() == label {
+            weights.set(&target_name, weight);
+            return;
+        }
+    }
+
+    println!(
+        "[Java_com_dropbear_ffi_JNINative_setMorphWeight] [ERROR] No morph weights exist on entity '{}'",
+        label
+    );
+}
+
+/// `JNIEXPORT void JNICALL Java_com_dropbear_ffi_JNINative_shakeCamera
+///   (JNIEnv *, jclass, jlong, jfloat, jfloat);`
+#[unsafe(no_mangle)]
+pub fn Java_com_dropbear_ffi_JNINative_shakeCamera(
+    _env: JNIEnv,
+    _class: JClass,
+    world_handle: jlong,
+    intensity: jfloat,
+    duration: jfloat,
+) {
+    let world = convert_ptr!(world_handle, WorldPtr => World);
+
+    // the debug camera is only used by the editor, so it never shakes
+    for (_, (camera, component)) in world.query::<(&mut Camera, &CameraComponent)>().iter() {
+        if !matches!(component.camera_type, CameraType::Debug) {
+            camera.apply_shake(CameraShake::new(intensity, duration));
+        }
+    }
+}
+
+/// `JNIEXPORT void JNICALL Java_com_dropbear_ffi_JNINative_playCameraPath
+///   (JNIEnv *, jclass, jlong, jstring);`
+#[unsafe(no_mangle)]
+pub fn Java_com_dropbear_ffi_JNINative_playCameraPath(
+    mut env: JNIEnv,
+    _class: JClass,
+    world_handle: jlong,
+    name: JString,
+) {
+    let world = world_handle as *mut World;
+    if world.is_null() {
+        println!("[Java_com_dropbear_ffi_JNINative_playCameraPath] [ERROR] World pointer is null");
+        return;
+    }
+    let world = unsafe { &mut *world };
+    let name = convert_jstring!(env, name);
+
+    let Some(path) = find_camera_path(&name) else {
+        println!(
+            "[Java_com_dropbear_ffi_JNINative_playCameraPath] [ERROR] No camera path named '{}' exists",
+            name
+        );
+        return;
+    };
+
+    let Some(camera) = scripted_camera(world) else {
+        println!("[Java_com_dropbear_ffi_JNINative_playCameraPath] [ERROR] No camera to play the path on");
+        return;
+    };
+
+    if let Err(e) = play_camera_path(world, camera, path) {
+        println!(
+            "[Java_com_dropbear_ffi_JNINative_playCameraPath] [ERROR] Unable to play camera path '{}': {}",
+            name, e
+        );
+    }
+}
+
+/// `JNIEXPORT jobjectArray JNICALL Java_com_dropbear_ffi_JNINative_findByTag
+///   (JNIEnv *, jclass, jlong, jstring);`
+#[unsafe(no_mangle)]
+pub fn Java_com_dropbear_ffi_JNINative_findByTag(
+    mut env: JNIEnv,
+    _class: JClass,
+    world_handle: jlong,
+    tag: JString,
+) -> jobjectArray {
+    let world = convert_ptr!(world_handle, WorldPtr => World);
+    let tag = magna_carta::normalise_tag(&convert_jstring!(env, tag));
+
+    let labels: Vec<String> = world
+        .query::<(&Label, &Script)>()
+        .iter()
+        .filter(|(_, (_, script))| script.normalised_tags().any(|t| t == tag))
+        .map(|(_, (label, _))| label.to_string())
+        .collect();
+
+    new_string_array(&mut env, &labels)
+}
+
+/// `JNIEXPORT jobjectArray JNICALL Java_com_dropbear_ffi_JNINative_getTags
+///   (JNIEnv *, jclass, jlong, jlong);`
+#[unsafe(no_mangle)]
+pub fn Java_com_dropbear_ffi_JNINative_getTags(
+    mut env: JNIEnv,
+    _class: JClass,
+    world_handle: jlong,
+    entity_id: jlong,
+) -> jobjectArray {
+    let world = convert_ptr!(world_handle, WorldPtr => World);
+    let entity = convert_jlong_to_entity!(entity_id);
+
+    let tags = match world.get::<&Script>(entity) {
+        Ok(script) => script.tags.clone(),
+        Err(_) => Vec::new(),
+    };
+
+    new_string_array(&mut env, &tags)
+}
+
+/// Gets a custom material property of the entity with the given label.
+///
+/// Returns `NaN` if the entity or the property doesn't exist.
+///
+/// `JNIEXPORT jfloat JNICALL Java_com_dropbear_ffi_JNINative_getMaterialProperty
+///   (JNIEnv *, jclass, jlong, jstring, jstring);`
+#[unsafe(no_mangle)]
+pub fn Java_com_dropbear_ffi_JNINative_getMaterialProperty(
+    mut env: JNIEnv,
+    _class: JClass,
+    world_handle: jlong,
+    label: JString,
+    key: JString,
+) -> jfloat {
+    let world = convert_ptr!(world_handle, WorldPtr => World);
+    let label = convert_jstring!(env, label);
+    let key = convert_jstring!(env, key);
+
+    for (_, (entity_label, properties)) in world.query::<(&Label, &MaterialProperties)>().iter() {
+        if entity_label.as_str() == label {
+            return properties.get(&key).unwrap_or(f32::NAN);
+        }
+    }
+
+    eprintln!(
+        "[Java_com_dropbear_ffi_JNINative_getMaterialProperty] [WARN] No entity '{}' with material properties",
+        label
+    );
+    f32::NAN
+}
+
+/// `JNIEXPORT void JNICALL Java_com_dropbear_ffi_JNINative_setMaterialProperty
+///   (JNIEnv *, jclass, jlong, jstring, jstring, jfloat);`
+#[unsafe(no_mangle)]
+pub fn Java_com_dropbear_ffi_JNINative_setMaterialProperty(
+    mut env: JNIEnv,
+    _class: JClass,
+    world_handle: jlong,
+    label: JString,
+    key: JString,
+    value: jfloat,
+) {
+    let world = convert_ptr!(mut world_handle, WorldPtr => World);
+    let label = convert_jstring!(env, label);
+    let key = convert_jstring!(env, key);
+
+    if let Err(e) = set_material_property(world, &label, key, value) {
+        eprintln!("[Java_com_dropbear_ffi_JNINative_setMaterialProperty] [ERROR] {}", e);
+    }
+}
+
+/// `JNIEXPORT void JNICALL Java_com_dropbear_ffi_JNINative_spawn
+///   (JNIEnv *, jclass, jstring, jstring, jdoubleArray);`
+///
+/// The transform is passed as `[px, py, pz, rx, ry, rz, rw, sx, sy, sz]`.
+#[unsafe(no_mangle)]
+pub fn Java_com_dropbear_ffi_JNINative_spawn(
+    mut env: JNIEnv,
+    _class: JClass,
+    model_path: JString,
+    label: JString,
+    transform: JDoubleArray,
+) {
+    let model_path = convert_jstring!(env, model_path);
+    let label = convert_jstring!(env, label);
+
+    let mut values = [0.0; 10];
+    if let Err(e) = env.get_double_array_region(&transform, 0, &mut values) {
+        eprintln!(
+            "[Java_com_dropbear_ffi_JNINative_spawn] [ERROR] Invalid transform: {}",
+            e
+        );
+        return;
+    }
+    let [px, py, pz, rx, ry, rz, rw, sx, sy, sz] = values;
+
+    request_spawn(SpawnRequest {
+        model_path,
+        label,
+        transform: Transform {
+            position: DVec3::new(px, py, pz),
+            rotation: DQuat::from_xyzw(rx, ry, rz, rw),
+            scale: DVec3::new(sx, sy, sz),
+        },
+    });
+}
+
+/// `JNIEXPORT void JNICALL Java_com_dropbear_ffi_JNINative_destroy
+///   (JNIEnv *, jclass, jstring);`
+#[unsafe(no_mangle)]
+pub fn Java_com_dropbear_ffi_JNINative_destroy(mut env: JNIEnv, _class: JClass, label: JString) {
+    let label = convert_jstring!(env, label);
+    request_despawn(label);
+}
+
+/// `JNIEXPORT jlong JNICALL Java_com_dropbear_ffi_JNINative_setTimer
+///   (JNIEnv *, jclass, jstring, jfloat, jboolean, jstring);`
+///
+/// `data` is a JSON string, and the returned id can be passed to `clearTimer`.
+#[unsafe(no_mangle)]
+pub fn Java_com_dropbear_ffi_JNINative_setTimer(
+    mut env: JNIEnv,
+    _class: JClass,
+    callback_name: JString,
+    delay: jfloat,
+    repeat: jboolean,
+    data: JString,
+) -> jlong {
+    let callback_name = convert_jstring!(env, callback_name);
+    let data = convert_jstring!(env, data);
+    request_timer(callback_name, delay, repeat != JNI_FALSE, &data) as jlong
+}
+
+/// `JNIEXPORT void JNICALL Java_com_dropbear_ffi_JNINative_clearTimer
+///   (JNIEnv *, jclass, jlong);`
+#[unsafe(no_mangle)]
+pub fn Java_com_dropbear_ffi_JNINative_clearTimer(_env: JNIEnv, _class: JClass, id: jlong) {
+    clear_timer(id as u64);
+}
+
+/// `JNIEXPORT void JNICALL Java_com_dropbear_ffi_JNINative_setGlobal
+///   (JNIEnv *, jclass, jstring, jstring);`
+///
+/// `json` is stored as a plain string if it isn't valid JSON.
+#[unsafe(no_mangle)]
+pub fn Java_com_dropbear_ffi_JNINative_setGlobal(
+    mut env: JNIEnv,
+    _class: JClass,
+    key: JString,
+    json: JString,
+) {
+    let key = convert_jstring!(env, key);
+    let json = convert_jstring!(env, json);
+    let value = serde_json::from_str(&json).unwrap_or(serde_json::Value::String(json));
+    ResourceRegistry::global().set_value(key, value);
+}
+
+/// `JNIEXPORT jstring JNICALL Java_com_dropbear_ffi_JNINative_getGlobal
+///   (JNIEnv *, jclass, jstring);`
+///
+/// Returns the value as a JSON string, or `null` if nothing is stored under `key`.
+#[unsafe(no_mangle)]
+pub fn Java_com_dropbear_ffi_JNINative_getGlobal(
+    mut env: JNIEnv,
+    _class: JClass,
+    key: JString,
+) -> jstring {
+    let key = convert_jstring!(env, key);
+    let Some(value) = ResourceRegistry::global().get_value(&key) else {
+        return std::ptr::null_mut();
+    };
+
+    match env.new_string(value.to_string()) {
+        Ok(string) => string.as_raw(),
+        Err(e) => {
+            eprintln!(
+                "[Java_com_dropbear_ffi_JNINative_getGlobal] [ERROR] Failed to create string: {}",
+                e
+            );
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// `JNIEXPORT void JNICALL Java_com_dropbear_ffi_JNINative_setStore
+///   (JNIEnv *, jclass, jstring, jstring);`
+///
+/// `json` is stored as a plain string if it isn't valid JSON.
+#[unsafe(no_mangle)]
+pub fn Java_com_dropbear_ffi_JNINative_setStore(
+    mut env: JNIEnv,
+    _class: JClass,
+    key: JString,
+    json: JString,
+) {
+    let key = convert_jstring!(env, key);
+    let json = convert_jstring!(env, json);
+    let value = serde_json::from_str(&json).unwrap_or(serde_json::Value::String(json));
+    set_store(key, value);
+}
+
+/// `JNIEXPORT jstring JNICALL Java_com_dropbear_ffi_JNINative_getStore
+///   (JNIEnv *, jclass, jstring);`
+///
+/// Returns the value as a JSON string, or `null` if nothing is stored under `key`.
+#[unsafe(no_mangle)]
+pub fn Java_com_dropbear_ffi_JNINative_getStore(
+    mut env: JNIEnv,
+    _class: JClass,
+    key: JString,
+) -> jstring {
+    let key = convert_jstring!(env, key);
+    let Some(value) = get_store(&key) else {
+        return std::ptr::null_mut();
+    };
+
+    match env.new_string(value.to_string()) {
+        Ok(string) => string.as_raw(),
+        Err(e) => {
+            eprintln!(
+                "[Java_com_dropbear_ffi_JNINative_getStore] [ERROR] Failed to create string: {}",
+                e
+            );
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// `JNIEXPORT void JNICALL Java_com_dropbear_ffi_JNINative_drawText
+///   (JNIEnv *, jclass, jstring, jstring, jfloat, jdouble, jdouble, jdouble, jfloat, jfloat, jfloat, jfloat);`
+#[unsafe(no_mangle)]
+pub fn Java_com_dropbear_ffi_JNINative_drawText(
+    mut env: JNIEnv,
+    _class: JClass,
+    text: JString,
+    font: JString,
+    size: jfloat,
+    x: jdouble,
+    y: jdouble,
+    z: jdouble,
+    r: jfloat,
+    g: jfloat,
+    b: jfloat,
+    a: jfloat,
+) {
+    let text = convert_jstring!(env, text);
+    let font = convert_jstring!(env, font);
+
+    let anchor = TextAnchor::World(DVec3::new(x, y, z));
+    if let Err(e) = draw_text(text, &font, size, anchor, [r, g, b, a]) {
+        eprintln!(
+            "[Java_com_dropbear_ffi_JNINative_drawText] [ERROR] Unable to draw text: {}",
+            e
+        );
+    }
+}
+
+/// `JNIEXPORT void JNICALL Java_com_dropbear_ffi_JNINative_drawTextScreen
+///   (JNIEnv *, jclass, jstring, jstring, jfloat, jfloat, jfloat, jfloat, jfloat, jfloat, jfloat);`
+#[unsafe(no_mangle)]
+pub fn Java_com_dropbear_ffi_JNINative_drawTextScreen(
+    mut env: JNIEnv,
+    _class: JClass,
+    text: JString,
+    font: JString,
+    size: jfloat,
+    x: jfloat,
+    y: jfloat,
+    r: jfloat,
+    g: jfloat,
+    b: jfloat,
+    a: jfloat,
+) {
+    let text = convert_jstring!(env, text);
+    let font = convert_jstring!(env, font);
+
+    let anchor = TextAnchor::Screen(Vec2::new(x, y));
+    if let Err(e) = draw_text(text, &font, size, anchor, [r, g, b, a]) {
+        eprintln!(
+            "[Java_com_dropbear_ffi_JNINative_drawTextScreen] [ERROR] Unable to draw text: {}",
+            e
+        );
+    }
+}
+
+/// `JNIEXPORT void JNICALL Java_com_dropbear_ffi_JNINative_setDepthOfField
+///   (JNIEnv *, jclass, jfloat, jfloat, jfloat, jstring);`
+///
+/// `focus_entity` can be `null` to use the focal distance instead.
+#[unsafe(no_mangle)]
+pub fn Java_com_dropbear_ffi_JNINative_setDepthOfField(
+    mut env: JNIEnv,
+    _class: JClass,
+    focal_distance: jfloat,
+    focal_range: jfloat,
+    max_blur: jfloat,
+    focus_entity: JString,
+) {
+    let focus_entity = if focus_entity.is_null() {
+        None
+    } else {
+        Some(convert_jstring!(env, focus_entity))
+    };
+
+    depth_of_field::set_depth_of_field(DepthOfFieldSettings {
+        focal_distance,
+        focal_range,
+        max_blur,
+        focus_entity,
+    });
+}
+
+/// `JNIEXPORT void JNICALL Java_com_dropbear_ffi_JNINative_clearDepthOfField
+///   (JNIEnv *, jclass);`
+#[unsafe(no_mangle)]
+pub fn Java_com_dropbear_ffi_JNINative_clearDepthOfField(_env: JNIEnv, _class: JClass) {
+    depth_of_field::clear_depth_of_field();
+}
@@ -1,7 +1,7 @@
 use glam::Vec3;
 use jni::JNIEnv;
 use jni::objects::{JFloatArray, JObject, JValue};
-use jni::sys::{jfloatArray, jint};
+use jni::sys::{jfloatArray, jint, jobjectArray};
 
 pub fn new_float_array(env: &mut JNIEnv, x: f32, y: f32) -> jfloatArray {
     let java_array: JFloatArray = match env.new_float_array(2) {
@@ -26,6 +26,30 @@ pub fn new_float_array(env: &mut JNIEnv, x: f32, y: f32) -> jfloatArray {
     }
 }
 
+/// Creates a `String[]` from a list of Rust strings, returning null on failure.
+pub fn new_string_array(env: &mut JNIEnv, values: &[String]) -> jobjectArray {
+    let array = match env.new_object_array(values.len() as i32, "java/lang/String", JObject::null())
+    {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("[ERROR] Failed to create string array: {}", e);
+            return std::ptr::null_mut();
+        }
+    };
+
+    for (index, value) in values.iter().enumerate() {
+        let result = env
+            .new_string(value)
+            .and_then(|s| env.set_object_array_element(&array, index as i32, JObject::from(s)));
+        if let Err(e) = result {
+            eprintln!("[ERROR] Failed to set string array element: {}", e);
+            return std::ptr::null_mut();
+        }
+    }
+
+    array.into_raw()
+}
+
 const JAVA_MOUSE_BUTTON_LEFT: jint = 0;
 const JAVA_MOUSE_BUTTON_RIGHT: jint = 1;
 const JAVA_MOUSE_BUTTON_MIDDLE: jint = 2;
@@ -1,13 +1,23 @@
-use crate::camera::{CameraComponent, CameraType};
+use crate::camera::{
+    CameraComponent, CameraType, find_camera_path, play_camera_path, scripted_camera,
+};
 use crate::ptr::{GraphicsPtr, InputStatePtr};
+use crate::runtime::ResourceRegistry;
 use crate::scripting::native::DropbearNativeError;
 use crate::scripting::native::types::{NativeCamera, NativeTransform, Vector3D};
-use crate::states::{Label, ModelProperties, Value};
-use crate::utils::keycode_from_ordinal;
+use crate::scripting::{
+    SpawnRequest, draw_text, get_store, request_despawn, request_spawn, set_store,
+};
+use crate::states::{Label, MaterialProperties, ModelProperties, Value, set_material_property};
+use crate::utils::{gamepad_button_from_ordinal, keycode_from_ordinal};
 use crate::window::{GraphicsCommand, WindowCommand};
-use dropbear_engine::camera::Camera;
-use dropbear_engine::entity::{EntityTransform, MeshRenderer};
-use glam::DVec3;
+use dropbear_engine::camera::{Camera, CameraShake};
+use dropbear_engine::depth_of_field::{self, DepthOfFieldSettings};
+use dropbear_engine::entity::{EntityTransform, MeshRenderer, Transform};
+use dropbear_engine::font::TextAnchor;
+use dropbear_engine::morph::MorphWeights;
+use dropbear_engine::particles::ParticleEmitter;
+use glam::{DQuat, DVec3, Vec2};
 use hecs::World;
 use std::ffi::{CStr, c_char};
 
@@ -1050,3 +1060,533 @@ pub unsafe extern "C" fn dropbear_set_cursor_hidden(
         DropbearNativeError::Success as i32
     }
 }
+
+/// Writes the ids of up to `capacity` connected gamepads to `out_ids`, and the total number of
+/// connected gamepads to `out_count`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dropbear_get_connected_gamepads(
+    input_state_ptr: InputStatePtr,
+    out_ids: *mut u64,
+    capacity: usize,
+    out_count: *mut usize,
+) -> i32 {
+    if input_state_ptr.is_null() || out_count.is_null() || (capacity > 0 && out_ids.is_null()) {
+        return DropbearNativeError::NullPointer as i32;
+    }
+
+    let input = unsafe { &*input_state_ptr };
+
+    for (i, id) in input.connected_gamepads.iter().take(capacity).enumerate() {
+        unsafe { *out_ids.add(i) = usize::from(*id) as u64 };
+    }
+    unsafe { *out_count = input.connected_gamepads.len() };
+
+    DropbearNativeError::Success as i32
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dropbear_is_gamepad_button_pressed(
+    input_state_ptr: InputStatePtr,
+    gamepad_id: u64,
+    button: i32,
+    out_pressed: *mut i32,
+) -> i32 {
+    if input_state_ptr.is_null() || out_pressed.is_null() {
+        return DropbearNativeError::NullPointer as i32;
+    }
+
+    let input = unsafe { &*input_state_ptr };
+
+    let Some(button) = gamepad_button_from_ordinal(button) else {
+        eprintln!("[dropbear_is_gamepad_button_pressed] [WARN] Invalid button code");
+        unsafe { *out_pressed = 0 };
+        return DropbearNativeError::Success as i32;
+    };
+
+    let pressed = input
+        .gamepad_from_id(gamepad_id as usize)
+        .is_some_and(|gamepad| input.is_button_pressed(gamepad, button));
+    unsafe { *out_pressed = if pressed { 1 } else { 0 } };
+
+    DropbearNativeError::Success as i32
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dropbear_get_gamepad_sticks(
+    input_state_ptr: InputStatePtr,
+    gamepad_id: u64,
+    out_left_x: *mut f32,
+    out_left_y: *mut f32,
+    out_right_x: *mut f32,
+    out_right_y: *mut f32,
+) -> i32 {
+    if input_state_ptr.is_null()
+        || out_left_x.is_null()
+        || out_left_y.is_null()
+        || out_right_x.is_null()
+        || out_right_y.is_null()
+    {
+        return DropbearNativeError::NullPointer as i32;
+    }
+
+    let input = unsafe { &*input_state_ptr };
+
+    let (left, right) = match input.gamepad_from_id(gamepad_id as usize) {
+        Some(gamepad) => (
+            input.get_left_stick(gamepad),
+            input.get_right_stick(gamepad),
+        ),
+        None => ((0.0, 0.0), (0.0, 0.0)),
+    };
+
+    unsafe {
+        *out_left_x = left.0;
+        *out_left_y = left.1;
+        *out_right_x = right.0;
+        *out_right_y = right.1;
+    }
+
+    DropbearNativeError::Success as i32
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dropbear_emit_particles(
+    world_ptr: *const World,
+    label: *const c_char,
+    count: i32,
+) -> i32 {
+    if world_ptr.is_null() || label.is_null() {
+        return DropbearNativeError::NullPointer as i32;
+    }
+
+    let world = unsafe { &*world_ptr };
+    let Ok(label_str) = unsafe { CStr::from_ptr(label) }.to_str() else {
+        return DropbearNativeError::InvalidUTF8 as i32;
+    };
+
+    for (_, (entity_label, emitter)) in world.query::<(&Label, &mut ParticleEmitter)>().iter() {
+        if entity_label.as_str() == label_str {
+            emitter.emit(count.max(0) as u32);
+            return DropbearNativeError::Success as i32;
+        }
+    }
+
+    DropbearNativeError::NoSuchComponent as i32
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dropbear_set_emission_rate(
+    world_ptr: *const World,
+    label: *const c_char,
+    rate: f32,
+) -> i32 {
+    if world_ptr.is_null() || label.is_null() {
+        return DropbearNativeError::NullPointer as i32;
+    }
+
+    let world = unsafe { &*world_ptr };
+    let Ok(label_str) = unsafe { CStr::from_ptr(label) }.to_str() else {
+        return DropbearNativeError::InvalidUTF8 as i32;
+    };
+
+    for (_, (entity_label, emitter)) in world.query::<(&Label, &mut ParticleEmitter)>().iter() {
+        if entity_label.as_str() == label_str {
+            emitter.emission_rate = rate.max(0.0);
+            return DropbearNativeError::Success as i32;
+        }
+    }
+
+    DropbearNativeError::NoSuchComponent as i32
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dropbear_set_morph_weight(
+    world_ptr: *const World,
+    label: *const c_char,
+    target_name: *const c_char,
+    weight: f32,
+) -> i32 {
+    if world_ptr.is_null() || label.is_null() || target_name.is_null() {
+        return DropbearNativeError::NullPointer as i32;
+    }
+
+    let world = unsafe { &*world_ptr };
+    let Ok(label_str) = unsafe { CStr::from_ptr(label) }.to_str() else {
+        return DropbearNativeError::InvalidUTF8 as i32;
+    };
+    let Ok(target_str) = unsafe { CStr::from_ptr(target_name) }.to_str() else {
+        return DropbearNativeError::InvalidUTF8 as i32;
+    };
+
+    for (_, (entity_label, weights)) in world.query::<(&Label, &mut MorphWeights)>().iter() {
+        if entity_label.as_str() == label_str {
+            weights.set(target_str, weight);
+            return DropbearNativeError::Success as i32;
+        }
+    }
+
+    DropbearNativeError::NoSuchComponent as i32
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dropbear_shake_camera(
+    world_ptr: *const World,
+    intensity: f32,
+    duration: f32,
+) -> i32 {
+    if world_ptr.is_null() {
+        return DropbearNativeError::NullPointer as i32;
+    }
+
+    let world = unsafe { &*world_ptr };
+
+    // the debug camera is only used by the editor, so it never shakes
+    for (_, (camera, component)) in world.query::<(&mut Camera, &CameraComponent)>().iter() {
+        if !matches!(component.camera_type, CameraType::Debug) {
+            camera.apply_shake(CameraShake::new(intensity, duration));
+        }
+    }
+
+    DropbearNativeError::Success as i32
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dropbear_play_camera_path(
+    world_ptr: *mut World,
+    name: *const c_char,
+) -> i32 {
+    if world_ptr.is_null() || name.is_null() {
+        return DropbearNativeError::NullPointer as i32;
+    }
+
+    let world = unsafe { &mut *world_ptr };
+    let name = unsafe { CStr::from_ptr(name) };
+    let Ok(name) = name.to_str() else {
+        return DropbearNativeError::InvalidUTF8 as i32;
+    };
+
+    let Some(path) = find_camera_path(name) else {
+        return DropbearNativeError::QueryFailed as i32;
+    };
+
+    let Some(camera) = scripted_camera(world) else {
+        return DropbearNativeError::NoSuchEntity as i32;
+    };
+
+    match play_camera_path(world, camera, path) {
+        Ok(()) => DropbearNativeError::Success as i32,
+        Err(_) => DropbearNativeError::WorldInsertError as i32,
+    }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dropbear_get_material_property(
+    world_ptr: *const World,
+    label: *const c_char,
+    key: *const c_char,
+    out_value: *mut f32,
+) -> i32 {
+    if world_ptr.is_null() || label.is_null() || key.is_null() || out_value.is_null() {
+        return DropbearNativeError::NullPointer as i32;
+    }
+
+    let world = unsafe { &*world_ptr };
+    let Ok(label_str) = unsafe { CStr::from_ptr(label) }.to_str() else {
+        return DropbearNativeError::InvalidUTF8 as i32;
+    };
+    let Ok(key_str) = unsafe { CStr::from_ptr(key) }.to_str() else {
+        return DropbearNativeError::InvalidUTF8 as i32;
+    };
+
+    for (_, (entity_label, properties)) in world.query::<(&Label, &MaterialProperties)>().iter() {
+        if entity_label.as_str() == label_str {
+            return match properties.get(key_str) {
+                Some(value) => {
+                    unsafe { *out_value = value };
+                    DropbearNativeError::Success as i32
+                }
+                None => DropbearNativeError::QueryFailed as i32,
+            };
+        }
+    }
+
+    DropbearNativeError::NoSuchComponent as i32
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dropbear_set_material_property(
+    world_ptr: *mut World,
+    label: *const c_char,
+    key: *const c_char,
+    value: f32,
+) -> i32 {
+    if world_ptr.is_null() || label.is_null() || key.is_null() {
+        return DropbearNativeError::NullPointer as i32;
+    }
+
+    let world = unsafe { &mut *world_ptr };
+    let Ok(label_str) = unsafe { CStr::from_ptr(label) }.to_str() else {
+        return DropbearNativeError::InvalidUTF8 as i32;
+    };
+    let Ok(key_str) = unsafe { CStr::from_ptr(key) }.to_str() else {
+        return DropbearNativeError::InvalidUTF8 as i32;
+    };
+
+    match set_material_property(world, label_str, key_str, value) {
+        Ok(()) => DropbearNativeError::Success as i32,
+        Err(_) => DropbearNativeError::WorldInsertError as i32,
+    }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dropbear_spawn(
+    model_path: *const c_char,
+    label: *const c_char,
+    transform: *const NativeTransform,
+) -> i32 {
+    if model_path.is_null() || label.is_null() || transform.is_null() {
+        return DropbearNativeError::NullPointer as i32;
+    }
+
+    let Ok(model_path) = unsafe { CStr::from_ptr(model_path) }.to_str() else {
+        return DropbearNativeError::InvalidUTF8 as i32;
+    };
+    let Ok(label) = unsafe { CStr::from_ptr(label) }.to_str() else {
+        return DropbearNativeError::InvalidUTF8 as i32;
+    };
+    let transform = unsafe { &*transform };
+
+    request_spawn(SpawnRequest {
+        model_path: model_path.to_string(),
+        label: label.to_string(),
+        transform: Transform {
+            position: DVec3::new(
+                transform.position_x,
+                transform.position_y,
+                transform.position_z,
+            ),
+            rotation: DQuat::from_xyzw(
+                transform.rotation_x,
+                transform.rotation_y,
+                transform.rotation_z,
+                transform.rotation_w,
+            ),
+            scale: DVec3::new(transform.scale_x, transform.scale_y, transform.scale_z),
+        },
+    });
+
+    DropbearNativeError::Success as i32
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dropbear_destroy(label: *const c_char) -> i32 {
+    if label.is_null() {
+        return DropbearNativeError::NullPointer as i32;
+    }
+
+    let Ok(label) = unsafe { CStr::from_ptr(label) }.to_str() else {
+        return DropbearNativeError::InvalidUTF8 as i32;
+    };
+
+    request_despawn(label.to_string());
+    DropbearNativeError::Success as i32
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dropbear_set_global(key: *const c_char, json: *const c_char) -> i32 {
+    if key.is_null() || json.is_null() {
+        return DropbearNativeError::NullPointer as i32;
+    }
+
+    let Ok(key) = unsafe { CStr::from_ptr(key) }.to_str() else {
+        return DropbearNativeError::InvalidUTF8 as i32;
+    };
+    let Ok(json) = unsafe { CStr::from_ptr(json) }.to_str() else {
+        return DropbearNativeError::InvalidUTF8 as i32;
+    };
+
+    let value =
+        serde_json::from_str(json).unwrap_or_else(|_| serde_json::Value::String(json.to_string()));
+    ResourceRegistry::global().set_value(key, value);
+    DropbearNativeError::Success as i32
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dropbear_get_global(
+    key: *const c_char,
+    out_json: *mut c_char,
+    out_json_max_length: usize,
+) -> i32 {
+    if key.is_null() || out_json.is_null() {
+        return DropbearNativeError::NullPointer as i32;
+    }
+
+    let Ok(key) = unsafe { CStr::from_ptr(key) }.to_str() else {
+        return DropbearNativeError::InvalidUTF8 as i32;
+    };
+    let Some(value) = ResourceRegistry::global().get_value(key) else {
+        return DropbearNativeError::QueryFailed as i32;
+    };
+
+    // cutting the json off would leave it unparseable, so it has to fit with its null terminator
+    let json = value.to_string();
+    if json.len() >= out_json_max_length {
+        eprintln!(
+            "[dropbear_get_global] [ERROR] Value of '{}' does not fit in the buffer",
+            key
+        );
+        return DropbearNativeError::UnknownError as i32;
+    }
+
+    unsafe {
+        std::ptr::copy_nonoverlapping(json.as_ptr(), out_json as *mut u8, json.len());
+        *out_json.add(json.len()) = 0;
+    }
+    DropbearNativeError::Success as i32
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dropbear_set_store(key: *const c_char, json: *const c_char) -> i32 {
+    if key.is_null() || json.is_null() {
+        return DropbearNativeError::NullPointer as i32;
+    }
+
+    let Ok(key) = unsafe { CStr::from_ptr(key) }.to_str() else {
+        return DropbearNativeError::InvalidUTF8 as i32;
+    };
+    let Ok(json) = unsafe { CStr::from_ptr(json) }.to_str() else {
+        return DropbearNativeError::InvalidUTF8 as i32;
+    };
+
+    let value =
+        serde_json::from_str(json).unwrap_or_else(|_| serde_json::Value::String(json.to_string()));
+    set_store(key, value);
+    DropbearNativeError::Success as i32
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dropbear_get_store(
+    key: *const c_char,
+    out_json: *mut c_char,
+    out_json_max_length: usize,
+) -> i32 {
+    if key.is_null() || out_json.is_null() {
+        return DropbearNativeError::NullPointer as i32;
+    }
+
+    let Ok(key) = unsafe { CStr::from_ptr(key) }.to_str() else {
+        return DropbearNativeError::InvalidUTF8 as i32;
+    };
+    let Some(value) = get_store(key) else {
+        return DropbearNativeError::QueryFailed as i32;
+    };
+
+    // cutting the json off would leave it unparseable, so it has to fit with its null terminator
+    let json = value.to_string();
+    if json.len() >= out_json_max_length {
+        eprintln!(
+            "[dropbear_get_store] [ERROR] Value of '{}' does not fit in the buffer",
+            key
+        );
+        return DropbearNativeError::UnknownError as i32;
+    }
+
+    unsafe {
+        std::ptr::copy_nonoverlapping(json.as_ptr(), out_json as *mut u8, json.len());
+        *out_json.add(json.len()) = 0;
+    }
+    DropbearNativeError::Success as i32
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dropbear_draw_text(
+    text: *const c_char,
+    font: *const c_char,
+    size: f32,
+    x: f64,
+    y: f64,
+    z: f64,
+    r: f32,
+    g: f32,
+    b: f32,
+    a: f32,
+) -> i32 {
+    let anchor = TextAnchor::World(DVec3::new(x, y, z));
+    unsafe { queue_text(text, font, size, anchor, [r, g, b, a]) }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dropbear_draw_text_screen(
+    text: *const c_char,
+    font: *const c_char,
+    size: f32,
+    x: f32,
+    y: f32,
+    r: f32,
+    g: f32,
+    b: f32,
+    a: f32,
+) -> i32 {
+    let anchor = TextAnchor::Screen(Vec2::new(x, y));
+    unsafe { queue_text(text, font, size, anchor, [r, g, b, a]) }
+}
+
+/// Reads the strings passed to [`dropbear_draw_text`] and [`dropbear_draw_text_screen`] and
+/// queues the text.
+unsafe fn queue_text(
+    text: *const c_char,
+    font: *const c_char,
+    size: f32,
+    anchor: TextAnchor,
+    color: [f32; 4],
+) -> i32 {
+    if text.is_null() || font.is_null() {
+        return DropbearNativeError::NullPointer as i32;
+    }
+
+    let Ok(text) = unsafe { CStr::from_ptr(text) }.to_str() else {
+        return DropbearNativeError::InvalidUTF8 as i32;
+    };
+    let Ok(font) = unsafe { CStr::from_ptr(font) }.to_str() else {
+        return DropbearNativeError::InvalidUTF8 as i32;
+    };
+
+    match draw_text(text.to_string(), font, size, anchor, color) {
+        Ok(()) => DropbearNativeError::Success as i32,
+        Err(e) => {
+            eprintln!("[dropbear_draw_text] [ERROR] Unable to draw text: {}", e);
+            DropbearNativeError::UnknownError as i32
+        }
+    }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dropbear_set_depth_of_field(
+    focal_distance: f32,
+    focal_range: f32,
+    max_blur: f32,
+    focus_entity: *const c_char,
+) -> i32 {
+    let focus_entity = if focus_entity.is_null() {
+        None
+    } else {
+        let Ok(label) = unsafe { CStr::from_ptr(focus_entity) }.to_str() else {
+            return DropbearNativeError::InvalidUTF8 as i32;
+        };
+        Some(label.to_string())
+    };
+
+    depth_of_field::set_depth_of_field(DepthOfFieldSettings {
+        focal_distance,
+        focal_range,
+        max_blur,
+        focus_entity,
+    });
+    DropbearNativeError::Success as i32
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dropbear_clear_depth_of_field() -> i32 {
+    depth_of_field::clear_depth_of_field();
+    DropbearNativeError::Success as i32
+}
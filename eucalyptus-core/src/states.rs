@@ -2,9 +2,10 @@ use crate::camera::{CameraComponent, CameraType};
 use crate::config::{ProjectConfig, ResourceConfig, SourceConfig};
 use crate::scene::SceneConfig;
 use crate::traits::SerializableComponent;
-use dropbear_engine::camera::Camera;
+use dropbear_engine::camera::{Camera, ProjectionKind};
 use dropbear_engine::entity::{MaterialOverride, MeshRenderer, Transform};
 use dropbear_engine::lighting::LightComponent;
+use dropbear_engine::tone_mapping::ToneMappingOperator;
 use dropbear_engine::utils::ResourceReference;
 use dropbear_macro::SerializableComponent;
 use egui::Ui;
@@ -144,6 +145,19 @@ impl Display for ResourceType {
 #[derive(Default, Debug, Serialize, Deserialize, Clone, SerializableComponent)]
 pub struct Script {
     pub tags: Vec<String>,
+    /// The order in which this entity's scripts are updated each frame. Lower values run first.
+    #[serde(default)]
+    pub priority: i32,
+}
+
+impl Script {
+    /// The tags of the script, trimmed and lowercased to match the tags in the script manifest.
+    pub fn normalised_tags(&self) -> impl Iterator<Item = String> + '_ {
+        self.tags
+            .iter()
+            .map(|tag| magna_carta::normalise_tag(tag))
+            .filter(|tag| !tag.is_empty())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, SerializableComponent)]
@@ -156,11 +170,20 @@ pub struct Camera3D {
     pub fov: f32,
     pub near: f32,
     pub far: f32,
+    #[serde(default)]
+    pub projection: ProjectionKind,
 
     pub speed: f32,
     pub sensitivity: f32,
 
     pub starting_camera: bool,
+
+    #[serde(default = "all_layers")]
+    pub visible_layers: u32,
+}
+
+fn all_layers() -> u32 {
+    u32::MAX
 }
 
 impl Default for Camera3D {
@@ -172,11 +195,13 @@ impl Default for Camera3D {
             fov: 45.0,
             near: 0.1,
             far: 100.0,
+            projection: ProjectionKind::Perspective,
             label: String::new(),
             camera_type: CameraType::Normal,
             speed: default.settings.speed as f32,
             sensitivity: default.settings.sensitivity as f32,
             starting_camera: false,
+            visible_layers: u32::MAX,
         }
     }
 }
@@ -210,9 +235,11 @@ impl Camera3D {
             fov: camera.settings.fov_y as f32,
             near: camera.znear as f32,
             far: camera.zfar as f32,
+            projection: camera.projection,
             speed: component.settings.speed as f32,
             sensitivity: component.settings.sensitivity as f32,
             starting_camera: component.starting_camera,
+            visible_layers: camera.visible_layers,
         }
     }
 }
@@ -369,6 +396,112 @@ impl Default for ModelProperties {
     }
 }
 
+/// The maximum number of custom material properties that can be sent to the shader.
+pub const MAX_MATERIAL_PROPERTIES: usize = 8;
+
+/// Named float values that are passed to the shader of an entity, queried with
+/// `engine.getMaterialProperty` and `engine.setMaterialProperty`.
+///
+/// Up to [`MAX_MATERIAL_PROPERTIES`] values are packed into two `vec4<f32>`s per instance, in
+/// alphabetical order of their keys.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Clone, SerializableComponent)]
+pub struct MaterialProperties {
+    pub custom: HashMap<String, f32>,
+}
+
+impl MaterialProperties {
+    /// Creates a new [MaterialProperties] without any values
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fetches the value of a property
+    pub fn get(&self, key: &str) -> Option<f32> {
+        self.custom.get(key).copied()
+    }
+
+    /// Sets the value of a property, adding it if it does not exist yet.
+    ///
+    /// Returns an error if the property is new and there are already
+    /// [`MAX_MATERIAL_PROPERTIES`] properties.
+    pub fn set(&mut self, key: impl Into<String>, value: f32) -> anyhow::Result<()> {
+        let key = key.into();
+        if !self.custom.contains_key(&key) && self.custom.len() >= MAX_MATERIAL_PROPERTIES {
+            anyhow::bail!(
+                "Unable to add material property '{}', only {} are supported",
+                key,
+                MAX_MATERIAL_PROPERTIES
+            );
+        }
+        self.custom.insert(key, value);
+        Ok(())
+    }
+
+    /// Returns the keys sorted in the order they are packed for the shader
+    pub fn sorted_keys(&self) -> Vec<&String> {
+        let mut keys: Vec<&String> = self.custom.keys().collect();
+        keys.sort();
+        keys
+    }
+
+    /// Packs the values into the two `vec4<f32>`s read by the shader
+    pub fn packed(&self) -> [[f32; 4]; 2] {
+        let mut packed = [[0.0; 4]; 2];
+        for (i, key) in self
+            .sorted_keys()
+            .into_iter()
+            .take(MAX_MATERIAL_PROPERTIES)
+            .enumerate()
+        {
+            packed[i / 4][i % 4] = self.custom[key];
+        }
+        packed
+    }
+}
+
+/// Replaces the default PBR shader of an entity with a WGSL shader from the project.
+///
+/// The shader is drawn with the same vertex buffers and bind groups as the default shader, so it
+/// needs a `vs_main` and `fs_main` entry point that take the same inputs.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Clone, SerializableComponent)]
+pub struct MaterialShader {
+    /// The `.wgsl` file relative to the root of the project, or `None` to use the default shader
+    pub shader_path: Option<PathBuf>,
+}
+
+impl MaterialShader {
+    pub fn new(shader_path: impl Into<PathBuf>) -> Self {
+        Self {
+            shader_path: Some(shader_path.into()),
+        }
+    }
+}
+
+/// Sets a material property on the entity with the given label, adding a [MaterialProperties]
+/// component to it if it doesn't have one.
+pub fn set_material_property(
+    world: &mut hecs::World,
+    label: &str,
+    key: impl Into<String>,
+    value: f32,
+) -> anyhow::Result<()> {
+    let entity = world
+        .query::<&Label>()
+        .iter()
+        .find(|(_, entity_label)| entity_label.as_str() == label)
+        .map(|(entity, _)| entity)
+        .ok_or_else(|| anyhow::anyhow!("No entity with the label '{}'", label))?;
+
+    if let Ok(mut properties) = world.get::<&mut MaterialProperties>(entity) {
+        return properties.set(key, value);
+    }
+
+    let mut properties = MaterialProperties::new();
+    properties.set(key, value)?;
+    world.insert_one(entity, properties)?;
+    Ok(())
+}
+
 // A serializable configuration struct for the [Light] type
 #[derive(Debug, Serialize, Deserialize, Clone, SerializableComponent)]
 pub struct Light {
@@ -394,9 +527,121 @@ impl Default for Light {
 }
 
 /// Describes the settings of the editor, not the project or the scene.
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct EditorSettings {
     pub is_debug_menu_shown: bool,
+    /// The index of the monitor the editor goes fullscreen on, `None` for the primary monitor
+    #[serde(default)]
+    pub preferred_monitor: Option<usize>,
+    /// The port external tools connect to, `None` for the default of 7890
+    #[serde(default)]
+    pub remote_port: Option<u16>,
+    /// How the gizmo snaps transforms while they are dragged
+    #[serde(default)]
+    pub snap: SnapSettings,
+    /// Draws the bounding box of every entity in the viewport, not just the selected one
+    #[serde(default)]
+    pub always_show_aabbs: bool,
+    /// How much bigger the editor's UI is drawn than the monitor's scale factor asks for, within
+    /// [`dropbear_engine::UI_SCALE_RANGE`]
+    #[serde(default = "default_ui_scale")]
+    pub ui_scale: f32,
+    /// The curve the viewport is tone mapped with
+    #[serde(default)]
+    pub tone_mapping: ToneMappingOperator,
+}
+
+fn default_ui_scale() -> f32 {
+    1.0
+}
+
+impl Default for EditorSettings {
+    fn default() -> Self {
+        Self {
+            is_debug_menu_shown: false,
+            preferred_monitor: None,
+            remote_port: None,
+            snap: SnapSettings::default(),
+            always_show_aabbs: false,
+            ui_scale: default_ui_scale(),
+            tone_mapping: ToneMappingOperator::default(),
+        }
+    }
+}
+
+/// The steps that the gizmo rounds transforms to while dragging, where `None` turns snapping off.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub struct SnapSettings {
+    /// The size of the grid that positions are rounded to
+    pub position_snap: Option<f64>,
+    /// The step that rotations are rounded to, in degrees
+    pub rotation_snap: Option<f64>,
+    /// The step that scales are rounded to
+    pub scale_snap: Option<f64>,
+}
+
+impl SnapSettings {
+    pub const DEFAULT_POSITION_SNAP: f64 = 0.25;
+    pub const DEFAULT_ROTATION_SNAP: f64 = 15.0;
+    pub const DEFAULT_SCALE_SNAP: f64 = 0.1;
+
+    /// Checks if any kind of snapping is turned on.
+    pub fn is_enabled(&self) -> bool {
+        self.position_snap.is_some() || self.rotation_snap.is_some() || self.scale_snap.is_some()
+    }
+
+    /// Returns the settings with each kind of snapping flipped, turning on the ones that are off
+    /// with their default step. Used while Ctrl is held.
+    pub fn toggled(&self) -> Self {
+        let flip = |snap: Option<f64>, default: f64| match snap {
+            Some(_) => None,
+            None => Some(default),
+        };
+        Self {
+            position_snap: flip(self.position_snap, Self::DEFAULT_POSITION_SNAP),
+            rotation_snap: flip(self.rotation_snap, Self::DEFAULT_ROTATION_SNAP),
+            scale_snap: flip(self.scale_snap, Self::DEFAULT_SCALE_SNAP),
+        }
+    }
+
+    /// Rounds each part of the transform to its step. Rotations are rounded as YXZ euler angles,
+    /// the same ones shown in the inspector, and scales never round down to zero.
+    pub fn apply(&self, transform: &mut Transform) {
+        let round = |value: f64, step: f64| {
+            if step > 0.0 {
+                (value / step).round() * step
+            } else {
+                value
+            }
+        };
+
+        if let Some(step) = self.position_snap {
+            transform.position = transform.position.map(|v| round(v, step));
+        }
+
+        if let Some(step) = self.rotation_snap {
+            let step = step.to_radians();
+            let (y, x, z) = transform.rotation.to_euler(glam::EulerRot::YXZ);
+            transform.rotation = glam::DQuat::from_euler(
+                glam::EulerRot::YXZ,
+                round(y, step),
+                round(x, step),
+                round(z, step),
+            );
+        }
+
+        if let Some(step) = self.scale_snap {
+            // a scale of zero collapses the entity, so it is kept at least one step away
+            transform.scale = transform.scale.map(|v| {
+                let snapped = round(v, step);
+                if step > 0.0 && snapped == 0.0 {
+                    step.copysign(v)
+                } else {
+                    snapped
+                }
+            });
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -406,6 +651,9 @@ pub enum EditorTab {
     ModelEntityList,   // right side,
     Viewport,          // middle,
     ErrorConsole,
+    CameraPath,
+    UndoHistory,
+    Statistics,
     Plugin(usize),
 }
 
@@ -420,12 +668,53 @@ pub struct PluginInfo {
 /// is recommended to be used with an [`UnboundedSender`]
 pub enum WorldLoadingStatus {
     Idle,
-    LoadingEntity {
-        index: usize,
-        name: String,
-        total: usize,
+    /// Something is being loaded, which is step `current` (counting from 1) out of `total` steps
+    /// across every phase.
+    Progress {
+        current: u32,
+        total: u32,
+        phase: LoadPhase,
+        current_name: String,
     },
     Completed,
+    /// The load was cancelled before it finished, and its world was discarded
+    Cancelled,
+}
+
+impl WorldLoadingStatus {
+    /// How much of the world has been loaded, from `0.0` to `1.0`.
+    pub fn progress(&self) -> f32 {
+        match self {
+            WorldLoadingStatus::Progress { current, total, .. } if *total > 0 => {
+                (*current as f32 / *total as f32).min(1.0)
+            }
+            WorldLoadingStatus::Completed => 1.0,
+            _ => 0.0,
+        }
+    }
+}
+
+/// What kind of thing is being loaded in [`WorldLoadingStatus::Progress`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadPhase {
+    Entities,
+    Lights,
+    Cameras,
+    Scripts,
+    Textures,
+}
+
+impl Display for LoadPhase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LoadPhase::Entities => "Entities",
+            LoadPhase::Lights => "Lights",
+            LoadPhase::Cameras => "Cameras",
+            LoadPhase::Scripts => "Scripts",
+            LoadPhase::Textures => "Textures",
+        };
+        write!(f, "{}", name)
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, bincode::Encode, bincode::Decode)]
@@ -530,6 +819,9 @@ impl DerefMut for Label {
 pub struct SerializedMeshRenderer {
     pub handle: ResourceReference,
     pub material_override: Vec<MaterialOverride>,
+    /// Emissive factors changed in the editor, by material name
+    #[serde(default)]
+    pub emissive_overrides: HashMap<String, [f32; 3]>,
 }
 
 #[typetag::serde]
@@ -562,6 +854,7 @@ impl SerializedMeshRenderer {
         Self {
             handle: handle.path.clone(),
             material_override: renderer.material_overrides.clone(),
+            emissive_overrides: renderer.emissive_overrides.clone(),
         }
     }
 }
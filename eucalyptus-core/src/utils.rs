@@ -1,4 +1,5 @@
 use crate::states::Node;
+use dropbear_engine::gilrs::Button;
 use dropbear_engine::utils::{ResourceReference, ResourceReferenceType, relative_path_from_euca};
 use std::path::PathBuf;
 use winit::keyboard::KeyCode;
@@ -24,11 +25,7 @@ where
 
 /// Progress events for project creation
 pub enum ProjectProgress {
-    Step {
-        _progress: f32,
-        _message: String,
-    },
-    #[allow(dead_code)] // idk why its giving me this warning :(
+    Step { progress: f32, message: String },
     Error(String),
     Done,
 }
@@ -38,6 +35,8 @@ pub enum ViewportMode {
     None,
     CameraMove,
     Gizmo,
+    /// Clicking in the viewport places the ends of a distance measurement
+    Measure,
 }
 
 pub fn keycode_from_ordinal(ordinal: i32) -> Option<KeyCode> {
@@ -240,6 +239,33 @@ pub fn keycode_from_ordinal(ordinal: i32) -> Option<KeyCode> {
     }
 }
 
+/// Converts the ordinal of the Kotlin `GamepadButton` enum to a gilrs button.
+pub fn gamepad_button_from_ordinal(ordinal: i32) -> Option<Button> {
+    match ordinal {
+        0 => Some(Button::Unknown),
+        1 => Some(Button::South),
+        2 => Some(Button::East),
+        3 => Some(Button::North),
+        4 => Some(Button::West),
+        5 => Some(Button::C),
+        6 => Some(Button::Z),
+        7 => Some(Button::LeftTrigger),
+        8 => Some(Button::RightTrigger),
+        9 => Some(Button::LeftTrigger2),
+        10 => Some(Button::RightTrigger2),
+        11 => Some(Button::Select),
+        12 => Some(Button::Start),
+        13 => Some(Button::Mode),
+        14 => Some(Button::LeftThumb),
+        15 => Some(Button::RightThumb),
+        16 => Some(Button::DPadUp),
+        17 => Some(Button::DPadDown),
+        18 => Some(Button::DPadLeft),
+        19 => Some(Button::DPadRight),
+        _ => None,
+    }
+}
+
 pub trait ResolveReference {
     /// This function attempts to resolve the [`ResourceReference`]
     /// (specifically the [`ResourceReferenceType::File`]) into
@@ -300,8 +326,25 @@ impl ResolveReference for ResourceReference {
 ///
 /// let asset = convert_ptr!(asset_handle, AssetRegistryPtr => AssetRegistry);
 /// ```
+///
+/// Put `mut` in front of the pointer to get a mutable reference instead, such as
+/// `convert_ptr!(mut world_handle, WorldPtr => World)`.
 #[macro_export]
 macro_rules! convert_ptr {
+    (mut $ptr:expr, $ptr_ty:ty => $target_ty:ty) => {{
+        let ptr = $ptr as $ptr_ty;
+        if ptr.is_null() {
+            let message = format!(
+                "[{}] [ERROR] {} pointer is null",
+                std::any::type_name::<$target_ty>(),
+                stringify!($ptr)
+            );
+            println!("{}", message);
+            return $crate::ffi_error_return!();
+        }
+        unsafe { &mut *(ptr as *mut $target_ty) }
+    }};
+
     ($ptr:expr, $ptr_ty:ty => $target_ty:ty) => {{
         let ptr = $ptr as $ptr_ty;
         if ptr.is_null() {
@@ -471,6 +514,12 @@ macro_rules! ffi_error_return {
             }
         }
 
+        impl ErrorValue for f32 {
+            fn error_value() -> Self {
+                f32::NAN
+            }
+        }
+
         impl ErrorValue for f64 {
             fn error_value() -> Self {
                 f64::NAN
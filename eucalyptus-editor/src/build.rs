@@ -1,7 +1,22 @@
+use crate::editor::export::GltfBuilder;
+use dropbear_engine::camera::ProjectionKind;
+use dropbear_engine::entity::{EntityTransform, Transform};
+use dropbear_engine::lighting::{LightComponent, LightType};
+use dropbear_engine::model::ModelVertex;
+use dropbear_engine::sprite::SpriteRenderer;
+use dropbear_engine::stats::format_bytes;
+use dropbear_engine::utils::ResourceReference;
 use eucalyptus_core::config::ProjectConfig;
-use eucalyptus_core::runtime::RuntimeProjectConfig;
-use eucalyptus_core::scene::SceneConfig;
+use eucalyptus_core::runtime::{RuntimeProjectConfig, collect_shader_sources};
+use eucalyptus_core::scene::{SceneConfig, SceneEntity};
+use eucalyptus_core::states::{
+    Camera3D, Label, Light, ModelProperties, PROJECT, SerializedMeshRenderer,
+};
+use glam::{DMat4, DQuat, DVec3, Mat4, Vec3};
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
 /// Builds a eucalyptus project into a single bundle. 
@@ -14,6 +29,11 @@ pub fn build(project_config: PathBuf) -> anyhow::Result<PathBuf> {
         .parent()
         .ok_or(anyhow::anyhow!("Unable to locate parent folder of config"))?
         .to_path_buf();
+
+    for error in ProjectConfig::validate_asset_references(&project_root) {
+        log::warn!("{}", error);
+    }
+
     let build_dir = project_root.join("build/output");
 
     if build_dir.exists() {
@@ -48,11 +68,23 @@ pub fn build(project_config: PathBuf) -> anyhow::Result<PathBuf> {
         }
     }
 
+    // sorted so the package is the same no matter what order the folder is read in
+    scenes.sort_by(|a, b| a.scene_name.cmp(&b.scene_name));
+    let default_scene = config
+        .starting_scene(&scenes)
+        .map(|scene| scene.scene_name.clone())
+        .ok_or_else(|| anyhow::anyhow!("The project does not have any scenes to start in"))?;
+    log::debug!("Starting scene is '{}'", default_scene);
+
     // convert to runtime project config
+    let shaders = collect_shader_sources(&project_root, &scenes);
+    log::debug!("Packaged {} custom shaders", shaders.len());
     let runtime_config = RuntimeProjectConfig {
         project_name: config.project_name.clone(),
         runtime_settings: config.runtime_settings.clone(),
         scenes,
+        default_scene,
+        shaders,
     };
     log::debug!("Converted to runtime project config");
 
@@ -98,4 +130,595 @@ pub fn read(eupak: PathBuf) -> anyhow::Result<RuntimeProjectConfig> {
         bincode::decode_from_slice(&bytes, bincode::config::standard())?;
     println!("{} contents: {:#?}", eupak.display(), content);
     Ok(content)
-}
\ No newline at end of file
+}
+
+/// What an asset in a [`DependencyGraph`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DependencyKind {
+    Package,
+    Scene,
+    Model,
+    Texture,
+}
+
+/// An asset in the build output, along with the assets it depends on.
+#[derive(Debug, Clone)]
+pub struct DependencyNode {
+    /// The file name of the package, the name of a scene, or the euca URI of a file
+    pub name: String,
+    pub kind: DependencyKind,
+    /// The size of the asset itself in bytes, not counting its children. For a scene this is its
+    /// share of the `.eupak`.
+    pub size: u64,
+    pub children: Vec<DependencyNode>,
+}
+
+impl DependencyNode {
+    fn new(name: impl Into<String>, kind: DependencyKind, size: u64) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            size,
+            children: Vec::new(),
+        }
+    }
+
+    /// The size of the asset and everything under it, in bytes.
+    pub fn total_size(&self) -> u64 {
+        self.size + self.children.iter().map(Self::total_size).sum::<u64>()
+    }
+}
+
+/// What a `.eupak` is made of, going from the package to its scenes, then to the models and
+/// textures each scene uses.
+///
+/// An asset used by more than one scene shows up under each of them.
+#[derive(Debug, Clone)]
+pub struct DependencyGraph {
+    pub root: DependencyNode,
+}
+
+impl DependencyGraph {
+    /// Writes the graph in Graphviz DOT format, with every asset labelled with its size.
+    ///
+    /// Assets shared between scenes are only written once, with an edge from each scene.
+    pub fn render_to_dot(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        fn escape(name: &str) -> String {
+            name.replace('\\', "\\\\").replace('"', "\\\"")
+        }
+
+        fn write_node(
+            node: &DependencyNode,
+            writer: &mut impl Write,
+            written: &mut HashSet<String>,
+        ) -> std::io::Result<()> {
+            if written.insert(node.name.clone()) {
+                let shape = match node.kind {
+                    DependencyKind::Package => "folder",
+                    DependencyKind::Scene => "box",
+                    DependencyKind::Model => "ellipse",
+                    DependencyKind::Texture => "note",
+                };
+                writeln!(
+                    writer,
+                    "    \"{}\" [label=\"{}\\n{}\", shape={}];",
+                    escape(&node.name),
+                    escape(&node.name),
+                    format_bytes(node.total_size()),
+                    shape
+                )?;
+            }
+
+            for child in &node.children {
+                write_node(child, writer, written)?;
+                let edge = format!("{} -> {}", node.name, child.name);
+                if written.insert(edge) {
+                    writeln!(
+                        writer,
+                        "    \"{}\" -> \"{}\";",
+                        escape(&node.name),
+                        escape(&child.name)
+                    )?;
+                }
+            }
+            Ok(())
+        }
+
+        writeln!(writer, "digraph dependencies {{")?;
+        writeln!(writer, "    rankdir=LR;")?;
+        write_node(&self.root, writer, &mut HashSet::new())?;
+        writeln!(writer, "}}")
+    }
+}
+
+/// Reads a `.eupak` and works out which models and textures each of its scenes use, and how big
+/// they are.
+///
+/// Files are looked up in the `resources` folder next to the `.eupak`, and are counted as empty
+/// if they are missing.
+pub fn generate_dependency_graph(eupak_path: &Path) -> anyhow::Result<DependencyGraph> {
+    let bytes = fs::read(eupak_path)?;
+    let (content, _): (RuntimeProjectConfig, usize) =
+        bincode::decode_from_slice(&bytes, bincode::config::standard())?;
+    let resources_dir = eupak_path
+        .parent()
+        .ok_or_else(|| anyhow::anyhow!("Unable to locate parent folder of the .eupak"))?
+        .join("resources");
+
+    let package_name = eupak_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| content.project_name.clone());
+    let mut root = DependencyNode::new(package_name, DependencyKind::Package, 0);
+
+    let mut scenes_size = 0;
+    for scene in &content.scenes {
+        let scene_size = bincode::serde::encode_to_vec(scene, bincode::config::standard())
+            .map(|bytes| bytes.len() as u64)
+            .unwrap_or(0);
+        scenes_size += scene_size;
+
+        // keyed by URI, so each asset is only listed once per scene
+        let mut models: BTreeMap<String, DependencyNode> = BTreeMap::new();
+        let mut textures: BTreeMap<String, DependencyNode> = BTreeMap::new();
+
+        for entity in &scene.entities {
+            for component in &entity.components {
+                if let Some(renderer) = component.as_any().downcast_ref::<SerializedMeshRenderer>()
+                {
+                    let overrides = renderer.material_override.iter().map(|o| &o.source_model);
+                    for reference in std::iter::once(&renderer.handle).chain(overrides) {
+                        if let Some(uri) = reference.as_uri()
+                            && !models.contains_key(uri)
+                        {
+                            let node = model_dependency(uri, reference, &resources_dir);
+                            models.insert(uri.to_string(), node);
+                        }
+                    }
+                } else if let Some(sprite) = component.as_any().downcast_ref::<SpriteRenderer>()
+                    && let Some(uri) = sprite.texture.as_uri()
+                    && !textures.contains_key(uri)
+                {
+                    let size = resource_size(&resources_dir, sprite.texture.relative_path());
+                    let node = DependencyNode::new(uri, DependencyKind::Texture, size);
+                    textures.insert(uri.to_string(), node);
+                }
+            }
+        }
+
+        let mut scene_node =
+            DependencyNode::new(scene.scene_name.clone(), DependencyKind::Scene, scene_size);
+        scene_node.children.extend(models.into_values());
+        scene_node.children.extend(textures.into_values());
+        root.children.push(scene_node);
+    }
+
+    // whatever isn't a scene, such as the project name and runtime settings
+    root.size = (bytes.len() as u64).saturating_sub(scenes_size);
+
+    Ok(DependencyGraph { root })
+}
+
+/// A model and the external images it references, which only `.gltf` files have. Images in a
+/// `.glb` are part of the file itself.
+fn model_dependency(
+    uri: &str,
+    reference: &ResourceReference,
+    resources_dir: &Path,
+) -> DependencyNode {
+    let relative_path = reference.relative_path();
+    let mut node = DependencyNode::new(
+        uri,
+        DependencyKind::Model,
+        resource_size(resources_dir, relative_path),
+    );
+
+    let Some(relative_path) = relative_path else {
+        return node;
+    };
+    let path = resources_dir.join(relative_path);
+    let is_gltf = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gltf"));
+    if !is_gltf {
+        return node;
+    }
+
+    let document = match gltf::Gltf::open(&path) {
+        Ok(gltf) => gltf.document,
+        Err(e) => {
+            log::warn!("Unable to read {} for its textures: {}", path.display(), e);
+            return node;
+        }
+    };
+
+    let model_dir = Path::new(relative_path).parent().unwrap_or(Path::new(""));
+    for image in document.images() {
+        if let gltf::image::Source::Uri { uri, .. } = image.source()
+            && !uri.starts_with("data:")
+        {
+            let texture_path = model_dir.join(uri);
+            let texture_path = texture_path.to_string_lossy().replace('\\', "/");
+            let size = resource_size(resources_dir, Some(&texture_path));
+            node.children.push(DependencyNode::new(
+                format!("euca://{}", texture_path),
+                DependencyKind::Texture,
+                size,
+            ));
+        }
+    }
+
+    node
+}
+
+/// The size of a file under `resources_dir`, or 0 if it doesn't exist.
+fn resource_size(resources_dir: &Path, relative_path: Option<&str>) -> u64 {
+    let Some(relative_path) = relative_path else {
+        return 0;
+    };
+    let path = resources_dir.join(relative_path);
+    match fs::metadata(&path) {
+        Ok(metadata) => metadata.len(),
+        Err(_) => {
+            log::warn!("Unable to find {} in the build output", path.display());
+            0
+        }
+    }
+}
+
+/// Imports a `.glb` or `.gltf` file as a new scene in the current project.
+///
+/// Each mesh is written out to its own model under `resources/models/<scene>`, and every node
+/// with a mesh, light or camera becomes an entity. Node hierarchies are flattened into world
+/// space transforms. Images used by a mesh's material are embedded into that mesh's model.
+///
+/// The scene is not written to disk, that is left up to the caller.
+pub fn import_gltf_scene(path: &Path) -> anyhow::Result<SceneConfig> {
+    let project_root = PROJECT.read().project_path.clone();
+    if project_root.as_os_str().is_empty() {
+        anyhow::bail!("Project path is not set");
+    }
+
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| anyhow::anyhow!("glTF file name is invalid"))?;
+    let stem = sanitize_file_name(stem);
+
+    let scenes_dir = project_root.join("scenes");
+    let mut scene_name = stem.clone();
+    let mut suffix = 1;
+    while scenes_dir.join(format!("{}.eucs", scene_name)).exists() {
+        scene_name = format!("{}_{}", stem, suffix);
+        suffix += 1;
+    }
+
+    let base = path.parent().unwrap_or(Path::new("."));
+    let gltf::Gltf { document, blob } = gltf::Gltf::open(path)?;
+    let buffers = gltf::import_buffers(&document, Some(base), blob)?;
+    log::debug!("Read glTF document {}", path.display());
+
+    let mut images = Vec::new();
+    for image in document.images() {
+        let data = match image.source() {
+            gltf::image::Source::View { view, mime_type } => {
+                let buffer = &buffers[view.buffer().index()];
+                let bytes = buffer[view.offset()..view.offset() + view.length()].to_vec();
+                Some((bytes, mime_type.to_string()))
+            }
+            gltf::image::Source::Uri { uri, .. } if uri.starts_with("data:") => {
+                log::warn!("Embedded data URI images are not supported, skipping");
+                None
+            }
+            gltf::image::Source::Uri { uri, mime_type } => {
+                let image_path = base.join(uri);
+                match fs::read(&image_path) {
+                    Ok(bytes) => {
+                        let mime_type = mime_type
+                            .map(str::to_string)
+                            .unwrap_or_else(|| image_mime_type(&image_path).to_string());
+                        Some((bytes, mime_type))
+                    }
+                    Err(e) => {
+                        log::warn!("Unable to read image {}: {}", image_path.display(), e);
+                        None
+                    }
+                }
+            }
+        };
+
+        images.push(data);
+    }
+
+    let models_dir = project_root.join("resources/models").join(&scene_name);
+    let mut models = Vec::new();
+    for mesh in document.meshes() {
+        let name = mesh
+            .name()
+            .map(sanitize_file_name)
+            .unwrap_or_else(|| "mesh".to_string());
+        let model_path = models_dir.join(format!("{}_{}.glb", name, mesh.index()));
+
+        if write_mesh_glb(&mesh, &buffers, &images, &model_path)? {
+            models.push(Some(ResourceReference::from_path(&model_path)?));
+        } else {
+            log::warn!("Mesh {} has no geometry, skipping", mesh.index());
+            models.push(None);
+        }
+    }
+
+    let mut scene = SceneConfig::new(
+        scene_name.clone(),
+        scenes_dir.join(format!("{}.eucs", scene_name)),
+    );
+
+    let gltf_scene = document
+        .default_scene()
+        .or_else(|| document.scenes().next())
+        .ok_or_else(|| anyhow::anyhow!("glTF file does not contain any scenes"))?;
+
+    for node in gltf_scene.nodes() {
+        import_node(&node, DMat4::IDENTITY, &models, &mut scene.entities);
+    }
+
+    log::info!(
+        "Imported {} entities from {} into scene '{}'",
+        scene.entities.len(),
+        path.display(),
+        scene_name
+    );
+
+    Ok(scene)
+}
+
+/// Converts a node and its children into scene entities, flattening the hierarchy into world
+/// space transforms.
+fn import_node(
+    node: &gltf::Node,
+    parent: DMat4,
+    models: &[Option<ResourceReference>],
+    entities: &mut Vec<SceneEntity>,
+) {
+    let local = Mat4::from_cols_array_2d(&node.transform().matrix()).as_dmat4();
+    let world = parent * local;
+    let (scale, rotation, position) = world.to_scale_rotation_translation();
+    let transform = Transform {
+        position,
+        rotation,
+        scale,
+    };
+
+    let name = node
+        .name()
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("Node {}", node.index()));
+
+    if let Some(mesh) = node.mesh()
+        && let Some(Some(handle)) = models.get(mesh.index())
+    {
+        entities.push(SceneEntity {
+            label: Label::new(name.clone()),
+            components: vec![
+                Box::new(EntityTransform::new_from_world(transform)),
+                Box::new(SerializedMeshRenderer {
+                    handle: handle.clone(),
+                    material_override: Vec::new(),
+                    emissive_overrides: HashMap::new(),
+                }),
+                Box::new(ModelProperties::new()),
+            ],
+            entity_id: None,
+        });
+    }
+
+    if let Some(light) = node.light() {
+        let mut light_component = LightComponent {
+            position,
+            // glTF lights shine down the local -Z axis
+            direction: rotation * DVec3::NEG_Z,
+            colour: Vec3::from_array(light.color()).as_dvec3(),
+            intensity: light.intensity(),
+            ..Default::default()
+        };
+
+        match light.kind() {
+            gltf::khr_lights_punctual::Kind::Directional => {
+                light_component.light_type = LightType::Directional;
+            }
+            gltf::khr_lights_punctual::Kind::Point => {
+                light_component.light_type = LightType::Point;
+            }
+            gltf::khr_lights_punctual::Kind::Spot {
+                inner_cone_angle,
+                outer_cone_angle,
+            } => {
+                light_component.light_type = LightType::Spot;
+                light_component.cutoff_angle = inner_cone_angle.to_degrees();
+                light_component.outer_cutoff_angle = outer_cone_angle.to_degrees();
+            }
+        }
+
+        if let Some(range) = light.range() {
+            light_component.attenuation.range = range;
+        }
+
+        let label = light
+            .name()
+            .map(str::to_string)
+            .unwrap_or_else(|| name.clone());
+        entities.push(SceneEntity {
+            label: Label::new(label.clone()),
+            components: vec![Box::new(Light {
+                label,
+                transform,
+                light_component,
+                ..Default::default()
+            })],
+            entity_id: None,
+        });
+    }
+
+    if let Some(camera) = node.camera() {
+        let mut camera_3d = Camera3D {
+            label: name.clone(),
+            // glTF cameras look down the local -Z axis, while ours look down +Z
+            transform: Transform {
+                position,
+                rotation: rotation * DQuat::from_rotation_y(std::f64::consts::PI),
+                scale: DVec3::ONE,
+            },
+            ..Default::default()
+        };
+
+        match camera.projection() {
+            gltf::camera::Projection::Perspective(perspective) => {
+                camera_3d.fov = perspective.yfov().to_degrees();
+                camera_3d.near = perspective.znear();
+                if let Some(far) = perspective.zfar() {
+                    camera_3d.far = far;
+                }
+                if let Some(aspect) = perspective.aspect_ratio() {
+                    camera_3d.aspect = aspect as f64;
+                }
+            }
+            gltf::camera::Projection::Orthographic(orthographic) => {
+                let (xmag, ymag) = (orthographic.xmag() as f64, orthographic.ymag() as f64);
+                camera_3d.near = orthographic.znear();
+                camera_3d.far = orthographic.zfar();
+                camera_3d.projection = ProjectionKind::Orthographic {
+                    left: -xmag,
+                    right: xmag,
+                    bottom: -ymag,
+                    top: ymag,
+                    near: orthographic.znear() as f64,
+                    far: orthographic.zfar() as f64,
+                };
+                if ymag > 0.0 {
+                    camera_3d.aspect = xmag / ymag;
+                }
+            }
+        }
+
+        camera_3d.starting_camera = !entities.iter().any(|entity| {
+            entity.components.iter().any(|component| {
+                component
+                    .as_any()
+                    .downcast_ref::<Camera3D>()
+                    .is_some_and(|camera| camera.starting_camera)
+            })
+        });
+
+        entities.push(SceneEntity {
+            label: Label::new(name),
+            components: vec![Box::new(camera_3d)],
+            entity_id: None,
+        });
+    }
+
+    for child in node.children() {
+        import_node(&child, world, models, entities);
+    }
+}
+
+/// Writes a single glTF mesh and the materials it uses into its own `.glb` file.
+///
+/// Returns `false` if the mesh has no primitives with positions, in which case nothing is written.
+fn write_mesh_glb(
+    mesh: &gltf::Mesh,
+    buffers: &[gltf::buffer::Data],
+    images: &[Option<(Vec<u8>, String)>],
+    path: &Path,
+) -> anyhow::Result<bool> {
+    let mut builder = GltfBuilder::default();
+    let mut materials: HashMap<usize, usize> = HashMap::new();
+    let mut primitives = Vec::new();
+
+    for primitive in mesh.primitives() {
+        let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+        let Some(positions) = reader.read_positions() else {
+            continue;
+        };
+        let positions: Vec<[f32; 3]> = positions.collect();
+
+        let normals: Vec<[f32; 3]> = reader
+            .read_normals()
+            .map(|iter| iter.collect())
+            .unwrap_or_else(|| vec![[0.0, 1.0, 0.0]; positions.len()]);
+
+        let tex_coords: Vec<[f32; 2]> = reader
+            .read_tex_coords(0)
+            .map(|iter| iter.into_f32().collect())
+            .unwrap_or_else(|| vec![[0.0, 0.0]; positions.len()]);
+
+        let vertices: Vec<ModelVertex> = positions
+            .iter()
+            .zip(normals.iter())
+            .zip(tex_coords.iter())
+            .map(|((position, normal), tex_coords)| ModelVertex {
+                position: *position,
+                tex_coords: *tex_coords,
+                normal: *normal,
+            })
+            .collect();
+
+        let indices: Vec<u32> = reader
+            .read_indices()
+            .map(|iter| iter.into_u32().collect())
+            .unwrap_or_else(|| (0..vertices.len() as u32).collect());
+
+        let material = primitive.material();
+        let material = material.index().map(|index| {
+            *materials.entry(index).or_insert_with(|| {
+                let image = material
+                    .pbr_metallic_roughness()
+                    .base_color_texture()
+                    .and_then(|info| images.get(info.texture().source().index()))
+                    .and_then(|image| image.as_ref())
+                    .map(|(bytes, mime_type)| (bytes.as_slice(), mime_type.as_str()));
+                builder.add_material(
+                    material.name().unwrap_or("Unnamed Material"),
+                    image,
+                    material.pbr_metallic_roughness().base_color_factor(),
+                    material.emissive_factor(),
+                )
+            })
+        });
+
+        primitives.push(builder.add_primitive(&vertices, &indices, material));
+    }
+
+    if primitives.is_empty() {
+        return Ok(false);
+    }
+
+    let mesh_index = builder.add_mesh(mesh.name().unwrap_or("Unnamed Mesh"), primitives);
+    builder.add_node(json!({ "mesh": mesh_index }));
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, builder.into_glb()?)?;
+    Ok(true)
+}
+
+fn image_mime_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("jpg") || ext.eq_ignore_ascii_case("jpeg") => {
+            "image/jpeg"
+        }
+        _ => "image/png",
+    }
+}
+
+/// Replaces anything that isn't safe to use in a file name with an underscore.
+fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
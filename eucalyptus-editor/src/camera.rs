@@ -1,60 +1,343 @@
 use crate::editor::component::InspectableComponent;
 use crate::editor::{Signal, StaticallyKept, UndoableAction};
-use dropbear_engine::camera::Camera;
-use egui::{CollapsingHeader, Ui};
+use dropbear_engine::camera::{Camera, CameraSettings, ProjectionKind};
+use dropbear_engine::entity::{EntityLayer, NAMED_LAYER_COUNT};
+use egui::{CollapsingHeader, Response, Ui};
 use eucalyptus_core::camera::{CameraComponent, CameraType};
+use eucalyptus_core::states::PROJECT;
+use glam::DVec3;
 use hecs::Entity;
 
+/// The values of a [`Camera`] that can be changed in the inspector, kept so an edit can be
+/// undone.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraProperties {
+    pub label: String,
+    pub eye: DVec3,
+    pub target: DVec3,
+    pub up: DVec3,
+    pub znear: f64,
+    pub zfar: f64,
+    pub projection: ProjectionKind,
+    pub yaw: f64,
+    pub pitch: f64,
+    pub settings: CameraSettings,
+    pub visible_layers: u32,
+}
+
+impl CameraProperties {
+    pub fn of(camera: &Camera) -> Self {
+        Self {
+            label: camera.label.clone(),
+            eye: camera.eye,
+            target: camera.target,
+            up: camera.up,
+            znear: camera.znear,
+            zfar: camera.zfar,
+            projection: camera.projection,
+            yaw: camera.yaw,
+            pitch: camera.pitch,
+            settings: camera.settings,
+            visible_layers: camera.visible_layers,
+        }
+    }
+
+    pub fn apply(&self, camera: &mut Camera) {
+        camera.label = self.label.clone();
+        camera.eye = self.eye;
+        camera.target = self.target;
+        camera.up = self.up;
+        camera.znear = self.znear;
+        camera.zfar = self.zfar;
+        camera.projection = self.projection;
+        camera.yaw = self.yaw;
+        camera.pitch = self.pitch;
+        camera.settings = self.settings;
+        camera.visible_layers = self.visible_layers;
+    }
+}
+
+/// What a camera looked like before it was edited in the inspector.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CameraEdit {
+    Camera(CameraProperties),
+    Component(CameraComponent),
+}
+
+/// Pushes an undo action if the camera was changed this frame, where `before` is what it looked
+/// like at the start of the frame and `response` is every widget that could have changed it.
+///
+/// Drags and text edits keep what the camera looked like when they started in `original`, so
+/// the whole edit is undone at once.
+fn record_edit(
+    response: Option<Response>,
+    entity: Entity,
+    before: CameraEdit,
+    after: CameraEdit,
+    original: &mut Option<(Entity, CameraEdit)>,
+    undo_stack: &mut Vec<UndoableAction>,
+) {
+    if let Some(response) = &response {
+        if response.drag_started() || response.gained_focus() {
+            *original = Some((entity, before.clone()));
+        }
+        if response.dragged() || response.has_focus() {
+            return;
+        }
+    }
+
+    let before = match original.take() {
+        Some((original_entity, original)) if original_entity == entity => original,
+        Some(_) => return,
+        None => before,
+    };
+
+    if before != after {
+        UndoableAction::push_to_undo(undo_stack, UndoableAction::CameraAction(entity, before));
+        log::debug!("Pushed camera edit to undo stack");
+    }
+}
+
+fn vector_ui(ui: &mut Ui, name: &str, value: &mut DVec3, responses: &mut Vec<Response>) {
+    ui.horizontal(|ui| {
+        ui.label(name);
+        responses.push(ui.add(egui::DragValue::new(&mut value.x).speed(0.1).prefix("X: ")));
+        responses.push(ui.add(egui::DragValue::new(&mut value.y).speed(0.1).prefix("Y: ")));
+        responses.push(ui.add(egui::DragValue::new(&mut value.z).speed(0.1).prefix("Z: ")));
+    });
+}
+
+/// A drag value that shows an angle stored in radians as degrees.
+fn angle_ui(ui: &mut Ui, radians: &mut f64) -> Response {
+    let mut degrees = radians.to_degrees();
+    let response = ui.add(egui::DragValue::new(&mut degrees).speed(0.5).suffix("°"));
+    if response.changed() {
+        *radians = degrees.to_radians();
+    }
+    response
+}
+
 impl InspectableComponent for Camera {
     fn inspect(
         &mut self,
-        _entity: &mut Entity,
-        _cfg: &mut StaticallyKept,
+        entity: &mut Entity,
+        cfg: &mut StaticallyKept,
         ui: &mut Ui,
-        _undo_stack: &mut Vec<UndoableAction>,
+        undo_stack: &mut Vec<UndoableAction>,
         _signal: &mut Signal,
         _label: &mut String,
     ) {
+        let before = CameraProperties::of(self);
+        let mut responses = Vec::new();
+
         ui.vertical(|ui| {
             CollapsingHeader::new("Camera")
                 .default_open(true)
                 .show(ui, |ui| {
                     ui.horizontal(|ui| {
-                        ui.label("Position:");
-                        ui.label(format!(
-                            "{:.2}, {:.2}, {:.2}",
-                            self.eye.x, self.eye.y, self.eye.z
-                        ));
-                        if ui.button("Reset").clicked() {
-                            self.eye = glam::DVec3::ZERO;
-                        }
+                        ui.label("Label:");
+                        responses.push(ui.text_edit_singleline(&mut self.label));
                     });
 
+                    vector_ui(ui, "Position:", &mut self.eye, &mut responses);
+                    vector_ui(ui, "Target:", &mut self.target, &mut responses);
+                    vector_ui(ui, "Up:", &mut self.up, &mut responses);
+
+                    responses.push(projection_ui(
+                        ui,
+                        &mut self.projection,
+                        self.aspect,
+                        self.znear,
+                        self.zfar,
+                    ));
+
+                    if matches!(self.projection, ProjectionKind::Perspective) {
+                        ui.horizontal(|ui| {
+                            ui.label("FOV:");
+                            responses.push(
+                                ui.add(
+                                    egui::Slider::new(&mut self.settings.fov_y, 10.0..=120.0)
+                                        .suffix("°"),
+                                ),
+                            );
+                        });
+                    }
+
                     ui.horizontal(|ui| {
-                        ui.label("Target:");
-                        ui.label(format!(
-                            "{:.2}, {:.2}, {:.2}",
-                            self.target.x, self.target.y, self.target.z
-                        ));
-                        if ui.button("Reset").clicked() {
-                            self.target = glam::DVec3::ZERO;
+                        ui.label("Near:");
+                        responses.push(
+                            ui.add(
+                                egui::DragValue::new(&mut self.znear)
+                                    .speed(0.01)
+                                    .range(0.001..=self.zfar),
+                            ),
+                        );
+                        ui.label("Far:");
+                        responses.push(
+                            ui.add(
+                                egui::DragValue::new(&mut self.zfar)
+                                    .speed(1.0)
+                                    .range(self.znear..=f64::MAX),
+                            ),
+                        );
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label("Yaw:");
+                        responses.push(angle_ui(ui, &mut self.yaw));
+                        ui.label("Pitch:");
+                        responses.push(angle_ui(ui, &mut self.pitch));
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label("Speed:");
+                        responses.push(
+                            ui.add(
+                                egui::DragValue::new(&mut self.settings.speed)
+                                    .speed(0.1)
+                                    .range(0.1..=20.0),
+                            ),
+                        );
+                        ui.label("Sensitivity:");
+                        responses.push(
+                            ui.add(
+                                egui::DragValue::new(&mut self.settings.sensitivity)
+                                    .speed(0.0001)
+                                    .range(0.0001..=1.0),
+                            ),
+                        );
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label("Shake:");
+                        if let Some(shake) = &self.shake {
+                            ui.label(format!(
+                                "{:.2} intensity, {:.2}s remaining",
+                                shake.amplitude(),
+                                shake.remaining()
+                            ));
+                        } else {
+                            ui.label("None");
+                        }
+                    });
+
+                    CollapsingHeader::new("Visible Layers").show(ui, |ui| {
+                        let project = PROJECT.read();
+                        for layer in 0..NAMED_LAYER_COUNT {
+                            let mask = EntityLayer(layer).mask();
+                            let mut visible = self.can_see(mask);
+                            if ui
+                                .checkbox(&mut visible, project.layer_name(layer))
+                                .changed()
+                            {
+                                if visible {
+                                    self.visible_layers |= mask;
+                                } else {
+                                    self.visible_layers &= !mask;
+                                }
+                            }
                         }
                     });
                 });
         });
+
+        record_edit(
+            responses.into_iter().reduce(|a, b| a | b),
+            *entity,
+            CameraEdit::Camera(before),
+            CameraEdit::Camera(CameraProperties::of(self)),
+            &mut cfg.camera_original,
+            undo_stack,
+        );
     }
 }
 
+/// Shows a picker for the kind of projection, along with the bounds of an orthographic one.
+///
+/// `aspect`, `near` and `far` are used for the bounds when switching to orthographic. Returns the
+/// response of every widget shown, so dragging a bound can be undone as one edit.
+pub(crate) fn projection_ui(
+    ui: &mut Ui,
+    projection: &mut ProjectionKind,
+    aspect: f64,
+    near: f64,
+    far: f64,
+) -> Response {
+    let picker_row = ui.horizontal(|ui| {
+        ui.label("Projection:");
+        let mut orthographic = matches!(projection, ProjectionKind::Orthographic { .. });
+        let picker = egui::ComboBox::from_id_salt("camera_projection")
+            .selected_text(if orthographic {
+                "Orthographic"
+            } else {
+                "Perspective"
+            })
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut orthographic, false, "Perspective");
+                ui.selectable_value(&mut orthographic, true, "Orthographic");
+            });
+
+        match (orthographic, *projection) {
+            (true, ProjectionKind::Perspective) => {
+                *projection = ProjectionKind::orthographic(5.0, aspect, near, far);
+            }
+            (false, ProjectionKind::Orthographic { .. }) => {
+                *projection = ProjectionKind::Perspective;
+            }
+            _ => {}
+        }
+        picker.response
+    });
+    let mut response = picker_row.inner;
+
+    if let ProjectionKind::Orthographic {
+        left,
+        right,
+        bottom,
+        top,
+        near,
+        far,
+    } = projection
+    {
+        egui::Grid::new("camera_orthographic_bounds")
+            .num_columns(4)
+            .show(ui, |ui| {
+                ui.label("Left");
+                response |= ui.add(egui::DragValue::new(left).speed(0.1));
+                ui.label("Right");
+                response |= ui.add(egui::DragValue::new(right).speed(0.1));
+                ui.end_row();
+
+                ui.label("Bottom");
+                response |= ui.add(egui::DragValue::new(bottom).speed(0.1));
+                ui.label("Top");
+                response |= ui.add(egui::DragValue::new(top).speed(0.1));
+                ui.end_row();
+
+                ui.label("Near");
+                response |= ui.add(egui::DragValue::new(near).speed(0.1));
+                ui.label("Far");
+                response |= ui.add(egui::DragValue::new(far).speed(1.0));
+                ui.end_row();
+            });
+    }
+
+    response
+}
+
 impl InspectableComponent for CameraComponent {
     fn inspect(
         &mut self,
-        _entity: &mut Entity,
-        _cfg: &mut StaticallyKept,
+        entity: &mut Entity,
+        cfg: &mut StaticallyKept,
         ui: &mut Ui,
-        _undo_stack: &mut Vec<UndoableAction>,
-        _signal: &mut Signal,
+        undo_stack: &mut Vec<UndoableAction>,
+        signal: &mut Signal,
         _label: &mut String,
     ) {
+        let before = self.clone();
+        let mut responses = Vec::new();
+
         ui.vertical(|ui| {
             CollapsingHeader::new("Camera Component")
                 .default_open(true)
@@ -75,31 +358,50 @@ impl InspectableComponent for CameraComponent {
                         });
                     }
 
+                    ui.checkbox(&mut self.starting_camera, "Starting Camera");
+
                     ui.horizontal(|ui| {
                         ui.label("Speed:");
-                        ui.add(
-                            egui::DragValue::new(&mut self.settings.speed)
-                                .speed(0.1)
-                                .range(0.1..=20.0),
+                        responses.push(
+                            ui.add(
+                                egui::DragValue::new(&mut self.settings.speed)
+                                    .speed(0.1)
+                                    .range(0.1..=20.0),
+                            ),
                         );
                     });
 
                     ui.horizontal(|ui| {
                         ui.label("Sensitivity:");
-                        ui.add(
-                            egui::DragValue::new(&mut self.settings.sensitivity)
-                                .speed(0.0001)
-                                .range(0.0001..=1.0),
+                        responses.push(
+                            ui.add(
+                                egui::DragValue::new(&mut self.settings.sensitivity)
+                                    .speed(0.0001)
+                                    .range(0.0001..=1.0),
+                            ),
                         );
                     });
 
                     ui.horizontal(|ui| {
                         ui.label("FOV:");
-                        ui.add(
+                        responses.push(ui.add(
                             egui::Slider::new(&mut self.settings.fov_y, 10.0..=120.0).suffix("°"),
-                        );
+                        ));
                     });
+
+                    if ui.button("Make Active").clicked() {
+                        *signal = Signal::MakeActiveCamera(*entity);
+                    }
                 });
         });
+
+        record_edit(
+            responses.into_iter().reduce(|a, b| a | b),
+            *entity,
+            CameraEdit::Component(before),
+            CameraEdit::Component(self.clone()),
+            &mut cfg.camera_component_original,
+            undo_stack,
+        );
     }
 }
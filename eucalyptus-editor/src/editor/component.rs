@@ -1,17 +1,29 @@
 //! This module should describe the different components that are editable in the resource inspector.
 
-use crate::editor::{Signal, StaticallyKept, UndoableAction};
+use crate::editor::{Signal, StaticallyKept, UndoableAction, undo_generation};
 use dropbear_engine::asset::{ASSET_REGISTRY, AssetHandle};
 use dropbear_engine::attenuation::ATTENUATION_PRESETS;
-use dropbear_engine::entity::{EntityTransform, MeshRenderer, Transform};
+use dropbear_engine::camera::ProjectionKind;
+use dropbear_engine::entity::{
+    EntityLayer, EntityTransform, MeshRenderer, NAMED_LAYER_COUNT, Transform,
+};
 use dropbear_engine::graphics::NO_TEXTURE;
+use dropbear_engine::light_probe::LightProbe;
 use dropbear_engine::lighting::{LightComponent, LightType};
+use dropbear_engine::lod::LodGroup;
+use dropbear_engine::morph::MorphWeights;
+use dropbear_engine::particles::{MAX_PARTICLES_PER_EMITTER, ParticleEmitter};
+use dropbear_engine::sprite::SpriteRenderer;
 use dropbear_engine::utils::ResourceReference;
 use egui::{CollapsingHeader, ComboBox, DragValue, Grid, RichText, TextEdit, Ui, UiBuilder};
-use eucalyptus_core::states::{Camera3D, Light, ModelProperties, Property, Script, Value};
-use eucalyptus_core::camera::CameraType;
+use eucalyptus_core::camera::{CameraFollowTarget, CameraType};
+use eucalyptus_core::inspect::Inspectable;
+use eucalyptus_core::states::{
+    Camera3D, Light, MAX_MATERIAL_PROPERTIES, MaterialProperties, MaterialShader, ModelProperties,
+    PROJECT, Property, Script, Value,
+};
 use eucalyptus_core::{fatal, warn};
-use glam::{DVec3, Vec3};
+use glam::{DVec3, Vec3, Vec4};
 use hecs::Entity;
 use std::time::Instant;
 
@@ -208,6 +220,125 @@ impl InspectableComponent for ModelProperties {
     }
 }
 
+impl InspectableComponent for MaterialProperties {
+    fn inspect(
+        &mut self,
+        _entity: &mut Entity,
+        cfg: &mut StaticallyKept,
+        ui: &mut Ui,
+        _undo_stack: &mut Vec<UndoableAction>,
+        _signal: &mut Signal,
+        _label: &mut String,
+    ) {
+        CollapsingHeader::new("Material Properties")
+            .default_open(true)
+            .show(ui, |ui| {
+                let mut to_delete: Option<String> = None;
+
+                Grid::new("material_properties")
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        let keys: Vec<String> = self.sorted_keys().into_iter().cloned().collect();
+                        for key in keys {
+                            ui.label(&key);
+                            if let Some(value) = self.custom.get_mut(&key) {
+                                ui.add(egui::Slider::new(value, 0.0..=1.0).clamping(
+                                    egui::SliderClamping::Never,
+                                ));
+                            }
+                            if ui.button("🗑️").clicked() {
+                                to_delete = Some(key);
+                            }
+                            ui.end_row();
+                        }
+                    });
+
+                if let Some(key) = to_delete {
+                    log::debug!("Removing material property {}", key);
+                    self.custom.remove(&key);
+                }
+
+                ui.horizontal(|ui| {
+                    ui.add_sized(
+                        [100.0, 20.0],
+                        TextEdit::singleline(&mut cfg.material_property_new_key)
+                            .hint_text("Name"),
+                    );
+
+                    let key = cfg.material_property_new_key.trim().to_string();
+                    let can_add = !key.is_empty()
+                        && !self.custom.contains_key(&key)
+                        && self.custom.len() < MAX_MATERIAL_PROPERTIES;
+                    if ui.add_enabled(can_add, egui::Button::new("Add")).clicked() {
+                        if let Err(e) = self.set(key, 0.0) {
+                            warn!("{}", e);
+                        }
+                        cfg.material_property_new_key.clear();
+                    }
+                });
+
+                ui.label(
+                    RichText::new(format!(
+                        "{}/{} properties",
+                        self.custom.len(),
+                        MAX_MATERIAL_PROPERTIES
+                    ))
+                    .weak(),
+                );
+            });
+    }
+}
+
+impl InspectableComponent for MaterialShader {
+    fn inspect(
+        &mut self,
+        _entity: &mut Entity,
+        _cfg: &mut StaticallyKept,
+        ui: &mut Ui,
+        _undo_stack: &mut Vec<UndoableAction>,
+        _signal: &mut Signal,
+        _label: &mut String,
+    ) {
+        CollapsingHeader::new("Material Shader")
+            .default_open(true)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Custom Shader");
+                    match &self.shader_path {
+                        Some(path) => ui.monospace(path.display().to_string()),
+                        None => ui.label(RichText::new("Default PBR").weak()),
+                    };
+                });
+
+                ui.horizontal(|ui| {
+                    if ui.button("Browse...").clicked() {
+                        let project_root = PROJECT.read().project_path.clone();
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("WGSL shader", &["wgsl"])
+                            .set_directory(&project_root)
+                            .pick_file()
+                        {
+                            match path.strip_prefix(&project_root) {
+                                Ok(relative) => self.shader_path = Some(relative.to_path_buf()),
+                                Err(_) => warn!(
+                                    "The shader has to be inside of the project folder: {}",
+                                    path.display()
+                                ),
+                            }
+                        }
+                    }
+
+                    if ui
+                        .add_enabled(self.shader_path.is_some(), egui::Button::new("Clear"))
+                        .clicked()
+                    {
+                        self.shader_path = None;
+                    }
+                });
+            });
+    }
+}
+
 impl InspectableComponent for EntityTransform {
     fn inspect(
         &mut self,
@@ -234,9 +365,90 @@ impl InspectableComponent for EntityTransform {
             signal,
             &mut "World Transform".to_string(),
         );
+
+        ui.vertical(|ui| {
+            CollapsingHeader::new("Pivot")
+                .default_open(true)
+                .show(ui, |ui| {
+                    let mut pivot = self.pivot();
+                    let mut changed = false;
+
+                    ui.horizontal_wrapped(|ui| {
+                        for (axis, value) in [
+                            ("X:", &mut pivot.x),
+                            ("Y:", &mut pivot.y),
+                            ("Z:", &mut pivot.z),
+                        ] {
+                            ui.horizontal(|ui| {
+                                ui.label(axis);
+                                let response = ui
+                                    .add(egui::DragValue::new(value).speed(0.1).fixed_decimals(3));
+
+                                if response.drag_started() {
+                                    cfg.pivot_original = Some((*entity, self.pivot()));
+                                }
+
+                                changed |= response.changed();
+
+                                if response.drag_stopped()
+                                    && let Some((ent, original)) = cfg.pivot_original.take()
+                                {
+                                    UndoableAction::push_to_undo(
+                                        undo_stack,
+                                        UndoableAction::Pivot(ent, original),
+                                    );
+                                    log::debug!("Pushed pivot change to undo stack");
+                                }
+                            });
+                        }
+                    });
+
+                    if changed {
+                        self.set_pivot(pivot);
+                    }
+
+                    ui.horizontal(|ui| {
+                        if ui
+                            .button("Center Pivot")
+                            .on_hover_text("Moves the pivot to the centre of the model's bounds")
+                            .clicked()
+                        {
+                            *signal = Signal::CenterPivot(*entity);
+                        }
+
+                        if ui.button("Reset").clicked() && self.pivot() != DVec3::ZERO {
+                            UndoableAction::push_to_undo(
+                                undo_stack,
+                                UndoableAction::Pivot(*entity, self.pivot()),
+                            );
+                            self.set_pivot(DVec3::ZERO);
+                        }
+                    });
+                });
+        });
     }
 }
 
+/// Pushes a transform change to the undo stack, batching it with the previous change if that was
+/// made on the same entity during the same drag, and the undo stack has not changed since.
+fn push_transform_undo(
+    cfg: &mut StaticallyKept,
+    undo_stack: &mut Vec<UndoableAction>,
+    action: UndoableAction,
+) {
+    let entity = action.entity();
+    let coalesce =
+        entity.is_some_and(|entity| cfg.transform_last_drag == Some((entity, undo_generation())));
+    UndoableAction::push_coalesced(undo_stack, action, coalesce);
+    cfg.transform_last_drag = entity.map(|entity| (entity, undo_generation()));
+}
+
+/// Ends a transform drag, so the next one starts a new undo entry.
+fn end_transform_drag(cfg: &mut StaticallyKept) {
+    cfg.transform_in_progress = false;
+    cfg.transform_last_drag = None;
+}
+
 fn inspect_transform(
     transform: &mut Transform,
     entity: &mut Entity,
@@ -276,13 +488,14 @@ fn inspect_transform(
                                 if let Some(ent) = cfg.transform_old_entity.take()
                                     && let Some(orig) = cfg.transform_original_transform.take()
                                 {
-                                    UndoableAction::push_to_undo(
+                                    push_transform_undo(
+                                        cfg,
                                         undo_stack,
                                         UndoableAction::Transform(ent, orig),
                                     );
                                     log::debug!("Pushed X transform change to undo stack");
                                 }
-                                cfg.transform_in_progress = false;
+                                end_transform_drag(cfg);
                             }
                         });
                         ui.horizontal(|ui| {
@@ -303,13 +516,14 @@ fn inspect_transform(
                                 if let Some(ent) = cfg.transform_old_entity.take()
                                     && let Some(orig) = cfg.transform_original_transform.take()
                                 {
-                                    UndoableAction::push_to_undo(
+                                    push_transform_undo(
+                                        cfg,
                                         undo_stack,
                                         UndoableAction::Transform(ent, orig),
                                     );
                                     log::debug!("Pushed Y transform change to undo stack");
                                 }
-                                cfg.transform_in_progress = false;
+                                end_transform_drag(cfg);
                             }
                         });
 
@@ -331,13 +545,14 @@ fn inspect_transform(
                                 if let Some(ent) = cfg.transform_old_entity.take()
                                     && let Some(orig) = cfg.transform_original_transform.take()
                                 {
-                                    UndoableAction::push_to_undo(
+                                    push_transform_undo(
+                                        cfg,
                                         undo_stack,
                                         UndoableAction::Transform(ent, orig),
                                     );
                                     log::debug!("Pushed Z transform change to undo stack");
                                 }
-                                cfg.transform_in_progress = false;
+                                end_transform_drag(cfg);
                             }
                         });
                     });
@@ -405,13 +620,14 @@ fn inspect_transform(
                                 if let Some(ent) = cfg.transform_old_entity.take()
                                     && let Some(orig) = cfg.transform_original_transform.take()
                                 {
-                                    UndoableAction::push_to_undo(
+                                    push_transform_undo(
+                                        cfg,
                                         undo_stack,
                                         UndoableAction::Transform(ent, orig),
                                     );
                                     log::debug!("Pushed X rotation change to undo stack");
                                 }
-                                cfg.transform_in_progress = false;
+                                end_transform_drag(cfg);
                             }
                         });
 
@@ -439,13 +655,14 @@ fn inspect_transform(
                                 if let Some(ent) = cfg.transform_old_entity.take()
                                     && let Some(orig) = cfg.transform_original_transform.take()
                                 {
-                                    UndoableAction::push_to_undo(
+                                    push_transform_undo(
+                                        cfg,
                                         undo_stack,
                                         UndoableAction::Transform(ent, orig),
                                     );
                                     log::debug!("Pushed Y rotation change to undo stack");
                                 }
-                                cfg.transform_in_progress = false;
+                                end_transform_drag(cfg);
                             }
                         });
 
@@ -473,13 +690,14 @@ fn inspect_transform(
                                 if let Some(ent) = cfg.transform_old_entity.take()
                                     && let Some(orig) = cfg.transform_original_transform.take()
                                 {
-                                    UndoableAction::push_to_undo(
+                                    push_transform_undo(
+                                        cfg,
                                         undo_stack,
                                         UndoableAction::Transform(ent, orig),
                                     );
                                     log::debug!("Pushed Z rotation change to undo stack");
                                 }
-                                cfg.transform_in_progress = false;
+                                end_transform_drag(cfg);
                             }
                         });
 
@@ -548,13 +766,14 @@ fn inspect_transform(
                             if let Some(ent) = cfg.transform_old_entity.take()
                                 && let Some(orig) = cfg.transform_original_transform.take()
                             {
-                                UndoableAction::push_to_undo(
+                                push_transform_undo(
+                                    cfg,
                                     undo_stack,
                                     UndoableAction::Transform(ent, orig),
                                 );
                                 log::debug!("Pushed X scale change to undo stack");
                             }
-                            cfg.transform_in_progress = false;
+                            end_transform_drag(cfg);
                         }
                     });
 
@@ -580,13 +799,14 @@ fn inspect_transform(
                             if let Some(ent) = cfg.transform_old_entity.take()
                                 && let Some(orig) = cfg.transform_original_transform.take()
                             {
-                                UndoableAction::push_to_undo(
+                                push_transform_undo(
+                                    cfg,
                                     undo_stack,
                                     UndoableAction::Transform(ent, orig),
                                 );
                                 log::debug!("Pushed Y scale change to undo stack");
                             }
-                            cfg.transform_in_progress = false;
+                            end_transform_drag(cfg);
                         }
                     });
 
@@ -612,13 +832,14 @@ fn inspect_transform(
                             if let Some(ent) = cfg.transform_old_entity.take()
                                 && let Some(orig) = cfg.transform_original_transform.take()
                             {
-                                UndoableAction::push_to_undo(
+                                push_transform_undo(
+                                    cfg,
                                     undo_stack,
                                     UndoableAction::Transform(ent, orig),
                                 );
                                 log::debug!("Pushed Z scale change to undo stack");
                             }
-                            cfg.transform_in_progress = false;
+                            end_transform_drag(cfg);
                         }
                     });
 
@@ -674,6 +895,12 @@ impl InspectableComponent for Script {
             CollapsingHeader::new("Scripting")
                 .default_open(true)
                 .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Priority");
+                        ui.add(DragValue::new(&mut self.priority).speed(0.1))
+                            .on_hover_text("Lower priorities are updated first");
+                    });
+
                     CollapsingHeader::new("Tags")
                         .default_open(true)
                         .show(ui, |ui| {
@@ -702,6 +929,408 @@ impl InspectableComponent for Script {
     }
 }
 
+impl InspectableComponent for LightProbe {
+    fn inspect(
+        &mut self,
+        entity: &mut Entity,
+        _cfg: &mut StaticallyKept,
+        ui: &mut Ui,
+        _undo_stack: &mut Vec<UndoableAction>,
+        signal: &mut Signal,
+        _label: &mut String,
+    ) {
+        ui.vertical(|ui| {
+            CollapsingHeader::new("Light Probe")
+                .default_open(true)
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Radius");
+                        ui.add(
+                            DragValue::new(&mut self.radius)
+                                .speed(0.1)
+                                .range(0.0..=f32::MAX),
+                        );
+                    });
+
+                    if self.is_baked() {
+                        ui.label("Status: Baked");
+                    } else {
+                        ui.label(RichText::new("Status: Not baked").color(ui.visuals().warn_fg_color));
+                    }
+
+                    if ui.button("Rebake").clicked() {
+                        *signal = Signal::BakeProbes(Some(*entity));
+                    }
+                });
+        });
+    }
+}
+
+impl InspectableComponent for ParticleEmitter {
+    fn inspect(
+        &mut self,
+        _entity: &mut Entity,
+        _cfg: &mut StaticallyKept,
+        ui: &mut Ui,
+        _undo_stack: &mut Vec<UndoableAction>,
+        _signal: &mut Signal,
+        _label: &mut String,
+    ) {
+        ui.vertical(|ui| {
+            CollapsingHeader::new("Particle Emitter")
+                .default_open(true)
+                .show(ui, |ui| {
+                    Grid::new("particle_emitter_grid")
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            ui.label("Max Particles");
+                            ui.add(
+                                DragValue::new(&mut self.max_particles)
+                                    .range(1..=MAX_PARTICLES_PER_EMITTER),
+                            );
+                            ui.end_row();
+
+                            ui.label("Emission Rate");
+                            ui.add(
+                                DragValue::new(&mut self.emission_rate)
+                                    .speed(0.5)
+                                    .range(0.0..=f32::MAX)
+                                    .suffix("/s"),
+                            );
+                            ui.end_row();
+
+                            ui.label("Lifetime");
+                            ui.add(
+                                DragValue::new(&mut self.lifetime)
+                                    .speed(0.05)
+                                    .range(0.0..=f32::MAX)
+                                    .suffix("s"),
+                            );
+                            ui.end_row();
+
+                            ui.label("Initial Velocity");
+                            ui.horizontal(|ui| {
+                                ui.add(DragValue::new(&mut self.initial_velocity.x).speed(0.05));
+                                ui.add(DragValue::new(&mut self.initial_velocity.y).speed(0.05));
+                                ui.add(DragValue::new(&mut self.initial_velocity.z).speed(0.05));
+                            });
+                            ui.end_row();
+
+                            ui.label("Spread");
+                            ui.add(
+                                DragValue::new(&mut self.spread)
+                                    .speed(0.5)
+                                    .range(0.0..=180.0)
+                                    .suffix("°"),
+                            );
+                            ui.end_row();
+                        });
+
+                    CollapsingHeader::new("Size Over Life")
+                        .id_salt("particle_size_over_life")
+                        .show(ui, |ui| {
+                            let mut changed = false;
+                            let mut to_delete = None;
+                            for (i, (time, size)) in
+                                self.size_over_life.keys.iter_mut().enumerate()
+                            {
+                                ui.horizontal(|ui| {
+                                    ui.label("t");
+                                    changed |= ui
+                                        .add(DragValue::new(time).speed(0.01).range(0.0..=1.0))
+                                        .changed();
+                                    ui.label("size");
+                                    ui.add(DragValue::new(size).speed(0.01).range(0.0..=f32::MAX));
+                                    if ui.button("🗑️").clicked() {
+                                        to_delete = Some(i);
+                                    }
+                                });
+                            }
+                            if let Some(i) = to_delete {
+                                self.size_over_life.keys.remove(i);
+                            }
+                            if ui.button("➕ Add Key").clicked() {
+                                let size = self.size_over_life.evaluate(1.0);
+                                self.size_over_life.keys.push((1.0, size));
+                                changed = true;
+                            }
+                            if changed {
+                                self.size_over_life.sort();
+                            }
+                        });
+
+                    CollapsingHeader::new("Colour Over Life")
+                        .id_salt("particle_colour_over_life")
+                        .show(ui, |ui| {
+                            let mut changed = false;
+                            let mut to_delete = None;
+                            for (i, (time, colour)) in
+                                self.color_over_life.keys.iter_mut().enumerate()
+                            {
+                                ui.horizontal(|ui| {
+                                    ui.label("t");
+                                    changed |= ui
+                                        .add(DragValue::new(time).speed(0.01).range(0.0..=1.0))
+                                        .changed();
+                                    let mut rgba = colour.to_array();
+                                    egui::color_picker::color_edit_button_rgba_unmultiplied(
+                                        ui, &mut rgba,
+                                    );
+                                    *colour = Vec4::from_array(rgba);
+                                    if ui.button("🗑️").clicked() {
+                                        to_delete = Some(i);
+                                    }
+                                });
+                            }
+                            if let Some(i) = to_delete {
+                                self.color_over_life.keys.remove(i);
+                            }
+                            if ui.button("➕ Add Key").clicked() {
+                                let colour = self.color_over_life.evaluate(1.0);
+                                self.color_over_life.keys.push((1.0, colour));
+                                changed = true;
+                            }
+                            if changed {
+                                self.color_over_life.sort();
+                            }
+                        });
+
+                    if ui
+                        .button("Burst")
+                        .on_hover_text("Emits 10% of the max particles")
+                        .clicked()
+                    {
+                        self.emit((self.max_particles / 10).max(1));
+                    }
+                });
+        });
+    }
+}
+
+impl InspectableComponent for SpriteRenderer {
+    fn inspect(
+        &mut self,
+        _entity: &mut Entity,
+        _cfg: &mut StaticallyKept,
+        ui: &mut Ui,
+        _undo_stack: &mut Vec<UndoableAction>,
+        _signal: &mut Signal,
+        _label: &mut String,
+    ) {
+        ui.vertical(|ui| {
+            CollapsingHeader::new("Sprite Renderer")
+                .default_open(true)
+                .show(ui, |ui| {
+                    Grid::new("sprite_renderer_grid")
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            ui.label("Texture");
+                            let mut uri = self.texture.as_uri().unwrap_or_default().to_string();
+                            let response = ui.add(
+                                TextEdit::singleline(&mut uri).hint_text("euca://textures/..."),
+                            );
+                            if response.lost_focus()
+                                && uri != self.texture.as_uri().unwrap_or_default()
+                            {
+                                if uri.is_empty() {
+                                    self.texture = ResourceReference::new();
+                                    self.texture_handle = None;
+                                } else {
+                                    match ResourceReference::from_euca_uri(&uri) {
+                                        Ok(reference) => {
+                                            self.texture = reference;
+                                            self.texture_handle = None;
+                                        }
+                                        Err(e) => warn!("Invalid sprite texture: {}", e),
+                                    }
+                                }
+                            }
+                            ui.end_row();
+
+                            ui.label("Colour");
+                            egui::color_picker::color_edit_button_rgba_unmultiplied(
+                                ui,
+                                &mut self.color,
+                            );
+                            ui.end_row();
+
+                            ui.label("Size");
+                            ui.horizontal(|ui| {
+                                ui.add(
+                                    DragValue::new(&mut self.size.x)
+                                        .speed(0.01)
+                                        .range(0.0..=f32::MAX),
+                                );
+                                ui.add(
+                                    DragValue::new(&mut self.size.y)
+                                        .speed(0.01)
+                                        .range(0.0..=f32::MAX),
+                                );
+                            });
+                            ui.end_row();
+
+                            ui.label("Billboard");
+                            ui.checkbox(&mut self.billboard, "")
+                                .on_hover_text("Always face the camera");
+                            ui.end_row();
+                        });
+                });
+        });
+    }
+}
+
+impl InspectableComponent for MorphWeights {
+    fn inspect(
+        &mut self,
+        _entity: &mut Entity,
+        _cfg: &mut StaticallyKept,
+        ui: &mut Ui,
+        _undo_stack: &mut Vec<UndoableAction>,
+        _signal: &mut Signal,
+        _label: &mut String,
+    ) {
+        ui.vertical(|ui| {
+            CollapsingHeader::new("Morph Targets")
+                .default_open(true)
+                .show(ui, |ui| {
+                    if self.weights.is_empty() {
+                        ui.label(
+                            RichText::new("The model of this entity has no morph targets")
+                                .small()
+                                .color(ui.visuals().weak_text_color()),
+                        );
+                        return;
+                    }
+
+                    Grid::new("morph_weights_grid")
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            for (name, weight) in self.weights.iter_mut() {
+                                ui.label(name.as_str());
+                                ui.add(egui::Slider::new(weight, 0.0..=1.0));
+                                ui.end_row();
+                            }
+                        });
+
+                    if ui.button("Reset").clicked() {
+                        // the defaults from the model are filled back in on the next update
+                        self.weights.clear();
+                    }
+                });
+        });
+    }
+}
+
+impl InspectableComponent for LodGroup {
+    fn inspect(
+        &mut self,
+        _entity: &mut Entity,
+        _cfg: &mut StaticallyKept,
+        ui: &mut Ui,
+        _undo_stack: &mut Vec<UndoableAction>,
+        _signal: &mut Signal,
+        _label: &mut String,
+    ) {
+        ui.vertical(|ui| {
+            CollapsingHeader::new("LOD Group")
+                .default_open(true)
+                .show(ui, |ui| {
+                    let mut changed = false;
+                    let mut remove = None;
+
+                    Grid::new("lod_group_grid").num_columns(3).show(ui, |ui| {
+                        ui.label("Max Distance");
+                        ui.label("Model");
+                        ui.label("");
+                        ui.end_row();
+
+                        for (i, (distance, reference)) in self.sources.iter_mut().enumerate() {
+                            changed |= ui
+                                .add(
+                                    DragValue::new(distance)
+                                        .speed(0.1)
+                                        .range(0.0..=f32::MAX)
+                                        .suffix(" m"),
+                                )
+                                .changed();
+
+                            let selected_text = reference.as_uri().unwrap_or("None").to_string();
+                            ComboBox::from_id_salt(("lod_level_model", i))
+                                .selected_text(selected_text)
+                                .show_ui(ui, |ui| {
+                                    for model in ASSET_REGISTRY.iter_model() {
+                                        let Some(uri) = model.path.as_uri() else {
+                                            continue;
+                                        };
+
+                                        if ui
+                                            .selectable_label(*reference == model.path, uri)
+                                            .clicked()
+                                        {
+                                            *reference = model.path.clone();
+                                            changed = true;
+                                        }
+                                    }
+                                });
+
+                            if ui.button("🗑️").on_hover_text("Remove level").clicked() {
+                                remove = Some(i);
+                            }
+                            ui.end_row();
+                        }
+                    });
+
+                    if let Some(i) = remove {
+                        self.sources.remove(i);
+                        changed = true;
+                    }
+
+                    if ui.button("Add Level").clicked() {
+                        let distance = self.sources.last().map_or(10.0, |(d, _)| d * 2.0);
+                        self.sources.push((distance, ResourceReference::new()));
+                        changed = true;
+                    }
+
+                    if self.levels.len() != self.sources.len() {
+                        ui.label(
+                            RichText::new("Some levels do not have a loaded model")
+                                .small()
+                                .color(ui.visuals().warn_fg_color),
+                        );
+                    }
+
+                    if changed {
+                        self.resolve();
+                    }
+                });
+        });
+    }
+}
+
+impl InspectableComponent for EntityLayer {
+    fn inspect(
+        &mut self,
+        _entity: &mut Entity,
+        _cfg: &mut StaticallyKept,
+        ui: &mut Ui,
+        _undo_stack: &mut Vec<UndoableAction>,
+        _signal: &mut Signal,
+        _label: &mut String,
+    ) {
+        let project = PROJECT.read();
+        ui.horizontal(|ui| {
+            ui.label("Layer");
+            ComboBox::from_id_salt("entity_layer")
+                .selected_text(project.layer_name(self.0))
+                .show_ui(ui, |ui| {
+                    for layer in 0..NAMED_LAYER_COUNT {
+                        ui.selectable_value(&mut self.0, layer, project.layer_name(layer));
+                    }
+                });
+        });
+    }
+}
+
 impl InspectableComponent for eucalyptus_core::states::Label {
     fn inspect(
         &mut self,
@@ -751,7 +1380,7 @@ impl InspectableComponent for MeshRenderer {
         entity: &mut Entity,
         _cfg: &mut StaticallyKept,
         ui: &mut Ui,
-        _undo_stack: &mut Vec<UndoableAction>,
+        undo_stack: &mut Vec<UndoableAction>,
         signal: &mut Signal,
         _label: &mut String,
     ) {
@@ -1044,9 +1673,12 @@ impl InspectableComponent for MeshRenderer {
                                     }
                                 });
 
+                            let mut applied = false;
                             if restore_original {
                                 if let Err(err) = self.restore_original_material(&material.name) {
                                     fatal!("Failed to restore material: {}", err);
+                                } else {
+                                    applied = true;
                                 }
                             } else if let Some((source_model, source_material)) = pending_override {
                                 if let Err(err) = self.apply_material_override(
@@ -1055,8 +1687,39 @@ impl InspectableComponent for MeshRenderer {
                                     &source_material,
                                 ) {
                                     fatal!("Failed to apply material override: {}", err);
+                                } else {
+                                    applied = true;
                                 }
                             }
+
+                            let new_override = self
+                                .material_overrides()
+                                .iter()
+                                .find(|o| o.target_material == material.name)
+                                .cloned();
+                            if applied && new_override != override_snapshot {
+                                UndoableAction::push_to_undo(
+                                    undo_stack,
+                                    UndoableAction::MaterialOverride {
+                                        entity: *entity,
+                                        target_material: material.name.clone(),
+                                        old_override: override_snapshot.clone(),
+                                        new_override,
+                                    },
+                                );
+                            }
+                        });
+
+                        ui.horizontal(|ui| {
+                            ui.label("Emissive Factor");
+                            let mut emissive_factor = material.emissive_factor;
+                            if ui.color_edit_button_rgb(&mut emissive_factor).changed() {
+                                *signal = Signal::SetEmissiveFactor(
+                                    *entity,
+                                    material.name.clone(),
+                                    emissive_factor,
+                                );
+                            }
                         });
                     }
                 });
@@ -1089,12 +1752,14 @@ impl InspectableComponent for LightComponent {
                             );
                             ui.selectable_value(&mut self.light_type, LightType::Point, "Point");
                             ui.selectable_value(&mut self.light_type, LightType::Spot, "Spot");
+                            ui.selectable_value(&mut self.light_type, LightType::Area, "Area");
                         });
                 });
 
                 // let is_dir = matches!(self.light_type, LightType::Directional);
                 let is_point = matches!(self.light_type, LightType::Point);
                 let is_spot = matches!(self.light_type, LightType::Spot);
+                let is_area = matches!(self.light_type, LightType::Area);
 
                 // colour
                 ui.separator();
@@ -1119,7 +1784,7 @@ impl InspectableComponent for LightComponent {
                     ui.checkbox(&mut self.visible, "Visible");
                 });
 
-                if is_spot || is_point {
+                if is_spot || is_point || is_area {
                     // attenuation
                     ui.separator();
                     ui.horizontal(|ui| {
@@ -1161,6 +1826,25 @@ impl InspectableComponent for LightComponent {
                     let cone_softness = self.outer_cutoff_angle - self.cutoff_angle;
                     ui.label(format!("Soft edge: {:.1}°", cone_softness));
                 }
+
+                if is_area {
+                    // size of the emitting rectangle
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::Slider::new(&mut self.area_width, 0.01..=20.0)
+                                .text("Width")
+                                .step_by(0.01),
+                        );
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::Slider::new(&mut self.area_height, 0.01..=20.0)
+                                .text("Height")
+                                .step_by(0.01),
+                        );
+                    });
+                }
             });
         });
         ui.separator();
@@ -1177,7 +1861,10 @@ impl InspectableComponent for Light {
         signal: &mut Signal,
         label: &mut String,
     ) {
-        let show_position = matches!(self.light_component.light_type, LightType::Point | LightType::Spot);
+        let show_position = matches!(
+            self.light_component.light_type,
+            LightType::Point | LightType::Spot | LightType::Area
+        );
 
         inspect_transform(
             &mut self.transform,
@@ -1222,10 +1909,20 @@ impl InspectableComponent for Camera3D {
                             });
                     });
 
-                    ui.horizontal(|ui| {
-                        ui.label("FOV");
-                        ui.add(egui::Slider::new(&mut self.fov, 1.0..=179.0).suffix("°"));
-                    });
+                    crate::camera::projection_ui(
+                        ui,
+                        &mut self.projection,
+                        self.aspect,
+                        self.near as f64,
+                        self.far as f64,
+                    );
+
+                    if matches!(self.projection, ProjectionKind::Perspective) {
+                        ui.horizontal(|ui| {
+                            ui.label("FOV");
+                            ui.add(egui::Slider::new(&mut self.fov, 1.0..=179.0).suffix("°"));
+                        });
+                    }
 
                     ui.horizontal(|ui| {
                         ui.label("Near Plane");
@@ -1257,3 +1954,24 @@ impl InspectableComponent for Camera3D {
         ui.separator();
     }
 }
+
+impl InspectableComponent for CameraFollowTarget {
+    fn inspect(
+        &mut self,
+        _entity: &mut Entity,
+        _cfg: &mut StaticallyKept,
+        ui: &mut Ui,
+        _undo_stack: &mut Vec<UndoableAction>,
+        _signal: &mut Signal,
+        _label: &mut String,
+    ) {
+        ui.vertical(|ui| {
+            CollapsingHeader::new("Camera Follow Target")
+                .default_open(true)
+                .show(ui, |ui| {
+                    Inspectable::inspect(self, ui);
+                });
+        });
+        ui.separator();
+    }
+}
@@ -0,0 +1,57 @@
+//! Dialogs shared by different parts of the editor.
+
+use std::path::Path;
+
+/// What the user has picked in a dialog so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmState {
+    /// The dialog is still open
+    Pending,
+    Confirmed,
+    Cancelled,
+}
+
+/// Shows a modal asking the user to confirm an action that can't be undone, such as one that
+/// throws away unsaved changes.
+///
+/// This has to be called every frame until it returns something other than
+/// [`ConfirmState::Pending`]. Clicking outside the modal or pressing escape cancels it.
+pub fn confirm_destructive_action(
+    ctx: &egui::Context,
+    title: &str,
+    message: &str,
+    confirm_text: &str,
+) -> ConfirmState {
+    let mut state = ConfirmState::Pending;
+
+    let modal =
+        egui::Modal::new(egui::Id::new("confirm_destructive_action").with(title)).show(ctx, |ui| {
+            ui.heading(title);
+            ui.label(message);
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                let confirm = egui::Button::new(
+                    egui::RichText::new(confirm_text).color(ui.visuals().error_fg_color),
+                );
+                if ui.add(confirm).clicked() {
+                    state = ConfirmState::Confirmed;
+                }
+                if ui.button("Cancel").clicked() {
+                    state = ConfirmState::Cancelled;
+                }
+            });
+        });
+
+    if state == ConfirmState::Pending && modal.should_close() {
+        state = ConfirmState::Cancelled;
+    }
+
+    state
+}
+
+/// Checks if a folder already has files in it, such as another project, so creating a project
+/// there should be confirmed first.
+pub fn folder_has_files(path: &Path) -> bool {
+    path.read_dir()
+        .is_ok_and(|mut entries| entries.next().is_some())
+}
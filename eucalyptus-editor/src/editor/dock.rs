@@ -14,18 +14,29 @@ use std::{
 };
 
 use crate::editor::component::InspectableComponent;
+use crate::editor::measure::{self, MeasureTool};
+use crate::editor::viewport;
 use crate::plugin::PluginRegistry;
+use dropbear_engine::asset::{AssetHandle, FilterMode, TextureImportSettings, WrapMode};
 use dropbear_engine::graphics::NO_TEXTURE;
 use dropbear_engine::utils::ResourceReference;
 use dropbear_engine::{
-    entity::{EntityTransform, MeshRenderer, Transform},
-    lighting::{Light as EngineLight, LightComponent},
+    entity::{EntityLayer, EntityTransform, MeshRenderer, Transform},
+    light_probe::LightProbe,
+    lighting::{Light as EngineLight, LightComponent, LightType},
+    lod::LodGroup,
+    morph::MorphWeights,
+    particles::ParticleEmitter,
+    picking,
+    sprite::SpriteRenderer,
+    stats::{self, SceneStats},
 };
 use egui::{self, Margin, RichText};
 use egui_dock::TabViewer;
 use egui_ltreeview::{NodeBuilder, TreeViewBuilder};
+use eucalyptus_core::camera::{CameraFollowTarget, CameraKeyframe, CameraPath, play_camera_path};
 use eucalyptus_core::states::{
-    Label, Light, ModelProperties, PROJECT, Script,
+    Label, Light, MaterialProperties, ModelProperties, PROJECT, Script,
 };
 use eucalyptus_core::traits::registry::ComponentRegistry;
 use eucalyptus_core::hierarchy::{Children, Hierarchy, Parent};
@@ -42,7 +53,13 @@ pub struct EditorTabViewer<'a> {
     pub world: &'a mut World,
     pub selected_entity: &'a mut Option<Entity>,
     pub viewport_mode: &'a mut ViewportMode,
+    pub measure_tool: &'a mut MeasureTool,
+    pub viewport_layout: ViewportLayout,
+    pub sub_viewports: &'a mut [SubViewport; SUB_VIEWPORT_COUNT],
     pub undo_stack: &'a mut Vec<UndoableAction>,
+    pub redo_stack: &'a Vec<UndoableAction>,
+    /// Whether the redo stack can still be applied, see [`Editor::can_redo`]
+    pub redo_valid: bool,
     pub signal: &'a mut Signal,
     pub gizmo_mode: &'a mut EnumSet<GizmoMode>,
     pub gizmo_orientation: &'a mut GizmoOrientation,
@@ -51,6 +68,12 @@ pub struct EditorTabViewer<'a> {
     pub plugin_registry: &'a mut PluginRegistry,
     pub component_registry: &'a ComponentRegistry,
     pub build_logs: &'a mut Vec<String>,
+    pub input_contexts: &'a InputContexts,
+    pub scene_stats: &'a SceneStats,
+    /// The slowest scripts, see [`Editor::refresh_script_timings`]
+    pub script_timings: &'a [(String, Duration)],
+    /// See [`Editor::viewport_rect`]
+    pub viewport_rect: &'a mut Option<egui::Rect>,
 
     // "wah wah its unsafe, its using raw pointers" shut the fuck up if it breaks i will know
     pub editor: *mut Editor,
@@ -113,6 +136,10 @@ pub struct StaticallyKept {
     context_menu_tab: Option<EditorTab>,
     is_focused: bool,
     old_pos: Transform,
+    /// The view of the viewport tab that the gizmo was last used in
+    gizmo_viewport: usize,
+    /// The entity under the pointer in the entity list, which has its bounds drawn in the viewport
+    hovered_entity: Option<Entity>,
     pub(crate) scale_locked: bool,
 
     pub(crate) old_label_entity: Option<hecs::Entity>,
@@ -122,8 +149,20 @@ pub struct StaticallyKept {
     pub(crate) transform_old_entity: Option<hecs::Entity>,
     pub(crate) transform_original_transform: Option<Transform>,
     pub(crate) entity_transform_original: Option<EntityTransform>,
+    /// The transform the gizmo is dragging before it is snapped, so small movements add up
+    /// instead of being rounded away every frame
+    gizmo_unsnapped: Option<Transform>,
+    pub(crate) pivot_original: Option<(hecs::Entity, glam::DVec3)>,
+    pub(crate) camera_original: Option<(hecs::Entity, CameraEdit)>,
+    pub(crate) camera_component_original: Option<(hecs::Entity, CameraEdit)>,
 
     pub(crate) transform_in_progress: bool,
+    /// The entity of the last transform change pushed during the current drag, and the undo
+    /// generation after it was pushed, so later changes in the same drag are merged into its
+    /// undo entry. Cleared when the drag ends.
+    pub(crate) transform_last_drag: Option<(Entity, u64)>,
+
+    pub(crate) import_settings_target: Option<(PathBuf, TextureImportSettings)>,
     pub(crate) transform_rotation_cache: HashMap<Entity, glam::DVec3>,
 
     component_node_ids: HashMap<ComponentNodeKey, u64>,
@@ -131,6 +170,15 @@ pub struct StaticallyKept {
     next_component_node_id: u64,
     pub(crate) last_component_lookup: Option<ComponentNodeSelection>,
     pub(crate) pending_component_drag: Option<ComponentNodeSelection>,
+
+    pub(crate) camera_path_selected: Option<String>,
+    pub(crate) camera_path_new_name: String,
+    pub(crate) camera_path_selected_key: Option<usize>,
+
+    pub(crate) material_property_new_key: String,
+
+    /// Atlas textures uploaded to egui for the asset viewer, by atlas texture handle
+    pub(crate) atlas_previews: HashMap<AssetHandle, egui::TextureHandle>,
 }
 
 impl StaticallyKept {
@@ -186,6 +234,9 @@ impl<'a> TabViewer for EditorTabViewer<'a> {
                 }
             }
             EditorTab::ErrorConsole => "Error Console".into(),
+            EditorTab::CameraPath => "Camera Path".into(),
+            EditorTab::UndoHistory => "Undo History".into(),
+            EditorTab::Statistics => "Statistics".into(),
         }
     }
 
@@ -222,54 +273,110 @@ impl<'a> TabViewer for EditorTabViewer<'a> {
                 // }
                 // -------------------------------------------------------------------------
 
+                viewport::toolbar(ui);
+
                 let available_rect = ui.available_rect_before_wrap();
                 let available_size = available_rect.size();
 
                 let tex_aspect = self.tex_size.width as f32 / self.tex_size.height as f32;
-                let available_aspect = available_size.x / available_size.y;
+                let image_rects = self.viewport_layout.image_rects(available_rect, tex_aspect);
 
-                let (display_width, display_height) = if available_aspect > tex_aspect {
-                    let height = available_size.y * 0.95;
-                    let width = height * tex_aspect;
-                    (width, height)
-                } else {
-                    let width = available_size.x * 0.95;
-                    let height = width / tex_aspect;
-                    (width, height)
-                };
+                let (_rect, _response) =
+                    ui.allocate_exact_size(available_size, egui::Sense::click_and_drag());
 
-                let center_x = available_rect.center().x;
-                let center_y = available_rect.center().y;
+                let image_responses: Vec<egui::Response> = image_rects
+                    .iter()
+                    .map(|image_rect| ui.allocate_rect(*image_rect, egui::Sense::click_and_drag()))
+                    .collect();
+
+                // the cursor is handled in physical pixels by winit
+                if let Some(image_rect) = image_rects.first() {
+                    let pixels_per_point = ui.ctx().pixels_per_point();
+                    *self.viewport_rect = Some(egui::Rect::from_min_max(
+                        (image_rect.min.to_vec2() * pixels_per_point).to_pos2(),
+                        (image_rect.max.to_vec2() * pixels_per_point).to_pos2(),
+                    ));
+                }
 
-                let image_rect = egui::Rect::from_center_size(
-                    egui::pos2(center_x, center_y),
-                    egui::vec2(display_width, display_height),
-                );
+                // clicking the viewport hands the keyboard back to the camera and scripts
+                if (_response.clicked() || image_responses.iter().any(|r| r.clicked()))
+                    && self.input_contexts.current() != InputContext::Gameplay
+                {
+                    self.input_contexts.push(InputContext::Gameplay);
+                }
 
-                let (_rect, _response) =
-                    ui.allocate_exact_size(available_size, egui::Sense::click_and_drag());
+                // the active camera is always the first view, followed by the sub viewports
+                let mut views = vec![(Some(self.view), *self.active_camera.lock())];
+                if matches!(self.viewport_layout, ViewportLayout::Quad) {
+                    views.extend(self.sub_viewports.iter().map(|sub_viewport| {
+                        let texture_id = sub_viewport.target.as_ref().map(|t| t.texture_id);
+                        (texture_id, sub_viewport.camera)
+                    }));
+                }
 
-                let _image_response = ui.allocate_rect(image_rect, egui::Sense::click_and_drag());
+                for (index, (image_rect, (texture_id, camera))) in
+                    image_rects.iter().zip(&views).enumerate()
+                {
+                    let Some(texture_id) = *texture_id else {
+                        continue;
+                    };
+                    if index > 0 && camera.is_none() {
+                        continue;
+                    }
 
-                ui.scope_builder(egui::UiBuilder::new().max_rect(image_rect), |ui| {
-                    ui.add_sized(
-                        [display_width, display_height],
-                        egui::Image::new((self.view, [display_width, display_height].into()))
-                            .fit_to_exact_size([display_width, display_height].into()),
-                    )
-                });
+                    let size = image_rect.size();
+                    ui.scope_builder(egui::UiBuilder::new().max_rect(*image_rect), |ui| {
+                        ui.add_sized(
+                            size,
+                            egui::Image::new((texture_id, size)).fit_to_exact_size(size),
+                        )
+                    });
+                }
+
+                if matches!(self.viewport_layout, ViewportLayout::Quad) {
+                    for (index, sub_viewport) in self.sub_viewports.iter_mut().enumerate() {
+                        let picker_rect = image_rects[index + 1].shrink(4.0);
+                        ui.scope_builder(egui::UiBuilder::new().max_rect(picker_rect), |ui| {
+                            viewport::camera_picker(
+                                ui,
+                                ("sub_viewport_camera", index),
+                                self.world,
+                                &mut sub_viewport.camera,
+                            );
+                        });
+                    }
+                }
+
+                let snap_settings = {
+                    let settings = PROJECT.read().editor_settings.snap;
+                    if ui.input(|input| input.modifiers.ctrl) {
+                        settings.toggled()
+                    } else {
+                        settings
+                    }
+                };
 
-                let snapping = ui.input(|input| input.modifiers.shift);
+                // the gizmo works in the view under the mouse, and stays there while it is dragged
+                if !self.gizmo.is_focused()
+                    && let Some(index) = image_responses.iter().position(|r| r.contains_pointer())
+                {
+                    cfg.gizmo_viewport = index;
+                }
+                let gizmo_viewport = cfg.gizmo_viewport.min(views.len() - 1);
 
                 // Note to self: fuck you >:(
                 // Note to self: ok wow thats pretty rude im trying my best ＞﹏＜
                 // Note to self: finally holy shit i got it working
-                let active_cam = self.active_camera.lock();
-                if let Some(active_camera) = *active_cam {
+                for (index, (image_rect, (_, camera_entity))) in
+                    image_rects.iter().copied().zip(&views).enumerate()
+                {
+                    let Some(camera_entity) = *camera_entity else {
+                        continue;
+                    };
                     let camera_data = {
                         if let Ok(mut q) = self
                             .world
-                            .query_one::<(&Camera, &CameraComponent)>(active_camera)
+                            .query_one::<(&Camera, &CameraComponent)>(camera_entity)
                         {
                             let val = q.get();
                             if let Some(val) = val {
@@ -285,19 +392,60 @@ impl<'a> TabViewer for EditorTabViewer<'a> {
                     };
 
                     if let Some(camera) = camera_data {
-                        self.gizmo.update_config(GizmoConfig {
-                            view_matrix: camera.view_mat.into(),
-                            projection_matrix: camera.proj_mat.into(),
-                            viewport: image_rect,
-                            modes: *self.gizmo_mode,
-                            orientation: *self.gizmo_orientation,
-                            snapping,
-                            snap_distance: 1.0,
-                            ..Default::default()
-                        });
+                        if index == gizmo_viewport {
+                            self.gizmo.update_config(GizmoConfig {
+                                view_matrix: camera.view_mat.into(),
+                                projection_matrix: camera.proj_mat.into(),
+                                viewport: image_rect,
+                                modes: *self.gizmo_mode,
+                                orientation: *self.gizmo_orientation,
+                                ..Default::default()
+                            });
+                        }
+
+                        let measuring = matches!(self.viewport_mode, ViewportMode::Measure);
+
+                        let image_response = &image_responses[index];
+                        if image_response.clicked()
+                            && !self.gizmo.is_focused()
+                            && let Some(pos) = image_response.interact_pointer_pos()
+                        {
+                            let cursor = pos - image_rect.min;
+                            let ray = picking::ray_from_screen(
+                                glam::vec2(cursor.x, cursor.y),
+                                &camera,
+                                (image_rect.width() as u32, image_rect.height() as u32),
+                            );
+                            if measuring {
+                                // fall back to the ground plane so points can be placed in
+                                // empty space
+                                let distance = picking::raycast_world(&ray, self.world)
+                                    .map(|(_, t)| t)
+                                    .or_else(|| picking::intersect_ground(&ray, 0.0));
+                                if let Some(t) = distance {
+                                    self.measure_tool.click(ray.at(t));
+                                }
+                            } else if let Some(hit) = picking::intersect_world(&ray, self.world)
+                            {
+                                log::debug!("Picked entity {:?} from the viewport", hit);
+                                *self.selected_entity = Some(hit);
+                            }
+                        }
+
+                        let painter = ui.painter_at(image_rect);
+                        self.measure_tool.draw(&painter, &camera, image_rect);
+                        draw_area_lights(&painter, &camera, image_rect, self.world);
+                        draw_aabbs(
+                            &painter,
+                            &camera,
+                            image_rect,
+                            self.world,
+                            *self.selected_entity,
+                            cfg.hovered_entity,
+                        );
                     }
                 }
-                if !matches!(self.viewport_mode, ViewportMode::None)
+                if !matches!(self.viewport_mode, ViewportMode::None | ViewportMode::Measure)
                     && let Some(entity_id) = self.selected_entity
                 {
                     let mut handled = false;
@@ -312,7 +460,10 @@ impl<'a> TabViewer for EditorTabViewer<'a> {
                             cfg.entity_transform_original = Some(*entity_transform);
                         }
 
-                        let synced = entity_transform.sync();
+                        let synced = match cfg.gizmo_unsnapped {
+                            Some(unsnapped) if cfg.is_focused => unsnapped,
+                            _ => entity_transform.sync(),
+                        };
                         let gizmo_transform =
                             transform_gizmo_egui::math::Transform::from_scale_rotation_translation(
                                 synced.scale,
@@ -324,9 +475,17 @@ impl<'a> TabViewer for EditorTabViewer<'a> {
                             self.gizmo.interact(ui, &[gizmo_transform])
                             && let Some(new_transform) = new_transforms.first()
                         {
-                            let new_synced_pos: glam::DVec3 = new_transform.translation.into();
-                            let new_synced_rot: glam::DQuat = new_transform.rotation.into();
-                            let new_synced_scale: glam::DVec3 = new_transform.scale.into();
+                            let mut new_synced = Transform {
+                                position: new_transform.translation.into(),
+                                rotation: new_transform.rotation.into(),
+                                scale: new_transform.scale.into(),
+                            };
+                            cfg.gizmo_unsnapped = Some(new_synced);
+                            snap_settings.apply(&mut new_synced);
+
+                            let new_synced_pos = new_synced.position;
+                            let new_synced_rot = new_synced.rotation;
+                            let new_synced_scale = new_synced.scale;
 
                             let parent_transform = entity_transform.world();
                             let parent_scale = parent_transform.scale;
@@ -349,6 +508,7 @@ impl<'a> TabViewer for EditorTabViewer<'a> {
                         }
 
                         if was_focused && !cfg.is_focused {
+                            cfg.gizmo_unsnapped = None;
                             if let Some(original) = cfg.entity_transform_original {
                                 if original != *entity_transform {
                                     UndoableAction::push_to_undo(
@@ -373,11 +533,15 @@ impl<'a> TabViewer for EditorTabViewer<'a> {
                                 cfg.old_pos = *transform;
                             }
 
+                            let unsnapped = match cfg.gizmo_unsnapped {
+                                Some(unsnapped) if cfg.is_focused => unsnapped,
+                                _ => *transform,
+                            };
                             let gizmo_transform =
                                         transform_gizmo_egui::math::Transform::from_scale_rotation_translation(
-                                            transform.scale,
-                                            transform.rotation,
-                                            transform.position,
+                                            unsnapped.scale,
+                                            unsnapped.rotation,
+                                            unsnapped.position,
                                         );
 
                             if let Some((_result, new_transforms)) =
@@ -387,9 +551,12 @@ impl<'a> TabViewer for EditorTabViewer<'a> {
                                 transform.position = new_transform.translation.into();
                                 transform.rotation = new_transform.rotation.into();
                                 transform.scale = new_transform.scale.into();
+                                cfg.gizmo_unsnapped = Some(*transform);
+                                snap_settings.apply(transform);
                             }
 
                             if was_focused && !cfg.is_focused {
+                                cfg.gizmo_unsnapped = None;
                                 let transform_changed = cfg.old_pos.position != transform.position
                                     || cfg.old_pos.rotation != transform.rotation
                                     || cfg.old_pos.scale != transform.scale;
@@ -407,6 +574,7 @@ impl<'a> TabViewer for EditorTabViewer<'a> {
                 }
             }
             EditorTab::ModelEntityList => {
+                cfg.hovered_entity = None;
                 let (_response, action) = egui_ltreeview::TreeView::new(egui::Id::new(
                     "model_entity_list",
                 ))
@@ -452,8 +620,16 @@ impl<'a> TabViewer for EditorTabViewer<'a> {
 
                         builder.node(
                             NodeBuilder::dir(entity_id)
-                                .label(label.as_str())
+                                .label_ui(|ui| {
+                                    if ui.label(label.as_str()).contains_pointer() {
+                                        cfg.hovered_entity = Some(entity);
+                                    }
+                                })
                                 .context_menu(|ui| {
+                                    if ui.button("Duplicate").clicked() {
+                                        *signal = Signal::Duplicate(entity);
+                                        ui.close();
+                                    }
                                     ui.menu_button("New", |ui| {
                                         if ui.button("Child").clicked() {
                                             let child = world.spawn((Label::new("New Entity"),));
@@ -480,7 +656,7 @@ impl<'a> TabViewer for EditorTabViewer<'a> {
                                                     *signal = Signal::AddComponent(entity, "MeshRenderer".to_string());
                                                 } else if name.contains("CameraComponent") || name.contains("Camera3D") {
                                                     *signal = Signal::AddComponent(entity, "CameraComponent".to_string());
-                                                } else if name.contains("Light") {
+                                                } else if short_name == "Light" {
                                                     *signal = Signal::AddComponent(entity, "Light".to_string());
                                                 } else {
                                                     if let Some(comp) = registry.create_default_component(id) {
@@ -591,7 +767,16 @@ impl<'a> TabViewer for EditorTabViewer<'a> {
                 }
             }
             EditorTab::AssetViewer => {
-                self.show_asset_viewer(ui);
+                self.show_asset_viewer(ui, &mut cfg);
+            }
+            EditorTab::CameraPath => {
+                self.show_camera_path_editor(ui, &mut cfg);
+            }
+            EditorTab::UndoHistory => {
+                self.show_undo_history(ui);
+            }
+            EditorTab::Statistics => {
+                self.show_statistics(ui);
             }
             EditorTab::ResourceInspector => {
                 if let Some(entity) = self.selected_entity {
@@ -653,11 +838,26 @@ impl<'a> TabViewer for EditorTabViewer<'a> {
                                 );
                             }
 
+                            if let Ok(mut q) =
+                                self.world.query_one::<&mut MaterialProperties>(*entity)
+                                && let Some(props) = q.get()
+                            {
+                                props.inspect(
+                                    entity,
+                                    &mut cfg,
+                                    ui,
+                                    self.undo_stack,
+                                    self.signal,
+                                    label.as_mut_string(),
+                                );
+                            }
+
                             if let Ok(mut q) = self
                                 .world
                                 .query_one::<(&mut Camera, &mut CameraComponent)>(*entity)
                                 && let Some((camera, camera_component)) = q.get()
                             {
+                                let settings = camera.settings;
                                 camera.inspect(
                                     entity,
                                     &mut cfg,
@@ -666,6 +866,10 @@ impl<'a> TabViewer for EditorTabViewer<'a> {
                                     self.signal,
                                     &mut String::new(),
                                 );
+                                // the component overwrites the camera's settings every frame
+                                if camera.settings != settings {
+                                    camera_component.settings = camera.settings;
+                                }
 
                                 camera_component.inspect(
                                     entity,
@@ -680,22 +884,12 @@ impl<'a> TabViewer for EditorTabViewer<'a> {
 
                                 // camera controller
                                 ui.label("Camera Controls:");
-                                let mut active_camera = self.active_camera.lock();
-
-                                if active_camera.equivalent(&Some(*entity)) {
+                                if self.active_camera.lock().equivalent(&Some(*entity)) {
                                     ui.label("Status: Currently viewing through camera");
                                 } else {
                                     ui.label("Status: Not viewing through this camera");
                                 }
 
-                                if ui.button("Set as active camera").clicked() {
-                                    *active_camera = Some(*entity);
-                                    log::info!(
-                                        "Currently viewing from camera angle '{}'",
-                                        camera.label
-                                    );
-                                }
-
                                 if camera_component.starting_camera {
                                     if ui.button("Stop making camera initial").clicked() {
                                         log::debug!("'Stop making camera initial' button clicked");
@@ -795,6 +989,113 @@ impl<'a> TabViewer for EditorTabViewer<'a> {
                                 );
                             }
 
+                            if let Ok(mut q) = self.world.query_one::<&mut EntityLayer>(*entity)
+                                && let Some(layer) = q.get()
+                            {
+                                layer.inspect(
+                                    entity,
+                                    &mut cfg,
+                                    ui,
+                                    self.undo_stack,
+                                    self.signal,
+                                    label.as_mut_string(),
+                                );
+                            }
+
+                            if let Ok(mut q) = self.world.query_one::<&mut LightProbe>(*entity)
+                                && let Some(probe) = q.get()
+                            {
+                                probe.inspect(
+                                    entity,
+                                    &mut cfg,
+                                    ui,
+                                    self.undo_stack,
+                                    self.signal,
+                                    label.as_mut_string(),
+                                );
+                            }
+
+                            if let Ok(mut q) =
+                                self.world.query_one::<&mut ParticleEmitter>(*entity)
+                                && let Some(emitter) = q.get()
+                            {
+                                emitter.inspect(
+                                    entity,
+                                    &mut cfg,
+                                    ui,
+                                    self.undo_stack,
+                                    self.signal,
+                                    label.as_mut_string(),
+                                );
+                            }
+
+                            if let Ok(mut q) =
+                                self.world.query_one::<&mut SpriteRenderer>(*entity)
+                                && let Some(sprite) = q.get()
+                            {
+                                sprite.inspect(
+                                    entity,
+                                    &mut cfg,
+                                    ui,
+                                    self.undo_stack,
+                                    self.signal,
+                                    label.as_mut_string(),
+                                );
+                            }
+
+                            if let Ok(mut q) = self.world.query_one::<&mut MaterialShader>(*entity)
+                                && let Some(shader) = q.get()
+                            {
+                                shader.inspect(
+                                    entity,
+                                    &mut cfg,
+                                    ui,
+                                    self.undo_stack,
+                                    self.signal,
+                                    label.as_mut_string(),
+                                );
+                            }
+
+                            if let Ok(mut q) =
+                                self.world.query_one::<&mut CameraFollowTarget>(*entity)
+                                && let Some(follow) = q.get()
+                            {
+                                follow.inspect(
+                                    entity,
+                                    &mut cfg,
+                                    ui,
+                                    self.undo_stack,
+                                    self.signal,
+                                    label.as_mut_string(),
+                                );
+                            }
+
+                            if let Ok(mut q) = self.world.query_one::<&mut LodGroup>(*entity)
+                                && let Some(group) = q.get()
+                            {
+                                group.inspect(
+                                    entity,
+                                    &mut cfg,
+                                    ui,
+                                    self.undo_stack,
+                                    self.signal,
+                                    label.as_mut_string(),
+                                );
+                            }
+
+                            if let Ok(mut q) = self.world.query_one::<&mut MorphWeights>(*entity)
+                                && let Some(weights) = q.get()
+                            {
+                                weights.inspect(
+                                    entity,
+                                    &mut cfg,
+                                    ui,
+                                    self.undo_stack,
+                                    self.signal,
+                                    label.as_mut_string(),
+                                );
+                            }
+
                             if let Some(t) = cfg.label_last_edit
                                 && t.elapsed() >= Duration::from_millis(500)
                             {
@@ -831,6 +1132,8 @@ impl<'a> TabViewer for EditorTabViewer<'a> {
                     }
                 } else {
                     ui.label("No entity selected, therefore no info to provide. Go on, what are you waiting for? Click an entity!");
+                    ui.separator();
+                    self.show_scene_properties(ui);
                 }
             }
             EditorTab::Plugin(dock_info) => {
@@ -974,7 +1277,7 @@ impl<'a> TabViewer for EditorTabViewer<'a> {
 }
 
 impl<'a> EditorTabViewer<'a> {
-    fn show_asset_viewer(&mut self, ui: &mut egui::Ui) {
+    fn show_asset_viewer(&mut self, ui: &mut egui::Ui, cfg: &mut StaticallyKept) {
         let project_root = {
             let project = PROJECT.read();
             if project.project_path.as_os_str().is_empty() {
@@ -984,16 +1287,630 @@ impl<'a> EditorTabViewer<'a> {
             project.project_path.clone()
         };
 
-        egui_ltreeview::TreeView::new(egui::Id::new("asset_viewer")).show(ui, |builder| {
-            builder.node(Self::dir_node("euca://"));
-            Self::build_resource_branch(builder, &project_root);
-            Self::build_scripts_branch(builder, &project_root);
-            Self::build_scene_branch(builder, &project_root);
-            Self::build_internal_models_branch(builder);
-            builder.close_dir();
+        let (_, actions) =
+            egui_ltreeview::TreeView::new(egui::Id::new("asset_viewer")).show(ui, |builder| {
+                builder.node(Self::dir_node("euca://"));
+                Self::build_resource_branch(builder, &project_root);
+                Self::build_scripts_branch(builder, &project_root);
+                Self::build_scene_branch(builder, &project_root);
+                Self::build_internal_models_branch(builder);
+                builder.close_dir();
+            });
+
+        for action in actions {
+            if let egui_ltreeview::Action::Activate(activate) = action {
+                let resources_root = project_root.join("resources");
+                for node_id in activate.selected {
+                    let Some(path) =
+                        Self::find_resource_path(&resources_root, &resources_root, node_id)
+                    else {
+                        continue;
+                    };
+                    if Self::is_texture_path(&path) {
+                        let settings = ASSET_REGISTRY.texture_import_settings(&path);
+                        cfg.import_settings_target = Some((path, settings));
+                    } else if Self::is_model_path(&path) {
+                        // applies to every material texture of the model
+                        let settings = ASSET_REGISTRY.model_texture_settings(&path);
+                        cfg.import_settings_target = Some((path, settings));
+                    }
+                }
+            }
+        }
+
+        self.show_atlases(ui, cfg);
+        self.show_import_settings_modal(ui, cfg);
+    }
+
+    /// Lists every packed atlas as a grid of the sprites in it.
+    fn show_atlases(&mut self, ui: &mut egui::Ui, cfg: &mut StaticallyKept) {
+        let atlases: Vec<_> = ASSET_REGISTRY
+            .iter_atlas()
+            .map(|entry| (*entry.key(), entry.value().clone()))
+            .collect();
+        if atlases.is_empty() {
+            return;
+        }
+
+        ui.separator();
+        for (handle, atlas) in atlases {
+            let texture = cfg.atlas_previews.entry(handle).or_insert_with(|| {
+                let image = egui::ColorImage::from_rgba_unmultiplied(
+                    [atlas.size.0 as usize, atlas.size.1 as usize],
+                    atlas.image.as_raw(),
+                );
+                ui.ctx().load_texture(
+                    format!("atlas-{}", handle.raw()),
+                    image,
+                    egui::TextureOptions::NEAREST,
+                )
+            });
+
+            egui::CollapsingHeader::new(format!("🗺 {}", atlas.label))
+                .id_salt(("atlas", handle.raw()))
+                .show(ui, |ui| {
+                    ui.label(
+                        RichText::new(format!(
+                            "{}x{}, {} sprites",
+                            atlas.size.0,
+                            atlas.size.1,
+                            atlas.sprites.len()
+                        ))
+                        .weak(),
+                    );
+
+                    egui::Grid::new(("atlas_grid", handle.raw()))
+                        .num_columns(4)
+                        .show(ui, |ui| {
+                            for (i, (label, rect)) in atlas.sprites.iter().enumerate() {
+                                let [min_u, min_v, max_u, max_v] = rect.uv(atlas.size);
+                                ui.vertical(|ui| {
+                                    ui.add(
+                                        egui::Image::new((texture.id(), egui::vec2(48.0, 48.0)))
+                                            .uv(egui::Rect::from_min_max(
+                                                egui::pos2(min_u, min_v),
+                                                egui::pos2(max_u, max_v),
+                                            ))
+                                            .maintain_aspect_ratio(true),
+                                    )
+                                    .on_hover_text(format!(
+                                        "{} ({}x{})",
+                                        label, rect.width, rect.height
+                                    ));
+                                    ui.label(RichText::new(label).small());
+                                });
+
+                                if (i + 1) % 4 == 0 {
+                                    ui.end_row();
+                                }
+                            }
+                        });
+                });
+        }
+    }
+
+    fn show_import_settings_modal(&mut self, ui: &mut egui::Ui, cfg: &mut StaticallyKept) {
+        let Some((path, settings)) = cfg.import_settings_target.as_mut() else {
+            return;
+        };
+
+        let mut close = false;
+        let modal = egui::Modal::new(egui::Id::new("import_settings_modal")).show(ui.ctx(), |ui| {
+            ui.heading("Import Settings");
+            ui.label(
+                RichText::new(
+                    path.file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                )
+                .weak(),
+            );
+            if Self::is_model_path(path) {
+                ui.label("Used for every texture of the model's materials");
+            } else {
+                ui.add(
+                    egui::Image::new(format!("file://{}", path.display()))
+                        .max_size(egui::vec2(128.0, 128.0)),
+                );
+            }
+            ui.separator();
+
+            egui::Grid::new("import_settings_grid")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("sRGB");
+                    ui.checkbox(&mut settings.srgb, "")
+                        .on_hover_text("Disable for linear data such as normal maps");
+                    ui.end_row();
+
+                    for (label, wrap) in [("Wrap U", &mut settings.wrap_u), ("Wrap V", &mut settings.wrap_v)] {
+                        ui.label(label);
+                        egui::ComboBox::from_id_salt(label)
+                            .selected_text(format!("{:?}", wrap))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(wrap, WrapMode::ClampToEdge, "ClampToEdge");
+                                ui.selectable_value(wrap, WrapMode::Repeat, "Repeat");
+                                ui.selectable_value(wrap, WrapMode::MirrorRepeat, "MirrorRepeat");
+                            });
+                        ui.end_row();
+                    }
+
+                    ui.label("Filter");
+                    egui::ComboBox::from_id_salt("import_filter")
+                        .selected_text(format!("{:?}", settings.filter))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut settings.filter, FilterMode::Nearest, "Nearest");
+                            ui.selectable_value(&mut settings.filter, FilterMode::Linear, "Linear");
+                        });
+                    ui.end_row();
+
+                    ui.label("Generate Mipmaps");
+                    ui.checkbox(&mut settings.generate_mipmaps, "");
+                    ui.end_row();
+                });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("Apply").clicked() {
+                    match ASSET_REGISTRY.set_texture_import_settings(path, *settings) {
+                        Ok(()) => {
+                            if ASSET_REGISTRY.is_texture_loaded(path) {
+                                *self.signal = Signal::ReimportTexture(path.clone());
+                            } else if let Some(handle) = ASSET_REGISTRY.model_for_path(path) {
+                                *self.signal = Signal::ReloadModel(handle);
+                            }
+                            info!("Saved import settings for {}", path.display());
+                        }
+                        Err(e) => {
+                            warn!("Unable to save import settings for {}: {}", path.display(), e);
+                        }
+                    }
+                    close = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    close = true;
+                }
+            });
+        });
+
+        if close || modal.should_close() {
+            cfg.import_settings_target = None;
+        }
+    }
+
+    /// Shows the settings of the open scene, which the resource inspector shows when no entity is
+    /// selected.
+    fn show_scene_properties(&self, ui: &mut egui::Ui) {
+        let Some(scene_name) = PROJECT.read().last_opened_scene.clone() else {
+            return;
+        };
+
+        let mut scenes = SCENES.write();
+        let Some(scene) = scenes.iter_mut().find(|s| s.scene_name == scene_name) else {
+            return;
+        };
+
+        ui.heading(format!("Scene: {}", scene_name));
+        ui.horizontal(|ui| {
+            ui.label("Clear Color");
+            let mut color = scene.clear_color.map(|c| c as f32);
+            if ui.color_edit_button_rgba_unmultiplied(&mut color).changed() {
+                scene.clear_color = color.map(f64::from);
+            }
+            if ui
+                .add_enabled(
+                    scene.clear_color != SceneConfig::DEFAULT_CLEAR_COLOR,
+                    egui::Button::new("Reset").small(),
+                )
+                .clicked()
+            {
+                scene.clear_color = SceneConfig::DEFAULT_CLEAR_COLOR;
+            }
         });
     }
 
+    fn show_camera_path_editor(&mut self, ui: &mut egui::Ui, cfg: &mut StaticallyKept) {
+        let Some(scene_name) = PROJECT.read().last_opened_scene.clone() else {
+            ui.label("Open a scene to edit its camera paths.");
+            return;
+        };
+
+        let mut scenes = SCENES.write();
+        let Some(scene) = scenes.iter_mut().find(|s| s.scene_name == scene_name) else {
+            ui.label(format!("Scene '{}' is not loaded", scene_name));
+            return;
+        };
+
+        if cfg
+            .camera_path_selected
+            .as_ref()
+            .is_some_and(|name| !scene.camera_paths.contains_key(name))
+        {
+            cfg.camera_path_selected = None;
+            cfg.camera_path_selected_key = None;
+        }
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("camera_path_select")
+                .selected_text(cfg.camera_path_selected.clone().unwrap_or("None".to_string()))
+                .show_ui(ui, |ui| {
+                    for name in scene.camera_paths.keys() {
+                        if ui
+                            .selectable_label(cfg.camera_path_selected.as_ref() == Some(name), name)
+                            .clicked()
+                        {
+                            cfg.camera_path_selected = Some(name.clone());
+                            cfg.camera_path_selected_key = None;
+                        }
+                    }
+                });
+
+            if let Some(name) = cfg.camera_path_selected.clone()
+                && ui.button("Delete").clicked()
+            {
+                scene.camera_paths.remove(&name);
+                cfg.camera_path_selected = None;
+                cfg.camera_path_selected_key = None;
+                info!("Deleted camera path '{}'", name);
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut cfg.camera_path_new_name);
+            let name = cfg.camera_path_new_name.trim().to_string();
+            if ui
+                .add_enabled(!name.is_empty(), egui::Button::new("New Path"))
+                .clicked()
+            {
+                if scene.camera_paths.contains_key(&name) {
+                    warn!("A camera path named '{}' already exists", name);
+                } else {
+                    scene.camera_paths.insert(name.clone(), CameraPath::default());
+                    cfg.camera_path_selected = Some(name);
+                    cfg.camera_path_selected_key = None;
+                    cfg.camera_path_new_name.clear();
+                }
+            }
+        });
+
+        let Some(path_name) = cfg.camera_path_selected.clone() else {
+            return;
+        };
+        let Some(path) = scene.camera_paths.get_mut(&path_name) else {
+            return;
+        };
+
+        ui.separator();
+
+        // timeline
+        let duration = path.duration().max(1.0);
+        let (rect, _) = ui.allocate_exact_size(
+            egui::vec2(ui.available_width(), 32.0),
+            egui::Sense::hover(),
+        );
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 4.0, ui.visuals().extreme_bg_color);
+        painter.line_segment(
+            [rect.left_center(), rect.right_center()],
+            ui.visuals().widgets.noninteractive.fg_stroke,
+        );
+
+        let mut sort_keys = false;
+        for (i, key) in path.keyframes.iter_mut().enumerate() {
+            let x = rect.left() + rect.width() * (key.time / duration);
+            let center = egui::pos2(x, rect.center().y);
+            let response = ui.interact(
+                egui::Rect::from_center_size(center, egui::vec2(12.0, 12.0)),
+                ui.id().with(("camera_path_key", i)),
+                egui::Sense::click_and_drag(),
+            );
+
+            if response.clicked() || response.drag_started() {
+                cfg.camera_path_selected_key = Some(i);
+            }
+            if response.dragged() {
+                key.time = (key.time + response.drag_delta().x / rect.width() * duration).max(0.0);
+            }
+            if response.drag_stopped() {
+                sort_keys = true;
+            }
+
+            let colour = if cfg.camera_path_selected_key == Some(i) {
+                ui.visuals().selection.bg_fill
+            } else {
+                ui.visuals().widgets.inactive.fg_stroke.color
+            };
+            painter.circle_filled(center, 6.0, colour);
+        }
+
+        if sort_keys {
+            let selected_time = cfg
+                .camera_path_selected_key
+                .and_then(|i| path.keyframes.get(i))
+                .map(|k| k.time);
+            path.sort();
+            cfg.camera_path_selected_key =
+                selected_time.and_then(|t| path.keyframes.iter().position(|k| k.time == t));
+        }
+
+        ui.label(format!(
+            "{} keyframes, {:.2}s",
+            path.keyframes.len(),
+            path.duration()
+        ));
+
+        ui.horizontal(|ui| {
+            if ui.button("Record Keyframe").clicked() {
+                let debug_camera = self
+                    .world
+                    .query::<(&Camera, &CameraComponent)>()
+                    .iter()
+                    .find(|(_, (_, component))| matches!(component.camera_type, CameraType::Debug))
+                    .map(|(_, (camera, component))| {
+                        (camera.eye, camera.target, component.settings.fov_y)
+                    });
+
+                if let Some((eye, target, fov)) = debug_camera {
+                    let time = path.keyframes.last().map(|k| k.time + 1.0).unwrap_or(0.0);
+                    path.keyframes.push(CameraKeyframe {
+                        time,
+                        eye,
+                        target,
+                        fov,
+                    });
+                    cfg.camera_path_selected_key = Some(path.keyframes.len() - 1);
+                } else {
+                    warn!("Unable to record keyframe: no debug camera found");
+                }
+            }
+
+            if let Some(i) = cfg.camera_path_selected_key
+                && ui.button("Remove Keyframe").clicked()
+                && i < path.keyframes.len()
+            {
+                path.keyframes.remove(i);
+                cfg.camera_path_selected_key = None;
+            }
+
+            if ui.button("Play Path").clicked() {
+                if let Some(camera) = *self.active_camera.lock() {
+                    if let Err(e) = play_camera_path(self.world, camera, path.clone()) {
+                        warn!("Unable to play camera path '{}': {}", path_name, e);
+                    }
+                } else {
+                    warn!("Unable to play camera path: no active camera");
+                }
+            }
+        });
+
+        if let Some(key) = cfg
+            .camera_path_selected_key
+            .and_then(|i| path.keyframes.get_mut(i))
+        {
+            ui.separator();
+            let mut changed_time = false;
+            egui::Grid::new("camera_path_key_grid")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Time");
+                    changed_time = ui
+                        .add(
+                            egui::DragValue::new(&mut key.time)
+                                .speed(0.05)
+                                .range(0.0..=f32::MAX),
+                        )
+                        .drag_stopped();
+                    ui.end_row();
+
+                    ui.label("Eye");
+                    ui.horizontal(|ui| {
+                        ui.add(egui::DragValue::new(&mut key.eye.x).speed(0.1));
+                        ui.add(egui::DragValue::new(&mut key.eye.y).speed(0.1));
+                        ui.add(egui::DragValue::new(&mut key.eye.z).speed(0.1));
+                    });
+                    ui.end_row();
+
+                    ui.label("Target");
+                    ui.horizontal(|ui| {
+                        ui.add(egui::DragValue::new(&mut key.target.x).speed(0.1));
+                        ui.add(egui::DragValue::new(&mut key.target.y).speed(0.1));
+                        ui.add(egui::DragValue::new(&mut key.target.z).speed(0.1));
+                    });
+                    ui.end_row();
+
+                    ui.label("FOV");
+                    ui.add(
+                        egui::DragValue::new(&mut key.fov)
+                            .speed(0.5)
+                            .range(1.0..=179.0),
+                    );
+                    ui.end_row();
+                });
+
+            if changed_time {
+                path.sort();
+                cfg.camera_path_selected_key = None;
+            }
+        }
+    }
+
+    fn show_statistics(&mut self, ui: &mut egui::Ui) {
+        let scene_stats = *self.scene_stats;
+        let rows = [
+            ("Entities", scene_stats.entity_count.to_string()),
+            ("Draw Calls", scene_stats.draw_call_count.to_string()),
+            ("Vertices", scene_stats.total_vertex_count.to_string()),
+            ("Indices", scene_stats.total_index_count.to_string()),
+            ("Triangles", (scene_stats.total_index_count / 3).to_string()),
+            (
+                "Texture Memory",
+                stats::format_bytes(scene_stats.texture_memory_bytes),
+            ),
+            (
+                "Mesh Buffer Memory",
+                stats::format_bytes(scene_stats.gpu_buffer_memory_bytes),
+            ),
+            (
+                "Texture Streaming",
+                format!(
+                    "Streamed: {} / Total: {} textures loaded",
+                    scene_stats.streamed_texture_count, scene_stats.total_streamed_texture_count
+                ),
+            ),
+        ];
+
+        egui::Grid::new("scene_statistics")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                for (name, value) in rows {
+                    ui.label(name);
+                    ui.monospace(value);
+                    ui.end_row();
+                }
+
+                let lights = scene_stats.lights;
+                ui.label("Lights");
+                ui.horizontal(|ui| {
+                    ui.monospace(format!("{} / {}", lights.active_count, lights.max_count));
+                    if lights.is_full() {
+                        ui.label(RichText::new("⚠").color(ui.visuals().warn_fg_color))
+                            .on_hover_text(
+                                "The light limit has been reached, any more lights are not drawn",
+                            );
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Light Types");
+                ui.monospace(format!(
+                    "Directional: {} / Point: {} / Spot: {} / Area: {}",
+                    lights.directional, lights.point, lights.spot, lights.area
+                ));
+                ui.end_row();
+            });
+
+        ui.add_space(4.0);
+        ui.label(
+            RichText::new("Memory usage is estimated from the loaded textures and meshes")
+                .small()
+                .color(ui.visuals().weak_text_color()),
+        );
+
+        ui.separator();
+        ui.strong("Slowest Scripts");
+        if self.script_timings.is_empty() {
+            ui.label(
+                RichText::new("Scripts are timed while playing")
+                    .color(ui.visuals().weak_text_color()),
+            );
+            return;
+        }
+
+        egui::Grid::new("script_timings")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                for (label, time) in self.script_timings {
+                    ui.label(label);
+                    ui.monospace(format!("{:.3} ms", time.as_secs_f64() * 1000.0));
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn show_undo_history(&mut self, ui: &mut egui::Ui) {
+        let entry_text = |action: &UndoableAction, world: &World| {
+            let target = action
+                .entity()
+                .map(|entity| match world.get::<&Label>(entity) {
+                    Ok(label) => label.to_string(),
+                    Err(_) => format!("{:?}", entity),
+                })
+                .unwrap_or_else(|| "multiple entities".to_string());
+            format!("{} {} - {}", action.icon(), action.kind(), target)
+        };
+
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                // undone actions, with the furthest from the current state at the top
+                for (i, action) in self.redo_stack.iter().enumerate() {
+                    let text = RichText::new(entry_text(action, self.world)).italics();
+                    let response = ui
+                        .add_enabled(self.redo_valid, egui::Button::selectable(false, text))
+                        .on_disabled_hover_text(
+                            "A new action was made after undoing, so this can no longer be redone",
+                        );
+                    if response.clicked() {
+                        *self.signal = Signal::RedoTo(i);
+                    }
+                }
+
+                if !self.redo_stack.is_empty() {
+                    ui.separator();
+                }
+
+                let last = self.undo_stack.len().saturating_sub(1);
+                for (i, action) in self.undo_stack.iter().enumerate().rev() {
+                    let text = RichText::new(entry_text(action, self.world));
+                    let text = if i == last { text.strong() } else { text };
+                    if ui
+                        .add(egui::Button::selectable(i == last, text))
+                        .on_hover_text(action.summary())
+                        .clicked()
+                        && i != last
+                    {
+                        *self.signal = Signal::UndoTo(i + 1);
+                    }
+                }
+
+                let is_start = self.undo_stack.is_empty();
+                if ui
+                    .add(egui::Button::selectable(
+                        is_start,
+                        RichText::new("Initial state").weak(),
+                    ))
+                    .clicked()
+                    && !is_start
+                {
+                    *self.signal = Signal::UndoTo(0);
+                }
+            });
+    }
+
+    /// Finds the resource file whose asset viewer node id matches `node_id`.
+    fn find_resource_path(base_path: &Path, current_path: &Path, node_id: u64) -> Option<PathBuf> {
+        for entry in Self::sorted_entries(current_path).ok()? {
+            if entry.is_dir {
+                if let Some(found) = Self::find_resource_path(base_path, &entry.path, node_id) {
+                    return Some(found);
+                }
+            } else if Self::asset_node_id(&Self::resource_label(base_path, &entry.path)) == node_id {
+                return Some(entry.path);
+            }
+        }
+        None
+    }
+
+    fn is_texture_path(path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| {
+                matches!(
+                    ext.to_ascii_lowercase().as_str(),
+                    "png" | "jpg" | "jpeg" | "tga" | "bmp"
+                )
+            })
+            .unwrap_or(false)
+    }
+
+    fn is_model_path(path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("glb") || ext.eq_ignore_ascii_case("gltf"))
+    }
+
     fn build_internal_models_branch(builder: &mut TreeViewBuilder<u64>) {
         let label = "euca://internal";
         builder.node(Self::dir_node_labeled(label, "internal"));
@@ -1050,10 +1967,20 @@ impl<'a> EditorTabViewer<'a> {
                 Self::walk_resource_directory(builder, base_path, &entry.path);
                 builder.close_dir();
             } else {
-                if entry.name.eq_ignore_ascii_case("resources.eucc") {
+                if entry.name.eq_ignore_ascii_case("resources.eucc")
+                    || entry.name.ends_with(".euimport")
+                {
                     continue;
                 }
-                builder.node(Self::leaf_node_labeled(&full_label, &entry.name));
+                if Self::is_texture_path(&entry.path) {
+                    builder.node(Self::texture_leaf_node(
+                        &full_label,
+                        &entry.name,
+                        &entry.path,
+                    ));
+                } else {
+                    builder.node(Self::leaf_node_labeled(&full_label, &entry.name));
+                }
             }
         }
     }
@@ -1391,6 +2318,17 @@ impl<'a> EditorTabViewer<'a> {
         Self::with_icon(NodeBuilder::leaf(Self::asset_node_id(id_source)).label(label.to_string()))
     }
 
+    /// Creates a leaf for an image, using a thumbnail of the image itself as the icon.
+    fn texture_leaf_node<'ui>(id_source: &str, label: &str, path: &Path) -> NodeBuilder<'ui, u64> {
+        let uri = format!("file://{}", path.display());
+        NodeBuilder::leaf(Self::asset_node_id(id_source))
+            .label(label.to_string())
+            .icon(move |ui| {
+                egui_extras::install_image_loaders(ui.ctx());
+                ui.add(egui::Image::new(uri.clone()).max_size(egui::vec2(14.0, 14.0)));
+            })
+    }
+
     fn with_icon<'ui>(builder: NodeBuilder<'ui, u64>) -> NodeBuilder<'ui, u64> {
         builder.icon(|ui| {egui_extras::install_image_loaders(ui.ctx()); Self::draw_asset_icon(ui)})
     }
@@ -1452,9 +2390,75 @@ impl<'a> EditorTabViewer<'a> {
                     target_entity
                 );
             }
+            return;
+        }
+
+        // dropping onto a component moves the entity under the component's entity
+        let new_parent = match cfg.component_selection(drag.target) {
+            Some(selection) => selection.entity(),
+            None => Self::entity_from_node_id(drag.target),
+        };
+
+        let actions: Vec<UndoableAction> = drag
+            .source
+            .iter()
+            .filter(|node_id| cfg.component_selection(**node_id).is_none())
+            .filter_map(|node_id| Self::entity_from_node_id(*node_id))
+            .filter_map(|entity| self.reparent_entity(entity, new_parent))
+            .collect();
+
+        match actions.len() {
+            0 => {}
+            1 => UndoableAction::push_to_undo(self.undo_stack, actions.into_iter().next().unwrap()),
+            _ => UndoableAction::push_to_undo(self.undo_stack, UndoableAction::Batch(actions)),
         }
     }
 
+    /// Moves an entity under a new parent, or to the root of the scene if `new_parent` is `None`.
+    ///
+    /// Returns the action that undoes the move, or `None` if nothing changed, such as when the
+    /// entity would end up under itself.
+    fn reparent_entity(
+        &mut self,
+        entity: Entity,
+        new_parent: Option<Entity>,
+    ) -> Option<UndoableAction> {
+        if !self.world.contains(entity) {
+            return None;
+        }
+
+        let old_parent = Hierarchy::get_parent(self.world, entity);
+        if old_parent == new_parent {
+            return None;
+        }
+
+        match new_parent {
+            Some(parent) => {
+                if parent == entity
+                    || !self.world.contains(parent)
+                    || Hierarchy::is_descendant_of(self.world, parent, entity)
+                {
+                    log::warn!("Cannot move {:?} under {:?}", entity, parent);
+                    return None;
+                }
+                Hierarchy::set_parent(self.world, entity, parent);
+            }
+            None => Hierarchy::remove_parent(self.world, entity),
+        }
+
+        log::debug!(
+            "Moved {:?} from {:?} to {:?}",
+            entity,
+            old_parent,
+            new_parent
+        );
+        Some(UndoableAction::SetParent {
+            entity,
+            old_parent,
+            new_parent,
+        })
+    }
+
     fn resolve_tree_node(&mut self, cfg: &mut StaticallyKept, node_id: u64) {
         if let Some(selection) = cfg.component_selection(node_id) {
             self.inspect_component_selection(cfg, selection);
@@ -1516,3 +2520,83 @@ pub enum EditorTabMenuAction {
     RemoveComponent,
     ViewportOption,
 }
+
+/// Outlines every area light in the viewport with a white rectangle.
+fn draw_area_lights(painter: &egui::Painter, camera: &Camera, rect: egui::Rect, world: &World) {
+    let stroke = egui::Stroke::new(1.5, egui::Color32::WHITE);
+    for (_, (light, t, et)) in world
+        .query::<(
+            &LightComponent,
+            Option<&Transform>,
+            Option<&EntityTransform>,
+        )>()
+        .iter()
+    {
+        if light.light_type != LightType::Area {
+            continue;
+        }
+
+        let transform = et.map(|et| et.sync()).or(t.copied()).unwrap_or_default();
+        let right = transform.rotation * glam::DVec3::X * (light.area_width as f64 * 0.5);
+        let up = transform.rotation * glam::DVec3::Y * (light.area_height as f64 * 0.5);
+        let corners = [
+            transform.position - right - up,
+            transform.position + right - up,
+            transform.position + right + up,
+            transform.position - right + up,
+        ];
+
+        let Some(points) = corners
+            .iter()
+            .map(|corner| measure::project(camera, rect, *corner))
+            .collect::<Option<Vec<_>>>()
+        else {
+            continue;
+        };
+
+        painter.add(egui::Shape::closed_line(points, stroke));
+    }
+}
+
+/// Draws the world space bounding box of the selected entity and the one hovered in the entity
+/// list, or of every entity with a model if "Always Show AABBs" is turned on.
+fn draw_aabbs(
+    painter: &egui::Painter,
+    camera: &Camera,
+    rect: egui::Rect,
+    world: &World,
+    selected: Option<Entity>,
+    hovered: Option<Entity>,
+) {
+    let show_all = PROJECT.read().editor_settings.always_show_aabbs;
+    for (entity, renderer) in world.query::<&MeshRenderer>().iter() {
+        let stroke = if selected == Some(entity) {
+            egui::Stroke::new(1.5, egui::Color32::from_rgb(255, 170, 0))
+        } else if hovered == Some(entity) {
+            egui::Stroke::new(1.5, egui::Color32::from_white_alpha(140))
+        } else if show_all {
+            egui::Stroke::new(1.0, egui::Color32::from_white_alpha(40))
+        } else {
+            continue;
+        };
+
+        let Some(bounds) = picking::world_bounds(renderer) else {
+            continue;
+        };
+        let corners = bounds
+            .corners()
+            .map(|corner| measure::project(camera, rect, corner.as_dvec3()));
+
+        // corners share an edge when their indices differ by one bit, see `Aabb::corners`
+        for (i, a) in corners.iter().enumerate() {
+            for bit in [1, 2, 4] {
+                if i & bit != 0 {
+                    continue;
+                }
+                if let (Some(a), Some(b)) = (a, corners[i | bit]) {
+                    painter.line_segment([*a, b], stroke);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,395 @@
+//! Exports the editor world as a binary glTF (`.glb`) file so it can be opened in external
+//! tools such as Blender.
+
+use dropbear_engine::entity::{MeshRenderer, Transform};
+use dropbear_engine::lighting::{LightComponent, LightType};
+use dropbear_engine::model::{Model, ModelVertex};
+use dropbear_engine::picking::Aabb;
+use eucalyptus_core::states::Label;
+use glam::{DMat4, DQuat, DVec3};
+use hecs::World;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+const GLB_MAGIC: u32 = 0x4654_6C67;
+const GLB_VERSION: u32 = 2;
+const CHUNK_JSON: u32 = 0x4E4F_534A;
+const CHUNK_BIN: u32 = 0x004E_4942;
+
+const COMPONENT_FLOAT: u32 = 5126;
+const COMPONENT_UNSIGNED_INT: u32 = 5125;
+const TARGET_ARRAY_BUFFER: u32 = 34962;
+const TARGET_ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+/// Builds up the json document and binary buffer of a glTF asset.
+#[derive(Default)]
+pub(crate) struct GltfBuilder {
+    buffer: Vec<u8>,
+    buffer_views: Vec<Value>,
+    accessors: Vec<Value>,
+    images: Vec<Value>,
+    textures: Vec<Value>,
+    materials: Vec<Value>,
+    meshes: Vec<Value>,
+    nodes: Vec<Value>,
+    lights: Vec<Value>,
+    /// Maps a model to its glTF mesh so entities sharing a model also share the geometry. Models
+    /// with per entity changes such as emissive overrides are separate copies, so they are looked
+    /// up by address rather than [`ModelId`](dropbear_engine::model::ModelId).
+    mesh_lookup: HashMap<*const Model, usize>,
+}
+
+impl GltfBuilder {
+    /// Appends bytes to the binary buffer as a new buffer view, returning its index.
+    fn push_view(&mut self, bytes: &[u8], stride: Option<usize>, target: Option<u32>) -> usize {
+        while !self.buffer.len().is_multiple_of(4) {
+            self.buffer.push(0);
+        }
+
+        let mut view = json!({
+            "buffer": 0,
+            "byteOffset": self.buffer.len(),
+            "byteLength": bytes.len(),
+        });
+        if let Some(stride) = stride {
+            view["byteStride"] = json!(stride);
+        }
+        if let Some(target) = target {
+            view["target"] = json!(target);
+        }
+
+        self.buffer.extend_from_slice(bytes);
+        self.buffer_views.push(view);
+        self.buffer_views.len() - 1
+    }
+
+    fn push_accessor(&mut self, accessor: Value) -> usize {
+        self.accessors.push(accessor);
+        self.accessors.len() - 1
+    }
+
+    /// Adds a material, embedding the encoded base colour image if one is given.
+    pub(crate) fn add_material(
+        &mut self,
+        name: &str,
+        image: Option<(&[u8], &str)>,
+        base_colour_factor: [f32; 4],
+        emissive_factor: [f32; 3],
+    ) -> usize {
+        let mut pbr = json!({
+            "baseColorFactor": base_colour_factor,
+            "metallicFactor": 0.0,
+            "roughnessFactor": 1.0,
+        });
+
+        if let Some((bytes, mime_type)) = image {
+            let view = self.push_view(bytes, None, None);
+            self.images.push(json!({
+                "bufferView": view,
+                "mimeType": mime_type,
+            }));
+            self.textures
+                .push(json!({ "source": self.images.len() - 1 }));
+            pbr["baseColorTexture"] = json!({ "index": self.textures.len() - 1 });
+        }
+
+        self.materials.push(json!({
+            "name": name,
+            "pbrMetallicRoughness": pbr,
+            "emissiveFactor": emissive_factor,
+        }));
+        self.materials.len() - 1
+    }
+
+    /// Writes the vertices and indices into the buffer, returning the json of a primitive that
+    /// can be passed to [`GltfBuilder::add_mesh`].
+    pub(crate) fn add_primitive(
+        &mut self,
+        vertices: &[ModelVertex],
+        indices: &[u32],
+        material: Option<usize>,
+    ) -> Value {
+        let bounds = Aabb::from_points(vertices.iter().map(|v| &v.position));
+
+        let vertex_view = self.push_view(
+            bytemuck::cast_slice(vertices),
+            Some(size_of::<ModelVertex>()),
+            Some(TARGET_ARRAY_BUFFER),
+        );
+        let index_view = self.push_view(
+            bytemuck::cast_slice(indices),
+            None,
+            Some(TARGET_ELEMENT_ARRAY_BUFFER),
+        );
+
+        let position = self.push_accessor(json!({
+            "bufferView": vertex_view,
+            "byteOffset": std::mem::offset_of!(ModelVertex, position),
+            "componentType": COMPONENT_FLOAT,
+            "count": vertices.len(),
+            "type": "VEC3",
+            "min": bounds.min.to_array(),
+            "max": bounds.max.to_array(),
+        }));
+        let tex_coords = self.push_accessor(json!({
+            "bufferView": vertex_view,
+            "byteOffset": std::mem::offset_of!(ModelVertex, tex_coords),
+            "componentType": COMPONENT_FLOAT,
+            "count": vertices.len(),
+            "type": "VEC2",
+        }));
+        let normal = self.push_accessor(json!({
+            "bufferView": vertex_view,
+            "byteOffset": std::mem::offset_of!(ModelVertex, normal),
+            "componentType": COMPONENT_FLOAT,
+            "count": vertices.len(),
+            "type": "VEC3",
+        }));
+        let indices = self.push_accessor(json!({
+            "bufferView": index_view,
+            "componentType": COMPONENT_UNSIGNED_INT,
+            "count": indices.len(),
+            "type": "SCALAR",
+        }));
+
+        let mut primitive = json!({
+            "attributes": {
+                "POSITION": position,
+                "NORMAL": normal,
+                "TEXCOORD_0": tex_coords,
+            },
+            "indices": indices,
+        });
+        if let Some(material) = material {
+            primitive["material"] = json!(material);
+        }
+        primitive
+    }
+
+    /// Adds a mesh made up of primitives created with [`GltfBuilder::add_primitive`].
+    pub(crate) fn add_mesh(&mut self, name: &str, primitives: Vec<Value>) -> usize {
+        self.meshes.push(json!({
+            "name": name,
+            "primitives": primitives,
+        }));
+        self.meshes.len() - 1
+    }
+
+    /// Adds a node to the root of the scene.
+    pub(crate) fn add_node(&mut self, node: Value) {
+        self.nodes.push(node);
+    }
+
+    /// Adds every mesh of the model as a primitive of a single glTF mesh, reusing the mesh if the
+    /// model has already been added.
+    fn add_model(&mut self, model: &Arc<Model>) -> Option<usize> {
+        if let Some(index) = self.mesh_lookup.get(&Arc::as_ptr(model)) {
+            return Some(*index);
+        }
+
+        let material_offset = self.materials.len();
+        for material in &model.materials {
+            self.add_material(
+                &material.name,
+                None,
+                material.base_colour_factor,
+                material.emissive_factor,
+            );
+        }
+
+        let mut primitives = Vec::new();
+        for mesh in &model.meshes {
+            if mesh.vertices.is_empty() || mesh.indices.is_empty() {
+                continue;
+            }
+
+            let material =
+                (mesh.material < model.materials.len()).then_some(material_offset + mesh.material);
+            primitives.push(self.add_primitive(&mesh.vertices, &mesh.indices, material));
+        }
+
+        if primitives.is_empty() {
+            return None;
+        }
+
+        let index = self.add_mesh(&model.label, primitives);
+        self.mesh_lookup.insert(Arc::as_ptr(model), index);
+        Some(index)
+    }
+
+    pub(crate) fn add_light(&mut self, name: &str, light: &LightComponent) -> usize {
+        let colour = light.colour.as_vec3().to_array();
+        let mut value = match light.light_type {
+            LightType::Directional => json!({ "type": "directional" }),
+            // KHR_lights_punctual has no area lights, so they are exported from their centre
+            LightType::Point | LightType::Area => json!({
+                "type": "point",
+                "range": light.attenuation.range,
+            }),
+            LightType::Spot => json!({
+                "type": "spot",
+                "range": light.attenuation.range,
+                "spot": {
+                    "innerConeAngle": light.cutoff_angle.to_radians(),
+                    "outerConeAngle": light.outer_cutoff_angle.to_radians(),
+                },
+            }),
+        };
+        value["name"] = json!(name);
+        value["color"] = json!(colour);
+        value["intensity"] = json!(light.intensity);
+
+        self.lights.push(value);
+        self.lights.len() - 1
+    }
+
+    /// Assembles the json document and binary chunk into a `.glb` file.
+    pub(crate) fn into_glb(self) -> anyhow::Result<Vec<u8>> {
+        let mut document = json!({
+            "asset": {
+                "version": "2.0",
+                "generator": format!("eucalyptus-editor {}", env!("CARGO_PKG_VERSION")),
+            },
+            "scene": 0,
+            "scenes": [{ "nodes": (0..self.nodes.len()).collect::<Vec<_>>() }],
+            "nodes": self.nodes,
+        });
+
+        if !self.meshes.is_empty() {
+            document["meshes"] = json!(self.meshes);
+            document["accessors"] = json!(self.accessors);
+        }
+        if !self.materials.is_empty() {
+            document["materials"] = json!(self.materials);
+        }
+        if !self.images.is_empty() {
+            document["images"] = json!(self.images);
+            document["textures"] = json!(self.textures);
+        }
+        if !self.buffer.is_empty() {
+            document["bufferViews"] = json!(self.buffer_views);
+            document["buffers"] = json!([{ "byteLength": self.buffer.len() }]);
+        }
+
+        if !self.lights.is_empty() {
+            document["extensionsUsed"] = json!(["KHR_lights_punctual"]);
+            document["extensions"] = json!({
+                "KHR_lights_punctual": { "lights": self.lights },
+            });
+        }
+
+        let mut json_chunk = serde_json::to_vec(&document)?;
+        while !json_chunk.len().is_multiple_of(4) {
+            json_chunk.push(b' ');
+        }
+
+        let mut bin_chunk = self.buffer;
+        while !bin_chunk.len().is_multiple_of(4) {
+            bin_chunk.push(0);
+        }
+
+        let mut length = 12 + 8 + json_chunk.len();
+        if !bin_chunk.is_empty() {
+            length += 8 + bin_chunk.len();
+        }
+
+        let mut glb = Vec::with_capacity(length);
+        glb.extend_from_slice(&GLB_MAGIC.to_le_bytes());
+        glb.extend_from_slice(&GLB_VERSION.to_le_bytes());
+        glb.extend_from_slice(&u32::try_from(length)?.to_le_bytes());
+
+        glb.extend_from_slice(&u32::try_from(json_chunk.len())?.to_le_bytes());
+        glb.extend_from_slice(&CHUNK_JSON.to_le_bytes());
+        glb.extend_from_slice(&json_chunk);
+
+        if !bin_chunk.is_empty() {
+            glb.extend_from_slice(&u32::try_from(bin_chunk.len())?.to_le_bytes());
+            glb.extend_from_slice(&CHUNK_BIN.to_le_bytes());
+            glb.extend_from_slice(&bin_chunk);
+        }
+
+        Ok(glb)
+    }
+}
+
+/// Column major matrix of a node, in the layout glTF expects.
+pub(crate) fn node_matrix(matrix: DMat4) -> [f32; 16] {
+    matrix.as_mat4().to_cols_array()
+}
+
+/// Serialises every rendered entity and light in the world into a single `.glb` file.
+///
+/// Entities with a [`MeshRenderer`] become nodes referencing their mesh and materials, and
+/// lights are written using the `KHR_lights_punctual` extension. Materials keep their base colour
+/// and emissive factors, but textures are not embedded as they only live on the GPU.
+pub fn export_scene_glb(world: &World, path: impl AsRef<Path>) -> anyhow::Result<()> {
+    let mut builder = GltfBuilder::default();
+
+    for (entity, renderer) in world.query::<&MeshRenderer>().iter() {
+        let model = renderer.model();
+        let Some(mesh) = builder.add_model(&model) else {
+            log::warn!(
+                "Skipping export of entity {:?}, its model has no geometry",
+                entity
+            );
+            continue;
+        };
+
+        let name = world
+            .get::<&Label>(entity)
+            .map(|label| label.to_string())
+            .unwrap_or_else(|_| model.label.clone());
+
+        let instance = &renderer.instance;
+        let matrix = DMat4::from_scale_rotation_translation(
+            instance.scale,
+            instance.rotation,
+            instance.position,
+        );
+
+        builder.add_node(json!({
+            "name": name,
+            "mesh": mesh,
+            "matrix": node_matrix(matrix),
+        }));
+    }
+
+    for (entity, (light, transform)) in world.query::<(&LightComponent, &Transform)>().iter() {
+        let name = world
+            .get::<&Label>(entity)
+            .map(|label| label.to_string())
+            .unwrap_or_else(|_| format!("Light {}", entity.id()));
+        let index = builder.add_light(&name, light);
+
+        // glTF lights shine down the local -Z axis
+        let direction = light.direction.normalize_or(DVec3::NEG_Z);
+        let rotation = DQuat::from_rotation_arc(DVec3::NEG_Z, direction);
+        let matrix =
+            DMat4::from_scale_rotation_translation(DVec3::ONE, rotation, transform.position);
+
+        builder.add_node(json!({
+            "name": name,
+            "matrix": node_matrix(matrix),
+            "extensions": {
+                "KHR_lights_punctual": { "light": index },
+            },
+        }));
+    }
+
+    let node_count = builder.nodes.len();
+    if node_count == 0 {
+        anyhow::bail!("The scene has no models or lights to export");
+    }
+
+    let glb = builder.into_glb()?;
+    std::fs::write(path.as_ref(), glb)?;
+
+    log::info!(
+        "Exported {} nodes to {}",
+        node_count,
+        path.as_ref().display()
+    );
+    Ok(())
+}
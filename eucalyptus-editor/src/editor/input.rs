@@ -44,9 +44,32 @@ impl Keyboard for Editor {
         //
         // }
 
+        let action = self
+            .shortcuts
+            .iter()
+            .find(|(_, binding)| binding.matches(key, ctrl_pressed, shift_pressed))
+            .map(|(action, _)| *action);
+        if let Some(action) = action {
+            self.run_editor_action(action, is_playing);
+            self.input_state.pressed_keys.insert(key);
+            return;
+        }
+
+        // keys typed into a text field should not switch viewport modes
+        if !self.input_contexts.current().allows(InputContext::Gameplay) {
+            self.input_state.pressed_keys.insert(key);
+            return;
+        }
+
         match key {
             KeyCode::KeyG => {
-                if self.is_viewport_focused && !is_playing {
+                if self.is_viewport_focused
+                    && !is_playing
+                    && matches!(self.viewport_mode, ViewportMode::Gizmo)
+                    && let Some(entity) = self.selected_entity
+                {
+                    self.signal = Signal::SnapToGround(entity);
+                } else if self.is_viewport_focused && !is_playing {
                     self.viewport_mode = ViewportMode::Gizmo;
                     info!("Switched to Viewport::Gizmo");
 
@@ -75,15 +98,18 @@ impl Keyboard for Editor {
                     self.input_state.pressed_keys.insert(key);
                 }
             }
-            KeyCode::Delete => {
-                if !is_playing {
-                    if let Some((_, tab)) = self.dock_state.find_active_focused()
-                        && matches!(tab, EditorTab::ModelEntityList)
-                    {
-                        if self.selected_entity.is_some() {
-                            self.signal = Signal::Delete;
-                        } else {
-                            warn!("Failed to delete: No entity selected");
+            KeyCode::KeyM => {
+                if self.is_viewport_focused && !is_playing {
+                    if matches!(self.viewport_mode, ViewportMode::Measure) {
+                        self.measure_tool.clear();
+                        self.viewport_mode = ViewportMode::None;
+                        info!("Switched to Viewport::None");
+                    } else {
+                        self.viewport_mode = ViewportMode::Measure;
+                        info!("Switched to Viewport::Measure");
+                        if let Some(window) = &self.window {
+                            window.set_cursor_visible(true);
+                            let _ = window.set_cursor_grab(CursorGrabMode::None);
                         }
                     }
                 } else {
@@ -91,24 +117,169 @@ impl Keyboard for Editor {
                 }
             }
             KeyCode::Escape => {
+                // the cursor should never be stuck grabbed, whatever else escape does
+                self.release_cursor();
+
                 if is_double_press {
                     if self.selected_entity.is_some() {
                         self.selected_entity = None;
                         log::debug!("Deselected entity");
                     }
                 } else if self.is_viewport_focused && !is_playing {
+                    if matches!(self.viewport_mode, ViewportMode::Measure) {
+                        self.measure_tool.clear();
+                    }
                     self.viewport_mode = ViewportMode::None;
                     info!("Switched to Viewport::None");
-                    if let Some(window) = &self.window {
-                        window.set_cursor_visible(true);
-                        let _ = window.set_cursor_grab(CursorGrabMode::None);
-                    }
                 } else {
                     self.input_state.pressed_keys.insert(key);
                 }
             }
-            KeyCode::KeyQ => {
-                if ctrl_pressed && !is_playing {
+            KeyCode::KeyC => {
+                if matches!(self.viewport_mode, ViewportMode::Gizmo) {
+                    info!("GizmoMode set to scale");
+                    self.gizmo_mode = GizmoMode::all_scale();
+                } else {
+                    self.input_state.pressed_keys.insert(key);
+                }
+            }
+            KeyCode::KeyZ => {
+                if matches!(self.viewport_mode, ViewportMode::Gizmo) && !is_playing {
+                    info!("GizmoMode set to translate");
+                    self.gizmo_mode = GizmoMode::all_translate();
+                } else {
+                    self.input_state.pressed_keys.insert(key);
+                }
+            }
+            KeyCode::KeyX => {
+                if matches!(self.viewport_mode, ViewportMode::Gizmo) && !is_playing {
+                    info!("GizmoMode set to rotate");
+                    self.gizmo_mode = GizmoMode::all_rotate();
+                } else {
+                    self.input_state.pressed_keys.insert(key);
+                }
+            }
+            KeyCode::KeyL => {
+                if matches!(self.viewport_mode, ViewportMode::Gizmo) && !is_playing {
+                    info!("GizmoOrientation set to Local");
+                    self.gizmo_orientation = GizmoOrientation::Local;
+                } else {
+                    self.input_state.pressed_keys.insert(key);
+                }
+            }
+            KeyCode::KeyW => {
+                if matches!(self.viewport_mode, ViewportMode::Gizmo) && !is_playing {
+                    info!("GizmoOrientation set to Global");
+                    self.gizmo_orientation = GizmoOrientation::Global;
+                } else {
+                    self.input_state.pressed_keys.insert(key);
+                }
+            }
+            _ => {
+                self.input_state.pressed_keys.insert(key);
+            }
+        }
+        self.input_state.pressed_keys.insert(key);
+    }
+
+    fn key_up(&mut self, key: KeyCode, _event_loop: &ActiveEventLoop) {
+        self.input_state.pressed_keys.remove(&key);
+    }
+}
+
+impl Editor {
+    /// Switches to [`InputContext::UI`] while an egui widget wants the keyboard, such as a text
+    /// field being typed into, and back once it lets go.
+    ///
+    /// A [`InputContext::Gameplay`] context pushed by clicking the viewport is popped as soon as
+    /// egui takes the keyboard.
+    pub(crate) fn update_input_context(&mut self, ctx: &egui::Context) {
+        let wants_keyboard = ctx.wants_keyboard_input();
+        let current = self.input_contexts.current();
+
+        if wants_keyboard && current != InputContext::UI {
+            if current == InputContext::Gameplay {
+                self.input_contexts.pop();
+            }
+            self.input_contexts.push(InputContext::UI);
+        } else if !wants_keyboard && current == InputContext::UI {
+            self.input_contexts.pop();
+        }
+    }
+
+    /// Hides the cursor and keeps it inside the window while the right mouse button is held down
+    /// over the viewport, see [`Editor::is_mouse_looking`].
+    fn grab_cursor(&mut self) {
+        let Some(center) = self.viewport_center() else {
+            return;
+        };
+        let Some(window) = &self.window else {
+            return;
+        };
+
+        window.set_cursor_visible(false);
+        if let Err(e) = window
+            .set_cursor_grab(CursorGrabMode::Confined)
+            .or_else(|_| {
+                log_once::warn_once!("Using cursor grab fallback: CursorGrabMode::Locked");
+                window.set_cursor_grab(CursorGrabMode::Locked)
+            })
+        {
+            log_once::error_once!("Unable to grab mouse: {}", e);
+        }
+        let (x, y) = self.input_state.mouse_pos;
+        self.mouse_look_origin = Some(PhysicalPosition::new(x, y));
+        let _ = window.set_cursor_position(center);
+        self.is_mouse_looking = true;
+    }
+
+    /// Shows the cursor again and lets go of it, putting it back where mouse look started.
+    pub(crate) fn release_cursor(&mut self) {
+        self.is_mouse_looking = false;
+        let origin = self.mouse_look_origin.take();
+        if let Some(window) = &self.window {
+            window.set_cursor_visible(true);
+            if let Err(e) = window.set_cursor_grab(CursorGrabMode::None) {
+                log_once::error_once!("Unable to release mouse grab: {}", e);
+            }
+            if let Some(origin) = origin {
+                let _ = window.set_cursor_position(origin);
+            }
+        }
+    }
+
+    /// Whether the cursor is over the active camera's view in the viewport tab.
+    fn is_cursor_over_viewport(&self) -> bool {
+        let (x, y) = self.input_state.mouse_pos;
+        self.viewport_rect
+            .is_some_and(|rect| rect.contains(egui::pos2(x as f32, y as f32)))
+    }
+
+    /// The middle of the active camera's view in the viewport tab, in physical pixels.
+    fn viewport_center(&self) -> Option<PhysicalPosition<f64>> {
+        let center = self.viewport_rect?.center();
+        Some(PhysicalPosition::new(center.x as f64, center.y as f64))
+    }
+
+    /// Runs the action of a keyboard shortcut from [`Editor::shortcuts`].
+    fn run_editor_action(&mut self, action: EditorAction, is_playing: bool) {
+        match action {
+            EditorAction::Save => {
+                if !is_playing {
+                    match self.save_project_config() {
+                        Ok(_) => {
+                            success!("Successfully saved project");
+                        }
+                        Err(e) => {
+                            fatal!("Error saving project: {}", e);
+                        }
+                    }
+                } else {
+                    warn!("Unable to save project config, please quit your playing and try again");
+                }
+            }
+            EditorAction::SaveAndQuit => {
+                if !is_playing {
                     match self.save_project_config() {
                         Ok(_) => {}
                         Err(e) => {
@@ -118,14 +289,14 @@ impl Keyboard for Editor {
                     log::info!("Successfully saved project, about to quit...");
                     success_without_console!("Successfully saved project");
                     self.scene_command = SceneCommand::Quit;
-                } else if is_playing {
+                } else {
                     warn!(
                         "Unable to save-quit project, please pause your playing state, then try again"
                     );
                 }
             }
-            KeyCode::KeyC => {
-                if ctrl_pressed && !is_playing {
+            EditorAction::Copy => {
+                if !is_playing {
                     if let Some((_, tab)) = self.dock_state.find_active_focused()
                         && matches!(tab, EditorTab::ModelEntityList)
                     {
@@ -166,117 +337,65 @@ impl Keyboard for Editor {
                             warn!("Unable to copy entity: None selected");
                         }
                     }
-                } else if matches!(self.viewport_mode, ViewportMode::Gizmo) {
-                    info!("GizmoMode set to scale");
-                    self.gizmo_mode = GizmoMode::all_scale();
-                } else {
-                    self.input_state.pressed_keys.insert(key);
                 }
             }
-            KeyCode::KeyV => {
-                if ctrl_pressed && !is_playing {
-                    if let Signal::Copy(entity) = &self.signal {
-                        self.signal = Signal::Paste(entity.clone());
-                    }
-                } else {
-                    self.input_state.pressed_keys.insert(key);
+            EditorAction::Paste => {
+                if !is_playing && let Signal::Copy(entity) = &self.signal {
+                    self.signal = Signal::Paste(entity.clone());
                 }
             }
-            KeyCode::KeyS => {
-                if ctrl_pressed {
-                    if !is_playing {
-                        match self.save_project_config() {
-                            Ok(_) => {
-                                success!("Successfully saved project");
-                            }
-                            Err(e) => {
-                                fatal!("Error saving project: {}", e);
-                            }
-                        }
-                    } else {
-                        warn!(
-                            "Unable to save project config, please quit your playing and try again"
-                        );
-                    }
-                } else {
-                    self.input_state.pressed_keys.insert(key);
-                }
-            }
-            KeyCode::KeyZ => {
-                if ctrl_pressed && !is_playing {
-                    if shift_pressed {
-                        // redo
-                        info!("Redo not implemented yet, please report this")
-                    } else {
-                        // undo
-                        log::debug!("Undo signal sent");
-                        self.signal = Signal::Undo;
-                    }
-                } else if matches!(self.viewport_mode, ViewportMode::Gizmo) && !is_playing {
-                    info!("GizmoMode set to translate");
-                    self.gizmo_mode = GizmoMode::all_translate();
-                } else {
-                    self.input_state.pressed_keys.insert(key);
+            EditorAction::Duplicate => {
+                if !is_playing && let Some(entity) = self.selected_entity {
+                    self.signal = Signal::Duplicate(entity);
                 }
             }
-            KeyCode::F1 => {
+            EditorAction::Undo => {
                 if !is_playing {
-                    if self.is_using_debug_camera() {
-                        self.switch_to_player_camera();
-                    } else {
-                        self.switch_to_debug_camera();
-                    }
-                } else {
-                    self.input_state.pressed_keys.insert(key);
+                    log::debug!("Undo signal sent");
+                    self.signal = Signal::Undo;
                 }
             }
-            KeyCode::KeyX => {
-                if matches!(self.viewport_mode, ViewportMode::Gizmo) && !is_playing {
-                    info!("GizmoMode set to rotate");
-                    self.gizmo_mode = GizmoMode::all_rotate();
-                } else {
-                    self.input_state.pressed_keys.insert(key);
+            EditorAction::Redo => {
+                if !is_playing {
+                    log::debug!("Redo signal sent");
+                    self.signal = Signal::Redo;
                 }
             }
-            KeyCode::KeyP => {
-                if !is_playing && ctrl_pressed {
+            EditorAction::Play => {
+                if !is_playing {
                     self.signal = Signal::Play
-                } else {
-                    self.input_state.pressed_keys.insert(key);
                 }
             }
-            KeyCode::KeyL => {
-                if matches!(self.viewport_mode, ViewportMode::Gizmo) && !is_playing {
-                    info!("GizmoOrientation set to Local");
-                    self.gizmo_orientation = GizmoOrientation::Local;
-                } else {
-                    self.input_state.pressed_keys.insert(key);
+            EditorAction::Delete => {
+                if !is_playing
+                    && let Some((_, tab)) = self.dock_state.find_active_focused()
+                    && matches!(tab, EditorTab::ModelEntityList)
+                {
+                    if self.selected_entity.is_some() {
+                        self.signal = Signal::Delete;
+                    } else {
+                        warn!("Failed to delete: No entity selected");
+                    }
                 }
             }
-            KeyCode::KeyW => {
-                if matches!(self.viewport_mode, ViewportMode::Gizmo) && !is_playing {
-                    info!("GizmoOrientation set to Global");
-                    self.gizmo_orientation = GizmoOrientation::Global;
-                } else {
-                    self.input_state.pressed_keys.insert(key);
+            EditorAction::ToggleDebugCamera => {
+                if !is_playing {
+                    if self.is_using_debug_camera() {
+                        self.switch_to_player_camera();
+                    } else {
+                        self.switch_to_debug_camera();
+                    }
                 }
             }
-            _ => {
-                self.input_state.pressed_keys.insert(key);
-            }
         }
-        self.input_state.pressed_keys.insert(key);
-    }
-
-    fn key_up(&mut self, key: KeyCode, _event_loop: &ActiveEventLoop) {
-        self.input_state.pressed_keys.remove(&key);
     }
 }
 
 impl Mouse for Editor {
     fn mouse_move(&mut self, position: PhysicalPosition<f64>, delta: Option<(f64, f64)>) {
-        if self.is_viewport_focused && matches!(self.viewport_mode, ViewportMode::CameraMove) {
-            if let Some(window) = &self.window {
+        let is_camera_move = matches!(self.viewport_mode, ViewportMode::CameraMove);
+        if (self.is_viewport_focused && is_camera_move) || self.is_mouse_looking {
+            if is_camera_move && let Some(window) = &self.window {
                 window.set_cursor_visible(false);
                 if let Err(e) = window.set_cursor_grab(CursorGrabMode::Locked).or_else(|_| {
                     log_once::warn_once!("Using cursor grab fallback: CursorGrabMode::Locked");
@@ -290,7 +409,8 @@ impl Mouse for Editor {
                 && let Ok(mut q) = self
                     .world
                     .query_one::<(&mut Camera, &CameraComponent)>(active_camera)
-                && let Some((camera, _)) = q.get()
+                && let Some((camera, component)) = q.get()
+                && !matches!(component.camera_type, CameraType::Cinematic)
             {
                 if let Some((dx, dy)) = delta {
                     camera.track_mouse_delta(
@@ -298,6 +418,20 @@ impl Mouse for Editor {
                         dy * camera.settings.sensitivity,
                     );
                     self.input_state.mouse_delta = Some((dx, dy));
+                } else if self.is_mouse_looking
+                    && let Some(window) = &self.window
+                    && let Some(center) = self.viewport_center()
+                {
+                    // the cursor is put back in the middle after every move, so how far it is
+                    // from the middle is how far it moved
+                    let dx = position.x - center.x;
+                    let dy = position.y - center.y;
+                    camera.track_mouse_delta(
+                        dx * camera.settings.sensitivity,
+                        dy * camera.settings.sensitivity,
+                    );
+                    self.input_state.mouse_delta = Some((dx, dy));
+                    let _ = window.set_cursor_position(center);
                 } else {
                     log_once::warn_once!("Unable to track mouse delta, attempting fallback");
                     // fallback for mouse tracking
@@ -335,10 +469,20 @@ impl Mouse for Editor {
     }
 
     fn mouse_down(&mut self, button: MouseButton) {
+        if button == MouseButton::Right
+            && self.is_cursor_over_viewport()
+            && !matches!(self.editor_state, EditorState::Playing)
+            && !matches!(self.viewport_mode, ViewportMode::CameraMove)
+        {
+            self.grab_cursor();
+        }
         self.input_state.mouse_button.insert(button);
     }
 
     fn mouse_up(&mut self, button: MouseButton) {
+        if button == MouseButton::Right && self.is_mouse_looking {
+            self.release_cursor();
+        }
         self.input_state.mouse_button.remove(&button);
     }
 }
@@ -0,0 +1,93 @@
+//! The viewport ruler, used to measure distances between points in the scene.
+//!
+//! Measurements only live in the editor and are never saved to the scene.
+
+use dropbear_engine::camera::Camera;
+use glam::{DVec3, DVec4};
+
+/// Collects pairs of points clicked in the viewport while in [`ViewportMode::Measure`].
+///
+/// [`ViewportMode::Measure`]: eucalyptus_core::utils::ViewportMode::Measure
+#[derive(Default, Debug, Clone)]
+pub struct MeasureTool {
+    /// The first point of the measurement that is currently being placed
+    pub start: Option<DVec3>,
+    /// The second point of the measurement that is currently being placed
+    pub end: Option<DVec3>,
+    /// Completed measurements, as start and end points
+    pub measurements: Vec<(DVec3, DVec3)>,
+}
+
+impl MeasureTool {
+    /// Records a clicked point. Every second click completes a measurement.
+    pub fn click(&mut self, point: DVec3) {
+        match self.start {
+            None => {
+                self.start = Some(point);
+                self.end = None;
+            }
+            Some(start) => {
+                self.end = Some(point);
+                self.measurements.push((start, point));
+                log::debug!(
+                    "Measured {:.3} between {} and {}",
+                    start.distance(point),
+                    start,
+                    point
+                );
+                self.start = None;
+            }
+        }
+    }
+
+    /// Removes every measurement.
+    pub fn clear(&mut self) {
+        self.start = None;
+        self.end = None;
+        self.measurements.clear();
+    }
+
+    /// Draws the measurements as lines with their distance on top of the viewport image.
+    pub fn draw(&self, painter: &egui::Painter, camera: &Camera, rect: egui::Rect) {
+        let stroke = egui::Stroke::new(2.0, egui::Color32::YELLOW);
+
+        for (start, end) in &self.measurements {
+            let (Some(a), Some(b)) = (project(camera, rect, *start), project(camera, rect, *end))
+            else {
+                continue;
+            };
+
+            painter.line_segment([a, b], stroke);
+            painter.circle_filled(a, 3.0, egui::Color32::YELLOW);
+            painter.circle_filled(b, 3.0, egui::Color32::YELLOW);
+            painter.text(
+                a + (b - a) * 0.5,
+                egui::Align2::CENTER_BOTTOM,
+                format!("{:.3} m", start.distance(*end)),
+                egui::FontId::monospace(14.0),
+                egui::Color32::WHITE,
+            );
+        }
+
+        if let Some(start) = self.start
+            && let Some(a) = project(camera, rect, start)
+        {
+            painter.circle_stroke(a, 4.0, stroke);
+        }
+    }
+}
+
+/// Projects a world space point onto the viewport image, returning `None` if the point is behind
+/// the camera.
+pub(crate) fn project(camera: &Camera, rect: egui::Rect, point: DVec3) -> Option<egui::Pos2> {
+    let clip = camera.proj_mat * camera.view_mat * DVec4::new(point.x, point.y, point.z, 1.0);
+    if clip.w <= 0.0 {
+        return None;
+    }
+
+    let ndc = clip.truncate() / clip.w;
+    Some(egui::pos2(
+        rect.min.x + ((ndc.x + 1.0) * 0.5) as f32 * rect.width(),
+        rect.min.y + ((1.0 - ndc.y) * 0.5) as f32 * rect.height(),
+    ))
+}
@@ -1,70 +1,109 @@
 pub mod component;
 pub mod console_error;
+pub mod dialogs;
 pub mod dock;
+pub mod export;
 pub mod input;
+pub mod measure;
+pub mod remote;
 pub mod scene;
+pub mod shortcuts;
+pub mod treemap;
+pub mod validation;
+pub mod viewport;
+pub mod watcher;
 
 pub(crate) use crate::editor::dock::*;
 
-use crate::build::build;
+use crate::build::{DependencyGraph, build, generate_dependency_graph};
+use crate::camera::{CameraEdit, CameraProperties};
 use crate::debug;
 use crate::graphics::OutlineShader;
-use crate::plugin::PluginRegistry;
+use crate::plugin::{EditorPanel, PluginRegistry};
+use crate::editor::dialogs::ConfirmState;
+use crate::editor::measure::MeasureTool;
+use crate::editor::remote::{DEFAULT_REMOTE_PORT, RemoteServer};
+use crate::editor::shortcuts::{EditorAction, KeyBinding};
+use crate::editor::validation::SceneValidationIssue;
+use crate::editor::viewport::{SUB_VIEWPORT_COUNT, SubViewport, ViewportLayout};
+use crate::editor::watcher::SceneWatcher;
 use crate::stats::NerdStats;
+use crate::utils::ProjectCreation;
 use crossbeam_channel::Receiver;
-use dropbear_engine::asset::ASSET_REGISTRY;
-use dropbear_engine::entity::EntityTransform;
+use dropbear_engine::asset::{ASSET_REGISTRY, AssetHandle};
+use dropbear_engine::entity::{EntityLayer, EntityTransform, MaterialOverride, NAMED_LAYER_COUNT};
 use dropbear_engine::shader::Shader;
+use dropbear_engine::stats::format_bytes;
 use dropbear_engine::{
-    camera::Camera,
+    buffer::TypedBuffer,
+    camera::{Camera, CameraBuilder},
+    depth_of_field::DepthOfField,
     entity::{MeshRenderer, Transform},
-    future::FutureHandle,
-    graphics::{RenderContext, SharedGraphicsContext},
-    lighting::LightManager,
+    font::TextRenderer,
+    future::{FutureHandle, FutureQueue},
+    graphics::{InstanceRaw, RenderContext, SharedGraphicsContext},
+    input::{InputContext, InputContexts},
+    light_probe::LightProbe,
+    lighting::{Light as EngineLight, LightComponent, LightManager},
+    lod::LodGroup,
     model::{MODEL_CACHE, ModelId},
+    morph::{MorphSystem, MorphWeights},
+    particles::{ParticleEmitter, ParticleSystem},
+    resources::TextureStreamer,
     scene::SceneCommand,
+    sprite::{SpritePipeline, SpriteRenderer},
+    ssao::Ssao,
+    stats::SceneStats,
+    tone_mapping::{self, ToneMapping, ToneMappingOperator},
 };
-use egui::{self, Context};
+use egui::{self, CollapsingHeader, Context};
 use egui_dock::{DockArea, DockState, NodeIndex, Style};
 use eucalyptus_core::APP_INFO;
-use eucalyptus_core::hierarchy::{Children, SceneHierarchy};
-use eucalyptus_core::scene::{SceneConfig, SceneEntity};
+use eucalyptus_core::contacts::ContactTracker;
+use eucalyptus_core::hierarchy::{Children, Hierarchy, SceneHierarchy};
+use eucalyptus_core::macros::EntitySnapshot;
+use eucalyptus_core::scene::{SceneConfig, SceneEntity, SceneOrigin, SceneTemplate};
 use eucalyptus_core::states::{Label, SerializedMeshRenderer};
 use eucalyptus_core::traits::SerializableComponent;
 use eucalyptus_core::traits::registry::ComponentRegistry;
 use eucalyptus_core::{
-    camera::{CameraComponent, CameraType, DebugCamera},
+    camera::{CameraComponent, CameraFollowTarget, CameraType, DebugCamera},
+    config::{AssetValidationError, EditorCameraState, ProjectConfig},
     fatal, info,
     input::InputState,
     ptr::{GraphicsPtr, InputStatePtr, WorldPtr},
-    scripting::{BuildStatus, ScriptManager, ScriptTarget},
+    scripting::{BuildStatus, SceneEvent, ScriptManager, ScriptTarget},
     states,
     states::{
-        Camera3D, EditorTab, Light, ModelProperties, PROJECT, SCENES, Script,
-        WorldLoadingStatus,
+        Camera3D, EditorTab, Light, MaterialProperties, MaterialShader, ModelProperties, PROJECT,
+        SCENES, Script, WorldLoadingStatus,
     },
     success, success_without_console,
     utils::ViewportMode,
     warn,
     window::GRAPHICS_COMMAND,
 };
-use hecs::{Entity, World};
+use glam::DVec3;
+use hecs::{Entity, EntityBuilder, World};
 use parking_lot::Mutex;
 use rfd::FileDialog;
 use std::path::Path;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fs,
     path::PathBuf,
-    sync::Arc,
-    time::{Duration, Instant},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant, SystemTime},
 };
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 use tokio::sync::oneshot;
 use transform_gizmo_egui::{EnumSet, Gizmo, GizmoMode, GizmoOrientation};
 use wgpu::{Color, Extent3d, RenderPipeline};
 use winit::window::CursorGrabMode;
-use winit::{keyboard::KeyCode, window::Window};
+use winit::{dpi::PhysicalPosition, keyboard::KeyCode, window::Window};
 
 pub struct Editor {
     pub scene_command: SceneCommand,
@@ -73,30 +112,73 @@ pub struct Editor {
     pub texture_id: Option<egui::TextureId>,
     pub size: Extent3d,
     pub render_pipeline: Option<RenderPipeline>,
+    /// Pipelines for the shaders of [`MaterialShader`] components by path, along with when the
+    /// file was last modified. `None` if the shader failed to compile.
+    pub(crate) custom_pipelines: HashMap<PathBuf, (Option<SystemTime>, Option<RenderPipeline>)>,
     pub outline_pipeline: Option<OutlineShader>,
     pub light_manager: LightManager,
+    pub particle_system: Option<ParticleSystem>,
+    pub morph_system: Option<MorphSystem>,
+    /// The instances of each batch of models drawn last frame, kept so their buffers can be
+    /// reused instead of created every frame
+    pub(crate) instance_buffers: HashMap<(ModelId, Option<PathBuf>), TypedBuffer<InstanceRaw>>,
+    /// The instance of each entity with blended morph targets, which are drawn on their own
+    pub(crate) morphed_instance_buffers: HashMap<Entity, TypedBuffer<InstanceRaw>>,
+    /// Entities touching each other in play mode, for the collision callbacks of scripts
+    pub(crate) contact_tracker: ContactTracker,
+    pub sprite_pipeline: Option<SpritePipeline>,
+    /// Text drawn by scripts this frame, which is drawn along with the sprites
+    pub(crate) text_renderer: TextRenderer,
+    /// Loads sprite textures near the active camera and unloads far away ones
+    pub texture_streamer: Option<TextureStreamer>,
+    pub ssao: Option<Ssao>,
+    pub depth_of_field: Option<DepthOfField>,
+    pub tone_mapping: Option<ToneMapping>,
     pub color: Color,
 
     pub active_camera: Arc<Mutex<Option<hecs::Entity>>>,
 
     pub is_viewport_focused: bool,
+    /// Whether the right mouse button is held down over the viewport, which hides and grabs the
+    /// cursor so it can be used to look around with the camera
+    pub(crate) is_mouse_looking: bool,
+    /// Where the cursor was when mouse look started, so it can be put back afterwards
+    pub(crate) mouse_look_origin: Option<PhysicalPosition<f64>>,
+    /// The view of the active camera in the viewport tab, in physical pixels of the window.
+    /// `None` while the tab isn't shown.
+    pub(crate) viewport_rect: Option<egui::Rect>,
+    /// The input context stack of the app's input manager, see [`Editor::update_input_context`]
+    pub input_contexts: InputContexts,
     // is_cursor_locked: bool,
     pub window: Option<Arc<Window>>,
 
     pub show_new_project: bool,
     pub project_name: String,
+    scene_template: SceneTemplate,
     pub(crate) project_path: Arc<Mutex<Option<PathBuf>>>,
     pub pending_scene_switch: bool,
+    /// A project from the new project window waiting to be started on the future queue
+    queued_project_creation: Option<(String, PathBuf, SceneTemplate)>,
+    /// The project being created in the background, see [`crate::utils::start_project_creation`]
+    project_creation: Option<ProjectCreation>,
 
     pub gizmo: Gizmo,
     pub previously_selected_entity: Option<hecs::Entity>,
     pub selected_entity: Option<hecs::Entity>,
     pub viewport_mode: ViewportMode,
+    pub(crate) measure_tool: MeasureTool,
+    /// Whether the viewport tab shows one view or four
+    pub(crate) viewport_layout: ViewportLayout,
+    /// The views besides the active camera, shown in [`ViewportLayout::Quad`]
+    pub(crate) sub_viewports: [SubViewport; SUB_VIEWPORT_COUNT],
 
     pub(crate) signal: Signal,
     pub(crate) undo_stack: Vec<UndoableAction>,
-    // todo: add redo (later)
-    // redo_stack: Vec<UndoableAction>,
+    /// The inverses of undone actions, with the next action to redo at the end
+    pub(crate) redo_stack: Vec<UndoableAction>,
+    /// The [`undo_generation`] after the last undo or redo. If it has changed since, a new
+    /// action was made and the redo stack can no longer be applied.
+    pub(crate) redo_anchor: u64,
     pub(crate) editor_state: EditorState,
     pub gizmo_mode: EnumSet<GizmoMode>,
     pub gizmo_orientation: GizmoOrientation,
@@ -120,6 +202,8 @@ pub struct Editor {
     pub world_load_handle: Option<FutureHandle>,
     pub(crate) light_spawn_queue: Vec<FutureHandle>,
     pub(crate) pending_components: Vec<(hecs::Entity, FutureHandle)>,
+    /// Files dropped onto the window that are waiting to be imported
+    pub(crate) dropped_files: Vec<PathBuf>,
     pub world_receiver: Option<oneshot::Receiver<hecs::World>>,
 
     // building
@@ -129,10 +213,16 @@ pub struct Editor {
     pub build_progress: f32,
     pub show_build_window: bool,
     pub last_build_error: Option<String>,
+    /// What the last build from the export menu is made of, shown as a treemap
+    build_output_graph: Option<DependencyGraph>,
     pub show_build_error_window: bool,
 
     // plugins
     pub plugin_registry: PluginRegistry,
+    /// Panels registered with [`Editor::register_panel`]
+    panels: Vec<(String, Box<dyn EditorPanel>)>,
+    /// The names of the registered panels that are open
+    open_panels: HashSet<String>,
 
     pub dock_state_shared: Option<Arc<Mutex<DockState<EditorTab>>>>,
 
@@ -141,11 +231,50 @@ pub struct Editor {
     new_scene_name: String,
     current_scene_name: Option<String>,
     pending_scene_load: Option<PendingSceneLoad>,
+    /// Scenes waiting to be loaded into the world on top of the current scene
+    pending_additive_scenes: Vec<String>,
+    /// The scene being loaded additively, so scripts can be told once its entities are in
+    loading_additive_scene: Option<String>,
     pending_scene_creation: Option<String>,
 
+    // scene hot reloading
+    scene_watcher: Option<SceneWatcher>,
+    /// The project that the scene watcher couldn't be started for, so it isn't tried again every
+    /// frame
+    scene_watcher_failed: Option<PathBuf>,
+    /// A scene file that was changed outside of the editor and can be reloaded
+    pub(crate) scene_changed_on_disk: Option<PathBuf>,
+    /// A destructive action waiting for the user to confirm it
+    deferred_action: Option<DeferredAction>,
+    /// Lets external tools report assets they changed
+    remote_server: Option<RemoteServer>,
+    /// Models that changed on disk and are being reloaded
+    model_reloads: Vec<(AssetHandle, FutureHandle)>,
+    /// Files referenced by the project's scenes that are missing, found when the project was opened
+    asset_validation_errors: Vec<AssetValidationError>,
+    /// Problems found with the scene when play was last pressed, shown until dismissed
+    pub(crate) play_validation_issues: Vec<SceneValidationIssue>,
+    /// Set when the user chose to play despite warnings, so the next play isn't checked again
+    pub(crate) skip_play_validation: bool,
+    /// When the editor last wrote the active scene, so its own saves are not reported as changes
+    last_scene_write: Option<Instant>,
+    /// The length of the undo stack when the scene was last saved or loaded
+    saved_undo_len: usize,
+
+    /// The editor wide keyboard shortcuts
+    pub(crate) shortcuts: HashMap<EditorAction, KeyBinding>,
+    show_shortcuts: bool,
+    shortcut_search: String,
+
     // about
     show_about: bool,
+    show_project_settings: bool,
     nerd_stats: NerdStats,
+    /// Draw calls, geometry and memory of the last rendered frame
+    scene_stats: SceneStats,
+    /// The slowest scripts as (entity label, time taken), refreshed every second while playing
+    script_timings: Vec<(String, Duration)>,
+    script_timings_refreshed: Option<Instant>,
 
     // component registry
     component_registry: Arc<ComponentRegistry>,
@@ -196,16 +325,26 @@ impl Editor {
         ) {
             component_registry.register_with_default::<EntityTransform>();
             component_registry.register_with_default::<ModelProperties>();
+            component_registry.register_with_default::<MaterialProperties>();
+            component_registry.register_with_default::<MaterialShader>();
             component_registry.register_with_default::<Light>();
             component_registry.register_with_default::<Script>();
             component_registry.register_with_default::<SerializedMeshRenderer>();
             component_registry.register_with_default::<Camera3D>();
+            component_registry.register_with_default::<LightProbe>();
+            component_registry.register_with_default::<EntityLayer>();
+            component_registry.register_with_default::<ParticleEmitter>();
+            component_registry.register_with_default::<SpriteRenderer>();
+            component_registry.register_with_default::<LodGroup>();
+            component_registry.register_with_default::<MorphWeights>();
+            component_registry.register_with_default::<CameraFollowTarget>();
 
             component_registry.register_converter::<MeshRenderer, SerializedMeshRenderer, _>(
                 |_, _, renderer| {
                     Some(SerializedMeshRenderer {
                         handle: renderer.handle().path.clone(),
                         material_override: renderer.material_overrides().to_vec(),
+                        emissive_overrides: renderer.emissive_overrides.clone(),
                     })
                 },
             );
@@ -256,21 +395,34 @@ impl Editor {
             texture_id: None,
             size: Extent3d::default(),
             render_pipeline: None,
+            custom_pipelines: HashMap::new(),
             color: Color::default(),
             is_viewport_focused: false,
+            is_mouse_looking: false,
+            mouse_look_origin: None,
+            viewport_rect: None,
+            input_contexts: InputContexts::default(),
             // is_cursor_locked: false,
             window: None,
             world: Box::new(World::new()),
             show_new_project: false,
             project_name: String::new(),
+            scene_template: SceneTemplate::default(),
             project_path: Arc::new(Mutex::new(None)),
             pending_scene_switch: false,
+            queued_project_creation: None,
+            project_creation: None,
             gizmo: Gizmo::default(),
             previously_selected_entity: None,
             selected_entity: None,
             viewport_mode: ViewportMode::None,
+            measure_tool: MeasureTool::default(),
+            viewport_layout: ViewportLayout::Single,
+            sub_viewports: Default::default(),
             signal: Signal::None,
             undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            redo_anchor: 0,
             script_manager: ScriptManager::new()?,
             editor_state: EditorState::Editing,
             gizmo_mode: EnumSet::empty(),
@@ -278,6 +430,17 @@ impl Editor {
             play_mode_backup: None,
             input_state: Box::new(InputState::new()),
             light_manager: LightManager::new(),
+            particle_system: None,
+            morph_system: None,
+            instance_buffers: HashMap::new(),
+            morphed_instance_buffers: HashMap::new(),
+            contact_tracker: ContactTracker::new(),
+            sprite_pipeline: None,
+            text_renderer: TextRenderer::new(),
+            texture_streamer: None,
+            ssao: None,
+            depth_of_field: None,
+            tone_mapping: None,
             active_camera: Arc::new(Mutex::new(None)),
             progress_tx: None,
             is_world_loaded: IsWorldLoadedYet::new(),
@@ -285,6 +448,7 @@ impl Editor {
             world_load_handle: None,
             light_spawn_queue: vec![],
             pending_components: vec![],
+            dropped_files: vec![],
             world_receiver: None,
             progress_rx: None,
             handle_created: None,
@@ -292,6 +456,7 @@ impl Editor {
             build_progress: 0.0,
             show_build_window: false,
             last_build_error: None,
+            build_output_graph: None,
             show_build_error_window: false,
             plugin_registry,
             dock_state_shared: None,
@@ -300,13 +465,87 @@ impl Editor {
             new_scene_name: String::new(),
             current_scene_name: None,
             pending_scene_load: None,
+            pending_additive_scenes: Vec::new(),
+            loading_additive_scene: None,
             pending_scene_creation: None,
+            scene_watcher: None,
+            scene_watcher_failed: None,
+            scene_changed_on_disk: None,
+            deferred_action: None,
+            remote_server: None,
+            model_reloads: Vec::new(),
+            asset_validation_errors: Vec::new(),
+            play_validation_issues: Vec::new(),
+            skip_play_validation: false,
+            last_scene_write: None,
+            saved_undo_len: 0,
+            shortcuts: shortcuts::default_shortcuts(),
+            show_shortcuts: false,
+            shortcut_search: String::new(),
             show_about: false,
+            show_project_settings: false,
             nerd_stats: NerdStats::default(),
+            scene_stats: SceneStats::default(),
+            script_timings: Vec::new(),
+            script_timings_refreshed: None,
             component_registry,
+            panels: Vec::new(),
+            open_panels: HashSet::new(),
         })
     }
 
+    /// Refreshes [`Editor::script_timings`] with the 10 slowest scripts of the last update, at most
+    /// once a second so the list can be read.
+    fn refresh_script_timings(&mut self) {
+        if self
+            .script_timings_refreshed
+            .is_some_and(|refreshed| refreshed.elapsed() < Duration::from_secs(1))
+        {
+            return;
+        }
+        self.script_timings_refreshed = Some(Instant::now());
+
+        let mut timings: Vec<(String, Duration)> = self
+            .script_manager
+            .entity_timings()
+            .iter()
+            .map(|(entity, time)| {
+                let label = self
+                    .world
+                    .get::<&Label>(*entity)
+                    .map(|label| label.to_string())
+                    .unwrap_or_else(|_| format!("Entity {}", entity.id()));
+                (label, *time)
+            })
+            .collect();
+        timings.sort_by(|a, b| b.1.cmp(&a.1));
+        timings.truncate(10);
+        self.script_timings = timings;
+    }
+
+    /// Adds a game specific panel to the Window menu, where it is listed as "Open <name>".
+    ///
+    /// Panels should be registered before the app is run with [`dropbear_engine::run_app!`].
+    pub fn register_panel<P: EditorPanel>(&mut self, panel: P, name: &'static str) {
+        if self.panels.iter().any(|(existing, _)| existing == name) {
+            log::warn!(
+                "A panel named \"{}\" is already registered, replacing it",
+                name
+            );
+            self.panels.retain(|(existing, _)| existing != name);
+        }
+        self.panels.push((name.to_string(), Box::new(panel)));
+    }
+
+    /// Shows every registered panel that is open.
+    fn show_panels(&mut self, ctx: &Context) {
+        for (name, panel) in &mut self.panels {
+            if self.open_panels.contains(name) {
+                panel.ui(ctx, &mut self.world, &mut self.signal);
+            }
+        }
+    }
+
     fn double_key_pressed(&mut self, key: KeyCode) -> bool {
         let now = Instant::now();
 
@@ -349,11 +588,13 @@ impl Editor {
             scene.scene_name
         );
 
+        // entities loaded additively belong to their own scene, so they are not saved into this one
         let labels = self
             .world
-            .query::<&Label>()
+            .query::<(&Label, Option<&SceneOrigin>)>()
             .iter()
-            .map(|(e, l)| (e, l.clone()))
+            .filter(|(_, (_, origin))| origin.is_none_or(|origin| origin.0 == target_scene_name))
+            .map(|(e, (l, _))| (e, l.clone()))
             .collect::<Vec<_>>();
 
         for (id, label) in labels {
@@ -398,7 +639,7 @@ impl Editor {
         Ok(())
     }
 
-    fn persist_active_scene_to_disk(&self) -> anyhow::Result<()> {
+    fn persist_active_scene_to_disk(&mut self) -> anyhow::Result<()> {
         let target_scene_name = self.current_scene_name.clone().or_else(|| {
             let scenes = SCENES.read();
             scenes.first().map(|scene| scene.scene_name.clone())
@@ -430,12 +671,14 @@ impl Editor {
         };
 
         scene_clone.write_to(&project_path)?;
+        self.last_scene_write = Some(Instant::now());
         Ok(())
     }
 
     pub fn save_project_config(&mut self) -> anyhow::Result<()> {
         self.save_current_scene()?;
         self.persist_active_scene_to_disk()?;
+        self.saved_undo_len = self.undo_stack.len();
 
         {
             let mut config = PROJECT.write();
@@ -443,6 +686,13 @@ impl Editor {
             config.dock_layout = Some(dock_state);
         }
 
+        if !matches!(self.editor_state, EditorState::Playing)
+            && let Some(camera_state) = self.editor_camera_state()
+        {
+            let mut config = PROJECT.write();
+            config.editor_camera = Some(camera_state);
+        }
+
         {
             let mut config = PROJECT.write();
             config.write_to_all()?;
@@ -451,15 +701,39 @@ impl Editor {
         Ok(())
     }
 
+    /// Captures the debug camera of the current world, if there is one.
+    fn editor_camera_state(&self) -> Option<EditorCameraState> {
+        self.world
+            .query::<(&Camera, &CameraComponent)>()
+            .iter()
+            .find(|(_, (_, component))| matches!(component.camera_type, CameraType::Debug))
+            .map(|(_, (camera, component))| EditorCameraState::capture(camera, component))
+    }
+
     /// The window when loading a project or a scene or anything that uses [`WorldLoadingStatus`]
     fn show_project_loading_window(&mut self, ctx: &egui::Context) {
         if let Some(ref mut rx) = self.progress_tx {
             match rx.try_recv() {
                 Ok(status) => match status {
-                    WorldLoadingStatus::LoadingEntity { index, name, total } => {
-                        log::debug!("Loading entity: {} ({}/{})", name, index + 1, total);
-                        self.current_state =
-                            WorldLoadingStatus::LoadingEntity { index, name, total };
+                    WorldLoadingStatus::Progress {
+                        current,
+                        total,
+                        phase,
+                        current_name,
+                    } => {
+                        log::debug!(
+                            "Loading {}: {} ({}/{})",
+                            phase,
+                            current_name,
+                            current,
+                            total
+                        );
+                        self.current_state = WorldLoadingStatus::Progress {
+                            current,
+                            total,
+                            phase,
+                            current_name,
+                        };
                     }
                     WorldLoadingStatus::Completed => {
                         log::debug!(
@@ -474,6 +748,12 @@ impl Editor {
                     WorldLoadingStatus::Idle => {
                         log::debug!("Project loading is idle");
                     }
+                    WorldLoadingStatus::Cancelled => {
+                        log::debug!("Received WorldLoadingStatus::Cancelled");
+                        self.current_state = WorldLoadingStatus::Cancelled;
+                        self.progress_tx = None;
+                        return;
+                    }
                 },
                 Err(_) => {
                     // log::debug!("Unable to receive the progress: {}", e);
@@ -487,7 +767,7 @@ impl Editor {
             .collapsible(false)
             .resizable(false)
             .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
-            .fixed_size([300.0, 100.0])
+            .fixed_size([300.0, 130.0])
             .show(ctx, |ui| {
                 ui.vertical_centered(|ui| {
                     ui.add_space(10.0);
@@ -495,18 +775,28 @@ impl Editor {
                         ui.spinner();
                         ui.label("Loading...");
                     });
-                    // ui.add_space(5.0);
-                    // ui.add(egui::ProgressBar::new(progress).text(format!("{:.0}%", progress * 100.0)));
+                    let progress = self.current_state.progress();
+                    ui.add_space(5.0);
+                    ui.add(
+                        egui::ProgressBar::new(progress).text(format!("{:.0}%", progress * 100.0)),
+                    );
                     match &self.current_state {
                         WorldLoadingStatus::Idle => {
                             ui.label("Initialising...");
                         }
-                        WorldLoadingStatus::LoadingEntity { name, .. } => {
-                            ui.label(format!("Loading entity: {}", name));
+                        WorldLoadingStatus::Progress {
+                            phase,
+                            current_name,
+                            ..
+                        } => {
+                            ui.label(format!("Loading {}: {}", phase, current_name));
                         }
                         WorldLoadingStatus::Completed => {
                             ui.label("Done!");
                         }
+                        WorldLoadingStatus::Cancelled => {
+                            ui.label("Cancelled");
+                        }
                     }
                 });
             });
@@ -563,6 +853,7 @@ impl Editor {
                         graphics,
                         Some(component_registry.as_ref()),
                         sender.clone(),
+                        None,
                     )
                     .await?;
                 let mut a_c = active_camera.lock();
@@ -605,6 +896,17 @@ impl Editor {
             }
         }
 
+        let editor_camera = { PROJECT.read().editor_camera };
+        if let Some(camera_state) = editor_camera {
+            for (_, (camera, component)) in world.query_mut::<(&mut Camera, &mut CameraComponent)>()
+            {
+                if matches!(component.camera_type, CameraType::Debug) {
+                    camera_state.apply(camera, component);
+                }
+            }
+            log::debug!("Restored editor camera to {}", camera_state.eye);
+        }
+
         if let Some(ref s) = sender.clone() {
             let _ = s.send(WorldLoadingStatus::Completed);
         }
@@ -617,11 +919,18 @@ impl Editor {
     }
 
     fn queue_scene_load_by_name(&mut self, scene_name: &str) -> anyhow::Result<()> {
+        self.queue_scene_load(scene_name, true)
+    }
+
+    /// Stages a scene to be loaded from disk, saving the active scene first if `persist_current`
+    /// is true.
+    fn queue_scene_load(&mut self, scene_name: &str, persist_current: bool) -> anyhow::Result<()> {
         if scene_name.trim().is_empty() {
             return Err(anyhow::anyhow!("Scene name cannot be empty"));
         }
 
-        let should_persist_current = self.current_scene_name.is_some()
+        let should_persist_current = persist_current
+            && self.current_scene_name.is_some()
             && self.is_world_loaded.is_fully_loaded()
             && self.world.len() > 0
             && {
@@ -666,8 +975,12 @@ impl Editor {
         }
 
         self.light_spawn_queue.clear();
+        for (_, handle) in self.model_reloads.drain(..) {
+            graphics.shared.future_queue.cancel(&handle);
+        }
         self.progress_tx = None;
         self.world_receiver = None;
+        self.loading_additive_scene = None;
         self.current_state = WorldLoadingStatus::Idle;
 
         self.world.clear();
@@ -676,9 +989,22 @@ impl Editor {
         self.active_camera.lock().take();
 
         self.render_pipeline = None;
+        self.custom_pipelines.clear();
         self.outline_pipeline = None;
         self.texture_id = None;
         self.light_manager = LightManager::new();
+        self.particle_system = None;
+        self.morph_system = None;
+        self.instance_buffers.clear();
+        self.morphed_instance_buffers.clear();
+        self.sprite_pipeline = None;
+        self.text_renderer = TextRenderer::new();
+        self.texture_streamer = None;
+        self.ssao = None;
+        self.depth_of_field = None;
+        self.tone_mapping = None;
+        self.sub_viewports = Default::default();
+        self.saved_undo_len = self.undo_stack.len();
 
         {
             let mut cache = MODEL_CACHE.lock();
@@ -686,6 +1012,434 @@ impl Editor {
         }
     }
 
+    /// Whether anything has been changed since the active scene was last saved or loaded.
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.undo_stack.len() != self.saved_undo_len
+    }
+
+    /// Discards the in-memory copy of the active scene and loads it again from disk.
+    fn reload_scene_from_disk(&mut self) -> anyhow::Result<()> {
+        let Some(scene_name) = self.current_scene_name.clone() else {
+            anyhow::bail!("No scene is open");
+        };
+
+        // the scene in memory isn't saved, as that would overwrite the changed file
+        self.queue_scene_load(&scene_name, false)?;
+        self.scene_changed_on_disk = None;
+        info!("Reloaded scene '{}' from disk", scene_name);
+        Ok(())
+    }
+
+    /// Starts watching the project's scenes, and signals if the active scene was changed by
+    /// something other than the editor.
+    fn poll_scene_watcher(&mut self) {
+        let project_path = { PROJECT.read().project_path.clone() };
+        if project_path.as_os_str().is_empty() {
+            return;
+        }
+
+        if self
+            .scene_watcher
+            .as_ref()
+            .is_none_or(|w| w.project_path() != project_path)
+        {
+            if self.scene_watcher_failed.as_ref() == Some(&project_path) {
+                return;
+            }
+
+            self.scene_watcher = match SceneWatcher::new(&project_path) {
+                Ok(watcher) => Some(watcher),
+                Err(e) => {
+                    warn!("Unable to watch scenes for changes: {}", e);
+                    self.scene_watcher_failed = Some(project_path);
+                    None
+                }
+            };
+        }
+
+        let Some(watcher) = &self.scene_watcher else {
+            return;
+        };
+
+        let recently_written = self
+            .last_scene_write
+            .is_some_and(|t| t.elapsed() < Duration::from_secs(2));
+
+        for path in watcher.poll() {
+            let is_active_scene = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .is_some_and(|stem| self.current_scene_name.as_deref() == Some(stem));
+            if !is_active_scene || recently_written {
+                continue;
+            }
+
+            if matches!(self.signal, Signal::None) {
+                self.signal = Signal::SceneFileChanged(path);
+            } else {
+                self.scene_changed_on_disk = Some(path);
+            }
+        }
+    }
+
+    /// Starts watching the models in the registry, reloads the ones that changed on disk and
+    /// gives every renderer using them the new model once it is loaded.
+    fn poll_model_watcher(&mut self, graphics: Arc<SharedGraphicsContext>) {
+        if let Err(e) = ASSET_REGISTRY.watch_for_changes() {
+            log_once::warn_once!("Unable to watch models for changes: {}", e);
+            return;
+        }
+
+        for handle in ASSET_REGISTRY.changed_models() {
+            self.queue_model_reload(graphics.clone(), handle);
+        }
+
+        let mut completed = Vec::new();
+        for (i, (handle, future)) in self.model_reloads.iter().enumerate() {
+            let Some(result) = graphics
+                .future_queue
+                .exchange_owned_as::<Result<(), String>>(future)
+            else {
+                continue;
+            };
+            completed.push(i);
+
+            match result {
+                Ok(()) => {
+                    for (_, renderer) in self.world.query_mut::<&mut MeshRenderer>() {
+                        if renderer.asset_handle() == *handle
+                            && let Err(e) = renderer.refresh_model(graphics.clone())
+                        {
+                            warn!("Unable to refresh renderer: {}", e);
+                        }
+                    }
+                    success!("Model reloaded");
+                }
+                Err(e) => {
+                    warn!("Unable to reload model: {}", e);
+                }
+            }
+        }
+
+        for &i in completed.iter().rev() {
+            self.model_reloads.remove(i);
+        }
+    }
+
+    /// Starts reloading a model on the future queue, which [`Editor::poll_model_watcher`] hands
+    /// to the renderers using it once it is done. Does nothing if it is already being reloaded.
+    pub(crate) fn queue_model_reload(
+        &mut self,
+        graphics: Arc<SharedGraphicsContext>,
+        handle: AssetHandle,
+    ) {
+        if self
+            .model_reloads
+            .iter()
+            .any(|(pending, _)| *pending == handle)
+        {
+            return;
+        }
+
+        let reload_graphics = graphics.clone();
+        let future = graphics.future_queue.push(async move {
+            ASSET_REGISTRY
+                .reload_model(reload_graphics, handle)
+                .await
+                .map_err(|e| e.to_string())
+        });
+        self.model_reloads.push((handle, future));
+    }
+
+    /// Starts creating the project queued by the new project window, and switches to it once it
+    /// has been created.
+    fn poll_project_creation(&mut self, future_queue: Arc<FutureQueue>) {
+        if let Some((name, path, template)) = self.queued_project_creation.take() {
+            log::debug!("Creating project '{}' at {}", name, path.display());
+            self.project_creation = Some(crate::utils::start_project_creation(
+                &future_queue,
+                name,
+                path,
+                template,
+            ));
+        }
+
+        if let Some(creation) = &mut self.project_creation
+            && creation.receive_progress()
+        {
+            success!("Created project '{}'", PROJECT.read().project_name);
+            self.project_creation = None;
+            self.show_new_project = false;
+            self.pending_scene_switch = true;
+        }
+    }
+
+    /// Shows the progress of the project being created, or why creating it failed.
+    fn show_project_creation_window(&mut self, ctx: &Context) {
+        let Some(creation) = &self.project_creation else {
+            return;
+        };
+
+        let mut close = false;
+        let modal = egui::Modal::new(egui::Id::new("project_creation")).show(ctx, |ui| {
+            ui.set_width(300.0);
+            if let Some(error) = &creation.error {
+                ui.heading("Unable to create the project");
+                ui.add_space(4.0);
+                ui.label(egui::RichText::new(error).color(ui.visuals().error_fg_color));
+                ui.add_space(8.0);
+                if ui.button("Close").clicked() {
+                    close = true;
+                }
+            } else {
+                ui.heading("Creating Project");
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(&creation.message);
+                });
+                ui.add(
+                    egui::ProgressBar::new(creation.progress)
+                        .text(format!("{:.0}%", creation.progress * 100.0)),
+                );
+            }
+        });
+
+        // the modal can only be dismissed once creation has stopped
+        if creation.error.is_some() && (close || modal.should_close()) {
+            self.project_creation = None;
+        }
+    }
+
+    /// Starts the server for external tools, and signals for every asset they reported as changed.
+    fn poll_remote_server(&mut self, future_queue: Arc<FutureQueue>) {
+        let (project_path, port) = {
+            let project = PROJECT.read();
+            (
+                project.project_path.clone(),
+                project
+                    .editor_settings
+                    .remote_port
+                    .unwrap_or(DEFAULT_REMOTE_PORT),
+            )
+        };
+        if project_path.as_os_str().is_empty() {
+            return;
+        }
+
+        if self.remote_server.as_ref().is_none_or(|s| s.port() != port) {
+            self.remote_server = Some(RemoteServer::start(future_queue, port));
+        }
+
+        if !matches!(self.signal, Signal::None) {
+            // picked up on a later frame, once the current signal is handled
+            return;
+        }
+
+        let Some(path) = self
+            .remote_server
+            .as_ref()
+            .and_then(|s| s.next_changed_asset())
+        else {
+            return;
+        };
+
+        let path = if path.is_relative() {
+            project_path.join(path)
+        } else {
+            path
+        };
+        self.signal = if path.extension().is_some_and(|ext| ext == "kt") {
+            Signal::ReloadScript(path)
+        } else {
+            Signal::HotReloadAsset(path)
+        };
+    }
+
+    /// The notification bar shown when the active scene was changed on disk.
+    fn show_scene_changed_bar(&mut self, ctx: &Context) {
+        let Some(path) = self.scene_changed_on_disk.clone() else {
+            return;
+        };
+
+        let mut reload = false;
+        egui::TopBottomPanel::top("scene_changed_bar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "Scene {} changed on disk – reload?",
+                    path.file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default()
+                ));
+
+                let editing = matches!(self.editor_state, EditorState::Editing);
+                if ui
+                    .add_enabled(editing, egui::Button::new("Reload"))
+                    .on_disabled_hover_text("Stop playing to reload the scene")
+                    .clicked()
+                {
+                    if self.has_unsaved_changes() {
+                        self.deferred_action = Some(DeferredAction::ReloadScene);
+                    } else {
+                        reload = true;
+                    }
+                }
+
+                if ui.button("Dismiss").clicked() {
+                    self.scene_changed_on_disk = None;
+                }
+            });
+        });
+
+        if reload {
+            self.run_deferred_action(DeferredAction::ReloadScene);
+        }
+    }
+
+    /// Asks the user to confirm the deferred action, and runs it once they have.
+    fn show_deferred_action_confirmation(&mut self, ctx: &Context) {
+        let Some(action) = &self.deferred_action else {
+            return;
+        };
+
+        let (title, message, confirm_text) = action.confirmation();
+        match dialogs::confirm_destructive_action(ctx, title, &message, confirm_text) {
+            ConfirmState::Pending => {}
+            ConfirmState::Confirmed => {
+                if let Some(action) = self.deferred_action.take() {
+                    self.run_deferred_action(action);
+                }
+            }
+            ConfirmState::Cancelled => {
+                log::debug!("Cancelled {:?}", self.deferred_action);
+                self.deferred_action = None;
+            }
+        }
+    }
+
+    fn run_deferred_action(&mut self, action: DeferredAction) {
+        match action {
+            DeferredAction::ReloadScene => {
+                if let Err(e) = self.reload_scene_from_disk() {
+                    fatal!("Unable to reload scene: {}", e);
+                }
+            }
+            DeferredAction::ImportGltfScene(path) => {
+                let result = crate::build::import_gltf_scene(&path).and_then(|scene| {
+                    let project_path = PROJECT.read().project_path.clone();
+                    scene.write_to(&project_path)?;
+                    self.queue_scene_load_by_name(&scene.scene_name)?;
+                    Ok(scene.scene_name)
+                });
+
+                match result {
+                    Ok(scene_name) => {
+                        success!("Imported {} as scene '{}'", path.display(), scene_name)
+                    }
+                    Err(e) => fatal!("Failed to import glTF scene: {}", e),
+                }
+            }
+            DeferredAction::CreateProject {
+                name,
+                path,
+                template,
+            } => {
+                self.queued_project_creation = Some((name, path, template));
+            }
+        }
+    }
+
+    /// Checks the project's scenes for references to files that no longer exist.
+    pub(crate) fn validate_project_assets(&mut self) {
+        let project_root = PROJECT.read().project_path.clone();
+        self.asset_validation_errors = ProjectConfig::validate_asset_references(&project_root);
+        for error in &self.asset_validation_errors {
+            log::warn!("{}", error);
+        }
+    }
+
+    /// Lists the missing assets found by [`Editor::validate_project_assets`].
+    fn show_asset_validation_window(&mut self, ctx: &Context) {
+        if self.asset_validation_errors.is_empty() {
+            return;
+        }
+
+        let mut open = true;
+        let mut revalidate = false;
+        egui::Window::new("Missing Assets")
+            .collapsible(false)
+            .resizable(true)
+            .default_width(520.0)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{} asset reference(s) point to files that don't exist. These entities \
+                     will fail to load until the files are restored or the references are changed.",
+                    self.asset_validation_errors.len()
+                ));
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("asset_validation_errors")
+                            .num_columns(3)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong("Scene");
+                                ui.strong("Entity");
+                                ui.strong("Missing File");
+                                ui.end_row();
+
+                                for error in &self.asset_validation_errors {
+                                    ui.label(&error.scene_name);
+                                    ui.label(&error.entity_label);
+                                    ui.monospace(error.missing_path.display().to_string());
+                                    ui.end_row();
+                                }
+                            });
+                    });
+
+                ui.separator();
+                if ui.button("Check Again").clicked() {
+                    revalidate = true;
+                }
+            });
+
+        if !open {
+            self.asset_validation_errors.clear();
+        } else if revalidate {
+            self.validate_project_assets();
+        }
+    }
+
+    /// Shows what the last build from the export menu is made of, as a treemap of asset sizes.
+    fn show_build_output_window(&mut self, ctx: &Context) {
+        let Some(graph) = &self.build_output_graph else {
+            return;
+        };
+
+        let mut open = true;
+        egui::Window::new("Build Output")
+            .collapsible(false)
+            .resizable(true)
+            .default_width(640.0)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{} in total, across {} scene(s). Hover over an asset to see its size.",
+                    format_bytes(graph.root.total_size()),
+                    graph.root.children.len()
+                ));
+                ui.separator();
+                treemap::show_treemap(ui, &graph.root, 360.0);
+            });
+
+        if !open {
+            self.build_output_graph = None;
+        }
+    }
+
     fn start_async_scene_load(&mut self, scene: SceneConfig, graphics: &mut RenderContext) {
         self.cleanup_scene_resources(graphics);
 
@@ -705,7 +1459,8 @@ impl Editor {
         let scene_name = scene.scene_name.clone();
         let component_registry_clone = self.component_registry.clone();
 
-        let handle = graphics.shared.future_queue.push(async move {
+        let future_queue = graphics.shared.future_queue.clone();
+        let handle = future_queue.push_cancellable(move |token| async move {
             let mut temp_world = World::new();
 
             let load_result = scene
@@ -714,9 +1469,17 @@ impl Editor {
                     graphics_shared.clone(),
                     Some(component_registry_clone.as_ref()),
                     Some(progress_sender.clone()),
+                    Some(&token),
                 )
                 .await;
 
+            if token.is_cancelled() {
+                // dropping the world sender closes the channel, so the stale world is discarded
+                log::info!("Loading of scene '{}' was cancelled", scene_name);
+                let _ = progress_sender.send(WorldLoadingStatus::Cancelled);
+                return;
+            }
+
             match load_result {
                 Ok(active_entity) => {
                     let mut camera_lock = active_camera.lock();
@@ -737,6 +1500,79 @@ impl Editor {
         self.world_load_handle = Some(handle);
     }
 
+    /// Starts loading the next scene queued by [`SceneCommand::LoadAdditively`] into the world,
+    /// keeping the entities that are already there.
+    ///
+    /// The world is moved into the loading task and sent back through the world receiver once the
+    /// scene has been added, the same way a full scene load delivers its world.
+    fn load_pending_additive_scene(&mut self, graphics: &mut RenderContext) {
+        if self.pending_additive_scenes.is_empty() || self.world_receiver.is_some() {
+            return;
+        }
+
+        let scene_name = self.pending_additive_scenes.remove(0);
+        let scene = match states::load_scene(&scene_name) {
+            Ok(scene) => scene,
+            Err(e) => {
+                warn!("Unable to load scene '{}' additively: {}", scene_name, e);
+                return;
+            }
+        };
+
+        let (progress_sender, progress_receiver) =
+            tokio::sync::mpsc::unbounded_channel::<WorldLoadingStatus>();
+        self.progress_tx = Some(progress_receiver);
+        self.current_state = WorldLoadingStatus::Idle;
+
+        let (world_sender, world_receiver) = oneshot::channel();
+        self.world_receiver = Some(world_receiver);
+
+        self.loading_additive_scene = Some(scene_name.clone());
+
+        let mut world = std::mem::take(&mut *self.world);
+        let graphics_shared = graphics.shared.clone();
+        let component_registry = self.component_registry.clone();
+
+        graphics.shared.future_queue.push(async move {
+            if let Err(e) = scene
+                .load_additive(
+                    &mut world,
+                    graphics_shared,
+                    Some(component_registry.as_ref()),
+                    Some(progress_sender.clone()),
+                )
+                .await
+            {
+                log::error!("Failed to load scene '{}' additively: {}", scene_name, e);
+            }
+
+            let _ = progress_sender.send(WorldLoadingStatus::Completed);
+
+            if world_sender.send(world).is_err() {
+                log::error!(
+                    "Failed to deliver world after loading scene '{}'",
+                    scene_name
+                );
+            }
+        });
+    }
+
+    /// Tells the scripts that a scene was loaded or is about to be unloaded, which only happens
+    /// while playing.
+    fn broadcast_scene_event(&mut self, event: SceneEvent) {
+        if !matches!(self.editor_state, EditorState::Playing) {
+            return;
+        }
+
+        if let Err(e) = self
+            .script_manager
+            .broadcast_scene_event(event, &self.world)
+        {
+            fatal!("Failed to broadcast scene event to scripts: {}", e);
+            self.signal = Signal::StopPlaying;
+        }
+    }
+
     fn create_new_scene(&mut self, name: &str) -> anyhow::Result<()> {
         let trimmed_name = name.trim();
         if trimmed_name.is_empty() {
@@ -855,6 +1691,19 @@ impl Editor {
                         }
                     }
 
+                    if ui.button("Import glTF Scene").clicked() {
+                        let dialog = FileDialog::new().add_filter("glTF", &["glb", "gltf"]);
+
+                        if let Some(path) = dialog.pick_file() {
+                            let action = DeferredAction::ImportGltfScene(path);
+                            if self.has_unsaved_changes() {
+                                self.deferred_action = Some(action);
+                            } else {
+                                self.run_deferred_action(action);
+                            }
+                        }
+                    }
+
                     if ui.button("Save").clicked() {
                         match self.save_project_config() {
                             Ok(_) => {}
@@ -873,7 +1722,9 @@ impl Editor {
                             Err(e) => warn!("Unable to open project: {}", e),
                         }
                     }
-                    if ui.button("Project Settings").clicked() {};
+                    if ui.button("Project Settings").clicked() {
+                        self.show_project_settings = true;
+                    };
                     if matches!(self.editor_state, EditorState::Playing) {
                         if ui.button("Stop").clicked() {
                             self.signal = Signal::StopPlaying;
@@ -887,7 +1738,13 @@ impl Editor {
                             {
                                 let proj = PROJECT.read();
                                 match build(proj.project_path.join(format!("{}.eucp", proj.project_name.clone())).clone()) {
-                                    Ok(thingy) => success!("Project output at {}", thingy.display()),
+                                    Ok(thingy) => {
+                                        success!("Project output at {}", thingy.display());
+                                        match generate_dependency_graph(&thingy.join("data.eupak")) {
+                                            Ok(graph) => self.build_output_graph = Some(graph),
+                                            Err(e) => warn!("Unable to work out the size of the build output: {}", e),
+                                        }
+                                    }
                                     Err(e) => {
                                         fatal!("Unable to build project [{}]: {}", proj.project_path.clone().display(), e);
                                     },
@@ -895,6 +1752,19 @@ impl Editor {
                             }
                         }
                         ui.label("Package"); // todo: create a window for label
+                        ui.separator();
+                        if ui.button("Export Scene as glTF").clicked() {
+                            let dialog = FileDialog::new()
+                                .add_filter("glTF Binary", &["glb"])
+                                .set_file_name(format!("{}.glb", self.current_scene_name.clone().unwrap_or_else(|| "scene".to_string())));
+
+                            if let Some(path) = dialog.save_file() {
+                                match export::export_scene_glb(&self.world, &path) {
+                                    Ok(()) => success!("Exported scene to {}", path.display()),
+                                    Err(e) => fatal!("Unable to export scene: {}", e),
+                                }
+                            }
+                        }
                     });
                     ui.separator();
                     if ui.button("Quit").clicked() {
@@ -928,6 +1798,7 @@ impl Editor {
                                     let serialized_renderer = SerializedMeshRenderer {
                                         handle: renderer.handle().path.clone(),
                                         material_override: renderer.material_overrides().to_vec(),
+                                        emissive_overrides: renderer.emissive_overrides.clone(),
                                     };
                                     components.push(Box::new(serialized_renderer));
 
@@ -953,6 +1824,14 @@ impl Editor {
 
                     }
 
+                    if ui.button("Duplicate").clicked() {
+                        if let Some(entity) = self.selected_entity {
+                            self.signal = Signal::Duplicate(entity);
+                        } else {
+                            warn!("Unable to duplicate entity: None selected");
+                        }
+                    }
+
                     if ui.button("Paste").clicked() {
                         match &self.signal {
                             Signal::Copy(entity) => {
@@ -967,7 +1846,41 @@ impl Editor {
                     if ui.button("Undo").clicked() {
                         self.signal = Signal::Undo;
                     }
-                    ui.label("Redo");
+                    if ui
+                        .add_enabled(self.can_redo(), egui::Button::new("Redo"))
+                        .clicked()
+                    {
+                        self.signal = Signal::Redo;
+                    }
+
+                    ui.menu_button("Undo History", |ui| {
+                        if self.undo_stack.is_empty() {
+                            ui.label(
+                                egui::RichText::new("Nothing to undo")
+                                    .color(ui.visuals().weak_text_color()),
+                            );
+                        }
+                        for (i, action) in self.undo_stack.iter().enumerate().rev() {
+                            if let UndoableAction::Batch(actions) = action {
+                                CollapsingHeader::new(action.summary())
+                                    .id_salt(("undo_history", i))
+                                    .default_open(false)
+                                    .show(ui, |ui| {
+                                        for inner in actions {
+                                            ui.label(inner.summary());
+                                        }
+                                    });
+                            } else {
+                                ui.label(action.summary());
+                            }
+                        }
+                    });
+
+                    ui.separator();
+
+                    if ui.button("Bake Probes").clicked() {
+                        self.signal = Signal::BakeProbes(None);
+                    }
                 });
 
                 ui.menu_button("Window", |ui_window| {
@@ -988,6 +1901,15 @@ impl Editor {
                     if ui_window.button("Open Error Console").clicked() {
                         self.dock_state.push_to_focused_leaf(EditorTab::ErrorConsole);
                     }
+                    if ui_window.button("Open Camera Path Editor").clicked() {
+                        self.dock_state.push_to_focused_leaf(EditorTab::CameraPath);
+                    }
+                    if ui_window.button("Open Undo History").clicked() {
+                        self.dock_state.push_to_focused_leaf(EditorTab::UndoHistory);
+                    }
+                    if ui_window.button("Open Statistics").clicked() {
+                        self.dock_state.push_to_focused_leaf(EditorTab::Statistics);
+                    }
                     if self.plugin_registry.plugins.len() == 0 {
                         ui_window.label(
                             egui::RichText::new("No plugins ")
@@ -999,7 +1921,66 @@ impl Editor {
                             self.dock_state.push_to_focused_leaf(EditorTab::Plugin(i));
                         }
                     }
-                });
+                    if !self.panels.is_empty() {
+                        ui_window.separator();
+                    }
+                    for (name, _) in &self.panels {
+                        let open = self.open_panels.contains(name);
+                        if ui_window.selectable_label(open, format!("Open {}", name)).clicked() {
+                            if open {
+                                self.open_panels.remove(name);
+                            } else {
+                                self.open_panels.insert(name.clone());
+                            }
+                        }
+                    }
+                });
+
+                ui.menu_button("View", |ui| {
+                    ui.menu_button("UI Scale", |ui| {
+                        let mut ui_scale = PROJECT.read().editor_settings.ui_scale;
+                        let before = ui_scale;
+                        for (name, preset) in [
+                            ("50%", 0.5),
+                            ("75%", 0.75),
+                            ("100%", 1.0),
+                            ("150%", 1.5),
+                            ("200%", 2.0),
+                        ] {
+                            ui.radio_value(&mut ui_scale, preset, name);
+                        }
+                        ui.separator();
+                        let slider = ui.add(
+                            egui::Slider::new(&mut ui_scale, dropbear_engine::UI_SCALE_RANGE)
+                                .step_by(0.05)
+                                .custom_formatter(|v, _| format!("{:.0}%", v * 100.0)),
+                        );
+
+                        if ui_scale != before {
+                            PROJECT.write().editor_settings.ui_scale = ui_scale;
+                        }
+                        // rescaling mid drag would move the slider out from under the pointer
+                        if !slider.dragged() && ui_scale != dropbear_engine::ui_scale() {
+                            dropbear_engine::set_ui_scale(ui_scale);
+                        }
+                    });
+
+                    ui.menu_button("Post Processing", |ui| {
+                        let mut operator = PROJECT.read().editor_settings.tone_mapping;
+                        egui::ComboBox::from_label("Tone Mapping")
+                            .selected_text(operator.to_string())
+                            .show_ui(ui, |ui| {
+                                for option in ToneMappingOperator::ALL {
+                                    ui.selectable_value(&mut operator, option, option.to_string());
+                                }
+                            });
+
+                        if operator != tone_mapping::tone_mapping() {
+                            PROJECT.write().editor_settings.tone_mapping = operator;
+                            tone_mapping::set_tone_mapping(operator);
+                        }
+                    });
+                });
 
                 ui.menu_button("Help", |ui| {
                     if ui.button("Show AppData folder").clicked() {
@@ -1016,6 +1997,10 @@ impl Editor {
                         };
                     }
 
+                    if ui.button("Keyboard Shortcuts").clicked() {
+                        self.show_shortcuts = true
+                    }
+
                     if ui.button("Nerdy Stuff").clicked() {
                         self.nerd_stats.show_window = true
                     }
@@ -1049,12 +2034,30 @@ impl Editor {
                             }
                         });
                     });
+
+                    let is_quad = matches!(self.viewport_layout, ViewportLayout::Quad);
+                    if ui
+                        .selectable_label(is_quad, "⊞")
+                        .on_hover_text("Toggle between a single view and four views")
+                        .clicked()
+                    {
+                        self.viewport_layout.toggle();
+                        log::debug!("Viewport layout set to {:?}", self.viewport_layout);
+                    }
                 });
             });
         });
 
+        self.show_scene_changed_bar(ctx);
+        self.show_asset_validation_window(ctx);
+        self.show_build_output_window(ctx);
+        self.show_panels(ctx);
+
         let editor_ptr = self as *mut Editor;
 
+        // set again below if the viewport tab is shown this frame
+        self.viewport_rect = None;
+
         egui::CentralPanel::default().show(ctx, |ui| {
             DockArea::new(&mut self.dock_state)
                 .style(Style::from_egui(ui.style().as_ref()))
@@ -1067,7 +2070,12 @@ impl Editor {
                         world: &mut self.world,
                         selected_entity: &mut self.selected_entity,
                         viewport_mode: &mut self.viewport_mode,
+                        measure_tool: &mut self.measure_tool,
+                        viewport_layout: self.viewport_layout,
+                        sub_viewports: &mut self.sub_viewports,
+                        redo_valid: undo_generation() == self.redo_anchor,
                         undo_stack: &mut self.undo_stack,
+                        redo_stack: &self.redo_stack,
                         signal: &mut self.signal,
                         active_camera: &mut self.active_camera,
                         gizmo_mode: &mut self.gizmo_mode,
@@ -1077,21 +2085,54 @@ impl Editor {
                         editor: editor_ptr,
                         build_logs: &mut self.build_logs,
                         component_registry: &self.component_registry,
+                        input_contexts: &self.input_contexts,
+                        scene_stats: &self.scene_stats,
+                        script_timings: &self.script_timings,
+                        viewport_rect: &mut self.viewport_rect,
                     },
                 );
         });
 
+        let mut run_now = None;
+        let mut deferred_action = None;
         let mut project_path = self.project_path.lock();
         crate::utils::show_new_project_window(
             ctx,
             &mut self.show_new_project,
             &mut self.project_name,
             &mut project_path,
-            |name, path| {
-                crate::utils::start_project_creation(name.to_string(), Some(path.clone()));
-                self.pending_scene_switch = true;
+            &mut self.scene_template,
+            |name, path, template| {
+                let action = DeferredAction::CreateProject {
+                    name: name.to_string(),
+                    path: path.clone(),
+                    template,
+                };
+                // the folder could already hold another project
+                if dialogs::folder_has_files(path) {
+                    deferred_action = Some(action);
+                } else {
+                    run_now = Some(action);
+                }
             },
         );
+        drop(project_path);
+        if let Some(action) = run_now {
+            self.run_deferred_action(action);
+        }
+        if let Some(action) = deferred_action {
+            self.deferred_action = Some(action);
+        }
+        self.show_deferred_action_confirmation(ctx);
+        self.show_play_validation(ctx);
+        self.show_project_creation_window(ctx);
+
+        shortcuts::show_cheat_sheet(
+            ctx,
+            &mut self.show_shortcuts,
+            &mut self.shortcut_search,
+            &self.shortcuts,
+        );
 
         egui::Window::new("About")
             .resizable(false)
@@ -1134,6 +2175,80 @@ impl Editor {
                 });
             });
 
+        egui::Window::new("Project Settings")
+            .collapsible(false)
+            .open(&mut self.show_project_settings)
+            .show(ctx, |ui| {
+                CollapsingHeader::new("Layers")
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        let mut project = PROJECT.write();
+                        project
+                            .layer_names
+                            .resize(NAMED_LAYER_COUNT as usize, String::new());
+
+                        egui::Grid::new("layer_names").num_columns(2).show(ui, |ui| {
+                            for (i, name) in project.layer_names.iter_mut().enumerate() {
+                                ui.label(format!("Layer {}", i));
+                                ui.add(
+                                    egui::TextEdit::singleline(name)
+                                        .hint_text(format!("Layer {}", i)),
+                                );
+                                ui.end_row();
+                            }
+                        });
+                    });
+
+                CollapsingHeader::new("Editor")
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        let monitors: Vec<String> = self
+                            .window
+                            .as_ref()
+                            .map(|window| {
+                                window
+                                    .available_monitors()
+                                    .enumerate()
+                                    .map(|(i, monitor)| match monitor.name() {
+                                        Some(name) => format!("{}: {}", i, name),
+                                        None => format!("Monitor {}", i),
+                                    })
+                                    .collect()
+                            })
+                            .unwrap_or_default();
+
+                        let mut project = PROJECT.write();
+                        let preferred = &mut project.editor_settings.preferred_monitor;
+                        let selected_text = match *preferred {
+                            Some(i) => monitors
+                                .get(i)
+                                .cloned()
+                                .unwrap_or_else(|| format!("Monitor {} (disconnected)", i)),
+                            None => "Primary Monitor".to_string(),
+                        };
+
+                        let mut changed = false;
+                        ui.horizontal(|ui| {
+                            ui.label("Fullscreen Monitor");
+                            egui::ComboBox::from_id_salt("fullscreen_monitor")
+                                .selected_text(selected_text)
+                                .show_ui(ui, |ui| {
+                                    changed |= ui
+                                        .selectable_value(preferred, None, "Primary Monitor")
+                                        .changed();
+                                    for (i, name) in monitors.iter().enumerate() {
+                                        changed |=
+                                            ui.selectable_value(preferred, Some(i), name).changed();
+                                    }
+                                });
+                        });
+
+                        if changed {
+                            dropbear_engine::set_preferred_monitor(*preferred);
+                        }
+                    });
+            });
+
         if self.pending_scene_switch {
             self.scene_command = SceneCommand::SwitchScene("editor".to_string());
             self.pending_scene_switch = false;
@@ -1163,65 +2278,67 @@ impl Editor {
         self.open_new_scene_window = open_flag;
     }
 
-    /// Restores transform components back to its original state before PlayMode.
+    /// Restores the scene back to its original state before PlayMode.
+    ///
+    /// Entities spawned during play are despawned, and the ones scripts despawned are put back.
     pub fn restore(&mut self) -> anyhow::Result<()> {
         if let Some(window) = &self.window {
             let _ = window.set_cursor_grab(CursorGrabMode::None);
         }
 
-        if let Some(backup) = &self.play_mode_backup {
-            for (
-                entity_id,
-                original_mesh_renderer,
-                original_transform,
-                original_properties,
-                original_script,
-            ) in &backup.entities
-            {
-                if let Ok(mut mesh_renderer) = self.world.get::<&mut MeshRenderer>(*entity_id) {
-                    mesh_renderer.clone_from(original_mesh_renderer);
-                    mesh_renderer.sync_asset_registry();
-                }
-
-                if let Ok(mut transform) = self.world.get::<&mut Transform>(*entity_id) {
-                    *transform = *original_transform;
-                }
+        let despawned = self.script_manager.take_despawned_entities();
 
-                if let Ok(mut properties) = self.world.get::<&mut ModelProperties>(*entity_id) {
-                    properties.clone_from(original_properties);
-                }
+        if let Some(backup) = self.play_mode_backup.take() {
+            let spawned: Vec<Entity> = self
+                .world
+                .iter()
+                .map(|entity_ref| entity_ref.entity())
+                .filter(|entity| !backup.alive.contains(entity))
+                .collect();
+            for entity in &spawned {
+                let _ = self.world.despawn(*entity);
+            }
 
-                let has_script = self.world.get::<&Script>(*entity_id).is_ok();
-                match (has_script, original_script) {
-                    (true, Some(original)) => {
-                        if let Ok(mut script) = self.world.get::<&mut Script>(*entity_id) {
-                            *script = original.clone();
-                        }
-                    }
-                    (true, None) => {
-                        let _ = self.world.remove_one::<Script>(*entity_id);
-                    }
-                    (false, Some(original)) => {
-                        let _ = self.world.insert_one(*entity_id, original.clone());
+            let mut respawned = 0;
+            if let Some(mut despawned) = despawned {
+                let entities: Vec<Entity> = despawned
+                    .iter()
+                    .map(|entity_ref| entity_ref.entity())
+                    .filter(|entity| backup.alive.contains(entity))
+                    .collect();
+                for entity in entities {
+                    if let Ok(taken) = despawned.take(entity) {
+                        self.world.spawn_at(entity, taken);
+                        respawned += 1;
                     }
-                    (false, None) => {}
                 }
             }
 
-            for (entity_id, original_camera, original_camera_component) in &backup.camera_data {
-                if let Ok(mut camera) = self.world.get::<&mut Camera>(*entity_id) {
-                    *camera = original_camera.clone();
+            log::debug!(
+                "Despawned {} entities spawned during play, respawned {} despawned by scripts",
+                spawned.len(),
+                respawned
+            );
+
+            for (entity_id, snapshot) in backup.entities {
+                if let Err(e) = snapshot.restore(&mut self.world, entity_id) {
+                    log::warn!("Unable to restore entity {:?}: {}", entity_id, e);
+                    continue;
                 }
 
-                if let Ok(mut camera_component) = self.world.get::<&mut CameraComponent>(*entity_id)
-                {
-                    *camera_component = original_camera_component.clone();
+                if let Ok(mut mesh_renderer) = self.world.get::<&mut MeshRenderer>(entity_id) {
+                    mesh_renderer.sync_asset_registry();
+                }
+            }
+
+            for (entity_id, snapshot) in backup.camera_data {
+                if let Err(e) = snapshot.restore(&mut self.world, entity_id) {
+                    log::warn!("Unable to restore camera {:?}: {}", entity_id, e);
                 }
             }
 
             log::info!("Restored scene from play mode backup");
 
-            self.play_mode_backup = None;
             Ok(())
         } else {
             Err(anyhow::anyhow!("No play mode backup found to restore"))
@@ -1230,43 +2347,28 @@ impl Editor {
 
     pub fn create_backup(&mut self) -> anyhow::Result<()> {
         let mut entities = Vec::new();
+        let mut camera_data = Vec::new();
+        let mut alive = HashSet::new();
 
-        for (entity_id, (mesh_renderer, transform, properties)) in self
-            .world
-            .query::<(&MeshRenderer, &Transform, &ModelProperties)>()
-            .iter()
-        {
-            let script = self
-                .world
-                .query_one::<&Script>(entity_id)
-                .ok()
-                .and_then(|mut s| s.get().cloned());
-            entities.push((
-                entity_id,
-                mesh_renderer.clone(),
-                *transform,
-                properties.clone(),
-                script,
-            ));
-        }
+        for entity_ref in self.world.iter() {
+            let entity_id = entity_ref.entity();
+            alive.insert(entity_id);
 
-        let mut camera_data = Vec::new();
+            if let Some(snapshot) = ModelSnapshot::capture(&self.world, entity_id) {
+                entities.push((entity_id, snapshot));
+            }
 
-        for (entity_id, (camera, component)) in
-            self.world.query::<(&Camera, &CameraComponent)>().iter()
-        {
-            camera_data.push((
-                entity_id,
-                camera.clone(),
-                component.clone(),
-                // follow_target.cloned(),
-            ));
+            if let Some(snapshot) = CameraSnapshot::capture(&self.world, entity_id) {
+                camera_data.push((entity_id, snapshot));
+            }
         }
 
         self.play_mode_backup = Some(PlayModeBackup {
             entities,
             camera_data,
+            alive,
         });
+        self.script_manager.keep_despawned_entities();
 
         log::info!(
             "Created play mode backup with {} entities and {} cameras",
@@ -1298,6 +2400,16 @@ impl Editor {
         }
     }
 
+    /// Views the scene through `camera`, such as from the camera inspector.
+    pub fn switch_to_camera(&mut self, camera: hecs::Entity) {
+        let Ok(label) = self.world.get::<&Camera>(camera).map(|c| c.label.clone()) else {
+            warn!("Unable to switch camera: entity is not a camera");
+            return;
+        };
+        *self.active_camera.lock() = Some(camera);
+        info!("Switched to camera '{}'", label);
+    }
+
     pub fn switch_to_player_camera(&mut self) {
         let player_camera = self
             .world
@@ -1331,6 +2443,194 @@ impl Editor {
         false
     }
 
+    /// Imports every model dropped onto the window, spawning each as a new entity at the world
+    /// origin and selecting it.
+    pub(crate) fn import_dropped_files(&mut self, graphics: Arc<SharedGraphicsContext>) {
+        if self.dropped_files.is_empty() {
+            return;
+        }
+
+        let resources_dir = PROJECT.read().project_path.join("resources");
+
+        for path in std::mem::take(&mut self.dropped_files) {
+            let label = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or("Model".to_string());
+
+            let entity = self.world.spawn((
+                Label::new(label),
+                EntityTransform::default(),
+                ModelProperties::new(),
+            ));
+            UndoableAction::push_to_undo(&mut self.undo_stack, UndoableAction::Spawn(entity));
+
+            let graphics_clone = graphics.clone();
+            let resources_dir = resources_dir.clone();
+            let future = async move {
+                let model = ASSET_REGISTRY
+                    .import_model(graphics_clone, &path, &resources_dir)
+                    .await?;
+                Ok::<MeshRenderer, anyhow::Error>(MeshRenderer::from_handle(model))
+            };
+
+            let handle = graphics.future_queue.push(Box::pin(future));
+            self.pending_components.push((entity, handle));
+            self.selected_entity = Some(entity);
+        }
+    }
+
+    /// Spawns a copy of `entity` with all of its components, moved slightly so it does not overlap
+    /// the original.
+    ///
+    /// Components in the component registry are cloned through it. The camera and light need their
+    /// GPU resources recreated, so the camera is rebuilt here while the light is added once its
+    /// future completes.
+    pub(crate) fn duplicate_entity(
+        &mut self,
+        entity: Entity,
+        graphics: Arc<SharedGraphicsContext>,
+    ) -> anyhow::Result<Entity> {
+        const OFFSET: DVec3 = DVec3::new(1.0, 0.0, 0.0);
+
+        if !self.world.contains(entity) {
+            anyhow::bail!("Entity {:?} does not exist", entity);
+        }
+        if let Ok(camera) = self.world.get::<&CameraComponent>(entity)
+            && matches!(camera.camera_type, CameraType::Debug)
+        {
+            anyhow::bail!("The viewport camera can't be duplicated");
+        }
+
+        let label = self
+            .world
+            .get::<&Label>(entity)
+            .map(|label| format!("{} (Copy)", label))
+            .unwrap_or_else(|_| "Entity (Copy)".to_string());
+
+        let mut builder = EntityBuilder::new();
+        builder.add(Label::new(label.clone()));
+
+        let mut camera = None;
+        let mut light = None;
+        for component in self
+            .component_registry
+            .extract_all_components(&self.world, entity)
+        {
+            let any = component.as_any();
+            if let Some(camera_3d) = any.downcast_ref::<Camera3D>() {
+                camera = Some(camera_3d.clone());
+            } else if let Some(light_conf) = any.downcast_ref::<Light>() {
+                light = Some(light_conf.clone());
+            } else if !any.is::<SerializedMeshRenderer>() {
+                self.component_registry
+                    .deserialize_into_builder(component.as_ref(), &mut builder)?;
+            }
+        }
+
+        if let Ok(renderer) = self.world.get::<&MeshRenderer>(entity) {
+            let mut renderer = renderer.clone();
+            renderer.is_selected = false;
+            builder.add(renderer);
+        }
+
+        if let Some(camera_3d) = camera {
+            let mut camera = Camera::new(
+                graphics.clone(),
+                CameraBuilder::from(camera_3d.clone()),
+                Some(&label),
+            );
+            camera.visible_layers = camera_3d.visible_layers;
+            camera.eye += OFFSET;
+            camera.target += OFFSET;
+            // only one camera can be the one the scene starts with
+            let mut component = CameraComponent::from(camera_3d);
+            component.starting_camera = false;
+            builder.add_bundle((camera, component));
+        }
+
+        let duplicate = self.world.spawn(builder.build());
+
+        if let Ok(mut transform) = self.world.get::<&mut EntityTransform>(duplicate) {
+            transform.world_mut().position += OFFSET;
+        }
+
+        if let Some(mut light_conf) = light {
+            light_conf.label = label.clone();
+            light_conf.transform.position += OFFSET;
+            light_conf.entity_id = Some(duplicate);
+
+            let graphics_clone = graphics.clone();
+            let future = async move {
+                let engine_light = EngineLight::new(
+                    graphics_clone,
+                    light_conf.light_component.clone(),
+                    light_conf.transform,
+                    Some(&label),
+                )
+                .await;
+                let transform = light_conf.transform;
+                Ok::<(LightComponent, EngineLight, Light, Transform), anyhow::Error>((
+                    light_conf.light_component.clone(),
+                    engine_light,
+                    light_conf,
+                    transform,
+                ))
+            };
+            let handle = graphics.future_queue.push(Box::pin(future));
+            self.pending_components.push((duplicate, handle));
+        }
+
+        Ok(duplicate)
+    }
+
+    /// Whether the redo stack still applies, which is not the case once a new action has been
+    /// made after undoing.
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty() && undo_generation() == self.redo_anchor
+    }
+
+    /// Undoes the most recent action, moving its inverse onto the redo stack.
+    ///
+    /// Returns `None` if there is nothing to undo.
+    pub fn undo_last(&mut self) -> Option<anyhow::Result<()>> {
+        if undo_generation() != self.redo_anchor {
+            self.redo_stack.clear();
+        }
+
+        let action = self.undo_stack.pop()?;
+        let inverse = action.inverse(&self.world);
+        let result = action.undo(&mut self.world);
+        if result.is_ok() {
+            match inverse {
+                Some(inverse) => self.redo_stack.push(inverse),
+                None => log::debug!("'{}' cannot be redone", action.summary()),
+            }
+        }
+        self.redo_anchor = UNDO_GENERATION.fetch_add(1, Ordering::Relaxed) + 1;
+        Some(result)
+    }
+
+    /// Redoes the most recently undone action, moving its inverse back onto the undo stack.
+    ///
+    /// Returns `None` if there is nothing to redo.
+    pub fn redo_last(&mut self) -> Option<anyhow::Result<()>> {
+        if !self.can_redo() {
+            return None;
+        }
+
+        let action = self.redo_stack.pop()?;
+        let inverse = action.inverse(&self.world);
+        let result = action.undo(&mut self.world);
+        if result.is_ok()
+            && let Some(inverse) = inverse
+        {
+            self.undo_stack.push(inverse);
+        }
+        self.redo_anchor = UNDO_GENERATION.fetch_add(1, Ordering::Relaxed) + 1;
+        Some(result)
+    }
+
     /// Loads all the wgpu resources such as renderer.
     ///
     /// **Note**: To be ran AFTER [`Editor::load_project_config`]
@@ -1357,10 +2657,13 @@ impl Editor {
                             &graphics.shared.texture_bind_layout.clone(),
                             camera.layout(),
                             self.light_manager.layout(),
+                            &graphics.shared.emissive_bind_layout.clone(),
                         ],
                         None,
                     );
                     self.render_pipeline = Some(pipeline);
+                    // the layouts were recreated, so the custom shaders are built again
+                    self.custom_pipelines.clear();
 
                     // log::debug!("Contents of light shader: \n{:#?}", dropbear_engine::shader::shader_wesl::LIGHT_SHADER);
                     self.light_manager.create_render_pipeline(
@@ -1374,6 +2677,24 @@ impl Editor {
                     let outline_shader =
                         OutlineShader::init(graphics.shared.clone(), camera.layout());
                     self.outline_pipeline = Some(outline_shader);
+
+                    self.particle_system =
+                        Some(ParticleSystem::new(graphics.shared.clone(), camera.layout()));
+                    self.morph_system = Some(MorphSystem::new(graphics.shared.clone()));
+                    self.sprite_pipeline =
+                        Some(SpritePipeline::new(graphics.shared.clone(), camera.layout()));
+                    self.texture_streamer = Some(TextureStreamer::new(graphics.shared.clone()));
+                    self.ssao = Some(Ssao::new(
+                        graphics.shared.clone(),
+                        &[
+                            &graphics.shared.texture_bind_layout,
+                            camera.layout(),
+                            self.light_manager.layout(),
+                            &graphics.shared.emissive_bind_layout,
+                        ],
+                    ));
+                    self.depth_of_field = Some(DepthOfField::new(graphics.shared.clone()));
+                    self.tone_mapping = Some(ToneMapping::new(graphics.shared.clone()));
                 } else {
                     log_once::warn_once!(
                         "Unable to fetch the query result of camera: {:?}",
@@ -1412,6 +2733,7 @@ impl Editor {
             }
 
             self.editor_state = EditorState::Playing;
+            self.contact_tracker.clear();
 
             self.switch_to_player_camera();
 
@@ -1424,7 +2746,7 @@ impl Editor {
 
             let mut etag: HashMap<String, Vec<Entity>> = HashMap::new();
             for (entity_id, script) in script_entities {
-                for tag in script.tags {
+                for tag in script.normalised_tags() {
                     if etag.contains_key(&tag) {
                         etag.get_mut(&tag).unwrap().push(entity_id);
                     } else {
@@ -1468,6 +2790,10 @@ impl Editor {
                 log::info!("You are in play mode now! Press Escape to exit");
             }
 
+            if let Some(scene_name) = self.current_scene_name.clone() {
+                self.broadcast_scene_event(SceneEvent::Load { scene_name });
+            }
+
             self.signal = Signal::None;
             Ok(())
         } else {
@@ -1486,20 +2812,266 @@ pub enum UndoableAction {
     Transform(hecs::Entity, Transform),
     /// A change in EntityTransform. The entity + the old transform. Undoing will revert the transform
     EntityTransform(hecs::Entity, EntityTransform),
+    /// A change in the pivot of an EntityTransform. The entity + the old pivot. Undoing will
+    /// revert the pivot
+    Pivot(hecs::Entity, glam::DVec3),
     #[allow(dead_code)] // don't know why its considered dead code, todo: check the cause
     /// A spawn of the entity. Undoing will delete the entity
     Spawn(hecs::Entity),
     /// A change of label of the entity. Undoing will revert its label
     Label(hecs::Entity, String),
     RemoveStartingCamera(Entity),
+    /// A change to a camera made in the inspector. The entity + what the camera looked like
+    /// before. Undoing will revert the camera
+    CameraAction(hecs::Entity, CameraEdit),
+    /// A change of the parent of the entity, where `None` is the root of the scene. Undoing will
+    /// move the entity back under `old_parent`
+    SetParent {
+        entity: hecs::Entity,
+        old_parent: Option<hecs::Entity>,
+        new_parent: Option<hecs::Entity>,
+    },
+    /// A change of the material that replaces `target_material` on the entity's model, where
+    /// `None` is the model's own material. Undoing will put `old_override` back
+    MaterialOverride {
+        entity: hecs::Entity,
+        target_material: String,
+        old_override: Option<MaterialOverride>,
+        new_override: Option<MaterialOverride>,
+    },
+    /// A group of actions that are undone together, in reverse order.
+    Batch(Vec<UndoableAction>),
+}
+
+/// Counts every change made to the undo stack, including actions merged into the previous entry
+/// and undos and redos. See [`undo_generation`].
+static UNDO_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Returns a number that changes whenever the undo stack does, so a change can be noticed even
+/// when it leaves the stack the same length, such as when an action is merged into a batch.
+pub(crate) fn undo_generation() -> u64 {
+    UNDO_GENERATION.load(Ordering::Relaxed)
 }
 
 impl UndoableAction {
     pub fn push_to_undo(undo_stack: &mut Vec<UndoableAction>, action: Self) {
         undo_stack.push(action);
+        UNDO_GENERATION.fetch_add(1, Ordering::Relaxed);
         // log::debug!("Undo Stack contents: {:#?}", undo_stack);
     }
 
+    /// Pushes a transform action to the undo stack, merging it into the previous entry if
+    /// `coalesce` is true and the previous entry was a transform change of the same entity.
+    pub fn push_coalesced(undo_stack: &mut Vec<UndoableAction>, action: Self, coalesce: bool) {
+        let entity = action.transform_entity();
+        let mergeable = coalesce
+            && entity.is_some()
+            && undo_stack
+                .last()
+                .is_some_and(|last| last.transform_entity() == entity);
+
+        if !mergeable {
+            Self::push_to_undo(undo_stack, action);
+            return;
+        }
+
+        if let Some(last) = undo_stack.pop() {
+            let batch = match last {
+                UndoableAction::Batch(mut actions) => {
+                    actions.push(action);
+                    actions
+                }
+                other => vec![other, action],
+            };
+            log::debug!("Coalesced transform change into batch of {}", batch.len());
+            undo_stack.push(UndoableAction::Batch(batch));
+            UNDO_GENERATION.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns the entity if this is a transform change (or a batch of transform changes) of a
+    /// single entity.
+    fn transform_entity(&self) -> Option<hecs::Entity> {
+        match self {
+            UndoableAction::Transform(entity, _) | UndoableAction::EntityTransform(entity, _) => {
+                Some(*entity)
+            }
+            UndoableAction::Batch(actions) => {
+                let first = actions.first()?.transform_entity()?;
+                actions
+                    .iter()
+                    .all(|a| a.transform_entity() == Some(first))
+                    .then_some(first)
+            }
+            _ => None,
+        }
+    }
+
+    /// The entity affected by the action, if it only affects one.
+    pub fn entity(&self) -> Option<hecs::Entity> {
+        match self {
+            UndoableAction::Transform(entity, _)
+            | UndoableAction::EntityTransform(entity, _)
+            | UndoableAction::Pivot(entity, _)
+            | UndoableAction::Spawn(entity)
+            | UndoableAction::Label(entity, _)
+            | UndoableAction::RemoveStartingCamera(entity)
+            | UndoableAction::CameraAction(entity, _)
+            | UndoableAction::SetParent { entity, .. }
+            | UndoableAction::MaterialOverride { entity, .. } => Some(*entity),
+            UndoableAction::Batch(_) => self.transform_entity(),
+        }
+    }
+
+    /// The name of the type of action, used in the undo history.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            UndoableAction::Transform(..) | UndoableAction::EntityTransform(..) => "Transform",
+            UndoableAction::Pivot(..) => "Pivot",
+            UndoableAction::Spawn(_) => "Spawn",
+            UndoableAction::Label(..) => "Label",
+            UndoableAction::RemoveStartingCamera(_) => "Starting Camera",
+            UndoableAction::CameraAction(..) => "Camera",
+            UndoableAction::SetParent { .. } => "Parent",
+            UndoableAction::MaterialOverride { .. } => "Material",
+            UndoableAction::Batch(_) => {
+                if self.transform_entity().is_some() {
+                    "Transform"
+                } else {
+                    "Batch"
+                }
+            }
+        }
+    }
+
+    /// The icon shown next to the action in the undo history.
+    pub fn icon(&self) -> &'static str {
+        match self.kind() {
+            "Transform" => "🔄",
+            "Pivot" => "📍",
+            "Spawn" => "➕",
+            "Label" => "✏️",
+            "Starting Camera" => "📷",
+            "Camera" => "🎥",
+            "Parent" => "🔗",
+            "Material" => "🎨",
+            _ => "📦",
+        }
+    }
+
+    /// Creates the action that reverts this action once it has been undone, by capturing the
+    /// current state of the world.
+    ///
+    /// Returns `None` if the action cannot be redone, such as a spawn, as the despawned entity
+    /// cannot be brought back.
+    pub fn inverse(&self, world: &World) -> Option<UndoableAction> {
+        match self {
+            UndoableAction::Transform(entity, _) => world
+                .get::<&Transform>(*entity)
+                .ok()
+                .map(|t| UndoableAction::Transform(*entity, *t)),
+            UndoableAction::EntityTransform(entity, _) => world
+                .get::<&EntityTransform>(*entity)
+                .ok()
+                .map(|t| UndoableAction::EntityTransform(*entity, *t)),
+            UndoableAction::Pivot(entity, _) => world
+                .get::<&EntityTransform>(*entity)
+                .ok()
+                .map(|t| UndoableAction::Pivot(*entity, t.pivot())),
+            UndoableAction::Spawn(_) => None,
+            UndoableAction::Label(entity, _) => world
+                .get::<&Label>(*entity)
+                .ok()
+                .map(|l| UndoableAction::Label(*entity, l.to_string())),
+            UndoableAction::RemoveStartingCamera(_) => world
+                .query::<&CameraComponent>()
+                .iter()
+                .find(|(_, comp)| comp.starting_camera)
+                .map(|(entity, _)| UndoableAction::RemoveStartingCamera(entity)),
+            UndoableAction::CameraAction(entity, CameraEdit::Camera(_)) => {
+                world.get::<&Camera>(*entity).ok().map(|c| {
+                    UndoableAction::CameraAction(
+                        *entity,
+                        CameraEdit::Camera(CameraProperties::of(&c)),
+                    )
+                })
+            }
+            UndoableAction::CameraAction(entity, CameraEdit::Component(_)) => {
+                world.get::<&CameraComponent>(*entity).ok().map(|c| {
+                    UndoableAction::CameraAction(*entity, CameraEdit::Component((*c).clone()))
+                })
+            }
+            UndoableAction::SetParent {
+                entity, old_parent, ..
+            } => world.contains(*entity).then(|| UndoableAction::SetParent {
+                entity: *entity,
+                old_parent: Hierarchy::get_parent(world, *entity),
+                new_parent: *old_parent,
+            }),
+            UndoableAction::MaterialOverride {
+                entity,
+                target_material,
+                old_override,
+                ..
+            } => world.get::<&MeshRenderer>(*entity).ok().map(|renderer| {
+                UndoableAction::MaterialOverride {
+                    entity: *entity,
+                    target_material: target_material.clone(),
+                    old_override: renderer
+                        .material_overrides()
+                        .iter()
+                        .find(|o| &o.target_material == target_material)
+                        .cloned(),
+                    new_override: old_override.clone(),
+                }
+            }),
+            UndoableAction::Batch(actions) => actions
+                .iter()
+                .map(|a| a.inverse(world))
+                .collect::<Option<Vec<_>>>()
+                .map(UndoableAction::Batch),
+        }
+    }
+
+    /// A short, human-readable description of the action.
+    pub fn summary(&self) -> String {
+        match self {
+            UndoableAction::Transform(entity, _) => format!("Transform {:?}", entity),
+            UndoableAction::EntityTransform(entity, _) => {
+                format!("Entity transform {:?}", entity)
+            }
+            UndoableAction::Pivot(entity, _) => format!("Pivot {:?}", entity),
+            UndoableAction::Spawn(entity) => format!("Spawn {:?}", entity),
+            UndoableAction::Label(entity, _) => format!("Rename {:?}", entity),
+            UndoableAction::RemoveStartingCamera(entity) => {
+                format!("Starting camera {:?}", entity)
+            }
+            UndoableAction::CameraAction(entity, _) => format!("Camera {:?}", entity),
+            UndoableAction::SetParent {
+                entity, new_parent, ..
+            } => match new_parent {
+                Some(parent) => format!("Attach {:?} to {:?}", entity, parent),
+                None => format!("Detach {:?}", entity),
+            },
+            UndoableAction::MaterialOverride {
+                entity,
+                target_material,
+                new_override,
+                ..
+            } => match new_override {
+                Some(o) => format!(
+                    "Override '{}' with '{}' on {:?}",
+                    target_material, o.source_material, entity
+                ),
+                None => format!("Restore '{}' on {:?}", target_material, entity),
+            },
+            UndoableAction::Batch(actions) => match self.transform_entity() {
+                Some(entity) => format!("{} transform changes on {:?}", actions.len(), entity),
+                None => format!("{} changes", actions.len()),
+            },
+        }
+    }
+
     pub fn undo(&self, world: &mut World) -> anyhow::Result<()> {
         match self {
             UndoableAction::Transform(entity, transform) => {
@@ -1528,6 +3100,15 @@ impl UndoableAction {
                     Err(anyhow::anyhow!("Could not find an entity to query"))
                 }
             }
+            UndoableAction::Pivot(entity, pivot) => {
+                if let Ok(e_t) = world.query_one_mut::<&mut EntityTransform>(*entity) {
+                    e_t.set_pivot(*pivot);
+                    log::debug!("Reverted pivot");
+                    Ok(())
+                } else {
+                    Err(anyhow::anyhow!("Could not find an entity to query"))
+                }
+            }
             UndoableAction::Spawn(entity) => {
                 if world.despawn(*entity).is_ok() {
                     log::debug!("Undid spawn by despawning entity {:?}", entity);
@@ -1556,6 +3137,74 @@ impl UndoableAction {
                 }
                 Ok(())
             }
+            UndoableAction::CameraAction(entity, edit) => {
+                let Ok((camera, component)) =
+                    world.query_one_mut::<(&mut Camera, Option<&mut CameraComponent>)>(*entity)
+                else {
+                    anyhow::bail!("Could not find a camera to query");
+                };
+                match edit {
+                    CameraEdit::Camera(properties) => {
+                        properties.apply(camera);
+                        if let Some(component) = component {
+                            component.settings = camera.settings;
+                        }
+                    }
+                    CameraEdit::Component(old) => {
+                        if let Some(component) = component {
+                            *component = old.clone();
+                        }
+                    }
+                }
+                log::debug!("Reverted camera '{}'", camera.label);
+                Ok(())
+            }
+            UndoableAction::SetParent {
+                entity, old_parent, ..
+            } => {
+                if !world.contains(*entity) {
+                    anyhow::bail!("Could not find entity {:?} to reparent", entity);
+                }
+                // the world transforms are propagated from the parents again on the next update
+                match old_parent {
+                    Some(parent) => {
+                        if !world.contains(*parent) {
+                            anyhow::bail!("The old parent {:?} no longer exists", parent);
+                        }
+                        Hierarchy::set_parent(world, *entity, *parent);
+                    }
+                    None => Hierarchy::remove_parent(world, *entity),
+                }
+                log::debug!("Reverted parent of {:?} to {:?}", entity, old_parent);
+                Ok(())
+            }
+            UndoableAction::MaterialOverride {
+                entity,
+                target_material,
+                old_override,
+                ..
+            } => {
+                let Ok(renderer) = world.query_one_mut::<&mut MeshRenderer>(*entity) else {
+                    anyhow::bail!("Could not find a mesh renderer to query");
+                };
+                match old_override {
+                    Some(o) => renderer.apply_material_override(
+                        target_material,
+                        o.source_model.clone(),
+                        &o.source_material,
+                    )?,
+                    None => renderer.restore_original_material(target_material)?,
+                }
+                log::debug!("Reverted material '{}' of {:?}", target_material, entity);
+                Ok(())
+            }
+            UndoableAction::Batch(actions) => {
+                for action in actions.iter().rev() {
+                    action.undo(world)?;
+                }
+                log::debug!("Reverted batch of {} actions", actions.len());
+                Ok(())
+            }
         }
     }
 }
@@ -1566,8 +3215,22 @@ pub enum Signal {
     None,
     Copy(SceneEntity),
     Paste(SceneEntity),
+    /// Spawns a copy of the entity with all of its components, and selects it
+    Duplicate(hecs::Entity),
+    /// Moves the pivot of the entity to the centre of its model's bounds
+    CenterPivot(hecs::Entity),
+    /// Drops the entity straight down onto whatever model is below it, or onto `y = 0` if there
+    /// is nothing below
+    SnapToGround(hecs::Entity),
+    /// Views the scene through the camera
+    MakeActiveCamera(hecs::Entity),
     Delete,
     Undo,
+    Redo,
+    /// Undoes actions until the undo stack has the given length
+    UndoTo(usize),
+    /// Redoes actions until the redo stack has the given length
+    RedoTo(usize),
     Play,
     StopPlaying,
     CreateEntity,
@@ -1575,18 +3238,42 @@ pub enum Signal {
     Spawn(PendingSpawnType),
     AddComponent(hecs::Entity, String),
     LoadModel(hecs::Entity, String),
+    /// Bakes a single light probe, or every probe in the scene if `None`
+    BakeProbes(Option<hecs::Entity>),
+    /// Reimports a texture with its updated import settings
+    ReimportTexture(PathBuf),
+    /// Loads a model again, such as after its texture import settings were changed
+    ReloadModel(AssetHandle),
+    /// Changes the emissive factor of a material on the entity's model, by material name
+    SetEmissiveFactor(hecs::Entity, String, [f32; 3]),
+    /// The active scene's file was changed by something other than the editor
+    SceneFileChanged(PathBuf),
+    /// A script was changed by an external tool
+    ReloadScript(PathBuf),
+    /// An asset was changed by an external tool
+    HotReloadAsset(PathBuf),
+}
+
+#[derive(Clone, EntitySnapshot)]
+struct ModelSnapshot {
+    mesh_renderer: MeshRenderer,
+    transform: Transform,
+    properties: ModelProperties,
+    script: Option<Script>,
+}
+
+#[derive(Clone, EntitySnapshot)]
+struct CameraSnapshot {
+    camera: Camera,
+    component: CameraComponent,
 }
 
 #[derive(Clone)]
 pub struct PlayModeBackup {
-    entities: Vec<(
-        Entity,
-        MeshRenderer,
-        Transform,
-        ModelProperties,
-        Option<Script>,
-    )>,
-    camera_data: Vec<(Entity, Camera, CameraComponent)>,
+    entities: Vec<(Entity, ModelSnapshot)>,
+    camera_data: Vec<(Entity, CameraSnapshot)>,
+    /// Every entity in the world when play mode started
+    alive: HashSet<Entity>,
 }
 
 #[derive(Debug)]
@@ -1596,6 +3283,51 @@ pub enum EditorState {
     Playing,
 }
 
+/// An action that throws away work, which waits for the user to confirm it with
+/// [`dialogs::confirm_destructive_action`] before it is run.
+#[derive(Debug)]
+pub enum DeferredAction {
+    /// Reloads the active scene from disk, discarding unsaved changes
+    ReloadScene,
+    /// Imports a glTF file as a new scene and switches to it
+    ImportGltfScene(PathBuf),
+    /// Creates a new project in a folder that is not empty
+    CreateProject {
+        name: String,
+        path: PathBuf,
+        template: SceneTemplate,
+    },
+}
+
+impl DeferredAction {
+    /// The title, message and confirm button text of the confirmation dialog.
+    fn confirmation(&self) -> (&'static str, String, &'static str) {
+        match self {
+            DeferredAction::ReloadScene => (
+                "Unsaved Changes",
+                "Reloading the scene will discard your unsaved changes.".to_string(),
+                "Reload Anyway",
+            ),
+            DeferredAction::ImportGltfScene(path) => (
+                "Unsaved Changes",
+                format!(
+                    "Importing {} will switch to a new scene and discard your unsaved changes.",
+                    path.display()
+                ),
+                "Import Anyway",
+            ),
+            DeferredAction::CreateProject { path, .. } => (
+                "Folder Not Empty",
+                format!(
+                    "{} already has files in it, which may be overwritten by the new project.",
+                    path.display()
+                ),
+                "Create Anyway",
+            ),
+        }
+    }
+}
+
 struct PendingSceneLoad {
     scene: SceneConfig,
 }
@@ -1647,3 +3379,104 @@ impl Default for IsWorldLoadedYet {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Moves `entity` under `new_parent` like the hierarchy panel does, returning the action
+    /// pushed to the undo stack.
+    fn reparent(world: &mut World, entity: Entity, new_parent: Option<Entity>) -> UndoableAction {
+        let old_parent = Hierarchy::get_parent(world, entity);
+        match new_parent {
+            Some(parent) => Hierarchy::set_parent(world, entity, parent),
+            None => Hierarchy::remove_parent(world, entity),
+        }
+        UndoableAction::SetParent {
+            entity,
+            old_parent,
+            new_parent,
+        }
+    }
+
+    /// Whether `entity` is under `parent`, checked from both sides of the relationship.
+    fn assert_parent(world: &World, entity: Entity, parent: Option<Entity>) {
+        assert_eq!(Hierarchy::get_parent(world, entity), parent);
+        for (other, children) in world.query::<&Children>().iter() {
+            assert_eq!(
+                children.children().contains(&entity),
+                Some(other) == parent,
+                "children of {:?}",
+                other
+            );
+        }
+    }
+
+    /// Undoes `action`, redoes it and undoes it again, the same way [`Editor::undo_last`] and
+    /// [`Editor::redo_last`] do.
+    fn assert_round_trip(
+        world: &mut World,
+        action: UndoableAction,
+        entity: Entity,
+        before: Option<Entity>,
+        after: Option<Entity>,
+    ) {
+        assert_parent(world, entity, after);
+
+        let redo = action.inverse(world).expect("reparenting can be redone");
+        action.undo(world).unwrap();
+        assert_parent(world, entity, before);
+
+        let undo = redo.inverse(world).expect("reparenting can be undone");
+        redo.undo(world).unwrap();
+        assert_parent(world, entity, after);
+
+        undo.undo(world).unwrap();
+        assert_parent(world, entity, before);
+    }
+
+    #[test]
+    fn test_undo_redo_attach() {
+        let mut world = World::new();
+        let parent = world.spawn((Label::new("parent"),));
+        let child = world.spawn((Label::new("child"),));
+
+        let action = reparent(&mut world, child, Some(parent));
+        assert_round_trip(&mut world, action, child, None, Some(parent));
+    }
+
+    #[test]
+    fn test_undo_redo_detach() {
+        let mut world = World::new();
+        let parent = world.spawn((Label::new("parent"),));
+        let child = world.spawn((Label::new("child"),));
+        Hierarchy::set_parent(&mut world, child, parent);
+
+        let action = reparent(&mut world, child, None);
+        assert_round_trip(&mut world, action, child, Some(parent), None);
+    }
+
+    #[test]
+    fn test_undo_redo_reparent() {
+        let mut world = World::new();
+        let first = world.spawn((Label::new("first"),));
+        let second = world.spawn((Label::new("second"),));
+        let child = world.spawn((Label::new("child"),));
+        Hierarchy::set_parent(&mut world, child, first);
+
+        let action = reparent(&mut world, child, Some(second));
+        assert_round_trip(&mut world, action, child, Some(first), Some(second));
+    }
+
+    #[test]
+    fn test_undo_reparent_to_despawned_parent() {
+        let mut world = World::new();
+        let parent = world.spawn((Label::new("parent"),));
+        let child = world.spawn((Label::new("child"),));
+        Hierarchy::set_parent(&mut world, child, parent);
+
+        let action = reparent(&mut world, child, None);
+        world.despawn(parent).unwrap();
+        assert!(action.undo(&mut world).is_err());
+    }
+}
@@ -0,0 +1,124 @@
+//! A WebSocket server that lets external tools, such as text editors or a Blender plugin, tell the
+//! editor about project assets they changed.
+//!
+//! Tools send JSON messages tagged by `type`:
+//! - `{ "type": "asset_changed", "path": "..." }` - An asset was changed, where `path` is absolute
+//!   or relative to the project
+//! - `{ "type": "ping" }` - Answered with `{ "type": "pong" }`, to check if the editor is running
+
+use crossbeam_channel::{Receiver, Sender, unbounded};
+use dropbear_engine::future::{CancellationToken, FutureHandle, FutureQueue};
+use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::tungstenite::Message;
+
+/// The port the server listens on if the project does not set one.
+pub const DEFAULT_REMOTE_PORT: u16 = 7890;
+
+/// A message sent by an external tool.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RemoteMessage {
+    AssetChanged { path: PathBuf },
+    Ping,
+}
+
+/// Listens for external tools on `127.0.0.1`, and collects the assets they report as changed.
+pub struct RemoteServer {
+    port: u16,
+    receiver: Receiver<PathBuf>,
+    future_queue: Arc<FutureQueue>,
+    handle: FutureHandle,
+}
+
+impl RemoteServer {
+    /// Starts the server on the future queue. Binding the port happens in the background, so
+    /// a port that is already taken is only logged.
+    pub fn start(future_queue: Arc<FutureQueue>, port: u16) -> Self {
+        let (sender, receiver) = unbounded();
+
+        let handle = future_queue.push_cancellable(move |token| async move {
+            if let Err(e) = serve(port, sender, token).await {
+                log::warn!("Remote server on port {} stopped: {}", port, e);
+            }
+        });
+
+        Self {
+            port,
+            receiver,
+            future_queue,
+            handle,
+        }
+    }
+
+    /// The port the server was started on.
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Returns the next asset reported as changed, if there is one.
+    pub fn next_changed_asset(&self) -> Option<PathBuf> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+impl Drop for RemoteServer {
+    fn drop(&mut self) {
+        self.future_queue.cancel(&self.handle);
+    }
+}
+
+async fn serve(port: u16, sender: Sender<PathBuf>, token: CancellationToken) -> anyhow::Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port)).await?;
+    log::info!("Listening for external tools on ws://127.0.0.1:{}", port);
+
+    while !token.is_cancelled() {
+        let (stream, address) = listener.accept().await?;
+        log::debug!("External tool connected from {}", address);
+
+        let sender = sender.clone();
+        let token = token.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, sender, token).await {
+                log::debug!("Connection from {} closed: {}", address, e);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    sender: Sender<PathBuf>,
+    token: CancellationToken,
+) -> anyhow::Result<()> {
+    let mut socket = tokio_tungstenite::accept_async(stream).await?;
+
+    while let Some(message) = socket.next().await {
+        if token.is_cancelled() {
+            break;
+        }
+
+        let text = match message? {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+
+        let reply = match serde_json::from_str::<RemoteMessage>(text.as_str()) {
+            Ok(RemoteMessage::AssetChanged { path }) => {
+                sender.send(path)?;
+                serde_json::json!({ "type": "ok" })
+            }
+            Ok(RemoteMessage::Ping) => serde_json::json!({ "type": "pong" }),
+            Err(e) => serde_json::json!({ "type": "error", "message": e.to_string() }),
+        };
+        socket.send(Message::text(reply.to_string())).await?;
+    }
+
+    Ok(())
+}
@@ -2,23 +2,26 @@ use super::*;
 use crate::signal::SignalController;
 use crate::spawn::PendingSpawnController;
 use dropbear_engine::asset::{ASSET_REGISTRY, PointerKind};
+use dropbear_engine::depth_of_field;
 use dropbear_engine::graphics::{InstanceRaw, RenderContext};
 use dropbear_engine::model::MODEL_CACHE;
 use dropbear_engine::{
-    entity::{EntityTransform, MeshRenderer, Transform},
+    entity::{EntityLayer, EntityTransform, MeshRenderer, Transform},
     lighting::{Light, LightComponent},
-    model::{DrawLight, DrawModel},
+    lod::{self, ActiveModel},
+    model::{DrawLight, DrawModel, Model},
     scene::{Scene, SceneCommand},
 };
 use eucalyptus_core::logging;
-use eucalyptus_core::states::{Label, WorldLoadingStatus};
+use eucalyptus_core::utils::ResolveReference;
+use eucalyptus_core::states::{Label, MaterialProperties, MaterialShader, WorldLoadingStatus};
 use eucalyptus_core::hierarchy::{EntityTransformExt};
 use eucalyptus_core::window::poll;
 use log;
 use parking_lot::Mutex;
+use std::collections::hash_map::Entry;
 use tokio::sync::mpsc::unbounded_channel;
 use wgpu::Color;
-use wgpu::util::DeviceExt;
 use winit::{event_loop::ActiveEventLoop, keyboard::KeyCode};
 
 impl Scene for Editor {
@@ -37,6 +40,11 @@ impl Scene for Editor {
             }
         };
 
+        self.validate_project_assets();
+        dropbear_engine::set_preferred_monitor(PROJECT.read().editor_settings.preferred_monitor);
+        dropbear_engine::set_ui_scale(PROJECT.read().editor_settings.ui_scale);
+        tone_mapping::set_tone_mapping(PROJECT.read().editor_settings.tone_mapping);
+
         let (tx, rx) = unbounded_channel::<WorldLoadingStatus>();
         let (tx2, rx2) = oneshot::channel::<World>();
         self.progress_tx = Some(rx);
@@ -80,12 +88,31 @@ impl Scene for Editor {
 
     fn update(&mut self, dt: f32, graphics: &mut RenderContext) {
         if let Some(request) = self.pending_scene_load.take() {
+            if let Some(handle) = &self.world_load_handle {
+                // the user switched scenes before the previous one finished loading
+                log::debug!("Cancelling in-progress world load");
+                handle.cancel();
+            }
             self.start_async_scene_load(request.scene, graphics);
         }
 
         if let Some(mut receiver) = self.world_receiver.take() {
             self.show_project_loading_window(&graphics.shared.get_egui_context());
-            if let Ok(loaded_world) = receiver.try_recv() {
+            let received = match receiver.try_recv() {
+                Ok(loaded_world) => Some(loaded_world),
+                Err(tokio::sync::oneshot::error::TryRecvError::Empty) => {
+                    self.world_receiver = Some(receiver);
+                    return;
+                }
+                Err(tokio::sync::oneshot::error::TryRecvError::Closed) => {
+                    // the sender is dropped without sending when the load is cancelled
+                    log::debug!("World load was cancelled before the world was sent");
+                    self.loading_additive_scene = None;
+                    None
+                }
+            };
+
+            if let Some(loaded_world) = received {
                 self.world = Box::new(loaded_world);
                 self.is_world_loaded.mark_project_loaded();
 
@@ -97,8 +124,10 @@ impl Scene for Editor {
                 }
 
                 log::debug!("World received");
+                if let Some(scene_name) = self.loading_additive_scene.take() {
+                    self.broadcast_scene_event(SceneEvent::Load { scene_name });
+                }
             } else {
-                self.world_receiver = Some(receiver);
                 return;
             }
         }
@@ -125,6 +154,13 @@ impl Scene for Editor {
             }
         }
 
+        self.import_dropped_files(graphics.shared.clone());
+        self.load_pending_additive_scene(graphics);
+        self.poll_scene_watcher();
+        self.poll_model_watcher(graphics.shared.clone());
+        self.poll_remote_server(graphics.shared.future_queue.clone());
+        self.poll_project_creation(graphics.shared.future_queue.clone());
+
         {
             // title to projects name
             let project_title = { PROJECT.read().project_name.clone() };
@@ -176,6 +212,13 @@ impl Scene for Editor {
             self.is_viewport_focused = false;
         }
 
+        // the viewport tab was closed or hidden while looking around
+        if self.is_mouse_looking && self.viewport_rect.is_none() {
+            self.release_cursor();
+        }
+
+        self.update_input_context(&graphics.shared.get_egui_context());
+
         if matches!(self.editor_state, EditorState::Playing) {
             if self.input_state.pressed_keys.contains(&KeyCode::Escape) {
                 self.signal = Signal::StopPlaying;
@@ -190,9 +233,20 @@ impl Scene for Editor {
                 fatal!("Failed to update script: {}", e);
                 self.signal = Signal::StopPlaying;
             }
+            self.refresh_script_timings();
+
+            if let Err(e) = self.script_manager.tick_timers(dt) {
+                fatal!("Failed to fire script timers: {}", e);
+                self.signal = Signal::StopPlaying;
+            }
+
+            self.script_manager.flush_spawn_queue(&mut self.world);
+            eucalyptus_core::camera::follow_system(&mut self.world, dt);
         }
 
-        if self.is_viewport_focused && matches!(self.viewport_mode, ViewportMode::CameraMove)
+        if self.is_viewport_focused
+            && matches!(self.viewport_mode, ViewportMode::CameraMove)
+            && self.input_contexts.current().allows(InputContext::Gameplay)
         // && self.is_using_debug_camera()
         {
             let active_cam = self.active_camera.lock();
@@ -200,7 +254,8 @@ impl Scene for Editor {
                 && let Ok(mut query) = self
                     .world
                     .query_one::<(&mut Camera, &CameraComponent)>(active_camera)
-                && let Some((camera, _)) = query.get()
+                && let Some((camera, component)) = query.get()
+                && !matches!(component.camera_type, CameraType::Cinematic)
             {
                 for key in &self.input_state.pressed_keys {
                     match key {
@@ -218,6 +273,8 @@ impl Scene for Editor {
 
         let _ = self.run_signal(graphics.shared.clone());
 
+        eucalyptus_core::camera::update_camera_paths(&mut self.world, dt);
+
         if let Some(e) = self.previously_selected_entity
             && let Ok(mut q) = self.world.query_one::<&mut MeshRenderer>(e)
             && let Some(entity) = q.get()
@@ -243,7 +300,18 @@ impl Scene for Editor {
                 && let Ok(mut query) = self.world.query_one::<&mut Camera>(active_camera)
                 && let Some(camera) = query.get()
             {
-                camera.aspect = new_aspect;
+                camera.set_aspect(new_aspect);
+            }
+        }
+
+        // the other views render at the same size as the main viewport
+        if matches!(self.viewport_layout, ViewportLayout::Quad) {
+            for sub_viewport in &self.sub_viewports {
+                if let Some(entity) = sub_viewport.camera
+                    && let Ok(mut camera) = self.world.get::<&mut Camera>(entity)
+                {
+                    camera.set_aspect(new_aspect);
+                }
             }
         }
 
@@ -254,6 +322,7 @@ impl Scene for Editor {
                 .iter()
             {
                 component.update(camera);
+                camera.update_shake(dt);
                 camera.update(graphics.shared.clone());
             }
         }
@@ -270,12 +339,12 @@ impl Scene for Editor {
                 let mut updates = Vec::new();
                 for (entity, transform) in self.world.query::<&EntityTransform>().iter() {
                     let final_transform = transform.propagate(&self.world, entity);
-                    updates.push((entity, final_transform));
+                    updates.push((entity, final_transform, transform.pivot()));
                 }
 
-                for (entity, final_transform) in updates {
+                for (entity, final_transform, pivot) in updates {
                     if let Ok(mut renderer) = self.world.get::<&mut MeshRenderer>(entity) {
-                        renderer.update(&final_transform);
+                        renderer.update_with_pivot(&final_transform, pivot);
                     }
                 }
             }
@@ -306,11 +375,98 @@ impl Scene for Editor {
             }
         }
 
+        // contacts are found once everything has moved for this frame, so anything the collision
+        // callbacks change is only picked up by the next one
+        if matches!(self.editor_state, EditorState::Playing) {
+            let events = self.contact_tracker.step(&self.world);
+            if let Err(e) = self
+                .script_manager
+                .dispatch_collision_events(&self.world, &events)
+            {
+                fatal!("Failed to dispatch collision events: {}", e);
+                self.signal = Signal::StopPlaying;
+            }
+        }
+
         {
             self.light_manager
                 .update(graphics.shared.clone(), &self.world);
         }
 
+        if let Some(particle_system) = &mut self.particle_system {
+            let camera = self
+                .active_camera
+                .lock()
+                .and_then(|entity| self.world.get::<&Camera>(entity).ok().map(|c| c.clone()));
+            if let Some(camera) = camera {
+                particle_system.update(graphics.shared.clone(), &mut self.world, &camera, dt);
+            }
+        }
+
+        if let Some(morph_system) = &mut self.morph_system {
+            morph_system.update(graphics.shared.clone(), &mut self.world);
+        }
+
+        // sprite textures are loaded in the background by the texture streamer, and are drawn
+        // grey until then
+        let mut sprite_textures = Vec::new();
+        for (_, (sprite, t, et)) in self.world.query_mut::<(
+            &mut SpriteRenderer,
+            Option<&Transform>,
+            Option<&EntityTransform>,
+        )>() {
+            if sprite.texture.as_uri().is_none() {
+                continue;
+            }
+
+            match sprite.texture.resolve() {
+                Ok(path) => {
+                    sprite.texture_handle = Some(ASSET_REGISTRY.reserve_texture_handle(&path));
+                    let position = et.map(|et| et.sync()).or(t.copied()).unwrap_or_default();
+                    sprite_textures.push((path, position.position));
+                }
+                Err(e) => {
+                    log_once::warn_once!("Unable to load sprite {}: {}", sprite.texture, e);
+                }
+            }
+        }
+
+        if let Some(texture_streamer) = &mut self.texture_streamer {
+            let camera_position = self
+                .active_camera
+                .lock()
+                .and_then(|entity| self.world.get::<&Camera>(entity).ok().map(|c| c.eye));
+            if let Some(camera_position) = camera_position {
+                texture_streamer.update(camera_position, sprite_textures);
+            }
+        }
+
+        let camera_eye = self
+            .active_camera
+            .lock()
+            .and_then(|entity| self.world.get::<&Camera>(entity).ok().map(|c| c.eye));
+        if let Some(camera_eye) = camera_eye {
+            lod::update_world(&mut self.world, camera_eye);
+        }
+
+        self.text_renderer.queue_requests(graphics.shared.clone());
+        if let Some(sprite_pipeline) = &mut self.sprite_pipeline {
+            let camera = self
+                .active_camera
+                .lock()
+                .and_then(|entity| self.world.get::<&Camera>(entity).ok().map(|c| c.clone()));
+            if let Some(camera) = camera {
+                sprite_pipeline.update_with_text(
+                    graphics.shared.clone(),
+                    &self.world,
+                    &camera,
+                    &mut self.text_renderer,
+                );
+            }
+        }
+        // text is only drawn in the frame it was asked for
+        self.text_renderer.clear();
+
         self.nerd_stats.update(dt, self.world.len());
 
         self.input_state.window = self.window.clone();
@@ -318,13 +474,7 @@ impl Scene for Editor {
     }
 
     fn render(&mut self, graphics: &mut RenderContext) {
-        // cornflower blue
-        let color = Color {
-            r: 100.0 / 255.0,
-            g: 149.0 / 255.0,
-            b: 237.0 / 255.0,
-            a: 1.0,
-        };
+        let color = self.scene_clear_color();
 
         self.color = color;
         self.size = graphics.shared.viewport_texture.size;
@@ -336,148 +486,542 @@ impl Scene for Editor {
 
         self.window = Some(graphics.shared.window.clone());
         logging::render(&graphics.shared.get_egui_context());
-        if let Some(pipeline) = &self.render_pipeline {
+
+        self.scene_stats.begin_frame(self.world.len());
+        self.scene_stats.measure_memory();
+        if let Some(texture_streamer) = &self.texture_streamer {
+            self.scene_stats.record_streaming(texture_streamer);
+        }
+        self.scene_stats.record_lights(&self.light_manager);
+        if self.render_pipeline.is_some() {
             log_once::debug_once!("Found render pipeline");
-            if let Some(active_camera) = *self.active_camera.lock() {
-                let cam = {
-                    if let Ok(mut query) = self.world.query_one::<&Camera>(active_camera) {
-                        query.get().cloned()
-                    } else {
-                        None
+            let active_camera = *self.active_camera.lock();
+            if let Some(active_camera) = active_camera {
+                self.prepare_custom_shaders(graphics, active_camera);
+                self.render_view(graphics, active_camera, color);
+            } else {
+                log_once::error_once!("No active camera found");
+            }
+
+            if matches!(self.viewport_layout, ViewportLayout::Quad) {
+                self.render_sub_viewports(graphics, color);
+            }
+        } else {
+            if self.is_world_loaded.is_fully_loaded() {
+                log_once::warn_once!("No render pipeline exists");
+            } else {
+                log_once::debug_once!("No render pipeline exists, but world not loaded yet");
+            }
+        }
+    }
+
+    fn exit(&mut self, _event_loop: &ActiveEventLoop) {}
+
+    fn run_command(&mut self) -> SceneCommand {
+        std::mem::replace(&mut self.scene_command, SceneCommand::None)
+    }
+
+    fn handle_command(&mut self, command: SceneCommand) {
+        match command {
+            SceneCommand::ImportDroppedFile(path) => {
+                let is_model = path.extension().and_then(|e| e.to_str()).is_some_and(|e| {
+                    e.eq_ignore_ascii_case("glb") || e.eq_ignore_ascii_case("gltf")
+                });
+
+                if !matches!(self.editor_state, EditorState::Editing) {
+                    warn!("Unable to import {}: stop playing first", path.display());
+                } else if PROJECT.read().project_path.as_os_str().is_empty() {
+                    warn!("Unable to import {}: no project is open", path.display());
+                } else if !is_model {
+                    warn!(
+                        "Unable to import {}: only glTF models can be dropped",
+                        path.display()
+                    );
+                } else {
+                    info!("Importing {}", path.display());
+                    self.dropped_files.push(path);
+                }
+            }
+            SceneCommand::LoadAdditively { name } => {
+                if self.current_scene_name.as_deref() == Some(name.as_str()) {
+                    warn!("Scene '{}' is already the current scene", name);
+                } else {
+                    info!("Loading scene '{}' additively", name);
+                    self.pending_additive_scenes.push(name);
+                }
+            }
+            SceneCommand::UnloadScene(name) => {
+                self.broadcast_scene_event(SceneEvent::Unload {
+                    scene_name: name.clone(),
+                });
+                let count = SceneConfig::unload_from_world(&mut self.world, &name);
+                if let Some(selected) = self.selected_entity
+                    && !self.world.contains(selected)
+                {
+                    self.selected_entity = None;
+                }
+                info!("Unloaded {} entities from scene '{}'", count, name);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Editor {
+    /// Returns the clear colour of the current scene. This is read every frame, so changes made
+    /// in the scene properties show up in the viewport straight away.
+    fn scene_clear_color(&self) -> Color {
+        let scenes = SCENES.read();
+        let [r, g, b, a] = self
+            .current_scene_name
+            .as_ref()
+            .and_then(|name| scenes.iter().find(|scene| &scene.scene_name == name))
+            .or_else(|| scenes.first())
+            .map_or(SceneConfig::DEFAULT_CLEAR_COLOR, |scene| scene.clear_color);
+        Color { r, g, b, a }
+    }
+
+    /// Compiles the shaders used by [`MaterialShader`] components that are new or have changed on
+    /// disk since they were last compiled.
+    ///
+    /// Shaders that fail to compile are reported to the console, and the entities using them are
+    /// drawn with the default shader until the file is fixed.
+    fn prepare_custom_shaders(&mut self, graphics: &RenderContext, camera_entity: Entity) {
+        let paths: HashSet<PathBuf> = self
+            .world
+            .query::<&MaterialShader>()
+            .iter()
+            .filter_map(|(_, shader)| shader.shader_path.clone())
+            .collect();
+        if paths.is_empty() {
+            return;
+        }
+
+        let Ok(camera) = self.world.get::<&Camera>(camera_entity) else {
+            return;
+        };
+        let project_root = PROJECT.read().project_path.clone();
+
+        for path in paths {
+            let full_path = project_root.join(&path);
+            let modified = fs::metadata(&full_path)
+                .and_then(|metadata| metadata.modified())
+                .ok();
+            if self
+                .custom_pipelines
+                .get(&path)
+                .is_some_and(|(compiled, _)| *compiled == modified)
+            {
+                continue;
+            }
+
+            let label = path.display().to_string();
+            let pipeline = fs::read_to_string(&full_path)
+                .map_err(anyhow::Error::from)
+                .and_then(|source| {
+                    let shader = Shader::try_new(graphics.shared.clone(), &source, Some(&label))?;
+                    graphics
+                        .shared
+                        .device
+                        .push_error_scope(wgpu::ErrorFilter::Validation);
+                    let pipeline = graphics.create_render_pipline(
+                        &shader,
+                        vec![
+                            &graphics.shared.texture_bind_layout,
+                            camera.layout(),
+                            self.light_manager.layout(),
+                            &graphics.shared.emissive_bind_layout,
+                        ],
+                        Some(&label),
+                    );
+                    match futures::executor::block_on(graphics.shared.device.pop_error_scope()) {
+                        Some(e) => Err(anyhow::anyhow!("{}", e)),
+                        None => Ok(pipeline),
                     }
-                };
+                });
 
-                if let Some(camera) = cam {
-                    let lights = {
-                        let mut lights = Vec::new();
-                        let mut light_query = self.world.query::<(&Light, &LightComponent)>();
-                        for (_, (light, comp)) in light_query.iter() {
-                            lights.push((light.clone(), comp.clone()));
-                        }
-                        lights
-                    };
+            let pipeline = match pipeline {
+                Ok(pipeline) => {
+                    success!("Compiled shader {}", label);
+                    Some(pipeline)
+                }
+                Err(e) => {
+                    fatal!("Unable to compile shader {}: {}", label, e);
+                    None
+                }
+            };
+            self.custom_pipelines.insert(path, (modified, pipeline));
+        }
+    }
+
+    /// Draws the world as seen through `camera_entity` into the frame's view.
+    fn render_view(&mut self, graphics: &mut RenderContext, camera_entity: Entity, color: Color) {
+        let Some(pipeline) = &self.render_pipeline else {
+            return;
+        };
+
+        let cam = {
+            if let Ok(mut query) = self
+                .world
+                .query_one::<(&Camera, Option<&CameraComponent>)>(camera_entity)
+            {
+                query.get().map(|(camera, component)| {
+                    // the debug camera always renders every layer
+                    let is_debug =
+                        component.is_some_and(|c| matches!(c.camera_type, CameraType::Debug));
+                    (camera.clone(), is_debug)
+                })
+            } else {
+                None
+            }
+        };
+
+        if let Some((camera, is_debug_camera)) = cam {
+            let lights = {
+                let mut lights = Vec::new();
+                let mut light_query = self.world.query::<(&Light, &LightComponent)>();
+                for (_, (light, comp)) in light_query.iter() {
+                    lights.push((light.clone(), comp.clone()));
+                }
+                lights
+            };
+
+            let entities = {
+                let mut entities = Vec::new();
+                let mut entity_query = self.world.query::<(
+                    &MeshRenderer,
+                    Option<&EntityLayer>,
+                    Option<&MaterialProperties>,
+                    Option<&ActiveModel>,
+                    Option<&MaterialShader>,
+                )>();
+                for (entity, (renderer, layer, material_properties, active_model, shader)) in
+                    entity_query.iter()
+                {
+                    let layer_mask = layer.copied().unwrap_or_default().mask();
+                    if !is_debug_camera && !camera.can_see(layer_mask) {
+                        continue;
+                    }
+                    let material_properties = material_properties
+                        .map(|properties| properties.packed())
+                        .unwrap_or_default();
+                    // the level picked by a LodGroup takes priority over the renderer's own model
+                    let lod_model =
+                        active_model.and_then(|active| ASSET_REGISTRY.get_model(active.0));
+                    let shader_path = shader.and_then(|shader| shader.shader_path.clone());
+                    entities.push((
+                        entity,
+                        renderer.clone(),
+                        material_properties,
+                        lod_model,
+                        shader_path,
+                    ));
+                }
+                entities
+            };
+            // entities with a custom shader that compiled are drawn with it, the rest fall back
+            // to the default shader
+            let pipeline_for = |shader_path: &Option<PathBuf>| {
+                shader_path
+                    .as_ref()
+                    .and_then(|path| self.custom_pipelines.get(path))
+                    .and_then(|(_, pipeline)| pipeline.as_ref())
+                    .unwrap_or(pipeline)
+            };
 
-                    let entities = {
-                        let mut entities = Vec::new();
-                        let mut entity_query = self.world.query::<&MeshRenderer>();
-                        for (_, renderer) in entity_query.iter() {
-                            entities.push(renderer.clone());
+            {
+                // light cube rendering
+                let mut render_pass = graphics.clear_colour(color);
+                if let Some(light_pipeline) = &self.light_manager.pipeline {
+                    render_pass.set_pipeline(light_pipeline);
+                    for (light, _component) in &lights {
+                        render_pass
+                            .set_vertex_buffer(1, light.instance_buffer.as_ref().unwrap().slice());
+                        if _component.visible {
+                            self.scene_stats.record_model(&light.cube_model, 1);
+                            render_pass.draw_light_model(
+                                &light.cube_model,
+                                camera.bind_group(),
+                                light.bind_group(),
+                            );
                         }
-                        entities
-                    };
+                    }
+                }
+            }
+
+            let mut model_batches: HashMap<(ModelId, Option<PathBuf>), Vec<InstanceRaw>> =
+                HashMap::new();
+            let mut lod_models: HashMap<ModelId, Arc<Model>> = HashMap::new();
+            let mut morphed = Vec::new();
+            for (entity, renderer, material_properties, lod_model, shader_path) in &entities {
+                let instance_raw = renderer
+                    .instance
+                    .to_raw()
+                    .with_material_properties(*material_properties);
+
+                // entities with blended morph targets have their own vertex buffers, so
+                // they can't be batched with other instances of their model
+                if lod_model.is_none()
+                    && self
+                        .morph_system
+                        .as_ref()
+                        .is_some_and(|m| m.is_morphed(*entity))
+                {
+                    morphed.push((*entity, renderer.model(), instance_raw, shader_path));
+                    continue;
+                }
+
+                let model_ptr = match lod_model {
+                    Some(model) => {
+                        lod_models.insert(model.id, model.clone());
+                        model.id
+                    }
+                    None => renderer.model_id(),
+                };
+                model_batches
+                    .entry((model_ptr, shader_path.clone()))
+                    .or_default()
+                    .push(instance_raw);
+            }
+
+            // buffers of batches that are no longer drawn are dropped
+            self.instance_buffers
+                .retain(|key, _| model_batches.contains_key(key));
+            self.morphed_instance_buffers
+                .retain(|entity, _| morphed.iter().any(|(e, ..)| e == entity));
 
-                    {
-                        // light cube rendering
-                        let mut render_pass = graphics.clear_colour(color);
-                        if let Some(light_pipeline) = &self.light_manager.pipeline {
-                            render_pass.set_pipeline(light_pipeline);
-                            for (light, _component) in &lights {
-                                render_pass.set_vertex_buffer(
-                                    1,
-                                    light.instance_buffer.as_ref().unwrap().slice(..),
+            // batches drawn with the default shader, which the ambient occlusion pass draws again
+            let mut default_shader_batches = Vec::new();
+            for ((model_ptr, shader_path), instances) in model_batches {
+                {
+                    let model_opt = lod_models.get(&model_ptr).cloned().or_else(|| {
+                        let cache = MODEL_CACHE.lock();
+                        cache.values().find(|m| m.id == model_ptr).cloned()
+                    });
+
+                    if let Some(model) = model_opt {
+                        let instance_buffer = match self
+                            .instance_buffers
+                            .entry((model_ptr, shader_path.clone()))
+                        {
+                            Entry::Occupied(entry) => {
+                                let buffer = entry.into_mut();
+                                buffer.update(
+                                    &graphics.shared.device,
+                                    &graphics.shared.queue,
+                                    &instances,
                                 );
-                                if _component.visible {
-                                    render_pass.draw_light_model(
-                                        &light.cube_model,
-                                        camera.bind_group(),
-                                        light.bind_group(),
-                                    );
-                                }
+                                buffer
                             }
+                            Entry::Vacant(entry) => entry.insert(TypedBuffer::new(
+                                &graphics.shared.device,
+                                wgpu::BufferUsages::VERTEX,
+                                &instances,
+                                Some("Batched Instance Buffer"),
+                            )),
+                        };
+
+                        {
+                            // normal model rendering
+                            let mut render_pass = graphics.continue_pass();
+                            render_pass.set_pipeline(pipeline_for(&shader_path));
+
+                            render_pass.set_vertex_buffer(1, instance_buffer.slice());
+                            self.scene_stats
+                                .record_model(&model, instances.len() as u32);
+                            render_pass.draw_model_instanced(
+                                &model,
+                                0..instances.len() as u32,
+                                camera.bind_group(),
+                                self.light_manager.bind_group(),
+                            );
+                        }
+
+                        if std::ptr::eq(pipeline_for(&shader_path), pipeline) {
+                            default_shader_batches.push((
+                                model.clone(),
+                                (model_ptr, shader_path.clone()),
+                                instances.len() as u32,
+                            ));
                         }
-                    }
 
-                    let mut model_batches: HashMap<ModelId, Vec<InstanceRaw>> = HashMap::new();
-                    for renderer in &entities {
-                        let model_ptr = renderer.model_id();
-                        let instance_raw = renderer.instance.to_raw();
-                        model_batches
-                            .entry(model_ptr)
-                            .or_default()
-                            .push(instance_raw);
+                        // // outline rendering
+                        // let has_selected = entities.iter()
+                        //     .any(|e| e.model_id() == model_ptr && e.is_selected);
+                        //
+                        // if has_selected && self.outline_pipeline.is_some() {
+                        //     let outline = self.outline_pipeline.as_ref().unwrap();
+                        //     let mut render_pass = graphics.continue_pass();
+                        //     render_pass.set_pipeline(&outline.pipeline);
+                        //
+                        //     render_pass.set_bind_group(0, &outline.bind_group, &[]);
+                        //     render_pass.set_bind_group(1, camera.bind_group(), &[]);
+                        //
+                        //     render_pass.set_vertex_buffer(1, instance_buffer.slice(..));
+                        //
+                        //     for mesh in &model.meshes {
+                        //         render_pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+                        //         render_pass.set_index_buffer(
+                        //             mesh.index_buffer.slice(..),
+                        //             wgpu::IndexFormat::Uint32,
+                        //         );
+                        //         render_pass.draw_indexed(
+                        //             0..mesh.num_elements,
+                        //             0,
+                        //             0..instances.len() as u32,
+                        //         );
+                        //     }
+                        // }
+                        log_once::debug_once!("Rendered {:?}", model_ptr);
+                    } else {
+                        log_once::error_once!("No such MODEL as {:?}", model_ptr);
                     }
+                }
+            }
 
-                    for (model_ptr, instances) in model_batches {
-                        {
-                            let model_opt = {
-                                let cache = MODEL_CACHE.lock();
-                                cache.values().find(|m| m.id == model_ptr).cloned()
-                            };
+            if let Some(morph_system) = &self.morph_system {
+                for (entity, model, instance_raw, shader_path) in &morphed {
+                    let instance_buffer = match self.morphed_instance_buffers.entry(*entity) {
+                        Entry::Occupied(entry) => {
+                            let buffer = entry.into_mut();
+                            buffer.update(
+                                &graphics.shared.device,
+                                &graphics.shared.queue,
+                                &[*instance_raw],
+                            );
+                            buffer
+                        }
+                        Entry::Vacant(entry) => entry.insert(TypedBuffer::new(
+                            &graphics.shared.device,
+                            wgpu::BufferUsages::VERTEX,
+                            &[*instance_raw],
+                            Some("Morphed Instance Buffer"),
+                        )),
+                    };
 
-                            if let Some(model) = model_opt {
-                                let instance_buffer = graphics.shared.device.create_buffer_init(
-                                    &wgpu::util::BufferInitDescriptor {
-                                        label: Some("Batched Instance Buffer"),
-                                        contents: bytemuck::cast_slice(&instances),
-                                        usage: wgpu::BufferUsages::VERTEX,
-                                    },
-                                );
+                    let mut render_pass = graphics.continue_pass();
+                    render_pass.set_pipeline(pipeline_for(*shader_path));
+                    render_pass.set_vertex_buffer(1, instance_buffer.slice());
+                    self.scene_stats.record_model(model, 1);
+                    morph_system.draw_model_instanced(
+                        &mut render_pass,
+                        *entity,
+                        model,
+                        0..1,
+                        camera.bind_group(),
+                        self.light_manager.bind_group(),
+                    );
+                }
+            }
+
+            // occlusion only comes from the opaque models, so it goes before anything blended
+            if let Some(ssao) = &mut self.ssao {
+                let draw_calls = ssao.render(&graphics.shared, &mut graphics.frame, &camera);
+                self.scene_stats.record_draw_calls(draw_calls);
 
-                                {
-                                    // normal model rendering
-                                    let mut render_pass = graphics.continue_pass();
-                                    render_pass.set_pipeline(pipeline);
-
-                                    render_pass.set_vertex_buffer(1, instance_buffer.slice(..));
-                                    render_pass.draw_model_instanced(
-                                        &model,
-                                        0..instances.len() as u32,
-                                        camera.bind_group(),
-                                        self.light_manager.bind_group(),
-                                    );
-                                }
-
-                                // // outline rendering
-                                // let has_selected = entities.iter()
-                                //     .any(|e| e.model_id() == model_ptr && e.is_selected);
-                                //
-                                // if has_selected && self.outline_pipeline.is_some() {
-                                //     let outline = self.outline_pipeline.as_ref().unwrap();
-                                //     let mut render_pass = graphics.continue_pass();
-                                //     render_pass.set_pipeline(&outline.pipeline);
-                                //
-                                //     render_pass.set_bind_group(0, &outline.bind_group, &[]);
-                                //     render_pass.set_bind_group(1, camera.bind_group(), &[]);
-                                //
-                                //     render_pass.set_vertex_buffer(1, instance_buffer.slice(..));
-                                //
-                                //     for mesh in &model.meshes {
-                                //         render_pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
-                                //         render_pass.set_index_buffer(
-                                //             mesh.index_buffer.slice(..),
-                                //             wgpu::IndexFormat::Uint32,
-                                //         );
-                                //         render_pass.draw_indexed(
-                                //             0..mesh.num_elements,
-                                //             0,
-                                //             0..instances.len() as u32,
-                                //         );
-                                //     }
-                                // }
-                                log_once::debug_once!("Rendered {:?}", model_ptr);
-                            } else {
-                                log_once::error_once!("No such MODEL as {:?}", model_ptr);
+                let mut render_pass = graphics.continue_pass();
+                if ssao.begin_ambient_pass(&mut render_pass) {
+                    for (model, key, instance_count) in &default_shader_batches {
+                        let Some(instance_buffer) = self.instance_buffers.get(key) else {
+                            continue;
+                        };
+                        render_pass.set_vertex_buffer(1, instance_buffer.slice());
+                        self.scene_stats.record_model(model, *instance_count);
+                        render_pass.draw_model_instanced(
+                            model,
+                            0..*instance_count,
+                            camera.bind_group(),
+                            self.light_manager.bind_group(),
+                        );
+                    }
+
+                    if let Some(morph_system) = &self.morph_system {
+                        for (entity, model, _, shader_path) in &morphed {
+                            let Some(instance_buffer) = self.morphed_instance_buffers.get(entity)
+                            else {
+                                continue;
+                            };
+                            if !std::ptr::eq(pipeline_for(*shader_path), pipeline) {
+                                continue;
                             }
+                            render_pass.set_vertex_buffer(1, instance_buffer.slice());
+                            self.scene_stats.record_model(model, 1);
+                            morph_system.draw_model_instanced(
+                                &mut render_pass,
+                                *entity,
+                                model,
+                                0..1,
+                                camera.bind_group(),
+                                self.light_manager.bind_group(),
+                            );
                         }
                     }
-                } else {
-                    log_once::error_once!("Camera returned None");
                 }
-            } else {
-                log_once::error_once!("No active camera found");
             }
-        } else {
-            if self.is_world_loaded.is_fully_loaded() {
-                log_once::warn_once!("No render pipeline exists");
-            } else {
-                log_once::debug_once!("No render pipeline exists, but world not loaded yet");
+
+            if let Some(particle_system) = &self.particle_system {
+                let mut render_pass = graphics.continue_pass();
+                let draw_calls = particle_system.render(&mut render_pass, &camera);
+                self.scene_stats.record_draw_calls(draw_calls);
             }
+
+            if let Some(sprite_pipeline) = &self.sprite_pipeline {
+                let mut render_pass = graphics.continue_pass();
+                let draw_calls = sprite_pipeline.render(&mut render_pass, &camera);
+                self.scene_stats.record_draw_calls(draw_calls);
+            }
+
+            // blurs everything drawn so far, so it goes after the other passes
+            if let Some(depth_of_field) = &mut self.depth_of_field {
+                let focus_distance = depth_of_field::depth_of_field()
+                    .and_then(|settings| settings.focus_entity)
+                    .and_then(|label| {
+                        self.world
+                            .query::<(&Label, &EntityTransform)>()
+                            .iter()
+                            .find(|(_, (entity_label, _))| entity_label.as_str() == label)
+                            .map(|(_, (_, transform))| {
+                                transform.sync().position.distance(camera.eye) as f32
+                            })
+                    });
+                let draw_calls = depth_of_field.render(
+                    &graphics.shared,
+                    &mut graphics.frame,
+                    &camera,
+                    focus_distance,
+                );
+                self.scene_stats.record_draw_calls(draw_calls);
+            }
+
+            // the curve is meant for the final colours, so it goes after every other effect
+            if let Some(tone_mapping) = &mut self.tone_mapping {
+                let draw_calls = tone_mapping.render(&graphics.shared, &mut graphics.frame);
+                self.scene_stats.record_draw_calls(draw_calls);
+            }
+        } else {
+            log_once::error_once!("Camera returned None");
         }
     }
 
-    fn exit(&mut self, _event_loop: &ActiveEventLoop) {}
+    /// Draws each view of [`ViewportLayout::Quad`] besides the active camera into its own target.
+    fn render_sub_viewports(&mut self, graphics: &mut RenderContext, color: Color) {
+        for index in 0..SUB_VIEWPORT_COUNT {
+            let Some(camera) = self.sub_viewports[index].camera else {
+                continue;
+            };
+            if !self.world.contains(camera) {
+                self.sub_viewports[index].camera = None;
+                continue;
+            }
 
-    fn run_command(&mut self) -> SceneCommand {
-        std::mem::replace(&mut self.scene_command, SceneCommand::None)
+            let sub_viewport = &mut self.sub_viewports[index];
+            sub_viewport.prepare_target(&graphics.shared, &format!("sub viewport {}", index + 1));
+            // taken out while drawing, since drawing borrows the rest of the editor
+            let Some(target) = sub_viewport.target.take() else {
+                continue;
+            };
+            self.render_view(&mut graphics.with_target(&target), camera, color);
+            self.sub_viewports[index].target = Some(target);
+        }
     }
 }
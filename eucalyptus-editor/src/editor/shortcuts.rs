@@ -0,0 +1,284 @@
+//! Editor wide keyboard shortcuts, and the cheat sheet that lists them.
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use winit::keyboard::KeyCode;
+
+/// An action that can be triggered from anywhere in the editor with a [`KeyBinding`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EditorAction {
+    Save,
+    SaveAndQuit,
+    Copy,
+    Paste,
+    Duplicate,
+    Undo,
+    Redo,
+    Play,
+    Delete,
+    ToggleDebugCamera,
+}
+
+impl EditorAction {
+    /// Every action, in the order they are listed in the cheat sheet.
+    pub const ALL: [EditorAction; 10] = [
+        EditorAction::Save,
+        EditorAction::SaveAndQuit,
+        EditorAction::Copy,
+        EditorAction::Paste,
+        EditorAction::Duplicate,
+        EditorAction::Undo,
+        EditorAction::Redo,
+        EditorAction::Play,
+        EditorAction::Delete,
+        EditorAction::ToggleDebugCamera,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EditorAction::Save => "Save",
+            EditorAction::SaveAndQuit => "Save and Quit",
+            EditorAction::Copy => "Copy",
+            EditorAction::Paste => "Paste",
+            EditorAction::Duplicate => "Duplicate",
+            EditorAction::Undo => "Undo",
+            EditorAction::Redo => "Redo",
+            EditorAction::Play => "Play",
+            EditorAction::Delete => "Delete",
+            EditorAction::ToggleDebugCamera => "Toggle Debug Camera",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            EditorAction::Save => "Saves the project and the active scene",
+            EditorAction::SaveAndQuit => "Saves the project, then closes the editor",
+            EditorAction::Copy => "Copies the selected entity in the entity list",
+            EditorAction::Paste => "Pastes the copied entity into the scene",
+            EditorAction::Duplicate => "Copies the selected entity with all of its components",
+            EditorAction::Undo => "Undoes the last action",
+            EditorAction::Redo => "Redoes the last undone action",
+            EditorAction::Play => "Builds the scripts and starts play mode",
+            EditorAction::Delete => "Deletes the selected entity in the entity list",
+            EditorAction::ToggleDebugCamera => "Switches between the debug and player camera",
+        }
+    }
+}
+
+/// A key, along with the modifiers that have to be held for it.
+///
+/// `ctrl` is the command key on macOS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    pub key: KeyCode,
+    pub ctrl: bool,
+    pub shift: bool,
+}
+
+impl KeyBinding {
+    pub const fn new(key: KeyCode) -> Self {
+        Self {
+            key,
+            ctrl: false,
+            shift: false,
+        }
+    }
+
+    pub const fn ctrl(key: KeyCode) -> Self {
+        Self {
+            key,
+            ctrl: true,
+            shift: false,
+        }
+    }
+
+    pub const fn ctrl_shift(key: KeyCode) -> Self {
+        Self {
+            key,
+            ctrl: true,
+            shift: true,
+        }
+    }
+
+    /// Checks if the binding is triggered by a key press with the given modifiers held.
+    pub fn matches(&self, key: KeyCode, ctrl: bool, shift: bool) -> bool {
+        self.key == key && self.ctrl == ctrl && self.shift == shift
+    }
+}
+
+impl Display for KeyBinding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.ctrl {
+            if cfg!(target_os = "macos") {
+                write!(f, "Cmd+")?;
+            } else {
+                write!(f, "Ctrl+")?;
+            }
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        write!(f, "{}", key_name(self.key))
+    }
+}
+
+/// The shortcuts the editor starts with.
+pub fn default_shortcuts() -> HashMap<EditorAction, KeyBinding> {
+    HashMap::from([
+        (EditorAction::Save, KeyBinding::ctrl(KeyCode::KeyS)),
+        (EditorAction::SaveAndQuit, KeyBinding::ctrl(KeyCode::KeyQ)),
+        (EditorAction::Copy, KeyBinding::ctrl(KeyCode::KeyC)),
+        (EditorAction::Paste, KeyBinding::ctrl(KeyCode::KeyV)),
+        (EditorAction::Duplicate, KeyBinding::ctrl(KeyCode::KeyD)),
+        (EditorAction::Undo, KeyBinding::ctrl(KeyCode::KeyZ)),
+        (EditorAction::Redo, KeyBinding::ctrl_shift(KeyCode::KeyZ)),
+        (EditorAction::Play, KeyBinding::ctrl(KeyCode::KeyP)),
+        (EditorAction::Delete, KeyBinding::new(KeyCode::Delete)),
+        (
+            EditorAction::ToggleDebugCamera,
+            KeyBinding::new(KeyCode::F1),
+        ),
+    ])
+}
+
+/// Keys that only do something while the viewport is focused, as `(action, shortcut, description)`.
+///
+/// These depend on the viewport mode, so they are handled directly in the editor's input code
+/// instead of going through an [`EditorAction`].
+pub const VIEWPORT_SHORTCUTS: &[(&str, &str, &str)] = &[
+    (
+        "Gizmo Mode",
+        "G",
+        "Shows the transform gizmo for the selected entity",
+    ),
+    (
+        "Snap to Ground",
+        "G",
+        "Drops the selected entity onto whatever is below it while in gizmo mode",
+    ),
+    (
+        "Camera Move Mode",
+        "F",
+        "Captures the mouse to fly the camera around",
+    ),
+    (
+        "Measure Mode",
+        "M",
+        "Measures the distance between two clicked points, press again to clear",
+    ),
+    (
+        "Exit Mode",
+        "Escape",
+        "Leaves the current viewport mode, press twice to deselect",
+    ),
+    (
+        "Translate",
+        "Z",
+        "Switches the gizmo to translation while in gizmo mode",
+    ),
+    (
+        "Rotate",
+        "X",
+        "Switches the gizmo to rotation while in gizmo mode",
+    ),
+    (
+        "Scale",
+        "C",
+        "Switches the gizmo to scaling while in gizmo mode",
+    ),
+    (
+        "Local Orientation",
+        "L",
+        "Aligns the gizmo to the entity while in gizmo mode",
+    ),
+    (
+        "Global Orientation",
+        "W",
+        "Aligns the gizmo to the world while in gizmo mode",
+    ),
+];
+
+fn key_name(key: KeyCode) -> String {
+    let name = format!("{:?}", key);
+    name.strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Digit"))
+        .unwrap_or(&name)
+        .to_string()
+}
+
+/// Shows the keyboard shortcut cheat sheet.
+pub fn show_cheat_sheet(
+    ctx: &egui::Context,
+    open: &mut bool,
+    search: &mut String,
+    shortcuts: &HashMap<EditorAction, KeyBinding>,
+) {
+    egui::Window::new("Keyboard Shortcuts")
+        .collapsible(false)
+        .resizable(true)
+        .default_width(520.0)
+        .open(open)
+        .show(ctx, |ui| {
+            ui.add(
+                egui::TextEdit::singleline(search)
+                    .hint_text("Search shortcuts...")
+                    .desired_width(f32::INFINITY),
+            );
+            ui.separator();
+
+            let query = search.to_lowercase();
+            let matches = |action: &str, shortcut: &str, description: &str| {
+                query.is_empty()
+                    || action.to_lowercase().contains(&query)
+                    || shortcut.to_lowercase().contains(&query)
+                    || description.to_lowercase().contains(&query)
+            };
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                egui::Grid::new("editor_shortcuts")
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("Action");
+                        ui.strong("Shortcut");
+                        ui.strong("Description");
+                        ui.end_row();
+
+                        for action in EditorAction::ALL {
+                            let Some(binding) = shortcuts.get(&action) else {
+                                continue;
+                            };
+                            let shortcut = binding.to_string();
+                            if !matches(action.name(), &shortcut, action.description()) {
+                                continue;
+                            }
+
+                            ui.label(action.name());
+                            ui.monospace(shortcut);
+                            ui.label(action.description());
+                            ui.end_row();
+                        }
+                    });
+
+                ui.add_space(8.0);
+                ui.heading("Viewport Controls");
+                ui.separator();
+
+                egui::Grid::new("viewport_shortcuts")
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (action, shortcut, description) in VIEWPORT_SHORTCUTS {
+                            if !matches(action, shortcut, description) {
+                                continue;
+                            }
+
+                            ui.label(*action);
+                            ui.monospace(*shortcut);
+                            ui.label(*description);
+                            ui.end_row();
+                        }
+                    });
+            });
+        });
+}
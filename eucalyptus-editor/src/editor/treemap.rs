@@ -0,0 +1,114 @@
+//! A treemap of the build output, showing which scenes and assets take up the most space.
+
+use crate::build::{DependencyKind, DependencyNode};
+use dropbear_engine::stats::format_bytes;
+use egui::{Color32, Rect, Sense, Stroke, StrokeKind};
+
+/// Space left around the children of a node, so the rectangle of their parent shows.
+const PADDING: f32 = 3.0;
+
+/// Draws `root` as a treemap filling the available width, where the area of every rectangle is
+/// the total size of its asset. Hovering over a rectangle shows its name and size.
+pub fn show_treemap(ui: &mut egui::Ui, root: &DependencyNode, height: f32) {
+    let (rect, response) =
+        ui.allocate_exact_size(egui::vec2(ui.available_width(), height), Sense::hover());
+    let painter = ui.painter_at(rect);
+
+    let mut hovered = None;
+    layout(root, rect, 0, &mut |node, rect, depth| {
+        let fill = colour_of(node.kind).gamma_multiply(1.0 - 0.15 * depth as f32);
+        painter.rect(
+            rect,
+            2.0,
+            fill,
+            Stroke::new(1.0, Color32::from_black_alpha(120)),
+            StrokeKind::Inside,
+        );
+
+        // only the leaves are big enough to be worth labelling
+        if node.children.is_empty() && rect.width() > 60.0 && rect.height() > 16.0 {
+            let name = node.name.rsplit('/').next().unwrap_or(&node.name);
+            painter.text(
+                rect.left_top() + egui::vec2(4.0, 2.0),
+                egui::Align2::LEFT_TOP,
+                name,
+                egui::FontId::proportional(11.0),
+                Color32::WHITE,
+            );
+        }
+
+        if let Some(pointer) = response.hover_pos()
+            && rect.contains(pointer)
+        {
+            // deeper nodes are drawn later, so the innermost one under the pointer wins
+            hovered = Some(node);
+        }
+    });
+
+    if let Some(node) = hovered {
+        response.on_hover_text(format!(
+            "{}\n{}",
+            node.name,
+            format_bytes(node.total_size())
+        ));
+    }
+}
+
+/// Lays out `node` in `rect`, then splits the rest of `rect` between its children by their size,
+/// alternating between rows and columns with every level.
+fn layout<'a>(
+    node: &'a DependencyNode,
+    rect: Rect,
+    depth: usize,
+    visit: &mut impl FnMut(&'a DependencyNode, Rect, usize),
+) {
+    visit(node, rect, depth);
+
+    let total = node.total_size();
+    if node.children.is_empty() || total == 0 {
+        return;
+    }
+
+    let inner = rect.shrink(PADDING);
+    if inner.width() <= 0.0 || inner.height() <= 0.0 {
+        return;
+    }
+
+    let horizontal = depth % 2 == 0;
+    let length = if horizontal {
+        inner.width()
+    } else {
+        inner.height()
+    };
+    // the node's own size is left as empty space at the end
+    let mut offset = 0.0;
+    for child in &node.children {
+        let share = child.total_size() as f32 / total as f32 * length;
+        if share < 1.0 {
+            continue;
+        }
+
+        let child_rect = if horizontal {
+            Rect::from_min_size(
+                inner.min + egui::vec2(offset, 0.0),
+                egui::vec2(share, inner.height()),
+            )
+        } else {
+            Rect::from_min_size(
+                inner.min + egui::vec2(0.0, offset),
+                egui::vec2(inner.width(), share),
+            )
+        };
+        layout(child, child_rect, depth + 1, visit);
+        offset += share;
+    }
+}
+
+fn colour_of(kind: DependencyKind) -> Color32 {
+    match kind {
+        DependencyKind::Package => Color32::from_rgb(60, 60, 70),
+        DependencyKind::Scene => Color32::from_rgb(70, 110, 160),
+        DependencyKind::Model => Color32::from_rgb(90, 150, 90),
+        DependencyKind::Texture => Color32::from_rgb(170, 120, 60),
+    }
+}
@@ -0,0 +1,261 @@
+//! Checks run on the scene before play mode starts, to catch problems that would otherwise crash
+//! or confuse the game once it is running.
+
+use super::{Editor, EditorState, Signal};
+use dropbear_engine::asset::ASSET_REGISTRY;
+use dropbear_engine::camera::Camera;
+use dropbear_engine::entity::{EntityTransform, MeshRenderer, Transform};
+use dropbear_engine::utils::ResourceReferenceType;
+use egui::{Context, RichText};
+use eucalyptus_core::camera::CameraComponent;
+use eucalyptus_core::states::{Label, PROJECT, Script};
+use eucalyptus_core::utils::ResolveReference;
+use glam::DVec3;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+/// How serious a [`SceneValidationIssue`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IssueSeverity {
+    /// The scene can still be played, but probably won't behave as expected
+    Warning,
+    /// Playing the scene would fail, so play mode is not started
+    Error,
+}
+
+/// A problem with the scene found by [`Editor::validate_scene_for_play`].
+#[derive(Debug, Clone)]
+pub struct SceneValidationIssue {
+    pub severity: IssueSeverity,
+    /// The entity the issue is about, if it is about a single one
+    pub entity: Option<hecs::Entity>,
+    pub message: String,
+}
+
+impl SceneValidationIssue {
+    fn error(entity: Option<hecs::Entity>, message: impl Into<String>) -> Self {
+        Self {
+            severity: IssueSeverity::Error,
+            entity,
+            message: message.into(),
+        }
+    }
+
+    fn warning(entity: Option<hecs::Entity>, message: impl Into<String>) -> Self {
+        Self {
+            severity: IssueSeverity::Warning,
+            entity,
+            message: message.into(),
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == IssueSeverity::Error
+    }
+}
+
+impl Display for SceneValidationIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.severity {
+            IssueSeverity::Warning => write!(f, "Warning: {}", self.message),
+            IssueSeverity::Error => write!(f, "Error: {}", self.message),
+        }
+    }
+}
+
+impl Editor {
+    /// Checks that the scene can be played, returning every problem found with the errors first.
+    ///
+    /// This checks for a starting camera, that every model is loaded and its file exists, that
+    /// scripted entities have Kotlin sources to run, and that no entity has a negative scale,
+    /// which flips the winding order of its faces.
+    pub fn validate_scene_for_play(&self) -> Vec<SceneValidationIssue> {
+        let mut issues = Vec::new();
+        let name_of = |entity: hecs::Entity| {
+            self.world
+                .get::<&Label>(entity)
+                .map(|l| l.to_string())
+                .unwrap_or_else(|_| format!("{:?}", entity))
+        };
+
+        let has_starting_camera = self
+            .world
+            .query::<(&Camera, &CameraComponent)>()
+            .iter()
+            .any(|(_, (_, comp))| comp.starting_camera);
+        if !has_starting_camera {
+            issues.push(SceneValidationIssue::error(
+                None,
+                "No camera is set as the starting camera",
+            ));
+        }
+
+        for (entity, renderer) in self.world.query::<&MeshRenderer>().iter() {
+            let handle = renderer.asset_handle();
+            if ASSET_REGISTRY.get_model(handle).is_none() {
+                issues.push(SceneValidationIssue::error(
+                    Some(entity),
+                    format!("'{}' uses a model that is not loaded", name_of(entity)),
+                ));
+                continue;
+            }
+
+            let model = renderer.model();
+            if let ResourceReferenceType::File(_) = &model.path.ref_type {
+                match model.path.resolve() {
+                    Ok(path) if path.exists() => {}
+                    Ok(path) => issues.push(SceneValidationIssue::error(
+                        Some(entity),
+                        format!(
+                            "The model of '{}' is missing: {}",
+                            name_of(entity),
+                            path.display()
+                        ),
+                    )),
+                    Err(e) => issues.push(SceneValidationIssue::error(
+                        Some(entity),
+                        format!("The model of '{}' cannot be found: {}", name_of(entity), e),
+                    )),
+                }
+            }
+        }
+
+        let mut scripted = Vec::new();
+        for (entity, script) in self.world.query::<&Script>().iter() {
+            if script.tags.is_empty() {
+                issues.push(SceneValidationIssue::warning(
+                    Some(entity),
+                    format!(
+                        "'{}' has a script without any tags, so nothing runs",
+                        name_of(entity)
+                    ),
+                ));
+            }
+            scripted.push(entity);
+        }
+
+        if !scripted.is_empty() {
+            let sources = PROJECT.read().project_path.join("src");
+            if !contains_kotlin_sources(&sources) {
+                issues.push(SceneValidationIssue::error(
+                    scripted.first().copied(),
+                    format!(
+                        "{} entities have scripts, but there are no Kotlin sources in {}",
+                        scripted.len(),
+                        sources.display()
+                    ),
+                ));
+            } else if self.last_build_error.is_some() {
+                issues.push(SceneValidationIssue::warning(
+                    None,
+                    "The last build of the scripts failed, they will be built again",
+                ));
+            }
+        }
+
+        for (entity, (transform, entity_transform)) in self
+            .world
+            .query::<(Option<&Transform>, Option<&EntityTransform>)>()
+            .iter()
+        {
+            let negative = transform.is_some_and(|t| has_negative_scale(t.scale))
+                || entity_transform.is_some_and(|t| {
+                    has_negative_scale(t.local().scale) || has_negative_scale(t.world().scale)
+                });
+            if negative {
+                issues.push(SceneValidationIssue::warning(
+                    Some(entity),
+                    format!(
+                        "'{}' has a negative scale, so its faces will be culled inside out",
+                        name_of(entity)
+                    ),
+                ));
+            }
+        }
+
+        issues.sort_by(|a, b| b.severity.cmp(&a.severity));
+        issues
+    }
+
+    /// Shows the issues found before playing, blocking play mode if any of them are errors.
+    pub(super) fn show_play_validation(&mut self, ctx: &Context) {
+        if self.play_validation_issues.is_empty() {
+            return;
+        }
+
+        let has_errors = self.play_validation_issues.iter().any(|i| i.is_error());
+        let mut close = false;
+        let mut play_anyway = false;
+        let mut select = None;
+
+        let modal = egui::Modal::new(egui::Id::new("play_validation")).show(ctx, |ui| {
+            ui.heading(if has_errors {
+                "Unable to play the scene"
+            } else {
+                "The scene has problems"
+            });
+            ui.add_space(4.0);
+
+            egui::ScrollArea::vertical()
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    for issue in &self.play_validation_issues {
+                        ui.horizontal(|ui| {
+                            let (icon, color) = match issue.severity {
+                                IssueSeverity::Error => ("⛔", ui.visuals().error_fg_color),
+                                IssueSeverity::Warning => ("⚠", ui.visuals().warn_fg_color),
+                            };
+                            ui.label(RichText::new(icon).color(color));
+                            ui.label(&issue.message);
+                            if let Some(entity) = issue.entity
+                                && ui.small_button("Select").clicked()
+                            {
+                                select = Some(entity);
+                            }
+                        });
+                    }
+                });
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                if !has_errors && ui.button("Play Anyway").clicked() {
+                    play_anyway = true;
+                }
+                if ui.button("Close").clicked() {
+                    close = true;
+                }
+            });
+        });
+
+        if let Some(entity) = select {
+            self.selected_entity = Some(entity);
+            close = true;
+        }
+
+        if play_anyway && matches!(self.editor_state, EditorState::Editing) {
+            self.play_validation_issues.clear();
+            self.skip_play_validation = true;
+            self.signal = Signal::Play;
+        } else if close || modal.should_close() {
+            self.play_validation_issues.clear();
+        }
+    }
+}
+
+fn has_negative_scale(scale: DVec3) -> bool {
+    scale.cmplt(DVec3::ZERO).any()
+}
+
+/// Checks if there is a Kotlin file anywhere under `dir`.
+fn contains_kotlin_sources(dir: &Path) -> bool {
+    walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .any(|entry| {
+            entry.file_type().is_file()
+                && entry
+                    .path()
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("kt"))
+        })
+}
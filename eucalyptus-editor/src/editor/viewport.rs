@@ -0,0 +1,200 @@
+//! Splitting the viewport tab into a 2×2 grid of views, each looking through its own camera.
+//!
+//! The top left view always shows the active camera and renders into the main viewport texture.
+//! The other three views render into their own [`ViewportTarget`]s.
+
+use dropbear_engine::camera::Camera;
+use dropbear_engine::graphics::{SharedGraphicsContext, ViewportTarget};
+use eucalyptus_core::states::{Label, PROJECT, SnapSettings};
+use hecs::{Entity, World};
+
+/// The number of views that are not the main viewport.
+pub const SUB_VIEWPORT_COUNT: usize = 3;
+
+/// How the viewport tab is split up.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewportLayout {
+    /// A single view through the active camera
+    #[default]
+    Single,
+    /// Four views in a 2×2 grid, with the active camera in the top left
+    Quad,
+}
+
+impl ViewportLayout {
+    /// Switches between [`ViewportLayout::Single`] and [`ViewportLayout::Quad`].
+    pub fn toggle(&mut self) {
+        *self = match self {
+            ViewportLayout::Single => ViewportLayout::Quad,
+            ViewportLayout::Quad => ViewportLayout::Single,
+        };
+    }
+
+    /// The number of views shown.
+    pub fn count(&self) -> usize {
+        match self {
+            ViewportLayout::Single => 1,
+            ViewportLayout::Quad => 1 + SUB_VIEWPORT_COUNT,
+        }
+    }
+
+    /// Splits `rect` into a cell for each view, then fits an image of `aspect` into the middle of
+    /// each cell.
+    pub fn image_rects(&self, rect: egui::Rect, aspect: f32) -> Vec<egui::Rect> {
+        let cells = match self {
+            ViewportLayout::Single => vec![rect],
+            ViewportLayout::Quad => {
+                let half = rect.size() / 2.0;
+                [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0)]
+                    .into_iter()
+                    .map(|(x, y)| {
+                        egui::Rect::from_min_size(
+                            rect.min + egui::vec2(half.x * x, half.y * y),
+                            half,
+                        )
+                    })
+                    .collect()
+            }
+        };
+
+        cells
+            .into_iter()
+            .map(|cell| {
+                let size = cell.size();
+                let (width, height) = if size.x / size.y > aspect {
+                    let height = size.y * 0.95;
+                    (height * aspect, height)
+                } else {
+                    let width = size.x * 0.95;
+                    (width, width / aspect)
+                };
+                egui::Rect::from_center_size(cell.center(), egui::vec2(width, height))
+            })
+            .collect()
+    }
+}
+
+/// A view other than the main viewport, shown in [`ViewportLayout::Quad`].
+#[derive(Default)]
+pub struct SubViewport {
+    /// The camera this view looks through. Nothing is rendered if this is `None`.
+    pub camera: Option<Entity>,
+    /// The textures this view renders into, created the first time it is rendered.
+    pub target: Option<ViewportTarget>,
+}
+
+impl SubViewport {
+    /// Creates or resizes the render target so it matches the main viewport texture.
+    pub fn prepare_target(&mut self, graphics: &SharedGraphicsContext, label: &str) {
+        let size = graphics.viewport_texture.size;
+        match &mut self.target {
+            Some(target) if target.size() == size => {}
+            Some(target) => target.resize(graphics, size, label),
+            None => self.target = Some(ViewportTarget::new(graphics, size, label)),
+        }
+    }
+}
+
+/// A combo box for picking any camera in the world.
+pub fn camera_picker(
+    ui: &mut egui::Ui,
+    id: impl std::hash::Hash,
+    world: &World,
+    camera: &mut Option<Entity>,
+) {
+    let label_of = |entity: Entity| {
+        world
+            .get::<&Label>(entity)
+            .map(|label| label.as_str().to_string())
+            .unwrap_or_else(|_| format!("Camera {}", entity.id()))
+    };
+
+    let selected = camera
+        .map(label_of)
+        .unwrap_or_else(|| "No camera".to_string());
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(selected)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(camera, None, "No camera");
+            for (entity, _) in world.query::<&Camera>().iter() {
+                ui.selectable_value(camera, Some(entity), label_of(entity));
+            }
+        });
+}
+
+/// The toolbar above the viewport for changing how the gizmo snaps and which bounding boxes are
+/// drawn.
+///
+/// Changes are written straight to the project's [`SnapSettings`] and editor settings.
+pub fn toolbar(ui: &mut egui::Ui) {
+    let (mut snap, mut always_show_aabbs) = {
+        let project = PROJECT.read();
+        (
+            project.editor_settings.snap,
+            project.editor_settings.always_show_aabbs,
+        )
+    };
+    let before = (snap, always_show_aabbs);
+
+    ui.horizontal(|ui| {
+        let grid = ui
+            .selectable_label(snap.is_enabled(), "▦")
+            .on_hover_text("Snap to grid (hold Ctrl to flip while dragging)");
+        if grid.clicked() {
+            snap = if snap.is_enabled() {
+                SnapSettings::default()
+            } else {
+                SnapSettings::default().toggled()
+            };
+        }
+
+        ui.separator();
+
+        for (name, value, default, suffix) in [
+            (
+                "Move",
+                &mut snap.position_snap,
+                SnapSettings::DEFAULT_POSITION_SNAP,
+                "",
+            ),
+            (
+                "Rotate",
+                &mut snap.rotation_snap,
+                SnapSettings::DEFAULT_ROTATION_SNAP,
+                "°",
+            ),
+            (
+                "Scale",
+                &mut snap.scale_snap,
+                SnapSettings::DEFAULT_SCALE_SNAP,
+                "",
+            ),
+        ] {
+            if ui.selectable_label(value.is_some(), name).clicked() {
+                *value = match value {
+                    Some(_) => None,
+                    None => Some(default),
+                };
+            }
+            if let Some(step) = value {
+                ui.add(
+                    egui::DragValue::new(step)
+                        .speed(0.01)
+                        .range(0.001..=f64::MAX)
+                        .suffix(suffix),
+                );
+            }
+        }
+
+        ui.separator();
+
+        ui.toggle_value(&mut always_show_aabbs, "⬚")
+            .on_hover_text("Always Show AABBs");
+    });
+
+    if (snap, always_show_aabbs) != before {
+        let mut project = PROJECT.write();
+        project.editor_settings.snap = snap;
+        project.editor_settings.always_show_aabbs = always_show_aabbs;
+    }
+}
@@ -0,0 +1,69 @@
+//! Watches the project's scene files for changes made outside of the editor.
+
+use crossbeam_channel::{Receiver, unbounded};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+
+/// Watches the `scenes` folder of a project and collects every `.eucs` file that is created or
+/// modified.
+pub struct SceneWatcher {
+    project_path: PathBuf,
+    receiver: Receiver<PathBuf>,
+    // kept alive for as long as the watcher should run
+    _watcher: RecommendedWatcher,
+}
+
+impl SceneWatcher {
+    pub fn new(project_path: &Path) -> anyhow::Result<Self> {
+        let (sender, receiver) = unbounded();
+
+        let mut watcher =
+            notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+                let event = match result {
+                    Ok(event) => event,
+                    Err(e) => {
+                        log::warn!("Scene watcher error: {}", e);
+                        return;
+                    }
+                };
+
+                if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                    return;
+                }
+
+                for path in event.paths {
+                    if path.extension().is_some_and(|ext| ext == "eucs") {
+                        let _ = sender.send(path);
+                    }
+                }
+            })?;
+
+        let scenes_dir = project_path.join("scenes");
+        watcher.watch(&scenes_dir, RecursiveMode::NonRecursive)?;
+        log::debug!("Watching {} for scene changes", scenes_dir.display());
+
+        Ok(Self {
+            project_path: project_path.to_path_buf(),
+            receiver,
+            _watcher: watcher,
+        })
+    }
+
+    /// The project that this watcher was created for.
+    pub fn project_path(&self) -> &Path {
+        &self.project_path
+    }
+
+    /// Returns every scene file that changed since the last poll, without duplicates.
+    ///
+    /// A single save usually fires several events, so they are collapsed into one.
+    pub fn poll(&self) -> Vec<PathBuf> {
+        let mut changed: Vec<PathBuf> = Vec::new();
+        for path in self.receiver.try_iter() {
+            if !changed.contains(&path) {
+                changed.push(path);
+            }
+        }
+        changed
+    }
+}
@@ -1,10 +1,14 @@
 // #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 // note to self: when it becomes release, remember to readd this back
 
-use clap::{Arg, Command};
+use anyhow::Context;
+use clap::{Arg, ArgAction, Command};
 use dropbear_engine::future::FutureQueue;
+use dropbear_engine::tone_mapping::ToneMappingOperator;
 use dropbear_engine::{MutableWindowConfiguration, WindowConfiguration, scene};
 use eucalyptus_core::APP_INFO;
+use eucalyptus_core::config::ProjectConfig;
+use eucalyptus_core::states::PROJECT;
 use eucalyptus_editor::{build, editor, menu};
 use parking_lot::RwLock;
 use std::sync::Arc;
@@ -104,6 +108,13 @@ async fn main() -> anyhow::Result<()> {
         .version(env!("CARGO_PKG_VERSION"))
         .subcommand_required(false)
         .arg_required_else_help(false)
+        .args_conflicts_with_subcommands(true)
+        .arg(
+            Arg::new("project")
+                .long("project")
+                .help("Opens a .eucp project file straight in the editor, skipping the main menu")
+                .value_name("PROJECT_FILE"),
+        )
         .subcommand(
             Command::new("build")
                 .about("Build a eucalyptus project, but only the .eupak file and its resources")
@@ -122,6 +133,12 @@ async fn main() -> anyhow::Result<()> {
                         .help("Path to the .eupak data file")
                         .value_name("EUPAK_FILE")
                         .required(true)
+                )
+                .arg(
+                    Arg::new("graph")
+                        .long("graph")
+                        .help("Print the asset dependency graph in Graphviz DOT format instead")
+                        .action(ArgAction::SetTrue),
                 ),
         )
         .get_matches();
@@ -147,9 +164,26 @@ async fn main() -> anyhow::Result<()> {
                 None => {log::error!("Eupak file returned none"); std::process::exit(1)},
             };
 
-            build::read(eupak)?;
+            if sub_matches.get_flag("graph") {
+                let graph = build::generate_dependency_graph(&eupak)?;
+                graph.render_to_dot(&mut std::io::stdout().lock())?;
+            } else {
+                build::read(eupak)?;
+            }
         }
         None => {
+            let first_scene = match matches.get_one::<String>("project") {
+                Some(path) => {
+                    let path = PathBuf::from(path);
+                    let project = ProjectConfig::read_from(&path)
+                        .with_context(|| format!("Unable to open project {}", path.display()))?;
+                    log::info!("Loaded project: {:?}", path);
+                    *PROJECT.write() = project;
+                    "editor"
+                }
+                None => "main_menu",
+            };
+
             let config = WindowConfiguration {
                 title: format!(
                     "Eucalyptus, built with dropbear | Version {} on commit {}",
@@ -161,6 +195,11 @@ async fn main() -> anyhow::Result<()> {
                     max_fps: dropbear_engine::App::NO_FPS_CAP,
                 },
                 app_info: APP_INFO,
+                icon: None,
+                preferred_monitor: None,
+                ssao: None,
+                depth_of_field: None,
+                tone_mapping: ToneMappingOperator::default(),
             };
 
             let future_queue = Arc::new(FutureQueue::new());
@@ -181,6 +220,7 @@ async fn main() -> anyhow::Result<()> {
                         main_menu,
                         "main_menu",
                     );
+                    editor.write().input_contexts = input_manager.contexts();
                     scene::add_scene_with_input(
                         &mut scene_manager,
                         &mut input_manager,
@@ -188,7 +228,7 @@ async fn main() -> anyhow::Result<()> {
                         "editor",
                     );
 
-                    scene_manager.switch("main_menu");
+                    scene_manager.switch(first_scene);
 
                     (scene_manager, input_manager)
                 }
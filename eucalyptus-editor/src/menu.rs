@@ -1,3 +1,4 @@
+use crate::editor::dialogs::{self, ConfirmState};
 use anyhow::{Context, anyhow};
 use dropbear_engine::{
     future::{FutureHandle, FutureQueue},
@@ -8,6 +9,7 @@ use dropbear_engine::{
 use egui::{self, FontId, Frame, RichText};
 use egui_toast::{ToastOptions, Toasts};
 use eucalyptus_core::config::ProjectConfig;
+use eucalyptus_core::scene::SceneTemplate;
 use eucalyptus_core::states::PROJECT;
 use git2::Repository;
 use log::{self, debug};
@@ -34,6 +36,7 @@ pub struct MainMenu {
     project_domain: String,
     project_path: Option<PathBuf>,
     project_error: Option<Vec<String>>,
+    scene_template: SceneTemplate,
 
     project_progress_rx: Option<watch::Receiver<ProjectProgress>>,
     show_progress: bool,
@@ -41,6 +44,8 @@ pub struct MainMenu {
     progress_message: String,
 
     project_creation_handle: Option<FutureHandle>,
+    /// Set while the user is asked to confirm creating a project in a folder with files in it
+    confirm_create_project: bool,
 
     toast: Toasts,
     is_in_file_dialogue: bool,
@@ -61,6 +66,7 @@ impl MainMenu {
         let project_name = self.project_name.clone();
         let project_path = self.project_path.clone();
         let project_domain = self.project_domain.clone();
+        let scene_template = self.scene_template;
 
         let (progress_tx, progress_rx) = watch::channel(ProjectProgress::Step {
             progress: 0.0,
@@ -110,10 +116,11 @@ impl MainMenu {
                         "src2" => {
                             log::debug!("Generating project config");
                             let mut config = ProjectConfig::new(project_name.clone(), path);
+                            let result = config.apply_scene_template(scene_template);
                             let _ = config.write_to_all();
                             let mut global = PROJECT.write();
                             *global = config;
-                            Ok(())
+                            result
                         }
                         "setting_config" => {
                             let project_root = path.clone();
@@ -418,6 +425,14 @@ impl Scene for MainMenu {
                     }
                     ui.add_space(10.0);
 
+                    ui.heading("Starting Scene:");
+                    ui.add_space(5.0);
+                    for template in SceneTemplate::ALL {
+                        ui.radio_value(&mut self.scene_template, template, template.name())
+                            .on_hover_text(template.description());
+                    }
+                    ui.add_space(10.0);
+
                     let can_create = self.project_path.is_some() && !self.project_name.is_empty();
                     if ui
                         .add_enabled(
@@ -426,13 +441,47 @@ impl Scene for MainMenu {
                         )
                         .clicked()
                     {
-                        log::info!("Creating new project at {:?}", self.project_path);
-                        self.start_project_creation(graphics.shared.future_queue.clone());
+                        // the folder could already hold another project
+                        if self
+                            .project_path
+                            .as_deref()
+                            .is_some_and(dialogs::folder_has_files)
+                        {
+                            self.confirm_create_project = true;
+                        } else {
+                            log::info!("Creating new project at {:?}", self.project_path);
+                            self.start_project_creation(graphics.shared.future_queue.clone());
+                        }
                     }
                 });
             });
         self.show_new_project = show_new_project;
 
+        if self.confirm_create_project
+            && let Some(path) = self.project_path.clone()
+        {
+            let message = format!(
+                "{} already has files in it, which may be overwritten by the new project.",
+                path.display()
+            );
+            match dialogs::confirm_destructive_action(
+                &egui_ctx,
+                "Folder Not Empty",
+                &message,
+                "Create Anyway",
+            ) {
+                ConfirmState::Pending => {}
+                ConfirmState::Confirmed => {
+                    self.confirm_create_project = false;
+                    log::info!("Creating new project at {:?}", path);
+                    self.start_project_creation(graphics.shared.future_queue.clone());
+                }
+                ConfirmState::Cancelled => {
+                    self.confirm_create_project = false;
+                }
+            }
+        }
+
         if local_select_project {
             log::debug!("Opening folder picker");
             self.is_in_file_dialogue = true;
@@ -1,4 +1,4 @@
-use crate::editor::Editor;
+use crate::editor::{Editor, Signal};
 use app_dirs2::AppDataType;
 use egui::Ui;
 use eucalyptus_core::APP_INFO;
@@ -23,6 +23,15 @@ pub trait EditorPlugin: Send + Sync {
 
 pub type PluginConstructor = fn() -> Box<dyn EditorPlugin>;
 
+/// A game specific panel, such as an AI state viewer or a quest editor, added to the editor
+/// with [`Editor::register_panel`] without having to add a new [`EditorTab`](eucalyptus_core::states::EditorTab).
+///
+/// Registered panels are listed in the Window menu as "Open <name>". While a panel is open,
+/// [`EditorPanel::ui`] is called every frame, and is expected to show its own windows.
+pub trait EditorPanel: 'static {
+    fn ui(&mut self, ctx: &egui::Context, world: &mut hecs::World, signal: &mut Signal);
+}
+
 pub struct PluginRegistry {
     pub plugins: IndexMap<String, Box<dyn EditorPlugin>>,
     loaded_libraries: Vec<lib::Library>,
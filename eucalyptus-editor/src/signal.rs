@@ -1,11 +1,16 @@
-use crate::editor::{Editor, EditorState, PendingSpawnType, Signal};
+use crate::editor::{Editor, EditorState, PendingSpawnType, Signal, UndoableAction};
+use dropbear_engine::asset::ASSET_REGISTRY;
 use dropbear_engine::camera::Camera;
 use dropbear_engine::entity::{EntityTransform, MeshRenderer, Transform};
 use dropbear_engine::graphics::SharedGraphicsContext;
+use dropbear_engine::light_probe::IrradianceBaker;
 use dropbear_engine::lighting::{Light as EngineLight, LightComponent};
+use dropbear_engine::picking::{self, Ray};
 use dropbear_engine::utils::{ResourceReference, ResourceReferenceType};
 use egui::Align2;
 use eucalyptus_core::camera::{CameraComponent, CameraType};
+use eucalyptus_core::hierarchy::Hierarchy;
+use eucalyptus_core::runtime::ResourceRegistry;
 use eucalyptus_core::scene::SceneEntity;
 use eucalyptus_core::scripting::{BuildStatus, build_jvm};
 use eucalyptus_core::spawn::{PendingSpawn, push_pending_spawn};
@@ -14,6 +19,7 @@ use eucalyptus_core::states::{
 };
 use eucalyptus_core::traits::SerializableComponent;
 use eucalyptus_core::{fatal, info, success, success_without_console, warn, warn_without_console};
+use glam::DVec3;
 use std::any::TypeId;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -43,6 +49,116 @@ impl SignalController for Editor {
                 self.signal = Signal::Copy(scene_entity.clone());
                 Ok(())
             }
+            Signal::Duplicate(entity) => {
+                let entity = *entity;
+                self.signal = Signal::None;
+                match self.duplicate_entity(entity, graphics.clone()) {
+                    Ok(duplicate) => {
+                        UndoableAction::push_to_undo(
+                            &mut self.undo_stack,
+                            UndoableAction::Spawn(duplicate),
+                        );
+                        self.selected_entity = Some(duplicate);
+                        success!("Duplicated entity");
+                    }
+                    Err(e) => {
+                        warn!("Unable to duplicate entity: {}", e);
+                    }
+                }
+                Ok(())
+            }
+            Signal::MakeActiveCamera(entity) => {
+                let entity = *entity;
+                self.signal = Signal::None;
+                self.switch_to_camera(entity);
+                Ok(())
+            }
+            Signal::CenterPivot(entity) => {
+                let entity = *entity;
+                self.signal = Signal::None;
+                let Ok((transform, renderer)) = self
+                    .world
+                    .query_one_mut::<(&mut EntityTransform, &MeshRenderer)>(entity)
+                else {
+                    warn!("Unable to center pivot: entity has no model");
+                    return Ok(());
+                };
+                let Some(bounds) = renderer.model().bounds() else {
+                    warn!("Unable to center pivot: model has no bounds");
+                    return Ok(());
+                };
+
+                let old_pivot = transform.pivot();
+                let pivot = -(transform.sync().scale * bounds.center().as_dvec3());
+                if pivot != old_pivot {
+                    transform.set_pivot(pivot);
+                    UndoableAction::push_to_undo(
+                        &mut self.undo_stack,
+                        UndoableAction::Pivot(entity, old_pivot),
+                    );
+                    info!("Centered pivot");
+                }
+                Ok(())
+            }
+            Signal::SnapToGround(entity) => {
+                let entity = *entity;
+                self.signal = Signal::None;
+                let bounds = self
+                    .world
+                    .get::<&MeshRenderer>(entity)
+                    .ok()
+                    .and_then(|renderer| picking::world_bounds(&renderer));
+                let position = if let Ok(transform) = self.world.get::<&EntityTransform>(entity) {
+                    transform.sync().position
+                } else if let Ok(transform) = self.world.get::<&Transform>(entity) {
+                    transform.position
+                } else {
+                    warn!("Unable to snap to ground: entity has no transform");
+                    return Ok(());
+                };
+
+                // cast from the top of the model so one that has sunk into the ground is lifted
+                // back out of it
+                let (origin, bottom) = match bounds {
+                    Some(bounds) => {
+                        let centre = bounds.center().as_dvec3();
+                        (
+                            DVec3::new(centre.x, bounds.max.y as f64, centre.z),
+                            bounds.min.y as f64,
+                        )
+                    }
+                    None => (position, position.y),
+                };
+                let ray = Ray::new(origin, DVec3::NEG_Y);
+                let ground = picking::raycast_world_filtered(&ray, &self.world, |other| {
+                    other != entity && !Hierarchy::is_descendant_of(&self.world, other, entity)
+                })
+                .map(|(_, distance)| ray.at(distance).y)
+                .unwrap_or(0.0);
+
+                let offset = ground - bottom;
+                if offset.abs() < f64::EPSILON {
+                    return Ok(());
+                }
+
+                if let Ok(transform) = self.world.query_one_mut::<&mut EntityTransform>(entity) {
+                    let old = *transform;
+                    transform.world_mut().position.y += offset;
+                    UndoableAction::push_to_undo(
+                        &mut self.undo_stack,
+                        UndoableAction::EntityTransform(entity, old),
+                    );
+                } else if let Ok(transform) = self.world.query_one_mut::<&mut Transform>(entity) {
+                    let old = *transform;
+                    transform.position.y += offset;
+                    UndoableAction::push_to_undo(
+                        &mut self.undo_stack,
+                        UndoableAction::Transform(entity, old),
+                    );
+                }
+                info!("Snapped to ground at y = {:.3}", ground);
+                Ok(())
+            }
             Signal::Delete => {
                 if let Some(sel_e) = &self.selected_entity {
                     let is_viewport_cam =
@@ -79,19 +195,67 @@ impl SignalController for Editor {
                 }
             }
             Signal::Undo => {
-                if let Some(action) = self.undo_stack.pop() {
-                    match action.undo(&mut self.world) {
-                        Ok(_) => {
-                            info!("Undid action");
-                        }
-                        Err(e) => {
+                match self.undo_last() {
+                    Some(Ok(())) => {
+                        info!("Undid action");
+                    }
+                    Some(Err(e)) => {
+                        warn!("Failed to undo action: {}", e);
+                    }
+                    None => {
+                        warn_without_console!("Nothing to undo");
+                        log::debug!("No undoable actions in stack");
+                    }
+                }
+                self.signal = Signal::None;
+                Ok(())
+            }
+            Signal::Redo => {
+                match self.redo_last() {
+                    Some(Ok(())) => {
+                        info!("Redid action");
+                    }
+                    Some(Err(e)) => {
+                        warn!("Failed to redo action: {}", e);
+                    }
+                    None => {
+                        warn_without_console!("Nothing to redo");
+                    }
+                }
+                self.signal = Signal::None;
+                Ok(())
+            }
+            Signal::UndoTo(len) => {
+                let len = *len;
+                let mut undone = 0;
+                while self.undo_stack.len() > len {
+                    match self.undo_last() {
+                        Some(Ok(())) => undone += 1,
+                        Some(Err(e)) => {
                             warn!("Failed to undo action: {}", e);
+                            break;
                         }
+                        None => break,
+                    }
+                }
+                info!("Undid {} actions", undone);
+                self.signal = Signal::None;
+                Ok(())
+            }
+            Signal::RedoTo(len) => {
+                let len = *len;
+                let mut redone = 0;
+                while self.redo_stack.len() > len {
+                    match self.redo_last() {
+                        Some(Ok(())) => redone += 1,
+                        Some(Err(e)) => {
+                            warn!("Failed to redo action: {}", e);
+                            break;
+                        }
+                        None => break,
                     }
-                } else {
-                    warn_without_console!("Nothing to undo");
-                    log::debug!("No undoable actions in stack");
                 }
+                info!("Redid {} actions", redone);
                 self.signal = Signal::None;
                 Ok(())
             }
@@ -102,6 +266,23 @@ impl SignalController for Editor {
                     return Err(anyhow::anyhow!("Unable to play: already in playing mode"));
                 }
 
+                if matches!(self.editor_state, EditorState::Editing)
+                    && !std::mem::take(&mut self.skip_play_validation)
+                {
+                    let issues = self.validate_scene_for_play();
+                    if !issues.is_empty() {
+                        for issue in &issues {
+                            log::warn!("{}", issue);
+                        }
+                        if issues.iter().any(|i| i.is_error()) {
+                            warn!("Unable to play, the scene has errors");
+                        }
+                        self.play_validation_issues = issues;
+                        self.signal = Signal::None;
+                        return Ok(());
+                    }
+                }
+
                 if matches!(self.editor_state, EditorState::Editing) {
                     log::debug!("Starting build process");
                     let (tx, rx) = crossbeam_channel::unbounded();
@@ -157,7 +338,7 @@ impl SignalController for Editor {
                         && alt_pressed
                         && self.input_state.pressed_keys.contains(&KeyCode::KeyP)
                     {
-                        if let Some(handle) = self.handle_created {
+                        if let Some(handle) = self.handle_created.clone() {
                             log::debug!("Cancelling build task due to manual intervention");
                             graphics.future_queue.cancel(&handle);
                         } else {
@@ -244,7 +425,7 @@ impl SignalController for Editor {
                                     success_without_console!("Build completed");
                                     log::info!("Build completed successfully!");
 
-                                    if let Some(handle) = self.handle_created {
+                                    if let Some(handle) = self.handle_created.clone() {
                                         if let Some(result) = graphics
                                             .future_queue
                                             .exchange_owned_as::<anyhow::Result<PathBuf>>(&handle)
@@ -333,7 +514,7 @@ impl SignalController for Editor {
                             });
 
                         if !window_open {
-                            if let Some(handle) = self.handle_created {
+                            if let Some(handle) = self.handle_created.clone() {
                                 log::warn!("Cancelling build task due to window close");
                                 graphics.future_queue.cancel(&handle);
                             }
@@ -430,6 +611,9 @@ impl SignalController for Editor {
                 }
 
                 self.editor_state = EditorState::Editing;
+                // globals and the store set by scripts only live for the play session
+                ResourceRegistry::global().clear();
+                self.script_manager.clear_store();
 
                 self.switch_to_debug_camera();
 
@@ -595,6 +779,7 @@ impl SignalController for Editor {
                         components.push(Box::new(SerializedMeshRenderer {
                             handle: ResourceReference::from_reference(ResourceReferenceType::Cube),
                             material_override: Vec::new(),
+                            emissive_overrides: Default::default(),
                         }));
                         components.push(Box::new(ModelProperties::new()));
 
@@ -734,6 +919,104 @@ impl SignalController for Editor {
                 self.signal = Signal::None;
                 Ok(())
             }
+            Signal::BakeProbes(target) => {
+                let baker = IrradianceBaker::default();
+                match target {
+                    Some(entity) => {
+                        if baker.bake_one(&mut self.world, *entity) {
+                            success!("Baked light probe for entity {:?}", entity);
+                        } else {
+                            warn!("Unable to bake light probe: entity {:?} has no LightProbe", entity);
+                        }
+                    }
+                    None => {
+                        let count = baker.bake_all(&mut self.world);
+                        if count == 0 {
+                            warn!("No light probes in the scene to bake");
+                        } else {
+                            success!("Baked {} light probe(s)", count);
+                        }
+                    }
+                }
+                self.signal = Signal::None;
+                Ok(())
+            }
+            Signal::ReimportTexture(path) => {
+                match ASSET_REGISTRY.reimport_texture(graphics.clone(), path) {
+                    Ok(_) => {
+                        success!("Reimported texture {}", path.display());
+                    }
+                    Err(e) => {
+                        warn!("Unable to reimport texture {}: {}", path.display(), e);
+                    }
+                }
+                self.signal = Signal::None;
+                Ok(())
+            }
+            Signal::ReloadModel(handle) => {
+                let handle = *handle;
+                self.queue_model_reload(graphics.clone(), handle);
+                self.signal = Signal::None;
+                Ok(())
+            }
+            Signal::SetEmissiveFactor(entity, material_name, emissive_factor) => {
+                if let Ok(mut renderer) = self.world.get::<&mut MeshRenderer>(*entity) {
+                    if let Err(e) = renderer.set_emissive_factor(
+                        graphics.clone(),
+                        material_name,
+                        *emissive_factor,
+                    ) {
+                        warn!("Unable to set emissive factor: {}", e);
+                    }
+                } else {
+                    warn!("Unable to set emissive factor: entity has no mesh renderer");
+                }
+                self.signal = Signal::None;
+                Ok(())
+            }
+            Signal::SceneFileChanged(path) => {
+                info!("{} was changed outside of the editor", path.display());
+                self.scene_changed_on_disk = Some(path.clone());
+                self.signal = Signal::None;
+                Ok(())
+            }
+            Signal::ReloadScript(path) => {
+                if matches!(self.editor_state, EditorState::Playing) {
+                    info!(
+                        "{} was changed, stop and play again to rebuild the scripts",
+                        path.display()
+                    );
+                } else {
+                    info!(
+                        "{} was changed, the scripts are rebuilt when play mode starts",
+                        path.display()
+                    );
+                }
+                self.signal = Signal::None;
+                Ok(())
+            }
+            Signal::HotReloadAsset(path) => {
+                if path.extension().is_some_and(|ext| ext == "eucs") {
+                    // goes through the same checks as a change picked up by the scene watcher
+                    self.signal = Signal::SceneFileChanged(path.clone());
+                    return Ok(());
+                }
+
+                if ASSET_REGISTRY.is_texture_loaded(path) {
+                    match ASSET_REGISTRY.reimport_texture(graphics.clone(), path) {
+                        Ok(_) => {
+                            success!("Reloaded texture {}", path.display());
+                        }
+                        Err(e) => {
+                            warn!("Unable to reload texture {}: {}", path.display(), e);
+                        }
+                    }
+                } else {
+                    info!("{} was changed outside of the editor", path.display());
+                }
+                self.signal = Signal::None;
+                Ok(())
+            }
         }?;
         if !show {
             self.signal = Signal::None;
@@ -307,5 +307,18 @@ async fn load_renderer_from_serialized(
         }
     }
 
+    for (material_name, emissive_factor) in renderer.emissive_overrides {
+        if let Err(err) =
+            mesh_renderer.set_emissive_factor(graphics.clone(), &material_name, emissive_factor)
+        {
+            log::warn!(
+                "Failed to apply emissive factor to '{}' on '{}': {}",
+                material_name,
+                label,
+                err
+            );
+        }
+    }
+
     Ok(mesh_renderer)
 }
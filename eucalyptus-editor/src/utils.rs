@@ -1,25 +1,28 @@
-use anyhow::anyhow;
+use anyhow::{Context as _, anyhow};
 use dropbear_engine::camera::Camera;
+use dropbear_engine::future::FutureQueue;
 use dropbear_engine::scene::SceneCommand;
 use egui::Context;
 use egui_toast::{Toast, ToastOptions, Toasts};
 use eucalyptus_core::config::ProjectConfig;
+use eucalyptus_core::scene::SceneTemplate;
 use eucalyptus_core::states::PROJECT;
 use eucalyptus_core::utils::ProjectProgress;
 use git2::Repository;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc;
-use std::sync::mpsc::Receiver;
+use std::sync::mpsc::{Receiver, Sender};
 
 pub fn show_new_project_window<F>(
     ctx: &Context,
     show_new_project: &mut bool,
     project_name: &mut String,
     project_path: &mut Option<PathBuf>,
+    scene_template: &mut SceneTemplate,
     on_create: F,
 ) where
-    F: FnOnce(&str, &PathBuf),
+    F: FnOnce(&str, &PathBuf, SceneTemplate),
 {
     let screen_size = egui::vec2(400.0, 220.0);
 
@@ -55,13 +58,21 @@ pub fn show_new_project_window<F>(
                     *project_path = Some(path);
                 }
 
+                ui.add_space(10.0);
+                ui.heading("Starting Scene:");
+                ui.add_space(5.0);
+                for template in SceneTemplate::ALL {
+                    ui.radio_value(scene_template, template, template.name())
+                        .on_hover_text(template.description());
+                }
+
                 let can_create = project_path.is_some() && !project_name.is_empty();
                 if ui
                     .add_enabled(can_create, egui::Button::new("Create Project"))
                     .clicked()
                 {
                     if let Some(path) = project_path {
-                        on_create(project_name, path);
+                        on_create(project_name, path, *scene_template);
                     }
                     ui.ctx().request_repaint();
                 }
@@ -101,84 +112,112 @@ pub fn screen_to_world_coords(
     (world_near, world_far)
 }
 
-/// Start creating a new project in a background thread.
-/// Returns a Receiver for progress updates.
+/// A project being created by [`start_project_creation`], along with the last progress received.
+pub struct ProjectCreation {
+    progress_rx: Receiver<ProjectProgress>,
+    pub progress: f32,
+    pub message: String,
+    /// Why creating the project failed, kept until the user dismisses it
+    pub error: Option<String>,
+}
+
+impl ProjectCreation {
+    /// Takes every progress update sent so far, returning `true` once the project is created.
+    pub fn receive_progress(&mut self) -> bool {
+        let mut done = false;
+        while let Ok(progress) = self.progress_rx.try_recv() {
+            match progress {
+                ProjectProgress::Step { progress, message } => {
+                    self.progress = progress;
+                    self.message = message;
+                }
+                ProjectProgress::Error(e) => self.error = Some(e),
+                ProjectProgress::Done => done = true,
+            }
+        }
+        done
+    }
+}
+
+/// Starts creating a new project on the [`FutureQueue`], so the window keeps responding while
+/// the folders and files are written.
+///
+/// Once every step has succeeded the new project is set as the current [`PROJECT`]. If a step
+/// fails, such as when the folder can't be written to, creation stops and the error is reported
+/// through [`ProjectCreation::error`].
 pub fn start_project_creation(
+    queue: &FutureQueue,
     project_name: String,
-    project_path: Option<PathBuf>,
-) -> Option<Receiver<ProjectProgress>> {
+    project_path: PathBuf,
+    scene_template: SceneTemplate,
+) -> ProjectCreation {
     let (tx, rx) = mpsc::channel();
-    let project_path = project_path.clone();
-
-    std::thread::spawn(move || {
-        let folders = [
-            ("git", 0.1, "Creating a git folder..."),
-            ("src", 0.2, "Creating src folder..."),
-            ("resources/models", 0.4, "Creating models folder..."),
-            ("resources/shaders", 0.6, "Creating shader folder..."),
-            ("resources/textures", 0.8, "Creating textures folder..."),
-            ("src2", 0.9, "Creating project config file..."),
-        ];
-
-        if let Some(path) = &project_path {
-            for (folder, progress, message) in folders {
-                tx.send(ProjectProgress::Step {
-                    _progress: progress,
-                    _message: message.to_string(),
-                })
-                .ok();
-
-                let full_path = path.join(folder);
-                let result: anyhow::Result<()> = if folder == "src" {
-                    if !full_path.exists() {
-                        fs::create_dir(&full_path)
-                            .map_err(|e| anyhow::anyhow!(e))
-                            .map(|_| ())
-                    } else {
-                        Ok(())
-                    }
-                } else if folder == "git" {
-                    match Repository::init(path) {
-                        Ok(_) => Ok(()),
-                        Err(e) => {
-                            if matches!(e.code(), git2::ErrorCode::Exists) {
-                                Ok(())
-                            } else {
-                                Err(anyhow!(e))
-                            }
-                        }
-                    }
-                } else if folder == "src2" {
-                    if let Some(path) = &project_path {
-                        let mut config = ProjectConfig::new(project_name.clone(), path);
-                        let _ = config.write_to_all();
-                        let mut global = PROJECT.write();
-                        *global = config;
-                        Ok(())
-                    } else {
-                        Err(anyhow!("Project path not found"))
-                    }
-                } else if !full_path.exists() {
-                    fs::create_dir_all(&full_path)
-                        .map_err(|e| anyhow!(e))
-                        .map(|_| ())
-                } else {
-                    Ok(())
-                };
-                if let Err(e) = result {
-                    tx.send(ProjectProgress::Error(e.to_string())).ok();
-                }
+
+    queue.push(async move {
+        match create_project(&project_name, &project_path, scene_template, &tx) {
+            Ok(()) => {
+                tx.send(ProjectProgress::Done).ok();
+            }
+            Err(e) => {
+                log::error!("Unable to create project: {:#}", e);
+                tx.send(ProjectProgress::Error(format!("{:#}", e))).ok();
             }
-            tx.send(ProjectProgress::Step {
-                _progress: 1.0,
-                _message: "Project creation complete!".to_string(),
-            })
-            .ok();
-            tx.send(ProjectProgress::Done).ok();
         }
     });
 
-    Some(rx)
+    ProjectCreation {
+        progress_rx: rx,
+        progress: 0.0,
+        message: "Starting project creation...".to_string(),
+        error: None,
+    }
+}
+
+fn create_project(
+    project_name: &str,
+    path: &Path,
+    scene_template: SceneTemplate,
+    tx: &Sender<ProjectProgress>,
+) -> anyhow::Result<()> {
+    let step = |progress: f32, message: &str| {
+        tx.send(ProjectProgress::Step {
+            progress,
+            message: message.to_string(),
+        })
+        .ok();
+    };
+
+    step(0.0, "Creating the project folder...");
+    fs::create_dir_all(path).with_context(|| format!("Unable to create {}", path.display()))?;
+
+    step(0.1, "Creating a git repository...");
+    match Repository::init(path) {
+        Ok(_) => {}
+        Err(e) if matches!(e.code(), git2::ErrorCode::Exists) => {}
+        Err(e) => return Err(anyhow!(e).context("Unable to create a git repository")),
+    }
+
+    let folders = [
+        ("src", 0.2, "Creating src folder..."),
+        ("resources/models", 0.4, "Creating models folder..."),
+        ("resources/shaders", 0.6, "Creating shader folder..."),
+        ("resources/textures", 0.8, "Creating textures folder..."),
+    ];
+    for (folder, progress, message) in folders {
+        step(progress, message);
+        let full_path = path.join(folder);
+        fs::create_dir_all(&full_path)
+            .with_context(|| format!("Unable to create {}", full_path.display()))?;
+    }
+
+    step(0.9, "Creating project config file...");
+    let mut config = ProjectConfig::new(project_name.to_string(), path);
+    config.apply_scene_template(scene_template)?;
+    config.write_to_all()?;
+    *PROJECT.write() = config;
+
+    step(1.0, "Project creation complete!");
+    Ok(())
 }
 
 #[allow(dead_code)]
@@ -111,6 +111,10 @@ int dropbear_set_double_property(const World* world_ptr, int64_t entity_handle,
 int dropbear_set_bool_property(const World* world_ptr, int64_t entity_handle, const char* label, int value); // value = 0 or 1
 int dropbear_set_vec3_property(const World* world_ptr, int64_t entity_handle, const char* label, float x, float y, float z);
 
+// material properties
+int dropbear_get_material_property(const World* world_ptr, const char* label, const char* key, float* out_value);
+int dropbear_set_material_property(World* world_ptr, const char* label, const char* key, float value);
+
 
 // input stuff
 void dropbear_print_input_state(const InputState* input_state_ptr);
@@ -123,11 +127,43 @@ int dropbear_set_cursor_locked(const GraphicsCommandQueue* graphics_ptr, const I
 int dropbear_get_last_mouse_pos(const InputState* input_state_ptr, float* out_x, float* out_y);
 int dropbear_is_cursor_hidden(const InputState* input_state_ptr, int* out_hidden);
 int dropbear_set_cursor_hidden(const GraphicsCommandQueue* graphics_ptr, const InputState* input_state_ptr, int hidden);
+int dropbear_get_connected_gamepads(const InputState* input_state_ptr, uint64_t* out_ids, size_t capacity, size_t* out_count);
+int dropbear_is_gamepad_button_pressed(const InputState* input_state_ptr, uint64_t gamepad_id, int button, int* out_pressed); // out_pressed = 0 or 1
+int dropbear_get_gamepad_sticks(const InputState* input_state_ptr, uint64_t gamepad_id, float* out_left_x, float* out_left_y, float* out_right_x, float* out_right_y);
 
 // camera
 int dropbear_get_camera(const World* world_ptr, const char* label, NativeCamera* out_camera);
 int dropbear_get_attached_camera(const World* world_ptr, int64_t id, NativeCamera* out_camera);
 int dropbear_set_camera(const World* world_ptr, const NativeCamera* camera);
+int dropbear_shake_camera(const World* world_ptr, float intensity, float duration);
+int dropbear_play_camera_path(World* world_ptr, const char* name);
+
+// particles
+int dropbear_emit_particles(const World* world_ptr, const char* label, int count);
+int dropbear_set_emission_rate(const World* world_ptr, const char* label, float rate);
+
+// morph targets
+int dropbear_set_morph_weight(const World* world_ptr, const char* label, const char* target_name, float weight);
+
+// spawning, applied once the scripts have finished updating
+int dropbear_spawn(const char* model_path, const char* label, const NativeTransform* transform);
+int dropbear_destroy(const char* label);
+
+// globals, shared between scenes as JSON
+int dropbear_set_global(const char* key, const char* json);
+int dropbear_get_global(const char* key, char* out_json, size_t out_json_max_length);
+
+// the scene store, kept as JSON until play mode stops
+int dropbear_set_store(const char* key, const char* json);
+int dropbear_get_store(const char* key, char* out_json, size_t out_json_max_length);
+
+// text, drawn for the current frame only. font is the euca URI of a font file
+int dropbear_draw_text(const char* text, const char* font, float size, double x, double y, double z, float r, float g, float b, float a);
+int dropbear_draw_text_screen(const char* text, const char* font, float size, float x, float y, float r, float g, float b, float a);
+
+// post processing
+int dropbear_set_depth_of_field(float focal_distance, float focal_range, float max_blur, const char* focus_entity); // focus_entity can be NULL
+int dropbear_clear_depth_of_field(void);
 
 // ===========================================
 
@@ -1,6 +1,7 @@
 package com.dropbear.magna_carta
 
 import org.gradle.api.DefaultTask
+import org.gradle.api.GradleException
 import org.gradle.api.file.DirectoryProperty
 import org.gradle.api.file.RegularFileProperty
 import org.gradle.api.provider.Property
@@ -29,6 +30,10 @@ abstract class GenerateMagnaCartaTask : DefaultTask() {
         val input = inputDir.get().asFile
         val output = outputDir.get().asFile
 
+        if (output.canonicalFile.startsWith(input.canonicalFile)) {
+            throw GradleException("The output directory $output is inside the input directory $input, so the generated manifest would be read back in as a script")
+        }
+
         output.mkdirs()
 
         val os = OperatingSystem.current()
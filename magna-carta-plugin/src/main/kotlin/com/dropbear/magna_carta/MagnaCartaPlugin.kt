@@ -2,15 +2,20 @@ package com.dropbear.magna_carta
 
 import org.gradle.api.Plugin
 import org.gradle.api.Project
+import org.gradle.api.provider.Property
 import org.gradle.kotlin.dsl.create
 import org.gradle.kotlin.dsl.getByType
 import org.gradle.kotlin.dsl.register
+import org.jetbrains.kotlin.gradle.dsl.KotlinJvmProjectExtension
 import org.jetbrains.kotlin.gradle.dsl.KotlinMultiplatformExtension
 import org.jetbrains.kotlin.gradle.plugin.mpp.KotlinNativeTarget
 
 class MagnaCartaPlugin : Plugin<Project> {
     override fun apply(project: Project) {
-        project.extensions.create("magna-carta", MagnaCartaExtension::class)
+        val extension = project.extensions.create("magnaCarta", MagnaCartaExtension::class).apply {
+            target.convention("jvm")
+            outputDir.convention("build/magna-carta/main")
+        }
 
         val downloadToolTask = project.tasks.register("downloadMagnaCartaTool", DownloadMagnaCartaToolTask::class) {
             toolVersion.set("magna-carta-v0.0.1")
@@ -33,6 +38,25 @@ class MagnaCartaPlugin : Plugin<Project> {
             outputDir.set(project.layout.buildDirectory.dir("magna-carta/nativeLibMain"))
         }
 
+        val generateScriptManifestTask = project.tasks.register("generateScriptManifest", GenerateMagnaCartaTask::class) {
+            group = "build"
+            description = "Generates the script manifest for the target set in the magnaCarta block"
+            dependsOn(downloadToolTask)
+            toolExecutable.set(downloadToolTask.flatMap { it.outputFile })
+            target.set(extension.target)
+            inputDir.set(project.projectDir.resolve("src/main/kotlin"))
+            outputDir.set(project.layout.projectDirectory.dir(extension.outputDir))
+        }
+
+        project.pluginManager.withPlugin("org.jetbrains.kotlin.jvm") {
+            val kotlin = project.extensions.getByType(KotlinJvmProjectExtension::class)
+            kotlin.sourceSets.getByName("main").kotlin.srcDir(generateScriptManifestTask.map { it.outputDir })
+
+            project.tasks.named("compileKotlin") {
+                dependsOn(generateScriptManifestTask)
+            }
+        }
+
         project.pluginManager.withPlugin("org.jetbrains.kotlin.multiplatform") {
             val kotlin = project.extensions.getByType(KotlinMultiplatformExtension::class)
 
@@ -72,4 +96,19 @@ class MagnaCartaPlugin : Plugin<Project> {
     }
 }
 
-abstract class MagnaCartaExtension { }
\ No newline at end of file
+/**
+ * Configures the `generateScriptManifest` task, set with the `magnaCarta { }` block in `build.gradle.kts`.
+ */
+abstract class MagnaCartaExtension {
+    /**
+     * The target to generate the manifest for, either `jvm`, `native` or `json`. Defaults to `jvm`.
+     */
+    abstract val target: Property<String>
+
+    /**
+     * Where the manifest is written, relative to the project directory. Defaults to `build/magna-carta/main`.
+     *
+     * This can't be inside `src/main/kotlin`, as that is where the scripts are read from.
+     */
+    abstract val outputDir: Property<String>
+}
\ No newline at end of file
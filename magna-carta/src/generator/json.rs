@@ -0,0 +1,39 @@
+use crate::ScriptManifest;
+use crate::generator::Generator;
+use serde::Serialize;
+
+/// Generates a pretty-printed JSON array from a [`ScriptManifest`].
+///
+/// This is useful for build tools that are not Kotlin-based but still need the manifest. The
+/// schema is documented in `script-manifest.schema.json` at the root of the crate.
+pub struct JsonGenerator;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonManifestItem<'a> {
+    fqcn: &'a str,
+    simple_name: &'a str,
+    tags: &'a [String],
+    file_path: String,
+    is_singleton: bool,
+}
+
+impl Generator for JsonGenerator {
+    fn generate(&self, manifest: &ScriptManifest) -> anyhow::Result<String> {
+        let items: Vec<JsonManifestItem> = manifest
+            .items()
+            .iter()
+            .map(|item| JsonManifestItem {
+                fqcn: item.fqcn(),
+                simple_name: item.simple_name(),
+                tags: item.tags(),
+                file_path: item.file_path().to_string_lossy().replace('\\', "/"),
+                is_singleton: item.is_singleton(),
+            })
+            .collect();
+
+        let mut output = serde_json::to_string_pretty(&items)?;
+        output.push('\n');
+        Ok(output)
+    }
+}
@@ -35,9 +35,14 @@ impl Generator for KotlinJVMGenerator {
         }
         writeln!(output)?;
 
+        // objects are already instantiated, so their factory hands out the single instance
         let mut tag_map: HashMap<String, Vec<String>> = HashMap::new();
         for item in manifest.items() {
-            let simple_name = item.simple_name();
+            let factory = if item.is_singleton() {
+                format!("{{ {} }}", item.simple_name())
+            } else {
+                format!("::{}", item.simple_name())
+            };
             if item.tags().is_empty() {
                 continue;
             }
@@ -45,7 +50,7 @@ impl Generator for KotlinJVMGenerator {
                 tag_map
                     .entry(tag.clone())
                     .or_default()
-                    .push(simple_name.to_string());
+                    .push(factory.clone());
             }
         }
 
@@ -62,13 +67,13 @@ impl Generator for KotlinJVMGenerator {
         writeln!(output)?;
 
         writeln!(output, "    private fun registerStaticScripts() {{")?;
-        for (tag, classes) in &tag_map {
+        for (tag, factories) in &tag_map {
             writeln!(output, "        // Tag: {}", tag)?;
-            for class in classes {
+            for factory in factories {
                 writeln!(
                     output,
-                    "        tagRegistry.computeIfAbsent(\"{}\") {{ mutableListOf() }}.add(::{})",
-                    tag, class
+                    "        tagRegistry.computeIfAbsent(\"{}\") {{ mutableListOf() }}.add({})",
+                    tag, factory
                 )?;
             }
         }
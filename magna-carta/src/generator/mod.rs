@@ -1,6 +1,7 @@
 use crate::ScriptManifest;
 use std::path::Path;
 
+pub mod json;
 pub mod jvm;
 pub mod native;
 
@@ -28,6 +29,7 @@ pub trait Generator {
 mod tests {
     use super::*;
     use crate::ManifestItem;
+    use crate::generator::json::JsonGenerator;
     use crate::generator::jvm::KotlinJVMGenerator;
     use crate::generator::native::KotlinNativeGenerator;
     use std::path::PathBuf;
@@ -82,4 +84,114 @@ mod tests {
         assert!(output.contains("import com.game.*"));
         assert!(output.contains("Player::class"));
     }
+
+    #[test]
+    fn test_singleton_factories() {
+        let mut manifest = ScriptManifest::new();
+        manifest.add_item(
+            ManifestItem::new(
+                "com.game.MySystem".to_string(),
+                "MySystem".to_string(),
+                vec!["systems".to_string()],
+                PathBuf::from("src/MySystem.kt"),
+            )
+            .with_singleton(true),
+        );
+
+        let jvm = KotlinJVMGenerator.generate(&manifest).unwrap();
+        assert!(jvm.contains(".add({ MySystem })"));
+        assert!(!jvm.contains("::MySystem"));
+
+        let native = KotlinNativeGenerator.generate(&manifest).unwrap();
+        assert!(native.contains("\"systems\" -> listOf({ MySystem })"));
+        assert!(!native.contains("{ MySystem() }"));
+    }
+
+    #[test]
+    fn test_cinterop_header() {
+        let mut manifest = ScriptManifest::new();
+        manifest.add_item(ManifestItem::new(
+            "com.game.Player".to_string(),
+            "Player".to_string(),
+            vec!["player".to_string()],
+            PathBuf::from("src/Player.kt"),
+        ));
+        manifest.add_item(
+            ManifestItem::new(
+                "com.game.MySystem".to_string(),
+                "MySystem".to_string(),
+                vec![],
+                PathBuf::from("src/MySystem.kt"),
+            )
+            .with_singleton(true),
+        );
+
+        let header = KotlinNativeGenerator::generate_cinterop_header(&manifest).unwrap();
+
+        assert!(header.contains("void* createRunnable_Player(void);"));
+        assert!(header.contains("void* createRunnable_MySystem(void);"));
+
+        // every line has to be a comment, a preprocessor directive, the C++ linkage block or a
+        // declaration, with the conditionals and braces balanced
+        let mut conditionals = 0;
+        let mut braces = 0;
+        for line in header.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with("//") {
+                continue;
+            }
+            if let Some(directive) = line.strip_prefix('#') {
+                let keyword = directive.split_whitespace().next().unwrap();
+                match keyword {
+                    "ifdef" | "ifndef" => conditionals += 1,
+                    "endif" => conditionals -= 1,
+                    "define" => {}
+                    other => panic!("Unexpected directive #{}", other),
+                }
+                assert!(conditionals >= 0, "#endif without a matching #if");
+                continue;
+            }
+            match line {
+                "extern \"C\" {" => braces += 1,
+                "}" => braces -= 1,
+                _ => {
+                    let name = line
+                        .strip_prefix("void* ")
+                        .and_then(|rest| rest.strip_suffix("(void);"))
+                        .unwrap_or_else(|| panic!("Not a declaration: {}", line));
+                    assert!(!name.starts_with(|c: char| c.is_ascii_digit()));
+                    assert!(name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+                }
+            }
+            assert!(braces >= 0, "Closing brace without an opening brace");
+        }
+        assert_eq!(conditionals, 0);
+        assert_eq!(braces, 0);
+
+        // the Kotlin file has to export what the header declares
+        let native = KotlinNativeGenerator.generate(&manifest).unwrap();
+        assert!(native.contains("@CName(\"createRunnable_Player\")"));
+        assert!(native.contains("StableRef.create(Player()).asCPointer()"));
+        assert!(native.contains("StableRef.create(MySystem).asCPointer()"));
+    }
+
+    #[test]
+    fn test_json_generator() {
+        let mut manifest = ScriptManifest::new();
+        manifest.add_item(ManifestItem::new(
+            "com.game.Player".to_string(),
+            "Player".to_string(),
+            vec!["player".to_string()],
+            PathBuf::from("src/Player.kt"),
+        ));
+
+        let generator = JsonGenerator;
+        let output = generator.generate(&manifest).unwrap();
+
+        assert!(output.trim_start().starts_with('['));
+        assert!(output.contains("\"fqcn\": \"com.game.Player\""));
+        assert!(output.contains("\"simpleName\": \"Player\""));
+        assert!(output.contains("\"player\""));
+        assert!(output.contains("\"filePath\": \"src/Player.kt\""));
+        assert!(output.contains("\"isSingleton\": false"));
+    }
 }
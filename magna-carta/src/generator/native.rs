@@ -1,11 +1,59 @@
-use crate::ScriptManifest;
 use crate::generator::Generator;
+use crate::{ManifestItem, ScriptManifest};
 use chrono::Utc;
 use std::collections::HashMap;
 use std::fmt::Write;
 
 pub struct KotlinNativeGenerator;
 
+impl KotlinNativeGenerator {
+    /// Generates a C header declaring the `createRunnable_<ClassName>` function that the
+    /// generated Kotlin file exports for every script in the manifest.
+    ///
+    /// Each function returns a `StableRef` to a new instance of the script (or the instance
+    /// itself for objects), which has to be disposed of by the caller.
+    pub fn generate_cinterop_header(manifest: &ScriptManifest) -> anyhow::Result<String> {
+        let mut output = String::new();
+        let local_time = Utc::now();
+        writeln!(
+            output,
+            "// Auto-generated by dropbear-engine with the magna-carta parser on {} UTC",
+            local_time.format("%Y-%m-%d %H:%M:%S")
+        )?;
+        writeln!(output, "#ifndef DROPBEAR_SCRIPT_MANIFEST_H")?;
+        writeln!(output, "#define DROPBEAR_SCRIPT_MANIFEST_H")?;
+        writeln!(output)?;
+        writeln!(output, "#ifdef __cplusplus")?;
+        writeln!(output, "extern \"C\" {{")?;
+        writeln!(output, "#endif")?;
+        writeln!(output)?;
+
+        for item in manifest.items() {
+            writeln!(output, "void* {}(void);", runnable_factory_name(item))?;
+        }
+
+        writeln!(output)?;
+        writeln!(output, "#ifdef __cplusplus")?;
+        writeln!(output, "}}")?;
+        writeln!(output, "#endif")?;
+        writeln!(output)?;
+        writeln!(output, "#endif // DROPBEAR_SCRIPT_MANIFEST_H")?;
+
+        Ok(output)
+    }
+}
+
+/// The exported name of the function that creates a script, with anything that can't be in a C
+/// identifier replaced by an underscore.
+fn runnable_factory_name(item: &ManifestItem) -> String {
+    let class_name: String = item
+        .simple_name()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("createRunnable_{}", class_name)
+}
+
 impl Generator for KotlinNativeGenerator {
     fn generate(&self, manifest: &ScriptManifest) -> anyhow::Result<String> {
         let mut output = String::new();
@@ -34,6 +82,7 @@ import com.dropbear.ffi.NativeEngine
 import com.dropbear.logging.Logger
 import kotlinx.cinterop.COpaquePointer
 import kotlinx.cinterop.ExperimentalForeignApi
+import kotlinx.cinterop.StableRef
 import kotlin.experimental.ExperimentalNativeApi
         "#
         )?;
@@ -52,9 +101,14 @@ import kotlin.experimental.ExperimentalNativeApi
         }
         writeln!(output)?;
 
+        // objects are already instantiated, so their factory hands out the single instance
         let mut tag_map: HashMap<String, Vec<String>> = HashMap::new();
         for item in manifest.items() {
-            let simple_name = item.simple_name();
+            let factory = if item.is_singleton() {
+                format!("{{ {} }}", item.simple_name())
+            } else {
+                format!("{{ {}() }}", item.simple_name())
+            };
             if item.tags().is_empty() {
                 continue;
             }
@@ -62,7 +116,7 @@ import kotlin.experimental.ExperimentalNativeApi
                 tag_map
                     .entry(tag.clone())
                     .or_default()
-                    .push(simple_name.to_string());
+                    .push(factory.clone());
             }
         }
 
@@ -184,11 +238,7 @@ object ScriptManager {{
             )?;
             writeln!(output, "       return when (tag) {{")?;
 
-            for (tag, classes) in &tag_map {
-                let factories: Vec<String> = classes
-                    .iter()
-                    .map(|cls| format!("{{ {}() }}", cls))
-                    .collect();
+            for (tag, factories) in &tag_map {
                 writeln!(
                     output,
                     "           \"{}\" -> listOf({})",
@@ -256,6 +306,25 @@ fun dropbear_set_last_error(err: String?) {{
         "#
         )?;
 
+        // declared by the header from generate_cinterop_header
+        for item in manifest.items() {
+            let instance = if item.is_singleton() {
+                item.simple_name().to_string()
+            } else {
+                format!("{}()", item.simple_name())
+            };
+            let name = runnable_factory_name(item);
+            writeln!(output, "@CName(\"{}\")", name)?;
+            writeln!(output, "fun {}(): COpaquePointer {{", name)?;
+            writeln!(
+                output,
+                "    return StableRef.create({}).asCPointer()",
+                instance
+            )?;
+            writeln!(output, "}}")?;
+            writeln!(output)?;
+        }
+
         Ok(output)
     }
 }
@@ -1,8 +1,16 @@
 pub mod generator;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tree_sitter::{Parser, Query, QueryCursor};
 
+/// Trims and lowercases a script tag.
+///
+/// Tags are matched case-insensitively, so this has to be used on both the tags of a
+/// [`ManifestItem`] and the tags given to entities before they are compared.
+pub fn normalise_tag(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
 /// A group of manifests.
 #[derive(Debug, Clone)]
 pub struct ScriptManifest {
@@ -24,9 +32,23 @@ impl ScriptManifest {
         self.items.push(item);
     }
 
+    /// Adds every item, such as all the classes found in a single file.
+    pub fn extend(&mut self, items: impl IntoIterator<Item = ManifestItem>) {
+        self.items.extend(items);
+    }
+
     pub fn items(&self) -> &[ManifestItem] {
         &self.items
     }
+
+    /// Returns every item with the tag, ignoring case.
+    pub fn find_by_tag(&self, tag: &str) -> Vec<&ManifestItem> {
+        let tag = normalise_tag(tag);
+        self.items
+            .iter()
+            .filter(|item| item.tags.contains(&tag))
+            .collect()
+    }
 }
 
 /// Represents a single script class. This struct contains all the necessary information to generate
@@ -49,20 +71,41 @@ pub struct ManifestItem {
     ///
     /// Example: `/home/tirbofish/project2/src/commonMain/kotlin/foo/bar/Enemy.kt`
     file_path: PathBuf,
+    /// Whether the script is a Kotlin `object` declaration instead of a class, in which case its
+    /// single instance is used instead of calling a constructor
+    is_singleton: bool,
 }
 
 impl ManifestItem {
     /// Creates a new manifest item from an fqcn (fully qualified class name), simple name, tags
     /// and file_path.
+    ///
+    /// Tags are trimmed and lowercased, and duplicates are removed while keeping the order they
+    /// were first given in.
     pub fn new(fqcn: String, simple_name: String, tags: Vec<String>, file_path: PathBuf) -> Self {
+        let mut unique_tags: Vec<String> = Vec::with_capacity(tags.len());
+        for tag in tags {
+            let tag = normalise_tag(&tag);
+            if !unique_tags.contains(&tag) {
+                unique_tags.push(tag);
+            }
+        }
+
         Self {
             fqcn,
             simple_name,
-            tags,
+            tags: unique_tags,
             file_path,
+            is_singleton: false,
         }
     }
 
+    /// Marks the item as a Kotlin `object` declaration.
+    pub fn with_singleton(mut self, is_singleton: bool) -> Self {
+        self.is_singleton = is_singleton;
+        self
+    }
+
     pub fn fqcn(&self) -> &str {
         &self.fqcn
     }
@@ -78,6 +121,10 @@ impl ManifestItem {
     pub fn file_path(&self) -> &PathBuf {
         &self.file_path
     }
+
+    pub fn is_singleton(&self) -> bool {
+        self.is_singleton
+    }
 }
 
 /// Processer for Kotlin files.
@@ -96,11 +143,14 @@ impl KotlinProcessor {
 
     /// Processes the file for `@Runnable` annotations, and check if that
     /// class inherits the `System()` abstract class.
+    ///
+    /// Returns an item for every `@Runnable` class or object in the file, in the order they are
+    /// declared.
     pub fn process_file(
         &mut self,
         source_code: &str,
         file_path: PathBuf,
-    ) -> anyhow::Result<Option<ManifestItem>> {
+    ) -> anyhow::Result<Vec<ManifestItem>> {
         let tree = self
             .parser
             .parse(source_code, None)
@@ -110,19 +160,22 @@ impl KotlinProcessor {
 
         let package = self.extract_package(root_node, source_code)?;
 
-        if let Some(class_info) = self.extract_class_info(root_node, source_code)? {
-            let (class_name, tags) = class_info;
-
-            let fqcn = if package.is_empty() {
-                class_name.clone()
-            } else {
-                format!("{}.{}", package, class_name)
-            };
+        let items = self
+            .extract_class_info(root_node, source_code, &file_path)?
+            .into_iter()
+            .map(|(class_name, tags, is_singleton)| {
+                let fqcn = if package.is_empty() {
+                    class_name.clone()
+                } else {
+                    format!("{}.{}", package, class_name)
+                };
 
-            return Ok(Some(ManifestItem::new(fqcn, class_name, tags, file_path)));
-        }
+                ManifestItem::new(fqcn, class_name, tags, file_path.clone())
+                    .with_singleton(is_singleton)
+            })
+            .collect();
 
-        Ok(None)
+        Ok(items)
     }
 
     fn extract_package(
@@ -157,7 +210,8 @@ impl KotlinProcessor {
         &self,
         root_node: tree_sitter::Node,
         source: &str,
-    ) -> anyhow::Result<Option<(String, Vec<String>)>> {
+        file_path: &Path,
+    ) -> anyhow::Result<Vec<(String, Vec<String>, bool)>> {
         let query = Query::new(
             &tree_sitter_kotlin::language(),
             r#"
@@ -180,6 +234,37 @@ impl KotlinProcessor {
                 (value_arguments)? @value_args)
               (#eq? @annotation_name2 "Runnable")))
           (type_identifier) @class_name2)
+
+        ; Case 3: @Runnable object, with or without parentheses
+        (object_declaration
+          (modifiers
+            (annotation
+              [
+                (user_type
+                  (type_identifier) @annotation_name3)
+                (constructor_invocation
+                  (user_type
+                    (type_identifier) @annotation_name3)
+                  (value_arguments)? @value_args)
+              ]
+              (#eq? @annotation_name3 "Runnable")))
+          (type_identifier) @object_name)
+
+        ; Case 4: tree-sitter-kotlin sometimes reads `@Runnable(...)` as an annotated expression
+        ; instead of a modifier, leaving the declaration as the next sibling
+        ((prefix_expression
+           (annotation
+             (user_type
+               (type_identifier) @annotation_name3))
+           (parenthesized_expression) @value_args)
+         .
+         [
+           (object_declaration
+             (type_identifier) @object_name)
+           (class_declaration
+             (type_identifier) @class_name)
+         ]
+         (#eq? @annotation_name3 "Runnable"))
         "#,
         )?;
 
@@ -190,11 +275,15 @@ impl KotlinProcessor {
         let annotation_name2_idx = query.capture_index_for_name("annotation_name2");
         let class_name_idx = query.capture_index_for_name("class_name");
         let class_name2_idx = query.capture_index_for_name("class_name2");
+        let annotation_name3_idx = query.capture_index_for_name("annotation_name3");
+        let object_name_idx = query.capture_index_for_name("object_name");
         let value_args_idx = query.capture_index_for_name("value_args");
 
+        let mut classes: Vec<(String, Vec<String>, bool)> = Vec::new();
         for match_ in matches {
             let mut class_name = String::new();
             let mut found_runnable = false;
+            let mut is_singleton = false;
             let mut value_args_node = None;
 
             for capture in match_.captures {
@@ -216,6 +305,14 @@ impl KotlinProcessor {
                     found_runnable = true;
                 }
 
+                // case 3 (objects)
+                if let Some(idx) = annotation_name3_idx
+                    && capture.index == idx
+                    && text == "Runnable"
+                {
+                    found_runnable = true;
+                }
+
                 // class names
                 if let Some(idx) = class_name_idx
                     && capture.index == idx
@@ -229,6 +326,13 @@ impl KotlinProcessor {
                     class_name = text.to_string();
                 }
 
+                if let Some(idx) = object_name_idx
+                    && capture.index == idx
+                {
+                    class_name = text.to_string();
+                    is_singleton = true;
+                }
+
                 // case 2 value args
                 if let Some(idx) = value_args_idx
                     && capture.index == idx
@@ -237,50 +341,54 @@ impl KotlinProcessor {
                 }
             }
 
-            if found_runnable && !class_name.is_empty() {
+            if found_runnable
+                && !class_name.is_empty()
+                && !classes.iter().any(|(name, _, _)| *name == class_name)
+            {
                 let tags = if let Some(value_args) = value_args_node {
-                    self.extract_tags_from_value_args(value_args, source)?
+                    self.extract_tags_from_value_args(value_args, source, file_path)?
                 } else {
                     Vec::new()
                 };
 
-                return Ok(Some((class_name, tags)));
+                classes.push((class_name, tags, is_singleton));
             }
         }
 
-        Ok(None)
+        Ok(classes)
     }
 
     fn extract_tags_from_value_args(
         &self,
         value_args_node: tree_sitter::Node,
         source: &str,
+        file_path: &Path,
     ) -> anyhow::Result<Vec<String>> {
         let mut tags = Vec::new();
 
-        let mut cursor = value_args_node.walk();
-        for value_arg in value_args_node.children(&mut cursor) {
-            if value_arg.kind() == "value_argument" {
-                let mut arg_cursor = value_arg.walk();
-                for child in value_arg.children(&mut arg_cursor) {
-                    // Case 1: Direct string literal (vararg style)
-                    if child.kind() == "string_literal" {
-                        let text = child.utf8_text(source.as_bytes())?;
-                        let clean_tag = text.trim_matches(|c| c == '"' || c == '\'').to_string();
-                        if !clean_tag.is_empty() {
-                            tags.push(clean_tag);
-                        }
-                    } else if child.kind() == "collection_literal" {
-                        let mut collection_cursor = child.walk();
-                        for collection_item in child.children(&mut collection_cursor) {
-                            if collection_item.kind() == "string_literal" {
-                                let text = collection_item.utf8_text(source.as_bytes())?;
-                                let clean_tag =
-                                    text.trim_matches(|c| c == '"' || c == '\'').to_string();
-                                if !clean_tag.is_empty() {
-                                    tags.push(clean_tag);
-                                }
-                            }
+        // the arguments of an annotation read as an expression (case 4) are a single
+        // parenthesized expression rather than a list of value arguments
+        let value_args = if value_args_node.kind() == "parenthesized_expression" {
+            vec![value_args_node]
+        } else {
+            let mut cursor = value_args_node.walk();
+            value_args_node
+                .children(&mut cursor)
+                .filter(|value_arg| value_arg.kind() == "value_argument")
+                .collect()
+        };
+
+        for value_arg in value_args {
+            let mut arg_cursor = value_arg.walk();
+            for child in value_arg.children(&mut arg_cursor) {
+                // Case 1: Direct string literal (vararg style)
+                if child.kind() == "string_literal" {
+                    tags.push(parse_tag(child, source, file_path)?);
+                } else if child.kind() == "collection_literal" {
+                    let mut collection_cursor = child.walk();
+                    for collection_item in child.children(&mut collection_cursor) {
+                        if collection_item.kind() == "string_literal" {
+                            tags.push(parse_tag(collection_item, source, file_path)?);
                         }
                     }
                 }
@@ -291,6 +399,39 @@ impl KotlinProcessor {
     }
 }
 
+/// Reads a tag from a string literal, trimming any whitespace around it.
+///
+/// Returns an error with the file and line of the tag if it is empty, or contains whitespace, a
+/// dot or a non-ASCII character.
+fn parse_tag(node: tree_sitter::Node, source: &str, file_path: &Path) -> anyhow::Result<String> {
+    let text = node.utf8_text(source.as_bytes())?;
+    let tag = text.trim_matches(|c| c == '"' || c == '\'').trim();
+    let line = node.start_position().row + 1;
+
+    if tag.is_empty() {
+        return Err(anyhow::anyhow!(
+            "{}:{}: Tags cannot be empty",
+            file_path.display(),
+            line
+        ));
+    }
+
+    if let Some(invalid) = tag
+        .chars()
+        .find(|c| c.is_whitespace() || *c == '.' || !c.is_ascii())
+    {
+        return Err(anyhow::anyhow!(
+            "{}:{}: Tag \"{}\" contains the invalid character {:?}",
+            file_path.display(),
+            line,
+            tag,
+            invalid
+        ));
+    }
+
+    Ok(tag.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -328,8 +469,8 @@ class Player: System {
             )
             .unwrap();
 
-        assert!(result.is_some());
-        let item = result.unwrap();
+        assert_eq!(result.len(), 1);
+        let item = &result[0];
 
         assert_eq!(item.fqcn(), "com.dropbear.Player");
         assert_eq!(item.simple_name(), "Player");
@@ -339,4 +480,180 @@ class Player: System {
             &PathBuf::from("src/main/kotlin/com/dropbear/Player.kt")
         );
     }
+
+    #[test]
+    fn test_process_multiple_classes() {
+        let source = r#"
+package com.dropbear
+
+@Runnable
+class Enemy: System {
+    override fun update(engine: DropbearEngine, deltaTime: Float) {}
+}
+
+class Helper
+
+@Runnable(["pickup"])
+class Coin: System {
+    override fun update(engine: DropbearEngine, deltaTime: Float) {}
+}
+"#;
+
+        let mut processor = KotlinProcessor::new().unwrap();
+        let result = processor
+            .process_file(
+                source,
+                PathBuf::from("src/main/kotlin/com/dropbear/Enemies.kt"),
+            )
+            .unwrap();
+
+        assert_eq!(result.len(), 2);
+
+        assert_eq!(result[0].fqcn(), "com.dropbear.Enemy");
+        assert!(result[0].tags().is_empty());
+
+        assert_eq!(result[1].fqcn(), "com.dropbear.Coin");
+        assert_eq!(result[1].tags(), &["pickup"]);
+    }
+
+    #[test]
+    fn test_process_object_declaration() {
+        let source = r#"
+package com.dropbear
+
+@Runnable
+object MySystem : System {
+    override fun update(engine: DropbearEngine, deltaTime: Float) {}
+}
+
+@Runnable(["ui"])
+object Hud : System {
+    override fun update(engine: DropbearEngine, deltaTime: Float) {}
+}
+
+@Runnable
+class Enemy : System {
+    override fun update(engine: DropbearEngine, deltaTime: Float) {}
+}
+"#;
+
+        let mut processor = KotlinProcessor::new().unwrap();
+        let result = processor
+            .process_file(
+                source,
+                PathBuf::from("src/main/kotlin/com/dropbear/MySystem.kt"),
+            )
+            .unwrap();
+
+        assert_eq!(result.len(), 3);
+
+        assert_eq!(result[0].fqcn(), "com.dropbear.MySystem");
+        assert!(result[0].is_singleton());
+        assert!(result[0].tags().is_empty());
+
+        assert_eq!(result[1].fqcn(), "com.dropbear.Hud");
+        assert!(result[1].is_singleton());
+        assert_eq!(result[1].tags(), &["ui"]);
+
+        assert_eq!(result[2].fqcn(), "com.dropbear.Enemy");
+        assert!(!result[2].is_singleton());
+    }
+
+    #[test]
+    fn test_duplicate_tags() {
+        let source = r#"
+package com.dropbear
+
+@Runnable(["player", "Player", "movement", "player"])
+class Player : System {
+    override fun update(engine: DropbearEngine, deltaTime: Float) {}
+}
+"#;
+
+        let mut processor = KotlinProcessor::new().unwrap();
+        let result = processor
+            .process_file(source, PathBuf::from("src/Player.kt"))
+            .unwrap();
+
+        assert_eq!(result[0].tags(), &["player", "movement"]);
+    }
+
+    #[test]
+    fn test_trimmed_tags() {
+        let source = r#"
+package com.dropbear
+
+@Runnable(["  player", "Movement  "])
+class Player : System {
+    override fun update(engine: DropbearEngine, deltaTime: Float) {}
+}
+"#;
+
+        let mut processor = KotlinProcessor::new().unwrap();
+        let result = processor
+            .process_file(source, PathBuf::from("src/Player.kt"))
+            .unwrap();
+
+        assert_eq!(result[0].tags(), &["player", "movement"]);
+    }
+
+    #[test]
+    fn test_invalid_tags() {
+        let mut processor = KotlinProcessor::new().unwrap();
+
+        for tag in ["", "   ", "two words", "com.player", "spieler\u{e4}"] {
+            let source = format!(
+                "package com.dropbear\n\n@Runnable([\"ok\", \"{}\"])\nclass Player : System\n",
+                tag
+            );
+            let error = processor
+                .process_file(&source, PathBuf::from("src/Player.kt"))
+                .unwrap_err();
+            assert!(
+                error.to_string().starts_with("src/Player.kt:3:"),
+                "Unexpected error for {:?}: {}",
+                tag,
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_by_tag() {
+        let mut manifest = ScriptManifest::new();
+        manifest.add_item(ManifestItem::new(
+            "com.game.Player".to_string(),
+            "Player".to_string(),
+            vec!["Player".to_string(), "movement".to_string()],
+            PathBuf::from("src/Player.kt"),
+        ));
+        manifest.add_item(ManifestItem::new(
+            "com.game.Enemy".to_string(),
+            "Enemy".to_string(),
+            vec!["movement".to_string()],
+            PathBuf::from("src/Enemy.kt"),
+        ));
+
+        let players = manifest.find_by_tag("PLAYER");
+        assert_eq!(players.len(), 1);
+        assert_eq!(players[0].fqcn(), "com.game.Player");
+
+        assert_eq!(manifest.find_by_tag("movement").len(), 2);
+        assert!(manifest.find_by_tag("coin").is_empty());
+    }
+
+    #[test]
+    fn test_mixed_case_entity_tag() {
+        let item = ManifestItem::new(
+            "com.game.Player".to_string(),
+            "Player".to_string(),
+            vec!["Player".to_string()],
+            PathBuf::from("src/Player.kt"),
+        );
+
+        // the tag an entity is given in the editor has to end up as the key in the manifest
+        let entity_tag = normalise_tag(" PLAYER ");
+        assert_eq!(entity_tag, "player");
+        assert!(item.tags().contains(&entity_tag));
+    }
 }
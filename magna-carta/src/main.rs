@@ -1,5 +1,7 @@
 use clap::{Parser, ValueEnum};
-use magna_carta::generator::{Generator, jvm::KotlinJVMGenerator, native::KotlinNativeGenerator};
+use magna_carta::generator::{
+    Generator, json::JsonGenerator, jvm::KotlinJVMGenerator, native::KotlinNativeGenerator,
+};
 use magna_carta::{KotlinProcessor, ScriptManifest};
 use std::fs;
 use std::path::PathBuf;
@@ -29,12 +31,19 @@ struct Cli {
 
     #[arg(long, help = "Print manifest raw")]
     raw: bool,
+
+    #[arg(
+        long,
+        help = "Also write a C header for the generated native functions (native target only)"
+    )]
+    emit_cinterop: bool,
 }
 
 #[derive(ValueEnum, Clone, Debug)]
 enum Target {
     Jvm,
     Native,
+    Json,
 }
 
 fn main() -> anyhow::Result<()> {
@@ -52,6 +61,18 @@ fn main() -> anyhow::Result<()> {
         ));
     }
 
+    if cli.emit_cinterop && !matches!(cli.target, Target::Native) {
+        return Err(anyhow::anyhow!(
+            "--emit-cinterop can only be used with --target native"
+        ));
+    }
+
+    if cli.emit_cinterop && cli.output.is_none() {
+        return Err(anyhow::anyhow!(
+            "--emit-cinterop writes a header next to the manifest, so --output must be used"
+        ));
+    }
+
     let mut processor = KotlinProcessor::new()?;
     let mut manifest = ScriptManifest::new();
 
@@ -73,6 +94,10 @@ fn main() -> anyhow::Result<()> {
             let generator = KotlinNativeGenerator;
             generator.generate(&manifest)?
         }
+        Target::Json => {
+            let generator = JsonGenerator;
+            generator.generate(&manifest)?
+        }
     };
 
     if cli.raw {
@@ -87,6 +112,7 @@ fn main() -> anyhow::Result<()> {
         let filename = match cli.target {
             Target::Jvm => "RunnableRegistry.kt",
             Target::Native => "ScriptManifest.kt",
+            Target::Json => "script-manifest.json",
         };
         let output_path = output_dir.join(filename);
         fs::write(&output_path, generated_content)?;
@@ -95,6 +121,15 @@ fn main() -> anyhow::Result<()> {
             cli.target,
             output_path.display()
         );
+
+        if cli.emit_cinterop {
+            let header_path = output_dir.join("ScriptManifest.h");
+            fs::write(
+                &header_path,
+                KotlinNativeGenerator::generate_cinterop_header(&manifest)?,
+            )?;
+            println!("Generated C interop header at: {}", header_path.display());
+        }
     }
 
     println!("Found {} script classes", manifest.items().len());
@@ -116,9 +151,7 @@ fn visit_kotlin_files(
             } else if path.extension() == Some(std::ffi::OsStr::new("kt")) {
                 let source_code = fs::read_to_string(&path)?;
 
-                if let Some(item) = processor.process_file(&source_code, path.clone())? {
-                    manifest.add_item(item);
-                }
+                manifest.extend(processor.process_file(&source_code, path.clone())?);
             }
         }
     }
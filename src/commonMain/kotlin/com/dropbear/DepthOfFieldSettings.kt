@@ -0,0 +1,19 @@
+package com.dropbear
+
+/**
+ * Settings for depth of field, which blurs what is in front of and behind the point the camera
+ * focuses on. Passed to [DropbearEngine.setDepthOfField].
+ *
+ * @property focalDistance The distance from the camera that is in focus, in world units.
+ * @property focalRange How deep the area around the focal distance that stays sharp is, in world
+ *                      units. The blur also fades in over the same distance.
+ * @property maxBlur The furthest the blur spreads, in pixels.
+ * @property focusEntity The label of an entity to keep in focus, measured every frame instead of
+ *                       using [focalDistance].
+ */
+data class DepthOfFieldSettings(
+    val focalDistance: Float = 10f,
+    val focalRange: Float = 5f,
+    val maxBlur: Float = 8f,
+    val focusEntity: String? = null,
+)
@@ -4,6 +4,10 @@ import com.dropbear.asset.AssetHandle
 import com.dropbear.ffi.NativeEngine
 import com.dropbear.input.InputState
 import com.dropbear.logging.Logger
+import com.dropbear.math.Transform
+import com.dropbear.math.Vector2D
+import com.dropbear.math.Vector3D
+import com.dropbear.math.Vector4D
 
 internal var exceptionOnError: Boolean = false
 var lastErrorMessage: String? = null
@@ -76,6 +80,160 @@ class DropbearEngine(val native: NativeEngine) {
         return if (id != null) AssetHandle(id) else null
     }
 
+    /**
+     * Returns the labels of every entity with a Script component that has the given [tag].
+     *
+     * For example, `findByTag("enemy")` finds all enemies so they can be destroyed at once.
+     */
+    fun findByTag(tag: String): List<String> = native.findByTag(tag).toList()
+
+    /**
+     * Shakes every non-debug camera for [duration] seconds, offsetting it by at most [intensity].
+     */
+    fun shakeCamera(intensity: Float, duration: Float) = native.shakeCamera(intensity, duration)
+
+    /**
+     * Plays the camera path with the given name from the current scene on the starting camera.
+     *
+     * The camera ignores user input until the path has finished.
+     */
+    fun playCameraPath(name: String) = native.playCameraPath(name)
+
+    /**
+     * Returns the custom material property [key] of the entity with the given label, or `null`
+     * if it doesn't exist.
+     */
+    fun getMaterialProperty(label: String, key: String): Float? = native.getMaterialProperty(label, key)
+
+    /**
+     * Sets the custom material property [key] of the entity with the given label, which is passed
+     * to its shader. An entity can have up to 8 material properties.
+     */
+    fun setMaterialProperty(label: String, key: String, value: Float) =
+        native.setMaterialProperty(label, key, value)
+
+    /**
+     * Spawns [count] particles from the particle emitter on the entity with the given label,
+     * on top of its emission rate.
+     */
+    fun emit(label: String, count: Int) = native.emitParticles(label, count)
+
+    /**
+     * Sets the number of particles spawned per second by the particle emitter on the
+     * entity with the given label.
+     */
+    fun setEmissionRate(label: String, rate: Float) = native.setEmissionRate(label, rate)
+
+    /**
+     * Sets the weight of the morph target [targetName] on the entity with the given label, between
+     * `0.0` (not applied) and `1.0` (fully applied).
+     *
+     * The entity needs a `MorphWeights` component, which can be added in the editor.
+     */
+    fun setMorphWeight(label: String, targetName: String, weight: Float) =
+        native.setMorphWeight(label, targetName, weight)
+
+    /**
+     * Spawns an entity with the given [label], rendered with the model at [modelPath] (a
+     * `euca://` URI) and placed at [transform].
+     *
+     * The entity is spawned once every script has finished updating, and only shows up after its
+     * model has loaded, so it can't be fetched with [getEntity] in the same frame.
+     */
+    fun spawn(modelPath: String, label: String, transform: Transform) =
+        native.spawn(modelPath, label, transform)
+
+    /**
+     * Despawns the entity with the given [label] once every script has finished updating.
+     */
+    fun destroy(label: String) = native.destroy(label)
+
+    /**
+     * Calls `onTimer` with [callback] and [data] on every loaded system once [delay] seconds have
+     * passed, returning an id that can be passed to [clearTimer].
+     *
+     * [data] is a JSON string, such as `"{\"enemy\": \"goblin\"}"`. Timers are only supported
+     * by JVM scripts.
+     */
+    fun setTimeout(callback: String, delay: Float, data: String = "null"): Long =
+        native.setTimer(callback, delay, false, data)
+
+    /**
+     * Calls `onTimer` with [callback] and [data] on every loaded system every [interval] seconds
+     * until it is cleared with [clearTimer].
+     */
+    fun setInterval(callback: String, interval: Float, data: String = "null"): Long =
+        native.setTimer(callback, interval, true, data)
+
+    /**
+     * Stops the timer with the given [id] from firing again.
+     */
+    fun clearTimer(id: Long) = native.clearTimer(id)
+
+    /**
+     * Stores [json] under [key], where it stays when another scene is loaded.
+     *
+     * Values are shared with every script, so prefix keys to avoid clashes, such as
+     * `"player.score"`.
+     */
+    fun setGlobal(key: String, json: String) = native.setGlobal(key, json)
+
+    /**
+     * Fetches the JSON stored under [key] with [setGlobal], or `null` if nothing is stored.
+     */
+    fun getGlobal(key: String): String? = native.getGlobal(key)
+
+    /**
+     * Stores [json] under [key] in the scene store, keeping state between updates such as kill
+     * counts or an inventory.
+     *
+     * The store is shared by every script and is emptied when play mode stops.
+     */
+    fun setStore(key: String, json: String) = native.setStore(key, json)
+
+    /**
+     * Fetches the JSON stored under [key] with [setStore], or `null` if nothing is stored.
+     */
+    fun getStore(key: String): String? = native.getStore(key)
+
+    /**
+     * Turns on depth of field with the given [settings], or changes the settings if it is already
+     * on.
+     */
+    fun setDepthOfField(settings: DepthOfFieldSettings) = native.setDepthOfField(settings)
+
+    /**
+     * Turns off depth of field.
+     */
+    fun clearDepthOfField() = native.clearDepthOfField()
+
+    /**
+     * Draws [text] centred on [position] in the world, always facing the camera, with the font
+     * at [font] (a `euca://` URI) rasterised at [size] pixels high.
+     *
+     * Text is only drawn for the frame it was asked for, so call this on every update it should
+     * stay visible. [colour] is RGBA from `0.0` to `1.0`.
+     */
+    fun drawText(
+        text: String,
+        position: Vector3D,
+        font: String,
+        size: Float = 32f,
+        colour: Vector4D = Vector4D(1.0, 1.0, 1.0, 1.0),
+    ) = native.drawText(text, font, size, position, colour)
+
+    /**
+     * Draws [text] over the scene with its top left corner at [position], in pixels from the top
+     * left of the viewport, like [drawText].
+     */
+    fun drawTextScreen(
+        text: String,
+        position: Vector2D,
+        font: String,
+        size: Float = 32f,
+        colour: Vector4D = Vector4D(1.0, 1.0, 1.0, 1.0),
+    ) = native.drawTextScreen(text, font, size, position, colour)
+
     /**
      * Globally sets whether exceptions should be thrown when an error occurs.
      *
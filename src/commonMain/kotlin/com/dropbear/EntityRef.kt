@@ -93,6 +93,13 @@ class EntityRef(val id: EntityId = EntityId(0L)) {
         }
     }
 
+    /**
+     * Returns the tags of the entity's Script component, or an empty array if it has none.
+     */
+    fun getTags(): Array<String> {
+        return engine.native.getTags(id)
+    }
+
     /**
      * Fetches the attached camera for the entity.
      *
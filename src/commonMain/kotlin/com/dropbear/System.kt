@@ -1,5 +1,7 @@
 package com.dropbear
 
+import com.dropbear.math.Vector3D
+
 /**
  * A class that contains the basic information of a system. 
  * 
@@ -8,6 +10,12 @@ package com.dropbear
  * 
  * The main functions you would want to look at is `load`, 
  * `update` and `destroy`(not impl). 
+ *
+ * Systems are updated in order of the priority set on the entity's
+ * Script component in the editor, with lower priorities running first
+ * (the default is 0). For example, give a system that moves the player
+ * a priority of -1 and a camera-follow system a priority of 1 so the
+ * camera always follows the player's latest position.
  */
 open class System {
     var currentEntity: EntityRef? = null
@@ -19,6 +27,39 @@ open class System {
     open fun update(engine: DropbearEngine, deltaTime: Float) {}
     open fun destroy(engine: DropbearEngine) {}
 
+    /**
+     * Called when the current entity starts touching the entity labelled [other].
+     *
+     * [normal] points from the current entity towards [other].
+     */
+    open fun onCollisionEnter(engine: DropbearEngine, other: String, normal: Vector3D) {}
+
+    /**
+     * Called when the current entity stops touching the entity labelled [other].
+     */
+    open fun onCollisionExit(engine: DropbearEngine, other: String, normal: Vector3D) {}
+
+    /**
+     * Called when the scene named [scene] has been loaded, including scenes loaded additively
+     * on top of the current one. The entities of the scene are in the world by the time this is
+     * called.
+     */
+    open fun onSceneLoad(engine: DropbearEngine, scene: String) {}
+
+    /**
+     * Called when the scene named [scene] is about to be unloaded, before its entities are
+     * removed. Use this to reset state or unsubscribe from events.
+     */
+    open fun onSceneUnload(engine: DropbearEngine, scene: String) {}
+
+    /**
+     * Called when a timer started with [DropbearEngine.setTimeout] or [DropbearEngine.setInterval]
+     * fires. Every system receives every timer, so check [callback] before acting on it.
+     *
+     * [data] is the JSON string the timer was started with.
+     */
+    open fun onTimer(engine: DropbearEngine, callback: String, data: String) {}
+
     fun attachEngine(engine: DropbearEngine) {
         engineRef = engine
         currentEntity?.engine = engine
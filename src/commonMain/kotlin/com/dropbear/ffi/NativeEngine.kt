@@ -1,16 +1,20 @@
 package com.dropbear.ffi
 
 import com.dropbear.Camera
+import com.dropbear.DepthOfFieldSettings
 import com.dropbear.EntityId
 import com.dropbear.EntityRef
 import com.dropbear.EntityTransform
 import com.dropbear.asset.AssetHandle
 import com.dropbear.asset.ModelHandle
 import com.dropbear.asset.TextureHandle
+import com.dropbear.input.GamepadButton
 import com.dropbear.input.KeyCode
 import com.dropbear.input.MouseButton
 import com.dropbear.math.Transform
 import com.dropbear.math.Vector2D
+import com.dropbear.math.Vector3D
+import com.dropbear.math.Vector4D
 
 /**
  * Native functions
@@ -37,6 +41,8 @@ expect class NativeEngine {
     fun getCamera(label: String): Camera?
     fun getAttachedCamera(entityId: EntityId): Camera?
     fun setCamera(camera: Camera);
+    fun shakeCamera(intensity: Float, duration: Float)
+    fun playCameraPath(name: String)
 
     fun getTransform(entityId: EntityId): EntityTransform?
     fun propagateTransform(entityId: EntityId): Transform?
@@ -46,6 +52,53 @@ expect class NativeEngine {
     fun getChildByLabel(entityId: EntityId, label: String): EntityRef?
     fun getParent(entityId: EntityId): EntityRef? 
 
+    fun findByTag(tag: String): Array<String>
+    fun getTags(entityId: EntityId): Array<String>
+
+    // ----------------------- MATERIAL PROPERTIES -----------------------
+
+    fun getMaterialProperty(label: String, key: String): Float?
+    fun setMaterialProperty(label: String, key: String, value: Float)
+
+    // ---------------------------- PARTICLES ----------------------------
+
+    fun emitParticles(label: String, count: Int)
+    fun setEmissionRate(label: String, rate: Float)
+
+    // -------------------------- MORPH TARGETS --------------------------
+
+    fun setMorphWeight(label: String, targetName: String, weight: Float)
+
+    // ----------------------------- SPAWNING ----------------------------
+
+    fun spawn(modelPath: String, label: String, transform: Transform)
+    fun destroy(label: String)
+
+    // ------------------------------ TIMERS -----------------------------
+
+    fun setTimer(callbackName: String, delay: Float, repeat: Boolean, data: String): Long
+    fun clearTimer(id: Long)
+
+    // ----------------------------- GLOBALS -----------------------------
+
+    fun setGlobal(key: String, json: String)
+    fun getGlobal(key: String): String?
+
+    // ------------------------------ STORE ------------------------------
+
+    fun setStore(key: String, json: String)
+    fun getStore(key: String): String?
+
+    // ------------------------------- TEXT ------------------------------
+
+    fun drawText(text: String, font: String, size: Float, position: Vector3D, colour: Vector4D)
+    fun drawTextScreen(text: String, font: String, size: Float, position: Vector2D, colour: Vector4D)
+
+    // ------------------------- POST PROCESSING -------------------------
+
+    fun setDepthOfField(settings: DepthOfFieldSettings)
+    fun clearDepthOfField()
+
     // ------------------------ MODEL PROPERTIES -------------------------
 
     fun getStringProperty(entityHandle: Long, label: String): String?
@@ -79,7 +132,13 @@ expect class NativeEngine {
     fun isCursorHidden(): Boolean
     fun setCursorHidden(hidden: Boolean)
     fun getLastMousePos(): Vector2D?
-//    fun getConnectedGamepads(): List<Gamepad>
+    fun getConnectedGamepads(): LongArray
+    fun isGamepadButtonPressed(gamepadId: Long, button: GamepadButton): Boolean
+
+    /**
+     * Fetches the positions of the left and right stick of a gamepad, in that order.
+     */
+    fun getGamepadSticks(gamepadId: Long): Pair<Vector2D, Vector2D>?
 
     // -------------------------------------------------------------------
 }
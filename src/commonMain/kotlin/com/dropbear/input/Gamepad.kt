@@ -1,13 +1,20 @@
 package com.dropbear.input
 
+import com.dropbear.DropbearEngine
 import com.dropbear.math.Vector2D
 
+/**
+ * A snapshot of a connected gamepad, taken when it was fetched from the [InputState].
+ *
+ * The stick positions range from `-1.0` to `1.0` on each axis.
+ */
 class Gamepad(
-    val id: Int,
+    private val engine: DropbearEngine,
+    val id: Long,
     val leftStickPosition: Vector2D,
     val rightStickPosition: Vector2D,
 ) {
     fun isButtonPressed(button: GamepadButton): Boolean {
-        TODO("Not yet implemented")
+        return engine.native.isGamepadButtonPressed(id, button)
     }
-}
\ No newline at end of file
+}
@@ -55,7 +55,14 @@ class InputState(private val engine: DropbearEngine) {
     }
 
     fun getConnectedGamepads(): List<Gamepad> {
-        TODO("Not yet implemented")
-//        return engine.native.getConnectedGamepads()
+        return engine.native.getConnectedGamepads().map { id ->
+            val sticks = engine.native.getGamepadSticks(id)
+            Gamepad(
+                engine,
+                id,
+                sticks?.first ?: Vector2D(0.0, 0.0),
+                sticks?.second ?: Vector2D(0.0, 0.0),
+            )
+        }
     }
 }
\ No newline at end of file
@@ -35,6 +35,8 @@ public class JNINative {
     public static native Camera getCamera(long worldHandle, String label);
     public static native Camera getAttachedCamera(long worldHandle, long entityHandle);
     public static native void setCamera(long worldHandle, Camera camera);
+    public static native void shakeCamera(long worldHandle, float intensity, float duration);
+    public static native void playCameraPath(long worldHandle, String name);
 
     // transformations
     public static native EntityTransform getTransform(long handle, long entityHandle);
@@ -46,6 +48,10 @@ public class JNINative {
     public static native long getChildByLabel(long worldHandle, long entityId, String label);
     public static native long getParent(long worldHandle, long entityId);
 
+    // tags
+    public static native String[] findByTag(long worldHandle, String tag);
+    public static native String[] getTags(long worldHandle, long entityId);
+
     // properties
     public static native String getStringProperty(long worldHandle, long entityHandle, String label);
     public static native int getIntProperty(long worldHandle, long entityHandle, String label);
@@ -72,5 +78,43 @@ public class JNINative {
     public static native float[] getLastMousePos(long inputHandle);
     public static native boolean isCursorHidden(long inputHandle);
     public static native void setCursorHidden(long inputHandle, long graphicsHandle, boolean hidden);
+    public static native long[] getConnectedGamepads(long inputHandle);
+    public static native boolean isGamepadButtonPressed(long inputHandle, long gamepadId, int ordinal);
+    public static native float[] getGamepadSticks(long inputHandle, long gamepadId);
     public static native String[] getAllTextures(long worldHandle, long entityHandle);
+
+    // material properties
+    public static native float getMaterialProperty(long worldHandle, String label, String key);
+    public static native void setMaterialProperty(long worldHandle, String label, String key, float value);
+
+    // particles
+    public static native void emitParticles(long worldHandle, String label, int count);
+    public static native void setEmissionRate(long worldHandle, String label, float rate);
+
+    // morph targets
+    public static native void setMorphWeight(long worldHandle, String label, String targetName, float weight);
+
+    // spawning
+    public static native void spawn(String modelPath, String label, double[] transform);
+    public static native void destroy(String label);
+
+    // timers
+    public static native long setTimer(String callbackName, float delay, boolean repeat, String data);
+    public static native void clearTimer(long id);
+
+    // globals
+    public static native void setGlobal(String key, String json);
+    public static native String getGlobal(String key);
+
+    // scene store
+    public static native void setStore(String key, String json);
+    public static native String getStore(String key);
+
+    // text
+    public static native void drawText(String text, String font, float size, double x, double y, double z, float r, float g, float b, float a);
+    public static native void drawTextScreen(String text, String font, float size, float x, float y, float r, float g, float b, float a);
+
+    // post processing
+    public static native void setDepthOfField(float focalDistance, float focalRange, float maxBlur, String focusEntity);
+    public static native void clearDepthOfField();
 }
@@ -1,6 +1,7 @@
 package com.dropbear.ffi
 
 import com.dropbear.Camera
+import com.dropbear.DepthOfFieldSettings
 import com.dropbear.DropbearEngine
 import com.dropbear.EntityId
 import com.dropbear.EntityRef
@@ -8,11 +9,14 @@ import com.dropbear.EntityTransform
 import com.dropbear.asset.TextureHandle
 import com.dropbear.exception.DropbearNativeException
 import com.dropbear.exceptionOnError
+import com.dropbear.input.GamepadButton
 import com.dropbear.input.KeyCode
 import com.dropbear.input.MouseButton
 import com.dropbear.input.MouseButtonCodes
 import com.dropbear.math.Transform
 import com.dropbear.math.Vector2D
+import com.dropbear.math.Vector3D
+import com.dropbear.math.Vector4D
 
 actual class NativeEngine {
     /**
@@ -242,6 +246,22 @@ actual class NativeEngine {
         JNINative.setCursorHidden(inputHandle, graphicsHandle, hidden)
     }
 
+    actual fun getConnectedGamepads(): LongArray {
+        return JNINative.getConnectedGamepads(inputHandle) ?: LongArray(0)
+    }
+
+    actual fun isGamepadButtonPressed(gamepadId: Long, button: GamepadButton): Boolean {
+        return JNINative.isGamepadButtonPressed(inputHandle, gamepadId, button.ordinal)
+    }
+
+    actual fun getGamepadSticks(gamepadId: Long): Pair<Vector2D, Vector2D>? {
+        val result = JNINative.getGamepadSticks(inputHandle, gamepadId) ?: return null
+        return Pair(
+            Vector2D(result[0].toDouble(), result[1].toDouble()),
+            Vector2D(result[2].toDouble(), result[3].toDouble()),
+        )
+    }
+
     actual fun getModel(entityHandle: Long): Long? {
         val result = JNINative.getModel(worldHandle, entityHandle)
         return if (result == -1L) {
@@ -374,4 +394,128 @@ actual class NativeEngine {
             EntityRef(EntityId(result))
         }
     }
+
+    actual fun findByTag(tag: String): Array<String> {
+        return JNINative.findByTag(worldHandle, tag) ?: emptyArray()
+    }
+
+    actual fun getTags(entityId: EntityId): Array<String> {
+        return JNINative.getTags(worldHandle, entityId.id) ?: emptyArray()
+    }
+
+    actual fun getMaterialProperty(label: String, key: String): Float? {
+        val result = JNINative.getMaterialProperty(worldHandle, label, key)
+        return if (result.isNaN()) {
+            if (exceptionOnError) {
+                throw DropbearNativeException("Unable to get material property $key for entity $label")
+            } else {
+                null
+            }
+        } else {
+            result
+        }
+    }
+
+    actual fun setMaterialProperty(label: String, key: String, value: Float) {
+        JNINative.setMaterialProperty(worldHandle, label, key, value)
+    }
+
+    actual fun emitParticles(label: String, count: Int) {
+        JNINative.emitParticles(worldHandle, label, count)
+    }
+
+    actual fun setEmissionRate(label: String, rate: Float) {
+        JNINative.setEmissionRate(worldHandle, label, rate)
+    }
+
+    actual fun setMorphWeight(label: String, targetName: String, weight: Float) {
+        JNINative.setMorphWeight(worldHandle, label, targetName, weight)
+    }
+
+    actual fun spawn(modelPath: String, label: String, transform: Transform) {
+        val values = doubleArrayOf(
+            transform.position.x, transform.position.y, transform.position.z,
+            transform.rotation.x, transform.rotation.y, transform.rotation.z, transform.rotation.w,
+            transform.scale.x, transform.scale.y, transform.scale.z,
+        )
+        JNINative.spawn(modelPath, label, values)
+    }
+
+    actual fun destroy(label: String) {
+        JNINative.destroy(label)
+    }
+
+    actual fun setTimer(callbackName: String, delay: Float, repeat: Boolean, data: String): Long {
+        return JNINative.setTimer(callbackName, delay, repeat, data)
+    }
+
+    actual fun clearTimer(id: Long) {
+        JNINative.clearTimer(id)
+    }
+
+    actual fun setGlobal(key: String, json: String) {
+        JNINative.setGlobal(key, json)
+    }
+
+    actual fun getGlobal(key: String): String? {
+        return JNINative.getGlobal(key)
+    }
+
+    actual fun setStore(key: String, json: String) {
+        JNINative.setStore(key, json)
+    }
+
+    actual fun getStore(key: String): String? {
+        return JNINative.getStore(key)
+    }
+
+    actual fun drawText(text: String, font: String, size: Float, position: Vector3D, colour: Vector4D) {
+        JNINative.drawText(
+            text,
+            font,
+            size,
+            position.x,
+            position.y,
+            position.z,
+            colour.x.toFloat(),
+            colour.y.toFloat(),
+            colour.z.toFloat(),
+            colour.w.toFloat()
+        )
+    }
+
+    actual fun drawTextScreen(text: String, font: String, size: Float, position: Vector2D, colour: Vector4D) {
+        JNINative.drawTextScreen(
+            text,
+            font,
+            size,
+            position.x.toFloat(),
+            position.y.toFloat(),
+            colour.x.toFloat(),
+            colour.y.toFloat(),
+            colour.z.toFloat(),
+            colour.w.toFloat()
+        )
+    }
+
+    actual fun setDepthOfField(settings: DepthOfFieldSettings) {
+        JNINative.setDepthOfField(
+            settings.focalDistance,
+            settings.focalRange,
+            settings.maxBlur,
+            settings.focusEntity
+        )
+    }
+
+    actual fun clearDepthOfField() {
+        JNINative.clearDepthOfField()
+    }
+
+    actual fun shakeCamera(intensity: Float, duration: Float) {
+        JNINative.shakeCamera(worldHandle, intensity, duration)
+    }
+
+    actual fun playCameraPath(name: String) {
+        JNINative.playCameraPath(worldHandle, name)
+    }
 }
\ No newline at end of file
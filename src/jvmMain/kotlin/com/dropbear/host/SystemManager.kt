@@ -6,6 +6,7 @@ import com.dropbear.logging.LogLevel
 import com.dropbear.logging.LogWriter
 import com.dropbear.logging.Logger
 import com.dropbear.logging.StdoutWriter
+import com.dropbear.math.Vector3D
 import kotlin.collections.emptyList
 
 @Suppress("UNUSED")
@@ -117,6 +118,69 @@ class SystemManager(
         }
     }
 
+    fun dispatchCollision(
+        tag: String,
+        entityId: Long,
+        other: String,
+        normalX: Double,
+        normalY: Double,
+        normalZ: Double,
+        entered: Boolean
+    ) {
+        val systems = activeSystems[tag] ?: return
+        val normal = Vector3D(normalX, normalY, normalZ)
+
+        for (system in systems) {
+            try {
+                system.attachEngine(engine)
+                system.setCurrentEntity(entityId)
+                if (entered) {
+                    system.onCollisionEnter(engine, other, normal)
+                } else {
+                    system.onCollisionExit(engine, other, normal)
+                }
+            } catch (ex: Exception) {
+                Logger.error("Failed to dispatch collision to system ${system.javaClass.name} for entity $entityId: ${ex.message}")
+            } finally {
+                system.clearCurrentEntity()
+            }
+        }
+    }
+
+    fun dispatchSceneEvent(tag: String, entityId: Long, scene: String, loaded: Boolean) {
+        val systems = activeSystems[tag] ?: return
+
+        for (system in systems) {
+            try {
+                system.attachEngine(engine)
+                system.setCurrentEntity(entityId)
+                if (loaded) {
+                    system.onSceneLoad(engine, scene)
+                } else {
+                    system.onSceneUnload(engine, scene)
+                }
+            } catch (ex: Exception) {
+                Logger.error("Failed to dispatch scene event for '$scene' to system ${system.javaClass.name} for entity $entityId: ${ex.message}")
+            } finally {
+                system.clearCurrentEntity()
+            }
+        }
+    }
+
+    fun dispatchTimer(callback: String, data: String) {
+        for ((tag, systems) in activeSystems) {
+            for (system in systems) {
+                try {
+                    system.attachEngine(engine)
+                    system.clearCurrentEntity()
+                    system.onTimer(engine, callback, data)
+                } catch (ex: Exception) {
+                    Logger.error("Failed to dispatch timer '$callback' to system ${system.javaClass.name} for tag $tag: ${ex.message}")
+                }
+            }
+        }
+    }
+
     private fun updateSystemsInternal(tag: String, systems: List<System>, deltaTime: Float) {
         for (system in systems) {
             try {
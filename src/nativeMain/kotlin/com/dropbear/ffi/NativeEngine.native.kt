@@ -8,6 +8,7 @@
 package com.dropbear.ffi
 
 import com.dropbear.Camera
+import com.dropbear.DepthOfFieldSettings
 import com.dropbear.EntityId
 import com.dropbear.EntityRef
 import com.dropbear.EntityTransform
@@ -15,12 +16,15 @@ import com.dropbear.asset.TextureHandle
 import com.dropbear.exception.DropbearNativeException
 import com.dropbear.exceptionOnError
 import com.dropbear.ffi.generated.*
+import com.dropbear.input.GamepadButton
 import com.dropbear.input.KeyCode
 import com.dropbear.input.MouseButton
 import com.dropbear.input.MouseButtonCodes
 import com.dropbear.logging.Logger
 import com.dropbear.math.Transform
 import com.dropbear.math.Vector2D
+import com.dropbear.math.Vector3D
+import com.dropbear.math.Vector4D
 import kotlinx.cinterop.*
 import kotlin.experimental.ExperimentalNativeApi
 
@@ -348,6 +352,92 @@ actual class NativeEngine {
         }
     }
 
+    actual fun getConnectedGamepads(): LongArray {
+        val input = inputHandle ?: return LongArray(0)
+        memScoped {
+            val capacity = 16
+            val ids = allocArray<ULongVar>(capacity)
+            val count = alloc<ULongVar>()
+
+            val result = dropbear_get_connected_gamepads(
+                input.reinterpret(),
+                ids,
+                capacity.toULong(),
+                count.ptr
+            )
+
+            if (result == 0) {
+                val size = minOf(count.value.toInt(), capacity)
+                return LongArray(size) { ids[it].toLong() }
+            } else {
+                if (exceptionOnError) {
+                    throw DropbearNativeException("getConnectedGamepads failed with code: $result")
+                } else {
+                    println("getConnectedGamepads failed with code: $result")
+                    return LongArray(0)
+                }
+            }
+        }
+    }
+
+    actual fun isGamepadButtonPressed(gamepadId: Long, button: GamepadButton): Boolean {
+        val input = inputHandle ?: return false
+        memScoped {
+            val pressedVar = alloc<IntVar>()
+
+            val result = dropbear_is_gamepad_button_pressed(
+                input.reinterpret(),
+                gamepadId.toULong(),
+                button.ordinal,
+                pressedVar.ptr
+            )
+
+            if (result == 0) {
+                return pressedVar.value != 0
+            } else {
+                if (exceptionOnError) {
+                    throw DropbearNativeException("isGamepadButtonPressed failed with code: $result")
+                } else {
+                    println("isGamepadButtonPressed failed with code: $result")
+                    return false
+                }
+            }
+        }
+    }
+
+    actual fun getGamepadSticks(gamepadId: Long): Pair<Vector2D, Vector2D>? {
+        val input = inputHandle ?: return null
+        memScoped {
+            val leftX = alloc<FloatVar>()
+            val leftY = alloc<FloatVar>()
+            val rightX = alloc<FloatVar>()
+            val rightY = alloc<FloatVar>()
+
+            val result = dropbear_get_gamepad_sticks(
+                input.reinterpret(),
+                gamepadId.toULong(),
+                leftX.ptr,
+                leftY.ptr,
+                rightX.ptr,
+                rightY.ptr
+            )
+
+            if (result == 0) {
+                return Pair(
+                    Vector2D(leftX.value.toDouble(), leftY.value.toDouble()),
+                    Vector2D(rightX.value.toDouble(), rightY.value.toDouble()),
+                )
+            } else {
+                if (exceptionOnError) {
+                    throw DropbearNativeException("getGamepadSticks failed with code: $result")
+                } else {
+                    println("getGamepadSticks failed with code: $result")
+                    return null
+                }
+            }
+        }
+    }
+
     actual fun getStringProperty(entityHandle: Long, label: String): String? {
         val world = worldHandle ?: return null
         memScoped {
@@ -858,4 +948,299 @@ actual class NativeEngine {
     actual fun getParent(entityId: EntityId): EntityRef? {
         TODO("Not yet implemented")
     }
+
+    actual fun findByTag(tag: String): Array<String> {
+        TODO("Not yet implemented")
+    }
+
+    actual fun getTags(entityId: EntityId): Array<String> {
+        TODO("Not yet implemented")
+    }
+
+    actual fun getMaterialProperty(label: String, key: String): Float? {
+        val world = worldHandle ?: return null
+        memScoped {
+            val output = alloc<FloatVar>()
+
+            val result = dropbear_get_material_property(world.reinterpret(), label, key, output.ptr)
+
+            if (result == 0) {
+                return output.value
+            } else {
+                if (exceptionOnError) {
+                    throw DropbearNativeException("getMaterialProperty failed with code: $result")
+                } else {
+                    println("getMaterialProperty failed with code: $result")
+                    return null
+                }
+            }
+        }
+    }
+
+    actual fun setMaterialProperty(label: String, key: String, value: Float) {
+        val world = worldHandle ?: return
+        val result = dropbear_set_material_property(world.reinterpret(), label, key, value)
+
+        if (result != 0) {
+            if (exceptionOnError) {
+                throw DropbearNativeException("setMaterialProperty failed with code: $result")
+            } else {
+                println("setMaterialProperty failed with code: $result")
+            }
+        }
+    }
+
+    actual fun emitParticles(label: String, count: Int) {
+        val world = worldHandle ?: return
+        val result = dropbear_emit_particles(world.reinterpret(), label, count)
+
+        if (result != 0) {
+            if (exceptionOnError) {
+                throw DropbearNativeException("emitParticles failed with code: $result")
+            } else {
+                println("emitParticles failed with code: $result")
+            }
+        }
+    }
+
+    actual fun setEmissionRate(label: String, rate: Float) {
+        val world = worldHandle ?: return
+        val result = dropbear_set_emission_rate(world.reinterpret(), label, rate)
+
+        if (result != 0) {
+            if (exceptionOnError) {
+                throw DropbearNativeException("setEmissionRate failed with code: $result")
+            } else {
+                println("setEmissionRate failed with code: $result")
+            }
+        }
+    }
+
+    actual fun setMorphWeight(label: String, targetName: String, weight: Float) {
+        val world = worldHandle ?: return
+        val result = dropbear_set_morph_weight(world.reinterpret(), label, targetName, weight)
+
+        if (result != 0) {
+            if (exceptionOnError) {
+                throw DropbearNativeException("setMorphWeight failed with code: $result")
+            } else {
+                println("setMorphWeight failed with code: $result")
+            }
+        }
+    }
+
+    actual fun spawn(modelPath: String, label: String, transform: Transform) {
+        memScoped {
+            val nativeTransform = alloc<NativeTransform>().apply {
+                position_x = transform.position.x
+                position_y = transform.position.y
+                position_z = transform.position.z
+
+                rotation_x = transform.rotation.x
+                rotation_y = transform.rotation.y
+                rotation_z = transform.rotation.z
+                rotation_w = transform.rotation.w
+
+                scale_x = transform.scale.x
+                scale_y = transform.scale.y
+                scale_z = transform.scale.z
+            }
+
+            val result = dropbear_spawn(modelPath, label, nativeTransform.ptr)
+
+            if (result != 0) {
+                if (exceptionOnError) {
+                    throw DropbearNativeException("spawn failed with code: $result")
+                } else {
+                    println("spawn failed with code: $result")
+                }
+            }
+        }
+    }
+
+    actual fun destroy(label: String) {
+        val result = dropbear_destroy(label)
+
+        if (result != 0) {
+            if (exceptionOnError) {
+                throw DropbearNativeException("destroy failed with code: $result")
+            } else {
+                println("destroy failed with code: $result")
+            }
+        }
+    }
+
+    // native scripts have no way to be called back with onTimer yet, so timers can't be started
+    actual fun setTimer(callbackName: String, delay: Float, repeat: Boolean, data: String): Long {
+        if (exceptionOnError) {
+            throw DropbearNativeException("setTimer is not supported by native scripts")
+        } else {
+            println("setTimer is not supported by native scripts")
+            return -1
+        }
+    }
+
+    actual fun clearTimer(id: Long) {}
+
+    actual fun setGlobal(key: String, json: String) {
+        val result = dropbear_set_global(key, json)
+
+        if (result != 0) {
+            if (exceptionOnError) {
+                throw DropbearNativeException("setGlobal failed with code: $result")
+            } else {
+                println("setGlobal failed with code: $result")
+            }
+        }
+    }
+
+    actual fun getGlobal(key: String): String? {
+        memScoped {
+            val bufferSize = 4096
+            val output = allocArray<ByteVar>(bufferSize)
+
+            val result = dropbear_get_global(key, output, bufferSize.convert())
+
+            if (result == 0) {
+                return output.toKString()
+            } else if (result == -2) {
+                // nothing is stored under the key
+                return null
+            } else {
+                if (exceptionOnError) {
+                    throw DropbearNativeException("getGlobal failed with code: $result")
+                } else {
+                    println("getGlobal failed with code: $result")
+                    return null
+                }
+            }
+        }
+    }
+
+    actual fun setStore(key: String, json: String) {
+        val result = dropbear_set_store(key, json)
+
+        if (result != 0) {
+            if (exceptionOnError) {
+                throw DropbearNativeException("setStore failed with code: $result")
+            } else {
+                println("setStore failed with code: $result")
+            }
+        }
+    }
+
+    actual fun getStore(key: String): String? {
+        memScoped {
+            val bufferSize = 4096
+            val output = allocArray<ByteVar>(bufferSize)
+
+            val result = dropbear_get_store(key, output, bufferSize.convert())
+
+            if (result == 0) {
+                return output.toKString()
+            } else if (result == -2) {
+                // nothing is stored under the key
+                return null
+            } else {
+                if (exceptionOnError) {
+                    throw DropbearNativeException("getStore failed with code: $result")
+                } else {
+                    println("getStore failed with code: $result")
+                    return null
+                }
+            }
+        }
+    }
+
+    actual fun drawText(text: String, font: String, size: Float, position: Vector3D, colour: Vector4D) {
+        val result = dropbear_draw_text(
+            text,
+            font,
+            size,
+            position.x,
+            position.y,
+            position.z,
+            colour.x.toFloat(),
+            colour.y.toFloat(),
+            colour.z.toFloat(),
+            colour.w.toFloat()
+        )
+
+        if (result != 0) {
+            if (exceptionOnError) {
+                throw DropbearNativeException("drawText failed with code: $result")
+            } else {
+                println("drawText failed with code: $result")
+            }
+        }
+    }
+
+    actual fun drawTextScreen(text: String, font: String, size: Float, position: Vector2D, colour: Vector4D) {
+        val result = dropbear_draw_text_screen(
+            text,
+            font,
+            size,
+            position.x.toFloat(),
+            position.y.toFloat(),
+            colour.x.toFloat(),
+            colour.y.toFloat(),
+            colour.z.toFloat(),
+            colour.w.toFloat()
+        )
+
+        if (result != 0) {
+            if (exceptionOnError) {
+                throw DropbearNativeException("drawTextScreen failed with code: $result")
+            } else {
+                println("drawTextScreen failed with code: $result")
+            }
+        }
+    }
+
+    actual fun setDepthOfField(settings: DepthOfFieldSettings) {
+        val result = dropbear_set_depth_of_field(
+            settings.focalDistance,
+            settings.focalRange,
+            settings.maxBlur,
+            settings.focusEntity
+        )
+
+        if (result != 0) {
+            if (exceptionOnError) {
+                throw DropbearNativeException("setDepthOfField failed with code: $result")
+            } else {
+                println("setDepthOfField failed with code: $result")
+            }
+        }
+    }
+
+    actual fun clearDepthOfField() {
+        dropbear_clear_depth_of_field()
+    }
+
+    actual fun shakeCamera(intensity: Float, duration: Float) {
+        val world = worldHandle ?: return
+        val result = dropbear_shake_camera(world.reinterpret(), intensity, duration)
+
+        if (result != 0) {
+            if (exceptionOnError) {
+                throw DropbearNativeException("shakeCamera failed with code: $result")
+            } else {
+                println("shakeCamera failed with code: $result")
+            }
+        }
+    }
+
+    actual fun playCameraPath(name: String) {
+        val world = worldHandle ?: return
+        val result = dropbear_play_camera_path(world.reinterpret(), name)
+
+        if (result != 0) {
+            if (exceptionOnError) {
+                throw DropbearNativeException("playCameraPath failed with code: $result")
+            } else {
+                println("playCameraPath failed with code: $result")
+            }
+        }
+    }
 }